@@ -165,6 +165,22 @@ impl From<okane_core::trade::port::TradeError> for ApiError {
             okane_core::trade::port::TradeError::BrokerIntegrationError(msg) => {
                 ApiError::upstream(msg.clone())
             }
+            okane_core::trade::port::TradeError::AlgoOrderNotFound(msg) => {
+                ApiError::NotFound(msg.clone())
+            }
+            okane_core::trade::port::TradeError::AlgoOrderError(_)
+            | okane_core::trade::port::TradeError::InvalidCostModel(_)
+            | okane_core::trade::port::TradeError::InvalidMarginModel(_)
+            | okane_core::trade::port::TradeError::InvalidOrder(_)
+            | okane_core::trade::port::TradeError::InvalidCashMovement(_)
+            | okane_core::trade::port::TradeError::InvalidCorporateAction(_)
+            | okane_core::trade::port::TradeError::InvalidCurrency(_) => {
+                ApiError::BadRequest(err.to_string())
+            }
+            okane_core::trade::port::TradeError::RiskRejected(_)
+            | okane_core::trade::port::TradeError::AccountHalted(_) => {
+                ApiError::Forbidden(err.to_string())
+            }
             _ => ApiError::runtime(err.to_string()),
         }
     }
@@ -3,14 +3,21 @@
 //! 实现 `/api/v1/user/account/{id}` 路径下的 REST 接口。
 //! 对应 UI 原型中 "Key Metrics" 顶部指标卡片区域的数据源。
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
+use serde::Deserialize;
 
 use crate::error::ApiError;
 use crate::middleware::auth::CurrentUser;
 use crate::server::AppState;
 use crate::types::{
-    AccountProfileResponse, AccountSnapshotResponse, ApiResponse, ApiResult, CreateAccountRequest,
+    AccountAuditResponse, AccountProfileResponse, AccountSnapshotResponse, ApiResponse, ApiResult,
+    CashMovementRequest, CreateAccountRequest, CurrencyConversionRequest, DailyPnlResponse,
+    HaltAccountRequest, KillSwitchResponse, LedgerEntryResponse, Page, ResumeAccountRequest,
+    TransferRequest, TransferResponse,
 };
+use okane_core::store::port::UserRole;
+use okane_core::trade::entity::AccountId;
+use okane_manager::kill_switch::KillSwitch;
 use rust_decimal::Decimal;
 use std::str::FromStr;
 use uuid::Uuid;
@@ -41,15 +48,20 @@ pub async fn register_account(
     }
 
     if req.account_name.trim().is_empty() {
-        return Err(ApiError::BadRequest(
-            "account_name is required".to_string(),
-        ));
+        return Err(ApiError::BadRequest("account_name is required".to_string()));
     }
 
     if !req.config.is_object() {
-        return Err(ApiError::BadRequest("config must be a JSON object".to_string()));
+        return Err(ApiError::BadRequest(
+            "config must be a JSON object".to_string(),
+        ));
     }
 
+    // 成本模型、风控规则与保证金模型在开户时校验，避免下单时才发现配置错误
+    okane_core::trade::cost::CostModel::from_account_config(&req.config)?;
+    okane_core::trade::risk::RiskRules::from_account_config(&req.config)?;
+    okane_core::trade::margin::MarginModel::from_account_config(&req.config)?;
+
     let mut config = req.config;
     let initial_balance = if let Some(balance_str) = config
         .get("initial_balance")
@@ -100,6 +112,7 @@ pub async fn register_account(
         account_type,
         config,
         created_at: chrono::Utc::now().to_rfc3339(),
+        halted: false,
     }))
 }
 
@@ -129,7 +142,7 @@ pub async fn list_accounts(
 
 /// 获取指定逻辑交易账号的资金与持仓快照
 ///
-/// 返回该账户当前的可用余额、冻结资金、总权益及全量持仓列表。
+/// 返回该账户当前的可用余额、冻结资金、总权益、盈亏统计及全量持仓列表。
 /// 对应 UI 原型中的 Total Equity / Available Funds / Positions 区域。
 #[utoipa::path(
     get,
@@ -176,3 +189,384 @@ pub async fn get_account_snapshot(
 
     Ok(ApiResult(response))
 }
+
+/// 账号管控操作的访问校验：账号所有者或管理员。
+async fn ensure_account_control(
+    state: &AppState,
+    user: &okane_core::store::port::User,
+    account_id: &str,
+) -> Result<(), ApiError> {
+    if user.role == UserRole::Admin {
+        return Ok(());
+    }
+    let is_owner = state
+        .system_store
+        .verify_account_ownership(&user.id, account_id)
+        .await
+        .map_err(|e| ApiError::database(format!("database error: {}", e)))?;
+    if !is_owner {
+        return Err(ApiError::Forbidden(format!(
+            "Account {} does not belong to user {}. Ownership required.",
+            account_id, user.id
+        )));
+    }
+    Ok(())
+}
+
+fn kill_switch(state: &AppState) -> KillSwitch {
+    KillSwitch::new(
+        state.strategy_manager.clone(),
+        state.trade_port.clone(),
+        state.algo_port.clone(),
+        state.system_store.clone(),
+        state.time_provider.clone(),
+    )
+}
+
+/// 紧急停止逻辑交易账号 (kill switch)
+///
+/// 停止账号下全部策略，撤销全部委托与算法单，可选地以市价单平掉全部持仓，
+/// 并在重新启用前拒绝该账号的任何新委托。操作人记入审计日志。
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/account/{account_id}/halt",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    request_body = HaltAccountRequest,
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID")
+    ),
+    responses(
+        (status = 200, description = "账号已停止", body = ApiResponse<KillSwitchResponse>),
+        (status = 403, description = "无权操作该账号"),
+        (status = 404, description = "账户不存在"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn halt_account(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    axum::Json(req): axum::Json<HaltAccountRequest>,
+) -> Result<ApiResult<KillSwitchResponse>, ApiError> {
+    ensure_account_control(&state, &user, &account_id).await?;
+    let report = kill_switch(&state)
+        .halt(&user.id, &account_id, req.flatten, req.reason)
+        .await?;
+    Ok(ApiResult(report.into()))
+}
+
+/// 重新启用已紧急停止的逻辑交易账号
+///
+/// 恢复接受新委托；被停止的策略需要手动重新部署。
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/account/{account_id}/resume",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    request_body = ResumeAccountRequest,
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID")
+    ),
+    responses(
+        (status = 200, description = "账号已重新启用"),
+        (status = 403, description = "无权操作该账号"),
+        (status = 404, description = "账户不存在"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn resume_account(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    axum::Json(req): axum::Json<ResumeAccountRequest>,
+) -> Result<ApiResult<String>, ApiError> {
+    ensure_account_control(&state, &user, &account_id).await?;
+    kill_switch(&state)
+        .resume(&user.id, &account_id, req.reason)
+        .await?;
+    Ok(ApiResult("账号已重新启用".to_string()))
+}
+
+/// 查询逻辑交易账号的管控审计记录
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/account/{account_id}/audit",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID")
+    ),
+    responses(
+        (status = 200, description = "成功获取审计记录", body = ApiResponse<Vec<AccountAuditResponse>>),
+        (status = 403, description = "无权访问该账号"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn list_account_audit(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+) -> Result<ApiResult<Vec<AccountAuditResponse>>, ApiError> {
+    ensure_account_control(&state, &user, &account_id).await?;
+    let records = state
+        .system_store
+        .list_account_audit(&account_id)
+        .await
+        .map_err(|e| ApiError::database(format!("failed to list audit records: {}", e)))?;
+    Ok(ApiResult(records.into_iter().map(Into::into).collect()))
+}
+
+/// 查询逻辑交易账号的每日盈亏快照
+///
+/// 按日期升序返回，每个自然日 (UTC) 保留当日最后一次估值。
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/account/{account_id}/pnl/daily",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID")
+    ),
+    responses(
+        (status = 200, description = "成功获取每日盈亏", body = ApiResponse<Vec<DailyPnlResponse>>),
+        (status = 403, description = "无权访问该账号"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn list_daily_pnl(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+) -> Result<ApiResult<Vec<DailyPnlResponse>>, ApiError> {
+    let is_owner = state
+        .system_store
+        .verify_account_ownership(&user.id, &account_id)
+        .await
+        .map_err(|e| ApiError::database(format!("database error: {}", e)))?;
+    if !is_owner {
+        return Err(ApiError::Forbidden("forbidden".to_string()));
+    }
+    let records = state
+        .trade_port
+        .get_daily_pnl(&okane_core::trade::entity::AccountId(account_id))
+        .await?;
+    Ok(ApiResult(records.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Deserialize)]
+pub struct LedgerQuery {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// 资金操作的访问校验：仅账号所有者。
+async fn ensure_account_owner(
+    state: &AppState,
+    user_id: &str,
+    account_id: &str,
+) -> Result<(), ApiError> {
+    let is_owner = state
+        .system_store
+        .verify_account_ownership(user_id, account_id)
+        .await
+        .map_err(|e| ApiError::database(format!("database error: {}", e)))?;
+    if !is_owner {
+        return Err(ApiError::Forbidden(format!(
+            "Account {} does not belong to user {}. Ownership required.",
+            account_id, user_id
+        )));
+    }
+    Ok(())
+}
+
+fn parse_amount(amount: &str) -> Result<Decimal, ApiError> {
+    Decimal::from_str(amount.trim()).map_err(|_| ApiError::BadRequest("invalid amount".to_string()))
+}
+
+/// 向逻辑交易账号入金
+///
+/// 增加可用资金并记入 `Deposit` 资金流水；入金不计入当日盈亏。
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/account/{account_id}/deposit",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    request_body = CashMovementRequest,
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID")
+    ),
+    responses(
+        (status = 200, description = "入金成功", body = ApiResponse<LedgerEntryResponse>),
+        (status = 400, description = "金额非法"),
+        (status = 403, description = "无权操作该账号"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn deposit(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    axum::Json(req): axum::Json<CashMovementRequest>,
+) -> Result<ApiResult<LedgerEntryResponse>, ApiError> {
+    ensure_account_owner(&state, &user.id, &account_id).await?;
+    let amount = parse_amount(&req.amount)?;
+    let entry = state
+        .trade_port
+        .deposit(&AccountId(account_id), req.currency.as_deref(), amount)
+        .await?;
+    Ok(ApiResult(entry.into()))
+}
+
+/// 从逻辑交易账号出金
+///
+/// 出金额不得超过可用资金，保证金账户另不得超过剩余保证金额度；记入 `Withdrawal` 资金流水。
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/account/{account_id}/withdraw",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    request_body = CashMovementRequest,
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID")
+    ),
+    responses(
+        (status = 200, description = "出金成功", body = ApiResponse<LedgerEntryResponse>),
+        (status = 400, description = "金额非法或可用资金不足"),
+        (status = 403, description = "无权操作该账号"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn withdraw(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    axum::Json(req): axum::Json<CashMovementRequest>,
+) -> Result<ApiResult<LedgerEntryResponse>, ApiError> {
+    ensure_account_owner(&state, &user.id, &account_id).await?;
+    let amount = parse_amount(&req.amount)?;
+    let entry = state
+        .trade_port
+        .withdraw(&AccountId(account_id), req.currency.as_deref(), amount)
+        .await?;
+    Ok(ApiResult(entry.into()))
+}
+
+/// 在同一用户的两个逻辑交易账号之间转账
+///
+/// 转出方记 `TransferOut`、转入方记 `TransferIn`，两条流水以同一转账 ID 关联并同时生效。
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/account/{account_id}/transfer",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    request_body = TransferRequest,
+    params(
+        ("account_id" = String, Path, description = "转出方逻辑交易账号 ID")
+    ),
+    responses(
+        (status = 200, description = "转账成功", body = ApiResponse<TransferResponse>),
+        (status = 400, description = "金额非法、可用资金不足或转入转出为同一账号"),
+        (status = 403, description = "任一账号不属于当前用户"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn transfer(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    axum::Json(req): axum::Json<TransferRequest>,
+) -> Result<ApiResult<TransferResponse>, ApiError> {
+    ensure_account_owner(&state, &user.id, &account_id).await?;
+    ensure_account_owner(&state, &user.id, &req.to_account_id).await?;
+    let amount = parse_amount(&req.amount)?;
+    let transfer_id = format!("xfer_{}", Uuid::new_v4().simple());
+    let transfer = state
+        .trade_port
+        .transfer(
+            &AccountId(account_id),
+            &AccountId(req.to_account_id),
+            req.currency.as_deref(),
+            amount,
+            &transfer_id,
+        )
+        .await?;
+    Ok(ApiResult(transfer.into()))
+}
+
+/// 在多币种逻辑交易账号内兑换货币
+///
+/// 按最新汇率行情从换出币种扣款并计入换入币种，两条 `FxConversion` 流水以同一兑换 ID 关联。
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/account/{account_id}/convert",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    request_body = CurrencyConversionRequest,
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID")
+    ),
+    responses(
+        (status = 200, description = "兑换成功", body = ApiResponse<TransferResponse>),
+        (status = 400, description = "金额或币种非法、可用资金不足或账户非多币种账户"),
+        (status = 403, description = "无权操作该账号"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn convert_currency(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    axum::Json(req): axum::Json<CurrencyConversionRequest>,
+) -> Result<ApiResult<TransferResponse>, ApiError> {
+    ensure_account_owner(&state, &user.id, &account_id).await?;
+    let amount = parse_amount(&req.amount)?;
+    let conversion_id = format!("fx_{}", Uuid::new_v4().simple());
+    let conversion = state
+        .trade_port
+        .convert_currency(
+            &AccountId(account_id),
+            &req.from_currency,
+            &req.to_currency,
+            amount,
+            &conversion_id,
+        )
+        .await?;
+    Ok(ApiResult(conversion.into()))
+}
+
+/// 分页查询逻辑交易账号的资金流水
+///
+/// 按流水号倒序返回，每条流水附带变动后的可用与冻结资金余额。
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/account/{account_id}/ledger",
+    tag = "账户 (Account)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID"),
+        ("limit" = Option<usize>, Query, description = "返回数量限制，默认 50，最多 500"),
+        ("offset" = Option<usize>, Query, description = "跳过的记录数，默认 0")
+    ),
+    responses(
+        (status = 200, description = "成功获取资金流水", body = ApiResponse<Page<LedgerEntryResponse>>),
+        (status = 403, description = "无权访问该账号"),
+        (status = 401, description = "未认证")
+    )
+)]
+pub async fn list_ledger(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    Query(query): Query<LedgerQuery>,
+) -> Result<ApiResult<Page<LedgerEntryResponse>>, ApiError> {
+    ensure_account_owner(&state, &user.id, &account_id).await?;
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(50).min(500);
+    let page = state
+        .trade_port
+        .get_ledger(&AccountId(account_id), offset, limit)
+        .await?;
+    let items = page.items.into_iter().map(Into::into).collect();
+    Ok(ApiResult(Page::new(items, page.total, offset, limit)))
+}
@@ -1,11 +1,15 @@
 use crate::error::ApiError;
 use crate::server::AppState;
 use crate::types::{
-    ApiResponse, ApiResult, CreateUserRequest, UpdateSettingsRequest, UserResponse,
+    ApiResponse, ApiResult, CandleCoverageRequest, CandleCoverageResponse, CorporateActionResponse,
+    CreateUserRequest, ImportCorporateActionsRequest, UpdateSettingsRequest, UserResponse,
 };
-use axum::extract::State;
-use chrono::Utc;
+use axum::extract::{Path, Query, State};
+use chrono::{DateTime, Utc};
+use okane_core::common::TimeFrame;
+use okane_core::market::entity::CorporateAction;
 use okane_core::store::port::{User, UserRole};
+use std::str::FromStr;
 
 /// 创建新子账户
 ///
@@ -105,3 +109,191 @@ pub async fn update_settings(
     // TODO: Broadcast event to Engine for hot-reloading if applicable
     Ok(ApiResult("ok".to_string()))
 }
+
+/// 导入公司行动
+///
+/// 只有 Admin 角色可以导入数据源未覆盖的拆股与现金分红记录，同一除权除息日的同类型记录将被覆盖。
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/market/corporate-actions/{symbol}",
+    tag = "系统管理 (Admin)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("symbol" = String, Path, description = "股票代码")
+    ),
+    request_body = ImportCorporateActionsRequest,
+    responses(
+        (status = 200, description = "导入成功", body = ApiResponse<Vec<CorporateActionResponse>>),
+        (status = 400, description = "无效的请求参数"),
+        (status = 403, description = "无权限执行此操作")
+    )
+)]
+pub async fn import_corporate_actions(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    axum::Json(req): axum::Json<ImportCorporateActionsRequest>,
+) -> Result<ApiResult<Vec<CorporateActionResponse>>, ApiError> {
+    let actions = req
+        .actions
+        .into_iter()
+        .map(|action| {
+            if action.symbol != symbol {
+                return Err(ApiError::BadRequest(format!(
+                    "corporate action symbol {} does not match {}",
+                    action.symbol, symbol
+                )));
+            }
+            CorporateAction::try_from(action).map_err(ApiError::BadRequest)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let stock = state
+        .market_port
+        .get_stock(&symbol)
+        .await
+        .map_err(|e| ApiError::upstream(format!("market error: {}", e)))?;
+    stock
+        .import_corporate_actions(actions.clone())
+        .await
+        .map_err(|e| ApiError::database(format!("failed to import corporate actions: {}", e)))?;
+
+    tracing::info!(
+        "Admin imported {} corporate actions for {}",
+        actions.len(),
+        symbol
+    );
+    Ok(ApiResult(actions.into_iter().map(Into::into).collect()))
+}
+
+/// 解析覆盖请求中的周期与时间区间。
+fn parse_coverage_request(
+    req: &CandleCoverageRequest,
+) -> Result<(TimeFrame, DateTime<Utc>, DateTime<Utc>), ApiError> {
+    let tf = TimeFrame::from_str(&req.tf)
+        .map_err(|e| ApiError::BadRequest(format!("invalid timeframe: {}", e)))?;
+    let start = DateTime::parse_from_rfc3339(&req.start)
+        .map_err(|_| {
+            ApiError::BadRequest("invalid start time format, expected RFC3339".to_string())
+        })?
+        .with_timezone(&Utc);
+    let end = DateTime::parse_from_rfc3339(&req.end)
+        .map_err(|_| ApiError::BadRequest("invalid end time format, expected RFC3339".to_string()))?
+        .with_timezone(&Utc);
+    if start > end {
+        return Err(ApiError::BadRequest(
+            "start time must not be after end time".to_string(),
+        ));
+    }
+    Ok((tf, start, end))
+}
+
+/// 读取覆盖情况并转换为响应。
+async fn load_coverage(
+    state: &AppState,
+    symbol: &str,
+    tf: TimeFrame,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<CandleCoverageResponse, ApiError> {
+    let stock = state
+        .market_port
+        .get_stock(symbol)
+        .await
+        .map_err(|e| ApiError::upstream(format!("market error: {}", e)))?;
+    let coverage = stock
+        .candle_coverage(tf, start, end)
+        .await
+        .map_err(|e| ApiError::database(format!("failed to load candle coverage: {}", e)))?;
+    Ok(CandleCoverageResponse {
+        symbol: symbol.to_string(),
+        timeframe: tf.to_string(),
+        covered: coverage.covered.into_iter().map(Into::into).collect(),
+        gaps: coverage.gaps.into_iter().map(Into::into).collect(),
+    })
+}
+
+/// 查询 K 线覆盖情况
+///
+/// 只有 Admin 角色可以查看本地已从数据源拉取的 K 线区间及区间内的缺口。时间必须为 RFC3339 格式。
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/market/coverage/{symbol}",
+    tag = "系统管理 (Admin)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("symbol" = String, Path, description = "股票代码"),
+        ("tf" = String, Query, description = "Timeframe (e.g., 1m, 1h, 1d)"),
+        ("start" = String, Query, description = "ISO 8601 start time"),
+        ("end" = String, Query, description = "ISO 8601 end time")
+    ),
+    responses(
+        (status = 200, description = "查询成功", body = ApiResponse<CandleCoverageResponse>),
+        (status = 400, description = "无效的请求参数"),
+        (status = 403, description = "无权限执行此操作")
+    )
+)]
+pub async fn get_candle_coverage(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(req): Query<CandleCoverageRequest>,
+) -> Result<ApiResult<CandleCoverageResponse>, ApiError> {
+    let (tf, start, end) = parse_coverage_request(&req)?;
+    Ok(ApiResult(
+        load_coverage(&state, &symbol, tf, start, end).await?,
+    ))
+}
+
+/// 修复 K 线缺口
+///
+/// 只有 Admin 角色可以调用。向数据源补拉区间内未覆盖的 K 线并落库，返回修复后的覆盖情况；
+/// 数据源无法提供的区间 (如尚未收盘的周期) 仍保留为缺口。`force` 为真时先丢弃区间内已记录的覆盖，
+/// 用于数据源修正过历史数据或本地数据有误时整段重新拉取。
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/market/coverage/{symbol}/repair",
+    tag = "系统管理 (Admin)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("symbol" = String, Path, description = "股票代码")
+    ),
+    request_body = CandleCoverageRequest,
+    responses(
+        (status = 200, description = "修复完成", body = ApiResponse<CandleCoverageResponse>),
+        (status = 400, description = "无效的请求参数"),
+        (status = 403, description = "无权限执行此操作"),
+        (status = 500, description = "数据源拉取失败")
+    )
+)]
+pub async fn repair_candle_coverage(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    axum::Json(req): axum::Json<CandleCoverageRequest>,
+) -> Result<ApiResult<CandleCoverageResponse>, ApiError> {
+    let (tf, start, end) = parse_coverage_request(&req)?;
+    let stock = state
+        .market_port
+        .get_stock(&symbol)
+        .await
+        .map_err(|e| ApiError::upstream(format!("market error: {}", e)))?;
+    if req.force {
+        stock
+            .clear_candle_coverage(tf, start, end)
+            .await
+            .map_err(|e| ApiError::database(format!("failed to clear candle coverage: {}", e)))?;
+    }
+    let candles = stock
+        .fetch_history(tf, start, end)
+        .await
+        .map_err(|e| ApiError::upstream(format!("fetch history error: {}", e)))?;
+
+    let coverage = load_coverage(&state, &symbol, tf, start, end).await?;
+    tracing::info!(
+        "Admin repaired {} {} coverage (force: {}): {} candles, {} gaps left",
+        symbol,
+        tf,
+        req.force,
+        candles.len(),
+        coverage.gaps.len()
+    );
+    Ok(ApiResult(coverage))
+}
@@ -32,11 +32,14 @@ use crate::types::{ApiResponse, ApiResult, BacktestRequest, BacktestResponse};
 )]
 pub async fn run_backtest(
     State(state): State<AppState>,
-    CurrentUser(_user): CurrentUser,
+    CurrentUser(user): CurrentUser,
     axum::Json(req): axum::Json<BacktestRequest>,
 ) -> Result<ApiResult<BacktestResponse>, ApiError> {
     use okane_core::common::TimeFrame;
+    use okane_core::config::FillModelConfig;
+    use okane_core::market::entity::{OhlcPath, PriceAdjustment};
     use okane_core::strategy::entity::EngineType;
+    use okane_core::trade::cost::CostModel;
     use rust_decimal::Decimal;
     use std::str::FromStr;
 
@@ -68,6 +71,39 @@ pub async fn run_backtest(
         ));
     }
 
+    // 解析价格口径
+    let price_adjustment: PriceAdjustment = req
+        .price_adjustment
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(|e: String| ApiError::BadRequest(e))?
+        .unwrap_or_default();
+
+    // 解析成交模型
+    let max_participation = req
+        .max_participation
+        .as_deref()
+        .map(Decimal::from_str)
+        .transpose()
+        .map_err(|_| ApiError::BadRequest("invalid max participation value".to_string()))?;
+    if max_participation.is_some_and(|rate| rate <= Decimal::ZERO || rate > Decimal::ONE) {
+        return Err(ApiError::BadRequest(
+            "max participation must be in (0, 1]".to_string(),
+        ));
+    }
+    let ohlc_path: OhlcPath = req
+        .ohlc_path
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(|e: String| ApiError::BadRequest(e))?
+        .unwrap_or_default();
+    let fill_model = FillModelConfig {
+        max_participation,
+        ohlc_path,
+    };
+
     // 解析初始资金
     let initial_balance = Decimal::from_str(&req.initial_balance)
         .map_err(|_| ApiError::BadRequest("invalid initial balance value".to_string()))?;
@@ -78,6 +114,30 @@ pub async fn run_backtest(
         .decode(&req.source_base64)
         .map_err(|e| ApiError::BadRequest(format!("base64 decode failed: {}", e)))?;
 
+    // 解析成本模型：取自指定逻辑交易账号的配置
+    let cost_model = match &req.account_id {
+        Some(account_id) => {
+            let is_owner = state
+                .system_store
+                .verify_account_ownership(&user.id, account_id)
+                .await
+                .map_err(|e| ApiError::database(format!("database error: {}", e)))?;
+            if !is_owner {
+                return Err(ApiError::Forbidden(
+                    "forbidden: account does not belong to you".to_string(),
+                ));
+            }
+            let profile = state
+                .system_store
+                .get_account_profile(account_id)
+                .await
+                .map_err(|e| ApiError::database(format!("database error: {}", e)))?
+                .ok_or_else(|| ApiError::NotFound(format!("account {} not found", account_id)))?;
+            CostModel::from_account_config(&profile.config)?
+        }
+        None => CostModel::default(),
+    };
+
     // 构建 Runner 请求
     let run_req = okane_manager::backtest::BacktestRequest {
         symbol: req.symbol,
@@ -87,6 +147,9 @@ pub async fn run_backtest(
         engine_type,
         source,
         initial_balance,
+        cost_model,
+        price_adjustment,
+        fill_model,
     };
 
     // 执行回测
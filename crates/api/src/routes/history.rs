@@ -0,0 +1,261 @@
+//! # 订单与成交历史路由控制器
+//!
+//! 实现 `/api/v1/user/account/{id}/history` 路径下的交易流水 (blotter) 查询接口，
+//! 支持按标的、时间区间、订单状态与策略运行过滤、分页及 CSV 导出。
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderValue, header};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::error::ApiError;
+use crate::middleware::auth::CurrentUser;
+use crate::server::AppState;
+use crate::types::{ApiResponse, ApiResult, OrderHistoryResponse, Page, TradeResponse};
+use okane_core::trade::entity::{AccountId, HistoryQuery, OrderStatus};
+
+/// 分页查询的默认与最大条数
+const DEFAULT_PAGE_LIMIT: usize = 50;
+const MAX_PAGE_LIMIT: usize = 500;
+/// CSV 导出单次的最大条数
+const MAX_EXPORT_LIMIT: usize = 10_000;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQueryParams {
+    /// 标的代码
+    pub symbol: Option<String>,
+    /// 起始时间 (毫秒级时间戳，含)
+    pub start: Option<i64>,
+    /// 截止时间 (毫秒级时间戳，不含)
+    pub end: Option<i64>,
+    /// 订单状态 (Pending, Submitted, PartialFilled, Filled, Canceled, Rejected, Expired)，仅作用于订单查询
+    pub status: Option<String>,
+    /// 策略运行 ID
+    pub strategy_run_id: Option<String>,
+    /// 返回数量限制，默认 50 (CSV 导出默认并最多 10000)
+    pub limit: Option<usize>,
+    /// 跳过的记录数，默认 0
+    pub offset: Option<usize>,
+    /// 输出格式 (json, csv)，默认 json
+    pub format: Option<String>,
+}
+
+impl HistoryQueryParams {
+    fn is_csv(&self) -> Result<bool, ApiError> {
+        match self.format.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("json") => Ok(false),
+            Some("csv") => Ok(true),
+            Some(other) => Err(ApiError::BadRequest(format!("unknown format: {}", other))),
+        }
+    }
+
+    fn to_query(&self, account_id: AccountId, csv: bool) -> Result<HistoryQuery, ApiError> {
+        let status = self.status.as_deref().map(parse_status).transpose()?;
+        if let (Some(start), Some(end)) = (self.start, self.end)
+            && start >= end
+        {
+            return Err(ApiError::BadRequest("start must be before end".to_string()));
+        }
+        let limit = if csv {
+            self.limit.unwrap_or(MAX_EXPORT_LIMIT).min(MAX_EXPORT_LIMIT)
+        } else {
+            self.limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT)
+        };
+        Ok(HistoryQuery {
+            account_id,
+            symbol: self.symbol.clone(),
+            start: self.start,
+            end: self.end,
+            status,
+            strategy_run_id: self.strategy_run_id.clone(),
+            offset: self.offset.unwrap_or(0),
+            limit,
+        })
+    }
+}
+
+fn parse_status(value: &str) -> Result<OrderStatus, ApiError> {
+    match value.to_lowercase().as_str() {
+        "pending" => Ok(OrderStatus::Pending),
+        "submitted" => Ok(OrderStatus::Submitted),
+        "partialfilled" | "partial_filled" => Ok(OrderStatus::PartialFilled),
+        "filled" => Ok(OrderStatus::Filled),
+        "canceled" => Ok(OrderStatus::Canceled),
+        "rejected" => Ok(OrderStatus::Rejected),
+        "expired" => Ok(OrderStatus::Expired),
+        _ => Err(ApiError::BadRequest(format!(
+            "unknown order status: {}",
+            value
+        ))),
+    }
+}
+
+async fn authorize(state: &AppState, user_id: &str, account_id: &str) -> Result<(), ApiError> {
+    let is_owner = state
+        .system_store
+        .verify_account_ownership(user_id, account_id)
+        .await
+        .map_err(|e| ApiError::database(format!("database error: {}", e)))?;
+    if !is_owner {
+        return Err(ApiError::Forbidden("forbidden".to_string()));
+    }
+    Ok(())
+}
+
+/// 按 RFC 4180 转义单个 CSV 字段。
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_response(file_name: &str, header_row: &str, rows: Vec<Vec<String>>) -> Response {
+    let mut body = String::from(header_row);
+    body.push('\n');
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        body.push_str(&fields.join(","));
+        body.push('\n');
+    }
+    let mut response = body.into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/csv; charset=utf-8"),
+    );
+    if let Ok(disposition) =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", file_name))
+    {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    response
+}
+
+/// 查询账户的订单历史
+///
+/// 包含已成交、撤销、拒绝与过期的订单及其状态变迁，按创建时间倒序返回；`format=csv` 时导出 CSV。
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/account/{account_id}/history/orders",
+    tag = "订单交易 (Trade)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID"),
+        HistoryQueryParams
+    ),
+    responses(
+        (status = 200, description = "查询成功", content(
+            (ApiResponse<Page<OrderHistoryResponse>> = "application/json"),
+            (String = "text/csv")
+        )),
+        (status = 400, description = "查询参数非法"),
+        (status = 403, description = "无权访问该账号")
+    )
+)]
+pub async fn get_order_history(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    Query(params): Query<HistoryQueryParams>,
+) -> Result<Response, ApiError> {
+    authorize(&state, &user.id, &account_id).await?;
+    let csv = params.is_csv()?;
+    let query = params.to_query(AccountId(account_id.clone()), csv)?;
+    let page = state.trade_port.get_order_history(&query).await?;
+    let items: Vec<OrderHistoryResponse> = page.items.into_iter().map(Into::into).collect();
+
+    if !csv {
+        return Ok(
+            ApiResult(Page::new(items, page.total, query.offset, query.limit)).into_response(),
+        );
+    }
+    let rows = items
+        .into_iter()
+        .map(|record| {
+            let order = record.order;
+            vec![
+                order.id,
+                order.symbol,
+                order.direction,
+                order.order_type,
+                order.price.unwrap_or_default(),
+                order.volume,
+                order.filled_volume,
+                order.status,
+                order.time_in_force,
+                order.strategy_run_id.unwrap_or_default(),
+                order.created_at.to_string(),
+                record.updated_at.to_string(),
+            ]
+        })
+        .collect();
+    Ok(csv_response(
+        &format!("orders_{}.csv", account_id),
+        "id,symbol,direction,order_type,price,volume,filled_volume,status,time_in_force,strategy_run_id,created_at,updated_at",
+        rows,
+    ))
+}
+
+/// 查询账户的成交历史
+///
+/// 按成交时间倒序返回每一笔成交；`format=csv` 时导出 CSV。
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/account/{account_id}/history/trades",
+    tag = "订单交易 (Trade)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("account_id" = String, Path, description = "逻辑交易账号 ID"),
+        HistoryQueryParams
+    ),
+    responses(
+        (status = 200, description = "查询成功", content(
+            (ApiResponse<Page<TradeResponse>> = "application/json"),
+            (String = "text/csv")
+        )),
+        (status = 400, description = "查询参数非法"),
+        (status = 403, description = "无权访问该账号")
+    )
+)]
+pub async fn get_trade_history(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(account_id): Path<String>,
+    Query(params): Query<HistoryQueryParams>,
+) -> Result<Response, ApiError> {
+    authorize(&state, &user.id, &account_id).await?;
+    let csv = params.is_csv()?;
+    let query = params.to_query(AccountId(account_id.clone()), csv)?;
+    let page = state.trade_port.get_trade_history(&query).await?;
+    let items: Vec<TradeResponse> = page.items.into_iter().map(Into::into).collect();
+
+    if !csv {
+        return Ok(
+            ApiResult(Page::new(items, page.total, query.offset, query.limit)).into_response(),
+        );
+    }
+    let rows = items
+        .into_iter()
+        .map(|trade| {
+            vec![
+                trade.order_id,
+                trade.symbol,
+                trade.direction,
+                trade.price,
+                trade.volume,
+                trade.commission,
+                trade.timestamp.to_string(),
+            ]
+        })
+        .collect();
+    Ok(csv_response(
+        &format!("trades_{}.csv", account_id),
+        "order_id,symbol,direction,price,volume,commission,timestamp",
+        rows,
+    ))
+}
@@ -9,7 +9,9 @@ use std::str::FromStr;
 
 use crate::error::ApiError;
 use crate::server::AppState;
-use crate::types::{ApiResponse, ApiResult, CandleResponse, StockMetadataResponse};
+use crate::types::{
+    ApiResponse, ApiResult, CandleResponse, CorporateActionResponse, StockMetadataResponse,
+};
 use okane_core::common::TimeFrame;
 use utoipa::ToSchema;
 
@@ -108,6 +110,60 @@ pub async fn get_candles(
     Ok(ApiResult(dtos))
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct CorporateActionsQuery {
+    pub start: String,
+    pub end: String,
+}
+
+/// 获取公司行动
+///
+/// 获取特定股票在时间区间内除权除息的拆股与现金分红记录。时间必须为 RFC3339 格式。
+#[utoipa::path(
+    get,
+    path = "/api/v1/market/corporate-actions/{symbol}",
+    tag = "行情 (Market)",
+    security(("bearer_jwt" = [])),
+    params(
+        ("symbol" = String, Path, description = "股票代码"),
+        ("start" = String, Query, description = "ISO 8601 start time"),
+        ("end" = String, Query, description = "ISO 8601 end time")
+    ),
+    responses(
+        (status = 200, description = "拉取成功", body = ApiResponse<Vec<CorporateActionResponse>>),
+        (status = 400, description = "无效的请求参数"),
+        (status = 500, description = "内部服务器错误")
+    )
+)]
+pub async fn get_corporate_actions(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<CorporateActionsQuery>,
+) -> Result<ApiResult<Vec<CorporateActionResponse>>, ApiError> {
+    let start = DateTime::parse_from_rfc3339(&query.start)
+        .map_err(|_| {
+            ApiError::BadRequest("invalid start time format, expected RFC3339".to_string())
+        })?
+        .with_timezone(&Utc);
+
+    let end = DateTime::parse_from_rfc3339(&query.end)
+        .map_err(|_| ApiError::BadRequest("invalid end time format, expected RFC3339".to_string()))?
+        .with_timezone(&Utc);
+
+    let stock_agg = state
+        .market_port
+        .get_stock(&symbol)
+        .await
+        .map_err(|e| ApiError::upstream(format!("market error: {}", e)))?;
+
+    let actions = stock_agg
+        .corporate_actions(start, end)
+        .await
+        .map_err(|e| ApiError::upstream(format!("fetch corporate actions error: {}", e)))?;
+
+    Ok(ApiResult(actions.into_iter().map(Into::into).collect()))
+}
+
 #[derive(Deserialize, ToSchema)]
 pub struct IndicatorQuery {
     pub tf: String,
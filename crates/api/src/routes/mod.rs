@@ -2,6 +2,7 @@ pub mod account;
 pub mod admin;
 pub mod auth;
 pub mod backtest;
+pub mod history;
 pub mod market;
 pub mod notify;
 pub mod strategy;
@@ -9,7 +9,10 @@ use crate::error::ApiError;
 use crate::middleware::auth::CurrentUser;
 use crate::server::AppState;
 use crate::types::{AlgoOrderResponse, ApiResponse, ApiResult, OrderResponse, Page};
-use okane_core::trade::entity::{AccountId, AlgoOrder, AlgoType, Order, OrderDirection, OrderId};
+use okane_core::trade::entity::{
+    AccountId, AlgoOrder, AlgoType, Order, OrderDirection, OrderId, OrderTrigger, TimeInForce,
+    TrailOffset,
+};
 
 #[derive(Deserialize, ToSchema)]
 pub struct GetOrdersQuery {
@@ -82,11 +85,87 @@ pub struct PlaceOrderRequest {
     pub volume: String,
     pub price: Option<String>,
     pub direction: String,
+    /// 止损触发价；与 `price` 同时给出时为止损限价单
+    #[serde(default)]
+    pub stop_price: Option<String>,
+    /// 跟踪止损的绝对偏移
+    #[serde(default)]
+    pub trail_amount: Option<String>,
+    /// 跟踪止损的百分比偏移 (例如 5 表示 5%)
+    #[serde(default)]
+    pub trail_percent: Option<String>,
+    /// 有效期 (day, gtc, ioc, fok, gtd)，默认 gtc
+    #[serde(default)]
+    pub time_in_force: Option<String>,
+    /// GTD 订单的过期时间 (毫秒时间戳)
+    #[serde(default)]
+    pub expire_at: Option<i64>,
+}
+
+fn parse_decimal_field(value: &str, field: &str) -> Result<Decimal, ApiError> {
+    Decimal::from_str(value).map_err(|_| ApiError::BadRequest(format!("invalid {}", field)))
+}
+
+/// 按 `stop_price` / `trail_amount` / `trail_percent` 解析触发条件，三者至多给出一个。
+fn parse_trigger(req: &PlaceOrderRequest) -> Result<Option<OrderTrigger>, ApiError> {
+    let trigger = match (&req.stop_price, &req.trail_amount, &req.trail_percent) {
+        (None, None, None) => return Ok(None),
+        (Some(stop), None, None) => OrderTrigger::stop(parse_decimal_field(stop, "stop_price")?),
+        (None, Some(amount), None) => OrderTrigger::trailing(TrailOffset::Amount(
+            parse_decimal_field(amount, "trail_amount")?,
+        )),
+        (None, None, Some(percent)) => OrderTrigger::trailing(TrailOffset::Percent(
+            parse_decimal_field(percent, "trail_percent")?,
+        )),
+        _ => {
+            return Err(ApiError::BadRequest(
+                "only one of stop_price, trail_amount and trail_percent may be set".to_string(),
+            ));
+        }
+    };
+    if !trigger.is_valid() {
+        return Err(ApiError::BadRequest(
+            "stop price and trail offset must be positive".to_string(),
+        ));
+    }
+    Ok(Some(trigger))
+}
+
+fn parse_time_in_force(req: &PlaceOrderRequest) -> Result<TimeInForce, ApiError> {
+    let time_in_force = match req
+        .time_in_force
+        .as_deref()
+        .map(str::to_lowercase)
+        .as_deref()
+    {
+        None | Some("gtc") => TimeInForce::Gtc,
+        Some("day") => TimeInForce::Day,
+        Some("ioc") => TimeInForce::Ioc,
+        Some("fok") => TimeInForce::Fok,
+        Some("gtd") => TimeInForce::Gtd {
+            expire_at: req.expire_at.ok_or_else(|| {
+                ApiError::BadRequest("expire_at is required for gtd orders".to_string())
+            })?,
+        },
+        Some(_) => {
+            return Err(ApiError::BadRequest(
+                "invalid time_in_force, expected day, gtc, ioc, fok or gtd".to_string(),
+            ));
+        }
+    };
+    if req.expire_at.is_some() && !matches!(time_in_force, TimeInForce::Gtd { .. }) {
+        return Err(ApiError::BadRequest(
+            "expire_at is only allowed for gtd orders".to_string(),
+        ));
+    }
+    Ok(time_in_force)
 }
 
 /// 提交新订单
 ///
-/// 限价单将被挂载在交易队列中，市价单将与最新价直接撮合
+/// 限价单将被挂载在交易队列中，市价单将与最新价直接撮合；
+/// 带 `stop_price` 或跟踪偏移的止损单挂起等待行情触发，触发后按市价或限价撮合；
+/// IOC / FOK 单立即撮合，未成交部分过期，DAY / GTD 单到期后自动过期并解冻资金
 #[utoipa::path(
     post,
     path = "/api/v1/user/orders",
@@ -134,6 +213,8 @@ pub async fn place_order(
         ));
     }
 
+    let trigger = parse_trigger(&req)?;
+    let time_in_force = parse_time_in_force(&req)?;
     let price = match req.price {
         Some(p) => Some(
             Decimal::from_str(&p)
@@ -142,7 +223,7 @@ pub async fn place_order(
         None => None,
     };
 
-    let order = Order::new(
+    let mut order = Order::new(
         OrderId(uuid::Uuid::new_v4().to_string()),
         AccountId(req.account_id),
         req.symbol,
@@ -151,6 +232,8 @@ pub async fn place_order(
         volume,
         Utc::now().timestamp_millis(),
     );
+    order.trigger = trigger;
+    order.time_in_force = time_in_force;
 
     match state.trade_port.submit_order(order).await {
         Ok(order_id) => Ok(ApiResult(order_id.0)),
@@ -223,6 +306,28 @@ pub struct SubmitAlgoRequest {
     pub params: serde_json::Value,
 }
 
+/// 从算法参数中读取字符串形式的十进制数值
+fn decimal_param(params: &serde_json::Value, key: &str) -> Result<Decimal, ApiError> {
+    let raw = params[key]
+        .as_str()
+        .ok_or_else(|| ApiError::BadRequest(format!("missing {}", key)))?;
+    Decimal::from_str(raw).map_err(|_| ApiError::BadRequest(format!("invalid {}", key)))
+}
+
+/// 从算法参数中读取交易方向，缺省为买入
+fn direction_param(params: &serde_json::Value) -> Result<OrderDirection, ApiError> {
+    match params["direction"].as_str() {
+        None => Ok(OrderDirection::Buy),
+        Some(d) => match d.to_uppercase().as_str() {
+            "BUY" => Ok(OrderDirection::Buy),
+            "SELL" => Ok(OrderDirection::Sell),
+            _ => Err(ApiError::BadRequest(
+                "invalid direction, expected buy or sell".to_string(),
+            )),
+        },
+    }
+}
+
 /// 提交算法单
 #[utoipa::path(
     post,
@@ -257,17 +362,44 @@ pub async fn submit_algo_order(
         ));
     }
 
-    if req.algo_type != "snipe" {
-        return Err(ApiError::BadRequest("unsupported algo type".into()));
-    }
-
-    let target = req.params["target_price"]
-        .as_str()
-        .ok_or(ApiError::BadRequest("missing target_price".into()))?;
-    let algo = AlgoType::Snipe {
-        target_price: Decimal::from_str(target)
-            .map_err(|_| ApiError::BadRequest("invalid price".into()))?,
-        max_slippage: Decimal::ZERO,
+    let algo = match req.algo_type.as_str() {
+        "snipe" => AlgoType::Snipe {
+            target_price: decimal_param(&req.params, "target_price")?,
+            max_slippage: match req.params.get("max_slippage") {
+                Some(_) => decimal_param(&req.params, "max_slippage")?,
+                None => Decimal::ZERO,
+            },
+            direction: direction_param(&req.params)?,
+        },
+        "grid" => AlgoType::Grid {
+            upper_price: decimal_param(&req.params, "upper_price")?,
+            lower_price: decimal_param(&req.params, "lower_price")?,
+            grids: req.params["grids"]
+                .as_u64()
+                .and_then(|g| u32::try_from(g).ok())
+                .ok_or(ApiError::BadRequest("missing or invalid grids".into()))?,
+        },
+        "twap" => AlgoType::Twap {
+            duration_secs: req.params["duration_secs"]
+                .as_u64()
+                .ok_or(ApiError::BadRequest(
+                    "missing or invalid duration_secs".into(),
+                ))?,
+            total_volume: requested_volume,
+            direction: direction_param(&req.params)?,
+        },
+        "vwap" => AlgoType::Vwap {
+            total_volume: requested_volume,
+            start_time: req.params["start_time"]
+                .as_i64()
+                .ok_or(ApiError::BadRequest("missing or invalid start_time".into()))?,
+            end_time: req.params["end_time"]
+                .as_i64()
+                .ok_or(ApiError::BadRequest("missing or invalid end_time".into()))?,
+            max_participation: decimal_param(&req.params, "max_participation")?,
+            direction: direction_param(&req.params)?,
+        },
+        _ => return Err(ApiError::BadRequest("unsupported algo type".into())),
     };
 
     let order = AlgoOrder::new(
@@ -20,7 +20,9 @@ use okane_core::store::port::SystemStore;
 use okane_core::trade::port::{AlgoOrderPort, TradePort};
 use okane_manager::strategy::StrategyManager;
 
-use crate::routes::{account, admin, auth, backtest, market, notify, strategy, trade, watchlist};
+use crate::routes::{
+    account, admin, auth, backtest, history, market, notify, strategy, trade, watchlist,
+};
 
 // ============================================================
 //  共享应用状态
@@ -46,6 +48,8 @@ pub struct AppState {
     pub market_port: Arc<dyn Market>,
     /// 回测运行器
     pub backtest_runner: Arc<okane_manager::backtest::BacktestRunner>,
+    /// 系统时钟源 (审计与平仓单时间戳)
+    pub time_provider: Arc<dyn okane_core::common::time::TimeProvider>,
     /// 应用全局配置
     pub app_config: Arc<okane_core::config::AppConfig>,
     /// 负载优先的 Session 缓存 (session_id -> UserSession)
@@ -140,8 +144,18 @@ pub fn build_app(state: AppState) -> Router {
         .routes(routes!(account::get_account_snapshot))
         .routes(routes!(account::register_account))
         .routes(routes!(account::list_accounts))
+        .routes(routes!(account::halt_account))
+        .routes(routes!(account::resume_account))
+        .routes(routes!(account::list_account_audit))
+        .routes(routes!(account::list_daily_pnl))
+        .routes(routes!(account::deposit))
+        .routes(routes!(account::withdraw))
+        .routes(routes!(account::transfer))
+        .routes(routes!(account::convert_currency))
+        .routes(routes!(account::list_ledger))
         .routes(routes!(market::search_stocks))
         .routes(routes!(market::get_candles))
+        .routes(routes!(market::get_corporate_actions))
         .routes(routes!(market::ws_handler))
         .routes(routes!(strategy::list_strategies))
         .routes(routes!(strategy::get_strategy))
@@ -163,6 +177,8 @@ pub fn build_app(state: AppState) -> Router {
         .routes(routes!(trade::get_algo_orders))
         .routes(routes!(trade::cancel_algo_order))
         .routes(routes!(trade::get_positions))
+        .routes(routes!(history::get_order_history))
+        .routes(routes!(history::get_trade_history))
         .routes(routes!(market::get_rsi_indicator))
         .layer(axum::middleware::from_fn(
             crate::middleware::auth::require_password_changed,
@@ -176,6 +192,9 @@ pub fn build_app(state: AppState) -> Router {
     let admin_protected_router = OpenApiRouter::new()
         .routes(routes!(admin::create_user))
         .routes(routes!(admin::update_settings))
+        .routes(routes!(admin::import_corporate_actions))
+        .routes(routes!(admin::get_candle_coverage))
+        .routes(routes!(admin::repair_candle_coverage))
         .layer(axum::middleware::from_fn(
             crate::middleware::auth::require_admin,
         ))
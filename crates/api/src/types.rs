@@ -22,6 +22,18 @@ pub struct PositionResponse {
     /// 持仓均价
     #[schema(example = "175.50")]
     pub average_price: String,
+    /// 最新价
+    #[schema(example = "180.00")]
+    pub last_price: Option<String>,
+    /// 未实现盈亏
+    #[schema(example = "450.00")]
+    pub unrealized_pnl: String,
+    /// 累计已实现盈亏 (不含费用)
+    #[schema(example = "120.00")]
+    pub realized_pnl: String,
+    /// 累计交易费用
+    #[schema(example = "3.50")]
+    pub commission: String,
 }
 
 /// 账户快照 DTO - 对应 UI 顶部 Key Metrics 区域
@@ -41,6 +53,184 @@ pub struct AccountSnapshotResponse {
     pub total_equity: String,
     /// 当前持仓列表
     pub positions: Vec<PositionResponse>,
+    /// 保证金状态，仅保证金账户返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub margin: Option<MarginStatusResponse>,
+    /// 累计已实现盈亏 (不含费用)
+    #[schema(example = "5230.00")]
+    pub realized_pnl: String,
+    /// 持仓未实现盈亏
+    #[schema(example = "-820.50")]
+    pub unrealized_pnl: String,
+    /// 累计交易费用
+    #[schema(example = "86.40")]
+    pub total_commission: String,
+    /// 当日盈亏
+    #[schema(example = "1024.00")]
+    pub day_pnl: String,
+    /// 基础货币，仅多币种账户返回；此时余额为基础货币现金，权益与盈亏均折算为基础货币
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "USD")]
+    pub base_currency: Option<String>,
+    /// 分币种的现金与持仓市值 (含基础货币)，仅多币种账户返回
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub currencies: Vec<CurrencyBalanceResponse>,
+}
+
+/// 单一币种的现金与持仓市值 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CurrencyBalanceResponse {
+    /// 币种代码
+    #[schema(example = "HKD")]
+    pub currency: String,
+    /// 可用现金
+    #[schema(example = "50000.00")]
+    pub available_balance: String,
+    /// 冻结现金
+    #[schema(example = "0")]
+    pub frozen_balance: String,
+    /// 以该币种计价的持仓市值
+    #[schema(example = "38000.00")]
+    pub market_value: String,
+    /// 1 单位该币种折合的基础货币
+    #[schema(example = "0.128")]
+    pub fx_rate: Option<String>,
+    /// 折算为基础货币的权益
+    #[schema(example = "11264.00")]
+    pub base_equity: String,
+}
+
+/// 每日盈亏快照 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DailyPnlResponse {
+    /// 自然日 (UTC)
+    #[schema(example = "2024-03-04")]
+    pub date: String,
+    /// 当日开盘权益
+    #[schema(example = "100000.00")]
+    pub opening_equity: String,
+    /// 当日最后一次估值的总权益
+    #[schema(example = "101024.00")]
+    pub total_equity: String,
+    /// 累计已实现盈亏
+    #[schema(example = "5230.00")]
+    pub realized_pnl: String,
+    /// 未实现盈亏
+    #[schema(example = "-820.50")]
+    pub unrealized_pnl: String,
+    /// 累计交易费用
+    #[schema(example = "86.40")]
+    pub total_commission: String,
+    /// 当日盈亏
+    #[schema(example = "1024.00")]
+    pub day_pnl: String,
+}
+
+/// 入金 / 出金请求体
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CashMovementRequest {
+    /// 金额 (字符串格式，须为正数)
+    #[schema(example = "10000.00")]
+    pub amount: String,
+    /// 币种代码，缺省为账户基础货币；单一货币账户不可指定其他币种
+    #[serde(default)]
+    #[schema(example = "HKD")]
+    pub currency: Option<String>,
+}
+
+/// 逻辑账号间转账请求体
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TransferRequest {
+    /// 转入方逻辑交易账号 ID (须属于同一用户)
+    pub to_account_id: String,
+    /// 金额 (字符串格式，须为正数)
+    #[schema(example = "5000.00")]
+    pub amount: String,
+    /// 币种代码，缺省为基础货币
+    #[serde(default)]
+    #[schema(example = "USD")]
+    pub currency: Option<String>,
+}
+
+/// 账户内货币兑换请求体
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CurrencyConversionRequest {
+    /// 换出币种
+    #[schema(example = "USD")]
+    pub from_currency: String,
+    /// 换入币种
+    #[schema(example = "HKD")]
+    pub to_currency: String,
+    /// 换出金额 (字符串格式，须为正数)
+    #[schema(example = "1000.00")]
+    pub amount: String,
+}
+
+/// 资金流水 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LedgerEntryResponse {
+    /// 账户内流水号
+    #[schema(example = 42)]
+    pub id: i64,
+    /// 流水类型 (Deposit, Withdrawal, TransferIn, TransferOut, FreezeFunds, UnfreezeFunds, FreezeMargin, TradeFilled, BorrowFee, Split, Dividend, FxConversion)
+    #[schema(example = "Deposit")]
+    pub action: String,
+    /// 可用资金变动
+    #[schema(example = "10000.00")]
+    pub asset_change: String,
+    /// 冻结资金变动
+    #[schema(example = "0")]
+    pub frozen_change: String,
+    /// 变动后的可用资金
+    #[schema(example = "110000.00")]
+    pub available_balance: String,
+    /// 变动后的冻结资金
+    #[schema(example = "0")]
+    pub frozen_balance: String,
+    /// 关联业务标识 (转账 ID、兑换 ID 或成交的订单 ID)
+    pub reference: Option<String>,
+    /// 变动的现金币种，为空表示基础货币
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// 发生时间 (毫秒级时间戳)
+    #[schema(example = 1710000000000_i64)]
+    pub created_at: i64,
+}
+
+/// 转账结果 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TransferResponse {
+    /// 转账 ID
+    pub transfer_id: String,
+    /// 转出方流水
+    pub outgoing: LedgerEntryResponse,
+    /// 转入方流水
+    pub incoming: LedgerEntryResponse,
+}
+
+/// 保证金状态 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MarginStatusResponse {
+    /// 总敞口 (各标的 |持仓| × 最新价之和)
+    #[schema(example = "150000.00")]
+    pub gross_exposure: String,
+    /// 空头市值
+    #[schema(example = "50000.00")]
+    pub short_market_value: String,
+    /// 初始保证金占用
+    #[schema(example = "75000.00")]
+    pub initial_requirement: String,
+    /// 维持保证金要求
+    #[schema(example = "37500.00")]
+    pub maintenance_requirement: String,
+    /// 剩余保证金额度 (可为负)
+    #[schema(example = "15000.00")]
+    pub excess_margin: String,
+    /// 剩余购买力 (名义金额)
+    #[schema(example = "30000.00")]
+    pub buying_power: String,
+    /// 是否处于追保状态
+    pub margin_call: bool,
 }
 
 /// 逻辑交易账号档案 DTO
@@ -61,6 +251,68 @@ pub struct AccountProfileResponse {
     /// 创建时间
     #[schema(example = "2026-03-19T10:00:00Z")]
     pub created_at: String,
+    /// 是否已紧急停止 (停止期间拒绝新委托)
+    #[schema(example = false)]
+    pub halted: bool,
+}
+
+/// 紧急停止账号请求体
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct HaltAccountRequest {
+    /// 是否以市价单平掉全部持仓
+    #[serde(default)]
+    #[schema(example = true)]
+    pub flatten: bool,
+    /// 停止原因，写入审计记录
+    #[schema(example = "strategy runaway")]
+    pub reason: Option<String>,
+}
+
+/// 重新启用账号请求体
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ResumeAccountRequest {
+    /// 重新启用原因，写入审计记录
+    #[schema(example = "issue resolved")]
+    pub reason: Option<String>,
+}
+
+/// 紧急停止执行结果 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct KillSwitchResponse {
+    /// 逻辑交易账号 ID
+    pub account_id: String,
+    /// 被停止的策略实例 ID
+    pub stopped_strategies: Vec<String>,
+    /// 被取消的算法单 ID
+    pub canceled_algo_orders: Vec<String>,
+    /// 被撤销的委托单 ID
+    pub canceled_orders: Vec<String>,
+    /// 为平仓提交的市价单 ID
+    pub flatten_orders: Vec<String>,
+    /// 未能完成的步骤
+    pub errors: Vec<String>,
+}
+
+/// 账号审计记录 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountAuditResponse {
+    /// 审计记录 ID
+    pub id: String,
+    /// 逻辑交易账号 ID
+    pub account_id: String,
+    /// 动作：`Halt` / `Resume`
+    #[schema(example = "Halt")]
+    pub action: String,
+    /// 触发动作的用户 ID
+    #[schema(example = "admin")]
+    pub operator_id: String,
+    /// 动作原因
+    pub reason: Option<String>,
+    /// 执行明细
+    pub detail: serde_json::Value,
+    /// 记录时间
+    #[schema(example = "2026-03-19T10:00:00Z")]
+    pub created_at: String,
 }
 
 /// 创建新逻辑交易账号请求体
@@ -107,6 +359,51 @@ pub struct OrderResponse {
     /// 创建时间 (毫秒级时间戳)
     #[schema(example = 1710000000000_i64)]
     pub created_at: i64,
+    /// 订单类型 (market, limit, stop, stop_limit, trailing_stop)
+    #[schema(example = "stop_limit")]
+    pub order_type: String,
+    /// 当前触发价 (跟踪止损随最有利价格移动，非止损单为 null)
+    #[schema(example = "118.00")]
+    pub stop_price: Option<String>,
+    /// 跟踪止损的绝对偏移
+    pub trail_amount: Option<String>,
+    /// 跟踪止损的百分比偏移
+    pub trail_percent: Option<String>,
+    /// 止损条件是否已触发
+    pub triggered: bool,
+    /// 有效期 (day, gtc, ioc, fok, gtd)
+    #[schema(example = "day")]
+    pub time_in_force: String,
+    /// 过期时间 (毫秒级时间戳，GTC 为 null)
+    pub expire_at: Option<i64>,
+    /// 下单的策略运行 ID (手工委托为 null)
+    pub strategy_run_id: Option<String>,
+}
+
+/// 订单状态变迁 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderStatusEventResponse {
+    /// 变迁后的状态
+    #[schema(example = "PartialFilled")]
+    pub status: String,
+    /// 变迁时的累计成交数量
+    #[schema(example = "50")]
+    pub filled_volume: String,
+    /// 变迁时间 (毫秒级时间戳)
+    #[schema(example = 1710000000000_i64)]
+    pub timestamp: i64,
+}
+
+/// 订单历史 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderHistoryResponse {
+    /// 订单的最新状态
+    pub order: OrderResponse,
+    /// 按时间先后排列的状态变迁
+    pub events: Vec<OrderStatusEventResponse>,
+    /// 最近一次变迁时间 (毫秒级时间戳)
+    #[schema(example = 1710000000000_i64)]
+    pub updated_at: i64,
 }
 
 /// 算法单 DTO
@@ -121,7 +418,7 @@ pub struct AlgoOrderResponse {
     /// 股票代码
     #[schema(example = "NVDA")]
     pub symbol: String,
-    /// 算法类型 (当前对外支持 Snipe)
+    /// 算法类型 (当前对外支持 Snipe、Grid、Twap、Vwap)
     #[schema(example = "Snipe")]
     pub algo_type: String,
     /// 算法参数 (JSON 对象)
@@ -216,6 +513,79 @@ pub struct CandleResponse {
     pub is_final: bool,
 }
 
+/// 公司行动 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorporateActionResponse {
+    /// 证券代码
+    #[schema(example = "AAPL")]
+    pub symbol: String,
+    /// 除权除息日 (RFC3339)
+    #[schema(example = "2020-08-31T00:00:00Z")]
+    pub ex_date: String,
+    /// 行动类型 ("split" 或 "dividend")
+    #[schema(example = "split")]
+    pub action_type: String,
+    /// 拆股后股数 (仅拆股)
+    #[schema(example = 4)]
+    pub numerator: Option<u32>,
+    /// 拆股前股数 (仅拆股)
+    #[schema(example = 1)]
+    pub denominator: Option<u32>,
+    /// 每股分红金额 (仅分红)
+    #[schema(example = "0.24")]
+    pub amount: Option<String>,
+}
+
+/// 导入公司行动请求体 (仅管理员)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportCorporateActionsRequest {
+    /// 待导入的公司行动，`symbol` 须与路径中的证券代码一致；同日同类型的记录将被覆盖
+    pub actions: Vec<CorporateActionResponse>,
+}
+
+/// K 线覆盖查询与修复请求 (仅管理员)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CandleCoverageRequest {
+    /// K 线周期 (如 "1m", "1d")
+    #[schema(example = "1d")]
+    pub tf: String,
+    /// 开始时间 (RFC3339)
+    #[schema(example = "2024-01-01T00:00:00Z")]
+    pub start: String,
+    /// 结束时间 (RFC3339)
+    #[schema(example = "2024-12-31T00:00:00Z")]
+    pub end: String,
+    /// 仅修复时生效：先丢弃区间内已记录的覆盖，再整段向数据源重新拉取
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// 时间区间 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimeRangeResponse {
+    /// 开始时间 (RFC3339)
+    #[schema(example = "2024-01-01T00:00:00+00:00")]
+    pub start: String,
+    /// 结束时间 (RFC3339)
+    #[schema(example = "2024-06-30T00:00:00+00:00")]
+    pub end: String,
+}
+
+/// K 线覆盖情况 DTO
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CandleCoverageResponse {
+    /// 证券代码
+    #[schema(example = "AAPL")]
+    pub symbol: String,
+    /// K 线周期
+    #[schema(example = "1d")]
+    pub timeframe: String,
+    /// 已从数据源拉取并保存的区间
+    pub covered: Vec<TimeRangeResponse>,
+    /// 尚未拉取的缺口
+    pub gaps: Vec<TimeRangeResponse>,
+}
+
 // ============================================================
 //  策略相关 DTO
 // ============================================================
@@ -378,6 +748,22 @@ pub struct BacktestRequest {
     /// 策略源码 (base64 编码的脚本)
     #[schema(example = "Y29uc29sZS5sb2coJ2hlbGxvJyk7")]
     pub source_base64: String,
+    /// 可选的逻辑交易账号 ID，回测按该账号配置中的成本模型撮合；缺省时不计滑点与费用
+    #[serde(default)]
+    #[schema(example = "acct_3f2a")]
+    pub account_id: Option<String>,
+    /// K 线价格口径 ("raw"、"split_adjusted" 或 "total_return")，缺省为 "raw"
+    #[serde(default)]
+    #[schema(example = "split_adjusted")]
+    pub price_adjustment: Option<String>,
+    /// 同一标的单根 K 线内累计成交量占 K 线成交量的上限，取值 (0, 1]；缺省不限
+    #[serde(default)]
+    #[schema(example = "0.1")]
+    pub max_participation: Option<String>,
+    /// K 线内部价格路径假设 ("ohlc" 或 "olhc")，缺省为 "ohlc"
+    #[serde(default)]
+    #[schema(example = "olhc")]
+    pub ohlc_path: Option<String>,
 }
 
 /// 回测结果
@@ -735,6 +1121,10 @@ impl From<okane_core::trade::entity::Position> for PositionResponse {
             symbol: p.symbol,
             volume: p.volume.to_string(),
             average_price: p.average_price.to_string(),
+            last_price: p.last_price.map(|price| price.to_string()),
+            unrealized_pnl: p.unrealized_pnl.to_string(),
+            realized_pnl: p.realized_pnl.to_string(),
+            commission: p.commission.to_string(),
         }
     }
 }
@@ -747,6 +1137,80 @@ impl From<okane_core::trade::entity::AccountSnapshot> for AccountSnapshotRespons
             frozen_balance: s.frozen_balance.to_string(),
             total_equity: s.total_equity.to_string(),
             positions: s.positions.into_iter().map(Into::into).collect(),
+            margin: s.margin.map(Into::into),
+            realized_pnl: s.realized_pnl.to_string(),
+            unrealized_pnl: s.unrealized_pnl.to_string(),
+            total_commission: s.total_commission.to_string(),
+            day_pnl: s.day_pnl.to_string(),
+            base_currency: s.base_currency,
+            currencies: s.currencies.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<okane_core::trade::entity::CurrencyBalance> for CurrencyBalanceResponse {
+    fn from(b: okane_core::trade::entity::CurrencyBalance) -> Self {
+        Self {
+            currency: b.currency,
+            available_balance: b.available_balance.to_string(),
+            frozen_balance: b.frozen_balance.to_string(),
+            market_value: b.market_value.to_string(),
+            fx_rate: b.fx_rate.map(|rate| rate.to_string()),
+            base_equity: b.base_equity.to_string(),
+        }
+    }
+}
+
+impl From<okane_core::trade::entity::DailyPnl> for DailyPnlResponse {
+    fn from(d: okane_core::trade::entity::DailyPnl) -> Self {
+        Self {
+            date: d.date.to_string(),
+            opening_equity: d.opening_equity.to_string(),
+            total_equity: d.total_equity.to_string(),
+            realized_pnl: d.realized_pnl.to_string(),
+            unrealized_pnl: d.unrealized_pnl.to_string(),
+            total_commission: d.total_commission.to_string(),
+            day_pnl: d.day_pnl.to_string(),
+        }
+    }
+}
+
+impl From<okane_core::trade::entity::LedgerEntry> for LedgerEntryResponse {
+    fn from(e: okane_core::trade::entity::LedgerEntry) -> Self {
+        Self {
+            id: e.id,
+            action: format!("{:?}", e.action),
+            asset_change: e.asset_change.to_string(),
+            frozen_change: e.frozen_change.to_string(),
+            available_balance: e.available_balance.to_string(),
+            frozen_balance: e.frozen_balance.to_string(),
+            reference: e.reference,
+            currency: e.currency,
+            created_at: e.created_at,
+        }
+    }
+}
+
+impl From<okane_core::trade::entity::CashTransfer> for TransferResponse {
+    fn from(t: okane_core::trade::entity::CashTransfer) -> Self {
+        Self {
+            transfer_id: t.transfer_id,
+            outgoing: t.outgoing.into(),
+            incoming: t.incoming.into(),
+        }
+    }
+}
+
+impl From<okane_core::trade::margin::MarginStatus> for MarginStatusResponse {
+    fn from(m: okane_core::trade::margin::MarginStatus) -> Self {
+        Self {
+            gross_exposure: m.gross_exposure.to_string(),
+            short_market_value: m.short_market_value.to_string(),
+            initial_requirement: m.initial_requirement.to_string(),
+            maintenance_requirement: m.maintenance_requirement.to_string(),
+            excess_margin: m.excess_margin.to_string(),
+            buying_power: m.buying_power.to_string(),
+            margin_call: m.margin_call,
         }
     }
 }
@@ -759,6 +1223,34 @@ impl From<okane_core::store::port::AccountProfile> for AccountProfileResponse {
             account_type: profile.account_type,
             config: profile.config,
             created_at: profile.created_at.to_rfc3339(),
+            halted: profile.halted,
+        }
+    }
+}
+
+impl From<okane_manager::kill_switch::KillSwitchReport> for KillSwitchResponse {
+    fn from(report: okane_manager::kill_switch::KillSwitchReport) -> Self {
+        Self {
+            account_id: report.account_id,
+            stopped_strategies: report.stopped_strategies,
+            canceled_algo_orders: report.canceled_algo_orders,
+            canceled_orders: report.canceled_orders,
+            flatten_orders: report.flatten_orders,
+            errors: report.errors,
+        }
+    }
+}
+
+impl From<okane_core::store::port::AccountAuditRecord> for AccountAuditResponse {
+    fn from(record: okane_core::store::port::AccountAuditRecord) -> Self {
+        Self {
+            id: record.id,
+            account_id: record.account_id,
+            action: record.action.to_string(),
+            operator_id: record.operator_id,
+            reason: record.reason,
+            detail: record.detail,
+            created_at: record.created_at.to_rfc3339(),
         }
     }
 }
@@ -820,8 +1312,98 @@ impl From<okane_core::market::entity::Candle> for CandleResponse {
     }
 }
 
+impl From<okane_core::market::coverage::TimeRange> for TimeRangeResponse {
+    fn from(r: okane_core::market::coverage::TimeRange) -> Self {
+        Self {
+            start: r.start.to_rfc3339(),
+            end: r.end.to_rfc3339(),
+        }
+    }
+}
+
+impl From<okane_core::market::entity::CorporateAction> for CorporateActionResponse {
+    fn from(a: okane_core::market::entity::CorporateAction) -> Self {
+        use okane_core::market::entity::CorporateActionKind;
+        let (action_type, numerator, denominator, amount) = match a.kind {
+            CorporateActionKind::Split {
+                numerator,
+                denominator,
+            } => ("split", Some(numerator), Some(denominator), None),
+            CorporateActionKind::Dividend { amount } => {
+                ("dividend", None, None, Some(amount.to_string()))
+            }
+        };
+        Self {
+            symbol: a.symbol,
+            ex_date: a.ex_date.to_rfc3339(),
+            action_type: action_type.to_string(),
+            numerator,
+            denominator,
+            amount,
+        }
+    }
+}
+
+impl TryFrom<CorporateActionResponse> for okane_core::market::entity::CorporateAction {
+    type Error = String;
+
+    fn try_from(a: CorporateActionResponse) -> Result<Self, Self::Error> {
+        use okane_core::market::entity::CorporateActionKind;
+        let ex_date = chrono::DateTime::parse_from_rfc3339(&a.ex_date)
+            .map_err(|e| format!("invalid ex_date: {}", e))?
+            .with_timezone(&chrono::Utc);
+        let kind = match a.action_type.to_lowercase().as_str() {
+            "split" => CorporateActionKind::Split {
+                numerator: a.numerator.ok_or("split requires numerator")?,
+                denominator: a.denominator.ok_or("split requires denominator")?,
+            },
+            "dividend" => CorporateActionKind::Dividend {
+                amount: a
+                    .amount
+                    .as_deref()
+                    .ok_or("dividend requires amount")?
+                    .parse()
+                    .map_err(|e| format!("invalid dividend amount: {}", e))?,
+            },
+            other => return Err(format!("unknown corporate action type: {}", other)),
+        };
+        let action = Self {
+            symbol: a.symbol,
+            ex_date,
+            kind,
+        };
+        action.validate()?;
+        Ok(action)
+    }
+}
+
 impl From<okane_core::trade::entity::Order> for OrderResponse {
     fn from(o: okane_core::trade::entity::Order) -> Self {
+        use okane_core::trade::entity::{TimeInForce, TrailOffset, TriggerKind};
+
+        let order_type = match (&o.trigger, o.price) {
+            (None, None) => "market",
+            (None, Some(_)) => "limit",
+            (Some(t), _) if matches!(t.kind, TriggerKind::TrailingStop { .. }) => "trailing_stop",
+            (Some(_), None) => "stop",
+            (Some(_), Some(_)) => "stop_limit",
+        };
+        let stop_price = o.trigger.as_ref().and_then(|t| match t.kind {
+            TriggerKind::Stop { stop_price } => Some(stop_price),
+            TriggerKind::TrailingStop { .. } => {
+                t.extreme.map(|extreme| t.level_from(o.direction, extreme))
+            }
+        });
+        let (trail_amount, trail_percent) = match o.trigger.as_ref().map(|t| t.kind) {
+            Some(TriggerKind::TrailingStop {
+                offset: TrailOffset::Amount(amount),
+            }) => (Some(amount.to_string()), None),
+            Some(TriggerKind::TrailingStop {
+                offset: TrailOffset::Percent(percent),
+            }) => (None, Some(percent.to_string())),
+            _ => (None, None),
+        };
+
         Self {
             id: o.id.0,
             account_id: o.account_id.0,
@@ -832,6 +1414,39 @@ impl From<okane_core::trade::entity::Order> for OrderResponse {
             filled_volume: o.filled_volume.to_string(),
             status: format!("{:?}", o.status),
             created_at: o.created_at,
+            order_type: order_type.to_string(),
+            stop_price: stop_price.map(|p| p.to_string()),
+            trail_amount,
+            trail_percent,
+            triggered: o.trigger.as_ref().is_some_and(|t| t.triggered),
+            time_in_force: match o.time_in_force {
+                TimeInForce::Gtc => "gtc",
+                TimeInForce::Day => "day",
+                TimeInForce::Ioc => "ioc",
+                TimeInForce::Fok => "fok",
+                TimeInForce::Gtd { .. } => "gtd",
+            }
+            .to_string(),
+            expire_at: o.expire_at,
+            strategy_run_id: o.strategy_run_id,
+        }
+    }
+}
+
+impl From<okane_core::trade::entity::OrderHistoryRecord> for OrderHistoryResponse {
+    fn from(r: okane_core::trade::entity::OrderHistoryRecord) -> Self {
+        Self {
+            order: r.order.into(),
+            events: r
+                .events
+                .into_iter()
+                .map(|e| OrderStatusEventResponse {
+                    status: format!("{:?}", e.status),
+                    filled_volume: e.filled_volume.to_string(),
+                    timestamp: e.timestamp,
+                })
+                .collect(),
+            updated_at: r.updated_at,
         }
     }
 }
@@ -2,10 +2,12 @@ mod common;
 
 use base64::{Engine as _, engine::general_purpose::STANDARD};
 use okane_api::types::{
-    ApiResponse, ChangePasswordRequest, CreateAccountRequest, CreateUserRequest, LoginRequest,
-    LoginResponse, StartStrategyRequest, StrategyResponse,
+    ApiResponse, CandleCoverageRequest, CandleCoverageResponse, ChangePasswordRequest,
+    CreateAccountRequest, CreateUserRequest, LoginRequest, LoginResponse, StartStrategyRequest,
+    StrategyResponse,
 };
 use reqwest::StatusCode;
+use rust_decimal_macros::dec;
 
 use anyhow::Context;
 use common::spawn_test_server;
@@ -162,6 +164,149 @@ async fn test_admin_user_management() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_admin_reports_and_repairs_candle_coverage() -> anyhow::Result<()> {
+    use chrono::TimeZone;
+
+    let (base_url, _store, _tmp, _keepalive, feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+
+    let res = assert_post!(
+        &client,
+        format!("{}/api/v1/auth/login", base_url),
+        None::<&str>,
+        &LoginRequest {
+            username: "admin".to_string(),
+            password: "test_admin_pwd".to_string(),
+            client_id: "test_client_id".to_string(),
+        },
+        StatusCode::OK
+    );
+    let admin_token = res
+        .json::<ApiResponse<LoginResponse>>()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .data
+        .ok_or_else(|| anyhow::anyhow!("Admin token null"))?
+        .access_token;
+
+    let mut history = Vec::new();
+    for d in 2..=4 {
+        let time = chrono::Utc
+            .with_ymd_and_hms(2024, 1, d, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?;
+        history.push(okane_core::market::entity::Candle {
+            time,
+            open: dec!(100),
+            high: dec!(100),
+            low: dec!(100),
+            close: dec!(100),
+            adj_close: None,
+            volume: dec!(1000),
+            is_final: true,
+        });
+    }
+    feed.set_history(history.clone())?;
+
+    let request = CandleCoverageRequest {
+        tf: "1d".to_string(),
+        start: "2024-01-01T00:00:00Z".to_string(),
+        end: "2024-01-04T00:00:00Z".to_string(),
+        force: false,
+    };
+    let res = assert_get!(
+        &client,
+        format!(
+            "{}/api/v1/admin/market/coverage/AAPL?tf={}&start={}&end={}",
+            base_url, request.tf, request.start, request.end
+        ),
+        Some(&admin_token),
+        StatusCode::OK
+    );
+    let before = res
+        .json::<ApiResponse<CandleCoverageResponse>>()
+        .await?
+        .data
+        .context("coverage data null")?;
+    assert!(before.covered.is_empty());
+    assert_eq!(before.gaps.len(), 1);
+
+    let res = assert_post!(
+        &client,
+        format!("{}/api/v1/admin/market/coverage/AAPL/repair", base_url),
+        Some(&admin_token),
+        &request,
+        StatusCode::OK
+    );
+    let after = res
+        .json::<ApiResponse<CandleCoverageResponse>>()
+        .await?
+        .data
+        .context("coverage data null")?;
+    assert_eq!(after.timeframe, "1d");
+    assert_eq!(after.covered.len(), 1);
+    assert!(after.gaps.is_empty());
+
+    // 数据源修正历史后，普通修复不再拉取已覆盖区间，强制修复整段重新拉取
+    for candle in &mut history {
+        candle.close = dec!(101);
+    }
+    feed.set_history(history)?;
+    let closes = |candles: Vec<okane_api::types::CandleResponse>| -> Vec<String> {
+        candles.into_iter().map(|c| c.close).collect()
+    };
+    let candles_url = format!(
+        "{}/api/v1/market/candles/AAPL?tf={}&start={}&end={}",
+        base_url, request.tf, request.start, request.end
+    );
+    assert_post!(
+        &client,
+        format!("{}/api/v1/admin/market/coverage/AAPL/repair", base_url),
+        Some(&admin_token),
+        &request,
+        StatusCode::OK
+    );
+    let res = assert_get!(&client, &candles_url, Some(&admin_token), StatusCode::OK);
+    let cached = res
+        .json::<ApiResponse<Vec<okane_api::types::CandleResponse>>>()
+        .await?
+        .data
+        .context("candles data null")?;
+    assert_eq!(closes(cached), vec!["100"; 3]);
+    assert_post!(
+        &client,
+        format!("{}/api/v1/admin/market/coverage/AAPL/repair", base_url),
+        Some(&admin_token),
+        &CandleCoverageRequest {
+            force: true,
+            ..request.clone()
+        },
+        StatusCode::OK
+    );
+    let res = assert_get!(&client, &candles_url, Some(&admin_token), StatusCode::OK);
+    let refetched = res
+        .json::<ApiResponse<Vec<okane_api::types::CandleResponse>>>()
+        .await?
+        .data
+        .context("candles data null")?;
+    assert_eq!(closes(refetched), vec!["101"; 3]);
+
+    assert_post!(
+        &client,
+        format!("{}/api/v1/admin/market/coverage/AAPL/repair", base_url),
+        Some(&admin_token),
+        &CandleCoverageRequest {
+            tf: "1d".to_string(),
+            start: request.end.clone(),
+            end: request.start.clone(),
+            force: false,
+        },
+        StatusCode::BAD_REQUEST
+    );
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_user_account_and_strategy_deployment() -> anyhow::Result<()> {
     let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
@@ -446,5 +591,41 @@ async fn test_strategy_backtest() -> anyhow::Result<()> {
         result.candle_count > 0,
         "backtest must report processed candles"
     );
+
+    // 成交模型参数：合法取值按请求撮合，非法取值拒绝
+    for (fill_model, status) in [
+        (
+            serde_json::json!({ "max_participation": "0.1", "ohlc_path": "olhc" }),
+            StatusCode::OK,
+        ),
+        (
+            serde_json::json!({ "max_participation": "1.5" }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            serde_json::json!({ "ohlc_path": "sideways" }),
+            StatusCode::BAD_REQUEST,
+        ),
+    ] {
+        let mut body = serde_json::json!({
+            "symbol": "AAPL",
+            "timeframe": "1d",
+            "start": start_time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "end": end_time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "initial_balance": "10000.00",
+            "engine_type": "JavaScript",
+            "source_base64": encode_js_source(js_code)
+        });
+        if let (Some(body), Some(fields)) = (body.as_object_mut(), fill_model.as_object()) {
+            body.extend(fields.clone());
+        }
+        assert_post!(
+            &client,
+            format!("{}/api/v1/user/backtest", base_url),
+            Some(&token),
+            &body,
+            status
+        );
+    }
     Ok(())
 }
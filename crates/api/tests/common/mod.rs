@@ -11,7 +11,7 @@ use okane_manager::backtest::{
     BacktestEnvironment, BacktestEnvironmentFactory, BacktestRequest, BacktestResultCollector,
 };
 use okane_manager::strategy::StrategyManager;
-use okane_market::history::BacktestMarket;
+use okane_market::history::{BacktestMarket, BacktestStock};
 use okane_market::indicator::MarketIndicatorService;
 use okane_market::manager::MarketImpl;
 use okane_store::market::SqliteMarketStore;
@@ -143,7 +143,11 @@ impl BacktestEnvironmentFactory for TestBacktestEnvironmentFactory {
         account_manager.ensure_account_exists(backtest_account_id.clone(), req.initial_balance);
 
         let pending_port = Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new());
-        let matcher = Arc::new(LocalMatchEngine::new(Decimal::ZERO));
+        let matcher = Arc::new(
+            LocalMatchEngine::with_cost_model(req.cost_model.clone()).with_fill_model(Arc::new(
+                okane_trade::fill_model::BarFillModel::from_config(&req.fill_model),
+            )),
+        );
         let trade_log = Arc::new(TradeLog::new());
         let lazy_market = Arc::new(TestLazyMarket::new());
         let candle_counter = Arc::new(AtomicUsize::new(0));
@@ -159,14 +163,17 @@ impl BacktestEnvironmentFactory for TestBacktestEnvironmentFactory {
             .with_trade_log(trade_log.clone()),
         );
 
-        let backtest_market: Arc<dyn Market> = Arc::new(BacktestMarket::with_source(
-            req.symbol.clone(),
-            source_stock,
-            req.start,
-            req.end,
-            fake_clock.clone(),
-            trade_service.clone(),
-            candle_counter.clone(),
+        let backtest_market: Arc<dyn Market> = Arc::new(BacktestMarket::from_stock(
+            BacktestStock::with_source(
+                req.symbol.clone(),
+                source_stock,
+                req.start,
+                req.end,
+                fake_clock.clone(),
+                trade_service.clone(),
+                candle_counter.clone(),
+            )
+            .with_price_adjustment(req.price_adjustment),
         ));
         lazy_market.set(backtest_market.clone()).map_err(|e| {
             okane_manager::strategy::ManagerError::Engine(
@@ -346,21 +353,40 @@ pub async fn spawn_test_server() -> anyhow::Result<(
         rust_decimal::Decimal::new(10_000_000, 2), // $100k test money
     );
     let pending_port = Arc::new(
-        okane_store::pending_order_sqlx::SqlitePendingOrderStore::new_with_path(Some(root_path))
-            .map_err(|e| anyhow::anyhow!("Failed to init SqlitePendingOrderStore: {}", e))?,
+        okane_store::pending_order_sqlx::SqlitePendingOrderStore::new_with_path(Some(
+            root_path.clone(),
+        ))
+        .map_err(|e| anyhow::anyhow!("Failed to init SqlitePendingOrderStore: {}", e))?,
+    );
+    let order_history = Arc::new(
+        okane_store::order_history_sqlx::SqliteOrderHistoryStore::new_with_path(Some(root_path))
+            .map_err(|e| anyhow::anyhow!("Failed to init SqliteOrderHistoryStore: {}", e))?,
     );
     let matcher = std::sync::Arc::new(LocalMatchEngine::new(rust_decimal::Decimal::ZERO));
-    let local_trade_service = Arc::new(TradeService::new(
-        account_manager,
-        matcher,
-        market.clone(),
-        pending_port,
-        Arc::new(okane_core::common::time::RealTimeProvider),
-    ));
-    let trade_service = Arc::new(okane_trade::router::RoutedTradePort::new(
+    let local_trade_service = Arc::new(
+        TradeService::new(
+            account_manager,
+            matcher,
+            market.clone(),
+            pending_port,
+            Arc::new(okane_core::common::time::RealTimeProvider),
+        )
+        .with_account_cost_models(
+            system_store.clone(),
+            LocalMatchEngine::factory(Arc::new(okane_trade::fill_model::BarFillModel::new())),
+        )
+        .with_order_history(order_history),
+    );
+    let routed_trade_port = Arc::new(okane_trade::router::RoutedTradePort::new(
         local_trade_service.clone(),
         system_store.clone(),
     ));
+    let trade_service = Arc::new(okane_trade::risk::RiskControlledTradePort::new(
+        routed_trade_port,
+        system_store.clone(),
+        market.clone(),
+        Arc::new(okane_core::common::time::RealTimeProvider),
+    ));
 
     let engine_builder_factory = Arc::new(|m: Arc<dyn okane_core::market::port::Market>| {
         Arc::new(okane_engine::factory::EngineFactory::new(m))
@@ -402,6 +428,7 @@ pub async fn spawn_test_server() -> anyhow::Result<(
         system_store: system_store.clone(),
         market_port: market,
         backtest_runner,
+        time_provider: Arc::new(okane_core::common::time::RealTimeProvider),
         app_config,
         session_cache: Arc::new(dashmap::DashMap::new()),
     };
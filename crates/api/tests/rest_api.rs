@@ -3,8 +3,9 @@ mod common;
 use anyhow::Context;
 use common::spawn_test_server;
 use okane_api::types::{
-    AccountProfileResponse, AccountSnapshotResponse, ApiResponse, CreateAccountRequest,
-    LoginRequest, LoginResponse, NotifyConfigResponse, OrderResponse, StockMetadataResponse,
+    AccountProfileResponse, AccountSnapshotResponse, ApiResponse, CashMovementRequest,
+    CreateAccountRequest, LedgerEntryResponse, LoginRequest, LoginResponse, NotifyConfigResponse,
+    OrderHistoryResponse, OrderResponse, StockMetadataResponse, TransferRequest, TransferResponse,
     UpdateNotifyConfigRequest, UpdateSettingsRequest, WatchlistRequest,
 };
 use reqwest::StatusCode;
@@ -436,5 +437,248 @@ async fn test_manual_trade_api() -> anyhow::Result<()> {
         !orders_page.items.iter().any(|o| o.id == order_id),
         "Order should be removed from active list after cancellation"
     );
+
+    // 5. 订单历史保留已撤销订单及其状态变迁
+    let res = assert_get!(
+        &client,
+        format!(
+            "{}/api/v1/user/account/{}/history/orders?symbol=AAPL&status=canceled",
+            base_url, trade_account_id
+        ),
+        Some(&token),
+        StatusCode::OK
+    );
+    let history = res
+        .json::<ApiResponse<okane_api::types::Page<OrderHistoryResponse>>>()
+        .await
+        .map_err(|e| anyhow::anyhow!("Parse: {}", e))?
+        .data
+        .ok_or_else(|| anyhow::anyhow!("Data null"))?;
+    let record = history
+        .items
+        .iter()
+        .find(|r| r.order.id == order_id)
+        .context("canceled order missing from history")?;
+    assert_eq!(
+        record.events.last().map(|e| e.status.as_str()),
+        Some("Canceled")
+    );
+    assert!(record.events.len() >= 2);
+
+    // 6. CSV 导出
+    let res = assert_get!(
+        &client,
+        format!(
+            "{}/api/v1/user/account/{}/history/orders?format=csv",
+            base_url, trade_account_id
+        ),
+        Some(&token),
+        StatusCode::OK
+    );
+    let content_type = res
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    assert!(content_type.starts_with("text/csv"));
+    let csv = res.text().await?;
+    assert!(csv.starts_with("id,symbol,direction"));
+    assert!(csv.lines().any(|line| line.starts_with(&order_id)));
+
+    assert_get!(
+        &client,
+        format!(
+            "{}/api/v1/user/account/{}/history/orders?status=unknown",
+            base_url, trade_account_id
+        ),
+        Some(&token),
+        StatusCode::BAD_REQUEST
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cash_ledger_api() -> anyhow::Result<()> {
+    let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+
+    let res = assert_post!(
+        &client,
+        format!("{}/api/v1/auth/login", base_url),
+        None::<&str>,
+        &LoginRequest {
+            username: "admin".to_string(),
+            password: "test_admin_pwd".to_string(),
+            client_id: "cash_ledger_client".to_string(),
+        },
+        StatusCode::OK
+    );
+    let token = res
+        .json::<ApiResponse<LoginResponse>>()
+        .await
+        .map_err(|e| anyhow::anyhow!("Parse login: {}", e))?
+        .data
+        .ok_or_else(|| anyhow::anyhow!("Token null"))?
+        .access_token;
+
+    let mut account_ids = Vec::new();
+    for (name, balance) in [("Cash Main", "1000.00"), ("Cash Side", "0")] {
+        let res = assert_post!(
+            &client,
+            format!("{}/api/v1/user/account", base_url),
+            Some(&token),
+            &CreateAccountRequest {
+                account_name: name.to_string(),
+                account_type: "local".to_string(),
+                config: serde_json::json!({ "initial_balance": balance }),
+            },
+            StatusCode::OK
+        );
+        account_ids.push(
+            res.json::<ApiResponse<AccountProfileResponse>>()
+                .await
+                .map_err(|e| anyhow::anyhow!("Parse create account: {}", e))?
+                .data
+                .ok_or_else(|| anyhow::anyhow!("Create account data null"))?
+                .account_id,
+        );
+    }
+    let (main, side) = (&account_ids[0], &account_ids[1]);
+
+    // 1. 入金
+    let res = assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/deposit", base_url, main),
+        Some(&token),
+        &CashMovementRequest {
+            amount: "500".to_string(),
+            currency: None,
+        },
+        StatusCode::OK
+    );
+    let entry = res
+        .json::<ApiResponse<LedgerEntryResponse>>()
+        .await?
+        .data
+        .context("deposit data null")?;
+    assert_eq!(entry.action, "Deposit");
+    assert_eq!(
+        rust_decimal::Decimal::from_str(&entry.available_balance)?,
+        rust_decimal::Decimal::from(1500)
+    );
+
+    // 2. 出金超出可用资金被拒绝，金额非法被拒绝
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/withdraw", base_url, main),
+        Some(&token),
+        &CashMovementRequest {
+            amount: "2000".to_string(),
+            currency: None,
+        },
+        StatusCode::BAD_REQUEST
+    );
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/withdraw", base_url, main),
+        Some(&token),
+        &CashMovementRequest {
+            amount: "-1".to_string(),
+            currency: None,
+        },
+        StatusCode::BAD_REQUEST
+    );
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/withdraw", base_url, main),
+        Some(&token),
+        &CashMovementRequest {
+            amount: "200".to_string(),
+            currency: None,
+        },
+        StatusCode::OK
+    );
+
+    // 3. 转账到同一用户的另一账号
+    let res = assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/transfer", base_url, main),
+        Some(&token),
+        &TransferRequest {
+            to_account_id: side.clone(),
+            amount: "300".to_string(),
+            currency: None,
+        },
+        StatusCode::OK
+    );
+    let transfer = res
+        .json::<ApiResponse<TransferResponse>>()
+        .await?
+        .data
+        .context("transfer data null")?;
+    assert_eq!(
+        transfer.outgoing.reference.as_deref(),
+        Some(transfer.transfer_id.as_str())
+    );
+    assert_eq!(transfer.incoming.action, "TransferIn");
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/transfer", base_url, main),
+        Some(&token),
+        &TransferRequest {
+            to_account_id: "acct_not_mine".to_string(),
+            amount: "1".to_string(),
+            currency: None,
+        },
+        StatusCode::FORBIDDEN
+    );
+
+    // 单一货币账户不接受其他币种的现金
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/deposit", base_url, main),
+        Some(&token),
+        &CashMovementRequest {
+            amount: "100".to_string(),
+            currency: Some("HKD".to_string()),
+        },
+        StatusCode::BAD_REQUEST
+    );
+
+    // 4. 资金流水倒序分页并附带余额
+    let res = assert_get!(
+        &client,
+        format!("{}/api/v1/user/account/{}/ledger?limit=2", base_url, main),
+        Some(&token),
+        StatusCode::OK
+    );
+    let page = res
+        .json::<ApiResponse<okane_api::types::Page<LedgerEntryResponse>>>()
+        .await?
+        .data
+        .context("ledger data null")?;
+    assert_eq!(page.total, 4);
+    let actions: Vec<&str> = page.items.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(actions, vec!["TransferOut", "Withdrawal"]);
+    assert_eq!(
+        rust_decimal::Decimal::from_str(&page.items[0].available_balance)?,
+        rust_decimal::Decimal::from(1000)
+    );
+
+    let snapshot = assert_get!(
+        &client,
+        format!("{}/api/v1/user/account/{}", base_url, side),
+        Some(&token),
+        StatusCode::OK
+    )
+    .json::<ApiResponse<AccountSnapshotResponse>>()
+    .await?
+    .data
+    .context("snapshot data null")?;
+    assert_eq!(
+        rust_decimal::Decimal::from_str(&snapshot.available_balance)?,
+        rust_decimal::Decimal::from(300)
+    );
     Ok(())
 }
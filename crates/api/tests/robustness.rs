@@ -285,7 +285,7 @@ async fn test_trade_cancel_order_not_found() -> anyhow::Result<()> {
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-async fn test_algo_grid_rejected_as_unsupported() -> anyhow::Result<()> {
+async fn test_algo_grid_requires_bounds() -> anyhow::Result<()> {
     let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
     let client = reqwest::Client::new();
     let token = get_admin_token(&client, &base_url).await?;
@@ -307,13 +307,179 @@ async fn test_algo_grid_rejected_as_unsupported() -> anyhow::Result<()> {
     assert!(
         body.error
             .context("Error body missing")?
-            .to_lowercase()
-            .contains("unsupported"),
-        "Error should mention unsupported algo type"
+            .contains("upper_price"),
+        "Error should name the missing grid parameter"
     );
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_algo_grid_accepted() -> anyhow::Result<()> {
+    let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+    let token = get_admin_token(&client, &base_url).await?;
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "grid",
+            "params": { "upper_price": "160", "lower_price": "140", "grids": 4 }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "grid",
+            "params": { "upper_price": "140", "lower_price": "160", "grids": 4 }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_algo_twap_requires_duration() -> anyhow::Result<()> {
+    let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+    let token = get_admin_token(&client, &base_url).await?;
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "twap",
+            "params": {}
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "twap",
+            "params": { "duration_secs": 600 }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::OK);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_algo_vwap_validates_window() -> anyhow::Result<()> {
+    let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+    let token = get_admin_token(&client, &base_url).await?;
+    let start = chrono::Utc::now().timestamp_millis();
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "vwap",
+            "params": {
+                "start_time": start,
+                "end_time": start - 60_000,
+                "max_participation": "0.1"
+            }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "vwap",
+            "params": {
+                "start_time": start,
+                "end_time": start + 3_600_000,
+                "max_participation": "0.1"
+            }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::OK);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_algo_snipe_direction_and_slippage() -> anyhow::Result<()> {
+    let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+    let token = get_admin_token(&client, &base_url).await?;
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "snipe",
+            "params": { "target_price": "200", "max_slippage": "0.5", "direction": "sell" }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "snipe",
+            "params": { "target_price": "200", "direction": "short" }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = client
+        .post(format!("{}/api/v1/user/algo", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_id": "trader_01",
+            "symbol": "AAPL",
+            "volume": "10",
+            "algo_type": "snipe",
+            "params": { "target_price": "200", "max_slippage": "-1" }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_algo_unsupported_type() -> anyhow::Result<()> {
     let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
@@ -991,8 +1157,8 @@ async fn test_algo_grid_invalid_prices() -> anyhow::Result<()> {
         body.error
             .context("Error body missing")?
             .to_lowercase()
-            .contains("unsupported"),
-        "Error msg should mention unsupported algo type"
+            .contains("missing lower_price"),
+        "Error msg should name the missing grid parameter"
     );
 
     // Invalid decimal (期望 400 并检查消息)
@@ -1010,8 +1176,8 @@ async fn test_algo_grid_invalid_prices() -> anyhow::Result<()> {
         body.error
             .context("Error body missing")?
             .to_lowercase()
-            .contains("unsupported"),
-        "Error msg should mention unsupported algo type"
+            .contains("invalid lower_price"),
+        "Error msg should name the malformed grid parameter"
     );
 
     Ok(())
@@ -1560,11 +1726,17 @@ fn test_from_position_to_response() {
         symbol: "AAPL".to_string(),
         volume: Decimal::ONE_HUNDRED,
         average_price: Decimal::from(150),
+        realized_pnl: Decimal::from(20),
+        commission: Decimal::ONE,
+        last_price: Some(Decimal::from(160)),
+        unrealized_pnl: Decimal::from(1000),
     };
     let pr: PositionResponse = p.into();
     assert_eq!(pr.symbol, "AAPL");
     assert_eq!(pr.volume, "100");
     assert_eq!(pr.average_price, "150");
+    assert_eq!(pr.unrealized_pnl, "1000");
+    assert_eq!(pr.last_price.as_deref(), Some("160"));
 }
 
 #[test]
@@ -1579,6 +1751,7 @@ fn test_from_account_snapshot_to_response() {
         frozen_balance: Decimal::from(500),
         total_equity: Decimal::from(1500),
         positions: vec![],
+        ..AccountSnapshot::default()
     };
     let sr: AccountSnapshotResponse = s.into();
     assert_eq!(sr.account_id, "test");
@@ -1627,3 +1800,222 @@ fn test_from_candle_to_response() {
     assert_eq!(cr.volume, "1000");
     assert!(cr.is_final);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_account_cost_model_applies_to_fills() -> anyhow::Result<()> {
+    let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+    let token = get_admin_token(&client, &base_url).await?;
+
+    // 非法成本模型在开户时即被拒绝
+    let res = client
+        .post(format!("{}/api/v1/user/account", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_name": "Bad Costs",
+            "type": "local",
+            "config": {
+                "initial_balance": "1000",
+                "cost_model": { "commission": { "type": "percent", "rate": "-0.1" } }
+            }
+        }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = client
+        .post(format!("{}/api/v1/user/account", base_url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "account_name": "Costed",
+            "type": "local",
+            "config": {
+                "initial_balance": "100000",
+                "cost_model": { "commission": { "type": "per_share", "rate": "0.01", "min": "5" } }
+            }
+        }))
+        .send()
+        .await?;
+    assert!(res.status().is_success());
+    let account_id = res
+        .json::<ApiResponse<okane_api::types::AccountProfileResponse>>()
+        .await?
+        .data
+        .context("account profile null")?
+        .account_id;
+
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/orders", base_url),
+        Some(&token),
+        &serde_json::json!({
+            "account_id": account_id, "symbol": "AAPL", "volume": "1", "direction": "BUY"
+        }),
+        StatusCode::OK
+    );
+
+    let snapshot = assert_get!(
+        &client,
+        format!("{}/api/v1/user/account/{}", base_url, account_id),
+        Some(&token),
+        StatusCode::OK
+    )
+    .json::<ApiResponse<okane_api::types::AccountSnapshotResponse>>()
+    .await?
+    .data
+    .context("snapshot null")?;
+    let position = snapshot.positions.first().context("position missing")?;
+    let available: rust_decimal::Decimal = snapshot.available_balance.parse()?;
+    let price: rust_decimal::Decimal = position.average_price.parse()?;
+    // 1 股按股计佣 0.01 低于最低佣金 5
+    assert_eq!(
+        rust_decimal::Decimal::from(100_000) - available - price,
+        rust_decimal::Decimal::from(5)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stop_orders_via_rest_api() -> anyhow::Result<()> {
+    let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+    let token = get_admin_token(&client, &base_url).await?;
+    let account_id = "trader_01";
+
+    // 触发条件只能三选一
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/orders", base_url),
+        Some(&token),
+        &serde_json::json!({
+            "account_id": account_id, "symbol": "AAPL", "volume": "1", "direction": "SELL",
+            "stop_price": "1", "trail_amount": "2"
+        }),
+        StatusCode::BAD_REQUEST
+    );
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/orders", base_url),
+        Some(&token),
+        &serde_json::json!({
+            "account_id": account_id, "symbol": "AAPL", "volume": "1", "direction": "SELL",
+            "trail_percent": "0"
+        }),
+        StatusCode::BAD_REQUEST
+    );
+
+    // 远低于市价的止损限价卖单挂起等待触发
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/orders", base_url),
+        Some(&token),
+        &serde_json::json!({
+            "account_id": account_id, "symbol": "AAPL", "volume": "1", "direction": "SELL",
+            "stop_price": "1", "price": "0.5"
+        }),
+        StatusCode::OK
+    );
+
+    let orders = assert_get!(
+        &client,
+        format!("{}/api/v1/user/orders?account_id={}", base_url, account_id),
+        Some(&token),
+        StatusCode::OK
+    )
+    .json::<ApiResponse<okane_api::types::Page<okane_api::types::OrderResponse>>>()
+    .await?
+    .data
+    .context("orders null")?;
+    let order = orders.items.first().context("stop order missing")?;
+    assert_eq!(order.order_type, "stop_limit");
+    assert_eq!(order.stop_price.as_deref(), Some("1"));
+    assert_eq!(order.price.as_deref(), Some("0.5"));
+    assert!(!order.triggered);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_account_kill_switch_via_rest_api() -> anyhow::Result<()> {
+    let (base_url, _store, _tmp, _keepalive, _feed) = spawn_test_server().await?;
+    let client = reqwest::Client::new();
+    let token = get_admin_token(&client, &base_url).await?;
+    let account_id = "trader_01";
+    let limit_buy = serde_json::json!({
+        "account_id": account_id, "symbol": "AAPL", "volume": "1", "direction": "BUY",
+        "price": "1"
+    });
+
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/orders", base_url),
+        Some(&token),
+        &limit_buy,
+        StatusCode::OK
+    );
+    // 市价买入形成持仓，停止后由平仓单平掉
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/orders", base_url),
+        Some(&token),
+        &serde_json::json!({
+            "account_id": account_id, "symbol": "AAPL", "volume": "1", "direction": "BUY"
+        }),
+        StatusCode::OK
+    );
+
+    let report = assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/halt", base_url, account_id),
+        Some(&token),
+        &serde_json::json!({ "flatten": true, "reason": "runaway strategy" }),
+        StatusCode::OK
+    )
+    .json::<ApiResponse<okane_api::types::KillSwitchResponse>>()
+    .await?
+    .data
+    .context("kill switch report null")?;
+    assert_eq!(report.canceled_orders.len(), 1);
+    // 账号先被标记停止，平仓单仍可通过路由与风控
+    assert_eq!(report.flatten_orders.len(), 1);
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    // 停止期间拒绝新委托
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/orders", base_url),
+        Some(&token),
+        &limit_buy,
+        StatusCode::FORBIDDEN
+    );
+
+    let audit = assert_get!(
+        &client,
+        format!("{}/api/v1/user/account/{}/audit", base_url, account_id),
+        Some(&token),
+        StatusCode::OK
+    )
+    .json::<ApiResponse<Vec<okane_api::types::AccountAuditResponse>>>()
+    .await?
+    .data
+    .context("audit records null")?;
+    let record = audit.first().context("halt audit record missing")?;
+    assert_eq!(record.action, "Halt");
+    assert_eq!(record.operator_id, "admin");
+    assert_eq!(record.reason.as_deref(), Some("runaway strategy"));
+
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/account/{}/resume", base_url, account_id),
+        Some(&token),
+        &serde_json::json!({ "reason": "fixed" }),
+        StatusCode::OK
+    );
+    assert_post!(
+        &client,
+        format!("{}/api/v1/user/orders", base_url),
+        Some(&token),
+        &limit_buy,
+        StatusCode::OK
+    );
+    Ok(())
+}
@@ -7,12 +7,11 @@ use okane_manager::backtest::{
     BacktestEnvironment, BacktestEnvironmentFactory, BacktestRequest, BacktestResultCollector,
 };
 use okane_manager::strategy::ManagerError;
-use okane_market::history::BacktestMarket;
+use okane_market::history::{BacktestMarket, BacktestStock};
 use okane_market::indicator::MarketIndicatorService;
 use okane_trade::algo::AlgoOrderService;
 use okane_trade::service::TradeService;
 use okane_trade::trade_log::TradeLog;
-use rust_decimal::Decimal;
 use std::sync::atomic::AtomicUsize;
 use std::sync::{Arc, Mutex};
 
@@ -111,13 +110,13 @@ impl BacktestEnvironmentFactory for DefaultBacktestEnvironmentFactory {
         source_stock: Arc<dyn Stock>,
     ) -> Result<BacktestEnvironment, ManagerError> {
         let fake_clock = Arc::new(FakeClockProvider::new(req.start));
-        let account_store = Arc::new(
-            okane_store::account::SqliteAccountStore::new().map_err(|e| {
+        let account_store = Arc::new(okane_store::account::SqliteAccountStore::new().map_err(
+            |e| {
                 ManagerError::Trade(okane_core::trade::port::TradeError::InternalError(
                     e.to_string(),
                 ))
-            })?,
-        );
+            },
+        )?);
         let backtest_account_id = AccountId(format!("backtest_{}", uuid::Uuid::new_v4()));
         let pending_port = Arc::new(
             okane_store::pending_order_sqlx::SqlitePendingOrderStore::new().map_err(|e| {
@@ -126,7 +125,12 @@ impl BacktestEnvironmentFactory for DefaultBacktestEnvironmentFactory {
                 ))
             })?,
         );
-        let matcher = Arc::new(okane_trade::matcher::LocalMatchEngine::new(Decimal::ZERO));
+        let matcher = Arc::new(
+            okane_trade::matcher::LocalMatchEngine::with_cost_model(req.cost_model.clone())
+                .with_fill_model(Arc::new(
+                    okane_trade::fill_model::BarFillModel::from_config(&req.fill_model),
+                )),
+        );
         let trade_log = Arc::new(TradeLog::new());
         let lazy_market = Arc::new(LazyMarket::new());
         let candle_counter = Arc::new(AtomicUsize::new(0));
@@ -145,14 +149,17 @@ impl BacktestEnvironmentFactory for DefaultBacktestEnvironmentFactory {
             .ensure_account(backtest_account_id.clone(), req.initial_balance)
             .await?;
 
-        let backtest_market: Arc<dyn Market> = Arc::new(BacktestMarket::with_source(
-            req.symbol.clone(),
-            source_stock,
-            req.start,
-            req.end,
-            fake_clock.clone(),
-            trade_service.clone(),
-            candle_counter.clone(),
+        let backtest_market: Arc<dyn Market> = Arc::new(BacktestMarket::from_stock(
+            BacktestStock::with_source(
+                req.symbol.clone(),
+                source_stock,
+                req.start,
+                req.end,
+                fake_clock.clone(),
+                trade_service.clone(),
+                candle_counter.clone(),
+            )
+            .with_price_adjustment(req.price_adjustment),
         ));
 
         lazy_market.set(backtest_market.clone()).map_err(|e| {
@@ -162,10 +169,10 @@ impl BacktestEnvironmentFactory for DefaultBacktestEnvironmentFactory {
             )))
         })?;
 
-        let algo_service = Arc::new(AlgoOrderService::new(
-            trade_service.clone(),
-            fake_clock.clone(),
-        ));
+        let algo_service = Arc::new(
+            AlgoOrderService::new(trade_service.clone(), fake_clock.clone())
+                .with_market(backtest_market.clone()),
+        );
         trade_service.set_algo_service(algo_service.clone())?;
 
         Ok(BacktestEnvironment {
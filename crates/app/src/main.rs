@@ -3,7 +3,13 @@ mod backtest;
 use std::sync::Arc;
 
 use okane_core::common::RealTimeProvider;
+use okane_core::common::time::TimeProvider;
+use okane_core::config::MarketDataConfig;
+use okane_core::market::port::{MarketDataProvider, TickRecorder};
 use okane_engine::factory::EngineFactory;
+use okane_feed::file::FileProvider;
+use okane_feed::recorder::FileTickRecorder;
+use okane_feed::replay::ReplayProvider;
 use okane_feed::yahoo::YahooProvider;
 use okane_manager::strategy::StrategyManager;
 use okane_market::indicator::MarketIndicatorService;
@@ -12,6 +18,7 @@ use okane_store::market::SqliteMarketStore;
 use okane_store::strategy::SqliteStrategyStore;
 use okane_store::system::SqliteSystemStore;
 use okane_trade::algo::AlgoOrderService;
+use okane_trade::live::LiveMatchingService;
 use okane_trade::service::TradeService;
 use tracing::info;
 use tracing_subscriber::fmt::writer::MakeWriterExt;
@@ -76,18 +83,58 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Configuration loaded: {:?}", app_config);
 
-    // 设置全局数据目录 (为 Store 层提供根路径)
-    okane_store::config::set_root_dir(std::path::PathBuf::from(
-        app_config.database.data_dir.clone(),
-    ));
+    // 设置全局数据目录 (为 Store 层提供根路径)；重放录制行情时使用独立的数据目录，
+    // 重放期间的账户、委托与行情缓存不写入实盘数据
+    let data_dir = std::path::PathBuf::from(&app_config.database.data_dir);
+    let data_root = match &app_config.market_data {
+        MarketDataConfig::Replay(replay) => {
+            let root = replay.data_root(&data_dir);
+            info!("Replay mode isolates its data under {}", root.display());
+            root
+        }
+        _ => data_dir.clone(),
+    };
+    okane_store::config::set_root_dir(data_root.clone());
 
     // 2. 实例化基础设施层
-    let feed = Arc::new(YahooProvider::new()?);
+    // 重放录制行情时，全局时钟跟随重放进度
+    let recordings_dir = data_dir.join("recordings");
+    let mut clock: Arc<dyn TimeProvider> = Arc::new(RealTimeProvider);
+    let feed: Arc<dyn MarketDataProvider> = match &app_config.market_data {
+        MarketDataConfig::Replay(replay) => {
+            let dir = replay
+                .dir
+                .as_ref()
+                .map_or(recordings_dir.clone(), std::path::PathBuf::from);
+            info!(
+                "Replaying market data recorded on {} from {} at speed {}",
+                replay.session,
+                dir.display(),
+                replay.speed
+            );
+            let mut provider = ReplayProvider::new(dir, replay.session, replay.speed)?;
+            match replay.history.as_deref() {
+                Some(history) => provider = provider.with_history(live_provider(history)?),
+                None => info!("Replay has no history provider, history queries will fail"),
+            }
+            clock = provider.clock();
+            Arc::new(provider)
+        }
+        live => live_provider(live)?,
+    };
     let market_store = Arc::new(SqliteMarketStore::new()?);
     let strategy_store = Arc::new(SqliteStrategyStore::new()?);
 
     // 3. 实例化领域实现层
-    let market = MarketImpl::new(feed, market_store);
+    let recorder: Option<Arc<dyn TickRecorder>> = if app_config.record_market_data
+        && !matches!(app_config.market_data, MarketDataConfig::Replay(_))
+    {
+        info!("Recording live market data to {}", recordings_dir.display());
+        Some(Arc::new(FileTickRecorder::new(recordings_dir)?))
+    } else {
+        None
+    };
+    let market = MarketImpl::new_with_clock(feed, market_store, recorder, clock.clone());
 
     // 4. 实例化引擎工厂（App 层知道具体实现，Manager 不知道）
     let engine_builder = Arc::new(EngineFactory::new(market.clone()));
@@ -98,32 +145,138 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 6. 实例化交易、算法单与指标服务
     let account_store = Arc::new(okane_store::account::SqliteAccountStore::new()?);
+    // 补完上次进程中断时停留在转账日志中的账户间转账
+    let recovered_transfers = account_store.recover_transfers().await?;
+    info!(
+        "Recovered {} pending account transfers.",
+        recovered_transfers
+    );
     let pending_port = Arc::new(okane_store::pending_order_sqlx::SqlitePendingOrderStore::new()?);
-    let matcher = Arc::new(okane_trade::matcher::LocalMatchEngine::new(
-        rust_decimal::Decimal::ZERO,
-    ));
-    let real_time = Arc::new(RealTimeProvider);
+    // 各逻辑交易账号按自身配置的成本模型撮合，成交模型 (参与率额度) 按全局配置共享
+    let fill_model: Arc<dyn okane_trade::fill_model::FillModel> = Arc::new(
+        okane_trade::fill_model::BarFillModel::from_config(&app_config.fill_model),
+    );
+    let matcher = Arc::new(
+        okane_trade::matcher::LocalMatchEngine::new(rust_decimal::Decimal::ZERO)
+            .with_fill_model(fill_model.clone()),
+    );
+    let real_time = clock;
+    let order_history = Arc::new(okane_store::order_history_sqlx::SqliteOrderHistoryStore::new()?);
+    // DAY 订单按标的所属市场的收盘时刻过期，配置可覆盖内置时段并追加休市日
+    let trading_calendar =
+        okane_trade::session::TradingCalendar::from_config(&app_config.trading_sessions)?;
+
+    let local_trade_service = Arc::new(
+        TradeService::new(
+            account_store.clone(),
+            matcher,
+            market.clone(),
+            pending_port.clone(),
+            real_time.clone(),
+        )
+        .with_account_cost_models(
+            system_store.clone(),
+            okane_trade::matcher::LocalMatchEngine::factory(fill_model),
+        )
+        .with_order_history(order_history.clone())
+        .with_trading_calendar(trading_calendar),
+    );
 
-    let local_trade_service = Arc::new(TradeService::new(
-        account_store.clone(),
-        matcher.clone(),
-        market.clone(),
-        pending_port.clone(),
-        real_time.clone(),
-    ));
+    // 非本地后端的账号经券商网关下单；配置开启时登记 simulated，按行情最新价模拟成交，用于离线联调
+    let mut broker_registry = okane_trade::gateway::BrokerRegistry::new();
+    if app_config.simulated_broker {
+        let simulated_broker = Arc::new(okane_trade::simulated_broker::SimulatedBroker::new(
+            market.clone(),
+            real_time.clone(),
+        ));
+        simulated_broker.start(std::time::Duration::from_secs(5));
+        broker_registry = broker_registry.with_broker("simulated", simulated_broker);
+    }
+    // 配置了 [fix] 时登记 FIX 4.4 券商，会话序号与报文日志保存在数据目录的 fix 子目录下
+    if let Some(fix_config) = app_config.fix.clone() {
+        let fix_dir = data_root.join("fix");
+        let fix_broker = okane_fix::broker::FixBroker::new(fix_config, &fix_dir).await?;
+        broker_registry = broker_registry.with_broker("fix", Arc::new(fix_broker));
+    }
+    let broker_order_store =
+        Arc::new(okane_store::broker_order_sqlx::SqliteBrokerOrderStore::new()?);
+    let broker_gateway = Arc::new(
+        okane_trade::gateway::BrokerGateway::new(
+            broker_registry,
+            account_store.clone(),
+            broker_order_store.clone(),
+            market.clone(),
+            real_time.clone(),
+        )
+        .with_order_history(order_history)
+        .with_account_configs(system_store.clone()),
+    );
+    // 订阅各网关的执行回报，连接建立 (及断线重连) 时与未终结的外发订单对账
+    broker_gateway.start();
 
-    let trade_service = Arc::new(okane_trade::router::RoutedTradePort::new(
-        local_trade_service.clone(),
+    let routed_trade_port = Arc::new(
+        okane_trade::router::RoutedTradePort::new(
+            local_trade_service.clone(),
+            system_store.clone(),
+        )
+        .with_gateway(broker_gateway),
+    );
+    // 事前风控位于路由之前，策略、算法单与 REST 下单统一按账号的 risk_rules 拦截
+    let trade_service = Arc::new(okane_trade::risk::RiskControlledTradePort::new(
+        routed_trade_port,
         system_store.clone(),
-    ));
-
-    let algo_port = Arc::new(AlgoOrderService::new(
-        trade_service.clone(),
+        market.clone(),
         real_time.clone(),
     ));
 
+    let algo_store = Arc::new(okane_store::algo_order_sqlx::SqliteAlgoOrderStore::new()?);
+    let algo_port = Arc::new(
+        AlgoOrderService::new(trade_service.clone(), real_time.clone())
+            .with_market(market.clone())
+            .with_store(algo_store)
+            .with_broker_orders(broker_order_store),
+    );
+
     local_trade_service.set_algo_service(algo_port.clone())?;
 
+    // 纸面交易实时撮合：为存在挂单或运行中算法单的标的订阅行情并驱动 tick
+    let live_matcher = Arc::new(
+        LiveMatchingService::new(
+            market.clone(),
+            local_trade_service.clone(),
+            pending_port.clone(),
+            real_time.clone(),
+        )
+        .with_algo_service(algo_port.clone()),
+    );
+    local_trade_service.set_live_matcher(live_matcher.clone())?;
+    algo_port.set_live_matcher(live_matcher.clone())?;
+    // 恢复重启前仍在运行的算法单，并与其在途子单对账，之后随实时撮合循环一并订阅
+    algo_port.recover(pending_port.as_ref()).await?;
+    live_matcher.start().await?;
+
+    // 恢复保证金账户登记，定时计提融券费、检查维持保证金并落地当日盈亏快照
+    let margin_accounts = local_trade_service.restore_margin_accounts().await?;
+    info!("Restored {} margin accounts.", margin_accounts);
+    // 恢复持仓账户的公司行动登记，停机期间除权除息的拆股与分红由定时维护补处理
+    let position_accounts = local_trade_service
+        .restore_corporate_action_accounts()
+        .await?;
+    info!(
+        "Restored corporate action tracking for {} accounts.",
+        position_accounts
+    );
+    let maintained_service = local_trade_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = maintained_service.maintain_accounts().await {
+                tracing::error!("Account maintenance failed: {}", e);
+            }
+        }
+    });
+
     let indicator_service = Arc::new(MarketIndicatorService::new(market.clone()));
 
     // 7. 创建通知工厂（根据用户 ID 动态创建 Notifier, 配置存储在数据库中）
@@ -138,7 +291,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         trade_port: trade_service.clone(),
         algo_port: algo_port.clone(),
         indicator_service: indicator_service.clone(),
-        time_provider: Arc::new(RealTimeProvider),
+        time_provider: real_time.clone(),
         notifier_factory,
         log_port: strategy_store,
     });
@@ -175,6 +328,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         system_store,
         market_port: market.clone(),
         backtest_runner,
+        time_provider: real_time.clone(),
         app_config: Arc::new(app_config.clone()),
         session_cache,
     };
@@ -194,3 +348,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// 按配置构建实时行情源 (Yahoo 或本地文件)，重放行情源由调用方构建。
+fn live_provider(
+    config: &MarketDataConfig,
+) -> Result<Arc<dyn MarketDataProvider>, Box<dyn std::error::Error>> {
+    match config {
+        MarketDataConfig::Yahoo => Ok(Arc::new(YahooProvider::new()?)),
+        MarketDataConfig::File(file) => {
+            info!("Serving market data from files under {}", file.dir);
+            Ok(Arc::new(FileProvider::new(file)?))
+        }
+        MarketDataConfig::Replay(_) => {
+            Err("replay cannot serve live or history market data for another provider".into())
+        }
+    }
+}
@@ -5,6 +5,24 @@ use serde::{Deserialize, Serialize};
 pub struct AppConfig {
     pub server: ServerConfig,
     pub database: DatabaseConfig,
+    /// FIX 券商会话，配置后 `fix` 类型的逻辑交易账号经该会话下单
+    #[serde(default)]
+    pub fix: Option<FixConfig>,
+    /// 行情数据源，缺省为 Yahoo Finance
+    #[serde(default)]
+    pub market_data: MarketDataConfig,
+    /// 是否录制实时行情的原始更新，录制文件位于 `<data_dir>/recordings`
+    #[serde(default)]
+    pub record_market_data: bool,
+    /// 是否登记 `simulated` 券商：按行情最新价模拟成交，仅用于离线联调
+    #[serde(default)]
+    pub simulated_broker: bool,
+    /// 各市场的交易时段，按交易所后缀覆盖内置的美股、港股常规时段
+    #[serde(default)]
+    pub trading_sessions: Vec<TradingSessionConfig>,
+    /// 本地撮合的 K 线成交模型
+    #[serde(default)]
+    pub fill_model: FillModelConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +37,153 @@ pub struct DatabaseConfig {
     pub data_dir: String,
 }
 
+/// FIX 4.4 发起方 (initiator) 会话配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixConfig {
+    /// 对端 (acceptor) 地址
+    pub host: String,
+    pub port: u16,
+    /// 本方 SenderCompID
+    pub sender_comp_id: String,
+    /// 对端 TargetCompID
+    pub target_comp_id: String,
+    /// 心跳间隔 (秒)
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+    /// 每次登录时重置会话 (ResetSeqNumFlag=Y)：序号回到 1，上一轮的报文日志归档
+    #[serde(default)]
+    pub reset_on_logon: bool,
+}
+
+fn default_heartbeat_interval_secs() -> u64 {
+    30
+}
+
+/// K 线成交模型配置，实盘纸面撮合与回测共用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FillModelConfig {
+    /// 同一标的单根 K 线内累计成交量占 K 线成交量的上限，取值 `(0, 1]`；缺省不限
+    #[serde(default)]
+    pub max_participation: Option<rust_decimal::Decimal>,
+    /// K 线内部价格路径假设，缺省为开 → 高 → 低 → 收
+    #[serde(default)]
+    pub ohlc_path: crate::market::entity::OhlcPath,
+}
+
+/// 单个市场的交易时段配置，决定 DAY 订单的过期时刻
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradingSessionConfig {
+    /// 适用的交易所后缀 (如 ".HK")，为空时作用于无后缀的标的 (美股)
+    #[serde(default)]
+    pub suffix: String,
+    /// 交易所所在的 IANA 时区
+    pub timezone: String,
+    /// 收盘时刻 (交易所本地时间)
+    pub close: chrono::NaiveTime,
+    /// 休市日 (交易所本地日期)
+    #[serde(default)]
+    pub holidays: Vec<chrono::NaiveDate>,
+}
+
+/// 行情数据源配置，以 `provider` 字段区分
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum MarketDataConfig {
+    /// Yahoo Finance 在线行情
+    #[default]
+    Yahoo,
+    /// 本地 CSV / Parquet 文件
+    File(Box<FileProviderConfig>),
+    /// 重放一个交易日的录制行情
+    Replay(ReplayConfig),
+}
+
+/// 本地文件行情源配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileProviderConfig {
+    /// 数据根目录，文件按 `<dir>/<周期>/<代码>.csv|parquet` 存放，周期为 1m / 5m / 1h / 1d
+    pub dir: String,
+    /// 列名映射
+    #[serde(default)]
+    pub columns: ColumnMapping,
+    /// 不带时区的时间列按此 IANA 时区解释
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// 实时订阅的回放倍速：相邻 K 线的时间间隔除以该倍速后等待，不大于 0 时不等待
+    #[serde(default = "default_replay_speed")]
+    pub replay_speed: f64,
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_replay_speed() -> f64 {
+    60.0
+}
+
+/// 录制行情重放配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayConfig {
+    /// 重放的交易日 (按接收时间的 UTC 日期)
+    pub session: chrono::NaiveDate,
+    /// 录制目录，缺省为 `<data_dir>/recordings`
+    #[serde(default)]
+    pub dir: Option<String>,
+    /// 重放倍速：1 为原速，不大于 0 时不等待
+    #[serde(default = "default_session_replay_speed")]
+    pub speed: f64,
+    /// 历史 K 线、公司行动与证券信息的数据源 (yahoo 或 file)，缺省时历史查询报错
+    #[serde(default)]
+    pub history: Option<Box<MarketDataConfig>>,
+    /// 重放使用的独立数据目录，缺省为 `<data_dir>/replay/<session>`；
+    /// 重放期间的账户、委托与行情缓存均写入此处，不触及实盘数据
+    #[serde(default)]
+    pub data_dir: Option<String>,
+}
+
+impl ReplayConfig {
+    /// 重放使用的数据根目录。
+    pub fn data_root(&self, data_dir: &std::path::Path) -> std::path::PathBuf {
+        match &self.data_dir {
+            Some(dir) => std::path::PathBuf::from(dir),
+            None => data_dir.join("replay").join(self.session.to_string()),
+        }
+    }
+}
+
+fn default_session_replay_speed() -> f64 {
+    1.0
+}
+
+/// OHLCV 列名映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnMapping {
+    pub time: String,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub volume: String,
+    /// 复权收盘价列，缺省时不读取
+    pub adj_close: Option<String>,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            time: "time".to_string(),
+            open: "open".to_string(),
+            high: "high".to_string(),
+            low: "low".to_string(),
+            close: "close".to_string(),
+            volume: "volume".to_string(),
+            adj_close: None,
+        }
+    }
+}
+
 /// Telegram Bot 推送配置
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TelegramConfig {
@@ -113,6 +278,12 @@ impl Default for AppConfig {
             database: DatabaseConfig {
                 data_dir: "data".to_string(),
             },
+            fix: None,
+            market_data: MarketDataConfig::default(),
+            record_market_data: false,
+            simulated_broker: false,
+            trading_sessions: Vec::new(),
+            fill_model: FillModelConfig::default(),
         }
     }
 }
@@ -128,5 +299,74 @@ mod tests {
         assert_eq!(config.server.port, 8080);
         assert_eq!(config.server.jwt_secret, "YOUR_SUPER_SECRET_KEY");
         assert_eq!(config.database.data_dir, "data");
+        assert!(config.fix.is_none());
+        assert!(matches!(config.market_data, MarketDataConfig::Yahoo));
+        assert!(!config.record_market_data);
+        assert!(!config.simulated_broker);
+        assert!(config.fill_model.max_participation.is_none());
+        assert_eq!(
+            config.fill_model.ohlc_path,
+            crate::market::entity::OhlcPath::OpenHighLowClose
+        );
+    }
+
+    #[test]
+    fn test_file_market_data_config_defaults() -> Result<(), serde_json::Error> {
+        let config: MarketDataConfig = serde_json::from_value(serde_json::json!({
+            "provider": "file",
+            "dir": "bars",
+            "columns": { "time": "Date", "adj_close": "Adj Close" }
+        }))?;
+        let file = match config {
+            MarketDataConfig::File(file) => file,
+            _ => return Err(serde::de::Error::custom("expected file provider")),
+        };
+        assert_eq!(file.dir, "bars");
+        assert_eq!(file.columns.time, "Date");
+        assert_eq!(file.columns.close, "close");
+        assert_eq!(file.columns.adj_close.as_deref(), Some("Adj Close"));
+        assert_eq!(file.timezone, "UTC");
+        assert_eq!(file.replay_speed, 60.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_market_data_config_defaults() -> Result<(), serde_json::Error> {
+        let config: MarketDataConfig = serde_json::from_value(serde_json::json!({
+            "provider": "replay",
+            "session": "2026-10-16"
+        }))?;
+        let replay = match config {
+            MarketDataConfig::Replay(replay) => replay,
+            _ => return Err(serde::de::Error::custom("expected replay provider")),
+        };
+        assert_eq!(replay.session.to_string(), "2026-10-16");
+        assert!(replay.dir.is_none());
+        assert_eq!(replay.speed, 1.0);
+        assert!(replay.history.is_none());
+        assert_eq!(
+            replay.data_root(std::path::Path::new("data")),
+            std::path::Path::new("data/replay/2026-10-16")
+        );
+
+        let config: MarketDataConfig = serde_json::from_value(serde_json::json!({
+            "provider": "replay",
+            "session": "2026-10-16",
+            "history": { "provider": "yahoo" },
+            "data_dir": "sandbox"
+        }))?;
+        let replay = match config {
+            MarketDataConfig::Replay(replay) => replay,
+            _ => return Err(serde::de::Error::custom("expected replay provider")),
+        };
+        assert!(matches!(
+            replay.history.as_deref(),
+            Some(MarketDataConfig::Yahoo)
+        ));
+        assert_eq!(
+            replay.data_root(std::path::Path::new("data")),
+            std::path::Path::new("sandbox")
+        );
+        Ok(())
     }
 }
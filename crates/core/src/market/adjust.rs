@@ -0,0 +1,241 @@
+//! # 复权计算
+//!
+//! 依据公司行动把原始 K 线换算为前复权或全收益口径，并给出回测账户仍需处理的公司行动。
+
+use crate::market::entity::{Candle, CorporateAction, CorporateActionKind, PriceAdjustment};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// # Summary
+/// 按回测区间内的公司行动对 K 线做向后复权 (以区间末的价格为基准)。
+///
+/// # Invariants
+/// - 每个因子只作用于除权除息日之前的 K 线，除权除息日及之后的 K 线保持原值。
+pub struct PriceAdjuster {
+    // (除权除息日, 价格乘数, 成交量乘数)
+    factors: Vec<(DateTime<Utc>, Decimal, Decimal)>,
+}
+
+impl PriceAdjuster {
+    /// # Logic
+    /// 1. `Raw` 不做任何调整。
+    /// 2. 拆股：价格乘以 `denominator / numerator`，成交量乘以其倒数。
+    /// 3. `TotalReturn` 另按 `1 - 每股分红 / 除息前一根 K 线的原始收盘价` 调整价格；
+    ///    缺少除息前收盘价或因子不为正的分红被忽略。
+    ///
+    /// # Arguments
+    /// * `mode` - 价格口径。
+    /// * `actions` - 回测区间内的公司行动。
+    /// * `prior_closes` - 以除息日为键的除息前原始收盘价。
+    pub fn new(
+        mode: PriceAdjustment,
+        actions: &[CorporateAction],
+        prior_closes: &HashMap<DateTime<Utc>, Decimal>,
+    ) -> Self {
+        let mut factors = Vec::new();
+        if mode == PriceAdjustment::Raw {
+            return Self { factors };
+        }
+        for action in actions {
+            match action.kind {
+                CorporateActionKind::Split { .. } => {
+                    if let Some(ratio) = action.split_ratio().filter(|r| !r.is_zero()) {
+                        factors.push((action.ex_date, Decimal::ONE / ratio, ratio));
+                    }
+                }
+                CorporateActionKind::Dividend { amount } => {
+                    if mode != PriceAdjustment::TotalReturn {
+                        continue;
+                    }
+                    let Some(close) = prior_closes
+                        .get(&action.ex_date)
+                        .filter(|close| !close.is_zero())
+                    else {
+                        tracing::warn!(
+                            "No prior close for dividend of {} on {}, skipped",
+                            action.symbol,
+                            action.ex_date
+                        );
+                        continue;
+                    };
+                    let factor = Decimal::ONE - amount / close;
+                    if factor > Decimal::ZERO {
+                        factors.push((action.ex_date, factor, Decimal::ONE));
+                    }
+                }
+            }
+        }
+        Self { factors }
+    }
+
+    /// 是否存在需要调整的因子。
+    pub fn is_identity(&self) -> bool {
+        self.factors.is_empty()
+    }
+
+    /// 将单根 K 线换算到复权口径。
+    pub fn adjust(&self, candle: &mut Candle) {
+        let (price, volume) = self
+            .factors
+            .iter()
+            .filter(|(ex_date, _, _)| candle.time < *ex_date)
+            .fold((Decimal::ONE, Decimal::ONE), |(p, v), (_, pf, vf)| {
+                (p * pf, v * vf)
+            });
+        if price != Decimal::ONE {
+            candle.open *= price;
+            candle.high *= price;
+            candle.low *= price;
+            candle.close *= price;
+        }
+        if volume != Decimal::ONE {
+            candle.volume *= volume;
+        }
+    }
+
+    /// # Logic
+    /// 价格口径未吸收的公司行动仍需作用于回测账户：
+    /// - `Raw`：全部公司行动。
+    /// - `SplitAdjusted`：仅分红，每股金额按其后的拆股折算到复权后的股数。
+    /// - `TotalReturn`：无。
+    pub fn account_actions(
+        mode: PriceAdjustment,
+        actions: &[CorporateAction],
+    ) -> Vec<CorporateAction> {
+        match mode {
+            PriceAdjustment::Raw => actions.to_vec(),
+            PriceAdjustment::TotalReturn => Vec::new(),
+            PriceAdjustment::SplitAdjusted => actions
+                .iter()
+                .filter_map(|action| {
+                    let CorporateActionKind::Dividend { amount } = action.kind else {
+                        return None;
+                    };
+                    let later_splits: Decimal = actions
+                        .iter()
+                        .filter(|other| other.ex_date > action.ex_date)
+                        .filter_map(CorporateAction::split_ratio)
+                        .filter(|ratio| !ratio.is_zero())
+                        .product();
+                    Some(CorporateAction {
+                        kind: CorporateActionKind::Dividend {
+                            amount: amount / later_splits,
+                        },
+                        ..action.clone()
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn candle(day: u32, close: i64) -> Candle {
+        let price = Decimal::from(close);
+        Candle {
+            time: Utc
+                .with_ymd_and_hms(2024, 1, day, 0, 0, 0)
+                .single()
+                .unwrap_or_default(),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            adj_close: None,
+            volume: Decimal::from(100),
+            is_final: true,
+        }
+    }
+
+    fn action(day: u32, kind: CorporateActionKind) -> CorporateAction {
+        CorporateAction {
+            symbol: "AAPL".to_string(),
+            ex_date: Utc
+                .with_ymd_and_hms(2024, 1, day, 0, 0, 0)
+                .single()
+                .unwrap_or_default(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_price_adjuster_modes() {
+        // 1 月 3 日 2 拆 1，1 月 5 日每股分红 1 (除息前收盘 50)
+        let actions = vec![
+            action(
+                3,
+                CorporateActionKind::Split {
+                    numerator: 2,
+                    denominator: 1,
+                },
+            ),
+            action(
+                5,
+                CorporateActionKind::Dividend {
+                    amount: Decimal::ONE,
+                },
+            ),
+        ];
+        let prior_closes = HashMap::from([(actions[1].ex_date, Decimal::from(50))]);
+
+        let raw = PriceAdjuster::new(PriceAdjustment::Raw, &actions, &prior_closes);
+        assert!(raw.is_identity());
+
+        let split = PriceAdjuster::new(PriceAdjustment::SplitAdjusted, &actions, &prior_closes);
+        let mut before = candle(2, 100);
+        split.adjust(&mut before);
+        assert_eq!(before.close, Decimal::from(50));
+        assert_eq!(before.volume, Decimal::from(200));
+        let mut after = candle(3, 50);
+        split.adjust(&mut after);
+        assert_eq!(after.close, Decimal::from(50));
+
+        let total = PriceAdjuster::new(PriceAdjustment::TotalReturn, &actions, &prior_closes);
+        let mut before = candle(2, 100);
+        total.adjust(&mut before);
+        // 100 × 1/2 × (1 - 1/50)
+        assert_eq!(before.close, Decimal::from(49));
+        let mut between = candle(4, 50);
+        total.adjust(&mut between);
+        assert_eq!(between.close, Decimal::from(49));
+        assert_eq!(between.volume, Decimal::from(100));
+    }
+
+    #[test]
+    fn test_account_actions_follow_price_mode() {
+        let actions = vec![
+            action(
+                2,
+                CorporateActionKind::Dividend {
+                    amount: Decimal::from(2),
+                },
+            ),
+            action(
+                3,
+                CorporateActionKind::Split {
+                    numerator: 4,
+                    denominator: 1,
+                },
+            ),
+        ];
+        assert_eq!(
+            PriceAdjuster::account_actions(PriceAdjustment::Raw, &actions),
+            actions
+        );
+        assert!(PriceAdjuster::account_actions(PriceAdjustment::TotalReturn, &actions).is_empty());
+
+        let adjusted = PriceAdjuster::account_actions(PriceAdjustment::SplitAdjusted, &actions);
+        assert_eq!(adjusted.len(), 1);
+        assert_eq!(
+            adjusted[0].kind,
+            CorporateActionKind::Dividend {
+                amount: Decimal::new(5, 1)
+            }
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! # K 线覆盖区间
+//!
+//! 记录某证券某周期已向数据源完整拉取过的时间区间，据此只补拉缺失的子区间。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// # Summary
+/// 闭区间 `[start, end]`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimeRange {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self { start, end }
+    }
+}
+
+/// # Summary
+/// 查询区间内的覆盖情况。
+///
+/// # Invariants
+/// - `covered` 与 `gaps` 均按时间升序、互不重叠，且都落在查询区间内。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CandleCoverage {
+    pub covered: Vec<TimeRange>,
+    pub gaps: Vec<TimeRange>,
+}
+
+impl CandleCoverage {
+    /// # Summary
+    /// 以已拉取区间计算查询区间 `[start, end]` 的覆盖与缺口。
+    pub fn within(ranges: &[TimeRange], start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        let covered = merge(ranges)
+            .into_iter()
+            .filter(|r| r.end >= start && r.start <= end)
+            .map(|r| TimeRange::new(r.start.max(start), r.end.min(end)))
+            .collect();
+        Self {
+            covered,
+            gaps: gaps(ranges, start, end),
+        }
+    }
+}
+
+/// # Summary
+/// 合并重叠或首尾相接的区间。
+///
+/// # Returns
+/// 按开始时间升序、互不重叠的区间列表；`start > end` 的无效区间被丢弃。
+pub fn merge(ranges: &[TimeRange]) -> Vec<TimeRange> {
+    let mut sorted: Vec<TimeRange> = ranges
+        .iter()
+        .filter(|r| r.start <= r.end)
+        .copied()
+        .collect();
+    sorted.sort_by_key(|r| r.start);
+    let mut merged: Vec<TimeRange> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// # Summary
+/// 计算 `[start, end]` 中未被已拉取区间覆盖的部分。
+///
+/// # Logic
+/// 缺口以相邻覆盖区间的边界为端点，补拉时边界上的 K 线会被重复拉取并覆盖写入。
+///
+/// # Returns
+/// 按时间升序的缺口列表，完全覆盖时为空。
+pub fn gaps(ranges: &[TimeRange], start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<TimeRange> {
+    if start > end {
+        return Vec::new();
+    }
+    let mut gaps = Vec::new();
+    let mut cursor = start;
+    for range in merge(ranges) {
+        if range.end < cursor {
+            continue;
+        }
+        if range.start > end {
+            break;
+        }
+        if range.start > cursor {
+            gaps.push(TimeRange::new(cursor, range.start));
+        }
+        cursor = range.end;
+        if cursor >= end {
+            return gaps;
+        }
+    }
+    gaps.push(TimeRange::new(cursor, end));
+    gaps
+}
+
+/// # Summary
+/// 从已拉取区间中移除 `[start, end]`，用于强制重新拉取。
+///
+/// # Returns
+/// 按开始时间升序、互不重叠的剩余区间；被切开的区间保留两侧端点。
+pub fn subtract(ranges: &[TimeRange], start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<TimeRange> {
+    let mut remaining = Vec::new();
+    for range in merge(ranges) {
+        if start > end || range.end < start || range.start > end {
+            remaining.push(range);
+            continue;
+        }
+        if range.start < start {
+            remaining.push(TimeRange::new(range.start, start));
+        }
+        if range.end > end {
+            remaining.push(TimeRange::new(end, range.end));
+        }
+    }
+    remaining
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0)
+            .single()
+            .unwrap_or_default()
+    }
+
+    fn range(a: u32, b: u32) -> TimeRange {
+        TimeRange::new(day(a), day(b))
+    }
+
+    #[test]
+    fn test_merge_joins_overlapping_and_touching_ranges() {
+        let merged = merge(&[
+            range(10, 12),
+            range(1, 3),
+            range(3, 5),
+            range(4, 6),
+            range(8, 7),
+        ]);
+        assert_eq!(merged, vec![range(1, 6), range(10, 12)]);
+    }
+
+    #[test]
+    fn test_gaps_cover_only_missing_sub_ranges() {
+        let covered = [range(3, 5), range(8, 10)];
+        assert_eq!(
+            gaps(&covered, day(1), day(12)),
+            vec![range(1, 3), range(5, 8), range(10, 12)]
+        );
+        assert_eq!(gaps(&covered, day(4), day(9)), vec![range(5, 8)]);
+        assert!(gaps(&covered, day(3), day(5)).is_empty());
+        assert_eq!(gaps(&[], day(1), day(2)), vec![range(1, 2)]);
+
+        let coverage = CandleCoverage::within(&covered, day(4), day(9));
+        assert_eq!(coverage.covered, vec![range(4, 5), range(8, 9)]);
+        assert_eq!(coverage.gaps, vec![range(5, 8)]);
+    }
+
+    #[test]
+    fn test_subtract_removes_range_and_keeps_both_sides() {
+        let covered = [range(1, 5), range(8, 12)];
+        assert_eq!(
+            subtract(&covered, day(3), day(10)),
+            vec![range(1, 3), range(10, 12)]
+        );
+        assert_eq!(subtract(&covered, day(6), day(7)), merge(&covered));
+        assert!(subtract(&covered, day(1), day(12)).is_empty());
+    }
+}
@@ -26,3 +26,146 @@ pub struct Candle {
     // 是否为最终数据 (即该周期已收盘)
     pub is_final: bool,
 }
+
+/// # Summary
+/// 公司行动的类型及参数。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CorporateActionKind {
+    /// 拆股 (或合股)：每 `denominator` 股变为 `numerator` 股
+    Split { numerator: u32, denominator: u32 },
+    /// 现金分红：每股派发 `amount`
+    Dividend { amount: Decimal },
+}
+
+/// # Summary
+/// 公司行动 (拆股、现金分红) 实体。
+///
+/// # Invariants
+/// - 拆股的 `numerator` 与 `denominator` 均为正；分红的 `amount` 为正，由 `validate` 保证。
+/// - 除权除息日 `ex_date` 当天开盘前的持仓享有该行动。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorporateAction {
+    // 证券代码
+    pub symbol: String,
+    // 除权除息日
+    pub ex_date: DateTime<Utc>,
+    // 行动类型及参数
+    pub kind: CorporateActionKind,
+}
+
+impl CorporateAction {
+    /// 校验拆股比例与分红金额。
+    pub fn validate(&self) -> Result<(), String> {
+        match self.kind {
+            CorporateActionKind::Split {
+                numerator,
+                denominator,
+            } if numerator == 0 || denominator == 0 => {
+                Err("split ratio must be positive".to_string())
+            }
+            CorporateActionKind::Dividend { amount } if amount <= Decimal::ZERO => {
+                Err("dividend amount must be positive".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 拆股后每股对应的新股数 (`numerator / denominator`)；分红返回 `None`。
+    pub fn split_ratio(&self) -> Option<Decimal> {
+        match self.kind {
+            CorporateActionKind::Split {
+                numerator,
+                denominator,
+            } if denominator != 0 => Some(Decimal::from(numerator) / Decimal::from(denominator)),
+            _ => None,
+        }
+    }
+
+    /// 行动的唯一标识，用作账户流水的关联标识以保证同一行动只入账一次。
+    pub fn key(&self) -> String {
+        let ex_date = self.ex_date.format("%Y-%m-%d");
+        match self.kind {
+            CorporateActionKind::Split {
+                numerator,
+                denominator,
+            } => format!(
+                "split:{}:{}:{}/{}",
+                self.symbol, ex_date, numerator, denominator
+            ),
+            CorporateActionKind::Dividend { amount } => {
+                format!(
+                    "dividend:{}:{}:{}",
+                    self.symbol,
+                    ex_date,
+                    amount.normalize()
+                )
+            }
+        }
+    }
+}
+
+/// # Summary
+/// K 线内部价格路径的确定性假设。
+///
+/// # Logic
+/// 一根 K 线只给出 OHLC 四个价位，无法得知盘中高低点的先后顺序。
+/// 撮合时按选定路径在四个价位之间线性移动，决定各挂单的触达先后。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OhlcPath {
+    /// 开 → 高 → 低 → 收
+    #[default]
+    OpenHighLowClose,
+    /// 开 → 低 → 高 → 收
+    OpenLowHighClose,
+}
+
+impl OhlcPath {
+    /// 按路径顺序返回 K 线的四个价位。
+    pub fn points(&self, candle: &Candle) -> [Decimal; 4] {
+        match self {
+            OhlcPath::OpenHighLowClose => [candle.open, candle.high, candle.low, candle.close],
+            OhlcPath::OpenLowHighClose => [candle.open, candle.low, candle.high, candle.close],
+        }
+    }
+}
+
+impl std::str::FromStr for OhlcPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ohlc" | "open_high_low_close" => Ok(OhlcPath::OpenHighLowClose),
+            "olhc" | "open_low_high_close" => Ok(OhlcPath::OpenLowHighClose),
+            _ => Err(format!("Unknown OhlcPath: {}", s)),
+        }
+    }
+}
+
+/// # Summary
+/// 回测使用的价格序列口径。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceAdjustment {
+    /// 原始成交价：拆股调整持仓，分红计入现金
+    #[default]
+    Raw,
+    /// 前复权 (仅拆股)：拆股已体现在价格中，分红按复权后的股数计入现金
+    SplitAdjusted,
+    /// 全收益 (拆股与分红再投资)：公司行动全部体现在价格中，不再调整账户
+    TotalReturn,
+}
+
+impl std::str::FromStr for PriceAdjustment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "raw" => Ok(PriceAdjustment::Raw),
+            "split_adjusted" | "splitadjusted" => Ok(PriceAdjustment::SplitAdjusted),
+            "total_return" | "totalreturn" => Ok(PriceAdjustment::TotalReturn),
+            _ => Err(format!("Unknown PriceAdjustment: {}", s)),
+        }
+    }
+}
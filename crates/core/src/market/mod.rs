@@ -1,3 +1,5 @@
+pub mod adjust;
+pub mod coverage;
 pub mod entity;
 pub mod error;
 pub mod indicator;
@@ -1,5 +1,6 @@
 use crate::common::{Stock as StockIdentity, TimeFrame};
-use crate::market::entity::Candle;
+use crate::market::coverage::CandleCoverage;
+use crate::market::entity::{Candle, CorporateAction};
 use crate::market::error::MarketError;
 use async_trait::async_trait;
 use futures::Stream;
@@ -85,7 +86,7 @@ pub trait Stock: Send + Sync {
     ///
     /// # Logic
     /// 1. 尝试从本地缓存或持久层回溯数据。
-    /// 2. 若本地缺失，则向原始提供者请求补全。
+    /// 2. 本地未覆盖的子区间向原始提供者请求补全，与本地数据合并后返回。
     ///
     /// # Arguments
     /// * `timeframe`: K 线周期。
@@ -101,6 +102,86 @@ pub trait Stock: Send + Sync {
         end: chrono::DateTime<chrono::Utc>,
     ) -> Result<Vec<Candle>, MarketError>;
 
+    /// # Summary
+    /// 获取该证券在指定时间范围内的公司行动 (拆股、现金分红)。
+    ///
+    /// # Logic
+    /// 1. 按除权除息日过滤 `[start, end]` 区间内的行动，按时间升序返回。
+    /// 2. 默认实现视为没有公司行动。
+    ///
+    /// # Arguments
+    /// * `start`: 开始时间。
+    /// * `end`: 结束时间。
+    ///
+    /// # Returns
+    /// 成功返回公司行动列表，失败返回 MarketError。
+    async fn corporate_actions(
+        &self,
+        _start: chrono::DateTime<chrono::Utc>,
+        _end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        Ok(Vec::new())
+    }
+
+    /// # Summary
+    /// 手工导入公司行动 (覆盖同一除权除息日的同类行动)。
+    ///
+    /// # Arguments
+    /// * `actions`: 待导入的公司行动，`symbol` 必须与本聚合根一致。
+    ///
+    /// # Returns
+    /// 默认实现不支持导入。
+    async fn import_corporate_actions(
+        &self,
+        _actions: Vec<CorporateAction>,
+    ) -> Result<(), MarketError> {
+        Err(MarketError::Unknown(
+            "corporate action import is not supported".to_string(),
+        ))
+    }
+
+    /// # Summary
+    /// 查询本地已从数据源拉取的 K 线覆盖区间与缺口。
+    ///
+    /// # Arguments
+    /// * `timeframe`: K 线周期。
+    /// * `start`: 开始时间。
+    /// * `end`: 结束时间。
+    ///
+    /// # Returns
+    /// 默认实现不支持覆盖查询。
+    async fn candle_coverage(
+        &self,
+        _timeframe: TimeFrame,
+        _start: chrono::DateTime<chrono::Utc>,
+        _end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<CandleCoverage, MarketError> {
+        Err(MarketError::Unknown(
+            "candle coverage is not supported".to_string(),
+        ))
+    }
+
+    /// # Summary
+    /// 丢弃本地记录的 K 线覆盖区间，使下次 `fetch_history` 向数据源重新拉取该区间。
+    ///
+    /// # Arguments
+    /// * `timeframe`: K 线周期。
+    /// * `start`: 开始时间。
+    /// * `end`: 结束时间。
+    ///
+    /// # Returns
+    /// 默认实现不支持覆盖区间。
+    async fn clear_candle_coverage(
+        &self,
+        _timeframe: TimeFrame,
+        _start: chrono::DateTime<chrono::Utc>,
+        _end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), MarketError> {
+        Err(MarketError::Unknown(
+            "candle coverage is not supported".to_string(),
+        ))
+    }
+
     /// # Summary
     /// 获取聚合根当前的运行状态。
     ///
@@ -168,6 +249,28 @@ pub trait MarketDataProvider: Send + Sync {
     /// 成功返回异步流。
     async fn subscribe_candles(&self, stock: &StockIdentity) -> Result<CandleStream, MarketError>;
 
+    /// # Summary
+    /// 获取特定证券在指定时间范围内的公司行动。
+    ///
+    /// # Logic
+    /// 1. 请求数据源的拆股与分红事件；默认实现视为数据源不提供公司行动。
+    ///
+    /// # Arguments
+    /// * `stock`: 证券身份。
+    /// * `start`: 开始时间。
+    /// * `end`: 结束时间。
+    ///
+    /// # Returns
+    /// 成功返回按除权除息日升序的公司行动列表。
+    async fn fetch_corporate_actions(
+        &self,
+        _stock: &StockIdentity,
+        _start: chrono::DateTime<chrono::Utc>,
+        _end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        Ok(Vec::new())
+    }
+
     /// # Summary
     /// 搜索股票元数据。
     ///
@@ -185,6 +288,33 @@ pub trait MarketDataProvider: Send + Sync {
     ) -> Result<Vec<crate::store::port::StockMetadata>, MarketError>;
 }
 
+/// # Summary
+/// 原始行情录制器契约，记录数据源推送的每一条原始更新，用于事后按原序列重放。
+///
+/// # Invariants
+/// - 同一证券的记录按接收顺序追加，不得重排或合并。
+pub trait TickRecorder: Send + Sync {
+    /// # Summary
+    /// 追加一条原始行情。
+    ///
+    /// # Arguments
+    /// * `stock`: 证券身份。
+    /// * `received_at`: 本地接收时间。
+    /// * `candle`: 数据源推送的原始 tick 或 K 线，未经聚合。
+    ///
+    /// # Logic
+    /// 在行情拉取任务中同步调用，实现不得在此阻塞于磁盘 I/O，可先入队再异步落盘。
+    ///
+    /// # Returns
+    /// 无法受理 (如录制器已停止) 时返回 MarketError。
+    fn record(
+        &self,
+        stock: &StockIdentity,
+        received_at: chrono::DateTime<chrono::Utc>,
+        candle: &Candle,
+    ) -> Result<(), MarketError>;
+}
+
 /// # Summary
 /// Market 领域服务契约（工厂与注册表）。
 ///
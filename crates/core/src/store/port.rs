@@ -1,6 +1,7 @@
 use super::error::StoreError;
 use crate::common::{Stock, TimeFrame};
-use crate::market::entity::Candle;
+use crate::market::coverage::TimeRange;
+use crate::market::entity::{Candle, CorporateAction};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
@@ -120,6 +121,54 @@ pub struct AccountProfile {
     pub account_type: String,
     pub config: serde_json::Value,
     pub created_at: DateTime<Utc>,
+    /// 是否已被紧急停止 (kill switch)；停止期间拒绝新委托，直至重新启用
+    #[serde(default)]
+    pub halted: bool,
+}
+
+/// # Summary
+/// 逻辑交易账号审计动作。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AccountAuditAction {
+    /// 紧急停止：停止策略、撤销全部委托并禁止新委托
+    Halt,
+    /// 解除停止，恢复接受委托
+    Resume,
+}
+
+impl std::str::FromStr for AccountAuditAction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Halt" => Ok(AccountAuditAction::Halt),
+            "Resume" => Ok(AccountAuditAction::Resume),
+            _ => Err(format!("Unknown account audit action: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for AccountAuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountAuditAction::Halt => write!(f, "Halt"),
+            AccountAuditAction::Resume => write!(f, "Resume"),
+        }
+    }
+}
+
+/// # Summary
+/// 逻辑交易账号审计记录，记录谁在何时对账号执行了何种管控动作。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AccountAuditRecord {
+    pub id: String,
+    pub account_id: String,
+    pub action: AccountAuditAction,
+    /// 触发动作的用户 ID
+    pub operator_id: String,
+    pub reason: Option<String>,
+    /// 动作执行明细 (如被停止的策略、被撤销的订单)
+    pub detail: serde_json::Value,
+    pub created_at: DateTime<Utc>,
 }
 
 /// # Summary
@@ -172,6 +221,93 @@ pub trait MarketStore: Send + Sync {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Vec<Candle>, StoreError>;
+
+    /// # Summary
+    /// 加载已从数据源完整拉取过的 K 线区间。
+    ///
+    /// # Arguments
+    /// * `stock`: 目标证券实体。
+    /// * `timeframe`: K 线周期。
+    ///
+    /// # Returns
+    /// 按开始时间升序、互不重叠的区间列表。
+    async fn load_candle_coverage(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+    ) -> Result<Vec<TimeRange>, StoreError>;
+
+    /// # Summary
+    /// 记录一个已从数据源完整拉取过的 K 线区间。
+    ///
+    /// # Logic
+    /// 1. 与已有区间合并，重叠或首尾相接的区间合为一段。
+    ///
+    /// # Arguments
+    /// * `stock`: 目标证券实体。
+    /// * `timeframe`: K 线周期。
+    /// * `range`: 已拉取的区间，区间内的 K 线必须已保存。
+    ///
+    /// # Returns
+    /// 成功返回 Ok，失败返回 `StoreError`。
+    async fn save_candle_coverage(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+        range: TimeRange,
+    ) -> Result<(), StoreError>;
+
+    /// # Summary
+    /// 移除一段已记录的 K 线覆盖区间，使其下次查询时重新向数据源拉取。
+    ///
+    /// # Arguments
+    /// * `stock`: 目标证券实体。
+    /// * `timeframe`: K 线周期。
+    /// * `range`: 待移除的区间，已保存的 K 线保留，重新拉取时覆盖写入。
+    ///
+    /// # Returns
+    /// 成功返回 Ok，失败返回 `StoreError`。
+    async fn clear_candle_coverage(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+        range: TimeRange,
+    ) -> Result<(), StoreError>;
+
+    /// # Summary
+    /// 批量保存公司行动。
+    ///
+    /// # Logic
+    /// 1. 以 (除权除息日, 行动类型) 为键覆盖写入，重复同步不会产生重复记录。
+    ///
+    /// # Arguments
+    /// * `stock`: 目标证券实体。
+    /// * `actions`: 待保存的公司行动。
+    ///
+    /// # Returns
+    /// 成功返回 Ok，失败返回 `StoreError`。
+    async fn save_corporate_actions(
+        &self,
+        stock: &Stock,
+        actions: &[CorporateAction],
+    ) -> Result<(), StoreError>;
+
+    /// # Summary
+    /// 加载除权除息日位于 `[start, end]` 区间内的公司行动，按时间升序返回。
+    ///
+    /// # Arguments
+    /// * `stock`: 目标证券实体。
+    /// * `start`: 开始时间。
+    /// * `end`: 结束时间。
+    ///
+    /// # Returns
+    /// 返回公司行动列表或 `StoreError`。
+    async fn load_corporate_actions(
+        &self,
+        stock: &Stock,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<CorporateAction>, StoreError>;
 }
 
 /// # Summary
@@ -207,6 +343,9 @@ pub trait SystemStore: Send + Sync {
         account_id: &str,
     ) -> Result<Option<AccountProfile>, StoreError>;
 
+    /// 列出全部逻辑交易账号档案，供启动时恢复账号级的后台任务
+    async fn list_account_profiles(&self) -> Result<Vec<AccountProfile>, StoreError>;
+
     /// 将新创建的逻辑交易账号绑定给目标用户
     async fn bind_account(
         &self,
@@ -217,6 +356,25 @@ pub trait SystemStore: Send + Sync {
         config: serde_json::Value,
     ) -> Result<(), StoreError>;
 
+    /// 替换逻辑交易账号的配置 (成本模型、风控规则、保证金模型等)
+    async fn update_account_config(
+        &self,
+        account_id: &str,
+        config: serde_json::Value,
+    ) -> Result<(), StoreError>;
+
+    /// 设置逻辑交易账号的紧急停止状态
+    async fn set_account_halted(&self, account_id: &str, halted: bool) -> Result<(), StoreError>;
+
+    /// 追加一条账号审计记录
+    async fn save_account_audit(&self, record: &AccountAuditRecord) -> Result<(), StoreError>;
+
+    /// 按时间倒序列出账号的审计记录
+    async fn list_account_audit(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<AccountAuditRecord>, StoreError>;
+
     /// 获取某用户拥有的所有逻辑交易账号 ID 列表
     async fn get_user_accounts(&self, user_id: &str) -> Result<Vec<String>, StoreError>;
 
@@ -4,7 +4,8 @@
 //! 这些工具被设计为跨模块通用，以消除测试代码中的逻辑重复。
 
 use crate::common::{Stock as StockIdentity, TimeFrame};
-use crate::market::entity::Candle;
+use crate::market::coverage::{self, TimeRange};
+use crate::market::entity::{Candle, CorporateAction};
 use crate::market::error::MarketError;
 use crate::market::port::{CandleStream, MarketDataProvider, StockStatus};
 use crate::store::error::StoreError;
@@ -25,6 +26,7 @@ pub struct MockMarketDataProvider {
     price_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Candle>>>,
     search_results: Arc<Mutex<Vec<StockMetadata>>>,
     history: Arc<Mutex<Vec<Candle>>>,
+    corporate_actions: Arc<Mutex<Vec<CorporateAction>>>,
 }
 
 impl Default for MockMarketDataProvider {
@@ -35,6 +37,7 @@ impl Default for MockMarketDataProvider {
             price_rx: Arc::new(tokio::sync::Mutex::new(rx)),
             search_results: Arc::new(Mutex::new(Vec::new())),
             history: Arc::new(Mutex::new(Vec::new())),
+            corporate_actions: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -70,6 +73,18 @@ impl MockMarketDataProvider {
         *guard = candles;
         Ok(())
     }
+
+    pub fn set_corporate_actions(
+        &self,
+        actions: Vec<CorporateAction>,
+    ) -> Result<(), crate::error::CoreError> {
+        let mut guard = self
+            .corporate_actions
+            .lock()
+            .map_err(|e| crate::error::CoreError::Poisoned(e.to_string()))?;
+        *guard = actions;
+        Ok(())
+    }
 }
 
 pub struct MockStock {
@@ -168,6 +183,23 @@ impl MarketDataProvider for MockMarketDataProvider {
         Ok(Box::pin(s))
     }
 
+    async fn fetch_corporate_actions(
+        &self,
+        _: &StockIdentity,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        let actions = self
+            .corporate_actions
+            .lock()
+            .map_err(|e| MarketError::Unknown(format!("Lock poisoned: {}", e)))?
+            .clone();
+        Ok(actions
+            .into_iter()
+            .filter(|a| a.ex_date >= start && a.ex_date <= end)
+            .collect())
+    }
+
     async fn search_symbols(&self, _query: &str) -> Result<Vec<StockMetadata>, MarketError> {
         let results = self
             .search_results
@@ -184,12 +216,16 @@ impl MarketDataProvider for MockMarketDataProvider {
 
 pub struct MemMarketStore {
     db: dashmap::DashMap<(String, TimeFrame), Vec<Candle>>,
+    actions: dashmap::DashMap<String, Vec<CorporateAction>>,
+    coverage: dashmap::DashMap<(String, TimeFrame), Vec<TimeRange>>,
 }
 
 impl Default for MemMarketStore {
     fn default() -> Self {
         Self {
             db: dashmap::DashMap::new(),
+            actions: dashmap::DashMap::new(),
+            coverage: dashmap::DashMap::new(),
         }
     }
 }
@@ -212,7 +248,10 @@ impl MarketStore for MemMarketStore {
             .db
             .entry((stock.symbol.clone(), timeframe))
             .or_default();
+        // 与 SQLite 实现一致：同一时间的 K 线覆盖写入
+        entry.retain(|c| !candles.iter().any(|n| n.time == c.time));
         entry.extend_from_slice(candles);
+        entry.sort_by_key(|c| c.time);
         Ok(())
     }
 
@@ -235,6 +274,81 @@ impl MarketStore for MemMarketStore {
             .filter(|c| c.time >= start && c.time <= end)
             .collect())
     }
+
+    async fn load_candle_coverage(
+        &self,
+        stock: &StockIdentity,
+        timeframe: TimeFrame,
+    ) -> Result<Vec<TimeRange>, StoreError> {
+        Ok(self
+            .coverage
+            .get(&(stock.symbol.clone(), timeframe))
+            .map(|ranges| ranges.clone())
+            .unwrap_or_default())
+    }
+
+    async fn save_candle_coverage(
+        &self,
+        stock: &StockIdentity,
+        timeframe: TimeFrame,
+        range: TimeRange,
+    ) -> Result<(), StoreError> {
+        let mut entry = self
+            .coverage
+            .entry((stock.symbol.clone(), timeframe))
+            .or_default();
+        entry.push(range);
+        *entry = coverage::merge(&entry);
+        Ok(())
+    }
+
+    async fn clear_candle_coverage(
+        &self,
+        stock: &StockIdentity,
+        timeframe: TimeFrame,
+        range: TimeRange,
+    ) -> Result<(), StoreError> {
+        if let Some(mut entry) = self.coverage.get_mut(&(stock.symbol.clone(), timeframe)) {
+            *entry = coverage::subtract(&entry, range.start, range.end);
+        }
+        Ok(())
+    }
+
+    async fn save_corporate_actions(
+        &self,
+        stock: &StockIdentity,
+        actions: &[CorporateAction],
+    ) -> Result<(), StoreError> {
+        let mut entry = self.actions.entry(stock.symbol.clone()).or_default();
+        for action in actions {
+            entry.retain(|a| {
+                a.ex_date != action.ex_date
+                    || std::mem::discriminant(&a.kind) != std::mem::discriminant(&action.kind)
+            });
+            entry.push(action.clone());
+        }
+        entry.sort_by_key(|a| a.ex_date);
+        Ok(())
+    }
+
+    async fn load_corporate_actions(
+        &self,
+        stock: &StockIdentity,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<CorporateAction>, StoreError> {
+        Ok(self
+            .actions
+            .get(&stock.symbol)
+            .map(|actions| {
+                actions
+                    .iter()
+                    .filter(|a| a.ex_date >= start && a.ex_date <= end)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
 }
 
 // ============================================================
@@ -0,0 +1,232 @@
+use super::entity::OrderDirection;
+use super::port::TradeError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// 逻辑交易账号配置 (`AccountProfile.config`) 中承载成本模型的键名。
+pub const COST_MODEL_KEY: &str = "cost_model";
+
+/// # Summary
+/// 成交滑点模型，成交价总是向不利于委托方的方向偏移。
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlippageModel {
+    /// 无滑点
+    #[default]
+    None,
+    /// 固定基点：偏移 `price * bps / 10000`
+    FixedBps { bps: Decimal },
+    /// 价差：偏移半个买卖价差 (绝对价格)
+    Spread { spread: Decimal },
+}
+
+/// # Summary
+/// 佣金模型。`min` 为单笔成交的最低佣金。
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommissionModel {
+    /// 免佣
+    #[default]
+    None,
+    /// 按股数计费：`volume * rate`
+    PerShare {
+        rate: Decimal,
+        #[serde(default)]
+        min: Decimal,
+    },
+    /// 按成交额比例计费：`price * volume * rate`
+    Percent {
+        rate: Decimal,
+        #[serde(default)]
+        min: Decimal,
+    },
+}
+
+/// # Summary
+/// 逻辑交易账号的交易成本模型，决定撮合时的滑点与各项费用。
+///
+/// # Invariants
+/// - 所有费率、价差与最低佣金均不为负，由 `validate` 保证。
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CostModel {
+    /// 滑点模型
+    pub slippage: SlippageModel,
+    /// 佣金模型
+    pub commission: CommissionModel,
+    /// 印花税率，仅对卖出成交额征收
+    pub stamp_duty_rate: Decimal,
+    /// 交易所规费率，买卖双向按成交额征收
+    pub exchange_fee_rate: Decimal,
+}
+
+impl CostModel {
+    /// 仅按成交额比例收取佣金、无最低佣金的成本模型。
+    pub fn percent_commission(rate: Decimal) -> Self {
+        Self {
+            commission: CommissionModel::Percent {
+                rate,
+                min: Decimal::ZERO,
+            },
+            ..Self::default()
+        }
+    }
+
+    /// # Logic
+    /// Read the `cost_model` entry of a logical account config; a missing entry
+    /// means no slippage and no fees.
+    ///
+    /// # Arguments
+    /// * `config` - `AccountProfile.config` of the account.
+    ///
+    /// # Returns
+    /// * `Err(TradeError::InvalidCostModel)` - If the entry is malformed or has negative values.
+    pub fn from_account_config(config: &serde_json::Value) -> Result<Self, TradeError> {
+        let Some(raw) = config.get(COST_MODEL_KEY) else {
+            return Ok(Self::default());
+        };
+        let model: Self = serde_json::from_value(raw.clone())
+            .map_err(|e| TradeError::InvalidCostModel(e.to_string()))?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// 校验所有参数非负。
+    pub fn validate(&self) -> Result<(), TradeError> {
+        let slippage = match &self.slippage {
+            SlippageModel::None => Decimal::ZERO,
+            SlippageModel::FixedBps { bps } => *bps,
+            SlippageModel::Spread { spread } => *spread,
+        };
+        let (rate, min) = match &self.commission {
+            CommissionModel::None => (Decimal::ZERO, Decimal::ZERO),
+            CommissionModel::PerShare { rate, min } | CommissionModel::Percent { rate, min } => {
+                (*rate, *min)
+            }
+        };
+        let values = [
+            ("slippage", slippage),
+            ("commission rate", rate),
+            ("commission min", min),
+            ("stamp_duty_rate", self.stamp_duty_rate),
+            ("exchange_fee_rate", self.exchange_fee_rate),
+        ];
+        match values.iter().find(|(_, value)| *value < Decimal::ZERO) {
+            Some((name, _)) => Err(TradeError::InvalidCostModel(format!(
+                "{} must not be negative",
+                name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// 将滑点作用于参考成交价：买入上浮、卖出下调。
+    pub fn apply_slippage(&self, direction: OrderDirection, price: Decimal) -> Decimal {
+        let offset = match &self.slippage {
+            SlippageModel::None => Decimal::ZERO,
+            SlippageModel::FixedBps { bps } => price * *bps / Decimal::from(10_000),
+            SlippageModel::Spread { spread } => *spread / Decimal::TWO,
+        };
+        match direction {
+            OrderDirection::Buy => price + offset,
+            OrderDirection::Sell => (price - offset).max(Decimal::ZERO),
+        }
+    }
+
+    /// # Logic
+    /// Total fees of one fill: commission (at least `min`), exchange fees and,
+    /// for sells only, stamp duty.
+    pub fn fees(&self, direction: OrderDirection, price: Decimal, volume: Decimal) -> Decimal {
+        if volume <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        let notional = price * volume;
+        let commission = match &self.commission {
+            CommissionModel::None => Decimal::ZERO,
+            CommissionModel::PerShare { rate, min } => (volume * *rate).max(*min),
+            CommissionModel::Percent { rate, min } => (notional * *rate).max(*min),
+        };
+        let stamp_duty = match direction {
+            OrderDirection::Buy => Decimal::ZERO,
+            OrderDirection::Sell => notional * self.stamp_duty_rate,
+        };
+        commission + stamp_duty + notional * self.exchange_fee_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_model_parses_from_account_config() -> Result<(), TradeError> {
+        let config = serde_json::json!({
+            "initial_balance": "10000",
+            "cost_model": {
+                "slippage": { "type": "fixed_bps", "bps": 10 },
+                "commission": { "type": "per_share", "rate": "0.01", "min": 1 },
+                "stamp_duty_rate": 0.001
+            }
+        });
+        let model = CostModel::from_account_config(&config)?;
+        assert_eq!(
+            model.slippage,
+            SlippageModel::FixedBps {
+                bps: Decimal::from(10)
+            }
+        );
+        assert_eq!(model.exchange_fee_rate, Decimal::ZERO);
+
+        assert_eq!(
+            CostModel::from_account_config(&serde_json::json!({}))?,
+            CostModel::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cost_model_fees_and_slippage() {
+        let model = CostModel {
+            slippage: SlippageModel::Spread {
+                spread: Decimal::new(2, 1),
+            },
+            commission: CommissionModel::PerShare {
+                rate: Decimal::new(1, 2),
+                min: Decimal::ONE,
+            },
+            stamp_duty_rate: Decimal::new(1, 3),
+            exchange_fee_rate: Decimal::ZERO,
+        };
+        let price = Decimal::from(100);
+        assert_eq!(
+            model.apply_slippage(OrderDirection::Buy, price),
+            Decimal::new(1001, 1)
+        );
+        assert_eq!(
+            model.apply_slippage(OrderDirection::Sell, price),
+            Decimal::new(999, 1)
+        );
+
+        // 10 股佣金 0.1 低于最低佣金 1
+        assert_eq!(
+            model.fees(OrderDirection::Buy, price, Decimal::from(10)),
+            Decimal::ONE
+        );
+        // 卖出额外收取 1000 * 0.1% 印花税
+        assert_eq!(
+            model.fees(OrderDirection::Sell, price, Decimal::from(10)),
+            Decimal::from(2)
+        );
+    }
+
+    #[test]
+    fn test_cost_model_rejects_negative_values() {
+        let config = serde_json::json!({
+            "cost_model": { "commission": { "type": "percent", "rate": -0.1 } }
+        });
+        assert!(matches!(
+            CostModel::from_account_config(&config),
+            Err(TradeError::InvalidCostModel(_))
+        ));
+    }
+}
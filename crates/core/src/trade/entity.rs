@@ -41,6 +41,33 @@ pub enum OrderStatus {
     Canceled,
     /// 拒绝 (风控拒绝或券商拒绝)
     Rejected,
+    /// 已过期 (按有效期规则终止，尚未成交的部分被回收)
+    Expired,
+}
+
+/// # Summary
+/// 订单的有效期 (Time In Force)。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimeInForce {
+    /// 撤销前一直有效
+    #[default]
+    Gtc,
+    /// 当日有效，交易时段收盘时过期
+    Day,
+    /// 立即成交，未成交的部分立即过期
+    Ioc,
+    /// 全部立即成交，否则整单过期
+    Fok,
+    /// 指定时间 (毫秒时间戳) 前有效
+    Gtd { expire_at: i64 },
+}
+
+impl TimeInForce {
+    /// 是否要求提交时立即成交 (IOC / FOK)。
+    pub fn is_immediate(&self) -> bool {
+        matches!(self, Self::Ioc | Self::Fok)
+    }
 }
 
 /// # Summary
@@ -65,6 +92,31 @@ pub struct Order {
     pub status: OrderStatus,
     /// 订单创建的系统时间戳 (毫秒)
     pub created_at: i64,
+    /// 触发条件 (止损 / 跟踪止损)。为空时按 `price` 直接以市价或限价参与撮合
+    #[serde(default)]
+    pub trigger: Option<OrderTrigger>,
+    /// 有效期
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
+    /// 过期时间 (毫秒)，提交时由有效期解析 (DAY 为交易时段收盘，GTD 为指定时间)
+    #[serde(default)]
+    pub expire_at: Option<i64>,
+    /// 所属订单组 (OCO / 括号单)
+    #[serde(default)]
+    pub group_id: Option<OrderId>,
+    /// 保证金账户冻结资金所依据的初始保证金率，由交易服务在提交时写入；
+    /// 为空时按现金账户规则 (买单全额冻结，卖单不冻结)，为零表示平仓委托不占用购买力
+    #[serde(default)]
+    pub margin_rate: Option<Decimal>,
+    /// 下单的策略运行 ID，手工委托为空
+    #[serde(default)]
+    pub strategy_run_id: Option<String>,
+    /// 结算币种 (标的挂牌货币)，由交易服务在提交时写入；为空时以账户基础货币结算
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// 最近一次已按拆股调整价格与数量的除权日 (毫秒)，防止同一拆股重复调整
+    #[serde(default)]
+    pub split_adjusted_at: Option<i64>,
 }
 
 impl Order {
@@ -89,7 +141,270 @@ impl Order {
             filled_volume: Decimal::ZERO,
             status: OrderStatus::Pending,
             created_at: now_ms,
+            trigger: None,
+            time_in_force: TimeInForce::Gtc,
+            expire_at: None,
+            group_id: None,
+            margin_rate: None,
+            strategy_run_id: None,
+            currency: None,
+            split_adjusted_at: None,
+        }
+    }
+
+    /// # Logic
+    /// 按拆股比例调整除权日前提交的挂单：数量 (含已成交数量) 乘以比例，限价、触发价、
+    /// 跟踪止损的极值与绝对偏移除以比例，委托的名义金额不变。
+    /// 除权日及之后提交的委托、或已按该次及更晚拆股调整过的委托不做调整。
+    ///
+    /// # Returns
+    /// 是否做了调整。
+    pub fn apply_split(&mut self, ratio: Decimal, ex_date_ms: i64) -> bool {
+        if ratio <= Decimal::ZERO
+            || self.created_at >= ex_date_ms
+            || self.split_adjusted_at.is_some_and(|at| at >= ex_date_ms)
+        {
+            return false;
         }
+        self.volume *= ratio;
+        self.filled_volume *= ratio;
+        self.price = self.price.map(|price| price / ratio);
+        if let Some(trigger) = &mut self.trigger {
+            trigger.kind = match trigger.kind {
+                TriggerKind::Stop { stop_price } => TriggerKind::Stop {
+                    stop_price: stop_price / ratio,
+                },
+                TriggerKind::TrailingStop {
+                    offset: TrailOffset::Amount(amount),
+                } => TriggerKind::TrailingStop {
+                    offset: TrailOffset::Amount(amount / ratio),
+                },
+                kind => kind,
+            };
+            trigger.extreme = trigger.extreme.map(|extreme| extreme / ratio);
+            trigger.initial_stop = trigger.initial_stop.map(|stop| stop / ratio);
+        }
+        self.split_adjusted_at = Some(ex_date_ms);
+        true
+    }
+
+    /// 附加触发条件：`price` 为空时为止损市价单，否则为止损限价单。
+    pub fn with_trigger(mut self, trigger: OrderTrigger) -> Self {
+        self.trigger = Some(trigger);
+        self
+    }
+
+    /// 设置有效期。
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    /// 在给定时刻是否已过期。
+    pub fn is_expired_at(&self, now_ms: i64) -> bool {
+        self.expire_at.is_some_and(|expire_at| expire_at <= now_ms)
+    }
+
+    /// 是否仍在等待触发。
+    pub fn is_awaiting_trigger(&self) -> bool {
+        self.trigger.as_ref().is_some_and(|t| !t.triggered)
+    }
+
+    /// # Logic
+    /// 买单冻结资金所依据的单价：限价单取限价，止损市价单取提交时的触发价。
+    /// 普通市价单返回 `None`，由调用方按最新价估算。
+    pub fn reserve_price(&self) -> Option<Decimal> {
+        self.price
+            .or_else(|| self.trigger.as_ref().and_then(|t| t.initial_stop))
+    }
+}
+
+/// # Summary
+/// 跟踪止损的偏移量。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum TrailOffset {
+    /// 绝对价格偏移
+    Amount(Decimal),
+    /// 相对最有利价格的百分比偏移 (例如 5 表示 5%)
+    Percent(Decimal),
+}
+
+/// # Summary
+/// 触发条件的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TriggerKind {
+    /// 固定触发价：买单价格上穿、卖单价格下穿 `stop_price` 时触发
+    Stop { stop_price: Decimal },
+    /// 跟踪止损：卖单在最高价回落 `offset`、买单在最低价反弹 `offset` 时触发
+    TrailingStop { offset: TrailOffset },
+}
+
+/// # Summary
+/// 订单的触发条件及其运行状态。
+///
+/// # Invariants
+/// - `triggered` 一旦为 true 不再回退，此后订单按市价 (`price` 为空) 或限价撮合。
+/// - `initial_stop` 在提交时确定且不再变化，买单据此冻结资金。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderTrigger {
+    /// 触发类型
+    pub kind: TriggerKind,
+    /// 跟踪止损迄今的最有利价格 (卖单为最高价，买单为最低价)
+    #[serde(default)]
+    pub extreme: Option<Decimal>,
+    /// 提交时的触发价
+    #[serde(default)]
+    pub initial_stop: Option<Decimal>,
+    /// 是否已触发
+    #[serde(default)]
+    pub triggered: bool,
+}
+
+impl OrderTrigger {
+    /// 固定触发价的止损条件。
+    pub fn stop(stop_price: Decimal) -> Self {
+        Self {
+            kind: TriggerKind::Stop { stop_price },
+            extreme: None,
+            initial_stop: Some(stop_price),
+            triggered: false,
+        }
+    }
+
+    /// 跟踪止损条件，提交时以最新价作为初始最有利价格。
+    pub fn trailing(offset: TrailOffset) -> Self {
+        Self {
+            kind: TriggerKind::TrailingStop { offset },
+            extreme: None,
+            initial_stop: None,
+            triggered: false,
+        }
+    }
+
+    /// # Logic
+    /// Trigger level implied by a given favourable extreme.
+    pub fn level_from(&self, direction: OrderDirection, extreme: Decimal) -> Decimal {
+        match self.kind {
+            TriggerKind::Stop { stop_price } => stop_price,
+            TriggerKind::TrailingStop { offset } => {
+                let distance = match offset {
+                    TrailOffset::Amount(amount) => amount,
+                    TrailOffset::Percent(percent) => extreme * percent / Decimal::ONE_HUNDRED,
+                };
+                match direction {
+                    OrderDirection::Buy => extreme + distance,
+                    OrderDirection::Sell => extreme - distance,
+                }
+            }
+        }
+    }
+
+    /// # Logic
+    /// Anchor the trigger at submission: trailing stops start tracking from
+    /// `market_price`, and `initial_stop` records the level funds are reserved at.
+    pub fn arm(&mut self, direction: OrderDirection, market_price: Decimal) {
+        let extreme = match self.kind {
+            TriggerKind::Stop { .. } => market_price,
+            TriggerKind::TrailingStop { .. } => *self.extreme.get_or_insert(market_price),
+        };
+        if self.initial_stop.is_none() {
+            self.initial_stop = Some(self.level_from(direction, extreme));
+        }
+    }
+
+    /// 校验触发参数为正。
+    pub fn is_valid(&self) -> bool {
+        match self.kind {
+            TriggerKind::Stop { stop_price } => stop_price > Decimal::ZERO,
+            TriggerKind::TrailingStop { offset } => match offset {
+                TrailOffset::Amount(amount) => amount > Decimal::ZERO,
+                TrailOffset::Percent(percent) => {
+                    percent > Decimal::ZERO && percent < Decimal::ONE_HUNDRED
+                }
+            },
+        }
+    }
+}
+
+/// # Summary
+/// 订单组的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderGroupKind {
+    /// 二选一：任一子单成交即撤销其余子单
+    Oco,
+    /// 括号单：入场单成交后才激活止盈、止损两个互斥子单
+    Bracket,
+}
+
+/// # Summary
+/// 生命周期相互关联的一组订单 (OCO / 括号单)。
+///
+/// # Invariants
+/// - 入场单与全部子单属于同一账户、同一标的。
+/// - 括号单的子单方向与入场单相反。
+/// - `activated` 之前子单仅为模板，不参与撮合、不冻结资金。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderGroup {
+    /// 订单组 ID
+    pub id: OrderId,
+    /// 归属的逻辑系统账户
+    pub account_id: AccountId,
+    /// 交易标的
+    pub symbol: String,
+    /// 订单组类型
+    pub kind: OrderGroupKind,
+    /// 括号单的入场单 ID (OCO 为空)
+    pub parent_id: Option<OrderId>,
+    /// 互斥子单。激活前为待提交的模板，激活后 ID 与活动订单一致
+    pub legs: Vec<Order>,
+    /// 子单是否已提交撮合
+    pub activated: bool,
+    /// 创建时间戳 (毫秒)
+    pub created_at: i64,
+}
+
+impl OrderGroup {
+    /// 由两个互斥订单组成的 OCO 组，提交时立即激活。
+    pub fn oco(id: OrderId, first: Order, second: Order, now_ms: i64) -> Self {
+        Self {
+            id,
+            account_id: first.account_id.clone(),
+            symbol: first.symbol.clone(),
+            kind: OrderGroupKind::Oco,
+            parent_id: None,
+            legs: vec![first, second],
+            activated: false,
+            created_at: now_ms,
+        }
+    }
+
+    /// # Logic
+    /// Bracket around `entry`: the take-profit and stop-loss legs stay dormant until
+    /// the entry order is done, then activate as an OCO pair sized to its filled volume.
+    pub fn bracket(
+        id: OrderId,
+        entry: &Order,
+        take_profit: Order,
+        stop_loss: Order,
+        now_ms: i64,
+    ) -> Self {
+        Self {
+            id,
+            account_id: entry.account_id.clone(),
+            symbol: entry.symbol.clone(),
+            kind: OrderGroupKind::Bracket,
+            parent_id: Some(entry.id.clone()),
+            legs: vec![take_profit, stop_loss],
+            activated: false,
+            created_at: now_ms,
+        }
+    }
+
+    /// 是否为该组的子单。
+    pub fn is_leg(&self, order_id: &OrderId) -> bool {
+        self.legs.iter().any(|leg| leg.id == *order_id)
     }
 }
 
@@ -114,6 +429,20 @@ pub struct Trade {
     pub commission: Decimal,
     /// 成交时间戳 (毫秒)
     pub timestamp: i64,
+    /// 外币结算信息；为空时以账户基础货币结算
+    #[serde(default)]
+    pub settlement: Option<FxSettlement>,
+}
+
+/// # Summary
+/// 以非基础货币结算的成交或公司行动的结算币种，及发生时折算为基础货币的汇率。
+///
+/// # Invariants
+/// - `rate` 为 1 单位 `currency` 折合的基础货币数量，必须为正。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxSettlement {
+    pub currency: String,
+    pub rate: Decimal,
 }
 
 /// # Summary
@@ -128,6 +457,18 @@ pub struct Position {
     pub volume: Decimal,
     /// 持仓均价 (用于计算盈亏)
     pub average_price: Decimal,
+    /// 累计已实现盈亏 (减仓成交按持仓均价结算，不含费用)
+    #[serde(default)]
+    pub realized_pnl: Decimal,
+    /// 累计交易费用
+    #[serde(default)]
+    pub commission: Decimal,
+    /// 最新价，估值时填充
+    #[serde(default)]
+    pub last_price: Option<Decimal>,
+    /// 未实现盈亏 `(最新价 - 持仓均价) × 持仓数量`，估值时填充
+    #[serde(default)]
+    pub unrealized_pnl: Decimal,
 }
 
 impl Position {
@@ -139,8 +480,42 @@ impl Position {
             symbol,
             volume: Decimal::ZERO,
             average_price: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            commission: Decimal::ZERO,
+            last_price: None,
+            unrealized_pnl: Decimal::ZERO,
+        }
+    }
+
+    /// # Logic
+    /// 一笔成交对持仓 (`volume`, `average_price`) 的已实现盈亏：
+    /// 反向成交平掉的数量 × (成交价 - 持仓均价)，空头取反；
+    /// 同向加仓或空仓开仓为零，反手时只结算原持仓部分。
+    pub fn closing_pnl(
+        volume: Decimal,
+        average_price: Decimal,
+        delta_volume: Decimal,
+        trade_price: Decimal,
+    ) -> Decimal {
+        if volume.is_zero()
+            || delta_volume.is_zero()
+            || volume.is_sign_positive() == delta_volume.is_sign_positive()
+        {
+            return Decimal::ZERO;
+        }
+        let closed = delta_volume.abs().min(volume.abs());
+        if volume.is_sign_positive() {
+            closed * (trade_price - average_price)
+        } else {
+            closed * (average_price - trade_price)
         }
     }
+
+    /// 按最新价填充估值字段。
+    pub fn mark(&mut self, last_price: Decimal) {
+        self.last_price = Some(last_price);
+        self.unrealized_pnl = (last_price - self.average_price) * self.volume;
+    }
 }
 
 /// # Summary
@@ -157,6 +532,199 @@ pub struct AccountSnapshot {
     pub total_equity: Decimal,
     /// 持仓列表
     pub positions: Vec<Position>,
+    /// 保证金状态，仅保证金账户按最新行情核算后填充
+    #[serde(default)]
+    pub margin: Option<super::margin::MarginStatus>,
+    /// 账户累计已实现盈亏 (含已平仓标的，不含费用)
+    #[serde(default)]
+    pub realized_pnl: Decimal,
+    /// 账户累计交易费用
+    #[serde(default)]
+    pub total_commission: Decimal,
+    /// 持仓未实现盈亏之和，估值时填充
+    #[serde(default)]
+    pub unrealized_pnl: Decimal,
+    /// 当日盈亏 (总权益相对当日开盘权益)，估值时填充
+    #[serde(default)]
+    pub day_pnl: Decimal,
+    /// 账户基础货币；为空表示单一货币账户，不做汇率折算。
+    /// 多币种账户的 `available_balance` 与 `frozen_balance` 为基础货币现金，其余金额均折算为基础货币
+    #[serde(default)]
+    pub base_currency: Option<String>,
+    /// 分币种的现金与持仓市值 (含基础货币)，仅多币种账户估值时填充
+    #[serde(default)]
+    pub currencies: Vec<CurrencyBalance>,
+}
+
+/// # Summary
+/// 账户在单一币种上的现金余额与持仓市值。
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CurrencyBalance {
+    /// 币种代码 (ISO 4217)
+    pub currency: String,
+    /// 可用现金 (保证金账户可为负，即融资)
+    pub available_balance: Decimal,
+    /// 冻结现金
+    pub frozen_balance: Decimal,
+    /// 以该币种计价的持仓市值，估值时填充
+    #[serde(default)]
+    pub market_value: Decimal,
+    /// 1 单位该币种折合的基础货币数量，估值时填充
+    #[serde(default)]
+    pub fx_rate: Option<Decimal>,
+    /// 折算为基础货币的权益 (现金 + 冻结 + 持仓市值)，估值时填充
+    #[serde(default)]
+    pub base_equity: Decimal,
+}
+
+/// # Summary
+/// 账户的每日盈亏快照，同一自然日 (UTC) 内以最后一次估值覆盖。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyPnl {
+    pub account_id: AccountId,
+    /// 自然日 (UTC)
+    pub date: chrono::NaiveDate,
+    /// 当日开盘权益：前一记录日的收盘权益，无前序记录时为当日首次估值的权益
+    pub opening_equity: Decimal,
+    /// 最近一次估值的总权益
+    pub total_equity: Decimal,
+    /// 截至当日的累计已实现盈亏
+    pub realized_pnl: Decimal,
+    /// 最近一次估值的未实现盈亏
+    pub unrealized_pnl: Decimal,
+    /// 截至当日的累计交易费用
+    pub total_commission: Decimal,
+    /// 当日盈亏 `total_equity - opening_equity`
+    pub day_pnl: Decimal,
+}
+
+/// # Summary
+/// 订单的一次状态变迁。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderStatusEvent {
+    pub status: OrderStatus,
+    /// 变迁时的累计成交数量
+    pub filled_volume: Decimal,
+    /// 变迁时间戳 (毫秒)
+    pub timestamp: i64,
+}
+
+/// # Summary
+/// 订单历史：订单的最新状态及其完整的状态变迁记录。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderHistoryRecord {
+    pub order: Order,
+    /// 按时间先后排列的状态变迁
+    pub events: Vec<OrderStatusEvent>,
+    /// 最近一次变迁的时间戳 (毫秒)
+    pub updated_at: i64,
+}
+
+/// # Summary
+/// 订单与成交历史的查询条件。
+///
+/// # Invariants
+/// - 时间区间为左闭右开 `[start, end)`，订单按创建时间、成交按成交时间过滤。
+/// - `status` 仅作用于订单查询。
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    pub account_id: AccountId,
+    pub symbol: Option<String>,
+    /// 起始时间戳 (毫秒，含)
+    pub start: Option<i64>,
+    /// 截止时间戳 (毫秒，不含)
+    pub end: Option<i64>,
+    pub status: Option<OrderStatus>,
+    pub strategy_run_id: Option<String>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// # Summary
+/// 历史查询的一页结果，按时间倒序 (最新在前)。
+#[derive(Debug, Clone)]
+pub struct HistoryPage<T> {
+    pub items: Vec<T>,
+    /// 满足条件的总记录数
+    pub total: usize,
+}
+
+impl<T> Default for HistoryPage<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            total: 0,
+        }
+    }
+}
+
+/// # Summary
+/// 资金流水的业务类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LedgerAction {
+    /// 入金
+    Deposit,
+    /// 出金
+    Withdrawal,
+    /// 从同一用户的其他逻辑账号转入
+    TransferIn,
+    /// 转出到同一用户的其他逻辑账号
+    TransferOut,
+    /// 挂单冻结
+    FreezeFunds,
+    /// 撤单解冻
+    UnfreezeFunds,
+    /// 保证金冻结
+    FreezeMargin,
+    /// 成交结算
+    TradeFilled,
+    /// 融券费
+    BorrowFee,
+    /// 拆股调整持仓 (不涉及资金，`reference` 为公司行动标识)
+    Split,
+    /// 现金分红入账 (空头为付出)，`reference` 为公司行动标识
+    Dividend,
+    /// 币种兑换，换出币种与换入币种各记一条，`reference` 为兑换 ID
+    FxConversion,
+}
+
+/// # Summary
+/// 一条资金流水及其发生后的账户余额。
+///
+/// # Invariants
+/// - `available_balance` 与 `frozen_balance` 分别等于截至本条 (含) 的 `asset_change` 与 `frozen_change` 累计值。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// 账户内单调递增的流水号
+    pub id: i64,
+    pub account_id: AccountId,
+    pub action: LedgerAction,
+    /// 可用资金变动
+    pub asset_change: Decimal,
+    /// 冻结资金变动
+    pub frozen_change: Decimal,
+    /// 变动后的可用资金
+    pub available_balance: Decimal,
+    /// 变动后的冻结资金
+    pub frozen_balance: Decimal,
+    /// 关联业务标识：转账为转账 ID
+    pub reference: Option<String>,
+    /// 发生时间戳 (毫秒)
+    pub created_at: i64,
+    /// 资金变动的币种；为空时为账户基础货币，余额亦为该币种的余额
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// # Summary
+/// 两个逻辑账号之间一次转账的双边流水；币种兑换时为同一账号换出与换入两个币种的流水。
+#[derive(Debug, Clone, PartialEq)]
+pub struct CashTransfer {
+    pub transfer_id: String,
+    /// 转出方流水
+    pub outgoing: LedgerEntry,
+    /// 转入方流水
+    pub incoming: LedgerEntry,
 }
 
 /// # Summary
@@ -174,14 +742,40 @@ pub enum AlgoType {
     Twap {
         duration_secs: u64,
         total_volume: Decimal,
+        /// 交易方向，缺省为买入
+        #[serde(default = "default_algo_direction")]
+        direction: OrderDirection,
+    },
+    /// 成交量加权平均价格：在时间窗口内按历史日内成交量分布下单，参与率不超过上限。
+    Vwap {
+        total_volume: Decimal,
+        /// 窗口开始时间 (毫秒级时间戳)
+        start_time: i64,
+        /// 窗口结束时间 (毫秒级时间戳)
+        end_time: i64,
+        /// 最大参与率，子单数量不超过同期成交量的该比例
+        max_participation: Decimal,
+        /// 交易方向，缺省为买入
+        #[serde(default = "default_algo_direction")]
+        direction: OrderDirection,
     },
     /// 狙击单：极速交易，通常用于捕捉瞬时机会。
+    /// 价格到达目标价后以 `target_price ± max_slippage` 挂出保护性限价单。
     Snipe {
         target_price: Decimal,
+        /// 可接受的最大滑点 (绝对价格)
         max_slippage: Decimal,
+        /// 交易方向，缺省为买入
+        #[serde(default = "default_algo_direction")]
+        direction: OrderDirection,
     },
 }
 
+/// 早期的狙击、TWAP 与 VWAP 算法单只支持买入，反序列化旧记录时缺省为买入。
+fn default_algo_direction() -> OrderDirection {
+    OrderDirection::Buy
+}
+
 /// # Summary
 /// 算法单状态。
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -194,6 +788,8 @@ pub enum AlgoOrderStatus {
     Completed,
     /// 已取消
     Canceled,
+    /// 已过期 (执行窗口结束时仍未完成全部数量)
+    Expired,
     /// 失败
     Failed,
 }
@@ -215,7 +811,7 @@ pub struct AlgoOrder {
     pub status: AlgoOrderStatus,
     /// 原始目标数量
     pub requested_volume: Decimal,
-    /// 已成交总量
+    /// 已成交总量；网格单为买入减去卖出后的净持仓
     pub filled_volume: Decimal,
     /// 创建时间
     pub created_at: i64,
@@ -255,3 +851,71 @@ impl AlgoOrder {
         }
     }
 }
+
+/// # Summary
+/// 算法单持久化记录：母单快照、已派生的子单序号及网格档位方向。
+///
+/// # Invariants
+/// - 子单 ID 形如 `{order.id}-{seq}`，`child_seq` 为已使用的最大序号；
+///   恢复后从其后继续编号，避免与重启前已成交的子单重名。
+/// - `grid_sides` 仅网格单非空，按档位自下而上排列。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlgoOrderRecord {
+    /// 母单快照
+    pub order: AlgoOrder,
+    /// 已派生的子单序号
+    pub child_seq: u64,
+    /// 网格各档位下一次挂单的方向
+    #[serde(default)]
+    pub grid_sides: Vec<OrderDirection>,
+}
+
+/// # Summary
+/// 券商回报的订单执行状态快照 (Execution Report)。
+///
+/// # Invariants
+/// - 成交数量、成交均价与手续费均为该订单截至回报时刻的累计值，
+///   重复或乱序到达的回报可按与已结算累计值的差额幂等处理。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    /// 券商侧订单号
+    pub external_order_id: String,
+    /// 券商侧的订单状态
+    pub status: OrderStatus,
+    /// 累计成交数量
+    pub filled_volume: Decimal,
+    /// 累计成交均价，尚无成交时为零
+    pub average_price: Decimal,
+    /// 累计手续费
+    pub commission: Decimal,
+    /// 回报时间戳 (毫秒)
+    pub timestamp: i64,
+    /// 拒绝或撤销原因
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// # Summary
+/// 外发至券商网关的订单持久化记录：系统订单、券商订单号及已结算的累计成交。
+///
+/// # Invariants
+/// - `(account_type, external_order_id)` 唯一对应一笔系统订单。
+/// - 外发前即保存：券商受理前 `external_order_id` 为订单 ID (即客户端订单号 ClOrdID)，
+///   订单状态为 `Pending`；受理后替换为券商返回的订单号。
+/// - `order.filled_volume` 为已结算入账的累计成交数量，`settled_notional` 与
+///   `settled_commission` 为对应的累计成交金额与手续费，新回报只结算超出部分。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokerOrderRecord {
+    /// 路由到的网关 (逻辑交易账号的后端类型)
+    pub account_type: String,
+    /// 券商侧订单号
+    pub external_order_id: String,
+    /// 系统订单快照
+    pub order: Order,
+    /// 买单每单位数量冻结的资金 (含预估手续费与市价单滑点)，卖单为空
+    pub reserved_price: Option<Decimal>,
+    /// 已结算的累计成交金额
+    pub settled_notional: Decimal,
+    /// 已结算的累计手续费
+    pub settled_commission: Decimal,
+}
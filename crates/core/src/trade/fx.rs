@@ -0,0 +1,98 @@
+use super::port::TradeError;
+
+/// 逻辑交易账号配置 (`AccountProfile.config`) 中承载基础货币的键名。
+pub const BASE_CURRENCY_KEY: &str = "base_currency";
+
+/// 标的代码缺少可识别的交易所后缀时的挂牌货币。
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+/// 按交易所后缀推断挂牌货币 (Yahoo 代码风格)。
+const SUFFIX_CURRENCIES: [(&str, &str); 10] = [
+    (".HK", "HKD"),
+    (".SS", "CNY"),
+    (".SZ", "CNY"),
+    (".T", "JPY"),
+    (".L", "GBP"),
+    (".TO", "CAD"),
+    (".AX", "AUD"),
+    (".SI", "SGD"),
+    (".DE", "EUR"),
+    (".PA", "EUR"),
+];
+
+/// # Logic
+/// 规范化币种代码：须为 3 位 ASCII 字母，统一为大写。
+///
+/// # Returns
+/// * `Err(TradeError::InvalidCurrency)` - If the code is not a 3-letter code.
+pub fn normalize_currency(code: &str) -> Result<String, TradeError> {
+    let code = code.trim();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(TradeError::InvalidCurrency(format!(
+            "currency must be a 3-letter code: {}",
+            code
+        )));
+    }
+    Ok(code.to_ascii_uppercase())
+}
+
+/// # Logic
+/// Read the `base_currency` entry of a logical account config; a missing entry
+/// means a single-currency account that settles everything in one cash balance.
+///
+/// # Returns
+/// * `Err(TradeError::InvalidCurrency)` - If the entry is not a 3-letter currency code.
+pub fn base_currency_from_account_config(
+    config: &serde_json::Value,
+) -> Result<Option<String>, TradeError> {
+    match config.get(BASE_CURRENCY_KEY) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(code)) => normalize_currency(code).map(Some),
+        Some(other) => Err(TradeError::InvalidCurrency(format!(
+            "base_currency must be a string: {}",
+            other
+        ))),
+    }
+}
+
+/// 按交易所后缀推断标的的挂牌货币，无可识别后缀时为 `DEFAULT_CURRENCY`。
+pub fn listing_currency(symbol: &str) -> &'static str {
+    let symbol = symbol.to_ascii_uppercase();
+    SUFFIX_CURRENCIES
+        .iter()
+        .find(|(suffix, _)| symbol.ends_with(suffix))
+        .map_or(DEFAULT_CURRENCY, |(_, currency)| currency)
+}
+
+/// 汇率的合成行情代码：其价格为 1 单位 `from` 折合的 `to` 数量 (如 `HKDUSD=X`)。
+pub fn fx_symbol(from: &str, to: &str) -> String {
+    format!("{}{}=X", from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_helpers() -> Result<(), TradeError> {
+        assert_eq!(normalize_currency(" hkd ")?, "HKD");
+        assert!(normalize_currency("HK").is_err());
+        assert_eq!(listing_currency("0700.HK"), "HKD");
+        assert_eq!(listing_currency("AAPL"), "USD");
+        assert_eq!(fx_symbol("HKD", "USD"), "HKDUSD=X");
+
+        let config = serde_json::json!({ "base_currency": "usd" });
+        assert_eq!(
+            base_currency_from_account_config(&config)?,
+            Some("USD".to_string())
+        );
+        assert_eq!(
+            base_currency_from_account_config(&serde_json::json!({}))?,
+            None
+        );
+        assert!(
+            base_currency_from_account_config(&serde_json::json!({ "base_currency": 1 })).is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use super::port::TradeError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// 逻辑交易账号配置 (`AccountProfile.config`) 中承载保证金模型的键名。
+pub const MARGIN_KEY: &str = "margin";
+
+/// 融券费按年化费率计息时的年计息天数。
+pub const BORROW_DAYS_PER_YEAR: i64 = 360;
+
+/// # Summary
+/// 资金账户类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountKind {
+    /// 现金账户：买单全额冻结，卖单不冻结
+    #[default]
+    Cash,
+    /// 保证金账户：多空开仓均按初始保证金率占用购买力，可融资买入与融券卖空
+    Margin,
+}
+
+/// # Summary
+/// 逻辑交易账号的保证金模型。
+///
+/// # Invariants
+/// - 保证金账户满足 `0 < maintenance_margin_rate <= initial_margin_rate <= 1`，
+///   且 `borrow_rate >= 0`，由 `validate` 保证。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarginModel {
+    /// 账户类型
+    pub kind: AccountKind,
+    /// 初始保证金率：开仓名义金额中须由自有权益覆盖的比例
+    pub initial_margin_rate: Decimal,
+    /// 维持保证金率：权益低于 `总敞口 × 维持保证金率` 时触发追保与强制平仓
+    pub maintenance_margin_rate: Decimal,
+    /// 融券年化费率，按空头市值逐日计提
+    pub borrow_rate: Decimal,
+}
+
+impl Default for MarginModel {
+    fn default() -> Self {
+        Self {
+            kind: AccountKind::Cash,
+            initial_margin_rate: Decimal::new(5, 1),
+            maintenance_margin_rate: Decimal::new(25, 2),
+            borrow_rate: Decimal::ZERO,
+        }
+    }
+}
+
+/// # Summary
+/// 按最新行情核算的保证金状态。
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MarginStatus {
+    /// 总敞口：各标的 |持仓数量| × 最新价之和
+    pub gross_exposure: Decimal,
+    /// 空头市值
+    pub short_market_value: Decimal,
+    /// 初始保证金占用
+    pub initial_requirement: Decimal,
+    /// 维持保证金要求
+    pub maintenance_requirement: Decimal,
+    /// 剩余保证金额度：`权益 - 冻结 - 初始保证金占用`，可为负
+    pub excess_margin: Decimal,
+    /// 剩余购买力 (名义金额)：`剩余保证金额度 / 初始保证金率`，不为负
+    pub buying_power: Decimal,
+    /// 权益低于维持保证金要求
+    pub margin_call: bool,
+}
+
+impl MarginModel {
+    /// # Logic
+    /// Read the `margin` entry of a logical account config; a missing entry
+    /// means a cash account.
+    ///
+    /// # Arguments
+    /// * `config` - `AccountProfile.config` of the account.
+    ///
+    /// # Returns
+    /// * `Err(TradeError::InvalidMarginModel)` - If the entry is malformed or the rates are out of range.
+    pub fn from_account_config(config: &serde_json::Value) -> Result<Self, TradeError> {
+        let Some(raw) = config.get(MARGIN_KEY) else {
+            return Ok(Self::default());
+        };
+        let model: Self = serde_json::from_value(raw.clone())
+            .map_err(|e| TradeError::InvalidMarginModel(e.to_string()))?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// 校验保证金率区间与融券费率。
+    pub fn validate(&self) -> Result<(), TradeError> {
+        let invalid = |msg: &str| Err(TradeError::InvalidMarginModel(msg.to_string()));
+        if self.borrow_rate < Decimal::ZERO {
+            return invalid("borrow_rate must not be negative");
+        }
+        if self.maintenance_margin_rate <= Decimal::ZERO {
+            return invalid("maintenance_margin_rate must be positive");
+        }
+        if self.maintenance_margin_rate > self.initial_margin_rate {
+            return invalid("maintenance_margin_rate must not exceed initial_margin_rate");
+        }
+        if self.initial_margin_rate > Decimal::ONE {
+            return invalid("initial_margin_rate must not exceed 1");
+        }
+        Ok(())
+    }
+
+    pub fn is_margin(&self) -> bool {
+        self.kind == AccountKind::Margin
+    }
+
+    /// # Logic
+    /// 以权益、冻结资金与 `(持仓数量, 最新价)` 列表核算保证金状态。
+    pub fn status(
+        &self,
+        equity: Decimal,
+        frozen: Decimal,
+        positions: impl IntoIterator<Item = (Decimal, Decimal)>,
+    ) -> MarginStatus {
+        let mut gross_exposure = Decimal::ZERO;
+        let mut short_market_value = Decimal::ZERO;
+        for (volume, price) in positions {
+            let value = volume.abs() * price;
+            gross_exposure += value;
+            if volume.is_sign_negative() {
+                short_market_value += value;
+            }
+        }
+        let initial_requirement = gross_exposure * self.initial_margin_rate;
+        let maintenance_requirement = gross_exposure * self.maintenance_margin_rate;
+        let excess_margin = equity - frozen - initial_requirement;
+        let buying_power = (excess_margin / self.initial_margin_rate).max(Decimal::ZERO);
+        MarginStatus {
+            gross_exposure,
+            short_market_value,
+            initial_requirement,
+            maintenance_requirement,
+            excess_margin,
+            buying_power,
+            margin_call: gross_exposure > Decimal::ZERO && equity < maintenance_requirement,
+        }
+    }
+
+    /// 空头市值 `short_market_value` 计提 `days` 天的融券费。
+    pub fn borrow_fee(&self, short_market_value: Decimal, days: i64) -> Decimal {
+        short_market_value * self.borrow_rate * Decimal::from(days)
+            / Decimal::from(BORROW_DAYS_PER_YEAR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_margin_model_parses_and_validates() -> Result<(), TradeError> {
+        let config = serde_json::json!({
+            "margin": {
+                "kind": "margin",
+                "initial_margin_rate": "0.5",
+                "maintenance_margin_rate": "0.3",
+                "borrow_rate": "0.036"
+            }
+        });
+        let model = MarginModel::from_account_config(&config)?;
+        assert!(model.is_margin());
+        assert_eq!(model.maintenance_margin_rate, Decimal::new(3, 1));
+        assert!(!MarginModel::from_account_config(&serde_json::json!({}))?.is_margin());
+
+        let inverted = serde_json::json!({
+            "margin": { "kind": "margin", "initial_margin_rate": "0.2", "maintenance_margin_rate": "0.3" }
+        });
+        assert!(matches!(
+            MarginModel::from_account_config(&inverted),
+            Err(TradeError::InvalidMarginModel(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_margin_status_and_borrow_fee() {
+        let model = MarginModel {
+            kind: AccountKind::Margin,
+            borrow_rate: Decimal::new(36, 3),
+            ..MarginModel::default()
+        };
+        // 10000 权益，多头 100 × 100 与空头 50 × 100，总敞口 15000
+        let status = model.status(
+            Decimal::from(10_000),
+            Decimal::from(1_000),
+            [
+                (Decimal::from(100), Decimal::from(100)),
+                (Decimal::from(-50), Decimal::from(100)),
+            ],
+        );
+        assert_eq!(status.gross_exposure, Decimal::from(15_000));
+        assert_eq!(status.short_market_value, Decimal::from(5_000));
+        assert_eq!(status.initial_requirement, Decimal::from(7_500));
+        assert_eq!(status.excess_margin, Decimal::from(1_500));
+        assert_eq!(status.buying_power, Decimal::from(3_000));
+        assert!(!status.margin_call);
+
+        let status = model.status(
+            Decimal::from(3_000),
+            Decimal::ZERO,
+            [(Decimal::from(150), Decimal::from(100))],
+        );
+        assert!(status.margin_call);
+        assert_eq!(status.buying_power, Decimal::ZERO);
+
+        assert_eq!(model.borrow_fee(Decimal::from(5_000), 2), Decimal::ONE);
+    }
+}
@@ -1,2 +1,6 @@
+pub mod cost;
 pub mod entity;
+pub mod fx;
+pub mod margin;
 pub mod port;
+pub mod risk;
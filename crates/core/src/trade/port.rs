@@ -1,8 +1,18 @@
-use super::entity::{AccountId, AccountSnapshot, AlgoOrder, Order, OrderId, Trade};
-use crate::market::entity::Candle;
+use super::entity::{
+    AccountId, AccountSnapshot, AlgoOrder, AlgoOrderRecord, BrokerOrderRecord, CashTransfer,
+    DailyPnl, ExecutionReport, FxSettlement, HistoryPage, HistoryQuery, LedgerEntry, Order,
+    OrderDirection, OrderGroup, OrderHistoryRecord, OrderId, Trade,
+};
+use crate::market::entity::{Candle, CorporateAction};
 use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
 use thiserror::Error;
 
+/// # Summary
+/// 券商执行回报流别名。连接断开时流结束，由调用方负责重连与对账。
+pub type ExecutionReportStream = Pin<Box<dyn Stream<Item = ExecutionReport> + Send>>;
+
 /// # Summary
 /// 交易执行环节中可能发生的错误。
 #[derive(Error, Debug)]
@@ -26,6 +36,22 @@ pub enum TradeError {
     AlgoOrderNotFound(String),
     #[error("algo order protocol error: {0}")]
     AlgoOrderError(String),
+    #[error("invalid cost model: {0}")]
+    InvalidCostModel(String),
+    #[error("invalid margin model: {0}")]
+    InvalidMarginModel(String),
+    #[error("invalid order: {0}")]
+    InvalidOrder(String),
+    #[error("invalid cash movement: {0}")]
+    InvalidCashMovement(String),
+    #[error("invalid corporate action: {0}")]
+    InvalidCorporateAction(String),
+    #[error("invalid currency: {0}")]
+    InvalidCurrency(String),
+    #[error("account is halted: {0}")]
+    AccountHalted(String),
+    #[error("rejected by risk control: {0}")]
+    RiskRejected(#[from] super::risk::RiskViolation),
 }
 
 /// # Summary
@@ -47,6 +73,17 @@ pub trait TradePort: Send + Sync {
     /// * `Err(TradeError)` - 如果资金不足、风控拦截或路由失败
     async fn submit_order(&self, order: Order) -> Result<OrderId, TradeError>;
 
+    /// 提交紧急停止 (kill switch) 的平仓单，不受账号停止状态与事前风控规则限制
+    ///
+    /// # Arguments
+    /// * `order` - 按持仓反向构造的市价平仓单
+    ///
+    /// # Returns
+    /// * `Ok(OrderId)` - 平仓单的追踪 ID
+    async fn submit_flatten_order(&self, order: Order) -> Result<OrderId, TradeError> {
+        self.submit_order(order).await
+    }
+
     /// 撤销一笔尚未完全成交的委托单
     ///
     /// # Arguments
@@ -77,6 +114,139 @@ pub trait TradePort: Send + Sync {
         account_id: AccountId,
         initial_balance: rust_decimal::Decimal,
     ) -> Result<(), TradeError>;
+
+    /// 提交一个订单组 (OCO / 括号单)
+    ///
+    /// # Arguments
+    /// * `group` - 订单组及其子单
+    /// * `entry` - 括号单的入场单，ID 须与 `group.parent_id` 一致；OCO 为空
+    ///
+    /// # Returns
+    /// * `Ok(OrderId)` - 订单组 ID
+    async fn submit_order_group(
+        &self,
+        _group: OrderGroup,
+        _entry: Option<Order>,
+    ) -> Result<OrderId, TradeError> {
+        Err(TradeError::InvalidOrder(
+            "order groups are not supported by this trade port".into(),
+        ))
+    }
+
+    /// 撤销订单组：撤销入场单与全部已激活的子单
+    async fn cancel_order_group(&self, _group_id: &OrderId) -> Result<(), TradeError> {
+        Err(TradeError::InvalidOrder(
+            "order groups are not supported by this trade port".into(),
+        ))
+    }
+
+    /// 查询仍在生效的订单组
+    async fn get_order_group(&self, _group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        Ok(None)
+    }
+
+    /// 查询账户的每日盈亏快照 (按日期升序)
+    async fn get_daily_pnl(&self, _account_id: &AccountId) -> Result<Vec<DailyPnl>, TradeError> {
+        Err(TradeError::InternalError(
+            "daily pnl is not supported by this trade port".into(),
+        ))
+    }
+
+    /// 按条件分页查询订单历史 (含已终结订单及其状态变迁)
+    async fn get_order_history(
+        &self,
+        _query: &HistoryQuery,
+    ) -> Result<HistoryPage<OrderHistoryRecord>, TradeError> {
+        Err(TradeError::InternalError(
+            "order history is not supported by this trade port".into(),
+        ))
+    }
+
+    /// 按条件分页查询成交历史
+    async fn get_trade_history(
+        &self,
+        _query: &HistoryQuery,
+    ) -> Result<HistoryPage<Trade>, TradeError> {
+        Err(TradeError::InternalError(
+            "trade history is not supported by this trade port".into(),
+        ))
+    }
+
+    /// 向账户入金
+    ///
+    /// # Arguments
+    /// * `currency` - 入金币种，为空时为账户基础货币
+    ///
+    /// # Returns
+    /// * `Ok(LedgerEntry)` - 入金流水 (含入金后该币种的余额)
+    async fn deposit(
+        &self,
+        _account_id: &AccountId,
+        _currency: Option<&str>,
+        _amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        Err(TradeError::InvalidCashMovement(
+            "cash movements are not supported by this trade port".into(),
+        ))
+    }
+
+    /// 从账户出金，金额不得超过该币种的可用资金 (保证金账户另不得超过剩余保证金额度)
+    async fn withdraw(
+        &self,
+        _account_id: &AccountId,
+        _currency: Option<&str>,
+        _amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        Err(TradeError::InvalidCashMovement(
+            "cash movements are not supported by this trade port".into(),
+        ))
+    }
+
+    /// 在两个逻辑账号之间划转资金，双边流水同时生效
+    ///
+    /// # Arguments
+    /// * `transfer_id` - 调用方生成的转账 ID，记入双边流水的 `reference`
+    async fn transfer(
+        &self,
+        _from: &AccountId,
+        _to: &AccountId,
+        _currency: Option<&str>,
+        _amount: rust_decimal::Decimal,
+        _transfer_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        Err(TradeError::InvalidCashMovement(
+            "cash movements are not supported by this trade port".into(),
+        ))
+    }
+
+    /// 按最新汇率把账户的 `amount` 单位 `from` 币种现金兑换为 `to` 币种，仅多币种账户可用
+    ///
+    /// # Arguments
+    /// * `conversion_id` - 调用方生成的兑换 ID，记入双边流水的 `reference`
+    async fn convert_currency(
+        &self,
+        _account_id: &AccountId,
+        _from: &str,
+        _to: &str,
+        _amount: rust_decimal::Decimal,
+        _conversion_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        Err(TradeError::InvalidCashMovement(
+            "currency conversion is not supported by this trade port".into(),
+        ))
+    }
+
+    /// 分页查询账户资金流水 (按流水号倒序)
+    async fn get_ledger(
+        &self,
+        _account_id: &AccountId,
+        _offset: usize,
+        _limit: usize,
+    ) -> Result<HistoryPage<LedgerEntry>, TradeError> {
+        Err(TradeError::InternalError(
+            "cash ledger is not supported by this trade port".into(),
+        ))
+    }
 }
 
 /// # Summary
@@ -88,11 +258,34 @@ pub trait PendingOrderPort: Send + Sync {
     async fn get(&self, order_id: &OrderId) -> Result<Option<Order>, TradeError>;
     async fn get_by_account(&self, account_id: &AccountId) -> Result<Vec<Order>, TradeError>;
     async fn get_by_symbol(&self, symbol: &str) -> Result<Vec<Order>, TradeError>;
+    /// 列出当前存在活动订单的全部标的 (去重)，用于实盘撮合循环在重启后恢复订阅。
+    async fn get_symbols(&self) -> Result<Vec<String>, TradeError>;
     async fn update_status(
         &self,
         order_id: &OrderId,
         status: crate::trade::entity::OrderStatus,
     ) -> Result<(), TradeError>;
+    /// 保存 (插入或覆盖) 仍在生效的订单组
+    async fn save_group(&self, group: OrderGroup) -> Result<(), TradeError>;
+    /// 按 ID 读取订单组
+    async fn get_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError>;
+    /// 移除订单组 (组内订单已全部终结或互斥撤销完成)
+    async fn remove_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError>;
+}
+
+/// # Summary
+/// 订单与成交历史的仓储端口。与活动订单仓储不同，终结的订单与全部成交均永久保留。
+#[async_trait]
+pub trait OrderHistoryPort: Send + Sync {
+    /// 写入订单的最新状态；状态或成交数量与上次记录不同时追加一条状态变迁
+    async fn record_order(&self, order: &Order, timestamp: i64) -> Result<(), TradeError>;
+    /// 追加一笔成交
+    async fn record_trade(&self, trade: &Trade) -> Result<(), TradeError>;
+    async fn query_orders(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<OrderHistoryRecord>, TradeError>;
+    async fn query_trades(&self, query: &HistoryQuery) -> Result<HistoryPage<Trade>, TradeError>;
 }
 
 /// # Summary
@@ -105,23 +298,132 @@ pub trait BacktestTradePort: TradePort {
 
 /// # Summary
 /// 针对账户资产的管理服务端口 (Repository / Port)。
+///
+/// # Invariants
+/// - 现金按币种分别记账：`currency` 为空的操作作用于账户基础货币现金 (账户级的已实现盈亏与
+///   费用累计也以基础货币记账)，其余币种各自维护可用与冻结余额。
 #[async_trait]
 pub trait AccountPort: Send + Sync {
-    /// 开仓挂单时，请求冻结预估金额。
+    /// 开仓挂单时，请求冻结该币种的预估金额。
     async fn freeze_funds(
         &self,
         account_id: &AccountId,
+        currency: Option<&str>,
         amount: rust_decimal::Decimal,
     ) -> Result<(), TradeError>;
 
-    /// 撤单时解冻未使用的金额。
+    /// 撤单时解冻该币种未使用的金额。
     async fn unfreeze_funds(
         &self,
         account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<(), TradeError>;
+
+    /// 保证金账户开仓挂单时按购买力冻结保证金。`excess_margin` 为调用方按最新行情核算、
+    /// 并已折算为 `currency` 的剩余保证金额度 (权益 - 冻结 - 初始保证金占用)，
+    /// 冻结后可用现金允许为负 (即融资)。
+    async fn freeze_margin(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+        excess_margin: rust_decimal::Decimal,
+    ) -> Result<(), TradeError>;
+
+    /// 保证金账户融券费已计提至的日期，从未计提时返回 `None`。
+    async fn borrow_fee_accrued_on(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<chrono::NaiveDate>, TradeError>;
+
+    /// 把融券费计提推进至 `accrued_on`：`amount` 为正时从可用现金扣收并记入 `BorrowFee` 流水，
+    /// 扣费与计提日期在同一事务中落地，重启后不会重复或遗漏计提。
+    async fn accrue_borrow_fee(
+        &self,
+        account_id: &AccountId,
+        amount: rust_decimal::Decimal,
+        accrued_on: chrono::NaiveDate,
+    ) -> Result<(), TradeError>;
+
+    /// 入金，记入 `Deposit` 流水。
+    async fn deposit(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError>;
+
+    /// 出金，该币种可用资金不足时返回 `InsufficientFunds`，记入 `Withdrawal` 流水。
+    async fn withdraw(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
         amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError>;
+
+    /// 原子化地从 `from` 划转资金到 `to`：双边的 `TransferOut` / `TransferIn` 流水
+    /// 以同一 `transfer_id` 关联，要么同时生效，要么都不生效。
+    async fn transfer(
+        &self,
+        from: &AccountId,
+        to: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+        transfer_id: &str,
+    ) -> Result<CashTransfer, TradeError>;
+
+    /// 原子化地把 `amount` 单位 `from` 币种现金按 `rate` 兑换为 `to` 币种：
+    /// 双边的 `FxConversion` 流水以同一 `conversion_id` 关联；换出币种可用资金不足时返回 `InsufficientFunds`。
+    async fn convert_currency(
+        &self,
+        account_id: &AccountId,
+        from: Option<&str>,
+        to: Option<&str>,
+        amount: rust_decimal::Decimal,
+        rate: rust_decimal::Decimal,
+        conversion_id: &str,
+    ) -> Result<CashTransfer, TradeError>;
+
+    /// 分页查询资金流水 (按流水号倒序)。
+    async fn list_ledger(
+        &self,
+        account_id: &AccountId,
+        offset: usize,
+        limit: usize,
+    ) -> Result<HistoryPage<LedgerEntry>, TradeError>;
+
+    /// 对持仓应用一项公司行动：拆股按比例调整持仓数量与均价，现金分红按持仓数量计入现金
+    /// 与已实现盈亏 (空头为付出)。以 `CorporateAction::key` 作为流水关联标识保证同一行动只处理一次；
+    /// 未持有该标的、已处理过或除权除息日不晚于该标的处理进度 (如持仓在除权除息日之后才开立) 时
+    /// 返回 `None`。`settlement` 为外币标的的分红币种及折算汇率。
+    async fn apply_corporate_action(
+        &self,
+        account_id: &AccountId,
+        action: &CorporateAction,
+        settlement: Option<&FxSettlement>,
+    ) -> Result<Option<LedgerEntry>, TradeError>;
+
+    /// 该标的公司行动已处理至的时刻，从未处理且未开过仓时返回 `None`。
+    async fn corporate_actions_processed_until(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+    ) -> Result<Option<chrono::DateTime<chrono::Utc>>, TradeError>;
+
+    /// 把该标的的公司行动处理进度推进至 `until`，进度只前进不后退，重启后从此处续处理。
+    async fn mark_corporate_actions_processed(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+        until: chrono::DateTime<chrono::Utc>,
     ) -> Result<(), TradeError>;
 
     /// 行情撮合成功后，交由账户中心进行原子化持仓更新与资金结算。
+    /// `est_req_funds` 为该笔成交对应的冻结资金 (买单全额或保证金委托的初始保证金)，
+    /// 先行解冻，再按成交额与费用结算现金；`trade.settlement` 非空时以其币种结算，
+    /// 已实现盈亏与费用按其汇率折算计入账户累计。
+    /// 空仓开仓时把该标的的公司行动处理进度推进至成交时刻，开仓前除权除息的行动不作用于新持仓。
     async fn process_trade(
         &self,
         account_id: &AccountId,
@@ -132,24 +434,43 @@ pub trait AccountPort: Send + Sync {
     /// 快照截取
     async fn snapshot(&self, account_id: &AccountId) -> Result<AccountSnapshot, TradeError>;
 
+    /// 写入每日盈亏快照，同一日期覆盖
+    async fn save_daily_pnl(&self, record: &DailyPnl) -> Result<(), TradeError>;
+
+    /// 查询账户的每日盈亏快照 (按日期升序)
+    async fn list_daily_pnl(&self, account_id: &AccountId) -> Result<Vec<DailyPnl>, TradeError>;
+
     /// 确保账户已加载或存在
     async fn ensure_account(
         &self,
         account_id: &AccountId,
         initial_balance: rust_decimal::Decimal,
     ) -> Result<(), TradeError>;
+
+    /// 账户中心已知的全部账户 (按账户 ID 升序)
+    async fn list_accounts(&self) -> Result<Vec<AccountId>, TradeError>;
 }
 
 /// # Summary
 /// 本地或远程撮合引擎对接端口。
 pub trait MatcherPort: Send + Sync {
-    /// 估算给定价格与数量下的手续费。
+    /// 估算给定方向、价格与数量下的全部交易费用 (佣金、规费及卖出印花税)。
     fn estimate_commission(
         &self,
+        direction: OrderDirection,
         price: rust_decimal::Decimal,
         volume: rust_decimal::Decimal,
     ) -> rust_decimal::Decimal;
 
+    /// 估算以参考价立即成交时的实际成交价 (含滑点)，用于市价单的资金预冻结。
+    fn estimate_fill_price(
+        &self,
+        _direction: OrderDirection,
+        reference_price: rust_decimal::Decimal,
+    ) -> rust_decimal::Decimal {
+        reference_price
+    }
+
     /// 执行/评估一张尚未完结的订单 (市场价或现价单，与当前 K 线价位对比)。
     fn execute_order(
         &self,
@@ -157,24 +478,66 @@ pub trait MatcherPort: Send + Sync {
         current_price: rust_decimal::Decimal,
         timestamp: i64,
     ) -> Option<Trade>;
+
+    /// 以一根 K 线撮合同一标的的活动挂单：原地更新订单的成交数量、状态
+    /// (未全部成交的为 `PartialFilled`) 与触发状态，并按 K 线内的成交先后返回成交记录。
+    fn execute_bar(&self, symbol: &str, orders: &mut [Order], candle: &Candle) -> Vec<Trade>;
 }
 
 /// # Summary
 /// 物理券商网关对接端口 (Broker Gateway Port)。
 /// 用于定义与外部真实交易所 (IB, Futu, Binance 等) 进行交互的标准接口。
-/// 未来的实盘组件将实现此接口。
+///
+/// # Invariants
+/// - 订单状态与成交只通过执行回报告知调用方；`send_order` 与 `cancel_order` 仅表示请求已被受理。
+/// - `query_order_status` 同时接受订单 ID，使调用方在未拿到券商订单号时 (如外发中途重启) 仍可对账；
+///   从未受理的订单返回 `TradeError::OrderNotFound`。
 #[async_trait]
 pub trait BrokerPort: Send + Sync {
-    /// 向外部网关发送一笔真实物理订单
+    /// 向外部网关发送一笔真实物理订单，返回券商侧订单号
     async fn send_order(&self, order: &Order) -> Result<String, TradeError>;
 
     /// 向外部网关请求取消某笔尚未成交的单子
     async fn cancel_order(&self, external_order_id: &str) -> Result<(), TradeError>;
 
-    /// 主动同步/查询某笔外发订单的最新网关状态 (如是否部分成交)
-    async fn query_order_status(&self, external_order_id: &str) -> Result<(), TradeError>;
+    /// 主动同步/查询某笔外发订单的最新网关状态 (如是否部分成交)，用于重连后的对账
+    async fn query_order_status(
+        &self,
+        external_order_id: &str,
+    ) -> Result<ExecutionReport, TradeError>;
 
-    // TODO: 未来还会增加类似 subscribe_execution_report 的流式回报接口
+    /// 订阅执行回报流；只推送订阅之后发生的回报
+    async fn subscribe_execution_reports(&self) -> Result<ExecutionReportStream, TradeError>;
+}
+
+/// # Summary
+/// 外发订单仓储端口，持久化系统订单与券商订单号的映射，使重启或重连后可以对账。
+#[async_trait]
+pub trait BrokerOrderStore: Send + Sync {
+    /// 保存 (插入或覆盖) 外发订单记录
+    async fn save(&self, record: BrokerOrderRecord) -> Result<(), TradeError>;
+    /// 按系统订单 ID 读取
+    async fn get(&self, order_id: &OrderId) -> Result<Option<BrokerOrderRecord>, TradeError>;
+    /// 按网关与券商订单号读取
+    async fn find_by_external(
+        &self,
+        account_type: &str,
+        external_order_id: &str,
+    ) -> Result<Option<BrokerOrderRecord>, TradeError>;
+    /// 列出全部未终结 (Pending / Submitted / PartialFilled) 的外发订单，用于对账
+    async fn load_open(&self) -> Result<Vec<BrokerOrderRecord>, TradeError>;
+}
+
+/// # Summary
+/// 算法单仓储端口，使运行中的算法单在进程重启后可以恢复。
+#[async_trait]
+pub trait AlgoOrderStore: Send + Sync {
+    /// 保存 (插入或覆盖) 算法单记录
+    async fn save(&self, record: AlgoOrderRecord) -> Result<(), TradeError>;
+    /// 按 ID 读取算法单记录
+    async fn get(&self, order_id: &OrderId) -> Result<Option<AlgoOrderRecord>, TradeError>;
+    /// 列出全部非终态 (Running / Paused) 的算法单，用于启动恢复
+    async fn load_active(&self) -> Result<Vec<AlgoOrderRecord>, TradeError>;
 }
 
 /// # Summary
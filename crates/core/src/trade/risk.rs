@@ -0,0 +1,165 @@
+use super::port::TradeError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// 逻辑交易账号配置 (`AccountProfile.config`) 中承载风控规则的键名。
+pub const RISK_RULES_KEY: &str = "risk_rules";
+
+/// # Summary
+/// 事前风控拒单原因，携带触发拒单的限额与实际值，便于策略与前端结构化处理。
+#[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum RiskViolation {
+    #[error("symbol {symbol} is not allowed for this account")]
+    SymbolNotAllowed { symbol: String },
+    #[error("order notional {notional} exceeds limit {limit}")]
+    OrderNotional { notional: Decimal, limit: Decimal },
+    #[error("position {projected} in {symbol} would exceed limit {limit}")]
+    PositionLimit {
+        symbol: String,
+        projected: Decimal,
+        limit: Decimal,
+    },
+    #[error("gross exposure {projected} would exceed limit {limit}")]
+    GrossExposure { projected: Decimal, limit: Decimal },
+    #[error("order rate limit of {limit} orders per minute reached")]
+    OrderRate { limit: u32 },
+    #[error("daily loss {loss} reached limit {limit}")]
+    DailyLoss { loss: Decimal, limit: Decimal },
+    #[error("selling {volume} {symbol} exceeds sellable position {sellable}")]
+    ShortSell {
+        symbol: String,
+        volume: Decimal,
+        sellable: Decimal,
+    },
+}
+
+/// # Summary
+/// 逻辑交易账号的事前风控规则。所有限额均为可选，缺省即不限制。
+///
+/// # Invariants
+/// - 所有金额与数量限额均为正，由 `validate` 保证。
+/// - `denied_symbols` 优先于 `allowed_symbols`。
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskRules {
+    /// 单笔委托名义金额上限 (价格 × 数量)
+    pub max_order_notional: Option<Decimal>,
+    /// 单一标的持仓数量上限 (绝对值，计入同向在途委托)
+    pub max_position_per_symbol: Option<Decimal>,
+    /// 总敞口上限：各标的 |持仓 + 在途委托| × 参考价之和
+    pub max_gross_exposure: Option<Decimal>,
+    /// 每分钟 (滑动窗口) 最多受理的委托笔数
+    pub max_orders_per_minute: Option<u32>,
+    /// 当日已实现亏损 (含费用) 上限，达到后仅允许减仓委托
+    pub daily_loss_limit: Option<Decimal>,
+    /// 禁止卖空：卖出数量不得超过持仓减去在途卖单
+    pub no_short: bool,
+    /// 标的白名单；为空表示不限制
+    pub allowed_symbols: Option<Vec<String>>,
+    /// 标的黑名单
+    pub denied_symbols: Vec<String>,
+}
+
+impl RiskRules {
+    /// # Logic
+    /// Read the `risk_rules` entry of a logical account config; a missing entry
+    /// means no pre-trade limits.
+    ///
+    /// # Arguments
+    /// * `config` - `AccountProfile.config` of the account.
+    ///
+    /// # Returns
+    /// * `Err(TradeError::InvalidOrder)` - If the entry is malformed or has non-positive limits.
+    pub fn from_account_config(config: &serde_json::Value) -> Result<Self, TradeError> {
+        let Some(raw) = config.get(RISK_RULES_KEY) else {
+            return Ok(Self::default());
+        };
+        let rules: Self = serde_json::from_value(raw.clone())
+            .map_err(|e| TradeError::InvalidOrder(format!("invalid risk rules: {}", e)))?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// 校验所有限额为正。
+    pub fn validate(&self) -> Result<(), TradeError> {
+        let limits = [
+            ("max_order_notional", self.max_order_notional),
+            ("max_position_per_symbol", self.max_position_per_symbol),
+            ("max_gross_exposure", self.max_gross_exposure),
+            ("daily_loss_limit", self.daily_loss_limit),
+            (
+                "max_orders_per_minute",
+                self.max_orders_per_minute.map(Decimal::from),
+            ),
+        ];
+        match limits
+            .iter()
+            .find(|(_, limit)| limit.is_some_and(|l| l <= Decimal::ZERO))
+        {
+            Some((name, _)) => Err(TradeError::InvalidOrder(format!(
+                "invalid risk rules: {} must be positive",
+                name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// 黑名单优先；白名单存在时标的必须在其中。
+    pub fn check_symbol(&self, symbol: &str) -> Result<(), RiskViolation> {
+        let denied = self.denied_symbols.iter().any(|s| s == symbol);
+        let allowed = self
+            .allowed_symbols
+            .as_ref()
+            .is_none_or(|list| list.iter().any(|s| s == symbol));
+        if denied || !allowed {
+            return Err(RiskViolation::SymbolNotAllowed {
+                symbol: symbol.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_risk_rules_parse_from_account_config() -> Result<(), TradeError> {
+        let config = serde_json::json!({
+            "risk_rules": {
+                "max_order_notional": "5000",
+                "max_orders_per_minute": 10,
+                "no_short": true,
+                "allowed_symbols": ["AAPL", "MSFT"],
+                "denied_symbols": ["MSFT"]
+            }
+        });
+        let rules = RiskRules::from_account_config(&config)?;
+        assert_eq!(rules.max_order_notional, Some(Decimal::from(5000)));
+        assert!(rules.no_short);
+        assert!(rules.check_symbol("AAPL").is_ok());
+        assert!(matches!(
+            rules.check_symbol("MSFT"),
+            Err(RiskViolation::SymbolNotAllowed { .. })
+        ));
+        assert!(rules.check_symbol("TSLA").is_err());
+
+        assert_eq!(
+            RiskRules::from_account_config(&serde_json::json!({}))?,
+            RiskRules::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_risk_rules_reject_non_positive_limits() {
+        let config = serde_json::json!({ "risk_rules": { "max_orders_per_minute": 0 } });
+        assert!(matches!(
+            RiskRules::from_account_config(&config),
+            Err(TradeError::InvalidOrder(_))
+        ));
+    }
+}
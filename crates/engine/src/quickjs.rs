@@ -8,8 +8,11 @@ use okane_core::market::entity::Candle;
 use okane_core::market::error::MarketError;
 use okane_core::market::indicator::IndicatorService;
 use okane_core::market::port::Market;
-use okane_core::trade::entity::{AccountId, AlgoOrder, AlgoType, OrderId};
-use okane_core::trade::port::{AlgoOrderPort, TradePort};
+use okane_core::trade::entity::{
+    AccountId, AlgoOrder, AlgoType, Order, OrderDirection, OrderGroup, OrderId, OrderTrigger,
+    TrailOffset,
+};
+use okane_core::trade::port::{AlgoOrderPort, TradeError, TradePort};
 use rquickjs::{AsyncContext, AsyncRuntime, Function, Object, Value, async_with};
 use rust_decimal::Decimal;
 use std::sync::{Arc, Mutex};
@@ -346,6 +349,7 @@ impl JsEngine {
                       -> Result<String, rquickjs::Error> {
                     let ctx_mutex = ctx_for_buy.lock().map_err(|_| rquickjs::Error::Exception)?;
                     let trade_port = ctx_mutex.trade_port.clone();
+                    let logger = ctx_mutex.logger.clone();
                     let account_id = ctx_mutex.account_id.clone();
                     drop(ctx_mutex);
 
@@ -374,7 +378,7 @@ impl JsEngine {
                         trade_port
                             .submit_order(order)
                             .await
-                            .map_err(|e| e.to_string())
+                            .map_err(|e| JsEngine::report_trade_error(logger.as_deref(), e))
                     }) {
                         Ok(Ok(oid)) => Ok(oid.0),
                         Ok(Err(e)) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
@@ -401,6 +405,7 @@ impl JsEngine {
                         .lock()
                         .map_err(|_| rquickjs::Error::Exception)?;
                     let trade_port = ctx_mutex.trade_port.clone();
+                    let logger = ctx_mutex.logger.clone();
                     let account_id = ctx_mutex.account_id.clone();
                     drop(ctx_mutex);
 
@@ -429,7 +434,82 @@ impl JsEngine {
                         trade_port
                             .submit_order(order)
                             .await
-                            .map_err(|e| e.to_string())
+                            .map_err(|e| JsEngine::report_trade_error(logger.as_deref(), e))
+                    }) {
+                        Ok(Ok(oid)) => Ok(oid.0),
+                        Ok(Err(e)) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                        Err(e) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                    }
+                },
+            )
+            .map_err(|_| EngineError::Plugin("log setup failed".to_string()))?,
+        )
+        .map_err(|_| EngineError::Plugin("log set failed".to_string()))?;
+
+        // host.stopOrder(symbol: string, direction: string, volume: string, params: object)
+        //   -> string (OrderId | Error)
+        // params: stop_price | trail_amount | trail_percent (三选一)，可选 limit_price 构成止损限价单
+        let ctx_for_stop = plugin_ctx.clone();
+        let bridge_for_stop = bridge.clone();
+        host.set(
+            "stopOrder",
+            Function::new(
+                ctx.clone(),
+                move |symbol: String,
+                      direction: String,
+                      volume: String,
+                      params: Object|
+                      -> Result<String, rquickjs::Error> {
+                    let ctx_mutex = ctx_for_stop
+                        .lock()
+                        .map_err(|_| rquickjs::Error::Exception)?;
+                    let trade_port = ctx_mutex.trade_port.clone();
+                    let logger = ctx_mutex.logger.clone();
+                    let account_id = ctx_mutex.account_id.clone();
+                    drop(ctx_mutex);
+
+                    let direction = JsEngine::parse_direction(&direction)?;
+                    let req_vol = volume
+                        .parse::<Decimal>()
+                        .map_err(|_| rquickjs::Error::Exception)?;
+                    let decimal_param = |key: &str| JsEngine::decimal_param(&params, key);
+                    let limit_price = decimal_param("limit_price")?;
+                    let trigger = match (
+                        decimal_param("stop_price")?,
+                        decimal_param("trail_amount")?,
+                        decimal_param("trail_percent")?,
+                    ) {
+                        (Some(stop), None, None) => OrderTrigger::stop(stop),
+                        (None, Some(amount), None) => {
+                            OrderTrigger::trailing(TrailOffset::Amount(amount))
+                        }
+                        (None, None, Some(percent)) => {
+                            OrderTrigger::trailing(TrailOffset::Percent(percent))
+                        }
+                        _ => {
+                            return Ok(serde_json::json!({
+                                "error": "exactly one of stop_price, trail_amount and trail_percent is required"
+                            })
+                            .to_string());
+                        }
+                    };
+
+                    let order = okane_core::trade::entity::Order::new(
+                        OrderId(uuid::Uuid::new_v4().to_string()),
+                        AccountId(account_id),
+                        symbol,
+                        direction,
+                        limit_price,
+                        req_vol,
+                        0,
+                    )
+                    .with_trigger(trigger);
+
+                    match bridge_for_stop.call(async move {
+                        trade_port
+                            .submit_order(order)
+                            .await
+                            .map_err(|e| JsEngine::report_trade_error(logger.as_deref(), e))
                     }) {
                         Ok(Ok(oid)) => Ok(oid.0),
                         Ok(Err(e)) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
@@ -536,6 +616,215 @@ impl JsEngine {
         )
         .map_err(|_| EngineError::Plugin("log set failed".to_string()))?;
 
+        // host.submitOco(symbol: string, direction: string, volume: string, params: object)
+        //   -> string (GroupId | Error)
+        // params: limit_price 与 stop_price 构成一对互斥子单，可选 stop_limit_price 使止损腿为止损限价单
+        let ctx_for_oco = plugin_ctx.clone();
+        let bridge_for_oco = bridge.clone();
+        host.set(
+            "submitOco",
+            Function::new(
+                ctx.clone(),
+                move |symbol: String,
+                      direction: String,
+                      volume: String,
+                      params: Object|
+                      -> Result<String, rquickjs::Error> {
+                    let ctx_mutex = ctx_for_oco.lock().map_err(|_| rquickjs::Error::Exception)?;
+                    let trade_port = ctx_mutex.trade_port.clone();
+                    let logger = ctx_mutex.logger.clone();
+                    let account_id = AccountId(ctx_mutex.account_id.clone());
+                    drop(ctx_mutex);
+
+                    let direction = JsEngine::parse_direction(&direction)?;
+                    let req_vol = volume
+                        .parse::<Decimal>()
+                        .map_err(|_| rquickjs::Error::Exception)?;
+                    let (Some(limit_price), Some(stop_price)) = (
+                        JsEngine::decimal_param(&params, "limit_price")?,
+                        JsEngine::decimal_param(&params, "stop_price")?,
+                    ) else {
+                        return Ok(serde_json::json!({
+                            "error": "limit_price and stop_price are required"
+                        })
+                        .to_string());
+                    };
+                    let stop_limit_price = JsEngine::decimal_param(&params, "stop_limit_price")?;
+
+                    let new_order = |price: Option<Decimal>| {
+                        Order::new(
+                            OrderId(uuid::Uuid::new_v4().to_string()),
+                            account_id.clone(),
+                            symbol.clone(),
+                            direction,
+                            price,
+                            req_vol,
+                            0,
+                        )
+                    };
+                    let group = OrderGroup::oco(
+                        OrderId(uuid::Uuid::new_v4().to_string()),
+                        new_order(Some(limit_price)),
+                        new_order(stop_limit_price).with_trigger(OrderTrigger::stop(stop_price)),
+                        0,
+                    );
+
+                    match bridge_for_oco.call(async move {
+                        trade_port
+                            .submit_order_group(group, None)
+                            .await
+                            .map_err(|e| JsEngine::report_trade_error(logger.as_deref(), e))
+                    }) {
+                        Ok(Ok(gid)) => Ok(gid.0),
+                        Ok(Err(e)) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                        Err(e) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                    }
+                },
+            )
+            .map_err(|_| EngineError::Plugin("log setup failed".to_string()))?,
+        )
+        .map_err(|_| EngineError::Plugin("log set failed".to_string()))?;
+
+        // host.submitBracket(symbol: string, direction: string, volume: string, params: object)
+        //   -> string (GroupId | Error)
+        // params: 可选 price (入场限价)，take_profit 与 stop_loss 为入场单成交后激活的反向止盈、止损价
+        let ctx_for_bracket = plugin_ctx.clone();
+        let bridge_for_bracket = bridge.clone();
+        host.set(
+            "submitBracket",
+            Function::new(
+                ctx.clone(),
+                move |symbol: String,
+                      direction: String,
+                      volume: String,
+                      params: Object|
+                      -> Result<String, rquickjs::Error> {
+                    let ctx_mutex = ctx_for_bracket
+                        .lock()
+                        .map_err(|_| rquickjs::Error::Exception)?;
+                    let trade_port = ctx_mutex.trade_port.clone();
+                    let logger = ctx_mutex.logger.clone();
+                    let account_id = AccountId(ctx_mutex.account_id.clone());
+                    drop(ctx_mutex);
+
+                    let direction = JsEngine::parse_direction(&direction)?;
+                    let exit_direction = match direction {
+                        OrderDirection::Buy => OrderDirection::Sell,
+                        OrderDirection::Sell => OrderDirection::Buy,
+                    };
+                    let req_vol = volume
+                        .parse::<Decimal>()
+                        .map_err(|_| rquickjs::Error::Exception)?;
+                    let (Some(take_profit), Some(stop_loss)) = (
+                        JsEngine::decimal_param(&params, "take_profit")?,
+                        JsEngine::decimal_param(&params, "stop_loss")?,
+                    ) else {
+                        return Ok(serde_json::json!({
+                            "error": "take_profit and stop_loss are required"
+                        })
+                        .to_string());
+                    };
+
+                    let new_order = |direction, price: Option<Decimal>| {
+                        Order::new(
+                            OrderId(uuid::Uuid::new_v4().to_string()),
+                            account_id.clone(),
+                            symbol.clone(),
+                            direction,
+                            price,
+                            req_vol,
+                            0,
+                        )
+                    };
+                    let entry = new_order(direction, JsEngine::decimal_param(&params, "price")?);
+                    let group = OrderGroup::bracket(
+                        OrderId(uuid::Uuid::new_v4().to_string()),
+                        &entry,
+                        new_order(exit_direction, Some(take_profit)),
+                        new_order(exit_direction, None).with_trigger(OrderTrigger::stop(stop_loss)),
+                        0,
+                    );
+
+                    match bridge_for_bracket.call(async move {
+                        trade_port
+                            .submit_order_group(group, Some(entry))
+                            .await
+                            .map_err(|e| JsEngine::report_trade_error(logger.as_deref(), e))
+                    }) {
+                        Ok(Ok(gid)) => Ok(gid.0),
+                        Ok(Err(e)) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                        Err(e) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                    }
+                },
+            )
+            .map_err(|_| EngineError::Plugin("log setup failed".to_string()))?,
+        )
+        .map_err(|_| EngineError::Plugin("log set failed".to_string()))?;
+
+        // host.getOrderGroup(groupId: string) -> string (JSON OrderGroup | null)
+        let ctx_for_get_group = plugin_ctx.clone();
+        let bridge_for_get_group = bridge.clone();
+        host.set(
+            "getOrderGroup",
+            Function::new(
+                ctx.clone(),
+                move |group_id: String| -> Result<String, rquickjs::Error> {
+                    let ctx_mutex = ctx_for_get_group
+                        .lock()
+                        .map_err(|_| rquickjs::Error::Exception)?;
+                    let trade_port = ctx_mutex.trade_port.clone();
+                    drop(ctx_mutex);
+
+                    match bridge_for_get_group.call(async move {
+                        trade_port
+                            .get_order_group(&OrderId(group_id))
+                            .await
+                            .map_err(|e| e.to_string())
+                    }) {
+                        Ok(Ok(Some(group))) => Ok(serde_json::to_string(&group)
+                            .map_err(|_| rquickjs::Error::Exception)?),
+                        Ok(Ok(None)) => Ok("null".to_string()),
+                        Ok(Err(e)) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                        Err(e) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                    }
+                },
+            )
+            .map_err(|_| EngineError::Plugin("log setup failed".to_string()))?,
+        )
+        .map_err(|_| EngineError::Plugin("log set failed".to_string()))?;
+
+        // host.cancelOrderGroup(groupId: string) -> string ("ok" | error)
+        let ctx_for_cancel_group = plugin_ctx.clone();
+        let bridge_for_cancel_group = bridge.clone();
+        host.set(
+            "cancelOrderGroup",
+            Function::new(
+                ctx.clone(),
+                move |group_id: String| -> Result<String, rquickjs::Error> {
+                    let ctx_mutex = ctx_for_cancel_group
+                        .lock()
+                        .map_err(|_| rquickjs::Error::Exception)?;
+                    let trade_port = ctx_mutex.trade_port.clone();
+                    drop(ctx_mutex);
+
+                    info!("host.cancelOrderGroup: groupId={}", group_id);
+
+                    match bridge_for_cancel_group.call(async move {
+                        trade_port
+                            .cancel_order_group(&OrderId(group_id))
+                            .await
+                            .map_err(|e| e.to_string())
+                    }) {
+                        Ok(Ok(())) => Ok("ok".to_string()),
+                        Ok(Err(e)) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                        Err(e) => Ok(serde_json::json!({"error": e.to_string()}).to_string()),
+                    }
+                },
+            )
+            .map_err(|_| EngineError::Plugin("log setup failed".to_string()))?,
+        )
+        .map_err(|_| EngineError::Plugin("log set failed".to_string()))?;
+
         // host.submitAlgoOrder(type: string, params: object) -> string (OrderId | Error)
         let ctx_for_algo = plugin_ctx.clone();
         let bridge_for_algo = bridge.clone();
@@ -559,17 +848,101 @@ impl JsEngine {
                         .timestamp_millis();
                     drop(ctx_mutex);
 
+                    let volume: Decimal = params
+                        .get::<_, String>("volume")
+                        .map_err(|_| rquickjs::Error::Exception)?
+                        .parse()
+                        .map_err(|_| rquickjs::Error::Exception)?;
+
                     // 根据类型解析参数
                     let algo = match algo_type.as_str() {
                         "snipe" => {
                             let target: String = params
                                 .get("target_price")
                                 .map_err(|_| rquickjs::Error::Exception)?;
+                            let slippage: Option<String> = params
+                                .get("max_slippage")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            let direction: Option<String> = params
+                                .get("direction")
+                                .map_err(|_| rquickjs::Error::Exception)?;
                             AlgoType::Snipe {
                                 target_price: target
                                     .parse()
                                     .map_err(|_| rquickjs::Error::Exception)?,
-                                max_slippage: Decimal::ZERO,
+                                max_slippage: match slippage {
+                                    Some(s) => s.parse().map_err(|_| rquickjs::Error::Exception)?,
+                                    None => Decimal::ZERO,
+                                },
+                                direction: match direction.as_deref() {
+                                    None | Some("buy") => OrderDirection::Buy,
+                                    Some("sell") => OrderDirection::Sell,
+                                    Some(_) => return Err(rquickjs::Error::Exception),
+                                },
+                            }
+                        }
+                        "grid" => {
+                            let upper: String = params
+                                .get("upper_price")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            let lower: String = params
+                                .get("lower_price")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            let grids: u32 = params
+                                .get("grids")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            AlgoType::Grid {
+                                upper_price: upper
+                                    .parse()
+                                    .map_err(|_| rquickjs::Error::Exception)?,
+                                lower_price: lower
+                                    .parse()
+                                    .map_err(|_| rquickjs::Error::Exception)?,
+                                grids,
+                            }
+                        }
+                        "twap" => {
+                            let duration_secs: u32 = params
+                                .get("duration_secs")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            let direction: Option<String> = params
+                                .get("direction")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            AlgoType::Twap {
+                                duration_secs: u64::from(duration_secs),
+                                total_volume: volume,
+                                direction: match direction.as_deref() {
+                                    None | Some("buy") => OrderDirection::Buy,
+                                    Some("sell") => OrderDirection::Sell,
+                                    Some(_) => return Err(rquickjs::Error::Exception),
+                                },
+                            }
+                        }
+                        "vwap" => {
+                            let start_time: i64 = params
+                                .get("start_time")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            let end_time: i64 = params
+                                .get("end_time")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            let participation: String = params
+                                .get("max_participation")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            let direction: Option<String> = params
+                                .get("direction")
+                                .map_err(|_| rquickjs::Error::Exception)?;
+                            AlgoType::Vwap {
+                                total_volume: volume,
+                                start_time,
+                                end_time,
+                                max_participation: participation
+                                    .parse()
+                                    .map_err(|_| rquickjs::Error::Exception)?,
+                                direction: match direction.as_deref() {
+                                    None | Some("buy") => OrderDirection::Buy,
+                                    Some("sell") => OrderDirection::Sell,
+                                    Some(_) => return Err(rquickjs::Error::Exception),
+                                },
                             }
                         }
                         _ => {
@@ -578,16 +951,12 @@ impl JsEngine {
                             );
                         }
                     };
-                    let volume: String = params
-                        .get("volume")
-                        .map_err(|_| rquickjs::Error::Exception)?;
-
                     let order = AlgoOrder::new(
                         OrderId(uuid::Uuid::new_v4().to_string()),
                         AccountId(account_id),
                         symbol,
                         algo,
-                        volume.parse().map_err(|_| rquickjs::Error::Exception)?,
+                        volume,
                         now_ms,
                     );
 
@@ -650,6 +1019,41 @@ impl JsEngine {
         Ok(())
     }
 
+    /// 将下单失败转为返回给策略的错误文本；风控拒单同时写入策略日志。
+    fn report_trade_error(
+        logger: Option<&dyn okane_core::strategy::port::StrategyLogger>,
+        error: TradeError,
+    ) -> String {
+        if let TradeError::RiskRejected(violation) = &error {
+            warn!("order rejected by risk control: {}", violation);
+            if let Some(logger) = logger {
+                logger.log(
+                    okane_core::strategy::entity::LogLevel::Warn,
+                    format!("order rejected by risk control: {}", violation),
+                );
+            }
+        }
+        error.to_string()
+    }
+
+    /// 解析 `"buy"` / `"sell"` 方向参数。
+    fn parse_direction(direction: &str) -> Result<OrderDirection, rquickjs::Error> {
+        match direction {
+            "buy" => Ok(OrderDirection::Buy),
+            "sell" => Ok(OrderDirection::Sell),
+            _ => Err(rquickjs::Error::Exception),
+        }
+    }
+
+    /// 读取参数对象中可选的十进制字符串字段。
+    fn decimal_param(params: &Object<'_>, key: &str) -> Result<Option<Decimal>, rquickjs::Error> {
+        let value: Option<String> = params.get(key).map_err(|_| rquickjs::Error::Exception)?;
+        value
+            .map(|v| v.parse::<Decimal>())
+            .transpose()
+            .map_err(|_| rquickjs::Error::Exception)
+    }
+
     /// # Summary
     /// 调用 JS 的 onCandle 函数。
     ///
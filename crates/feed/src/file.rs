@@ -0,0 +1,439 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use parquet::file::reader::SerializedFileReader;
+use parquet::record::Field;
+use rust_decimal::Decimal;
+use tokio::sync::mpsc;
+
+use okane_core::{
+    common::{Stock, TimeFrame},
+    config::{ColumnMapping, FileProviderConfig},
+    market::{
+        entity::Candle,
+        error::MarketError,
+        port::{CandleStream, MarketDataProvider},
+    },
+    store::port::StockMetadata,
+    trade::fx::listing_currency,
+};
+
+/// Timeframes with a data sub-directory, finest first.
+const TIMEFRAMES: [TimeFrame; 4] = [
+    TimeFrame::Minute1,
+    TimeFrame::Minute5,
+    TimeFrame::Hour1,
+    TimeFrame::Day1,
+];
+
+/// Exchange name reported for symbols served from files.
+const FILE_EXCHANGE: &str = "FILE";
+
+/// Naive datetime layouts accepted in the time column, interpreted in the configured timezone.
+const NAIVE_DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+];
+
+/// Market data provider reading OHLCV bars from local CSV and Parquet files.
+///
+/// Files are laid out as `<dir>/<timeframe>/<SYMBOL>.csv` or `<dir>/<timeframe>/<SYMBOL>.parquet`,
+/// where `<timeframe>` is one of `1m`, `5m`, `1h` and `1d`. Column names come from the
+/// configured `ColumnMapping`.
+///
+/// # Invariants
+/// - Symbols are matched against file names case-insensitively.
+/// - Bars are returned in ascending time order; rows sharing a timestamp keep the first one.
+#[derive(Debug, Clone)]
+pub struct FileProvider {
+    root: PathBuf,
+    columns: ColumnMapping,
+    timezone: Tz,
+    replay_speed: f64,
+}
+
+impl FileProvider {
+    /// Creates a new `FileProvider` from its configuration.
+    ///
+    /// # Returns
+    /// Returns `MarketError::Parse` if the timezone is not a valid IANA name.
+    pub fn new(config: &FileProviderConfig) -> Result<Self, MarketError> {
+        let timezone = Tz::from_str(&config.timezone).map_err(|e| {
+            MarketError::Parse(format!("invalid timezone {}: {}", config.timezone, e))
+        })?;
+        Ok(Self {
+            root: PathBuf::from(&config.dir),
+            columns: config.columns.clone(),
+            timezone,
+            replay_speed: config.replay_speed,
+        })
+    }
+
+    /// Reads every bar of a symbol at the given timeframe.
+    ///
+    /// # Logic
+    /// 1. Locates `<dir>/<timeframe>/<symbol>.csv|parquet`, preferring CSV when both exist.
+    /// 2. Parses all rows with the configured column mapping and timezone.
+    /// 3. Sorts the bars by time and drops duplicated timestamps.
+    ///
+    /// # Returns
+    /// Returns `MarketError::NotFound` if no file exists, or `MarketError::Parse` on a malformed row.
+    pub fn load(&self, symbol: &str, timeframe: TimeFrame) -> Result<Vec<Candle>, MarketError> {
+        let path = self
+            .locate(symbol, timeframe)
+            .ok_or(MarketError::NotFound)?;
+        let mut candles = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("parquet") => self.read_parquet(&path)?,
+            _ => self.read_csv(&path)?,
+        };
+        candles.sort_by_key(|c| c.time);
+        candles.dedup_by_key(|c| c.time);
+        Ok(candles)
+    }
+
+    /// Finds the data file of a symbol, matching the file stem case-insensitively.
+    fn locate(&self, symbol: &str, timeframe: TimeFrame) -> Option<PathBuf> {
+        let files = data_files(&self.root.join(timeframe.to_string()));
+        ["csv", "parquet"].into_iter().find_map(|wanted| {
+            files
+                .iter()
+                .find(|(stem, ext, _)| ext == wanted && stem.eq_ignore_ascii_case(symbol))
+                .map(|(_, _, path)| path.clone())
+        })
+    }
+
+    fn read_csv(&self, path: &Path) -> Result<Vec<Candle>, MarketError> {
+        let mut reader = csv::Reader::from_path(path)
+            .map_err(|e| MarketError::Parse(format!("failed to open {}: {}", path.display(), e)))?;
+        let headers = reader
+            .headers()
+            .map_err(|e| MarketError::Parse(format!("failed to read csv header: {}", e)))?
+            .clone();
+        let index = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h.trim() == name)
+                .or_else(|| {
+                    headers
+                        .iter()
+                        .position(|h| h.trim().eq_ignore_ascii_case(name))
+                })
+                .ok_or_else(|| {
+                    MarketError::Parse(format!("column {} missing in {}", name, path.display()))
+                })
+        };
+        let time = index(&self.columns.time)?;
+        let open = index(&self.columns.open)?;
+        let high = index(&self.columns.high)?;
+        let low = index(&self.columns.low)?;
+        let close = index(&self.columns.close)?;
+        let volume = index(&self.columns.volume)?;
+        let adj_close = self.columns.adj_close.as_deref().map(index).transpose()?;
+
+        let mut candles = Vec::new();
+        for (row, record) in reader.records().enumerate() {
+            let record = record.map_err(|e| MarketError::Parse(format!("bad csv row: {}", e)))?;
+            let cell = |i: usize| {
+                record.get(i).map(str::trim).ok_or_else(|| {
+                    MarketError::Parse(format!("csv row {} has too few columns", row + 1))
+                })
+            };
+            candles.push(Candle {
+                time: self.parse_time(cell(time)?)?,
+                open: parse_decimal(cell(open)?)?,
+                high: parse_decimal(cell(high)?)?,
+                low: parse_decimal(cell(low)?)?,
+                close: parse_decimal(cell(close)?)?,
+                adj_close: adj_close.map(|i| parse_decimal(cell(i)?)).transpose()?,
+                volume: parse_decimal(cell(volume)?)?,
+                is_final: true,
+            });
+        }
+        Ok(candles)
+    }
+
+    fn read_parquet(&self, path: &Path) -> Result<Vec<Candle>, MarketError> {
+        let file = File::open(path)
+            .map_err(|e| MarketError::Parse(format!("failed to open {}: {}", path.display(), e)))?;
+        let reader = SerializedFileReader::new(file)
+            .map_err(|e| MarketError::Parse(format!("failed to read parquet: {}", e)))?;
+
+        let mut candles = Vec::new();
+        for row in reader {
+            let row = row.map_err(|e| MarketError::Parse(format!("bad parquet row: {}", e)))?;
+            let field = |name: &str| {
+                row.get_column_iter()
+                    .find(|(column, _)| column.as_str() == name)
+                    .map(|(_, field)| field)
+                    .ok_or_else(|| {
+                        MarketError::Parse(format!("column {} missing in {}", name, path.display()))
+                    })
+            };
+            candles.push(Candle {
+                time: self.field_time(field(&self.columns.time)?)?,
+                open: field_decimal(field(&self.columns.open)?)?,
+                high: field_decimal(field(&self.columns.high)?)?,
+                low: field_decimal(field(&self.columns.low)?)?,
+                close: field_decimal(field(&self.columns.close)?)?,
+                adj_close: match self.columns.adj_close.as_deref() {
+                    Some(name) => match field(name)? {
+                        Field::Null => None,
+                        value => Some(field_decimal(value)?),
+                    },
+                    None => None,
+                },
+                volume: field_decimal(field(&self.columns.volume)?)?,
+                is_final: true,
+            });
+        }
+        Ok(candles)
+    }
+
+    /// Parses a textual timestamp.
+    ///
+    /// # Logic
+    /// 1. Integers are Unix epochs, in seconds, milliseconds, microseconds or nanoseconds by magnitude.
+    /// 2. RFC 3339 timestamps carry their own offset.
+    /// 3. Naive datetimes and bare dates (midnight) are interpreted in the configured timezone.
+    fn parse_time(&self, value: &str) -> Result<DateTime<Utc>, MarketError> {
+        if let Ok(epoch) = value.parse::<i64>() {
+            return epoch_time(epoch);
+        }
+        if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+            return Ok(time.with_timezone(&Utc));
+        }
+        if let Some(naive) = NAIVE_DATETIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        {
+            return self.localize(naive);
+        }
+        match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => self.localize(date.and_time(chrono::NaiveTime::MIN)),
+            Err(_) => Err(MarketError::Parse(format!("unrecognized time {}", value))),
+        }
+    }
+
+    fn field_time(&self, field: &Field) -> Result<DateTime<Utc>, MarketError> {
+        match field {
+            Field::Str(value) => self.parse_time(value.trim()),
+            Field::Long(epoch) => epoch_time(*epoch),
+            Field::Int(epoch) => epoch_time(i64::from(*epoch)),
+            Field::TimestampMillis(ms) => DateTime::from_timestamp_millis(*ms)
+                .ok_or_else(|| MarketError::Parse(format!("timestamp {} out of range", ms))),
+            Field::TimestampMicros(us) => DateTime::from_timestamp_micros(*us)
+                .ok_or_else(|| MarketError::Parse(format!("timestamp {} out of range", us))),
+            Field::Date(days) => NaiveDate::default()
+                .checked_add_signed(chrono::Duration::days(i64::from(*days)))
+                .ok_or_else(|| MarketError::Parse(format!("date {} out of range", days)))
+                .and_then(|date| self.localize(date.and_time(chrono::NaiveTime::MIN))),
+            other => Err(MarketError::Parse(format!(
+                "unsupported time value {}",
+                other
+            ))),
+        }
+    }
+
+    /// Resolves a wall-clock time in the configured timezone, taking the earlier instant on DST overlaps.
+    fn localize(&self, naive: NaiveDateTime) -> Result<DateTime<Utc>, MarketError> {
+        self.timezone
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|time| time.with_timezone(&Utc))
+            .ok_or_else(|| {
+                MarketError::Parse(format!(
+                    "time {} does not exist in {}",
+                    naive, self.timezone
+                ))
+            })
+    }
+
+    /// Delay between two replayed bars: their time gap shortened by the replay speed.
+    fn replay_delay(&self, gap: chrono::Duration) -> Option<Duration> {
+        if !(self.replay_speed > 0.0 && self.replay_speed.is_finite()) {
+            return None;
+        }
+        let gap = gap.to_std().ok()?;
+        Duration::try_from_secs_f64(gap.as_secs_f64() / self.replay_speed).ok()
+    }
+}
+
+/// Lists `(stem, extension, path)` of the CSV and Parquet files in a directory.
+fn data_files(dir: &Path) -> Vec<(String, String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?.to_string();
+            let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+            matches!(ext.as_str(), "csv" | "parquet").then_some((stem, ext, path))
+        })
+        .collect()
+}
+
+fn epoch_time(epoch: i64) -> Result<DateTime<Utc>, MarketError> {
+    let magnitude = epoch.unsigned_abs();
+    let time = if magnitude < 100_000_000_000 {
+        DateTime::from_timestamp(epoch, 0)
+    } else if magnitude < 100_000_000_000_000 {
+        DateTime::from_timestamp_millis(epoch)
+    } else if magnitude < 100_000_000_000_000_000 {
+        DateTime::from_timestamp_micros(epoch)
+    } else {
+        Some(DateTime::from_timestamp_nanos(epoch))
+    };
+    time.ok_or_else(|| MarketError::Parse(format!("timestamp {} out of range", epoch)))
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal, MarketError> {
+    Decimal::from_str(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .map_err(|e| MarketError::Parse(format!("invalid number {}: {}", value, e)))
+}
+
+fn field_decimal(field: &Field) -> Result<Decimal, MarketError> {
+    let from_float = |value: f64| {
+        Decimal::try_from(value)
+            .map_err(|e| MarketError::Parse(format!("invalid number {}: {}", value, e)))
+    };
+    match field {
+        Field::Byte(v) => Ok(Decimal::from(*v)),
+        Field::Short(v) => Ok(Decimal::from(*v)),
+        Field::Int(v) => Ok(Decimal::from(*v)),
+        Field::Long(v) => Ok(Decimal::from(*v)),
+        Field::UByte(v) => Ok(Decimal::from(*v)),
+        Field::UShort(v) => Ok(Decimal::from(*v)),
+        Field::UInt(v) => Ok(Decimal::from(*v)),
+        Field::ULong(v) => Ok(Decimal::from(*v)),
+        Field::Float(v) => from_float(f64::from(*v)),
+        Field::Double(v) => from_float(*v),
+        Field::Str(v) => parse_decimal(v.trim()),
+        Field::Decimal(d) => {
+            let bytes = d.data();
+            if bytes.is_empty() || bytes.len() > 16 {
+                return Err(MarketError::Parse(format!(
+                    "unsupported decimal width {}",
+                    bytes.len()
+                )));
+            }
+            // Big-endian two's complement, sign-extended to 128 bits.
+            let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0x00 };
+            let mut buf = [fill; 16];
+            buf[16 - bytes.len()..].copy_from_slice(bytes);
+            let scale = u32::try_from(d.scale())
+                .map_err(|_| MarketError::Parse(format!("invalid decimal scale {}", d.scale())))?;
+            Decimal::try_from_i128_with_scale(i128::from_be_bytes(buf), scale)
+                .map_err(|e| MarketError::Parse(format!("decimal out of range: {}", e)))
+        }
+        other => Err(MarketError::Parse(format!(
+            "unsupported numeric value {}",
+            other
+        ))),
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for FileProvider {
+    /// Fetches the bars of a stock within `[start_time, end_time]` from its data file.
+    ///
+    /// # Returns
+    /// Returns `MarketError::NotFound` if the symbol has no file at this timeframe.
+    async fn fetch_candles(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<Candle>, MarketError> {
+        let provider = self.clone();
+        let symbol = stock.symbol.clone();
+        let candles = tokio::task::spawn_blocking(move || provider.load(&symbol, timeframe))
+            .await
+            .map_err(|e| MarketError::Unknown(format!("file read task failed: {}", e)))??;
+        Ok(candles
+            .into_iter()
+            .filter(|c| c.time >= start_time && c.time <= end_time)
+            .collect())
+    }
+
+    /// Replays the finest available timeframe of a stock as a live stream.
+    ///
+    /// # Logic
+    /// 1. Picks the first of 1m / 5m / 1h / 1d that has a data file.
+    /// 2. Emits the bars in time order, sleeping the gap to the previous bar divided by the replay speed.
+    /// 3. 1m bars are emitted as closed (`is_final: true`); coarser bars are emitted as provisional ticks,
+    ///    so the live aggregator closes each one when the next arrives.
+    /// 4. The stream ends after the last bar.
+    ///
+    /// # Returns
+    /// Returns `MarketError::NotFound` if the symbol has no file at any timeframe.
+    async fn subscribe_candles(&self, stock: &Stock) -> Result<CandleStream, MarketError> {
+        let provider = self.clone();
+        let symbol = stock.symbol.clone();
+        let (timeframe, candles) = tokio::task::spawn_blocking(move || {
+            let timeframe = TIMEFRAMES
+                .into_iter()
+                .find(|tf| provider.locate(&symbol, *tf).is_some())
+                .ok_or(MarketError::NotFound)?;
+            provider.load(&symbol, timeframe).map(|c| (timeframe, c))
+        })
+        .await
+        .map_err(|e| MarketError::Unknown(format!("file read task failed: {}", e)))??;
+
+        let (tx, receiver) = mpsc::channel(100);
+        let provider = self.clone();
+        tokio::spawn(async move {
+            let mut previous: Option<DateTime<Utc>> = None;
+            for candle in candles {
+                if let Some(delay) =
+                    previous.and_then(|prev| provider.replay_delay(candle.time - prev))
+                {
+                    tokio::time::sleep(delay).await;
+                }
+                previous = Some(candle.time);
+                let candle = Candle {
+                    is_final: timeframe == TimeFrame::Minute1,
+                    ..candle
+                };
+                if tx.send(Ok(candle)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Box::pin(tokio_stream::wrappers::ReceiverStream::new(
+            receiver,
+        )))
+    }
+
+    /// Lists the symbols that have a data file at any timeframe and contain the query, case-insensitively.
+    async fn search_symbols(&self, query: &str) -> Result<Vec<StockMetadata>, MarketError> {
+        let query = query.to_ascii_uppercase();
+        let symbols: BTreeSet<String> = TIMEFRAMES
+            .iter()
+            .flat_map(|tf| data_files(&self.root.join(tf.to_string())))
+            .map(|(stem, _, _)| stem.to_ascii_uppercase())
+            .filter(|symbol| symbol.contains(&query))
+            .collect();
+        Ok(symbols
+            .into_iter()
+            .map(|symbol| StockMetadata {
+                name: symbol.clone(),
+                exchange: FILE_EXCHANGE.to_string(),
+                sector: None,
+                currency: listing_currency(&symbol).to_string(),
+                symbol,
+            })
+            .collect())
+    }
+}
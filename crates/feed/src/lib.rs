@@ -1 +1,4 @@
+pub mod file;
+pub mod recorder;
+pub mod replay;
 pub mod yahoo;
@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use okane_core::{
+    common::Stock,
+    error::CoreError,
+    market::{entity::Candle, error::MarketError, port::TickRecorder},
+};
+
+/// File name suffix of a recording part.
+const PART_SUFFIX: &str = ".jsonl.gz";
+
+/// One raw update as written to a recording, one JSON object per line.
+///
+/// Prices and volume are kept as decimal strings so that a replay reproduces them exactly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTick {
+    pub received_at: DateTime<Utc>,
+    pub time: DateTime<Utc>,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub adj_close: Option<String>,
+    pub volume: String,
+    pub is_final: bool,
+}
+
+impl RecordedTick {
+    pub fn new(received_at: DateTime<Utc>, candle: &Candle) -> Self {
+        Self {
+            received_at,
+            time: candle.time,
+            open: candle.open.to_string(),
+            high: candle.high.to_string(),
+            low: candle.low.to_string(),
+            close: candle.close.to_string(),
+            adj_close: candle.adj_close.map(|v| v.to_string()),
+            volume: candle.volume.to_string(),
+            is_final: candle.is_final,
+        }
+    }
+
+    /// Restores the update exactly as the provider delivered it.
+    pub fn candle(&self) -> Result<Candle, MarketError> {
+        let parse = |value: &str| {
+            Decimal::from_str(value).map_err(|e| {
+                MarketError::Parse(format!("invalid recorded number {}: {}", value, e))
+            })
+        };
+        Ok(Candle {
+            time: self.time,
+            open: parse(&self.open)?,
+            high: parse(&self.high)?,
+            low: parse(&self.low)?,
+            close: parse(&self.close)?,
+            adj_close: self.adj_close.as_deref().map(parse).transpose()?,
+            volume: parse(&self.volume)?,
+            is_final: self.is_final,
+        })
+    }
+}
+
+/// Recorder appending raw live updates to gzip-compressed JSON-lines files.
+///
+/// Files are laid out as `<dir>/<SYMBOL>/<YYYY-MM-DD>-<part>.jsonl.gz`, partitioned by the UTC day
+/// of the receive time. Every recorder run starts a new part instead of appending to an existing
+/// file, so a part left unfinished by a crash never corrupts later data.
+///
+/// `record` only queues the update; a dedicated writer thread does the file I/O, so the fetcher
+/// task never blocks on disk.
+///
+/// # Invariants
+/// - Updates are written in the order they were recorded, across all symbols.
+/// - Written updates are handed to the OS (gzip sync flush, no fsync) at least every
+///   `FLUSH_INTERVAL` and on `flush`; a part truncated by a crash stays readable up to its last
+///   flushed line. Dropping the recorder finishes every open part.
+pub struct FileTickRecorder {
+    commands: Option<mpsc::Sender<WriterCommand>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+/// Longest time a written update waits in the encoder before being flushed.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+enum WriterCommand {
+    Record { symbol: String, tick: RecordedTick },
+    Flush(mpsc::SyncSender<Result<(), MarketError>>),
+}
+
+struct DayWriter {
+    date: NaiveDate,
+    encoder: GzEncoder<File>,
+}
+
+impl FileTickRecorder {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, MarketError> {
+        let root = dir.into();
+        let (commands, rx) = mpsc::channel();
+        let writer = std::thread::Builder::new()
+            .name("tick-recorder".into())
+            .spawn(move || RecordingWriter::new(root).run(rx))
+            .map_err(|e| MarketError::Unknown(format!("failed to spawn recorder thread: {}", e)))?;
+        Ok(Self {
+            commands: Some(commands),
+            writer: Some(writer),
+        })
+    }
+
+    /// Writes and flushes every update recorded so far, blocking until the writer thread is done.
+    pub fn flush(&self) -> Result<(), MarketError> {
+        let (done, result) = mpsc::sync_channel(1);
+        self.send(WriterCommand::Flush(done))?;
+        result
+            .recv()
+            .map_err(|_| MarketError::Unknown("recorder thread dropped flush".to_string()))?
+    }
+
+    fn send(&self, command: WriterCommand) -> Result<(), MarketError> {
+        self.commands
+            .as_ref()
+            .ok_or_else(|| CoreError::Internal("recorder already closed".to_string()))?
+            .send(command)
+            .map_err(|_| MarketError::Unknown("recorder thread stopped".to_string()))
+    }
+}
+
+impl Drop for FileTickRecorder {
+    fn drop(&mut self) {
+        // Closing the channel lets the writer drain the queue and finish its parts.
+        self.commands.take();
+        if let Some(writer) = self.writer.take()
+            && writer.join().is_err()
+        {
+            tracing::error!("Tick recorder thread panicked");
+        }
+    }
+}
+
+impl TickRecorder for FileTickRecorder {
+    /// Queues an update for the symbol's current part; the writer rolls over to a new file when
+    /// the UTC day changes.
+    fn record(
+        &self,
+        stock: &Stock,
+        received_at: DateTime<Utc>,
+        candle: &Candle,
+    ) -> Result<(), MarketError> {
+        self.send(WriterCommand::Record {
+            symbol: stock.symbol.clone(),
+            tick: RecordedTick::new(received_at, candle),
+        })
+    }
+}
+
+/// State owned by the writer thread.
+struct RecordingWriter {
+    root: PathBuf,
+    writers: HashMap<String, DayWriter>,
+    dirty: bool,
+    flushed_at: Instant,
+}
+
+impl RecordingWriter {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            writers: HashMap::new(),
+            dirty: false,
+            flushed_at: Instant::now(),
+        }
+    }
+
+    /// Processes commands until the recorder is dropped, then finishes every open part.
+    fn run(mut self, commands: mpsc::Receiver<WriterCommand>) {
+        loop {
+            match commands.recv_timeout(FLUSH_INTERVAL) {
+                Ok(WriterCommand::Record { symbol, tick }) => match self.write(&symbol, &tick) {
+                    Ok(()) => self.dirty = true,
+                    Err(e) => tracing::error!("Failed to record {}: {}", symbol, e),
+                },
+                Ok(WriterCommand::Flush(done)) => {
+                    if done.send(self.flush()).is_err() {
+                        tracing::warn!("Recorder flush caller went away");
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+            if self.dirty
+                && self.flushed_at.elapsed() >= FLUSH_INTERVAL
+                && let Err(e) = self.flush()
+            {
+                tracing::error!("Failed to flush recordings: {}", e);
+            }
+        }
+        for (symbol, writer) in self.writers {
+            if let Err(e) = writer.encoder.finish() {
+                tracing::warn!("Failed to finish recording of {}: {}", symbol, e);
+            }
+        }
+    }
+
+    /// Appends an update to the symbol's current part, rolling over when the UTC day changes.
+    fn write(&mut self, symbol: &str, tick: &RecordedTick) -> Result<(), MarketError> {
+        let date = tick.received_at.date_naive();
+        if self.writers.get(symbol).is_none_or(|w| w.date != date) {
+            let encoder = self.open_part(symbol, date)?;
+            if let Some(previous) = self
+                .writers
+                .insert(symbol.to_string(), DayWriter { date, encoder })
+                && let Err(e) = previous.encoder.finish()
+            {
+                tracing::warn!("Failed to finish recording of {}: {}", symbol, e);
+            }
+        }
+        let writer = self
+            .writers
+            .get_mut(symbol)
+            .ok_or_else(|| CoreError::Internal("recording writer missing".to_string()))?;
+
+        let mut line = serde_json::to_vec(tick)
+            .map_err(|e| MarketError::Parse(format!("failed to encode update: {}", e)))?;
+        line.push(b'\n');
+        writer
+            .encoder
+            .write_all(&line)
+            .map_err(|e| MarketError::Unknown(format!("failed to record {}: {}", symbol, e)))
+    }
+
+    /// Sync-flushes every open part so that it decodes up to its last complete line.
+    fn flush(&mut self) -> Result<(), MarketError> {
+        self.dirty = false;
+        self.flushed_at = Instant::now();
+        for (symbol, writer) in &mut self.writers {
+            writer.encoder.flush().map_err(|e| {
+                MarketError::Unknown(format!("failed to flush recording of {}: {}", symbol, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Creates the next unused part of a symbol's day.
+    fn open_part(&self, symbol: &str, date: NaiveDate) -> Result<GzEncoder<File>, MarketError> {
+        let dir = self.root.join(symbol);
+        fs::create_dir_all(&dir).map_err(|e| {
+            MarketError::Unknown(format!("failed to create {}: {}", dir.display(), e))
+        })?;
+        let part = session_parts(&self.root, symbol, date)
+            .last()
+            .map_or(0, |(part, _)| part + 1);
+        let path = dir.join(format!("{}-{}{}", date, part, PART_SUFFIX));
+        let file = File::create_new(&path).map_err(|e| {
+            MarketError::Unknown(format!("failed to create {}: {}", path.display(), e))
+        })?;
+        Ok(GzEncoder::new(file, Compression::default()))
+    }
+}
+
+/// Lists the parts of a symbol's day as `(part, path)`, in part order.
+pub(crate) fn session_parts(root: &Path, symbol: &str, date: NaiveDate) -> Vec<(u32, PathBuf)> {
+    let Ok(entries) = fs::read_dir(root.join(symbol)) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}-", date);
+    let mut parts: Vec<(u32, PathBuf)> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let part = name.strip_prefix(&prefix)?.strip_suffix(PART_SUFFIX)?;
+            Some((part.parse().ok()?, path))
+        })
+        .collect();
+    parts.sort_by_key(|(part, _)| *part);
+    parts
+}
+
+/// Lists the symbols having at least one part for the day.
+pub(crate) fn session_symbols(root: &Path, date: NaiveDate) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut symbols: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|symbol| !session_parts(root, symbol, date).is_empty())
+        .collect();
+    symbols.sort();
+    symbols
+}
+
+/// Reads a symbol's day in recording order.
+///
+/// # Logic
+/// Parts are read one after another. A part whose compressed stream ends early (the recorder was
+/// killed) contributes every complete line before the break.
+///
+/// # Arguments
+/// * `limit`: Stops after this many updates when set.
+pub(crate) fn read_session(
+    root: &Path,
+    symbol: &str,
+    date: NaiveDate,
+    limit: Option<usize>,
+) -> Result<Vec<RecordedTick>, MarketError> {
+    let mut ticks = Vec::new();
+    for (_, path) in session_parts(root, symbol, date) {
+        let file = File::open(&path).map_err(|e| {
+            MarketError::Unknown(format!("failed to open {}: {}", path.display(), e))
+        })?;
+        for line in BufReader::new(MultiGzDecoder::new(file)).lines() {
+            if limit.is_some_and(|limit| ticks.len() >= limit) {
+                return Ok(ticks);
+            }
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    tracing::warn!("Recording {} ends early: {}", path.display(), e);
+                    break;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            ticks.push(serde_json::from_str(&line).map_err(|e| {
+                MarketError::Parse(format!("bad recorded update in {}: {}", path.display(), e))
+            })?);
+        }
+    }
+    Ok(ticks)
+}
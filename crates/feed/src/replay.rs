@@ -0,0 +1,338 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use tokio::sync::{mpsc, watch};
+
+use okane_core::{
+    common::{Stock, TimeFrame, time::TimeProvider},
+    error::CoreError,
+    market::{
+        entity::{Candle, CorporateAction},
+        error::MarketError,
+        port::{CandleStream, MarketDataProvider},
+    },
+    store::port::StockMetadata,
+    trade::fx::listing_currency,
+};
+
+use crate::recorder::{self, RecordedTick};
+
+/// Exchange name reported for symbols served from a recording.
+const REPLAY_EXCHANGE: &str = "REPLAY";
+
+/// Longest single wait of a replay task before it re-reads the clock.
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Virtual clock driving a replay.
+///
+/// The clock stands at the session start until the first replayed update is awaited, then advances
+/// `speed` times faster than wall time. A speed of zero or below releases every update immediately
+/// and moves the clock to the time of the latest released one.
+///
+/// # Invariants
+/// - Virtual time never moves backwards.
+/// - Pausing freezes virtual time; speed changes and pauses take effect on waiting replay tasks at once.
+pub struct ReplayClock {
+    state: Mutex<ClockState>,
+    changed: watch::Sender<()>,
+}
+
+struct ClockState {
+    virtual_anchor: DateTime<Utc>,
+    wall_anchor: Instant,
+    speed: f64,
+    paused: bool,
+    started: bool,
+}
+
+impl ClockState {
+    fn running(&self) -> bool {
+        self.started && !self.paused && self.speed > 0.0 && self.speed.is_finite()
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        if !self.running() {
+            return self.virtual_anchor;
+        }
+        Duration::try_from_secs_f64(self.wall_anchor.elapsed().as_secs_f64() * self.speed)
+            .ok()
+            .and_then(|elapsed| chrono::Duration::from_std(elapsed).ok())
+            .and_then(|elapsed| self.virtual_anchor.checked_add_signed(elapsed))
+            .unwrap_or(self.virtual_anchor)
+    }
+
+    /// Moves the anchors to the present so that a new speed only applies from now on.
+    fn rebase(&mut self) {
+        self.virtual_anchor = self.now();
+        self.wall_anchor = Instant::now();
+    }
+}
+
+impl ReplayClock {
+    pub fn new(start: DateTime<Utc>, speed: f64) -> Self {
+        let (changed, _) = watch::channel(());
+        Self {
+            state: Mutex::new(ClockState {
+                virtual_anchor: start,
+                wall_anchor: Instant::now(),
+                speed,
+                paused: false,
+                started: false,
+            }),
+            changed,
+        }
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<'_, ClockState>, CoreError> {
+        self.state
+            .lock()
+            .map_err(|e| CoreError::Poisoned(e.to_string()))
+    }
+
+    pub fn speed(&self) -> Result<f64, CoreError> {
+        Ok(self.lock_state()?.speed)
+    }
+
+    /// Changes the replay speed: 1 is real time, N is N times faster, zero or below is as fast as possible.
+    pub fn set_speed(&self, speed: f64) -> Result<(), CoreError> {
+        {
+            let mut state = self.lock_state()?;
+            state.rebase();
+            state.speed = speed;
+        }
+        self.changed.send_replace(());
+        Ok(())
+    }
+
+    pub fn pause(&self) -> Result<(), CoreError> {
+        {
+            let mut state = self.lock_state()?;
+            state.rebase();
+            state.paused = true;
+        }
+        self.changed.send_replace(());
+        Ok(())
+    }
+
+    pub fn resume(&self) -> Result<(), CoreError> {
+        {
+            let mut state = self.lock_state()?;
+            state.rebase();
+            state.paused = false;
+        }
+        self.changed.send_replace(());
+        Ok(())
+    }
+
+    /// Waits until virtual time reaches `time`.
+    ///
+    /// # Logic
+    /// 1. The first call starts the clock.
+    /// 2. While paused, waits for a resume.
+    /// 3. At a positive speed, sleeps the remaining virtual gap divided by the speed, waking early
+    ///    on any speed change or pause.
+    /// 4. Otherwise advances virtual time straight to `time`.
+    pub async fn wait_until(&self, time: DateTime<Utc>) -> Result<(), CoreError> {
+        loop {
+            let mut changed = self.changed.subscribe();
+            let delay = {
+                let mut state = self.lock_state()?;
+                if !state.started {
+                    state.started = true;
+                    state.wall_anchor = Instant::now();
+                }
+                if state.paused {
+                    None
+                } else if state.now() >= time {
+                    return Ok(());
+                } else if !state.running() {
+                    state.virtual_anchor = time;
+                    state.wall_anchor = Instant::now();
+                    return Ok(());
+                } else {
+                    let gap = (time - state.now()).to_std().unwrap_or(Duration::ZERO);
+                    Some(
+                        Duration::try_from_secs_f64(gap.as_secs_f64() / state.speed)
+                            .map_or(MAX_WAIT, |delay| delay.min(MAX_WAIT)),
+                    )
+                }
+            };
+            match delay {
+                Some(delay) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = changed.changed() => {}
+                    }
+                }
+                None => {
+                    if changed.changed().await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl TimeProvider for ReplayClock {
+    fn now(&self) -> Result<DateTime<Utc>, CoreError> {
+        Ok(self.lock_state()?.now())
+    }
+}
+
+/// Market data provider playing back a day recorded by `FileTickRecorder`.
+///
+/// Each subscription re-emits the symbol's recorded updates unchanged and in recording order,
+/// releasing every update when the shared `ReplayClock` reaches its original receive time.
+/// History and corporate actions come from an optional history provider, as a
+/// recording only holds live updates.
+///
+/// # Invariants
+/// - The stream of a symbol ends after its last recorded update.
+pub struct ReplayProvider {
+    root: PathBuf,
+    session: NaiveDate,
+    clock: Arc<ReplayClock>,
+    history: Option<Arc<dyn MarketDataProvider>>,
+}
+
+impl ReplayProvider {
+    /// Opens the recording of a UTC day.
+    ///
+    /// # Logic
+    /// Starts the clock at the earliest receive time across the recorded symbols.
+    ///
+    /// # Returns
+    /// Returns `MarketError::NotFound` if nothing was recorded that day.
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        session: NaiveDate,
+        speed: f64,
+    ) -> Result<Self, MarketError> {
+        let root = dir.into();
+        let mut start: Option<DateTime<Utc>> = None;
+        for symbol in recorder::session_symbols(&root, session) {
+            if let Some(first) = recorder::read_session(&root, &symbol, session, Some(1))?.first() {
+                start = Some(start.map_or(first.received_at, |s| s.min(first.received_at)));
+            }
+        }
+        let Some(start) = start else {
+            tracing::error!(
+                "No market data recorded on {} under {}",
+                session,
+                root.display()
+            );
+            return Err(MarketError::NotFound);
+        };
+        Ok(Self {
+            root,
+            session,
+            clock: Arc::new(ReplayClock::new(start, speed)),
+            history: None,
+        })
+    }
+
+    /// Serves history, corporate actions and symbol details from another provider.
+    pub fn with_history(mut self, provider: Arc<dyn MarketDataProvider>) -> Self {
+        self.history = Some(provider);
+        self
+    }
+
+    pub fn clock(&self) -> Arc<ReplayClock> {
+        self.clock.clone()
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for ReplayProvider {
+    /// Delegates to the history provider.
+    ///
+    /// # Returns
+    /// Returns `MarketError::NotFound` without a history provider, so that callers do not take the
+    /// missing history for an empty range.
+    async fn fetch_candles(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<Candle>, MarketError> {
+        match &self.history {
+            Some(history) => {
+                history
+                    .fetch_candles(stock, timeframe, start_time, end_time)
+                    .await
+            }
+            None => Err(MarketError::NotFound),
+        }
+    }
+
+    /// Replays the recorded updates of a stock against the replay clock.
+    ///
+    /// # Returns
+    /// Returns `MarketError::NotFound` if the stock was not recorded that day.
+    async fn subscribe_candles(&self, stock: &Stock) -> Result<CandleStream, MarketError> {
+        let root = self.root.clone();
+        let symbol = stock.symbol.clone();
+        let session = self.session;
+        let ticks: Vec<RecordedTick> = tokio::task::spawn_blocking(move || {
+            recorder::read_session(&root, &symbol, session, None)
+        })
+        .await
+        .map_err(|e| MarketError::Unknown(format!("replay read task failed: {}", e)))??;
+        if ticks.is_empty() {
+            return Err(MarketError::NotFound);
+        }
+
+        let (tx, receiver) = mpsc::channel(100);
+        let clock = self.clock.clone();
+        let symbol = stock.symbol.clone();
+        tokio::spawn(async move {
+            for tick in ticks {
+                if let Err(e) = clock.wait_until(tick.received_at).await {
+                    tracing::error!("Replay clock failed for {}: {}", symbol, e);
+                    break;
+                }
+                if tx.send(tick.candle()).await.is_err() {
+                    break;
+                }
+            }
+            tracing::info!("Replay of {} finished", symbol);
+        });
+
+        Ok(Box::pin(tokio_stream::wrappers::ReceiverStream::new(
+            receiver,
+        )))
+    }
+
+    async fn fetch_corporate_actions(
+        &self,
+        stock: &Stock,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        match &self.history {
+            Some(history) => history.fetch_corporate_actions(stock, start, end).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Lists the symbols recorded that day which contain the query, case-insensitively.
+    async fn search_symbols(&self, query: &str) -> Result<Vec<StockMetadata>, MarketError> {
+        let query = query.to_ascii_uppercase();
+        Ok(recorder::session_symbols(&self.root, self.session)
+            .into_iter()
+            .filter(|symbol| symbol.to_ascii_uppercase().contains(&query))
+            .map(|symbol| StockMetadata {
+                name: symbol.clone(),
+                exchange: REPLAY_EXCHANGE.to_string(),
+                sector: None,
+                currency: listing_currency(&symbol).to_string(),
+                symbol,
+            })
+            .collect())
+    }
+}
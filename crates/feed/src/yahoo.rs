@@ -10,7 +10,7 @@ use yfinance_rs::{SearchBuilder, StreamBuilder, StreamMethod, Ticker, YfClient};
 use okane_core::{
     common::{Stock, TimeFrame},
     market::{
-        entity::Candle,
+        entity::{Candle, CorporateAction, CorporateActionKind},
         error::MarketError,
         port::{CandleStream, MarketDataProvider},
     },
@@ -82,6 +82,8 @@ impl MarketDataProvider for YahooProvider {
             .history_builder()
             .interval(interval)
             .between(start_time, end_time)
+            // Keep as-traded prices; split and dividend adjustment is derived from corporate actions downstream.
+            .auto_adjust(false)
             .fetch_full()
             .await
             .map_err(|e| {
@@ -102,7 +104,7 @@ impl MarketDataProvider for YahooProvider {
                 high: c.high.amount(),
                 low: c.low.amount(),
                 close: c.close.amount(),
-                adj_close: c.close_unadj.as_ref().map(|a| a.amount()),
+                adj_close: None,
                 volume,
                 is_final: true,
             });
@@ -111,6 +113,76 @@ impl MarketDataProvider for YahooProvider {
         Ok(candles)
     }
 
+    /// Fetches split and cash dividend events for a given stock.
+    ///
+    /// # Logic
+    /// 1. Requests daily history with corporate actions enabled over the specified time range.
+    /// 2. Maps `Split` and `Dividend` events to domain `CorporateAction` entities; capital gain distributions are ignored.
+    /// 3. Returns the actions ordered by ex-date.
+    ///
+    /// # Arguments
+    /// * `stock`: Identity identifying the stock symbol.
+    /// * `start_time`: UTC start time for the range.
+    /// * `end_time`: UTC end time for the range.
+    ///
+    /// # Returns
+    /// Returns a `Vec<CorporateAction>` on success, or `MarketError` if the fetch fails.
+    async fn fetch_corporate_actions(
+        &self,
+        stock: &Stock,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        let ticker = Ticker::new(&self.client, &stock.symbol);
+        let response = ticker
+            .history_builder()
+            .interval(yfinance_rs::Interval::D1)
+            .between(start_time, end_time)
+            .auto_adjust(false)
+            .actions(true)
+            .fetch_full()
+            .await
+            .map_err(|e| {
+                MarketError::Unknown(
+                    format!("yahoo fetch_corporate_actions failed: {:?}", e).to_lowercase(),
+                )
+            })?;
+
+        let mut actions: Vec<CorporateAction> = response
+            .actions
+            .into_iter()
+            .filter_map(|action| {
+                let (ex_date, kind) = match action {
+                    yfinance_rs::Action::Split {
+                        ts,
+                        numerator,
+                        denominator,
+                    } => (
+                        ts,
+                        CorporateActionKind::Split {
+                            numerator,
+                            denominator,
+                        },
+                    ),
+                    yfinance_rs::Action::Dividend { ts, amount } => (
+                        ts,
+                        CorporateActionKind::Dividend {
+                            amount: amount.amount(),
+                        },
+                    ),
+                    yfinance_rs::Action::CapitalGain { .. } => return None,
+                };
+                Some(CorporateAction {
+                    symbol: stock.symbol.clone(),
+                    ex_date,
+                    kind,
+                })
+            })
+            .collect();
+        actions.sort_by_key(|a| a.ex_date);
+        Ok(actions)
+    }
+
     /// Subscribes to real-time market data for a given stock.
     ///
     /// # Logic
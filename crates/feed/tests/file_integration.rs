@@ -0,0 +1,261 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures::StreamExt;
+use okane_core::common::{Stock, TimeFrame};
+use okane_core::config::{ColumnMapping, FileProviderConfig};
+use okane_core::market::error::MarketError;
+use okane_core::market::port::MarketDataProvider;
+use okane_feed::file::FileProvider;
+use parquet::data_type::{DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use rust_decimal_macros::dec;
+
+fn stock(symbol: &str) -> Stock {
+    Stock {
+        symbol: symbol.to_string(),
+        exchange: None,
+    }
+}
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, min: u32) -> anyhow::Result<DateTime<Utc>> {
+    Utc.with_ymd_and_hms(year, month, day, hour, min, 0)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))
+}
+
+fn config(dir: &Path, columns: ColumnMapping, timezone: &str) -> FileProviderConfig {
+    FileProviderConfig {
+        dir: dir.to_string_lossy().into_owned(),
+        columns,
+        timezone: timezone.to_string(),
+        replay_speed: 0.0,
+    }
+}
+
+/// 以 Yahoo 导出风格的列名写入乱序的 1m CSV。
+fn write_csv(dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dir.join("1m"))?;
+    fs::write(
+        dir.join("1m").join("AAPL.csv"),
+        "Date,Open,High,Low,Close,Adj Close,Volume\n\
+         2024-01-03 09:31:00,101,102,100.5,101.5,101.4,2000\n\
+         2024-01-03 09:30:00,100,101.25,99.5,101,100.9,1.5e3\n\
+         2024-01-03 09:32:00,101.5,103,101,102.5,102.4,1800\n",
+    )?;
+    Ok(())
+}
+
+/// 写入时间列为 TIMESTAMP(MILLIS) 的日线 Parquet。
+fn write_parquet(dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dir.join("1d"))?;
+    let schema = Arc::new(parse_message_type(
+        "message bars {
+            REQUIRED INT64 ts (TIMESTAMP(MILLIS,true));
+            REQUIRED DOUBLE o;
+            REQUIRED DOUBLE h;
+            REQUIRED DOUBLE l;
+            REQUIRED DOUBLE c;
+            REQUIRED INT64 v;
+        }",
+    )?);
+    let file = fs::File::create(dir.join("1d").join("0700.HK.parquet"))?;
+    let mut writer =
+        SerializedFileWriter::new(file, schema, Arc::new(WriterProperties::builder().build()))?;
+    let day = 86_400_000_i64;
+    let start = 1_704_153_600_000_i64; // 2024-01-02 00:00 UTC
+    let times = [start, start + day, start + 2 * day];
+    let prices: [[f64; 3]; 4] = [
+        [300.0, 305.5, 310.0],
+        [306.0, 309.0, 312.0],
+        [298.0, 304.0, 305.0],
+        [305.0, 308.0, 311.0],
+    ];
+    let volumes = [10_000_i64, 12_000, 9_000];
+
+    let mut row_group = writer.next_row_group()?;
+    let mut index = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        match index {
+            0 => {
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&times, None, None)?;
+            }
+            1..=4 => {
+                column
+                    .typed::<DoubleType>()
+                    .write_batch(&prices[index - 1], None, None)?;
+            }
+            _ => {
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&volumes, None, None)?;
+            }
+        }
+        column.close()?;
+        index += 1;
+    }
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_provider_reads_csv_with_mapping_and_timezone() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    write_csv(dir.path())?;
+    let columns = ColumnMapping {
+        time: "Date".to_string(),
+        open: "Open".to_string(),
+        high: "High".to_string(),
+        low: "Low".to_string(),
+        close: "Close".to_string(),
+        volume: "Volume".to_string(),
+        adj_close: Some("Adj Close".to_string()),
+    };
+    let provider = FileProvider::new(&config(dir.path(), columns, "America/New_York"))?;
+
+    let start = utc(2024, 1, 3, 14, 30)?;
+    let end = utc(2024, 1, 3, 14, 31)?;
+    let candles = provider
+        .fetch_candles(&stock("aapl"), TimeFrame::Minute1, start, end)
+        .await?;
+    assert_eq!(candles.len(), 2);
+    // 09:30 纽约时间 (EST) 即 14:30 UTC，且按时间排序
+    assert_eq!(candles[0].time, start);
+    assert_eq!(candles[1].time, end);
+    assert_eq!(
+        (
+            candles[0].open,
+            candles[0].high,
+            candles[0].low,
+            candles[0].close
+        ),
+        (dec!(100), dec!(101.25), dec!(99.5), dec!(101))
+    );
+    assert_eq!(candles[0].adj_close, Some(dec!(100.9)));
+    assert_eq!(candles[0].volume, dec!(1500));
+    assert!(candles.iter().all(|c| c.is_final));
+
+    let missing = provider
+        .fetch_candles(&stock("AAPL"), TimeFrame::Day1, start, end)
+        .await;
+    assert!(matches!(missing, Err(MarketError::NotFound)));
+
+    let invalid = FileProvider::new(&config(dir.path(), ColumnMapping::default(), "Mars/Base"));
+    assert!(matches!(invalid, Err(MarketError::Parse(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_provider_reads_parquet_and_lists_symbols() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    write_csv(dir.path())?;
+    write_parquet(dir.path())?;
+    let columns = ColumnMapping {
+        time: "ts".to_string(),
+        open: "o".to_string(),
+        high: "h".to_string(),
+        low: "l".to_string(),
+        close: "c".to_string(),
+        volume: "v".to_string(),
+        adj_close: None,
+    };
+    let provider = FileProvider::new(&config(dir.path(), columns, "UTC"))?;
+
+    let candles = provider
+        .fetch_candles(
+            &stock("0700.HK"),
+            TimeFrame::Day1,
+            utc(2024, 1, 1, 0, 0)?,
+            utc(2024, 1, 31, 0, 0)?,
+        )
+        .await?;
+    assert_eq!(candles.len(), 3);
+    assert_eq!(candles[1].time, utc(2024, 1, 3, 0, 0)?);
+    assert_eq!(
+        (
+            candles[1].open,
+            candles[1].high,
+            candles[1].low,
+            candles[1].close
+        ),
+        (dec!(305.5), dec!(309), dec!(304), dec!(308))
+    );
+    assert_eq!(candles[1].volume, dec!(12000));
+    assert_eq!(candles[1].adj_close, None);
+
+    let all = provider.search_symbols("").await?;
+    let symbols: Vec<&str> = all.iter().map(|m| m.symbol.as_str()).collect();
+    assert_eq!(symbols, vec!["0700.HK", "AAPL"]);
+    let hk = provider.search_symbols("hk").await?;
+    assert_eq!(hk.len(), 1);
+    assert_eq!(hk[0].currency, "HKD");
+    assert_eq!(provider.search_symbols("aa").await?[0].currency, "USD");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_provider_replays_finest_timeframe() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    write_csv(dir.path())?;
+    write_parquet(dir.path())?;
+    let columns = ColumnMapping {
+        time: "Date".to_string(),
+        open: "Open".to_string(),
+        high: "High".to_string(),
+        low: "Low".to_string(),
+        close: "Close".to_string(),
+        volume: "Volume".to_string(),
+        adj_close: None,
+    };
+    // 1 分钟间隔按 3000 倍速回放为 20ms
+    let provider = FileProvider::new(&FileProviderConfig {
+        replay_speed: 3000.0,
+        ..config(dir.path(), columns, "America/New_York")
+    })?;
+
+    let started = std::time::Instant::now();
+    let minutes: Vec<_> = provider
+        .subscribe_candles(&stock("AAPL"))
+        .await?
+        .collect()
+        .await;
+    assert!(started.elapsed() >= std::time::Duration::from_millis(40));
+    let minutes = minutes.into_iter().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(minutes.len(), 3);
+    assert!(minutes.windows(2).all(|w| w[0].time < w[1].time));
+    assert!(minutes.iter().all(|c| c.is_final));
+
+    // 只有日线的标的以未收盘 K 线回放，由下游聚合在下一根到达时收盘
+    let provider = FileProvider::new(&config(
+        dir.path(),
+        ColumnMapping {
+            time: "ts".to_string(),
+            open: "o".to_string(),
+            high: "h".to_string(),
+            low: "l".to_string(),
+            close: "c".to_string(),
+            volume: "v".to_string(),
+            adj_close: None,
+        },
+        "UTC",
+    ))?;
+    let days: Vec<_> = provider
+        .subscribe_candles(&stock("0700.HK"))
+        .await?
+        .collect()
+        .await;
+    assert_eq!(days.len(), 3);
+    assert!(days.iter().all(|c| c.as_ref().is_ok_and(|c| !c.is_final)));
+    assert!(matches!(
+        provider.subscribe_candles(&stock("MSFT")).await,
+        Err(MarketError::NotFound)
+    ));
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures::StreamExt;
+use okane_core::common::Stock;
+use okane_core::common::time::TimeProvider;
+use okane_core::market::entity::Candle;
+use okane_core::market::error::MarketError;
+use okane_core::market::port::{MarketDataProvider, TickRecorder};
+use okane_feed::recorder::FileTickRecorder;
+use okane_feed::replay::ReplayProvider;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn stock(symbol: &str) -> Stock {
+    Stock {
+        symbol: symbol.to_string(),
+        exchange: None,
+    }
+}
+
+/// 2026-10-16 14:30:00 UTC 之后的第 `secs` 秒。
+fn at(secs: i64) -> anyhow::Result<DateTime<Utc>> {
+    Utc.timestamp_opt(1_792_161_000 + secs, 0)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))
+}
+
+fn tick(secs: i64, price: Decimal) -> anyhow::Result<Candle> {
+    Ok(Candle {
+        time: at(secs)?,
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        adj_close: None,
+        volume: dec!(1.50),
+        is_final: false,
+    })
+}
+
+#[tokio::test]
+async fn test_recording_replays_exact_sequence_across_parts_and_days() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let session = at(0)?.date_naive();
+    let aapl = stock("AAPL");
+
+    let first_run = FileTickRecorder::new(dir.path())?;
+    first_run.record(&aapl, at(0)?, &tick(0, dec!(100.2500))?)?;
+    first_run.record(&aapl, at(1)?, &tick(1, dec!(100.26))?)?;
+    // 同一标的同一秒内的重复更新也按原样保留
+    first_run.record(&aapl, at(1)?, &tick(1, dec!(100.26))?)?;
+    first_run.record(&stock("MSFT"), at(2)?, &tick(2, dec!(400))?)?;
+    // 后台写线程已把更新刷出，但分片未正常结束
+    first_run.flush()?;
+
+    // 未正常关闭的录制 (进程被杀) 仍可读出已写入的全部更新，重启后写入新的分片
+    let second_run = FileTickRecorder::new(dir.path())?;
+    second_run.record(&aapl, at(5)?, &tick(5, dec!(99.9))?)?;
+    second_run.record(&aapl, at(86_400)?, &tick(86_400, dec!(101))?)?;
+    second_run.flush()?;
+    assert!(
+        dir.path()
+            .join("AAPL")
+            .join(format!("{}-1.jsonl.gz", session))
+            .exists()
+    );
+
+    let provider = ReplayProvider::new(dir.path(), session, 0.0)?;
+    let replayed = provider
+        .subscribe_candles(&aapl)
+        .await?
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    let closes: Vec<String> = replayed.iter().map(|c| c.close.to_string()).collect();
+    assert_eq!(closes, vec!["100.2500", "100.26", "100.26", "99.9"]);
+    assert!(replayed.iter().all(|c| c.volume.to_string() == "1.50"));
+    assert_eq!(replayed[3].time, at(5)?);
+    // 极速重放后时钟停在最后一条更新的接收时间
+    assert_eq!(provider.clock().now()?, at(5)?);
+
+    let symbols: Vec<String> = provider
+        .search_symbols("")
+        .await?
+        .into_iter()
+        .map(|m| m.symbol)
+        .collect();
+    assert_eq!(symbols, vec!["AAPL", "MSFT"]);
+    assert!(matches!(
+        provider.subscribe_candles(&stock("TSLA")).await,
+        Err(MarketError::NotFound)
+    ));
+    // 未接历史数据源时历史查询报错，而不是返回空区间
+    assert!(matches!(
+        provider
+            .fetch_candles(&aapl, okane_core::common::TimeFrame::Day1, at(0)?, at(5)?)
+            .await,
+        Err(MarketError::NotFound)
+    ));
+    assert!(matches!(
+        ReplayProvider::new(dir.path(), session - chrono::Duration::days(1), 1.0),
+        Err(MarketError::NotFound)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_clock_paces_pauses_and_changes_speed() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let recorder = FileTickRecorder::new(dir.path())?;
+    for secs in 0..3 {
+        recorder.record(&stock("AAPL"), at(secs)?, &tick(secs, dec!(100))?)?;
+    }
+    recorder.record(&stock("AAPL"), at(3_600)?, &tick(3_600, dec!(101))?)?;
+    // 释放录制器会写完队列并正常结束分片
+    drop(recorder);
+
+    // 20 倍速：相邻 1 秒的更新间隔 50ms
+    let provider = ReplayProvider::new(dir.path(), at(0)?.date_naive(), 20.0)?;
+    let clock = provider.clock();
+    clock.pause()?;
+    let mut stream = provider.subscribe_candles(&stock("AAPL")).await?;
+    assert!(
+        tokio::time::timeout(Duration::from_millis(150), stream.next())
+            .await
+            .is_err()
+    );
+    assert_eq!(clock.now()?, at(0)?);
+
+    clock.resume()?;
+    let started = Instant::now();
+    for _ in 0..3 {
+        tokio::time::timeout(Duration::from_secs(2), stream.next())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("replay ended early"))??;
+    }
+    assert!(started.elapsed() >= Duration::from_millis(90));
+    assert!(clock.now()? >= at(2)?);
+
+    // 下一条在虚拟时间 1 小时后，切换为极速后立即到达
+    clock.set_speed(0.0)?;
+    let last = tokio::time::timeout(Duration::from_secs(2), stream.next())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("replay ended early"))??;
+    assert_eq!(last.close, dec!(101));
+    assert_eq!(clock.now()?, at(3_600)?);
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
@@ -0,0 +1,426 @@
+//! # FIX 券商端口
+//!
+//! `FixBroker` 以订单 ID 作为 ClOrdID 外发订单，券商订单号即 ClOrdID。ExecutionReport 按订单累计为
+//! `ExecutionReport` (累计成交、均价与手续费) 后推送给订阅者，由 `BrokerGateway` 结算。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use okane_core::config::FixConfig;
+use okane_core::trade::entity::{ExecutionReport, Order, OrderStatus};
+use okane_core::trade::port::{BrokerPort, ExecutionReportStream, TradeError};
+use rust_decimal::Decimal;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::FixError;
+use crate::convert::{self, FixExecution, OrderTicket, exec_type};
+use crate::message::{FixMessage, msg_type, tags};
+use crate::session::{ApplicationHandler, FixSession};
+use crate::store::FileSequenceStore;
+
+/// 订单状态查询的默认超时
+const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 没有订阅者期间暂存的回报上限，超出时丢弃最早的
+const MAX_UNDELIVERED_REPORTS: usize = 1024;
+
+/// # Summary
+/// 基于 FIX 4.4 会话的券商端口。
+///
+/// # Invariants
+/// - 会话在首次外发、撤单、查询或订阅时按需登录，断开后由下一次调用重新登录。
+/// - 进程重启后从会话日志恢复已外发的委托与累计成交，重连后的补发回报不会重复累计。
+pub struct FixBroker {
+    session: Arc<FixSession>,
+    book: Arc<FixOrderBook>,
+    query_timeout: Duration,
+}
+
+impl FixBroker {
+    /// # Logic
+    /// 打开 `store_dir` 下的会话存储，并以会话日志 (含重置时保留的报文) 中的
+    /// NewOrderSingle / OrderCancelRequest 与 ExecutionReport 恢复订单簿。
+    ///
+    /// # Returns
+    /// * `Err(FixError::Io)` - If the session store cannot be opened.
+    pub async fn new(config: FixConfig, store_dir: &Path) -> Result<Self, FixError> {
+        let store = Arc::new(
+            FileSequenceStore::open(store_dir, &config.sender_comp_id, &config.target_comp_id)
+                .await?,
+        );
+        let messages = store.recovery_messages().await?;
+        let book = Arc::new(FixOrderBook::default());
+        {
+            let mut state = book.lock_state()?;
+            for msg in &messages {
+                match msg.msg_type() {
+                    msg_type::NEW_ORDER_SINGLE => {
+                        let ticket = OrderTicket::from_new_order_single(msg)?;
+                        state.tickets.insert(ticket.cl_ord_id.clone(), ticket);
+                    }
+                    msg_type::ORDER_CANCEL_REQUEST => {
+                        let cancel_id = msg.require(tags::CL_ORD_ID)?.to_string();
+                        let orig = msg.require(tags::ORIG_CL_ORD_ID)?.to_string();
+                        state.aliases.insert(cancel_id, orig);
+                    }
+                    msg_type::EXECUTION_REPORT => {
+                        if let Err(e) = state.apply(msg) {
+                            tracing::warn!("Skipped unreadable FIX execution report in log: {}", e);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let session = Arc::new(FixSession::new(config, store, book.clone()));
+        Ok(Self {
+            session,
+            book,
+            query_timeout: DEFAULT_QUERY_TIMEOUT,
+        })
+    }
+
+    /// 设置订单状态查询等待 ExecutionReport 应答的超时。
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = timeout;
+        self
+    }
+
+    pub fn session(&self) -> &Arc<FixSession> {
+        &self.session
+    }
+
+    /// 主动登出会话。
+    pub async fn logout(&self) -> Result<(), TradeError> {
+        Ok(self.session.logout().await?)
+    }
+
+    fn ticket(&self, external_order_id: &str) -> Result<OrderTicket, TradeError> {
+        self.book
+            .lock_state()?
+            .tickets
+            .get(external_order_id)
+            .cloned()
+            .ok_or_else(|| TradeError::OrderNotFound(external_order_id.to_string()))
+    }
+}
+
+#[async_trait]
+impl BrokerPort for FixBroker {
+    /// # Logic
+    /// 以订单 ID 作为 ClOrdID 发送 NewOrderSingle，返回 ClOrdID 作为券商订单号。
+    /// 委托在登录前登记，登录期间对端推送或应答查询的回报都能定位到该订单；
+    /// 登录或发送失败时撤回登记的委托，同一订单可以重试外发。
+    async fn send_order(&self, order: &Order) -> Result<String, TradeError> {
+        let msg = convert::new_order_single(order, Utc::now())?;
+        let ticket = OrderTicket::from_order(order);
+        let cl_ord_id = ticket.cl_ord_id.clone();
+        {
+            let mut state = self.book.lock_state()?;
+            if state.tickets.contains_key(&cl_ord_id) {
+                return Err(TradeError::BrokerIntegrationError(format!(
+                    "duplicate ClOrdID {}",
+                    cl_ord_id
+                )));
+            }
+            state.tickets.insert(cl_ord_id.clone(), ticket);
+        }
+        let sent = match self.session.connect().await {
+            Ok(()) => self.session.send_app(msg).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = sent {
+            self.book.lock_state()?.tickets.remove(&cl_ord_id);
+            return Err(e.into());
+        }
+        Ok(cl_ord_id)
+    }
+
+    /// # Logic
+    /// 发送 OrderCancelRequest，撤单请求的 ClOrdID 为 `{原 ClOrdID}-C{n}`。
+    async fn cancel_order(&self, external_order_id: &str) -> Result<(), TradeError> {
+        let ticket = self.ticket(external_order_id)?;
+        self.session.connect().await?;
+        let cancel_id = {
+            let mut state = self.book.lock_state()?;
+            let count = state
+                .aliases
+                .values()
+                .filter(|orig| orig.as_str() == external_order_id)
+                .count();
+            let cancel_id = format!("{}-C{}", external_order_id, count + 1);
+            state
+                .aliases
+                .insert(cancel_id.clone(), external_order_id.to_string());
+            cancel_id
+        };
+        self.session
+            .send_app(convert::order_cancel_request(
+                &ticket,
+                &cancel_id,
+                Utc::now(),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// # Logic
+    /// 发送 OrderStatusRequest，等待该订单的下一条 ExecutionReport 作为应答。
+    ///
+    /// # Returns
+    /// * `Err(TradeError::BrokerIntegrationError)` - If no report arrives within the query timeout.
+    async fn query_order_status(
+        &self,
+        external_order_id: &str,
+    ) -> Result<ExecutionReport, TradeError> {
+        let ticket = self.ticket(external_order_id)?;
+        self.session.connect().await?;
+        let (tx, rx) = oneshot::channel();
+        self.book
+            .lock_state()?
+            .waiters
+            .entry(external_order_id.to_string())
+            .or_default()
+            .push(tx);
+        self.session
+            .send_app(convert::order_status_request(&ticket))
+            .await?;
+        match tokio::time::timeout(self.query_timeout, rx).await {
+            Ok(Ok(report)) => Ok(report),
+            _ => {
+                let mut state = self.book.lock_state()?;
+                if let Some(waiters) = state.waiters.get_mut(external_order_id) {
+                    waiters.retain(|waiter| !waiter.is_closed());
+                    if waiters.is_empty() {
+                        state.waiters.remove(external_order_id);
+                    }
+                }
+                Err(TradeError::BrokerIntegrationError(format!(
+                    "order status query for {} timed out",
+                    external_order_id
+                )))
+            }
+        }
+    }
+
+    /// # Logic
+    /// 先登记订阅者再登录，登录过程中对端补发的回报同样推送给新订阅者；
+    /// 没有订阅者期间暂存的回报先推送给新订阅者。
+    async fn subscribe_execution_reports(&self) -> Result<ExecutionReportStream, TradeError> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        {
+            let mut state = self.book.lock_state()?;
+            for report in std::mem::take(&mut state.undelivered) {
+                if tx.send(report).is_err() {
+                    tracing::debug!("Execution report subscriber closed before replay");
+                }
+            }
+            state.subscribers.push(tx);
+        }
+        self.session.connect().await?;
+        Ok(Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|report| (report, rx))
+        })))
+    }
+}
+
+/// # Summary
+/// 按 ClOrdID 维护的订单簿：原始委托、撤单别名与累计回报。
+#[derive(Default)]
+struct FixOrderBook {
+    state: Mutex<BookState>,
+}
+
+#[derive(Default)]
+struct BookState {
+    /// ClOrdID -> 原始委托要素
+    tickets: HashMap<String, OrderTicket>,
+    /// 撤单请求的 ClOrdID -> 原委托 ClOrdID
+    aliases: HashMap<String, String>,
+    /// 原委托 ClOrdID -> 累计回报
+    reports: HashMap<String, ExecutionReport>,
+    /// 原委托 ClOrdID -> 已累计过的 ExecID；订单终结后清理
+    exec_ids: HashMap<String, HashSet<String>>,
+    subscribers: Vec<mpsc::UnboundedSender<ExecutionReport>>,
+    /// 没有订阅者期间到达的回报，待下一个订阅者取走
+    undelivered: VecDeque<ExecutionReport>,
+    /// 等待状态查询应答的调用方
+    waiters: HashMap<String, Vec<oneshot::Sender<ExecutionReport>>>,
+}
+
+impl FixOrderBook {
+    fn lock_state(&self) -> Result<MutexGuard<'_, BookState>, FixError> {
+        self.state
+            .lock()
+            .map_err(|e| FixError::Session(format!("fix order book lock poisoned: {}", e)))
+    }
+}
+
+impl BookState {
+    /// 订单是否已终结 (全部成交、撤销、拒绝或过期)；没有回报的订单视为在途。
+    fn is_closed(&self, cl_ord_id: &str) -> bool {
+        self.reports.get(cl_ord_id).is_some_and(|report| {
+            matches!(
+                report.status,
+                OrderStatus::Filled
+                    | OrderStatus::Canceled
+                    | OrderStatus::Rejected
+                    | OrderStatus::Expired
+            )
+        })
+    }
+
+    /// 撤单请求的 ClOrdID 归并到原委托。
+    fn resolve(&self, cl_ord_id: &str) -> String {
+        self.aliases
+            .get(cl_ord_id)
+            .cloned()
+            .unwrap_or_else(|| cl_ord_id.to_string())
+    }
+
+    /// # Logic
+    /// 将一条 ExecutionReport 累计到对应订单：OrigClOrdID 存在时以其定位原委托，
+    /// 成交回报 (ExecType=F) 的手续费按 ExecID 去重后累加。状态查询应答 (ExecType=I)
+    /// 不参与去重，每次查询都会得到应答；订单终结后清理其 ExecID，之后只接受状态查询应答。
+    ///
+    /// # Returns
+    /// * `Ok(None)` - If the report belongs to an unknown order, repeats a seen ExecID
+    ///   or reports a new execution on an order that already finished.
+    fn apply(&mut self, msg: &FixMessage) -> Result<Option<ExecutionReport>, FixError> {
+        let exec = FixExecution::parse(msg)?;
+        let key = match &exec.orig_cl_ord_id {
+            Some(orig) => self.resolve(orig),
+            None => self.resolve(&exec.cl_ord_id),
+        };
+        if !self.tickets.contains_key(&key) {
+            tracing::warn!("Ignored FIX execution report for unknown order {}", key);
+            return Ok(None);
+        }
+        if exec.exec_type != exec_type::ORDER_STATUS {
+            if self.is_closed(&key) {
+                return Ok(None);
+            }
+            if !self
+                .exec_ids
+                .entry(key.clone())
+                .or_default()
+                .insert(exec.exec_id.clone())
+            {
+                return Ok(None);
+            }
+        }
+
+        let report = self
+            .reports
+            .entry(key.clone())
+            .or_insert_with(|| ExecutionReport {
+                external_order_id: key,
+                status: OrderStatus::Submitted,
+                filled_volume: Decimal::ZERO,
+                average_price: Decimal::ZERO,
+                commission: Decimal::ZERO,
+                timestamp: 0,
+                reason: None,
+            });
+        if exec.exec_type == exec_type::TRADE {
+            report.commission += exec.commission;
+        }
+        report.status = exec.status;
+        report.filled_volume = exec.cum_qty;
+        report.average_price = exec.avg_px;
+        report.timestamp = exec
+            .transact_time
+            .unwrap_or_else(|| Utc::now().timestamp_millis());
+        report.reason = exec.text;
+        let report = report.clone();
+        if self.is_closed(&report.external_order_id) {
+            self.exec_ids.remove(&report.external_order_id);
+        }
+        Ok(Some(report))
+    }
+}
+
+impl ApplicationHandler for FixOrderBook {
+    fn on_message(&self, msg: &FixMessage) {
+        let mut state = match self.lock_state() {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::error!("{}", e);
+                return;
+            }
+        };
+        match msg.msg_type() {
+            msg_type::EXECUTION_REPORT => match state.apply(msg) {
+                Ok(Some(report)) => {
+                    state
+                        .subscribers
+                        .retain(|subscriber| subscriber.send(report.clone()).is_ok());
+                    if state.subscribers.is_empty() {
+                        if state.undelivered.len() >= MAX_UNDELIVERED_REPORTS {
+                            state.undelivered.pop_front();
+                        }
+                        state.undelivered.push_back(report.clone());
+                    }
+                    for waiter in state
+                        .waiters
+                        .remove(&report.external_order_id)
+                        .unwrap_or_default()
+                    {
+                        if waiter.send(report.clone()).is_err() {
+                            tracing::debug!("Order status query gave up before the reply");
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to handle FIX execution report: {}", e),
+            },
+            msg_type::ORDER_CANCEL_REJECT => {
+                tracing::warn!(
+                    "FIX cancel request {} rejected: {}",
+                    msg.get(tags::CL_ORD_ID).unwrap_or_default(),
+                    msg.get(tags::TEXT).unwrap_or_default()
+                );
+            }
+            other => tracing::debug!("Ignored FIX application message {}", other),
+        }
+    }
+
+    fn on_disconnect(&self) {
+        match self.lock_state() {
+            Ok(mut state) => {
+                state.subscribers.clear();
+                state.waiters.clear();
+            }
+            Err(e) => tracing::error!("{}", e),
+        }
+    }
+
+    /// # Logic
+    /// 保留仍在途订单的委托、撤单请求与回报，重置后仍可撤单、查询并累计后续成交。
+    fn retain_on_reset(&self, msg: &FixMessage) -> bool {
+        let state = match self.lock_state() {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::error!("{}", e);
+                return true;
+            }
+        };
+        let key = match msg.msg_type() {
+            msg_type::NEW_ORDER_SINGLE => msg.get(tags::CL_ORD_ID),
+            msg_type::ORDER_CANCEL_REQUEST => msg.get(tags::ORIG_CL_ORD_ID),
+            msg_type::EXECUTION_REPORT => msg
+                .get(tags::ORIG_CL_ORD_ID)
+                .or_else(|| msg.get(tags::CL_ORD_ID)),
+            _ => None,
+        };
+        key.is_some_and(|key| {
+            let key = state.resolve(key);
+            state.tickets.contains_key(&key) && !state.is_closed(&key)
+        })
+    }
+}
@@ -0,0 +1,251 @@
+//! # 订单与 FIX 业务报文的映射
+//!
+//! - `Order` -> NewOrderSingle(D)；撤单与状态查询分别映射为 OrderCancelRequest(F) 与 OrderStatusRequest(H)。
+//! - ExecutionReport(8) -> `FixExecution`，由 `FixBroker` 累计为 `ExecutionReport`，
+//!   再经 `BrokerGateway` 按累计成交差额结算为 `Trade`。
+
+use chrono::{DateTime, Utc};
+use okane_core::trade::entity::{Order, OrderDirection, OrderStatus, TimeInForce};
+use rust_decimal::Decimal;
+
+use crate::FixError;
+use crate::message::{FixMessage, format_utc_timestamp, msg_type, parse_utc_timestamp, tags};
+
+/// # Summary
+/// 撤单与状态查询所需的原始委托要素。
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderTicket {
+    pub cl_ord_id: String,
+    pub symbol: String,
+    pub direction: OrderDirection,
+    pub volume: Decimal,
+}
+
+impl OrderTicket {
+    pub fn from_order(order: &Order) -> Self {
+        Self {
+            cl_ord_id: order.id.0.clone(),
+            symbol: order.symbol.clone(),
+            direction: order.direction,
+            volume: order.volume,
+        }
+    }
+
+    /// 从已发送的 NewOrderSingle 恢复委托要素。
+    pub fn from_new_order_single(msg: &FixMessage) -> Result<Self, FixError> {
+        Ok(Self {
+            cl_ord_id: msg.require(tags::CL_ORD_ID)?.to_string(),
+            symbol: msg.require(tags::SYMBOL)?.to_string(),
+            direction: parse_side(msg.require(tags::SIDE)?)?,
+            volume: msg
+                .get_decimal(tags::ORDER_QTY)?
+                .ok_or(FixError::MissingField(tags::ORDER_QTY))?,
+        })
+    }
+}
+
+fn side(direction: OrderDirection) -> &'static str {
+    match direction {
+        OrderDirection::Buy => "1",
+        OrderDirection::Sell => "2",
+    }
+}
+
+fn parse_side(value: &str) -> Result<OrderDirection, FixError> {
+    match value {
+        "1" => Ok(OrderDirection::Buy),
+        "2" => Ok(OrderDirection::Sell),
+        other => Err(FixError::InvalidField(tags::SIDE, other.to_string())),
+    }
+}
+
+/// # Logic
+/// 映射委托：`price` 为空时为市价单 (OrdType=1)，否则为限价单 (OrdType=2)；
+/// 有效期映射为 TimeInForce(59)，GTD 附带 ExpireTime(126)。
+///
+/// # Returns
+/// * `Err(FixError::InvalidField)` - If the order carries a stop trigger, which is not mapped.
+pub fn new_order_single(
+    order: &Order,
+    transact_time: DateTime<Utc>,
+) -> Result<FixMessage, FixError> {
+    if order.trigger.is_some() {
+        return Err(FixError::InvalidField(
+            tags::ORD_TYPE,
+            "stop orders are not supported".to_string(),
+        ));
+    }
+    let mut msg = FixMessage::new(msg_type::NEW_ORDER_SINGLE)
+        .with(tags::CL_ORD_ID, &order.id.0)
+        .with(tags::SYMBOL, &order.symbol)
+        .with(tags::SIDE, side(order.direction))
+        .with(tags::TRANSACT_TIME, format_utc_timestamp(transact_time))
+        .with(tags::ORDER_QTY, order.volume);
+    match order.price {
+        Some(price) => {
+            msg.set(tags::ORD_TYPE, "2");
+            msg.set(tags::PRICE, price);
+        }
+        None => msg.set(tags::ORD_TYPE, "1"),
+    }
+    let time_in_force = match order.time_in_force {
+        TimeInForce::Day => "0",
+        TimeInForce::Gtc => "1",
+        TimeInForce::Ioc => "3",
+        TimeInForce::Fok => "4",
+        TimeInForce::Gtd { expire_at } => {
+            let expire_at = DateTime::from_timestamp_millis(expire_at)
+                .ok_or_else(|| FixError::InvalidField(tags::EXPIRE_TIME, expire_at.to_string()))?;
+            msg.set(tags::EXPIRE_TIME, format_utc_timestamp(expire_at));
+            "6"
+        }
+    };
+    msg.set(tags::TIME_IN_FORCE, time_in_force);
+    Ok(msg)
+}
+
+/// 撤单请求：`cancel_id` 为本次撤单请求的 ClOrdID，OrigClOrdID 指向原委托。
+pub fn order_cancel_request(
+    ticket: &OrderTicket,
+    cancel_id: &str,
+    transact_time: DateTime<Utc>,
+) -> FixMessage {
+    FixMessage::new(msg_type::ORDER_CANCEL_REQUEST)
+        .with(tags::ORIG_CL_ORD_ID, &ticket.cl_ord_id)
+        .with(tags::CL_ORD_ID, cancel_id)
+        .with(tags::SYMBOL, &ticket.symbol)
+        .with(tags::SIDE, side(ticket.direction))
+        .with(tags::TRANSACT_TIME, format_utc_timestamp(transact_time))
+        .with(tags::ORDER_QTY, ticket.volume)
+}
+
+/// 订单状态查询，券商以 ExecType=I 的 ExecutionReport 应答。
+pub fn order_status_request(ticket: &OrderTicket) -> FixMessage {
+    FixMessage::new(msg_type::ORDER_STATUS_REQUEST)
+        .with(tags::CL_ORD_ID, &ticket.cl_ord_id)
+        .with(tags::SYMBOL, &ticket.symbol)
+        .with(tags::SIDE, side(ticket.direction))
+}
+
+/// ExecType(150) 中与累计口径相关的取值。
+pub mod exec_type {
+    /// 成交 (Trade)
+    pub const TRADE: &str = "F";
+    /// 状态查询应答 (Order Status)
+    pub const ORDER_STATUS: &str = "I";
+}
+
+/// # Summary
+/// 解析后的一条 ExecutionReport。
+///
+/// # Invariants
+/// - `cum_qty` 与 `avg_px` 为累计口径；`commission` 为本条回报 (单笔成交) 的手续费。
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixExecution {
+    pub cl_ord_id: String,
+    pub orig_cl_ord_id: Option<String>,
+    pub exec_id: String,
+    pub exec_type: String,
+    pub status: OrderStatus,
+    pub cum_qty: Decimal,
+    pub avg_px: Decimal,
+    pub commission: Decimal,
+    /// TransactTime(60)，缺失时为空
+    pub transact_time: Option<i64>,
+    pub text: Option<String>,
+}
+
+impl FixExecution {
+    /// # Logic
+    /// 解析 ExecutionReport；OrdStatus(39) 的挂起类状态 (待撤、待改等) 视为仍在生效，
+    /// 按是否已有成交映射为 `PartialFilled` 或 `Submitted`。
+    pub fn parse(msg: &FixMessage) -> Result<Self, FixError> {
+        let cum_qty = msg.get_decimal(tags::CUM_QTY)?.unwrap_or(Decimal::ZERO);
+        let active = if cum_qty > Decimal::ZERO {
+            OrderStatus::PartialFilled
+        } else {
+            OrderStatus::Submitted
+        };
+        let status = match msg.require(tags::ORD_STATUS)? {
+            "A" => OrderStatus::Pending,
+            "0" | "5" | "6" | "9" | "E" => active,
+            "1" => OrderStatus::PartialFilled,
+            "2" => OrderStatus::Filled,
+            "4" => OrderStatus::Canceled,
+            "8" => OrderStatus::Rejected,
+            "3" | "C" => OrderStatus::Expired,
+            other => return Err(FixError::InvalidField(tags::ORD_STATUS, other.to_string())),
+        };
+        Ok(Self {
+            cl_ord_id: msg.require(tags::CL_ORD_ID)?.to_string(),
+            orig_cl_ord_id: msg.get(tags::ORIG_CL_ORD_ID).map(str::to_string),
+            exec_id: msg.require(tags::EXEC_ID)?.to_string(),
+            exec_type: msg.require(tags::EXEC_TYPE)?.to_string(),
+            status,
+            cum_qty,
+            avg_px: msg.get_decimal(tags::AVG_PX)?.unwrap_or(Decimal::ZERO),
+            commission: msg.get_decimal(tags::COMMISSION)?.unwrap_or(Decimal::ZERO),
+            transact_time: msg
+                .get(tags::TRANSACT_TIME)
+                .map(parse_utc_timestamp)
+                .transpose()?
+                .map(|t| t.timestamp_millis()),
+            text: msg.get(tags::TEXT).map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use okane_core::trade::entity::{AccountId, OrderId};
+
+    #[test]
+    fn test_order_round_trips_through_new_order_single() -> Result<(), FixError> {
+        let order = Order::new(
+            OrderId("o-1".to_string()),
+            AccountId("a".to_string()),
+            "AAPL".to_string(),
+            OrderDirection::Sell,
+            Some(Decimal::new(1505, 1)),
+            Decimal::from(10),
+            0,
+        )
+        .with_time_in_force(TimeInForce::Ioc);
+        let msg = new_order_single(&order, Utc::now())?;
+        assert_eq!(msg.get(tags::ORD_TYPE), Some("2"));
+        assert_eq!(msg.get(tags::PRICE), Some("150.5"));
+        assert_eq!(msg.get(tags::TIME_IN_FORCE), Some("3"));
+        assert_eq!(
+            OrderTicket::from_new_order_single(&msg)?,
+            OrderTicket::from_order(&order)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_execution_report_status_mapping() -> Result<(), FixError> {
+        let report = |status: &str, cum_qty: &str| {
+            FixMessage::new(msg_type::EXECUTION_REPORT)
+                .with(tags::CL_ORD_ID, "o-1")
+                .with(tags::EXEC_ID, "e-1")
+                .with(tags::EXEC_TYPE, "6")
+                .with(tags::ORD_STATUS, status)
+                .with(tags::CUM_QTY, cum_qty)
+                .with(tags::TRANSACT_TIME, "20240102-03:04:05")
+        };
+        let pending_cancel = FixExecution::parse(&report("6", "4"))?;
+        assert_eq!(pending_cancel.status, OrderStatus::PartialFilled);
+        assert_eq!(pending_cancel.transact_time, Some(1_704_164_645_000));
+        assert_eq!(
+            FixExecution::parse(&report("6", "0"))?.status,
+            OrderStatus::Submitted
+        );
+        assert_eq!(
+            FixExecution::parse(&report("C", "0"))?.status,
+            OrderStatus::Expired
+        );
+        assert!(FixExecution::parse(&report("Z", "0")).is_err());
+        Ok(())
+    }
+}
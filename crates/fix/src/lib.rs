@@ -0,0 +1,39 @@
+//! # FIX 4.4 券商网关
+//!
+//! 以 FIX 4.4 发起方 (initiator) 身份连接券商，实现 `BrokerPort`：
+//! - `message`：报文编解码与流式切分。
+//! - `store`：持久化的会话序号与收发报文日志，用于重连续号、重发与进程重启后恢复订单状态。
+//! - `session`：登录、心跳、测试请求、序号缺口重发请求与对端重发请求的处理。
+//! - `convert`：`Order` 与 NewOrderSingle / OrderCancelRequest、ExecutionReport 之间的映射。
+//! - `broker`：`FixBroker`，供 `BrokerGateway` 以 `fix` 后端类型登记。
+
+use okane_core::trade::port::TradeError;
+use thiserror::Error;
+
+pub mod broker;
+pub mod convert;
+pub mod message;
+pub mod session;
+pub mod store;
+
+/// # Summary
+/// FIX 报文与会话层的错误。
+#[derive(Error, Debug)]
+pub enum FixError {
+    #[error("malformed fix message: {0}")]
+    Malformed(String),
+    #[error("missing required field {0}")]
+    MissingField(u32),
+    #[error("invalid value of field {0}: {1}")]
+    InvalidField(u32, String),
+    #[error("fix session error: {0}")]
+    Session(String),
+    #[error("fix io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<FixError> for TradeError {
+    fn from(e: FixError) -> Self {
+        TradeError::BrokerIntegrationError(e.to_string())
+    }
+}
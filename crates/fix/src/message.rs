@@ -0,0 +1,360 @@
+//! # FIX 报文编解码
+//!
+//! 以 `tag=value<SOH>` 的形式编码 FIX 4.4 报文，负责 BodyLength(9) 与 CheckSum(10) 的计算与校验，
+//! 以及从 TCP 字节流中切分完整报文。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use crate::FixError;
+
+/// 字段分隔符
+pub const SOH: u8 = 0x01;
+
+/// 协议版本 BeginString(8)
+pub const BEGIN_STRING: &str = "FIX.4.4";
+
+/// UTCTimestamp 字段格式 (毫秒精度)
+const UTC_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H:%M:%S%.3f";
+
+/// 本 crate 使用的字段标签。
+pub mod tags {
+    pub const AVG_PX: u32 = 6;
+    pub const BEGIN_SEQ_NO: u32 = 7;
+    pub const BEGIN_STRING: u32 = 8;
+    pub const BODY_LENGTH: u32 = 9;
+    pub const CHECK_SUM: u32 = 10;
+    pub const CL_ORD_ID: u32 = 11;
+    pub const COMMISSION: u32 = 12;
+    pub const CUM_QTY: u32 = 14;
+    pub const END_SEQ_NO: u32 = 16;
+    pub const EXEC_ID: u32 = 17;
+    pub const LAST_PX: u32 = 31;
+    pub const LAST_QTY: u32 = 32;
+    pub const MSG_SEQ_NUM: u32 = 34;
+    pub const MSG_TYPE: u32 = 35;
+    pub const NEW_SEQ_NO: u32 = 36;
+    pub const ORDER_ID: u32 = 37;
+    pub const ORDER_QTY: u32 = 38;
+    pub const ORD_STATUS: u32 = 39;
+    pub const ORD_TYPE: u32 = 40;
+    pub const ORIG_CL_ORD_ID: u32 = 41;
+    pub const POSS_DUP_FLAG: u32 = 43;
+    pub const PRICE: u32 = 44;
+    pub const SENDER_COMP_ID: u32 = 49;
+    pub const SENDING_TIME: u32 = 52;
+    pub const SIDE: u32 = 54;
+    pub const SYMBOL: u32 = 55;
+    pub const TARGET_COMP_ID: u32 = 56;
+    pub const TEXT: u32 = 58;
+    pub const TIME_IN_FORCE: u32 = 59;
+    pub const TRANSACT_TIME: u32 = 60;
+    pub const ENCRYPT_METHOD: u32 = 98;
+    pub const HEART_BT_INT: u32 = 108;
+    pub const TEST_REQ_ID: u32 = 112;
+    pub const RESET_SEQ_NUM_FLAG: u32 = 141;
+    pub const ORIG_SENDING_TIME: u32 = 122;
+    pub const GAP_FILL_FLAG: u32 = 123;
+    pub const EXPIRE_TIME: u32 = 126;
+    pub const EXEC_TYPE: u32 = 150;
+    pub const LEAVES_QTY: u32 = 151;
+}
+
+/// 本 crate 使用的报文类型 MsgType(35)。
+pub mod msg_type {
+    pub const HEARTBEAT: &str = "0";
+    pub const TEST_REQUEST: &str = "1";
+    pub const RESEND_REQUEST: &str = "2";
+    pub const REJECT: &str = "3";
+    pub const SEQUENCE_RESET: &str = "4";
+    pub const LOGOUT: &str = "5";
+    pub const EXECUTION_REPORT: &str = "8";
+    pub const ORDER_CANCEL_REJECT: &str = "9";
+    pub const LOGON: &str = "A";
+    pub const NEW_ORDER_SINGLE: &str = "D";
+    pub const ORDER_CANCEL_REQUEST: &str = "F";
+    pub const ORDER_STATUS_REQUEST: &str = "H";
+
+    /// 会话层报文不参与重发，重发时以 SequenceReset-GapFill 跳过。
+    pub fn is_admin(msg_type: &str) -> bool {
+        matches!(
+            msg_type,
+            HEARTBEAT | TEST_REQUEST | RESEND_REQUEST | REJECT | SEQUENCE_RESET | LOGOUT | LOGON
+        )
+    }
+}
+
+/// 按 UTCTimestamp 格式输出时间。
+pub fn format_utc_timestamp(time: DateTime<Utc>) -> String {
+    time.format(UTC_TIMESTAMP_FORMAT).to_string()
+}
+
+/// 解析 UTCTimestamp 字段 (秒或毫秒精度)。
+pub fn parse_utc_timestamp(value: &str) -> Result<DateTime<Utc>, FixError> {
+    chrono::NaiveDateTime::parse_from_str(value, "%Y%m%d-%H:%M:%S%.f")
+        .map(|t| t.and_utc())
+        .map_err(|e| FixError::InvalidField(tags::TRANSACT_TIME, format!("{}: {}", value, e)))
+}
+
+/// # Summary
+/// 一条 FIX 报文：MsgType(35) 及其后的全部字段，按写入顺序保存。
+///
+/// # Invariants
+/// - 第一个字段恒为 MsgType(35)；BeginString(8)、BodyLength(9) 与 CheckSum(10) 只在编解码时出现。
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixMessage {
+    fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    pub fn new(msg_type: &str) -> Self {
+        Self {
+            fields: vec![(tags::MSG_TYPE, msg_type.to_string())],
+        }
+    }
+
+    /// 追加一个字段。
+    pub fn with(mut self, tag: u32, value: impl ToString) -> Self {
+        self.set(tag, value);
+        self
+    }
+
+    /// 设置字段：已存在时原位覆盖，否则追加。
+    pub fn set(&mut self, tag: u32, value: impl ToString) {
+        let value = value.to_string();
+        match self.fields.iter_mut().find(|(t, _)| *t == tag) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((tag, value)),
+        }
+    }
+
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 读取必填字段。
+    pub fn require(&self, tag: u32) -> Result<&str, FixError> {
+        self.get(tag).ok_or(FixError::MissingField(tag))
+    }
+
+    pub fn msg_type(&self) -> &str {
+        self.get(tags::MSG_TYPE).unwrap_or_default()
+    }
+
+    pub fn seq_num(&self) -> Result<u64, FixError> {
+        self.get_u64(tags::MSG_SEQ_NUM)?
+            .ok_or(FixError::MissingField(tags::MSG_SEQ_NUM))
+    }
+
+    pub fn get_u64(&self, tag: u32) -> Result<Option<u64>, FixError> {
+        self.get(tag)
+            .map(|v| {
+                v.parse::<u64>()
+                    .map_err(|e| FixError::InvalidField(tag, format!("{}: {}", v, e)))
+            })
+            .transpose()
+    }
+
+    pub fn get_decimal(&self, tag: u32) -> Result<Option<Decimal>, FixError> {
+        self.get(tag)
+            .map(|v| {
+                Decimal::from_str(v)
+                    .map_err(|e| FixError::InvalidField(tag, format!("{}: {}", v, e)))
+            })
+            .transpose()
+    }
+
+    /// Boolean 字段是否为 `Y`。
+    pub fn is_flag_set(&self, tag: u32) -> bool {
+        self.get(tag) == Some("Y")
+    }
+
+    /// # Logic
+    /// 写入会话头：SenderCompID(49)、TargetCompID(56)、MsgSeqNum(34) 与 SendingTime(52)
+    /// 紧随 MsgType(35) 之后，已存在的同名字段被移除。
+    pub fn stamp_header(&mut self, sender: &str, target: &str, seq_num: u64, sending_time: &str) {
+        self.fields.retain(|(tag, _)| {
+            !matches!(
+                *tag,
+                tags::SENDER_COMP_ID
+                    | tags::TARGET_COMP_ID
+                    | tags::MSG_SEQ_NUM
+                    | tags::SENDING_TIME
+            )
+        });
+        let header = [
+            (tags::SENDER_COMP_ID, sender.to_string()),
+            (tags::TARGET_COMP_ID, target.to_string()),
+            (tags::MSG_SEQ_NUM, seq_num.to_string()),
+            (tags::SENDING_TIME, sending_time.to_string()),
+        ];
+        let at = usize::from(!self.fields.is_empty());
+        self.fields.splice(at..at, header);
+    }
+
+    /// # Logic
+    /// 标记为重发报文：在会话头之后写入 PossDupFlag(43)=Y 与 OrigSendingTime(122)。
+    pub fn mark_poss_dup(&mut self, orig_sending_time: &str) {
+        self.fields
+            .retain(|(tag, _)| !matches!(*tag, tags::POSS_DUP_FLAG | tags::ORIG_SENDING_TIME));
+        let at = self
+            .fields
+            .iter()
+            .position(|(tag, _)| *tag == tags::SENDING_TIME)
+            .map_or(self.fields.len(), |i| i + 1);
+        let flags = [
+            (tags::POSS_DUP_FLAG, "Y".to_string()),
+            (tags::ORIG_SENDING_TIME, orig_sending_time.to_string()),
+        ];
+        self.fields.splice(at..at, flags);
+    }
+
+    /// # Logic
+    /// 编码为完整报文：`8=FIX.4.4|9=<body 字节数>|<body>|10=<校验和>|`，
+    /// 校验和为 CheckSum 字段之前全部字节之和对 256 取模，按三位数字输出。
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (tag, value) in &self.fields {
+            body.extend_from_slice(format!("{}={}", tag, value).as_bytes());
+            body.push(SOH);
+        }
+        let mut out = format!("8={}\u{1}9={}\u{1}", BEGIN_STRING, body.len()).into_bytes();
+        out.extend_from_slice(&body);
+        let checksum = checksum(&out);
+        out.extend_from_slice(format!("10={:03}\u{1}", checksum).as_bytes());
+        out
+    }
+
+    /// # Logic
+    /// 解码一条完整报文，校验 BeginString、BodyLength 与 CheckSum。
+    ///
+    /// # Returns
+    /// * `Err(FixError::Malformed)` - If the frame structure, length or checksum is invalid.
+    pub fn decode(frame: &[u8]) -> Result<Self, FixError> {
+        let text = std::str::from_utf8(frame)
+            .map_err(|e| FixError::Malformed(format!("non utf-8 frame: {}", e)))?;
+        let mut fields = Vec::new();
+        for part in text.split('\u{1}').filter(|p| !p.is_empty()) {
+            let (tag, value) = part
+                .split_once('=')
+                .ok_or_else(|| FixError::Malformed(format!("field without '=': {}", part)))?;
+            let tag = tag
+                .parse::<u32>()
+                .map_err(|_| FixError::Malformed(format!("invalid tag: {}", tag)))?;
+            fields.push((tag, value.to_string()));
+        }
+
+        match fields.first() {
+            Some((tags::BEGIN_STRING, version)) if version == BEGIN_STRING => {}
+            _ => return Err(FixError::Malformed("unexpected begin string".to_string())),
+        }
+        let Some((tags::CHECK_SUM, expected)) = fields.last() else {
+            return Err(FixError::Malformed(
+                "checksum must be the last field".to_string(),
+            ));
+        };
+        let checksum_at = frame.len().saturating_sub(7);
+        let actual = format!("{:03}", checksum(&frame[..checksum_at]));
+        if *expected != actual {
+            return Err(FixError::Malformed(format!(
+                "checksum mismatch: expected {}, actual {}",
+                expected, actual
+            )));
+        }
+        if fields.get(2).map(|(tag, _)| *tag) != Some(tags::MSG_TYPE) {
+            return Err(FixError::Malformed(
+                "msg type must follow body length".to_string(),
+            ));
+        }
+
+        let len = fields.len();
+        Ok(Self {
+            fields: fields.drain(2..len - 1).collect(),
+        })
+    }
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |acc, b| acc.wrapping_add(*b))
+}
+
+/// # Logic
+/// 从接收缓冲区头部切出一条完整报文并从缓冲区移除；数据不足时返回 `None`。
+/// 报文边界由 BodyLength(9) 决定：body 之后紧跟 7 字节的 `10=nnn<SOH>`。
+///
+/// # Returns
+/// * `Err(FixError::Malformed)` - If the buffer does not start with a valid header.
+pub fn take_frame(buf: &mut Vec<u8>) -> Result<Option<Vec<u8>>, FixError> {
+    let Some(first_soh) = buf.iter().position(|b| *b == SOH) else {
+        return Ok(None);
+    };
+    if !buf.starts_with(b"8=") {
+        return Err(FixError::Malformed(
+            "frame must start with begin string".to_string(),
+        ));
+    }
+    let rest = &buf[first_soh + 1..];
+    let Some(len_end) = rest.iter().position(|b| *b == SOH) else {
+        return Ok(None);
+    };
+    let length_field = std::str::from_utf8(&rest[..len_end])
+        .map_err(|e| FixError::Malformed(format!("invalid body length: {}", e)))?;
+    let body_len = length_field
+        .strip_prefix("9=")
+        .and_then(|v| v.parse::<usize>().ok())
+        .ok_or_else(|| FixError::Malformed(format!("invalid body length: {}", length_field)))?;
+
+    let frame_len = first_soh + 1 + len_end + 1 + body_len + 7;
+    if buf.len() < frame_len {
+        return Ok(None);
+    }
+    Ok(Some(buf.drain(..frame_len).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_round_trip() -> Result<(), FixError> {
+        let mut msg = FixMessage::new(msg_type::HEARTBEAT).with(tags::TEST_REQ_ID, "T1");
+        msg.stamp_header("OKANE", "BROKER", 7, "20240102-03:04:05.678");
+        let bytes = msg.encode();
+        let text = String::from_utf8_lossy(&bytes).replace('\u{1}', "|");
+        assert!(text.starts_with("8=FIX.4.4|9="));
+        assert!(text.contains("|35=0|49=OKANE|56=BROKER|34=7|52=20240102-03:04:05.678|112=T1|10="));
+
+        let decoded = FixMessage::decode(&bytes)?;
+        assert_eq!(decoded, msg);
+        assert_eq!(decoded.seq_num()?, 7);
+
+        let mut corrupted = bytes.clone();
+        if let Some(b) = corrupted.iter_mut().find(|b| **b == b'T') {
+            *b = b'X';
+        }
+        assert!(FixMessage::decode(&corrupted).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_take_frame_splits_stream() -> Result<(), FixError> {
+        let first = FixMessage::new(msg_type::HEARTBEAT).encode();
+        let second = FixMessage::new(msg_type::LOGOUT)
+            .with(tags::TEXT, "bye")
+            .encode();
+        let mut buf = first.clone();
+        buf.extend_from_slice(&second[..5]);
+
+        assert_eq!(take_frame(&mut buf)?, Some(first));
+        assert_eq!(take_frame(&mut buf)?, None);
+        buf.extend_from_slice(&second[5..]);
+        let frame = take_frame(&mut buf)?.ok_or(FixError::Malformed("missing".to_string()))?;
+        assert_eq!(FixMessage::decode(&frame)?.get(tags::TEXT), Some("bye"));
+        assert!(buf.is_empty());
+        Ok(())
+    }
+}
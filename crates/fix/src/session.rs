@@ -0,0 +1,519 @@
+//! # FIX 会话层
+//!
+//! 发起方会话：建立 TCP 连接后发送 Logon 并等待对端确认，按心跳间隔发送 Heartbeat，
+//! 超过两个心跳间隔未收到任何报文时断开。收到的报文按 MsgSeqNum 校验：
+//! - 序号大于期望值：发出 ResendRequest，缺口补齐前丢弃后续报文 (Logon 与 ResendRequest 仍然处理)。
+//! - 序号小于期望值且非 PossDup：序号错乱，发送 Logout 后断开。
+//! - 对端的 ResendRequest：从发送日志重发业务报文 (PossDupFlag=Y)，会话层报文以 SequenceReset-GapFill 跳过。
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use okane_core::config::FixConfig;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::sync::oneshot;
+
+use crate::FixError;
+use crate::message::{FixMessage, format_utc_timestamp, msg_type, tags, take_frame};
+use crate::store::FileSequenceStore;
+
+/// 等待对端 Logon 确认的超时
+const LOGON_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// # Summary
+/// 会话层向上交付业务报文的回调。
+pub trait ApplicationHandler: Send + Sync {
+    /// 按序号顺序交付的业务报文，包括对端重发的报文。
+    fn on_message(&self, msg: &FixMessage);
+
+    /// 会话断开 (对端关闭、心跳超时或序号错乱)。
+    fn on_disconnect(&self);
+
+    /// 会话重置时是否将该报文保留到下一轮 (如仍在途订单的委托与回报)，缺省不保留。
+    fn retain_on_reset(&self, _msg: &FixMessage) -> bool {
+        false
+    }
+}
+
+/// # Summary
+/// FIX 4.4 发起方会话。
+///
+/// # Invariants
+/// - 发送序号在持有写锁期间分配，报文在连接上的顺序与序号一致；序号按预留步长批量落盘。
+/// - 业务报文先写入发送日志再写入连接。写入连接失败的报文标记为未发送并断开连接，
+///   对端的重发请求以 GapFill 跳过该序号，已判定失败的委托不会被补发执行；
+///   进程在两步之间中断时报文视为已发送，由上层对账确认其状态。
+/// - 每次连接对应一个代次 (`generation`)，旧连接的读取与心跳任务发现代次变化后退出。
+pub struct FixSession {
+    config: FixConfig,
+    store: Arc<FileSequenceStore>,
+    handler: Arc<dyn ApplicationHandler>,
+    writer: tokio::sync::Mutex<Option<OwnedWriteHalf>>,
+    connect_lock: tokio::sync::Mutex<()>,
+    logged_on: AtomicBool,
+    generation: AtomicU64,
+    state: Mutex<SessionState>,
+}
+
+struct SessionState {
+    last_received: Instant,
+    /// 已发出的 ResendRequest 覆盖到的序号，缺口补齐前不重复请求
+    resend_until: Option<u64>,
+    logon_waiter: Option<oneshot::Sender<()>>,
+}
+
+impl FixSession {
+    pub fn new(
+        config: FixConfig,
+        store: Arc<FileSequenceStore>,
+        handler: Arc<dyn ApplicationHandler>,
+    ) -> Self {
+        Self {
+            config,
+            store,
+            handler,
+            writer: tokio::sync::Mutex::new(None),
+            connect_lock: tokio::sync::Mutex::new(()),
+            logged_on: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            state: Mutex::new(SessionState {
+                last_received: Instant::now(),
+                resend_until: None,
+                logon_waiter: None,
+            }),
+        }
+    }
+
+    pub fn store(&self) -> &Arc<FileSequenceStore> {
+        &self.store
+    }
+
+    pub fn is_logged_on(&self) -> bool {
+        self.logged_on.load(Ordering::SeqCst)
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<'_, SessionState>, FixError> {
+        self.state
+            .lock()
+            .map_err(|e| FixError::Session(format!("session lock poisoned: {}", e)))
+    }
+
+    fn heartbeat_interval(&self) -> Duration {
+        Duration::from_secs(self.config.heartbeat_interval_secs.max(1))
+    }
+
+    /// # Logic
+    /// 未登录时建立连接并完成登录：发送 Logon(HeartBtInt) 后等待对端 Logon 确认，
+    /// 随后启动心跳任务。已登录时直接返回。
+    /// 配置了 `reset_on_logon` 时先重置会话存储，Logon 附带 ResetSeqNumFlag=Y。
+    ///
+    /// # Returns
+    /// * `Err(FixError::Io)` - If the connection cannot be established.
+    /// * `Err(FixError::Session)` - If the counterparty does not confirm the logon in time.
+    pub async fn connect(self: &Arc<Self>) -> Result<(), FixError> {
+        let _guard = self.connect_lock.lock().await;
+        if self.is_logged_on() {
+            return Ok(());
+        }
+
+        let stream = TcpStream::connect((self.config.host.as_str(), self.config.port)).await?;
+        let (read, write) = stream.into_split();
+        if self.config.reset_on_logon {
+            let handler = self.handler.clone();
+            self.store.reset(|msg| handler.retain_on_reset(msg)).await?;
+        }
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        *self.writer.lock().await = Some(write);
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut state = self.lock_state()?;
+            state.last_received = Instant::now();
+            state.resend_until = None;
+            state.logon_waiter = Some(tx);
+        }
+
+        let session = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = session.read_loop(read, generation).await {
+                tracing::warn!("FIX session read loop stopped: {}", e);
+            }
+            session.disconnect(generation).await;
+        });
+
+        let mut logon = FixMessage::new(msg_type::LOGON)
+            .with(tags::ENCRYPT_METHOD, 0)
+            .with(tags::HEART_BT_INT, self.heartbeat_interval().as_secs());
+        if self.config.reset_on_logon {
+            logon.set(tags::RESET_SEQ_NUM_FLAG, "Y");
+        }
+        if let Err(e) = self.send(logon).await {
+            self.disconnect(generation).await;
+            return Err(e);
+        }
+        match tokio::time::timeout(LOGON_TIMEOUT, rx).await {
+            Ok(Ok(())) => {}
+            _ => {
+                self.disconnect(generation).await;
+                return Err(FixError::Session("logon was not confirmed".to_string()));
+            }
+        }
+
+        let session = self.clone();
+        tokio::spawn(async move { session.heartbeat_loop(generation).await });
+        tracing::info!(
+            "FIX session {}-{} logged on",
+            self.config.sender_comp_id,
+            self.config.target_comp_id
+        );
+        Ok(())
+    }
+
+    /// 发送 Logout 并断开连接。
+    pub async fn logout(&self) -> Result<(), FixError> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        let result = self.send(FixMessage::new(msg_type::LOGOUT)).await;
+        self.disconnect(generation).await;
+        result
+    }
+
+    /// # Logic
+    /// 发送业务报文。
+    ///
+    /// # Returns
+    /// * `Err(FixError::Session)` - If the session is not logged on.
+    pub async fn send_app(&self, msg: FixMessage) -> Result<(), FixError> {
+        if !self.is_logged_on() {
+            return Err(FixError::Session("session is not logged on".to_string()));
+        }
+        self.send(msg).await
+    }
+
+    /// # Logic
+    /// 分配发送序号、写入会话头并发送；业务报文同时写入发送日志。
+    /// 写入连接失败时业务报文标记为未发送，并断开连接 (半写的报文之后不能再续写)。
+    async fn send(&self, mut msg: FixMessage) -> Result<(), FixError> {
+        let mut writer = self.writer.lock().await;
+        let generation = self.generation.load(Ordering::SeqCst);
+        let Some(stream) = writer.as_mut() else {
+            return Err(FixError::Session("session is not connected".to_string()));
+        };
+        let seq = self.store.next_sender_seq().await;
+        msg.stamp_header(
+            &self.config.sender_comp_id,
+            &self.config.target_comp_id,
+            seq,
+            &format_utc_timestamp(Utc::now()),
+        );
+        let frame = msg.encode();
+        let journaled = !msg_type::is_admin(msg.msg_type());
+        if journaled {
+            self.store.append_sent(&frame).await?;
+        }
+        self.store.set_next_sender_seq(seq + 1).await?;
+        let Err(e) = stream.write_all(&frame).await else {
+            return Ok(());
+        };
+        drop(writer);
+
+        if journaled && let Err(mark_err) = self.store.mark_unsent(seq).await {
+            tracing::error!("Failed to mark FIX message {} as unsent: {}", seq, mark_err);
+        }
+        self.disconnect(generation).await;
+        Err(e.into())
+    }
+
+    /// 原样写出已编号的报文 (重发与 GapFill)，不分配新序号。
+    async fn send_raw(&self, frame: &[u8]) -> Result<(), FixError> {
+        let mut writer = self.writer.lock().await;
+        let Some(stream) = writer.as_mut() else {
+            return Err(FixError::Session("session is not connected".to_string()));
+        };
+        stream.write_all(frame).await?;
+        Ok(())
+    }
+
+    /// # Logic
+    /// 结束指定代次的连接：关闭写半部并通知上层；代次已过期时不做任何事，保证只通知一次。
+    async fn disconnect(&self, generation: u64) {
+        if self
+            .generation
+            .compare_exchange(
+                generation,
+                generation + 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_err()
+        {
+            return;
+        }
+        self.logged_on.store(false, Ordering::SeqCst);
+        if let Some(mut stream) = self.writer.lock().await.take()
+            && let Err(e) = stream.shutdown().await
+        {
+            tracing::debug!("FIX connection shutdown failed: {}", e);
+        }
+        tracing::warn!(
+            "FIX session {}-{} disconnected",
+            self.config.sender_comp_id,
+            self.config.target_comp_id
+        );
+        self.handler.on_disconnect();
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    async fn read_loop(&self, mut read: OwnedReadHalf, generation: u64) -> Result<(), FixError> {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = read.read(&mut chunk).await?;
+            if n == 0 || !self.is_current(generation) {
+                return Ok(());
+            }
+            buf.extend_from_slice(&chunk[..n]);
+            while let Some(frame) = take_frame(&mut buf)? {
+                let msg = FixMessage::decode(&frame)?;
+                self.lock_state()?.last_received = Instant::now();
+                if !self.handle_incoming(&msg, &frame).await? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// # Logic
+    /// 每个心跳间隔发送一次 Heartbeat；超过一个间隔未收到报文时附带 TestRequest，
+    /// 超过两个间隔时判定连接失效并断开。
+    async fn heartbeat_loop(&self, generation: u64) {
+        let interval = self.heartbeat_interval();
+        loop {
+            tokio::time::sleep(interval).await;
+            if !self.is_current(generation) {
+                return;
+            }
+            let silence = match self.lock_state() {
+                Ok(state) => state.last_received.elapsed(),
+                Err(e) => {
+                    tracing::warn!("FIX heartbeat stopped: {}", e);
+                    return;
+                }
+            };
+            if silence > interval * 2 {
+                tracing::warn!("FIX counterparty silent for {:?}, disconnecting", silence);
+                self.disconnect(generation).await;
+                return;
+            }
+            let result = if silence > interval {
+                let test_req_id = format!("TEST-{}", Utc::now().timestamp_millis());
+                self.send(
+                    FixMessage::new(msg_type::TEST_REQUEST).with(tags::TEST_REQ_ID, test_req_id),
+                )
+                .await
+            } else {
+                self.send(FixMessage::new(msg_type::HEARTBEAT)).await
+            };
+            if let Err(e) = result {
+                tracing::warn!("FIX heartbeat failed: {}", e);
+                self.disconnect(generation).await;
+                return;
+            }
+        }
+    }
+
+    fn complete_logon(&self) -> Result<(), FixError> {
+        self.logged_on.store(true, Ordering::SeqCst);
+        if let Some(waiter) = self.lock_state()?.logon_waiter.take()
+            && waiter.send(()).is_err()
+        {
+            tracing::debug!("FIX logon confirmed after the waiter gave up");
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 处理一条收到的报文。
+    ///
+    /// # Returns
+    /// * `Ok(false)` - If the session has ended (Logout), the read loop should stop.
+    async fn handle_incoming(&self, msg: &FixMessage, frame: &[u8]) -> Result<bool, FixError> {
+        let seq = msg.seq_num()?;
+        let expected = self.store.next_target_seq().await;
+        let kind = msg.msg_type();
+
+        if kind == msg_type::SEQUENCE_RESET && !msg.is_flag_set(tags::GAP_FILL_FLAG) {
+            // Reset 模式不受序号约束，只允许前移
+            let new_seq = msg
+                .get_u64(tags::NEW_SEQ_NO)?
+                .ok_or(FixError::MissingField(tags::NEW_SEQ_NO))?;
+            if new_seq > expected {
+                self.store.set_next_target_seq(new_seq, true).await?;
+            }
+            return Ok(true);
+        }
+
+        if seq > expected {
+            match kind {
+                msg_type::LOGON => self.complete_logon()?,
+                msg_type::RESEND_REQUEST => self.resend(msg).await?,
+                _ => {}
+            }
+            self.request_resend(expected, seq).await?;
+            return Ok(true);
+        }
+
+        if seq < expected {
+            if msg.is_flag_set(tags::POSS_DUP_FLAG) {
+                return Ok(true);
+            }
+            let text = format!(
+                "MsgSeqNum too low, expecting {} but received {}",
+                expected, seq
+            );
+            if let Err(e) = self
+                .send(FixMessage::new(msg_type::LOGOUT).with(tags::TEXT, &text))
+                .await
+            {
+                tracing::warn!("Failed to send FIX logout: {}", e);
+            }
+            return Err(FixError::Session(text));
+        }
+
+        if kind == msg_type::SEQUENCE_RESET {
+            let new_seq = msg
+                .get_u64(tags::NEW_SEQ_NO)?
+                .ok_or(FixError::MissingField(tags::NEW_SEQ_NO))?;
+            self.advance_target_seq(new_seq.max(seq + 1), true).await?;
+            return Ok(true);
+        }
+        self.advance_target_seq(seq + 1, !msg_type::is_admin(kind))
+            .await?;
+
+        match kind {
+            msg_type::LOGON => self.complete_logon()?,
+            msg_type::HEARTBEAT => {}
+            msg_type::TEST_REQUEST => {
+                let mut heartbeat = FixMessage::new(msg_type::HEARTBEAT);
+                if let Some(test_req_id) = msg.get(tags::TEST_REQ_ID) {
+                    heartbeat.set(tags::TEST_REQ_ID, test_req_id);
+                }
+                self.send(heartbeat).await?;
+            }
+            msg_type::RESEND_REQUEST => self.resend(msg).await?,
+            msg_type::REJECT => {
+                tracing::warn!(
+                    "FIX session reject: {}",
+                    msg.get(tags::TEXT).unwrap_or_default()
+                );
+            }
+            msg_type::LOGOUT => {
+                if self.is_logged_on()
+                    && let Err(e) = self.send(FixMessage::new(msg_type::LOGOUT)).await
+                {
+                    tracing::warn!("Failed to confirm FIX logout: {}", e);
+                }
+                return Ok(false);
+            }
+            _ => {
+                self.store.append_received(frame).await?;
+                self.handler.on_message(msg);
+            }
+        }
+        Ok(true)
+    }
+
+    /// 更新期望接收序号，越过已请求的缺口后允许再次发出重发请求；
+    /// 会话层报文推进的序号不落盘 (`durable` 为 false)。
+    async fn advance_target_seq(&self, next: u64, durable: bool) -> Result<(), FixError> {
+        self.store.set_next_target_seq(next, durable).await?;
+        let mut state = self.lock_state()?;
+        if state.resend_until.is_some_and(|until| next > until) {
+            state.resend_until = None;
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 请求对端重发 `[expected, 最新]` (EndSeqNo=0)；已有未完成的重发请求时不重复发送。
+    async fn request_resend(&self, expected: u64, received: u64) -> Result<(), FixError> {
+        {
+            let mut state = self.lock_state()?;
+            if state.resend_until.is_some() {
+                return Ok(());
+            }
+            state.resend_until = Some(received);
+        }
+        tracing::info!(
+            "FIX sequence gap detected, expecting {} but received {}",
+            expected,
+            received
+        );
+        self.send(
+            FixMessage::new(msg_type::RESEND_REQUEST)
+                .with(tags::BEGIN_SEQ_NO, expected)
+                .with(tags::END_SEQ_NO, 0),
+        )
+        .await
+    }
+
+    /// # Logic
+    /// 应答对端的 ResendRequest：按原序号重发日志中的业务报文并标记 PossDup，
+    /// 日志中缺失的序号 (会话层报文) 以 SequenceReset-GapFill 跳过。
+    async fn resend(&self, request: &FixMessage) -> Result<(), FixError> {
+        let begin = request
+            .get_u64(tags::BEGIN_SEQ_NO)?
+            .ok_or(FixError::MissingField(tags::BEGIN_SEQ_NO))?
+            .max(1);
+        let last_sent = self.store.next_sender_seq().await.saturating_sub(1);
+        let end = match request.get_u64(tags::END_SEQ_NO)? {
+            Some(end) if end != 0 && end < last_sent => end,
+            _ => last_sent,
+        };
+        if begin > end {
+            return Ok(());
+        }
+
+        let mut next = begin;
+        for mut msg in self.store.sent_messages(begin, end).await? {
+            let seq = msg.seq_num()?;
+            if seq > next {
+                self.send_gap_fill(next, seq).await?;
+            }
+            let orig_sending_time = msg.get(tags::SENDING_TIME).unwrap_or_default().to_string();
+            msg.stamp_header(
+                &self.config.sender_comp_id,
+                &self.config.target_comp_id,
+                seq,
+                &format_utc_timestamp(Utc::now()),
+            );
+            msg.mark_poss_dup(&orig_sending_time);
+            self.send_raw(&msg.encode()).await?;
+            next = seq + 1;
+        }
+        if next <= end {
+            self.send_gap_fill(next, end + 1).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_gap_fill(&self, seq: u64, new_seq: u64) -> Result<(), FixError> {
+        let now = format_utc_timestamp(Utc::now());
+        let mut msg = FixMessage::new(msg_type::SEQUENCE_RESET)
+            .with(tags::GAP_FILL_FLAG, "Y")
+            .with(tags::NEW_SEQ_NO, new_seq);
+        msg.stamp_header(
+            &self.config.sender_comp_id,
+            &self.config.target_comp_id,
+            seq,
+            &now,
+        );
+        msg.mark_poss_dup(&now);
+        self.send_raw(&msg.encode()).await
+    }
+}
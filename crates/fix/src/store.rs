@@ -0,0 +1,348 @@
+//! # 会话序号与报文日志
+//!
+//! 每个会话 (`SenderCompID-TargetCompID`) 在存储目录下对应以下文件：
+//! - `<session>.seqnums`：下一个发送与期望接收的序号。
+//! - `<session>.sent`：已发送的业务报文，用于应答对端的重发请求及进程重启后恢复在途订单。
+//! - `<session>.received`：已接收的业务报文，用于进程重启后恢复订单的累计成交与手续费。
+//! - `<session>.unsent`：已写入发送日志但未能写出连接的业务报文序号，重发时以 GapFill 跳过。
+//! - `<session>.carried`：会话重置时从上一轮日志中保留的报文 (仍在途订单的委托与回报)。
+//!
+//! 会话重置时上一轮的日志移入 `archive/` 子目录，日志大小以一轮会话为界。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::FixError;
+use crate::message::FixMessage;
+
+/// 发送序号的预留步长：落盘的是预留上界而非逐条序号，进程重启后从上界续接，
+/// 跳过的序号在对端请求重发时以 GapFill 补齐。
+const SENDER_SEQ_RESERVE: u64 = 100;
+
+/// # Summary
+/// 基于文件的 FIX 会话存储。
+///
+/// # Invariants
+/// - 序号先写临时文件再原子替换，进程中断不会留下半写的序号文件。
+/// - 落盘的发送序号不小于任何已使用的发送序号；落盘的接收序号不大于实际处理到的位置，
+///   重启后多出的部分由对端重发并按 PossDup 去重。
+/// - 报文日志只追加，每行一条完整报文。
+/// - 文件读写均为异步 I/O，序号与日志的写入在状态锁内串行执行。
+pub struct FileSequenceStore {
+    dir: PathBuf,
+    session: String,
+    seq_path: PathBuf,
+    sent_path: PathBuf,
+    received_path: PathBuf,
+    unsent_path: PathBuf,
+    carried_path: PathBuf,
+    state: Mutex<SequenceState>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SequenceState {
+    next_sender_seq: u64,
+    next_target_seq: u64,
+    /// 已落盘的发送序号上界
+    reserved_sender_seq: u64,
+}
+
+impl FileSequenceStore {
+    /// # Logic
+    /// 打开 (不存在时创建) 存储目录下的会话文件，并载入序号；新会话的两个序号均从 1 开始。
+    ///
+    /// # Returns
+    /// * `Err(FixError::Io)` - If the directory cannot be created or read.
+    /// * `Err(FixError::Malformed)` - If the sequence file is corrupted.
+    pub async fn open(
+        dir: &Path,
+        sender_comp_id: &str,
+        target_comp_id: &str,
+    ) -> Result<Self, FixError> {
+        tokio::fs::create_dir_all(dir).await?;
+        let session = format!("{}-{}", sender_comp_id, target_comp_id);
+        let seq_path = dir.join(format!("{}.seqnums", session));
+        let state = match tokio::fs::read_to_string(&seq_path).await {
+            Ok(content) => {
+                let mut parts = content.split_whitespace().map(str::parse::<u64>);
+                match (parts.next(), parts.next()) {
+                    (Some(Ok(next_sender_seq)), Some(Ok(next_target_seq))) => SequenceState {
+                        next_sender_seq,
+                        next_target_seq,
+                        reserved_sender_seq: next_sender_seq,
+                    },
+                    _ => {
+                        return Err(FixError::Malformed(format!(
+                            "corrupted sequence file {}",
+                            seq_path.display()
+                        )));
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => SequenceState {
+                next_sender_seq: 1,
+                next_target_seq: 1,
+                reserved_sender_seq: 1,
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            seq_path,
+            sent_path: dir.join(format!("{}.sent", session)),
+            received_path: dir.join(format!("{}.received", session)),
+            unsent_path: dir.join(format!("{}.unsent", session)),
+            carried_path: dir.join(format!("{}.carried", session)),
+            session,
+            state: Mutex::new(state),
+        })
+    }
+
+    async fn persist(&self, state: &SequenceState) -> Result<(), FixError> {
+        let tmp = self.seq_path.with_extension("seqnums.tmp");
+        tokio::fs::write(
+            &tmp,
+            format!("{} {}\n", state.reserved_sender_seq, state.next_target_seq),
+        )
+        .await?;
+        tokio::fs::rename(&tmp, &self.seq_path).await?;
+        Ok(())
+    }
+
+    pub async fn next_sender_seq(&self) -> u64 {
+        self.state.lock().await.next_sender_seq
+    }
+
+    pub async fn next_target_seq(&self) -> u64 {
+        self.state.lock().await.next_target_seq
+    }
+
+    /// # Logic
+    /// 更新下一个发送序号；越过已落盘的上界时才重新预留并落盘。
+    pub async fn set_next_sender_seq(&self, seq: u64) -> Result<(), FixError> {
+        let mut state = self.state.lock().await;
+        state.next_sender_seq = seq;
+        if seq > state.reserved_sender_seq {
+            state.reserved_sender_seq = seq.saturating_add(SENDER_SEQ_RESERVE);
+            self.persist(&state).await?;
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 更新期望接收序号。`durable` 为 false 时只更新内存 (会话层报文)，
+    /// 重启后由对端重发并以 GapFill 跳过，避免每条心跳都重写序号文件。
+    pub async fn set_next_target_seq(&self, seq: u64, durable: bool) -> Result<(), FixError> {
+        let mut state = self.state.lock().await;
+        state.next_target_seq = seq;
+        if durable {
+            self.persist(&state).await?;
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 重置会话：上一轮的发送、接收与未发送日志移入 `archive/`，满足 `retain` 的报文
+    /// (含上一次保留的报文) 写入新的保留文件，两个序号均回到 1。
+    ///
+    /// # Returns
+    /// * `Err(FixError::Io)` - If the logs cannot be archived or the carried file cannot be written.
+    pub async fn reset(&self, retain: impl Fn(&FixMessage) -> bool) -> Result<(), FixError> {
+        let mut state = self.state.lock().await;
+        let mut carried = Vec::new();
+        for msg in self.recovery_messages().await? {
+            if retain(&msg) {
+                carried.extend_from_slice(&msg.encode());
+                carried.push(b'\n');
+            }
+        }
+
+        let archive_dir = self.dir.join("archive");
+        tokio::fs::create_dir_all(&archive_dir).await?;
+        let stamp = Utc::now().format("%Y%m%d-%H%M%S%.3f");
+        for (path, ext) in [
+            (&self.sent_path, "sent"),
+            (&self.received_path, "received"),
+            (&self.unsent_path, "unsent"),
+        ] {
+            let archived = archive_dir.join(format!("{}-{}.{}", self.session, stamp, ext));
+            match tokio::fs::rename(path, &archived).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let tmp = self.carried_path.with_extension("carried.tmp");
+        tokio::fs::write(&tmp, carried).await?;
+        tokio::fs::rename(&tmp, &self.carried_path).await?;
+
+        *state = SequenceState {
+            next_sender_seq: 1,
+            next_target_seq: 1,
+            reserved_sender_seq: 1,
+        };
+        self.persist(&state).await
+    }
+
+    async fn append(path: &Path, frame: &[u8]) -> Result<(), FixError> {
+        let mut line = Vec::with_capacity(frame.len() + 1);
+        line.extend_from_slice(frame);
+        line.push(b'\n');
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn read_log(path: &Path) -> Result<Vec<FixMessage>, FixError> {
+        let content = match tokio::fs::read(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut messages = Vec::new();
+        for line in content.split(|b| *b == b'\n').filter(|l| !l.is_empty()) {
+            match FixMessage::decode(line) {
+                Ok(msg) => messages.push(msg),
+                Err(e) => tracing::warn!(
+                    "Skipped corrupted fix log entry in {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        Ok(messages)
+    }
+
+    /// 记录一条已发送的业务报文。
+    pub async fn append_sent(&self, frame: &[u8]) -> Result<(), FixError> {
+        Self::append(&self.sent_path, frame).await
+    }
+
+    /// 记录一条已接收的业务报文。
+    pub async fn append_received(&self, frame: &[u8]) -> Result<(), FixError> {
+        Self::append(&self.received_path, frame).await
+    }
+
+    /// 记录一条写入连接失败的业务报文序号，该报文不再视为已发送。
+    pub async fn mark_unsent(&self, seq: u64) -> Result<(), FixError> {
+        Self::append(&self.unsent_path, seq.to_string().as_bytes()).await
+    }
+
+    async fn unsent_seqs(&self) -> Result<HashSet<u64>, FixError> {
+        match tokio::fs::read_to_string(&self.unsent_path).await {
+            Ok(content) => Ok(content
+                .split_whitespace()
+                .filter_map(|seq| seq.parse().ok())
+                .collect()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashSet::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 本轮会话中序号位于 `[begin, end]` 的已发送业务报文，按序号升序；写入连接失败的报文不包含在内。
+    pub async fn sent_messages(&self, begin: u64, end: u64) -> Result<Vec<FixMessage>, FixError> {
+        let unsent = self.unsent_seqs().await?;
+        let mut messages: Vec<FixMessage> = Self::read_log(&self.sent_path)
+            .await?
+            .into_iter()
+            .filter(|msg| {
+                msg.seq_num()
+                    .is_ok_and(|seq| seq >= begin && seq <= end && !unsent.contains(&seq))
+            })
+            .collect();
+        messages.sort_by_key(|msg| msg.seq_num().ok());
+        Ok(messages)
+    }
+
+    /// 本轮会话中全部已接收的业务报文，按接收顺序。
+    pub async fn received_messages(&self) -> Result<Vec<FixMessage>, FixError> {
+        Self::read_log(&self.received_path).await
+    }
+
+    /// # Logic
+    /// 恢复订单簿所需的全部报文：先是历次重置保留的报文，再是本轮的已发送与已接收报文。
+    pub async fn recovery_messages(&self) -> Result<Vec<FixMessage>, FixError> {
+        let mut messages = Self::read_log(&self.carried_path).await?;
+        messages.extend(self.sent_messages(1, u64::MAX).await?);
+        messages.extend(self.received_messages().await?);
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{msg_type, tags};
+
+    fn new_order(cl_ord_id: &str, seq: u64) -> FixMessage {
+        let mut msg = FixMessage::new(msg_type::NEW_ORDER_SINGLE).with(tags::CL_ORD_ID, cl_ord_id);
+        msg.stamp_header("OKANE", "BROKER", seq, "20240102-03:04:05.000");
+        msg
+    }
+
+    #[tokio::test]
+    async fn test_sequence_numbers_survive_reopen() -> Result<(), FixError> {
+        let dir = tempfile::tempdir()?;
+        let store = FileSequenceStore::open(dir.path(), "OKANE", "BROKER").await?;
+        assert_eq!(store.next_sender_seq().await, 1);
+        store.set_next_sender_seq(5).await?;
+        store.set_next_target_seq(3, true).await?;
+        // 会话层报文推进的接收序号不落盘
+        store.set_next_target_seq(4, false).await?;
+        let msg = new_order("o1", 4);
+        store.append_sent(&msg.encode()).await?;
+        drop(store);
+
+        // 发送序号从预留上界续接，不会重用已发送的序号
+        let store = FileSequenceStore::open(dir.path(), "OKANE", "BROKER").await?;
+        assert_eq!(store.next_sender_seq().await, 5 + SENDER_SEQ_RESERVE);
+        assert_eq!(store.next_target_seq().await, 3);
+        assert_eq!(store.sent_messages(4, 4).await?, vec![msg]);
+        assert!(store.sent_messages(5, u64::MAX).await?.is_empty());
+
+        // 写出失败的报文不再出现在发送日志的查询结果中
+        store.mark_unsent(4).await?;
+        drop(store);
+        let store = FileSequenceStore::open(dir.path(), "OKANE", "BROKER").await?;
+        assert!(store.sent_messages(1, u64::MAX).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reset_archives_logs_and_carries_retained_messages() -> Result<(), FixError> {
+        let dir = tempfile::tempdir()?;
+        let store = FileSequenceStore::open(dir.path(), "OKANE", "BROKER").await?;
+        let open_order = new_order("open", 1);
+        store.append_sent(&open_order.encode()).await?;
+        store.append_sent(&new_order("done", 2).encode()).await?;
+        store.set_next_sender_seq(3).await?;
+        store.set_next_target_seq(7, true).await?;
+
+        store
+            .reset(|msg| msg.get(tags::CL_ORD_ID) == Some("open"))
+            .await?;
+        assert_eq!(store.next_sender_seq().await, 1);
+        assert_eq!(store.next_target_seq().await, 1);
+        assert!(store.sent_messages(1, u64::MAX).await?.is_empty());
+        assert_eq!(store.recovery_messages().await?, vec![open_order.clone()]);
+        let archived = std::fs::read_dir(dir.path().join("archive"))?.count();
+        assert_eq!(archived, 1);
+        drop(store);
+
+        let store = FileSequenceStore::open(dir.path(), "OKANE", "BROKER").await?;
+        assert_eq!(store.next_sender_seq().await, 1);
+        assert_eq!(store.next_target_seq().await, 1);
+        assert_eq!(store.recovery_messages().await?, vec![open_order]);
+        Ok(())
+    }
+}
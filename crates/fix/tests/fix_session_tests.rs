@@ -0,0 +1,748 @@
+use futures::StreamExt;
+use okane_core::common::time::RealTimeProvider;
+use okane_core::common::{Stock as StockIdentity, TimeFrame};
+use okane_core::config::FixConfig;
+use okane_core::market::entity::Candle;
+use okane_core::market::error::MarketError;
+use okane_core::market::port::{CandleStream, Market, Stock, StockStatus};
+use okane_core::store::port::SystemStore;
+use okane_core::trade::entity::{
+    AccountId, ExecutionReport, Order, OrderDirection, OrderId, OrderStatus,
+};
+use okane_core::trade::port::{BrokerPort, TradePort};
+use okane_fix::broker::FixBroker;
+use okane_fix::message::{FixMessage, format_utc_timestamp, msg_type, tags, take_frame};
+use okane_trade::account::AccountManager;
+use okane_trade::gateway::{BrokerGateway, BrokerRegistry};
+use okane_trade::router::RoutedTradePort;
+use okane_trade::service::TradeService;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, sleep, timeout};
+
+const ACCEPTOR: &str = "BROKER";
+const INITIATOR: &str = "OKANE";
+
+/// 进程内的 FIX 接收方桩：应答登录与心跳，按固定价格分两笔撮合穿价的限价单，
+/// 序号在重连之间保持，并可按要求丢弃一条业务报文以制造序号缺口。
+struct AcceptorStub {
+    addr: SocketAddr,
+    state: Arc<Mutex<StubState>>,
+}
+
+struct StubState {
+    next_seq: u64,
+    next_exec_id: u64,
+    fill_price: Decimal,
+    /// 每笔成交回报的手续费
+    commission: Decimal,
+    received: Vec<FixMessage>,
+    sent: BTreeMap<u64, FixMessage>,
+    orders: HashMap<String, StubOrder>,
+    drop_next_app: bool,
+    /// 状态查询应答使用的固定 ExecID (部分券商对 ExecType=I 恒发 "0")
+    status_exec_id: Option<String>,
+    writer: Option<mpsc::UnboundedSender<Vec<u8>>>,
+}
+
+struct StubOrder {
+    symbol: String,
+    side: String,
+    qty: Decimal,
+    cum_qty: Decimal,
+    ord_status: &'static str,
+}
+
+impl StubState {
+    fn send(&mut self, mut msg: FixMessage) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        msg.stamp_header(
+            ACCEPTOR,
+            INITIATOR,
+            seq,
+            &format_utc_timestamp(chrono::Utc::now()),
+        );
+        if !msg_type::is_admin(msg.msg_type()) {
+            self.sent.insert(seq, msg.clone());
+            if self.drop_next_app {
+                self.drop_next_app = false;
+                return;
+            }
+        }
+        self.write(msg.encode());
+    }
+
+    fn write(&mut self, frame: Vec<u8>) {
+        if let Some(writer) = &self.writer
+            && writer.send(frame).is_err()
+        {
+            self.writer = None;
+        }
+    }
+
+    fn execution_report(&mut self, cl_ord_id: &str, exec_type: &str) -> Option<FixMessage> {
+        let order = self.orders.get(cl_ord_id)?;
+        self.next_exec_id += 1;
+        Some(
+            FixMessage::new(msg_type::EXECUTION_REPORT)
+                .with(tags::ORDER_ID, format!("B-{}", cl_ord_id))
+                .with(tags::CL_ORD_ID, cl_ord_id)
+                .with(tags::EXEC_ID, format!("E{}", self.next_exec_id))
+                .with(tags::EXEC_TYPE, exec_type)
+                .with(tags::ORD_STATUS, order.ord_status)
+                .with(tags::SYMBOL, &order.symbol)
+                .with(tags::SIDE, &order.side)
+                .with(tags::LEAVES_QTY, order.qty - order.cum_qty)
+                .with(tags::CUM_QTY, order.cum_qty)
+                .with(
+                    tags::AVG_PX,
+                    if order.cum_qty > Decimal::ZERO {
+                        self.fill_price
+                    } else {
+                        Decimal::ZERO
+                    },
+                )
+                .with(
+                    tags::TRANSACT_TIME,
+                    format_utc_timestamp(chrono::Utc::now()),
+                ),
+        )
+    }
+
+    fn handle(&mut self, msg: FixMessage) {
+        self.received.push(msg.clone());
+        if msg.is_flag_set(tags::POSS_DUP_FLAG) {
+            return;
+        }
+        match msg.msg_type() {
+            msg_type::LOGON => {
+                if msg.is_flag_set(tags::RESET_SEQ_NUM_FLAG) {
+                    self.next_seq = 1;
+                    self.sent.clear();
+                }
+                let logon = FixMessage::new(msg_type::LOGON)
+                    .with(tags::ENCRYPT_METHOD, 0)
+                    .with(
+                        tags::HEART_BT_INT,
+                        msg.get(tags::HEART_BT_INT).unwrap_or("30"),
+                    );
+                self.send(logon);
+            }
+            msg_type::TEST_REQUEST => {
+                let heartbeat = FixMessage::new(msg_type::HEARTBEAT).with(
+                    tags::TEST_REQ_ID,
+                    msg.get(tags::TEST_REQ_ID).unwrap_or_default(),
+                );
+                self.send(heartbeat);
+            }
+            msg_type::RESEND_REQUEST => self.resend(&msg),
+            msg_type::NEW_ORDER_SINGLE => self.accept_order(&msg),
+            msg_type::ORDER_CANCEL_REQUEST => {
+                let cancel_id = msg.get(tags::CL_ORD_ID).unwrap_or_default().to_string();
+                let orig = msg
+                    .get(tags::ORIG_CL_ORD_ID)
+                    .unwrap_or_default()
+                    .to_string();
+                if let Some(order) = self.orders.get_mut(&orig) {
+                    order.ord_status = "4";
+                }
+                if let Some(report) = self.execution_report(&orig, "4") {
+                    self.send(
+                        report
+                            .with(tags::CL_ORD_ID, cancel_id)
+                            .with(tags::ORIG_CL_ORD_ID, orig),
+                    );
+                }
+            }
+            msg_type::ORDER_STATUS_REQUEST => {
+                let cl_ord_id = msg.get(tags::CL_ORD_ID).unwrap_or_default().to_string();
+                if let Some(report) = self.execution_report(&cl_ord_id, "I") {
+                    let report = match self.status_exec_id.clone() {
+                        Some(exec_id) => report.with(tags::EXEC_ID, exec_id),
+                        None => report,
+                    };
+                    self.send(report);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 受理委托；穿价的限价单以 `fill_price` 分两笔全部成交。
+    fn accept_order(&mut self, msg: &FixMessage) {
+        let cl_ord_id = msg.get(tags::CL_ORD_ID).unwrap_or_default().to_string();
+        let side = msg.get(tags::SIDE).unwrap_or_default().to_string();
+        let qty = msg
+            .get_decimal(tags::ORDER_QTY)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let price = msg.get_decimal(tags::PRICE).ok().flatten();
+        self.orders.insert(
+            cl_ord_id.clone(),
+            StubOrder {
+                symbol: msg.get(tags::SYMBOL).unwrap_or_default().to_string(),
+                side: side.clone(),
+                qty,
+                cum_qty: Decimal::ZERO,
+                ord_status: "0",
+            },
+        );
+        if let Some(report) = self.execution_report(&cl_ord_id, "0") {
+            self.send(report);
+        }
+
+        let crossed = match (side.as_str(), price) {
+            (_, None) => true,
+            ("1", Some(limit)) => self.fill_price <= limit,
+            (_, Some(limit)) => self.fill_price >= limit,
+        };
+        if !crossed {
+            return;
+        }
+        let half = qty / dec!(2);
+        for (fill, status) in [(half, "1"), (qty - half, "2")] {
+            if let Some(order) = self.orders.get_mut(&cl_ord_id) {
+                order.cum_qty += fill;
+                order.ord_status = status;
+            }
+            if let Some(report) = self.execution_report(&cl_ord_id, "F") {
+                let report = report
+                    .with(tags::LAST_QTY, fill)
+                    .with(tags::LAST_PX, self.fill_price)
+                    .with(tags::COMMISSION, self.commission);
+                self.send(report);
+            }
+        }
+    }
+
+    /// 按原序号重发业务报文，其余序号逐个以 GapFill 跳过。
+    fn resend(&mut self, request: &FixMessage) {
+        let begin = request
+            .get_u64(tags::BEGIN_SEQ_NO)
+            .ok()
+            .flatten()
+            .unwrap_or(1);
+        let now = format_utc_timestamp(chrono::Utc::now());
+        for seq in begin..self.next_seq {
+            let mut msg = match self.sent.get(&seq) {
+                Some(msg) => msg.clone(),
+                None => FixMessage::new(msg_type::SEQUENCE_RESET)
+                    .with(tags::GAP_FILL_FLAG, "Y")
+                    .with(tags::NEW_SEQ_NO, seq + 1),
+            };
+            msg.stamp_header(ACCEPTOR, INITIATOR, seq, &now);
+            msg.mark_poss_dup(&now);
+            self.write(msg.encode());
+        }
+    }
+}
+
+impl AcceptorStub {
+    async fn start() -> anyhow::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(StubState {
+            next_seq: 1,
+            next_exec_id: 0,
+            fill_price: dec!(99),
+            commission: dec!(1),
+            received: Vec::new(),
+            sent: BTreeMap::new(),
+            orders: HashMap::new(),
+            drop_next_app: false,
+            status_exec_id: None,
+            writer: None,
+        }));
+        let accept_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(Self::serve(accept_state.clone(), stream));
+            }
+        });
+        Ok(Self { addr, state })
+    }
+
+    async fn serve(state: Arc<Mutex<StubState>>, stream: TcpStream) {
+        let (mut read, mut write) = stream.into_split();
+        let (tx, mut rx) = mpsc::unbounded_channel::<Vec<u8>>();
+        if let Ok(mut state) = state.lock() {
+            state.writer = Some(tx);
+        }
+        tokio::spawn(async move {
+            while let Some(frame) = rx.recv().await {
+                if write.write_all(&frame).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        while let Ok(n) = read.read(&mut chunk).await {
+            if n == 0 {
+                return;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+            while let Ok(Some(frame)) = take_frame(&mut buf) {
+                match (FixMessage::decode(&frame), state.lock()) {
+                    (Ok(msg), Ok(mut state)) => state.handle(msg),
+                    _ => return,
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> anyhow::Result<MutexGuard<'_, StubState>> {
+        self.state
+            .lock()
+            .map_err(|e| anyhow::anyhow!("stub lock poisoned: {}", e))
+    }
+
+    fn config(&self) -> FixConfig {
+        FixConfig {
+            host: self.addr.ip().to_string(),
+            port: self.addr.port(),
+            sender_comp_id: INITIATOR.to_string(),
+            target_comp_id: ACCEPTOR.to_string(),
+            heartbeat_interval_secs: 30,
+            reset_on_logon: false,
+        }
+    }
+
+    fn received(&self) -> anyhow::Result<Vec<FixMessage>> {
+        Ok(self.lock()?.received.clone())
+    }
+
+    /// 等待收到满足条件的报文。
+    async fn wait_for_received(
+        &self,
+        predicate: impl Fn(&FixMessage) -> bool,
+    ) -> anyhow::Result<FixMessage> {
+        for _ in 0..200 {
+            if let Some(msg) = self.received()?.into_iter().find(|msg| predicate(msg)) {
+                return Ok(msg);
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        Err(anyhow::anyhow!(
+            "acceptor never received the expected message"
+        ))
+    }
+}
+
+/// 所有标的的最新价固定为 99。
+struct FixedQuoteMarket;
+
+struct FixedQuoteStock {
+    identity: StockIdentity,
+}
+
+#[async_trait::async_trait]
+impl Stock for FixedQuoteStock {
+    fn identity(&self) -> &StockIdentity {
+        &self.identity
+    }
+    fn current_price(&self) -> Result<Option<Decimal>, MarketError> {
+        Ok(Some(dec!(99)))
+    }
+    fn latest_candle(&self, _timeframe: TimeFrame) -> Result<Option<Candle>, MarketError> {
+        Ok(None)
+    }
+    fn last_closed_candle(&self, _timeframe: TimeFrame) -> Result<Option<Candle>, MarketError> {
+        Ok(None)
+    }
+    fn subscribe(&self, _timeframe: TimeFrame) -> Result<CandleStream, MarketError> {
+        Err(MarketError::Unknown(
+            "subscribe is not supported in this test".to_string(),
+        ))
+    }
+    async fn fetch_history(
+        &self,
+        _timeframe: TimeFrame,
+        _start: chrono::DateTime<chrono::Utc>,
+        _end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Candle>, MarketError> {
+        Ok(vec![])
+    }
+    fn status(&self) -> StockStatus {
+        StockStatus::Online
+    }
+}
+
+#[async_trait::async_trait]
+impl Market for FixedQuoteMarket {
+    async fn get_stock(&self, symbol: &str) -> Result<Arc<dyn Stock>, MarketError> {
+        Ok(Arc::new(FixedQuoteStock {
+            identity: StockIdentity {
+                symbol: symbol.to_string(),
+                exchange: None,
+            },
+        }))
+    }
+
+    async fn search_symbols(
+        &self,
+        _query: &str,
+    ) -> Result<Vec<okane_core::store::port::StockMetadata>, MarketError> {
+        Ok(vec![])
+    }
+}
+
+fn limit_order(id: &str, direction: OrderDirection, price: Decimal, volume: Decimal) -> Order {
+    Order::new(
+        OrderId(id.to_string()),
+        AccountId("FixAcct".to_string()),
+        "AAPL".to_string(),
+        direction,
+        Some(price),
+        volume,
+        0,
+    )
+}
+
+async fn next_report(
+    stream: &mut okane_core::trade::port::ExecutionReportStream,
+) -> anyhow::Result<ExecutionReport> {
+    timeout(Duration::from_secs(5), stream.next())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("execution report stream ended"))
+}
+
+#[tokio::test]
+async fn test_fix_orders_route_through_gateway_and_settle() -> anyhow::Result<()> {
+    let stub = AcceptorStub::start().await?;
+    let tmp_dir = tempfile::tempdir()?;
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    system_store
+        .save_user(&okane_core::store::port::User {
+            id: "u1".to_string(),
+            name: "Fix Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: okane_core::store::port::UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account("u1", "FixAcct", "fix", "fix", serde_json::json!({}))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("FixAcct".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000));
+    let market = Arc::new(FixedQuoteMarket);
+    let clock = Arc::new(RealTimeProvider);
+    let broker = Arc::new(FixBroker::new(stub.config(), &tmp_dir.path().join("fix")).await?);
+    let gateway = Arc::new(
+        BrokerGateway::new(
+            BrokerRegistry::new().with_broker("fix", broker),
+            account_manager.clone(),
+            Arc::new(okane_store::broker_order::MemoryBrokerOrderStore::new()),
+            market.clone(),
+            clock.clone(),
+        )
+        .with_reconnect_delay(Duration::from_millis(10)),
+    );
+    gateway.start();
+    timeout(Duration::from_secs(5), gateway.wait_connected("fix")).await??;
+    let local = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(Decimal::ZERO)),
+        market,
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        clock,
+    ));
+    let router = RoutedTradePort::new(local, system_store).with_gateway(gateway.clone());
+
+    async fn wait_for(
+        router: &RoutedTradePort,
+        order_id: &OrderId,
+        status: OrderStatus,
+    ) -> anyhow::Result<Order> {
+        for _ in 0..200 {
+            if let Some(order) = router.get_order(order_id).await?
+                && order.status == status
+            {
+                return Ok(order);
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        Err(anyhow::anyhow!(
+            "order {} never reached {:?}",
+            order_id.0,
+            status
+        ))
+    }
+
+    // 限价 100 买入 10 股，接收方以 99 分两笔成交，每笔手续费 1；
+    // 外发期间并发对账 (如回报流重连)，在途订单不能被当作未受理拒绝
+    let buy = OrderId("FB1".to_string());
+    let (submitted, reconciled) = tokio::join!(
+        router.submit_order(limit_order("FB1", OrderDirection::Buy, dec!(100), dec!(10))),
+        gateway.reconcile("fix")
+    );
+    submitted?;
+    reconciled?;
+    let order = wait_for(&router, &buy, OrderStatus::Filled).await?;
+    assert_eq!(order.filled_volume, dec!(10));
+    let snapshot = router.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(9008));
+    assert_eq!(snapshot.frozen_balance, dec!(0));
+    assert_eq!(snapshot.positions[0].volume, dec!(10));
+    assert_eq!(snapshot.positions[0].average_price, dec!(99));
+
+    let nos = stub
+        .wait_for_received(|msg| msg.msg_type() == msg_type::NEW_ORDER_SINGLE)
+        .await?;
+    assert_eq!(nos.get(tags::CL_ORD_ID), Some("FB1"));
+    assert_eq!(nos.get(tags::ORD_TYPE), Some("2"));
+    assert_eq!(nos.get(tags::PRICE), Some("100"));
+
+    // 未穿价的限价卖单经 OrderCancelRequest 撤单
+    let sell = OrderId("FS1".to_string());
+    router
+        .submit_order(limit_order(
+            "FS1",
+            OrderDirection::Sell,
+            dec!(200),
+            dec!(10),
+        ))
+        .await?;
+    wait_for(&router, &sell, OrderStatus::Submitted).await?;
+    router.cancel_order(sell.clone()).await?;
+    wait_for(&router, &sell, OrderStatus::Canceled).await?;
+    let cancel = stub
+        .wait_for_received(|msg| msg.msg_type() == msg_type::ORDER_CANCEL_REQUEST)
+        .await?;
+    assert_eq!(cancel.get(tags::ORIG_CL_ORD_ID), Some("FS1"));
+    assert_eq!(cancel.get(tags::CL_ORD_ID), Some("FS1-C1"));
+    assert!(router.get_orders(&acct_id).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fix_session_resends_gaps_and_recovers_after_restart() -> anyhow::Result<()> {
+    let stub = AcceptorStub::start().await?;
+    let tmp_dir = tempfile::tempdir()?;
+    let store_dir = tmp_dir.path().join("fix");
+    let broker = FixBroker::new(stub.config(), &store_dir).await?;
+    let mut stream = broker.subscribe_execution_reports().await?;
+
+    // 接收方丢失受理回报：后续成交回报的序号出现缺口，发起方请求重发后按序收齐
+    stub.lock()?.drop_next_app = true;
+    let order = limit_order("FB1", OrderDirection::Buy, dec!(100), dec!(10));
+    assert_eq!(broker.send_order(&order).await?, "FB1");
+    let mut reports = Vec::new();
+    for _ in 0..3 {
+        reports.push(next_report(&mut stream).await?);
+    }
+    assert_eq!(
+        reports.iter().map(|r| r.status).collect::<Vec<_>>(),
+        vec![
+            OrderStatus::Submitted,
+            OrderStatus::PartialFilled,
+            OrderStatus::Filled
+        ]
+    );
+    assert_eq!(reports[1].filled_volume, dec!(5));
+    assert_eq!(reports[2].filled_volume, dec!(10));
+    assert_eq!(reports[2].average_price, dec!(99));
+    assert_eq!(reports[2].commission, dec!(2));
+    let resend_request = stub
+        .wait_for_received(|msg| msg.msg_type() == msg_type::RESEND_REQUEST)
+        .await?;
+    assert_eq!(resend_request.get(tags::BEGIN_SEQ_NO), Some("2"));
+    assert_eq!(resend_request.get(tags::END_SEQ_NO), Some("0"));
+
+    // 接收方请求全部重发：委托按原序号以 PossDup 重发，登录等会话层报文以 GapFill 跳过
+    let nos_seq = stub
+        .wait_for_received(|msg| msg.msg_type() == msg_type::NEW_ORDER_SINGLE)
+        .await?
+        .seq_num()?;
+    {
+        let mut state = stub.lock()?;
+        state.send(
+            FixMessage::new(msg_type::RESEND_REQUEST)
+                .with(tags::BEGIN_SEQ_NO, 1)
+                .with(tags::END_SEQ_NO, 0),
+        );
+    }
+    let resent = stub
+        .wait_for_received(|msg| {
+            msg.msg_type() == msg_type::NEW_ORDER_SINGLE && msg.is_flag_set(tags::POSS_DUP_FLAG)
+        })
+        .await?;
+    assert_eq!(resent.seq_num()?, nos_seq);
+    assert!(resent.get(tags::ORIG_SENDING_TIME).is_some());
+    let gap_fill = stub
+        .wait_for_received(|msg| msg.msg_type() == msg_type::SEQUENCE_RESET)
+        .await?;
+    assert_eq!(gap_fill.seq_num()?, 1);
+    assert!(gap_fill.is_flag_set(tags::GAP_FILL_FLAG));
+
+    // 重启：序号从会话存储续接，订单与累计手续费从会话日志恢复
+    broker.logout().await?;
+    drop(stream);
+    drop(broker);
+    let broker = FixBroker::new(stub.config(), &store_dir).await?;
+    let report = broker.query_order_status("FB1").await?;
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(report.filled_volume, dec!(10));
+    assert_eq!(report.commission, dec!(2));
+
+    let logons: Vec<u64> = stub
+        .received()?
+        .iter()
+        .filter(|msg| msg.msg_type() == msg_type::LOGON)
+        .map(|msg| msg.seq_num())
+        .collect::<Result<_, _>>()?;
+    assert_eq!(logons.len(), 2);
+    assert!(logons[1] > nos_seq);
+    let stub_logouts = stub
+        .received()?
+        .iter()
+        .filter(|msg| msg.msg_type() == msg_type::LOGOUT)
+        .count();
+    assert_eq!(stub_logouts, 1);
+    assert!(broker.session().is_logged_on());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fix_session_reset_on_logon_carries_open_orders() -> anyhow::Result<()> {
+    let stub = AcceptorStub::start().await?;
+    let tmp_dir = tempfile::tempdir()?;
+    let store_dir = tmp_dir.path().join("fix");
+    let config = FixConfig {
+        reset_on_logon: true,
+        ..stub.config()
+    };
+    let broker = FixBroker::new(config.clone(), &store_dir).await?;
+    let mut stream = broker.subscribe_execution_reports().await?;
+
+    // 未穿价的买单保持在途，穿价的买单全部成交
+    let open = limit_order("FO1", OrderDirection::Buy, dec!(50), dec!(10));
+    broker.send_order(&open).await?;
+    assert_eq!(
+        next_report(&mut stream).await?.status,
+        OrderStatus::Submitted
+    );
+    let filled = limit_order("FF1", OrderDirection::Buy, dec!(100), dec!(10));
+    broker.send_order(&filled).await?;
+    for _ in 0..3 {
+        next_report(&mut stream).await?;
+    }
+    broker.logout().await?;
+    drop(stream);
+    drop(broker);
+
+    // 重启后以 ResetSeqNumFlag 登录：序号从 1 开始，在途订单随重置保留，仍可查询
+    let broker = FixBroker::new(config.clone(), &store_dir).await?;
+    let report = broker.query_order_status("FO1").await?;
+    assert_eq!(report.status, OrderStatus::Submitted);
+    broker.logout().await?;
+    drop(broker);
+
+    // 已终结的订单随上一轮日志归档，不再恢复
+    let broker = FixBroker::new(config, &store_dir).await?;
+    assert!(matches!(
+        broker.query_order_status("FF1").await,
+        Err(okane_core::trade::port::TradeError::OrderNotFound(_))
+    ));
+    let logons: Vec<FixMessage> = stub
+        .received()?
+        .into_iter()
+        .filter(|msg| msg.msg_type() == msg_type::LOGON)
+        .collect();
+    assert_eq!(logons.len(), 2);
+    for logon in &logons {
+        assert_eq!(logon.seq_num()?, 1);
+        assert!(logon.is_flag_set(tags::RESET_SEQ_NUM_FLAG));
+    }
+    assert!(store_dir.join("archive").is_dir());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fix_broker_holds_reports_and_withdraws_failed_sends() -> anyhow::Result<()> {
+    let stub = AcceptorStub::start().await?;
+    let tmp_dir = tempfile::tempdir()?;
+    let broker = FixBroker::new(stub.config(), &tmp_dir.path().join("fix")).await?;
+
+    // 外发时尚无订阅者：回报暂存，首个订阅者按序收齐
+    let order = limit_order("FH1", OrderDirection::Buy, dec!(100), dec!(10));
+    broker.send_order(&order).await?;
+    let mut stream = broker.subscribe_execution_reports().await?;
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        statuses.push(next_report(&mut stream).await?.status);
+    }
+    assert_eq!(
+        statuses,
+        vec![
+            OrderStatus::Submitted,
+            OrderStatus::PartialFilled,
+            OrderStatus::Filled
+        ]
+    );
+
+    // 登录失败的外发撤回登记的委托，查询不到该订单，重试不会被当作重复 ClOrdID
+    let closed = TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+    let config = FixConfig {
+        port: closed.port(),
+        ..stub.config()
+    };
+    let offline = FixBroker::new(config, &tmp_dir.path().join("offline")).await?;
+    let order = limit_order("FX1", OrderDirection::Buy, dec!(100), dec!(10));
+    assert!(offline.send_order(&order).await.is_err());
+    assert!(matches!(
+        offline.query_order_status("FX1").await,
+        Err(okane_core::trade::port::TradeError::OrderNotFound(_))
+    ));
+    match offline.send_order(&order).await {
+        Err(okane_core::trade::port::TradeError::BrokerIntegrationError(msg)) => {
+            assert!(!msg.contains("duplicate"), "{}", msg)
+        }
+        other => return Err(anyhow::anyhow!("unexpected send result {:?}", other)),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fix_broker_answers_repeated_status_queries_with_fixed_exec_id() -> anyhow::Result<()>
+{
+    let stub = AcceptorStub::start().await?;
+    stub.lock()?.status_exec_id = Some("0".to_string());
+    let tmp_dir = tempfile::tempdir()?;
+    let broker = FixBroker::new(stub.config(), &tmp_dir.path().join("fix")).await?;
+
+    let order = limit_order("FS1", OrderDirection::Buy, dec!(100), dec!(10));
+    broker.send_order(&order).await?;
+    let mut stream = broker.subscribe_execution_reports().await?;
+    for _ in 0..3 {
+        next_report(&mut stream).await?;
+    }
+
+    // 状态查询应答复用同一 ExecID：每次查询都得到应答，且不重复累计手续费
+    for _ in 0..2 {
+        let report = broker.query_order_status("FS1").await?;
+        assert_eq!(report.status, OrderStatus::Filled);
+        assert_eq!(report.filled_volume, dec!(10));
+        assert_eq!(report.commission, dec!(2));
+    }
+    Ok(())
+}
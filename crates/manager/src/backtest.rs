@@ -2,10 +2,13 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use okane_core::common::TimeFrame;
 use okane_core::common::time::TimeProvider;
+use okane_core::config::FillModelConfig;
 use okane_core::engine::port::{EngineBuildParams, EngineBuilder};
+use okane_core::market::entity::PriceAdjustment;
 use okane_core::market::indicator::IndicatorService;
 use okane_core::market::port::{Market, Stock};
 use okane_core::strategy::entity::EngineType;
+use okane_core::trade::cost::CostModel;
 use okane_core::trade::entity::{AccountId, AccountSnapshot, Trade};
 use okane_core::trade::port::{AlgoOrderPort, BacktestTradePort};
 use rust_decimal::Decimal;
@@ -36,6 +39,12 @@ pub struct BacktestRequest {
     pub source: Vec<u8>,
     /// 初始资金
     pub initial_balance: Decimal,
+    /// 交易成本模型 (滑点与费用)，通常取自逻辑交易账号配置
+    pub cost_model: CostModel,
+    /// K 线价格口径 (原始 / 拆股复权 / 全收益)，决定公司行动作用于价格还是回测账户
+    pub price_adjustment: PriceAdjustment,
+    /// K 线成交模型 (参与率上限与价格路径假设)
+    pub fill_model: FillModelConfig,
 }
 
 /// # Summary
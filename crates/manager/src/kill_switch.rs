@@ -0,0 +1,266 @@
+use okane_core::common::time::TimeProvider;
+use okane_core::store::port::{AccountAuditAction, AccountAuditRecord, SystemStore};
+use okane_core::strategy::entity::StrategyStatus;
+use okane_core::trade::entity::{AccountId, AlgoOrderStatus, Order, OrderDirection, OrderId};
+use okane_core::trade::port::{AlgoOrderPort, TradeError, TradePort};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::strategy::{ManagerError, StrategyManager};
+
+/// # Summary
+/// 一次紧急停止的执行明细，同时作为审计记录的 `detail` 持久化。
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KillSwitchReport {
+    pub account_id: String,
+    /// 被停止的策略实例 ID
+    pub stopped_strategies: Vec<String>,
+    /// 被取消的算法单 ID
+    pub canceled_algo_orders: Vec<String>,
+    /// 被撤销的委托单 ID
+    pub canceled_orders: Vec<String>,
+    /// 为平仓提交的市价单 ID
+    pub flatten_orders: Vec<String>,
+    /// 执行过程中未能完成的步骤，不中断其余步骤
+    pub errors: Vec<String>,
+}
+
+/// # Summary
+/// 账号级紧急停止 (kill switch)：停止账号下全部策略、撤销全部委托与算法单、
+/// 可选地以市价单平掉全部持仓，并将账号标记为停止状态，直至重新启用。
+///
+/// # Invariants
+/// - 最先标记停止：此后路由拒绝该账号的一切新委托，停策略与撤单期间不会有新的委托进入。
+/// - 平仓单经 `submit_flatten_order` 提交，不受停止标记与事前风控限制。
+/// - 停止标记写入失败时不执行其余步骤；之后单个步骤失败只记入报告，审计记录总会写入。
+pub struct KillSwitch {
+    strategy_manager: Arc<StrategyManager>,
+    trade_port: Arc<dyn TradePort>,
+    algo_port: Arc<dyn AlgoOrderPort>,
+    system_store: Arc<dyn SystemStore>,
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl KillSwitch {
+    pub fn new(
+        strategy_manager: Arc<StrategyManager>,
+        trade_port: Arc<dyn TradePort>,
+        algo_port: Arc<dyn AlgoOrderPort>,
+        system_store: Arc<dyn SystemStore>,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            strategy_manager,
+            trade_port,
+            algo_port,
+            system_store,
+            time_provider,
+        }
+    }
+
+    /// # Logic
+    /// 1. 标记账号为停止状态。
+    /// 2. 停止账号所有者名下绑定该账号且仍在运行的策略。
+    /// 3. 取消运行中或暂停的算法单。
+    /// 4. 撤销剩余的活动委托 (已随订单组联动撤销的视为完成)。
+    /// 5. `flatten` 为真时，按持仓反向提交市价平仓单。
+    /// 6. 写入审计记录。
+    ///
+    /// # Arguments
+    /// * `operator_id` - User who triggered the kill switch.
+    /// * `account_id` - Logical account to halt.
+    /// * `flatten` - Whether to close all positions with market orders.
+    /// * `reason` - Free-text reason kept in the audit record.
+    ///
+    /// # Returns
+    /// * `Err(ManagerError::NotFound)` - If the account does not exist.
+    pub async fn halt(
+        &self,
+        operator_id: &str,
+        account_id: &str,
+        flatten: bool,
+        reason: Option<String>,
+    ) -> Result<KillSwitchReport, ManagerError> {
+        let owner = self
+            .system_store
+            .get_account_owner(account_id)
+            .await?
+            .ok_or_else(|| ManagerError::NotFound(format!("account {}", account_id)))?;
+        let account = AccountId(account_id.to_string());
+        self.system_store
+            .set_account_halted(account_id, true)
+            .await?;
+        let mut report = KillSwitchReport {
+            account_id: account_id.to_string(),
+            ..KillSwitchReport::default()
+        };
+
+        for instance in self.strategy_manager.list_strategies(&owner).await? {
+            if instance.account_id != account_id
+                || !matches!(
+                    instance.status,
+                    StrategyStatus::Running | StrategyStatus::Pending
+                )
+            {
+                continue;
+            }
+            match self
+                .strategy_manager
+                .stop_strategy(&owner, &instance.id)
+                .await
+            {
+                Ok(()) => report.stopped_strategies.push(instance.id),
+                Err(e) => report
+                    .errors
+                    .push(format!("stop strategy {}: {}", instance.id, e)),
+            }
+        }
+
+        match self.algo_port.get_algo_orders(&account).await {
+            Ok(algo_orders) => {
+                for algo_order in algo_orders.into_iter().filter(|o| {
+                    matches!(o.status, AlgoOrderStatus::Running | AlgoOrderStatus::Paused)
+                }) {
+                    match self.algo_port.cancel_algo_order(&algo_order.id).await {
+                        Ok(()) => report.canceled_algo_orders.push(algo_order.id.0),
+                        Err(e) => report
+                            .errors
+                            .push(format!("cancel algo order {}: {}", algo_order.id.0, e)),
+                    }
+                }
+            }
+            Err(e) => report.errors.push(format!("list algo orders: {}", e)),
+        }
+
+        match self.trade_port.get_orders(&account).await {
+            Ok(orders) => {
+                for order in orders {
+                    match self.trade_port.cancel_order(order.id.clone()).await {
+                        Ok(()) => report.canceled_orders.push(order.id.0),
+                        // 互斥/括号子单可能已随同组订单一并撤销
+                        Err(TradeError::OrderNotFound(_)) => {}
+                        Err(e) => report
+                            .errors
+                            .push(format!("cancel order {}: {}", order.id.0, e)),
+                    }
+                }
+            }
+            Err(e) => report.errors.push(format!("list orders: {}", e)),
+        }
+
+        if flatten {
+            self.flatten(&account, &mut report).await;
+        }
+
+        self.audit(
+            AccountAuditAction::Halt,
+            operator_id,
+            account_id,
+            reason,
+            &report,
+        )
+        .await?;
+        warn!(
+            "account {} halted by {}: {} strategies stopped, {} orders canceled, {} flatten orders",
+            account_id,
+            operator_id,
+            report.stopped_strategies.len(),
+            report.canceled_orders.len(),
+            report.flatten_orders.len()
+        );
+        Ok(report)
+    }
+
+    /// # Logic
+    /// 解除停止标记并写入审计记录；已停止的策略不会自动重启。
+    pub async fn resume(
+        &self,
+        operator_id: &str,
+        account_id: &str,
+        reason: Option<String>,
+    ) -> Result<(), ManagerError> {
+        self.system_store
+            .set_account_halted(account_id, false)
+            .await?;
+        let report = KillSwitchReport {
+            account_id: account_id.to_string(),
+            ..KillSwitchReport::default()
+        };
+        self.audit(
+            AccountAuditAction::Resume,
+            operator_id,
+            account_id,
+            reason,
+            &report,
+        )
+        .await?;
+        info!("account {} resumed by {}", account_id, operator_id);
+        Ok(())
+    }
+
+    async fn flatten(&self, account: &AccountId, report: &mut KillSwitchReport) {
+        let snapshot = match self.trade_port.get_account(account.clone()).await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                report.errors.push(format!("load positions: {}", e));
+                return;
+            }
+        };
+        let created_at = match self.time_provider.now() {
+            Ok(now) => now.timestamp_millis(),
+            Err(e) => {
+                report.errors.push(format!("read clock: {}", e));
+                return;
+            }
+        };
+        for position in snapshot.positions.iter().filter(|p| !p.volume.is_zero()) {
+            let direction = if position.volume.is_sign_positive() {
+                OrderDirection::Sell
+            } else {
+                OrderDirection::Buy
+            };
+            let order = Order::new(
+                OrderId(Uuid::new_v4().to_string()),
+                account.clone(),
+                position.symbol.clone(),
+                direction,
+                None,
+                position.volume.abs(),
+                created_at,
+            );
+            match self.trade_port.submit_flatten_order(order).await {
+                Ok(order_id) => report.flatten_orders.push(order_id.0),
+                Err(e) => report
+                    .errors
+                    .push(format!("flatten {}: {}", position.symbol, e)),
+            }
+        }
+    }
+
+    async fn audit(
+        &self,
+        action: AccountAuditAction,
+        operator_id: &str,
+        account_id: &str,
+        reason: Option<String>,
+        report: &KillSwitchReport,
+    ) -> Result<(), ManagerError> {
+        let detail = serde_json::to_value(report).map_err(|e| {
+            ManagerError::Store(okane_core::store::error::StoreError::Unknown(e.to_string()))
+        })?;
+        self.system_store
+            .save_account_audit(&AccountAuditRecord {
+                id: Uuid::new_v4().to_string(),
+                account_id: account_id.to_string(),
+                action,
+                operator_id: operator_id.to_string(),
+                reason,
+                detail,
+                created_at: self.time_provider.now()?,
+            })
+            .await?;
+        Ok(())
+    }
+}
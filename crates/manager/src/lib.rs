@@ -1,2 +1,3 @@
 pub mod backtest;
+pub mod kill_switch;
 pub mod strategy;
@@ -1,3 +1,4 @@
+use async_trait::async_trait;
 use chrono::Utc;
 use dashmap::DashMap;
 use okane_core::common::TimeFrame;
@@ -10,6 +11,11 @@ use okane_core::strategy::entity::{
     StrategyStatus,
 };
 use okane_core::strategy::port::{StrategyLogPort, StrategyLogger, StrategyStore};
+use okane_core::trade::entity::{
+    AccountId, AccountSnapshot, DailyPnl, HistoryPage, HistoryQuery, LedgerEntry, Order,
+    OrderGroup, OrderHistoryRecord, OrderId, Trade,
+};
+use okane_core::trade::port::{TradeError, TradePort};
 use std::collections::VecDeque;
 use std::sync::Arc;
 use thiserror::Error;
@@ -46,6 +52,96 @@ impl StrategyLogger for LogWrapper {
     }
 }
 
+/// # Summary
+/// 为单次策略运行提交的订单打上运行 ID 的交易端口包装，供订单历史按运行过滤。
+struct RunTradePort {
+    run_id: String,
+    inner: Arc<dyn TradePort>,
+}
+
+impl RunTradePort {
+    fn tag(&self, mut order: Order) -> Order {
+        order.strategy_run_id = Some(self.run_id.clone());
+        order
+    }
+}
+
+#[async_trait]
+impl TradePort for RunTradePort {
+    async fn submit_order(&self, order: Order) -> Result<OrderId, TradeError> {
+        self.inner.submit_order(self.tag(order)).await
+    }
+
+    async fn cancel_order(&self, order_id: OrderId) -> Result<(), TradeError> {
+        self.inner.cancel_order(order_id).await
+    }
+
+    async fn get_account(&self, account_id: AccountId) -> Result<AccountSnapshot, TradeError> {
+        self.inner.get_account(account_id).await
+    }
+
+    async fn get_orders(&self, account_id: &AccountId) -> Result<Vec<Order>, TradeError> {
+        self.inner.get_orders(account_id).await
+    }
+
+    async fn get_order(&self, order_id: &OrderId) -> Result<Option<Order>, TradeError> {
+        self.inner.get_order(order_id).await
+    }
+
+    async fn ensure_account(
+        &self,
+        account_id: AccountId,
+        initial_balance: rust_decimal::Decimal,
+    ) -> Result<(), TradeError> {
+        self.inner.ensure_account(account_id, initial_balance).await
+    }
+
+    async fn submit_order_group(
+        &self,
+        mut group: OrderGroup,
+        entry: Option<Order>,
+    ) -> Result<OrderId, TradeError> {
+        group.legs = group.legs.into_iter().map(|leg| self.tag(leg)).collect();
+        let entry = entry.map(|entry| self.tag(entry));
+        self.inner.submit_order_group(group, entry).await
+    }
+
+    async fn cancel_order_group(&self, group_id: &OrderId) -> Result<(), TradeError> {
+        self.inner.cancel_order_group(group_id).await
+    }
+
+    async fn get_order_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        self.inner.get_order_group(group_id).await
+    }
+
+    async fn get_daily_pnl(&self, account_id: &AccountId) -> Result<Vec<DailyPnl>, TradeError> {
+        self.inner.get_daily_pnl(account_id).await
+    }
+
+    async fn get_order_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<OrderHistoryRecord>, TradeError> {
+        self.inner.get_order_history(query).await
+    }
+
+    async fn get_trade_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<Trade>, TradeError> {
+        self.inner.get_trade_history(query).await
+    }
+
+    async fn get_ledger(
+        &self,
+        account_id: &AccountId,
+        offset: usize,
+        limit: usize,
+    ) -> Result<HistoryPage<LedgerEntry>, TradeError> {
+        self.inner.get_ledger(account_id, offset, limit).await
+    }
+}
+
 /// # Summary
 /// Manager 层的统一错误类型。
 #[derive(Error, Debug)]
@@ -282,7 +378,10 @@ impl StrategyManager {
             timeframe: req.timeframe,
             source: req.source,
             // handlers field removed — Signal 机制已移除
-            trade_port: self.trade_port.clone(),
+            trade_port: Arc::new(RunTradePort {
+                run_id: run_id.clone(),
+                inner: self.trade_port.clone(),
+            }),
             algo_port: self.algo_port.clone(),
             indicator_service: self.indicator_service.clone(),
             time_provider: self.time_provider.clone(),
@@ -156,3 +156,139 @@ async fn test_strategy_lifecycle() -> anyhow::Result<()> {
     assert_eq!(instance_inf.status, StrategyStatus::Stopped);
     Ok(())
 }
+
+#[tokio::test]
+async fn test_kill_switch_stops_strategies_cancels_orders_and_halts_account() -> anyhow::Result<()>
+{
+    use okane_core::store::port::{AccountAuditAction, SystemStore, User, UserRole};
+    use okane_core::trade::entity::{AccountId, Order, OrderDirection, OrderId};
+    use okane_core::trade::port::TradePort;
+    use okane_manager::kill_switch::KillSwitch;
+
+    struct InfiniteEngineBuilder;
+    impl EngineBuilder for InfiniteEngineBuilder {
+        fn build(&self, _params: EngineBuildParams) -> Result<EngineFuture, EngineError> {
+            Ok(Box::pin(async {
+                loop {
+                    sleep(Duration::from_millis(100)).await;
+                }
+            }))
+        }
+    }
+
+    let tmp_dir = tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let store = Arc::new(
+        SqliteStrategyStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+            .map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?,
+    );
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    let user_id = "kill_user";
+    let account_id = "acct_kill";
+    system_store
+        .save_user(&User {
+            id: user_id.to_string(),
+            name: "Kill Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account(user_id, account_id, "kill", "local", serde_json::json!({}))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let trade_port = Arc::new(SpyTradePort::new());
+    let manager = StrategyManager::new(okane_manager::strategy::StrategyManagerParams {
+        store: store.clone(),
+        engine_builder: Arc::new(InfiniteEngineBuilder)
+            as Arc<dyn okane_core::engine::port::EngineBuilder>,
+        trade_port: trade_port.clone(),
+        algo_port: Arc::new(MockAlgoOrderPort),
+        indicator_service: Arc::new(MockIndicatorService),
+        time_provider: Arc::new(okane_core::common::time::RealTimeProvider),
+        notifier_factory: Arc::new(NoopNotifierFactory),
+        log_port: store,
+    });
+    let strategy_id = manager
+        .start_strategy(
+            user_id,
+            StartRequest {
+                symbol: "AAPL".to_string(),
+                account_id: account_id.to_string(),
+                timeframe: TimeFrame::Minute1,
+                engine_type: EngineType::JavaScript,
+                run_mode: StrategyRunMode::LivePaper,
+                source: b"loop".to_vec(),
+            },
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Start failed: {:?}", e))?;
+    trade_port
+        .submit_order(Order::new(
+            OrderId("kill_order".to_string()),
+            AccountId(account_id.to_string()),
+            "AAPL".to_string(),
+            OrderDirection::Buy,
+            None,
+            rust_decimal::Decimal::ONE,
+            0,
+        ))
+        .await?;
+
+    let kill_switch = KillSwitch::new(
+        manager.clone(),
+        trade_port,
+        Arc::new(MockAlgoOrderPort),
+        system_store.clone(),
+        Arc::new(okane_core::common::time::RealTimeProvider),
+    );
+    let report = kill_switch
+        .halt("admin", account_id, true, Some("runaway".to_string()))
+        .await
+        .map_err(|e| anyhow::anyhow!("Halt failed: {:?}", e))?;
+    assert_eq!(report.stopped_strategies, vec![strategy_id.clone()]);
+    assert_eq!(report.canceled_orders, vec!["kill_order".to_string()]);
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert_eq!(
+        manager
+            .get_strategy(user_id, &strategy_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Get failed: {:?}", e))?
+            .status,
+        StrategyStatus::Stopped
+    );
+    let is_halted = |store: Arc<okane_store::system::SqliteSystemStore>| async move {
+        store
+            .get_account_profile(account_id)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?
+            .map(|p| p.halted)
+            .ok_or_else(|| anyhow::anyhow!("profile missing"))
+    };
+    assert!(is_halted(system_store.clone()).await?);
+
+    kill_switch
+        .resume(user_id, account_id, None)
+        .await
+        .map_err(|e| anyhow::anyhow!("Resume failed: {:?}", e))?;
+    assert!(!is_halted(system_store.clone()).await?);
+
+    let audit = system_store
+        .list_account_audit(account_id)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let actions: Vec<_> = audit.iter().map(|r| r.action).collect();
+    assert_eq!(
+        actions,
+        vec![AccountAuditAction::Resume, AccountAuditAction::Halt]
+    );
+    assert_eq!(audit.last().map(|r| r.operator_id.as_str()), Some("admin"));
+    Ok(())
+}
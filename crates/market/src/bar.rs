@@ -0,0 +1,243 @@
+use chrono::{DateTime, Utc};
+use okane_core::common::TimeFrame;
+use okane_core::market::entity::Candle;
+
+/// # Summary
+/// 一次折叠的输出：按顺序发布 `closed` (上一根 K 线的收盘版本) 与 `current` (当前 K 线的最新状态)。
+#[derive(Debug, Clone, Default)]
+pub struct BarUpdate {
+    pub closed: Option<Candle>,
+    pub current: Option<Candle>,
+}
+
+/// # Summary
+/// 将实时 tick 折叠为指定周期 OHLCV K 线的构建器。
+///
+/// # Invariants
+/// - 构建器本身无状态，当前 K 线由调用方保存并在每次折叠时传入。
+/// - K 线按 UTC 对齐：开始时间为 tick 时间向下取整到周期长度 (日线为 UTC 零点)。
+/// - 每根 K 线恰好以 `is_final: true` 发布一次：要么在下一根 K 线的首个 tick 到达时
+///   (无论中间静默了多久)，要么在数据源推送的已收盘 1m K 线覆盖到本周期末尾时。
+/// - 早于当前 K 线或落入已收盘 K 线的迟到 tick 被丢弃，已收盘的 K 线不再变化。
+#[derive(Debug, Clone, Copy)]
+pub struct BarBuilder {
+    timeframe: TimeFrame,
+}
+
+impl BarBuilder {
+    pub fn new(timeframe: TimeFrame) -> Self {
+        Self { timeframe }
+    }
+
+    /// # Summary
+    /// 计算时间所属 K 线的开始时间。
+    ///
+    /// # Arguments
+    /// * `time`: 任意时间点。
+    ///
+    /// # Returns
+    /// 向下取整到周期长度的 UTC 时间。
+    pub fn bar_start(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let period = self.timeframe.duration().num_seconds().max(1);
+        let ts = time.timestamp();
+        DateTime::from_timestamp(ts - ts.rem_euclid(period), 0).unwrap_or(time)
+    }
+
+    /// # Summary
+    /// 将一个 tick 折叠进当前 K 线。
+    ///
+    /// # Logic
+    /// 1. tick 落入当前 K 线：合并高低收与成交量 (tick 的 volume 为增量)。
+    /// 2. tick 落入更晚的 K 线：未收盘的当前 K 线先以 `is_final: true` 输出，再以 tick 开启新 K 线。
+    /// 3. tick 为数据源推送的已收盘 1m K 线且覆盖到本周期末尾时，合并后的 K 线直接收盘。
+    ///
+    /// # Arguments
+    /// * `current`: 当前 K 线 (上一次折叠输出的 `current`)。
+    /// * `tick`: 新到达的行情，单价 tick 或数据源推送的 1m K 线。
+    ///
+    /// # Returns
+    /// 需要依次发布的 K 线；迟到的 tick 返回空的 `BarUpdate`。
+    pub fn fold(&self, current: Option<&Candle>, tick: &Candle) -> BarUpdate {
+        let start = self.bar_start(tick.time);
+        let closes = tick.is_final
+            && BarBuilder::new(TimeFrame::Minute1).bar_start(tick.time)
+                + TimeFrame::Minute1.duration()
+                >= start + self.timeframe.duration();
+
+        match current {
+            Some(bar) if bar.time > start || (bar.time == start && bar.is_final) => {
+                tracing::debug!(
+                    "Dropped late tick at {} for closed {:?} bar {}",
+                    tick.time,
+                    self.timeframe,
+                    bar.time
+                );
+                BarUpdate::default()
+            }
+            Some(bar) if bar.time == start => {
+                let mut merged = bar.clone();
+                merged.high = merged.high.max(tick.high);
+                merged.low = merged.low.min(tick.low);
+                merged.close = tick.close;
+                merged.volume += tick.volume;
+                merged.is_final = closes;
+                BarUpdate {
+                    closed: None,
+                    current: Some(merged),
+                }
+            }
+            previous => BarUpdate {
+                closed: previous.filter(|bar| !bar.is_final).map(|bar| Candle {
+                    is_final: true,
+                    ..bar.clone()
+                }),
+                current: Some(Candle {
+                    time: start,
+                    open: tick.open,
+                    high: tick.high,
+                    low: tick.low,
+                    close: tick.close,
+                    adj_close: None,
+                    volume: tick.volume,
+                    is_final: closes,
+                }),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn tick(secs: i64, price: Decimal, volume: Decimal) -> anyhow::Result<Candle> {
+        Ok(Candle {
+            time: Utc
+                .timestamp_opt(1_704_189_600 + secs, 0)
+                .single()
+                .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            adj_close: None,
+            volume,
+            is_final: false,
+        })
+    }
+
+    /// 依次折叠 tick，返回发布的全部 K 线。
+    fn run(builder: BarBuilder, ticks: &[Candle]) -> Vec<Candle> {
+        let mut current: Option<Candle> = None;
+        let mut published = Vec::new();
+        for tick in ticks {
+            let update = builder.fold(current.as_ref(), tick);
+            published.extend(update.closed);
+            if let Some(bar) = update.current {
+                published.push(bar.clone());
+                current = Some(bar);
+            }
+        }
+        published
+    }
+
+    #[test]
+    fn test_minute_bars_close_on_first_tick_of_next_bar() -> anyhow::Result<()> {
+        // 10:00:05、10:00:30、10:00:50，静默 3 分钟后 10:04:10
+        let ticks = vec![
+            tick(5, dec!(10), dec!(1))?,
+            tick(30, dec!(12), dec!(2))?,
+            tick(50, dec!(9), dec!(3))?,
+            tick(250, dec!(11), dec!(4))?,
+        ];
+        let published = run(BarBuilder::new(TimeFrame::Minute1), &ticks);
+        assert_eq!(published.len(), 5);
+        let finals: Vec<&Candle> = published.iter().filter(|c| c.is_final).collect();
+        assert_eq!(finals.len(), 1);
+        let closed = finals[0];
+        assert_eq!(closed.time.timestamp(), 1_704_189_600);
+        assert_eq!(
+            (closed.open, closed.high, closed.low, closed.close),
+            (dec!(10), dec!(12), dec!(9), dec!(9))
+        );
+        assert_eq!(closed.volume, dec!(6));
+        let last = &published[4];
+        assert!(!last.is_final);
+        assert_eq!(last.time.timestamp(), 1_704_189_840);
+        assert_eq!(last.volume, dec!(4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_higher_timeframes_aggregate_across_minutes() -> anyhow::Result<()> {
+        let ticks = vec![
+            tick(5, dec!(10), dec!(1))?,
+            tick(130, dec!(15), dec!(1))?,
+            tick(299, dec!(8), dec!(1))?,
+            tick(301, dec!(9), dec!(1))?,
+        ];
+        let published = run(BarBuilder::new(TimeFrame::Minute5), &ticks);
+        let finals: Vec<&Candle> = published.iter().filter(|c| c.is_final).collect();
+        assert_eq!(finals.len(), 1);
+        assert_eq!(
+            (
+                finals[0].open,
+                finals[0].high,
+                finals[0].low,
+                finals[0].close
+            ),
+            (dec!(10), dec!(15), dec!(8), dec!(8))
+        );
+        assert_eq!(finals[0].volume, dec!(3));
+
+        let hourly = run(BarBuilder::new(TimeFrame::Hour1), &ticks);
+        assert!(hourly.iter().all(|c| !c.is_final));
+        assert_eq!(hourly.last().map(|c| c.volume), Some(dec!(4)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_final_provider_candle_closes_bar_once_and_late_ticks_are_dropped() -> anyhow::Result<()>
+    {
+        let builder = BarBuilder::new(TimeFrame::Minute1);
+        let open = builder.fold(None, &tick(5, dec!(10), dec!(1))?);
+        let current = open.current.ok_or_else(|| anyhow::anyhow!("bar missing"))?;
+        let final_candle = Candle {
+            is_final: true,
+            ..tick(40, dec!(11), dec!(2))?
+        };
+        let closed = builder
+            .fold(Some(&current), &final_candle)
+            .current
+            .ok_or_else(|| anyhow::anyhow!("bar missing"))?;
+        assert!(closed.is_final);
+        assert_eq!(closed.volume, dec!(3));
+
+        let late = builder.fold(Some(&closed), &tick(50, dec!(12), dec!(1))?);
+        assert!(late.closed.is_none() && late.current.is_none());
+        let next = builder.fold(Some(&closed), &tick(65, dec!(12), dec!(1))?);
+        assert!(next.closed.is_none());
+        assert!(next.current.is_some_and(|c| !c.is_final));
+
+        // 已收盘的 1m K 线只有覆盖到 5m 周期末尾时才收盘 5m K 线
+        let five = BarBuilder::new(TimeFrame::Minute5);
+        let last_minute = Candle {
+            is_final: true,
+            ..tick(250, dec!(11), dec!(2))?
+        };
+        assert!(
+            five.fold(None, &last_minute)
+                .current
+                .is_some_and(|c| c.is_final)
+        );
+        assert!(
+            five.fold(None, &final_candle)
+                .current
+                .is_some_and(|c| !c.is_final)
+        );
+        Ok(())
+    }
+}
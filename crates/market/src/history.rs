@@ -15,14 +15,17 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use okane_core::common::time::{FakeClockProvider, TimeProvider};
 use okane_core::common::{Stock as StockIdentity, TimeFrame};
-use okane_core::market::entity::Candle;
+use okane_core::market::adjust::PriceAdjuster;
+use okane_core::market::entity::{Candle, CorporateAction, CorporateActionKind, PriceAdjustment};
 use okane_core::market::error::MarketError;
 use okane_core::market::port::{CandleStream, Market, Stock, StockStatus};
 use okane_core::trade::port::BacktestTradePort;
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::OnceCell;
 use tracing::debug;
 
 /// # Summary
@@ -32,6 +35,8 @@ use tracing::debug;
 /// - 内部使用 RollingBuffer 维护最近的 K 线窗口，避免全量载入导致的 OOM。
 /// - `subscribe()` 返回的 stream 在每次 yield K 线前自动推进时钟和驱动撮合。
 /// - `current_price()` 等方法基于缓存窗口获取。
+/// - 回放的 K 线按 `adjustment` 换算价格口径；价格口径未吸收的公司行动经 `corporate_actions()`
+///   交由撮合端口作用于回测账户，且不会早于当前回测时刻暴露。
 pub struct BacktestStock {
     identity: StockIdentity,
     /// 固定容量的活跃 K 线窗口（默认 1000 根），用于满足 strategy 的 fetch_history 请求
@@ -50,6 +55,10 @@ pub struct BacktestStock {
     trade_port: Arc<dyn BacktestTradePort>,
     /// 已处理 K 线计数
     emitted_candles: Arc<AtomicUsize>,
+    /// 价格口径
+    adjustment: PriceAdjustment,
+    /// 回测区间内的原始公司行动，首次使用时从数据源加载
+    actions: Arc<OnceCell<Vec<CorporateAction>>>,
 }
 
 impl BacktestStock {
@@ -76,6 +85,8 @@ impl BacktestStock {
             time_provider,
             trade_port,
             emitted_candles,
+            adjustment: PriceAdjustment::Raw,
+            actions: Arc::new(OnceCell::new()),
         }
     }
 
@@ -105,10 +116,94 @@ impl BacktestStock {
             time_provider,
             trade_port,
             emitted_candles,
+            adjustment: PriceAdjustment::Raw,
+            actions: Arc::new(OnceCell::new()),
         }
     }
 }
 
+impl BacktestStock {
+    /// 指定回放的价格口径，缺省为原始成交价。
+    pub fn with_price_adjustment(mut self, adjustment: PriceAdjustment) -> Self {
+        self.adjustment = adjustment;
+        self
+    }
+
+    /// 指定回测区间内的公司行动，代替从数据源加载 (用于 Vec 模式)。
+    pub fn with_corporate_actions(mut self, actions: Vec<CorporateAction>) -> Self {
+        self.actions = Arc::new(OnceCell::new_with(Some(actions)));
+        self
+    }
+
+    /// 回测区间内的原始公司行动 (按除权除息日升序)。
+    async fn raw_actions(
+        actions: &OnceCell<Vec<CorporateAction>>,
+        source: Option<&Arc<dyn Stock>>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        actions
+            .get_or_try_init(|| async {
+                let mut loaded = match source {
+                    Some(source) => source.corporate_actions(start, end).await?,
+                    None => Vec::new(),
+                };
+                loaded.sort_by_key(|action| action.ex_date);
+                Ok(loaded)
+            })
+            .await
+            .cloned()
+    }
+
+    /// # Logic
+    /// 全收益口径需要各分红除息前最后一根 K 线的原始收盘价：
+    /// Vec 模式取自静态数据，流式模式向数据源回溯除息前 10 天的日线。
+    async fn prior_closes(
+        actions: &[CorporateAction],
+        source: Option<&Arc<dyn Stock>>,
+        static_candles: Option<&Vec<Candle>>,
+    ) -> HashMap<DateTime<Utc>, Decimal> {
+        let mut closes = HashMap::new();
+        for action in actions {
+            if !matches!(action.kind, CorporateActionKind::Dividend { .. }) {
+                continue;
+            }
+            let close = if let Some(candles) = static_candles {
+                candles
+                    .iter()
+                    .filter(|c| c.time < action.ex_date)
+                    .max_by_key(|c| c.time)
+                    .map(|c| c.close)
+            } else if let Some(source) = source {
+                match source
+                    .fetch_history(
+                        TimeFrame::Day1,
+                        action.ex_date - chrono::Duration::days(10),
+                        action.ex_date,
+                    )
+                    .await
+                {
+                    Ok(history) => history
+                        .into_iter()
+                        .filter(|c| c.time < action.ex_date)
+                        .max_by_key(|c| c.time)
+                        .map(|c| c.close),
+                    Err(e) => {
+                        debug!("Prior close fetch failed for {}: {}", action.symbol, e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            if let Some(close) = close {
+                closes.insert(action.ex_date, close);
+            }
+        }
+        closes
+    }
+}
+
 #[async_trait]
 impl Stock for BacktestStock {
     fn identity(&self) -> &StockIdentity {
@@ -159,8 +254,24 @@ impl Stock for BacktestStock {
         let start = self.start_time;
         let end = self.end_time;
         let emitted_candles = self.emitted_candles.clone();
+        let adjustment = self.adjustment;
+        let actions = self.actions.clone();
 
         Ok(Box::pin(async_stream::stream! {
+            let adjuster = if adjustment == PriceAdjustment::Raw {
+                PriceAdjuster::new(adjustment, &[], &HashMap::new())
+            } else {
+                match Self::raw_actions(&actions, source.as_ref(), start, end).await {
+                    Ok(raw) => {
+                        let closes = Self::prior_closes(&raw, source.as_ref(), static_candles.as_ref()).await;
+                        PriceAdjuster::new(adjustment, &raw, &closes)
+                    }
+                    Err(e) => {
+                        yield Err(MarketError::Unknown(format!("corporate action load failed: {}", e)));
+                        return;
+                    }
+                }
+            };
             let mut current = start;
             while current <= end {
                 let candles = if let Some(ref s) = source {
@@ -192,10 +303,11 @@ impl Stock for BacktestStock {
                     break;
                 }
 
-                for candle in candles {
+                for mut candle in candles {
                     if candle.time > end {
                         break;
                     }
+                    adjuster.adjust(&mut candle);
                     // 推进虚拟时钟
                     if let Err(e) = tp.set_time(candle.time) {
                         debug!("Clock set failed: {}", e);
@@ -223,6 +335,33 @@ impl Stock for BacktestStock {
         }))
     }
 
+    /// 获取价格口径未吸收、需作用于回测账户的公司行动。
+    ///
+    /// # 回测约束
+    /// 与 `fetch_history` 相同，截断到当前回测时刻，禁止暴露未来的公司行动。
+    async fn corporate_actions(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        let now = self
+            .time_provider
+            .now()
+            .map_err(|e| MarketError::Unknown(e.to_string()))?;
+        let safe_end = std::cmp::min(end, now);
+        let raw = Self::raw_actions(
+            &self.actions,
+            self.source.as_ref(),
+            self.start_time,
+            self.end_time,
+        )
+        .await?;
+        Ok(PriceAdjuster::account_actions(self.adjustment, &raw)
+            .into_iter()
+            .filter(|action| action.ex_date >= start && action.ex_date <= safe_end)
+            .collect())
+    }
+
     /// 获取历史 K 线数据。
     ///
     /// # 回测约束
@@ -288,6 +427,13 @@ impl BacktestMarket {
         }
     }
 
+    /// 以已构建的回测 Stock 创建回测市场实例
+    pub fn from_stock(stock: BacktestStock) -> Self {
+        Self {
+            stock: Arc::new(stock),
+        }
+    }
+
     /// 创建回测市场实例 (兼容 Vec)
     pub fn new(
         symbol: String,
@@ -533,4 +679,75 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_backtest_stock_split_adjusted_stream() -> anyhow::Result<()> {
+        let at = |secs| {
+            Utc.timestamp_opt(secs, 0)
+                .single()
+                .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))
+        };
+        let candle = |secs, close: rust_decimal::Decimal| -> anyhow::Result<Candle> {
+            Ok(Candle {
+                time: at(secs)?,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                adj_close: None,
+                volume: dec!(1000),
+                is_final: true,
+            })
+        };
+        let tp = Arc::new(FakeClockProvider::new(at(1000)?));
+        let stock = BacktestStock::new(
+            "AAPL".into(),
+            vec![
+                candle(1000, dec!(100))?,
+                candle(1060, dec!(50))?,
+                candle(1120, dec!(50))?,
+            ],
+            tp.clone(),
+            Arc::new(MockTradePort),
+            Arc::new(AtomicUsize::new(0)),
+        )
+        .with_price_adjustment(PriceAdjustment::SplitAdjusted)
+        .with_corporate_actions(vec![
+            CorporateAction {
+                symbol: "AAPL".into(),
+                ex_date: at(1060)?,
+                kind: CorporateActionKind::Split {
+                    numerator: 2,
+                    denominator: 1,
+                },
+            },
+            CorporateAction {
+                symbol: "AAPL".into(),
+                ex_date: at(1120)?,
+                kind: CorporateActionKind::Dividend { amount: dec!(1) },
+            },
+        ]);
+
+        let mut stream = stock.subscribe(TimeFrame::Minute1)?;
+        let first = stream
+            .next()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Stream ended too early"))??;
+        // 拆股前的 K 线按 1/2 复权，成交量翻倍
+        assert_eq!(first.close, dec!(50));
+        assert_eq!(first.volume, dec!(2000));
+        // 拆股已被价格吸收，未到除息日的分红不可见
+        assert!(stock.corporate_actions(at(0)?, at(2000)?).await?.is_empty());
+
+        while let Some(candle) = stream.next().await {
+            assert_eq!(candle?.close, dec!(50));
+        }
+        let actions = stock.corporate_actions(at(0)?, at(2000)?).await?;
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            actions[0].kind,
+            CorporateActionKind::Dividend { amount: dec!(1) }
+        );
+        Ok(())
+    }
 }
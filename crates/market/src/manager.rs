@@ -3,8 +3,9 @@ use async_trait::async_trait;
 use dashmap::DashMap;
 use okane_cache::mem::MemCache;
 use okane_core::common::Stock as StockIdentity;
+use okane_core::common::time::{RealTimeProvider, TimeProvider};
 use okane_core::market::error::MarketError;
-use okane_core::market::port::{Market, MarketDataProvider, Stock};
+use okane_core::market::port::{Market, MarketDataProvider, Stock, TickRecorder};
 use okane_core::store::port::MarketStore;
 use std::sync::{Arc, Weak};
 use tokio::sync::mpsc;
@@ -21,6 +22,10 @@ pub struct MarketImpl {
     provider: Arc<dyn MarketDataProvider>,
     // 持久化存储驱动
     store: Arc<dyn MarketStore>,
+    // 原始行情录制器，为空时不录制
+    recorder: Option<Arc<dyn TickRecorder>>,
+    // 时钟，注入各聚合根
+    time_provider: Arc<dyn TimeProvider>,
     // 活跃聚合根注册表，Key 为 Symbol，Value 为弱引用
     stocks: DashMap<String, Weak<StockInner>>,
     // 用于接收聚合根销毁信号的发送端
@@ -43,10 +48,56 @@ impl MarketImpl {
     /// # Returns
     /// 返回 MarketImpl 的共享指针。
     pub fn new(provider: Arc<dyn MarketDataProvider>, store: Arc<dyn MarketStore>) -> Arc<Self> {
+        Self::new_with_clock(provider, store, None, Arc::new(RealTimeProvider))
+    }
+
+    /// # Summary
+    /// 初始化录制原始行情的 Market 领域服务。
+    ///
+    /// # Logic
+    /// 与 `new` 相同，各聚合根的抓取协程在聚合前将数据源推送的每条原始行情交给录制器。
+    ///
+    /// # Arguments
+    /// * `provider`: 满足 MarketDataProvider 接口的数据源驱动。
+    /// * `store`: 满足 MarketStore 接口的持久化驱动。
+    /// * `recorder`: 原始行情录制器。
+    ///
+    /// # Returns
+    /// 返回 MarketImpl 的共享指针。
+    pub fn new_with_recorder(
+        provider: Arc<dyn MarketDataProvider>,
+        store: Arc<dyn MarketStore>,
+        recorder: Arc<dyn TickRecorder>,
+    ) -> Arc<Self> {
+        Self::new_with_clock(provider, store, Some(recorder), Arc::new(RealTimeProvider))
+    }
+
+    /// # Summary
+    /// 初始化使用指定时钟的 Market 领域服务。
+    ///
+    /// # Logic
+    /// 与 `new` 相同，各聚合根以注入的时钟判断历史 K 线的末尾周期是否已收盘 (如重放时使用虚拟时钟)。
+    ///
+    /// # Arguments
+    /// * `provider`: 满足 MarketDataProvider 接口的数据源驱动。
+    /// * `store`: 满足 MarketStore 接口的持久化驱动。
+    /// * `recorder`: 原始行情录制器，为空时不录制。
+    /// * `time_provider`: 时钟。
+    ///
+    /// # Returns
+    /// 返回 MarketImpl 的共享指针。
+    pub fn new_with_clock(
+        provider: Arc<dyn MarketDataProvider>,
+        store: Arc<dyn MarketStore>,
+        recorder: Option<Arc<dyn TickRecorder>>,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Arc<Self> {
         let (tx, mut rx) = mpsc::channel(100);
         let market = Arc::new(Self {
             provider,
             store,
+            recorder,
+            time_provider,
             stocks: DashMap::new(),
             cleanup_tx: tx,
         });
@@ -116,6 +167,8 @@ impl Market for MarketImpl {
             self.provider.clone(),
             MemCache::new(),
             self.store.clone(),
+            self.recorder.clone(),
+            self.time_provider.clone(),
         );
 
         self.stocks
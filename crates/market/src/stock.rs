@@ -1,18 +1,23 @@
+use crate::bar::BarBuilder;
 use crate::buffer::RollingBuffer;
 use async_trait::async_trait;
 use okane_cache::mem::MemCache;
 use okane_core::cache::port::CacheExt;
+use okane_core::common::time::TimeProvider;
 use okane_core::common::{Stock as StockIdentity, TimeFrame};
 use okane_core::error::CoreError;
-use okane_core::market::entity::Candle;
+use okane_core::market::coverage::{self, CandleCoverage, TimeRange};
+use okane_core::market::entity::{Candle, CorporateAction};
 use okane_core::market::error::MarketError;
-use okane_core::market::port::{CandleStream, MarketDataProvider, Stock, StockStatus};
+use okane_core::market::port::{
+    CandleStream, MarketDataProvider, Stock, StockStatus, TickRecorder,
+};
 use okane_core::store::port::MarketStore;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex, Weak};
 use tokio::sync::{broadcast, mpsc};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 /// # Summary
 /// Stock 聚合根的具体实现结构。
@@ -33,10 +38,23 @@ pub(crate) struct StockInner {
     cleanup_tx: mpsc::Sender<String>,
     // 数据源驱动
     provider: Arc<dyn MarketDataProvider>,
+    // 时钟，判断末尾周期是否已收盘
+    time_provider: Arc<dyn TimeProvider>,
 }
 
 pub const DEFAULT_CANDLE_BUFFER_SIZE: usize = 200;
 
+/// 实时 tick 聚合生成的全部周期
+const AGGREGATED_TIMEFRAMES: [TimeFrame; 4] = [
+    TimeFrame::Minute1,
+    TimeFrame::Minute5,
+    TimeFrame::Hour1,
+    TimeFrame::Day1,
+];
+
+/// 最近一次向数据源同步公司行动的日期的缓存键
+const CORPORATE_ACTIONS_SYNC_KEY: &str = "ca:synced";
+
 impl StockInner {
     /// # Summary
     /// 创建并初始化聚合根。
@@ -51,6 +69,8 @@ impl StockInner {
     /// * `provider`: 数据源驱动。
     /// * `cache`: 独占缓存实例。
     /// * `store`: 全局存储驱动。
+    /// * `recorder`: 原始行情录制器，为空时不录制。
+    /// * `time_provider`: 时钟。
     ///
     /// # Returns
     /// 返回聚合根实例的强引用 Arc。
@@ -60,6 +80,8 @@ impl StockInner {
         provider: Arc<dyn MarketDataProvider>,
         cache: MemCache,
         store: Arc<dyn MarketStore>,
+        recorder: Option<Arc<dyn TickRecorder>>,
+        time_provider: Arc<dyn TimeProvider>,
     ) -> Arc<Self> {
         let stock = Arc::new(Self {
             identity: identity.clone(),
@@ -68,9 +90,10 @@ impl StockInner {
             store,
             cleanup_tx,
             provider: provider.clone(),
+            time_provider,
         });
 
-        let fetcher = StockFetcher::new(identity, Arc::downgrade(&stock), provider);
+        let fetcher = StockFetcher::new(identity, Arc::downgrade(&stock), provider, recorder);
         tokio::spawn(fetcher.run());
 
         stock
@@ -136,15 +159,96 @@ impl StockInner {
         }
     }
 
+    /// # Summary
+    /// 向数据源同步全部历史公司行动并写入本地存储。
+    ///
+    /// # Logic
+    /// 1. 每个自然日至多同步一次，同步日期记录在独占缓存中。
+    /// 2. 数据源失败时仅记录日志并沿用本地数据，当日不再重试，避免逐根 K 线重复请求。
+    ///
+    /// # Arguments
+    /// 无。
+    ///
+    /// # Returns
+    /// 无。
+    async fn refresh_corporate_actions(&self) {
+        let now = chrono::Utc::now();
+        let today = now.date_naive();
+        if let Ok(Some(synced)) = self
+            .cache
+            .get::<chrono::NaiveDate>(CORPORATE_ACTIONS_SYNC_KEY)
+            .await
+            && synced == today
+        {
+            return;
+        }
+
+        match self
+            .provider
+            .fetch_corporate_actions(&self.identity, chrono::DateTime::UNIX_EPOCH, now)
+            .await
+        {
+            Ok(actions) if !actions.is_empty() => {
+                if let Err(e) = self
+                    .store
+                    .save_corporate_actions(&self.identity, &actions)
+                    .await
+                {
+                    error!(
+                        "Failed to cache corporate actions for {}: {}",
+                        self.identity.symbol, e
+                    );
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!(
+                "Failed to fetch corporate actions for {}: {}",
+                self.identity.symbol, e
+            ),
+        }
+        if let Err(e) = self.cache.set(CORPORATE_ACTIONS_SYNC_KEY, &today).await {
+            error!("Failed to record corporate action sync date: {}", e);
+        }
+    }
+
+    /// # Summary
+    /// 将一个实时 tick 聚合进各周期的 K 线并发布。
+    ///
+    /// # Logic
+    /// 1. 以缓存中各周期的最新 K 线快照 ("l:{tf}") 作为当前 K 线，经 `BarBuilder` 折叠 tick。
+    /// 2. 跨越周期边界时先发布上一根 K 线的收盘版本，再发布新 K 线的进行中版本。
+    ///
+    /// # Arguments
+    /// * `tick`: 数据源推送的实时行情。
+    ///
+    /// # Returns
+    /// 无。
+    pub async fn ingest_tick(&self, tick: Candle) -> Result<(), MarketError> {
+        for timeframe in AGGREGATED_TIMEFRAMES {
+            let current = self
+                .cache
+                .get::<Candle>(Self::l_key(timeframe))
+                .await
+                .map_err(|e| MarketError::Unknown(format!("Cache error: {}", e)))?;
+            let update = BarBuilder::new(timeframe).fold(current.as_ref(), &tick);
+            if let Some(closed) = update.closed {
+                self.update_and_broadcast(closed, timeframe).await?;
+            }
+            if let Some(bar) = update.current {
+                self.update_and_broadcast(bar, timeframe).await?;
+            }
+        }
+        Ok(())
+    }
+
     /// # Summary
     /// 更新内部状态并触发广播分发。
     ///
     /// # Logic
     /// 1. 更新缓存中的最新价格 ("p")。
     /// 2. 更新缓存中的最新 K 线快照 ("l:{tf}")。
-    /// 3. 获取并更新缓存中的 RollingBuffer ("k:{tf}")。
-    /// 4. 若收盘，更新缓存 ("lc:{tf}") 并异步落库。
-    /// 5. 触发广播。
+    /// 3. 若收盘，追加到缓存中的 RollingBuffer ("k:{tf}")，更新缓存 ("lc:{tf}") 并异步落库。
+    /// 4. 触发广播。
     ///
     /// # Arguments
     /// * `candle`: 新接收到的行情数据。
@@ -167,19 +271,18 @@ impl StockInner {
             .await
             .map_err(|e| MarketError::Unknown(format!("Cache error: {}", e)))?;
 
-        // 更新滚动缓冲区 (k:rollingbuff)
-        let key = Self::k_key(timeframe);
-        let mut buffer = match self.cache.get::<RollingBuffer<Candle>>(key).await {
-            Ok(Some(b)) => b,
-            Ok(None) | Err(_) => RollingBuffer::new(DEFAULT_CANDLE_BUFFER_SIZE),
-        };
-        buffer.push(candle.clone());
-        self.cache
-            .set(key, &buffer)
-            .await
-            .map_err(|e| MarketError::Unknown(format!("Cache error: {}", e)))?;
-
         if candle.is_final {
+            // 滚动缓冲区 (k:rollingbuff) 只保留已收盘的 K 线
+            let key = Self::k_key(timeframe);
+            let mut buffer = match self.cache.get::<RollingBuffer<Candle>>(key).await {
+                Ok(Some(b)) => b,
+                Ok(None) | Err(_) => RollingBuffer::new(DEFAULT_CANDLE_BUFFER_SIZE),
+            };
+            buffer.push(candle.clone());
+            self.cache
+                .set(key, &buffer)
+                .await
+                .map_err(|e| MarketError::Unknown(format!("Cache error: {}", e)))?;
             self.cache
                 .set(Self::lc_key(timeframe), &candle)
                 .await
@@ -369,59 +472,229 @@ impl Stock for StockInner {
     /// 历史行情回溯。
     ///
     /// # Logic
-    /// 优先从本地持久化 Store 加载，若不足则调 Provider。
-    /// 根据 `end_at` 确定时间窗口。
+    /// 1. 读取本地已拉取的覆盖区间，计算 `[start, end]` 内的缺口。
+    /// 2. 仅对缺口向 Provider 补拉，落库后记录至最后一根返回 K 线的覆盖区间；空响应与未收盘的
+    ///    末尾周期 (以注入的时钟判断) 不计入覆盖，下次重新拉取。
+    /// 3. 本地与补拉的数据按时间合并，同一时间以补拉数据为准。
     ///
     /// # Arguments
     /// * `timeframe`: 周期。
-    /// * `limit`: 回溯数量。
-    /// * `end_at`: 截止时间。
+    /// * `start`: 开始时间。
+    /// * `end`: 截止时间。
     ///
     /// # Returns
-    /// 历史数据结果向量。
+    /// 按时间升序的历史数据；补拉失败且无任何数据时返回错误。
     async fn fetch_history(
         &self,
         timeframe: TimeFrame,
         start: chrono::DateTime<chrono::Utc>,
         end: chrono::DateTime<chrono::Utc>,
     ) -> Result<Vec<Candle>, MarketError> {
-        // 优先从本地数据库加载
-        if let Some(local) = self
+        let covered = match self
             .store
-            .load_candles(&self.identity, timeframe, start, end)
+            .load_candle_coverage(&self.identity, timeframe)
             .await
-            .ok()
-            .filter(|l| !l.is_empty())
         {
-            return Ok(local);
-        }
+            Ok(covered) => covered,
+            Err(e) => {
+                warn!(
+                    "Failed to load candle coverage for {}: {}",
+                    self.identity.symbol, e
+                );
+                Vec::new()
+            }
+        };
+        let gaps = coverage::gaps(&covered, start, end);
+        let now = self.time_provider.now()?;
 
-        // 本地缺失则拉取远端数据
-        let upstream = self
-            .provider
-            .fetch_candles(&self.identity, timeframe, start, end)
-            .await?;
+        let mut merged: BTreeMap<chrono::DateTime<chrono::Utc>, Candle> = match self
+            .store
+            .load_candles(&self.identity, timeframe, start, end)
+            .await
+        {
+            Ok(local) => local.into_iter().map(|c| (c.time, c)).collect(),
+            Err(e) => {
+                warn!(
+                    "Failed to load local K-lines for {}: {}",
+                    self.identity.symbol, e
+                );
+                BTreeMap::new()
+            }
+        };
 
-        // 异步存入本地数据库作为缓存
-        if !upstream.is_empty() {
-            let store = self.store.clone();
-            let identity_clone = self.identity.clone();
-            let candles_clone = upstream.clone();
-            tokio::spawn(async move {
-                if let Err(e) = store
-                    .save_candles(&identity_clone, timeframe, &candles_clone)
-                    .await
-                {
-                    tracing::error!(
-                        "Failed to cache K-line data for {}: {}",
-                        identity_clone.symbol,
-                        e
-                    );
+        let mut failure = None;
+        for gap in gaps {
+            let upstream = match self
+                .provider
+                .fetch_candles(&self.identity, timeframe, gap.start, gap.end)
+                .await
+            {
+                Ok(upstream) => upstream,
+                Err(e) => {
+                    failure = Some(e);
+                    continue;
                 }
-            });
+            };
+
+            // 先落库再记录覆盖区间，保证覆盖区间内的数据均已保存；空响应不记录覆盖，
+            // 覆盖区间只延伸到最后一根返回的 K 线
+            let Some(last) = upstream.iter().map(|c| c.time).max() else {
+                continue;
+            };
+            match self
+                .store
+                .save_candles(&self.identity, timeframe, &upstream)
+                .await
+            {
+                Ok(()) => {
+                    let settled = gap.end.min(last).min(now - timeframe.duration());
+                    if settled > gap.start
+                        && let Err(e) = self
+                            .store
+                            .save_candle_coverage(
+                                &self.identity,
+                                timeframe,
+                                TimeRange::new(gap.start, settled),
+                            )
+                            .await
+                    {
+                        error!(
+                            "Failed to record candle coverage for {}: {}",
+                            self.identity.symbol, e
+                        );
+                    }
+                }
+                Err(e) => error!(
+                    "Failed to cache K-line data for {}: {}",
+                    self.identity.symbol, e
+                ),
+            }
+
+            merged.extend(
+                upstream
+                    .into_iter()
+                    .filter(|c| c.time >= start && c.time <= end)
+                    .map(|c| (c.time, c)),
+            );
         }
 
-        Ok(upstream)
+        if let Some(e) = failure {
+            if merged.is_empty() {
+                return Err(e);
+            }
+            warn!(
+                "Partial K-line history for {} {}: {}",
+                self.identity.symbol, timeframe, e
+            );
+        }
+        Ok(merged.into_values().collect())
+    }
+
+    /// # Summary
+    /// 查询覆盖情况。
+    ///
+    /// # Logic
+    /// 读取本地记录的覆盖区间并裁剪到查询区间。
+    ///
+    /// # Arguments
+    /// * `timeframe`: 周期。
+    /// * `start`: 开始时间。
+    /// * `end`: 结束时间。
+    ///
+    /// # Returns
+    /// 覆盖区间与缺口。
+    async fn candle_coverage(
+        &self,
+        timeframe: TimeFrame,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<CandleCoverage, MarketError> {
+        let covered = self
+            .store
+            .load_candle_coverage(&self.identity, timeframe)
+            .await
+            .map_err(|e| MarketError::Unknown(format!("candle coverage store error: {}", e)))?;
+        Ok(CandleCoverage::within(&covered, start, end))
+    }
+
+    /// # Summary
+    /// 丢弃覆盖区间。
+    ///
+    /// # Logic
+    /// 从本地记录的覆盖区间中扣除查询区间，已保存的 K 线保留，重新拉取时覆盖写入。
+    ///
+    /// # Arguments
+    /// * `timeframe`: 周期。
+    /// * `start`: 开始时间。
+    /// * `end`: 结束时间。
+    ///
+    /// # Returns
+    /// 成功返回 Ok。
+    async fn clear_candle_coverage(
+        &self,
+        timeframe: TimeFrame,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), MarketError> {
+        self.store
+            .clear_candle_coverage(&self.identity, timeframe, TimeRange::new(start, end))
+            .await
+            .map_err(|e| MarketError::Unknown(format!("candle coverage store error: {}", e)))
+    }
+
+    /// # Summary
+    /// 查询公司行动。
+    ///
+    /// # Logic
+    /// 1. 按日向 Provider 同步一次公司行动并落库。
+    /// 2. 从本地 Store 按除权除息日区间读取。
+    ///
+    /// # Arguments
+    /// * `start`: 开始时间。
+    /// * `end`: 结束时间。
+    ///
+    /// # Returns
+    /// 公司行动列表。
+    async fn corporate_actions(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        self.refresh_corporate_actions().await;
+        self.store
+            .load_corporate_actions(&self.identity, start, end)
+            .await
+            .map_err(|e| MarketError::Unknown(format!("corporate action store error: {}", e)))
+    }
+
+    /// # Summary
+    /// 导入公司行动。
+    ///
+    /// # Logic
+    /// 校验代码与参数后写入本地 Store，覆盖同日同类的已有记录。
+    ///
+    /// # Arguments
+    /// * `actions`: 待导入的公司行动。
+    ///
+    /// # Returns
+    /// 参数非法返回 `MarketError::Parse`。
+    async fn import_corporate_actions(
+        &self,
+        actions: Vec<CorporateAction>,
+    ) -> Result<(), MarketError> {
+        for action in &actions {
+            if action.symbol != self.identity.symbol {
+                return Err(MarketError::Parse(format!(
+                    "corporate action symbol {} does not match {}",
+                    action.symbol, self.identity.symbol
+                )));
+            }
+            action.validate().map_err(MarketError::Parse)?;
+        }
+        self.store
+            .save_corporate_actions(&self.identity, &actions)
+            .await
+            .map_err(|e| MarketError::Unknown(format!("corporate action store error: {}", e)))
     }
 
     /// # Summary
@@ -446,6 +719,7 @@ struct StockFetcher {
     identity: StockIdentity,
     inner: Weak<StockInner>,
     provider: Arc<dyn MarketDataProvider>,
+    recorder: Option<Arc<dyn TickRecorder>>,
 }
 
 impl StockFetcher {
@@ -459,6 +733,7 @@ impl StockFetcher {
     /// * `identity`: 证券身份。
     /// * `inner`: 聚合根弱引用。
     /// * `provider`: 数据源驱动。
+    /// * `recorder`: 原始行情录制器。
     ///
     /// # Returns
     /// 返回 Fetcher 实例。
@@ -466,11 +741,13 @@ impl StockFetcher {
         identity: StockIdentity,
         inner: Weak<StockInner>,
         provider: Arc<dyn MarketDataProvider>,
+        recorder: Option<Arc<dyn TickRecorder>>,
     ) -> Self {
         Self {
             identity,
             inner,
             provider,
+            recorder,
         }
     }
 
@@ -478,7 +755,7 @@ impl StockFetcher {
     /// 启动抓取协程。
     ///
     /// # Logic
-    /// 循环订阅原始行情流并分发至聚合根更新。
+    /// 循环订阅原始行情流，先交由录制器按接收顺序落盘，再交由聚合根聚合为各周期 K 线。
     ///
     /// # Arguments
     /// 无。
@@ -491,10 +768,14 @@ impl StockFetcher {
             while let Some(result) = futures::StreamExt::next(&mut stream).await {
                 match result {
                     Ok(candle) => {
+                        if let Some(recorder) = &self.recorder
+                            && let Err(e) =
+                                recorder.record(&self.identity, chrono::Utc::now(), &candle)
+                        {
+                            warn!("Fetcher: Failed to record {}: {}", self.identity.symbol, e);
+                        }
                         if let Some(stock) = self.inner.upgrade() {
-                            if let Err(e) =
-                                stock.update_and_broadcast(candle, TimeFrame::Minute1).await
-                            {
+                            if let Err(e) = stock.ingest_tick(candle).await {
                                 error!("Fetcher: Failed to update stock: {}", e);
                             }
                         } else {
@@ -580,6 +861,44 @@ mod tests {
         ) -> Result<Vec<Candle>, StoreError> {
             Ok(vec![])
         }
+        async fn load_candle_coverage(
+            &self,
+            _: &StockIdentity,
+            _: TimeFrame,
+        ) -> Result<Vec<TimeRange>, StoreError> {
+            Ok(vec![])
+        }
+        async fn save_candle_coverage(
+            &self,
+            _: &StockIdentity,
+            _: TimeFrame,
+            _: TimeRange,
+        ) -> Result<(), StoreError> {
+            Ok(())
+        }
+        async fn clear_candle_coverage(
+            &self,
+            _: &StockIdentity,
+            _: TimeFrame,
+            _: TimeRange,
+        ) -> Result<(), StoreError> {
+            Ok(())
+        }
+        async fn save_corporate_actions(
+            &self,
+            _: &StockIdentity,
+            _: &[CorporateAction],
+        ) -> Result<(), StoreError> {
+            Ok(())
+        }
+        async fn load_corporate_actions(
+            &self,
+            _: &StockIdentity,
+            _: DateTime<Utc>,
+            _: DateTime<Utc>,
+        ) -> Result<Vec<CorporateAction>, StoreError> {
+            Ok(vec![])
+        }
     }
 
     #[tokio::test]
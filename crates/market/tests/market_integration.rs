@@ -256,6 +256,174 @@ async fn test_stock_fetch_history() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_stock_fetch_history_backfills_only_missing_ranges() -> anyhow::Result<()> {
+    use chrono::TimeZone;
+    use okane_core::common::Stock as StockIdentity;
+    use okane_core::market::coverage::TimeRange;
+    use okane_core::store::port::MarketStore;
+
+    let day = |d: u32| {
+        Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))
+    };
+    let bar = |time, close| Candle {
+        time,
+        open: close,
+        high: close,
+        low: close,
+        close,
+        adj_close: None,
+        volume: dec!(1000),
+        is_final: true,
+    };
+
+    let provider = Arc::new(MockMarketDataProvider::new());
+    let store = Arc::new(MemMarketStore::new());
+    let identity = StockIdentity {
+        symbol: "AAPL".to_string(),
+        exchange: None,
+    };
+    // 本地已有 1 日至 10 日的数据，数据源上同期数据不同，用于区分来源
+    let mut local = Vec::new();
+    let mut upstream = Vec::new();
+    for d in 1..=20 {
+        if d <= 10 {
+            local.push(bar(day(d)?, dec!(100)));
+        }
+        upstream.push(bar(day(d)?, dec!(200)));
+    }
+    store
+        .save_candles(&identity, TimeFrame::Day1, &local)
+        .await?;
+    store
+        .save_candle_coverage(
+            &identity,
+            TimeFrame::Day1,
+            TimeRange::new(day(1)?, day(10)?),
+        )
+        .await?;
+    provider
+        .set_history(upstream)
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let market = MarketImpl::new(provider.clone(), store.clone());
+    let stock = market
+        .get_stock("AAPL")
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let history = stock
+        .fetch_history(TimeFrame::Day1, day(1)?, day(20)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(history.len(), 20);
+    assert!(history.windows(2).all(|w| w[0].time < w[1].time));
+    // 已覆盖区间保持本地数据，缺口 (含边界上的 10 日) 来自数据源
+    assert!(history[..9].iter().all(|c| c.close == dec!(100)));
+    assert!(history[9..].iter().all(|c| c.close == dec!(200)));
+
+    let coverage = stock
+        .candle_coverage(TimeFrame::Day1, day(1)?, day(25)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(coverage.covered, vec![TimeRange::new(day(1)?, day(20)?)]);
+    assert_eq!(coverage.gaps, vec![TimeRange::new(day(20)?, day(25)?)]);
+
+    // 完全覆盖后不再访问数据源
+    provider
+        .set_history(Vec::new())
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let cached = stock
+        .fetch_history(TimeFrame::Day1, day(5)?, day(15)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(cached.len(), 11);
+    assert_eq!(cached[10].close, dec!(200));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stock_fetch_history_records_coverage_only_up_to_returned_settled_bars()
+-> anyhow::Result<()> {
+    use chrono::TimeZone;
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::market::coverage::TimeRange;
+
+    let day = |d: u32| {
+        Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))
+    };
+    let mut upstream = Vec::new();
+    for d in 1..=9 {
+        upstream.push(Candle {
+            time: day(d)?,
+            open: dec!(100),
+            high: dec!(100),
+            low: dec!(100),
+            close: dec!(100),
+            adj_close: None,
+            volume: dec!(1000),
+            is_final: true,
+        });
+    }
+    let provider = Arc::new(MockMarketDataProvider::new());
+    let clock = Arc::new(FakeClockProvider::new(day(20)?));
+    let market = MarketImpl::new_with_clock(
+        provider.clone(),
+        Arc::new(MemMarketStore::new()),
+        None,
+        clock.clone(),
+    );
+    let stock = market
+        .get_stock("AAPL")
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    // 数据源没有任何数据时不记录覆盖
+    stock
+        .fetch_history(TimeFrame::Day1, day(1)?, day(25)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let coverage = stock
+        .candle_coverage(TimeFrame::Day1, day(1)?, day(25)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert!(coverage.covered.is_empty());
+
+    // 覆盖只延伸到最后一根返回的 K 线
+    provider
+        .set_history(upstream)
+        .map_err(|e| anyhow::anyhow!(e))?;
+    stock
+        .fetch_history(TimeFrame::Day1, day(1)?, day(25)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let coverage = stock
+        .candle_coverage(TimeFrame::Day1, day(1)?, day(25)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(coverage.covered, vec![TimeRange::new(day(1)?, day(9)?)]);
+
+    // 末尾周期是否收盘以注入的时钟判断
+    clock.set_time(day(5)?)?;
+    stock
+        .clear_candle_coverage(TimeFrame::Day1, day(1)?, day(25)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    stock
+        .fetch_history(TimeFrame::Day1, day(1)?, day(25)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let coverage = stock
+        .candle_coverage(TimeFrame::Day1, day(1)?, day(25)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(coverage.covered, vec![TimeRange::new(day(1)?, day(4)?)]);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_stock_status() -> anyhow::Result<()> {
     let (market, _) = setup().await;
@@ -266,3 +434,176 @@ async fn test_stock_status() -> anyhow::Result<()> {
     assert_eq!(stock.status(), StockStatus::Online);
     Ok(())
 }
+
+#[tokio::test]
+async fn test_stock_aggregates_ticks_into_finalized_bars() -> anyhow::Result<()> {
+    use chrono::TimeZone;
+
+    let (market, provider) = setup().await;
+    let stock = market
+        .get_stock("AAPL")
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let mut minute = stock
+        .subscribe(TimeFrame::Minute1)
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let mut five = stock
+        .subscribe(TimeFrame::Minute5)
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    // 2024-01-02 10:00:05、10:00:40，静默后 10:06:10
+    let base = Utc
+        .with_ymd_and_hms(2024, 1, 2, 10, 0, 0)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?;
+    for (secs, price) in [(5, dec!(100)), (40, dec!(103)), (370, dec!(101))] {
+        provider.push_candle(Candle {
+            time: base + chrono::Duration::seconds(secs),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            adj_close: None,
+            volume: dec!(10),
+            is_final: false,
+        });
+    }
+
+    async fn take(stream: &mut okane_core::market::port::CandleStream) -> anyhow::Result<Candle> {
+        tokio::time::timeout(Duration::from_secs(1), stream.next())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Stream ended too early"))?
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    let mut minute_bars = Vec::new();
+    for _ in 0..4 {
+        minute_bars.push(take(&mut minute).await?);
+    }
+    let finals: Vec<&Candle> = minute_bars.iter().filter(|c| c.is_final).collect();
+    assert_eq!(finals.len(), 1);
+    assert_eq!(finals[0].time, base);
+    assert_eq!((finals[0].open, finals[0].high), (dec!(100), dec!(103)));
+    assert_eq!(finals[0].close, dec!(103));
+    assert_eq!(finals[0].volume, dec!(20));
+    assert!(minute_bars[2].is_final);
+
+    let mut five_bars = Vec::new();
+    for _ in 0..4 {
+        five_bars.push(take(&mut five).await?);
+    }
+    assert_eq!(
+        five_bars.iter().map(|c| c.is_final).collect::<Vec<_>>(),
+        vec![false, false, true, false]
+    );
+    assert_eq!(five_bars[3].time, base + chrono::Duration::minutes(5));
+
+    let closed = stock
+        .last_closed_candle(TimeFrame::Minute5)
+        .map_err(|e| anyhow::anyhow!(e))?
+        .ok_or_else(|| anyhow::anyhow!("Closed candle null"))?;
+    assert_eq!(closed.volume, dec!(20));
+    assert!(
+        stock
+            .last_closed_candle(TimeFrame::Hour1)
+            .map_err(|e| anyhow::anyhow!(e))?
+            .is_none()
+    );
+    assert_eq!(
+        stock
+            .latest_candle(TimeFrame::Day1)
+            .map_err(|e| anyhow::anyhow!(e))?
+            .ok_or_else(|| anyhow::anyhow!("Daily candle null"))?
+            .volume,
+        dec!(30)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recorded_live_session_replays_to_identical_bars() -> anyhow::Result<()> {
+    use chrono::TimeZone;
+    use okane_feed::recorder::FileTickRecorder;
+    use okane_feed::replay::ReplayProvider;
+
+    async fn take(stream: &mut okane_core::market::port::CandleStream) -> anyhow::Result<Candle> {
+        tokio::time::timeout(Duration::from_secs(1), stream.next())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Stream ended too early"))?
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    let dir = tempfile::tempdir()?;
+    let provider = Arc::new(MockMarketDataProvider::new());
+    let recorder = Arc::new(FileTickRecorder::new(dir.path()).map_err(|e| anyhow::anyhow!(e))?);
+    let live = MarketImpl::new_with_recorder(
+        provider.clone(),
+        Arc::new(MemMarketStore::new()),
+        recorder.clone(),
+    );
+    let stock = live
+        .get_stock("AAPL")
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let mut minute = stock
+        .subscribe(TimeFrame::Minute1)
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let base = Utc
+        .with_ymd_and_hms(2024, 1, 2, 10, 0, 0)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?;
+    for (secs, price) in [(5, dec!(100)), (40, dec!(103)), (70, dec!(101))] {
+        provider.push_candle(Candle {
+            time: base + chrono::Duration::seconds(secs),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            adj_close: None,
+            volume: dec!(10),
+            is_final: false,
+        });
+    }
+    let mut live_bars = Vec::new();
+    for _ in 0..4 {
+        live_bars.push(take(&mut minute).await?);
+    }
+    recorder.flush().map_err(|e| anyhow::anyhow!(e))?;
+    let session = Utc::now().date_naive();
+
+    // 以录制文件重放：先暂停时钟，订阅后再放行，保证不漏掉首条更新
+    let replay = ReplayProvider::new(dir.path(), session, 0.0)
+        .or_else(|_| ReplayProvider::new(dir.path(), session - chrono::Duration::days(1), 0.0))
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let clock = replay.clock();
+    clock.pause().map_err(|e| anyhow::anyhow!(e))?;
+    let replayed = MarketImpl::new(Arc::new(replay), Arc::new(MemMarketStore::new()));
+    let stock = replayed
+        .get_stock("AAPL")
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let mut minute = stock
+        .subscribe(TimeFrame::Minute1)
+        .map_err(|e| anyhow::anyhow!(e))?;
+    clock.resume().map_err(|e| anyhow::anyhow!(e))?;
+
+    for expected in &live_bars {
+        let bar = take(&mut minute).await?;
+        assert_eq!(
+            (bar.time, bar.open, bar.high, bar.low, bar.close),
+            (
+                expected.time,
+                expected.open,
+                expected.high,
+                expected.low,
+                expected.close
+            )
+        );
+        assert_eq!(
+            (bar.volume, bar.is_final),
+            (expected.volume, expected.is_final)
+        );
+    }
+    Ok(())
+}
@@ -124,6 +124,11 @@ mod tests {
         ) -> Result<Option<okane_core::store::port::AccountProfile>, StoreError> {
             Err(unsupported_store_call())
         }
+        async fn list_account_profiles(
+            &self,
+        ) -> Result<Vec<okane_core::store::port::AccountProfile>, StoreError> {
+            Err(unsupported_store_call())
+        }
         async fn bind_account(
             &self,
             _: &str,
@@ -137,6 +142,28 @@ mod tests {
         async fn get_user_accounts(&self, _: &str) -> Result<Vec<String>, StoreError> {
             Err(unsupported_store_call())
         }
+        async fn update_account_config(
+            &self,
+            _: &str,
+            _: serde_json::Value,
+        ) -> Result<(), StoreError> {
+            Err(unsupported_store_call())
+        }
+        async fn set_account_halted(&self, _: &str, _: bool) -> Result<(), StoreError> {
+            Err(unsupported_store_call())
+        }
+        async fn save_account_audit(
+            &self,
+            _: &okane_core::store::port::AccountAuditRecord,
+        ) -> Result<(), StoreError> {
+            Err(unsupported_store_call())
+        }
+        async fn list_account_audit(
+            &self,
+            _: &str,
+        ) -> Result<Vec<okane_core::store::port::AccountAuditRecord>, StoreError> {
+            Err(unsupported_store_call())
+        }
         async fn save_user(&self, _: &User) -> Result<(), StoreError> {
             Err(unsupported_store_call())
         }
@@ -295,6 +322,11 @@ mod tests {
             ) -> Result<Option<okane_core::store::port::AccountProfile>, StoreError> {
                 Err(unsupported_store_call())
             }
+            async fn list_account_profiles(
+                &self,
+            ) -> Result<Vec<okane_core::store::port::AccountProfile>, StoreError> {
+                Err(unsupported_store_call())
+            }
             async fn bind_account(
                 &self,
                 _: &str,
@@ -308,6 +340,28 @@ mod tests {
             async fn get_user_accounts(&self, _: &str) -> Result<Vec<String>, StoreError> {
                 Err(unsupported_store_call())
             }
+            async fn update_account_config(
+                &self,
+                _: &str,
+                _: serde_json::Value,
+            ) -> Result<(), StoreError> {
+                Err(unsupported_store_call())
+            }
+            async fn set_account_halted(&self, _: &str, _: bool) -> Result<(), StoreError> {
+                Err(unsupported_store_call())
+            }
+            async fn save_account_audit(
+                &self,
+                _: &okane_core::store::port::AccountAuditRecord,
+            ) -> Result<(), StoreError> {
+                Err(unsupported_store_call())
+            }
+            async fn list_account_audit(
+                &self,
+                _: &str,
+            ) -> Result<Vec<okane_core::store::port::AccountAuditRecord>, StoreError> {
+                Err(unsupported_store_call())
+            }
             async fn save_user(&self, _: &User) -> Result<(), StoreError> {
                 Err(unsupported_store_call())
             }
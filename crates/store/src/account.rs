@@ -1,16 +1,20 @@
 use async_trait::async_trait;
 use chrono::Utc;
 use dashmap::DashMap;
-use okane_core::trade::entity::{AccountId, AccountSnapshot, OrderDirection, Position, Trade};
+use okane_core::market::entity::{CorporateAction, CorporateActionKind};
+use okane_core::trade::entity::{
+    AccountId, AccountSnapshot, CashTransfer, CurrencyBalance, DailyPnl, FxSettlement, HistoryPage,
+    LedgerAction, LedgerEntry, OrderDirection, Position, Trade,
+};
 use okane_core::trade::port::{AccountPort, TradeError};
 use rust_decimal::Decimal;
 use sqlx::{
-    SqlitePool,
+    SqliteConnection, SqlitePool,
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
 };
 use std::path::PathBuf;
 use std::str::FromStr;
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 
 /// Safely parse a database string into Decimal, returning an explicit error instead of defaulting to 0.
 fn parse_decimal(s: &str) -> Result<Decimal, TradeError> {
@@ -31,6 +35,8 @@ CREATE TABLE IF NOT EXISTS asset_status (
     id TEXT PRIMARY KEY,
     available_balance TEXT NOT NULL,
     frozen_balance TEXT NOT NULL,
+    realized_pnl TEXT NOT NULL DEFAULT '0',
+    total_commission TEXT NOT NULL DEFAULT '0',
     updated_at DATETIME NOT NULL
 );
 
@@ -38,6 +44,8 @@ CREATE TABLE IF NOT EXISTS positions (
     symbol TEXT PRIMARY KEY,
     quantity TEXT NOT NULL,
     avg_price TEXT NOT NULL,
+    realized_pnl TEXT NOT NULL DEFAULT '0',
+    commission TEXT NOT NULL DEFAULT '0',
     updated_at DATETIME NOT NULL
 );
 
@@ -46,30 +54,313 @@ CREATE TABLE IF NOT EXISTS trade_ledger (
     action_type TEXT NOT NULL,
     asset_change TEXT NOT NULL,
     frozen_change TEXT NOT NULL,
+    available_balance TEXT,
+    frozen_balance TEXT,
+    reference TEXT,
+    currency TEXT,
+    created_at DATETIME NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS daily_pnl (
+    date TEXT PRIMARY KEY,
+    opening_equity TEXT NOT NULL,
+    total_equity TEXT NOT NULL,
+    realized_pnl TEXT NOT NULL,
+    unrealized_pnl TEXT NOT NULL,
+    total_commission TEXT NOT NULL,
+    day_pnl TEXT NOT NULL,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS outgoing_transfers (
+    transfer_id TEXT PRIMARY KEY,
+    to_account TEXT NOT NULL,
+    currency TEXT,
+    amount TEXT NOT NULL,
     created_at DATETIME NOT NULL
 );
+
+CREATE TABLE IF NOT EXISTS borrow_fee_accrual (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    accrued_on TEXT NOT NULL,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS corporate_action_progress (
+    symbol TEXT PRIMARY KEY,
+    processed_until INTEGER NOT NULL,
+    updated_at DATETIME NOT NULL
+);
 "#;
 
+/// 旧库补列：盈亏与费用累计、流水余额、关联标识与币种
+const SQL_MIGRATIONS: [&str; 8] = [
+    "ALTER TABLE asset_status ADD COLUMN realized_pnl TEXT NOT NULL DEFAULT '0'",
+    "ALTER TABLE asset_status ADD COLUMN total_commission TEXT NOT NULL DEFAULT '0'",
+    "ALTER TABLE positions ADD COLUMN realized_pnl TEXT NOT NULL DEFAULT '0'",
+    "ALTER TABLE positions ADD COLUMN commission TEXT NOT NULL DEFAULT '0'",
+    "ALTER TABLE trade_ledger ADD COLUMN available_balance TEXT",
+    "ALTER TABLE trade_ledger ADD COLUMN frozen_balance TEXT",
+    "ALTER TABLE trade_ledger ADD COLUMN reference TEXT",
+    "ALTER TABLE trade_ledger ADD COLUMN currency TEXT",
+];
+
+/// 基础货币现金所在的资产槽位；外币现金以币种代码作为槽位 ID。
+const MAIN_SLOT: &str = "MAIN";
+
 const SQL_INSERT_ASSET_IGNORE: &str = r#"
 INSERT OR IGNORE INTO asset_status (id, available_balance, frozen_balance, updated_at)
-VALUES ('MAIN', '0', '0', ?)
+VALUES (?, '0', '0', ?)
 "#;
 
 const SQL_SELECT_ASSET: &str =
-    "SELECT available_balance, frozen_balance FROM asset_status WHERE id = 'MAIN'";
+    "SELECT available_balance, frozen_balance FROM asset_status WHERE id = ?";
+
+const SQL_SELECT_FOREIGN_ASSETS: &str = "SELECT id, available_balance, frozen_balance FROM asset_status WHERE id <> 'MAIN' ORDER BY id ASC";
+
+const SQL_UPDATE_ASSET_FULL: &str = "UPDATE asset_status SET available_balance = ?, frozen_balance = ?, updated_at = ? WHERE id = ?";
+
+const SQL_SELECT_PNL_TOTALS: &str =
+    "SELECT realized_pnl, total_commission FROM asset_status WHERE id = 'MAIN'";
+
+const SQL_UPDATE_PNL_TOTALS: &str =
+    "UPDATE asset_status SET realized_pnl = ?, total_commission = ? WHERE id = 'MAIN'";
+
+const SQL_UPSERT_DAILY_PNL: &str = r#"
+INSERT INTO daily_pnl (date, opening_equity, total_equity, realized_pnl, unrealized_pnl, total_commission, day_pnl, updated_at)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT(date) DO UPDATE SET
+    opening_equity = excluded.opening_equity,
+    total_equity = excluded.total_equity,
+    realized_pnl = excluded.realized_pnl,
+    unrealized_pnl = excluded.unrealized_pnl,
+    total_commission = excluded.total_commission,
+    day_pnl = excluded.day_pnl,
+    updated_at = excluded.updated_at
+"#;
+
+const SQL_SELECT_DAILY_PNL: &str = "SELECT date, opening_equity, total_equity, realized_pnl, unrealized_pnl, total_commission, day_pnl FROM daily_pnl ORDER BY date ASC";
+
+const SQL_SELECT_BORROW_FEE_ACCRUAL: &str =
+    "SELECT accrued_on FROM borrow_fee_accrual WHERE id = 1";
+
+const SQL_UPSERT_BORROW_FEE_ACCRUAL: &str = r#"
+INSERT INTO borrow_fee_accrual (id, accrued_on, updated_at)
+VALUES (1, ?, ?)
+ON CONFLICT(id) DO UPDATE SET
+    accrued_on = excluded.accrued_on,
+    updated_at = excluded.updated_at
+"#;
+
+const SQL_SELECT_CORPORATE_ACTION_PROGRESS: &str =
+    "SELECT processed_until FROM corporate_action_progress WHERE symbol = ?";
+
+const SQL_ADVANCE_CORPORATE_ACTION_PROGRESS: &str = r#"
+INSERT INTO corporate_action_progress (symbol, processed_until, updated_at)
+VALUES (?, ?, ?)
+ON CONFLICT(symbol) DO UPDATE SET
+    processed_until = MAX(processed_until, excluded.processed_until),
+    updated_at = excluded.updated_at
+"#;
+
+const SQL_INSERT_OUTGOING_TRANSFER: &str = "INSERT INTO outgoing_transfers (transfer_id, to_account, currency, amount, created_at) VALUES (?, ?, ?, ?, ?)";
+
+const SQL_SELECT_OUTGOING_TRANSFERS: &str = "SELECT transfer_id, to_account, currency, amount FROM outgoing_transfers ORDER BY created_at ASC";
+
+const SQL_DELETE_OUTGOING_TRANSFER: &str = "DELETE FROM outgoing_transfers WHERE transfer_id = ?";
+
+const SQL_COUNT_TRANSFER_IN: &str =
+    "SELECT COUNT(*) FROM trade_ledger WHERE action_type = 'TransferIn' AND reference = ?";
+
+const SQL_INSERT_LEDGER: &str = "INSERT INTO trade_ledger (action_type, asset_change, frozen_change, available_balance, frozen_balance, reference, currency, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
+
+const SQL_SELECT_LEDGER_PAGE: &str = "SELECT id, action_type, asset_change, frozen_change, available_balance, frozen_balance, reference, currency, created_at FROM trade_ledger ORDER BY id DESC LIMIT ? OFFSET ?";
+
+fn action_name(action: LedgerAction) -> &'static str {
+    match action {
+        LedgerAction::Deposit => "Deposit",
+        LedgerAction::Withdrawal => "Withdrawal",
+        LedgerAction::TransferIn => "TransferIn",
+        LedgerAction::TransferOut => "TransferOut",
+        LedgerAction::FreezeFunds => "FreezeFunds",
+        LedgerAction::UnfreezeFunds => "UnfreezeFunds",
+        LedgerAction::FreezeMargin => "FreezeMargin",
+        LedgerAction::TradeFilled => "TradeFilled",
+        LedgerAction::BorrowFee => "BorrowFee",
+        LedgerAction::Split => "Split",
+        LedgerAction::Dividend => "Dividend",
+        LedgerAction::FxConversion => "FxConversion",
+    }
+}
+
+fn parse_action(value: &str) -> Result<LedgerAction, TradeError> {
+    match value {
+        "Deposit" => Ok(LedgerAction::Deposit),
+        "Withdrawal" => Ok(LedgerAction::Withdrawal),
+        "TransferIn" => Ok(LedgerAction::TransferIn),
+        "TransferOut" => Ok(LedgerAction::TransferOut),
+        "FreezeFunds" => Ok(LedgerAction::FreezeFunds),
+        "UnfreezeFunds" => Ok(LedgerAction::UnfreezeFunds),
+        "FreezeMargin" => Ok(LedgerAction::FreezeMargin),
+        "TradeFilled" => Ok(LedgerAction::TradeFilled),
+        "BorrowFee" => Ok(LedgerAction::BorrowFee),
+        "Split" => Ok(LedgerAction::Split),
+        "Dividend" => Ok(LedgerAction::Dividend),
+        "FxConversion" => Ok(LedgerAction::FxConversion),
+        other => Err(TradeError::InternalError(format!(
+            "Invalid ledger action: {}",
+            other
+        ))),
+    }
+}
+
+/// 以变动后的余额构造一条待写入的流水 (`id` 由写入时回填)。
+fn ledger_entry(
+    account_id: &AccountId,
+    action: LedgerAction,
+    currency: Option<&str>,
+    asset_change: Decimal,
+    frozen_change: Decimal,
+    available_balance: Decimal,
+    frozen_balance: Decimal,
+) -> LedgerEntry {
+    LedgerEntry {
+        id: 0,
+        account_id: account_id.clone(),
+        action,
+        asset_change,
+        frozen_change,
+        available_balance,
+        frozen_balance,
+        reference: None,
+        created_at: Utc::now().timestamp_millis(),
+        currency: currency.map(str::to_string),
+    }
+}
+
+/// # Logic
+/// 读取币种对应槽位的 (可用, 冻结) 现金，`None` 为基础货币；外币槽位首次使用时以零余额开立。
+async fn load_cash(
+    conn: &mut SqliteConnection,
+    currency: Option<&str>,
+) -> Result<(Decimal, Decimal), TradeError> {
+    if let Some(code) = currency {
+        sqlx::query(SQL_INSERT_ASSET_IGNORE)
+            .bind(code)
+            .bind(Utc::now())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+    }
+    let row: (String, String) = sqlx::query_as(SQL_SELECT_ASSET)
+        .bind(currency.unwrap_or(MAIN_SLOT))
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+    Ok((parse_decimal(&row.0)?, parse_decimal(&row.1)?))
+}
 
-const SQL_UPDATE_ASSET_AVAIL: &str =
-    "UPDATE asset_status SET available_balance = ?, updated_at = ? WHERE id = 'MAIN'";
+/// 写回币种对应槽位的可用与冻结现金。
+async fn store_cash(
+    conn: &mut SqliteConnection,
+    currency: Option<&str>,
+    available: Decimal,
+    frozen: Decimal,
+) -> Result<(), TradeError> {
+    sqlx::query(SQL_UPDATE_ASSET_FULL)
+        .bind(available.to_string())
+        .bind(frozen.to_string())
+        .bind(Utc::now())
+        .bind(currency.unwrap_or(MAIN_SLOT))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+    Ok(())
+}
 
-const SQL_UPDATE_ASSET_FULL: &str = "UPDATE asset_status SET available_balance = ?, frozen_balance = ?, updated_at = ? WHERE id = 'MAIN'";
+/// 累加账户级的已实现盈亏与费用合计 (均以基础货币计)。
+async fn add_pnl_totals(
+    conn: &mut SqliteConnection,
+    realized: Decimal,
+    commission: Decimal,
+) -> Result<(), TradeError> {
+    let totals: (String, String) = sqlx::query_as(SQL_SELECT_PNL_TOTALS)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+    sqlx::query(SQL_UPDATE_PNL_TOTALS)
+        .bind((parse_decimal(&totals.0)? + realized).to_string())
+        .bind((parse_decimal(&totals.1)? + commission).to_string())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+    Ok(())
+}
 
-const SQL_INSERT_LEDGER: &str = "INSERT INTO trade_ledger (action_type, asset_change, frozen_change, created_at) VALUES (?, ?, ?, ?)";
+/// 在当前事务中追加一条流水并回填流水号。
+async fn insert_ledger(
+    conn: &mut SqliteConnection,
+    entry: &mut LedgerEntry,
+) -> Result<(), TradeError> {
+    let created_at =
+        chrono::DateTime::<Utc>::from_timestamp_millis(entry.created_at).unwrap_or_else(Utc::now);
+    let result = sqlx::query(SQL_INSERT_LEDGER)
+        .bind(action_name(entry.action))
+        .bind(entry.asset_change.to_string())
+        .bind(entry.frozen_change.to_string())
+        .bind(entry.available_balance.to_string())
+        .bind(entry.frozen_balance.to_string())
+        .bind(entry.reference.as_deref())
+        .bind(entry.currency.as_deref())
+        .bind(created_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+    entry.id = result.last_insert_rowid();
+    Ok(())
+}
+
+/// 读取该标的公司行动已处理至的时刻 (毫秒)。
+async fn load_corporate_action_progress(
+    conn: &mut SqliteConnection,
+    symbol: &str,
+) -> Result<Option<i64>, TradeError> {
+    sqlx::query_scalar(SQL_SELECT_CORPORATE_ACTION_PROGRESS)
+        .bind(symbol)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))
+}
+
+/// 把该标的公司行动处理进度推进至 `until_ms`，不后退。
+async fn advance_corporate_action_progress(
+    conn: &mut SqliteConnection,
+    symbol: &str,
+    until_ms: i64,
+) -> Result<(), TradeError> {
+    sqlx::query(SQL_ADVANCE_CORPORATE_ACTION_PROGRESS)
+        .bind(symbol)
+        .bind(until_ms)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+    Ok(())
+}
 
 impl SqliteAccountStore {
     pub fn new() -> Result<Self, TradeError> {
-        let base_path = crate::config::get_root_dir()
-            .map_err(|e| TradeError::InternalError(e.to_string()))?
-            .join("accounts");
+        Self::new_with_path(None)
+    }
+
+    /// 以指定数据根目录创建仓储，账户库位于 `<root>/accounts`；缺省使用全局配置的根目录。
+    pub fn new_with_path(root_path: Option<PathBuf>) -> Result<Self, TradeError> {
+        let base_path = match root_path {
+            Some(p) => p,
+            None => crate::config::get_root_dir()
+                .map_err(|e| TradeError::InternalError(e.to_string()))?,
+        }
+        .join("accounts");
         if !base_path.exists() {
             std::fs::create_dir_all(&base_path).map_err(|e| {
                 TradeError::InternalError(format!("Failed to create account dir: {}", e))
@@ -105,9 +396,14 @@ impl SqliteAccountStore {
             .execute(&pool)
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        crate::migration::add_columns(&pool, &SQL_MIGRATIONS)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Self::backfill_ledger_balances(&pool).await?;
 
         // 初始化默认的 MAIN 资产槽位
         sqlx::query(SQL_INSERT_ASSET_IGNORE)
+            .bind(MAIN_SLOT)
             .bind(Utc::now())
             .execute(&pool)
             .await
@@ -117,43 +413,197 @@ impl SqliteAccountStore {
         Ok(pool)
     }
 
-    /// 后台充值接口 (供系统管理或测试接入资本)
-    pub async fn deposit(&self, account_id: &AccountId, amount: Decimal) -> Result<(), TradeError> {
-        let pool = self.get_or_init_pool(&account_id.0).await?;
+    /// # Logic
+    /// 旧库的流水没有余额列：按流水号顺序累加 `asset_change` / `frozen_change` 补齐。
+    async fn backfill_ledger_balances(pool: &SqlitePool) -> Result<(), TradeError> {
+        let missing: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM trade_ledger WHERE available_balance IS NULL OR frozen_balance IS NULL",
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        if missing == 0 {
+            return Ok(());
+        }
+
         let mut tx = pool
             .begin()
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let rows: Vec<(i64, String, String)> = sqlx::query_as(
+            "SELECT id, asset_change, frozen_change FROM trade_ledger ORDER BY id ASC",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let mut available = Decimal::ZERO;
+        let mut frozen = Decimal::ZERO;
+        for (id, asset_change, frozen_change) in rows {
+            available += parse_decimal(&asset_change)?;
+            frozen += parse_decimal(&frozen_change)?;
+            sqlx::query(
+                "UPDATE trade_ledger SET available_balance = ?, frozen_balance = ? WHERE id = ?",
+            )
+            .bind(available.to_string())
+            .bind(frozen.to_string())
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        info!("Backfilled running balances for {} ledger entries", missing);
+        Ok(())
+    }
 
-        let row: (String, String) = sqlx::query_as(SQL_SELECT_ASSET)
+    /// # Logic
+    /// 在当前事务中按 `amount` (正为入账、负为出账) 调整该币种的可用资金并记流水。
+    /// 出账时可用资金不足返回 `InsufficientFunds`。
+    async fn move_cash(
+        conn: &mut SqliteConnection,
+        account_id: &AccountId,
+        action: LedgerAction,
+        currency: Option<&str>,
+        amount: Decimal,
+        reference: Option<&str>,
+    ) -> Result<LedgerEntry, TradeError> {
+        let (avail, frozen) = load_cash(conn, currency).await?;
+        if amount.is_sign_negative() && avail < -amount {
+            return Err(TradeError::InsufficientFunds {
+                required: -amount,
+                actual: avail,
+            });
+        }
+        let avail = avail + amount;
+        store_cash(conn, currency, avail, frozen).await?;
+
+        let mut entry = ledger_entry(
+            account_id,
+            action,
+            currency,
+            amount,
+            Decimal::ZERO,
+            avail,
+            frozen,
+        );
+        entry.reference = reference.map(str::to_string);
+        insert_ledger(conn, &mut entry).await?;
+        Ok(entry)
+    }
+
+    /// # Logic
+    /// 在转入方库的事务中入账一笔转账；转入方已有同 `transfer_id` 的 `TransferIn` 时不重复入账。
+    ///
+    /// # Returns
+    /// 新写入的流水；已入账过时返回 `None`。
+    async fn credit_transfer(
+        &self,
+        to: &AccountId,
+        currency: Option<&str>,
+        amount: Decimal,
+        transfer_id: &str,
+    ) -> Result<Option<LedgerEntry>, TradeError> {
+        let pool = self.get_or_init_pool(&to.0).await?;
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let credited: i64 = sqlx::query_scalar(SQL_COUNT_TRANSFER_IN)
+            .bind(transfer_id)
             .fetch_one(&mut *tx)
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        if credited > 0 {
+            return Ok(None);
+        }
+        let entry = Self::move_cash(
+            &mut tx,
+            to,
+            LedgerAction::TransferIn,
+            currency,
+            amount,
+            Some(transfer_id),
+        )
+        .await?;
+        tx.commit()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(Some(entry))
+    }
 
-        let mut avail = parse_decimal(&row.0)?;
-        avail += amount;
-
-        sqlx::query(SQL_UPDATE_ASSET_AVAIL)
-            .bind(avail.to_string())
-            .bind(Utc::now())
-            .execute(&mut *tx)
+    /// 转入方入账后从转出方库删除转账日志。
+    async fn clear_transfer_journal(
+        &self,
+        from: &AccountId,
+        transfer_id: &str,
+    ) -> Result<(), TradeError> {
+        let pool = self.get_or_init_pool(&from.0).await?;
+        sqlx::query(SQL_DELETE_OUTGOING_TRANSFER)
+            .bind(transfer_id)
+            .execute(&pool)
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(())
+    }
 
-        sqlx::query(SQL_INSERT_LEDGER)
-            .bind("Deposit")
-            .bind(amount.to_string())
-            .bind("0")
-            .bind(Utc::now())
-            .execute(&mut *tx)
+    /// # Logic
+    /// 启动时补完因进程中断而停留在转账日志中的转账：
+    /// 1. 扫描数据目录下的全部账户库，读取各库未删除的转出日志。
+    /// 2. 转出方扣款已提交，按日志向转入方补记入账 (已入账则跳过)，随后删除日志。
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - 补完的转账笔数。
+    /// * `Err(TradeError)` - 数据目录或账户库无法读取。
+    pub async fn recover_transfers(&self) -> Result<usize, TradeError> {
+        let mut recovered = 0;
+        for from in self.list_accounts().await? {
+            let pool = self.get_or_init_pool(&from.0).await?;
+            let rows = sqlx::query_as::<_, (String, String, Option<String>, String)>(
+                SQL_SELECT_OUTGOING_TRANSFERS,
+            )
+            .fetch_all(&pool)
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
+            for (transfer_id, to_account, currency, amount) in rows {
+                let to = AccountId(to_account);
+                self.credit_transfer(
+                    &to,
+                    currency.as_deref(),
+                    parse_decimal(&amount)?,
+                    &transfer_id,
+                )
+                .await?;
+                self.clear_transfer_journal(&from, &transfer_id).await?;
+                warn!(
+                    "Recovered transfer {} from account {} to {}",
+                    transfer_id, from.0, to.0
+                );
+                recovered += 1;
+            }
+        }
+        Ok(recovered)
+    }
 
+    /// 在单个账户库的事务中完成一笔入金或出金。
+    async fn single_cash_movement(
+        &self,
+        account_id: &AccountId,
+        action: LedgerAction,
+        currency: Option<&str>,
+        amount: Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let entry = Self::move_cash(&mut tx, account_id, action, currency, amount, None).await?;
         tx.commit()
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
-        info!("Deposited {} into account {}", amount, account_id.0);
-        Ok(())
+        Ok(entry)
     }
 }
 
@@ -162,6 +612,7 @@ impl AccountPort for SqliteAccountStore {
     async fn freeze_funds(
         &self,
         account_id: &AccountId,
+        currency: Option<&str>,
         amount: Decimal,
     ) -> Result<(), TradeError> {
         let pool = self.get_or_init_pool(&account_id.0).await?;
@@ -170,14 +621,7 @@ impl AccountPort for SqliteAccountStore {
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
 
-        let row: (String, String) = sqlx::query_as(SQL_SELECT_ASSET)
-            .fetch_one(&mut *tx)
-            .await
-            .map_err(|e| TradeError::InternalError(e.to_string()))?;
-
-        let mut avail = parse_decimal(&row.0)?;
-        let mut frozen = parse_decimal(&row.1)?;
-
+        let (mut avail, mut frozen) = load_cash(&mut tx, currency).await?;
         if avail < amount {
             return Err(TradeError::InsufficientFunds {
                 required: amount,
@@ -188,22 +632,18 @@ impl AccountPort for SqliteAccountStore {
         avail -= amount;
         frozen += amount;
 
-        sqlx::query(SQL_UPDATE_ASSET_FULL)
-            .bind(avail.to_string())
-            .bind(frozen.to_string())
-            .bind(Utc::now())
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        store_cash(&mut tx, currency, avail, frozen).await?;
 
-        sqlx::query(SQL_INSERT_LEDGER)
-            .bind("FreezeFunds")
-            .bind((-amount).to_string())
-            .bind(amount.to_string())
-            .bind(Utc::now())
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let mut entry = ledger_entry(
+            account_id,
+            LedgerAction::FreezeFunds,
+            currency,
+            -amount,
+            amount,
+            avail,
+            frozen,
+        );
+        insert_ledger(&mut tx, &mut entry).await?;
 
         tx.commit()
             .await
@@ -214,6 +654,7 @@ impl AccountPort for SqliteAccountStore {
     async fn unfreeze_funds(
         &self,
         account_id: &AccountId,
+        currency: Option<&str>,
         amount: Decimal,
     ) -> Result<(), TradeError> {
         let pool = self.get_or_init_pool(&account_id.0).await?;
@@ -222,14 +663,7 @@ impl AccountPort for SqliteAccountStore {
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
 
-        let row: (String, String) = sqlx::query_as(SQL_SELECT_ASSET)
-            .fetch_one(&mut *tx)
-            .await
-            .map_err(|e| TradeError::InternalError(e.to_string()))?;
-
-        let mut avail = parse_decimal(&row.0)?;
-        let mut frozen = parse_decimal(&row.1)?;
-
+        let (mut avail, mut frozen) = load_cash(&mut tx, currency).await?;
         let actual_unfreeze = if amount > frozen {
             warn!(
                 "account {} unfreeze anomaly: trying to unfreeze {} but only {} available",
@@ -243,18 +677,114 @@ impl AccountPort for SqliteAccountStore {
         frozen -= actual_unfreeze;
         avail += actual_unfreeze;
 
-        sqlx::query("UPDATE asset_status SET available_balance = ?, frozen_balance = ?, updated_at = ? WHERE id = 'MAIN'")
-            .bind(avail.to_string())
-            .bind(frozen.to_string())
-            .bind(Utc::now())
-            .execute(&mut *tx)
+        store_cash(&mut tx, currency, avail, frozen).await?;
+
+        let mut entry = ledger_entry(
+            account_id,
+            LedgerAction::UnfreezeFunds,
+            currency,
+            actual_unfreeze,
+            -actual_unfreeze,
+            avail,
+            frozen,
+        );
+        insert_ledger(&mut tx, &mut entry).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn freeze_margin(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: Decimal,
+        excess_margin: Decimal,
+    ) -> Result<(), TradeError> {
+        if excess_margin < amount {
+            return Err(TradeError::InsufficientFunds {
+                required: amount,
+                actual: excess_margin,
+            });
+        }
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        let (avail, frozen) = load_cash(&mut tx, currency).await?;
+        let avail = avail - amount;
+        let frozen = frozen + amount;
+
+        store_cash(&mut tx, currency, avail, frozen).await?;
+
+        let mut entry = ledger_entry(
+            account_id,
+            LedgerAction::FreezeMargin,
+            currency,
+            -amount,
+            amount,
+            avail,
+            frozen,
+        );
+        insert_ledger(&mut tx, &mut entry).await?;
+
+        tx.commit()
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(())
+    }
 
-        sqlx::query("INSERT INTO trade_ledger (action_type, asset_change, frozen_change, created_at) VALUES (?, ?, ?, ?)")
-            .bind("UnfreezeFunds")
-            .bind(actual_unfreeze.to_string())
-            .bind((-actual_unfreeze).to_string())
+    async fn borrow_fee_accrued_on(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<chrono::NaiveDate>, TradeError> {
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let row = sqlx::query_as::<_, (String,)>(SQL_SELECT_BORROW_FEE_ACCRUAL)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        row.map(|(date,)| {
+            chrono::NaiveDate::from_str(&date).map_err(|e| {
+                TradeError::InternalError(format!("Failed to parse date '{}': {}", date, e))
+            })
+        })
+        .transpose()
+    }
+
+    async fn accrue_borrow_fee(
+        &self,
+        account_id: &AccountId,
+        amount: Decimal,
+        accrued_on: chrono::NaiveDate,
+    ) -> Result<(), TradeError> {
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        if amount > Decimal::ZERO {
+            let (avail, frozen) = load_cash(&mut tx, None).await?;
+            let avail = avail - amount;
+            store_cash(&mut tx, None, avail, frozen).await?;
+
+            let mut entry = ledger_entry(
+                account_id,
+                LedgerAction::BorrowFee,
+                None,
+                -amount,
+                Decimal::ZERO,
+                avail,
+                frozen,
+            );
+            insert_ledger(&mut tx, &mut entry).await?;
+        }
+        sqlx::query(SQL_UPSERT_BORROW_FEE_ACCRUAL)
+            .bind(accrued_on.to_string())
             .bind(Utc::now())
             .execute(&mut *tx)
             .await
@@ -266,6 +796,152 @@ impl AccountPort for SqliteAccountStore {
         Ok(())
     }
 
+    async fn apply_corporate_action(
+        &self,
+        account_id: &AccountId,
+        action: &CorporateAction,
+        settlement: Option<&FxSettlement>,
+    ) -> Result<Option<LedgerEntry>, TradeError> {
+        action
+            .validate()
+            .map_err(TradeError::InvalidCorporateAction)?;
+        let key = action.key();
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        let applied: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM trade_ledger WHERE reference = ? AND action_type IN ('Split', 'Dividend') LIMIT 1",
+        )
+        .bind(&key)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        if applied.is_some() {
+            return Ok(None);
+        }
+        // 持仓在除权除息日之后才开立，或该行动所在区间已处理过
+        if load_corporate_action_progress(&mut tx, &action.symbol)
+            .await?
+            .is_some_and(|until| action.ex_date.timestamp_millis() <= until)
+        {
+            return Ok(None);
+        }
+
+        let position: Option<(String, String, String)> = sqlx::query_as(
+            "SELECT quantity, avg_price, realized_pnl FROM positions WHERE symbol = ?",
+        )
+        .bind(&action.symbol)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let Some((quantity, avg_price, realized)) = position else {
+            return Ok(None);
+        };
+        let quantity = parse_decimal(&quantity)?;
+        if quantity.is_zero() {
+            return Ok(None);
+        }
+
+        let currency = settlement.map(|s| s.currency.as_str());
+        let (mut avail, frozen) = load_cash(&mut tx, currency).await?;
+
+        let mut entry = match action.kind {
+            CorporateActionKind::Split { .. } => {
+                let ratio = action.split_ratio().ok_or_else(|| {
+                    TradeError::InvalidCorporateAction("split ratio must be positive".into())
+                })?;
+                sqlx::query(
+                    "UPDATE positions SET quantity = ?, avg_price = ?, updated_at = ? WHERE symbol = ?",
+                )
+                .bind((quantity * ratio).to_string())
+                .bind((parse_decimal(&avg_price)? / ratio).to_string())
+                .bind(Utc::now())
+                .bind(&action.symbol)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+                ledger_entry(
+                    account_id,
+                    LedgerAction::Split,
+                    currency,
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    avail,
+                    frozen,
+                )
+            }
+            CorporateActionKind::Dividend { amount } => {
+                let cash = quantity * amount;
+                avail += cash;
+                store_cash(&mut tx, currency, avail, frozen).await?;
+                sqlx::query(
+                    "UPDATE positions SET realized_pnl = ?, updated_at = ? WHERE symbol = ?",
+                )
+                .bind((parse_decimal(&realized)? + cash).to_string())
+                .bind(Utc::now())
+                .bind(&action.symbol)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+                let rate = settlement.map_or(Decimal::ONE, |s| s.rate);
+                add_pnl_totals(&mut tx, cash * rate, Decimal::ZERO).await?;
+                ledger_entry(
+                    account_id,
+                    LedgerAction::Dividend,
+                    currency,
+                    cash,
+                    Decimal::ZERO,
+                    avail,
+                    frozen,
+                )
+            }
+        };
+        entry.reference = Some(key);
+        insert_ledger(&mut tx, &mut entry).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        info!(
+            "Applied corporate action {} to account {}",
+            entry.reference.as_deref().unwrap_or_default(),
+            account_id.0
+        );
+        Ok(Some(entry))
+    }
+
+    async fn corporate_actions_processed_until(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+    ) -> Result<Option<chrono::DateTime<Utc>>, TradeError> {
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(load_corporate_action_progress(&mut conn, symbol)
+            .await?
+            .and_then(chrono::DateTime::<Utc>::from_timestamp_millis))
+    }
+
+    async fn mark_corporate_actions_processed(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+        until: chrono::DateTime<Utc>,
+    ) -> Result<(), TradeError> {
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        advance_corporate_action_progress(&mut conn, symbol, until.timestamp_millis()).await
+    }
+
     async fn process_trade(
         &self,
         account_id: &AccountId,
@@ -278,57 +954,37 @@ impl AccountPort for SqliteAccountStore {
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
 
-        // 1. 获取账金
-        let row: (String, String) = sqlx::query_as(SQL_SELECT_ASSET)
-            .fetch_one(&mut *tx)
-            .await
-            .map_err(|e| TradeError::InternalError(e.to_string()))?;
-        let mut avail = parse_decimal(&row.0)?;
-        let mut frozen = parse_decimal(&row.1)?;
+        // 1. 获取成交结算币种的账金
+        let currency = trade.settlement.as_ref().map(|s| s.currency.as_str());
+        let fx_rate = trade.settlement.as_ref().map_or(Decimal::ONE, |s| s.rate);
+        let (mut avail, mut frozen) = load_cash(&mut tx, currency).await?;
 
         let mut ledger_asset_change = Decimal::ZERO;
         let mut ledger_frozen_change = Decimal::ZERO;
 
-        // 2. 资金结转
-        if trade.direction == OrderDirection::Buy {
-            let actual_cost = trade.price * trade.volume + trade.commission;
-
-            if frozen >= actual_cost {
-                frozen -= actual_cost;
-                ledger_frozen_change -= actual_cost;
-            } else {
-                let remain = actual_cost - frozen;
-                ledger_frozen_change -= frozen;
-                frozen = Decimal::ZERO;
-                avail -= remain;
-                ledger_asset_change -= remain;
-            }
+        // 2. 资金结转：先解冻该笔成交对应的冻结资金，再按成交额与费用结算现金
+        let release = if est_req_funds > frozen {
+            warn!(
+                "account {} settle anomaly: trying to release {} but only {} frozen",
+                account_id.0, est_req_funds, frozen
+            );
+            frozen
+        } else {
+            est_req_funds.max(Decimal::ZERO)
+        };
+        frozen -= release;
+        ledger_frozen_change -= release;
 
-            let over_frozen = est_req_funds - actual_cost;
-            if over_frozen > Decimal::ZERO {
-                let actual_unfreeze = if over_frozen > frozen {
-                    frozen
-                } else {
-                    over_frozen
-                };
-                frozen -= actual_unfreeze;
-                avail += actual_unfreeze;
-                ledger_frozen_change -= actual_unfreeze;
-                ledger_asset_change += actual_unfreeze;
-            }
+        let notional = trade.price * trade.volume;
+        let cash_flow = if trade.direction == OrderDirection::Buy {
+            release - notional - trade.commission
         } else {
-            let actual_gain = trade.price * trade.volume - trade.commission;
-            avail += actual_gain;
-            ledger_asset_change += actual_gain;
-        }
+            release + notional - trade.commission
+        };
+        avail += cash_flow;
+        ledger_asset_change += cash_flow;
 
-        sqlx::query("UPDATE asset_status SET available_balance = ?, frozen_balance = ?, updated_at = ? WHERE id = 'MAIN'")
-            .bind(avail.to_string())
-            .bind(frozen.to_string())
-            .bind(Utc::now())
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        store_cash(&mut tx, currency, avail, frozen).await?;
 
         // 3. 持仓清算
         let delta_volume = if trade.direction == OrderDirection::Buy {
@@ -338,19 +994,31 @@ impl AccountPort for SqliteAccountStore {
         };
         let mut pos_vol = Decimal::ZERO;
         let mut pos_price = Decimal::ZERO;
+        let mut pos_realized = Decimal::ZERO;
+        let mut pos_commission = Decimal::ZERO;
 
-        let existing_pos: Option<(String, String)> =
-            sqlx::query_as("SELECT quantity, avg_price FROM positions WHERE symbol = ?")
-                .bind(&trade.symbol)
-                .fetch_optional(&mut *tx)
-                .await
-                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let existing_pos: Option<(String, String, String, String)> = sqlx::query_as(
+            "SELECT quantity, avg_price, realized_pnl, commission FROM positions WHERE symbol = ?",
+        )
+        .bind(&trade.symbol)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
 
-        if let Some((qv, qp)) = existing_pos {
+        if let Some((qv, qp, qr, qc)) = existing_pos {
             pos_vol = parse_decimal(&qv)?;
             pos_price = parse_decimal(&qp)?;
+            pos_realized = parse_decimal(&qr)?;
+            pos_commission = parse_decimal(&qc)?;
+        }
+        if pos_vol.is_zero() {
+            advance_corporate_action_progress(&mut tx, &trade.symbol, trade.timestamp).await?;
         }
 
+        let realized = Position::closing_pnl(pos_vol, pos_price, delta_volume, trade.price);
+        pos_realized += realized;
+        pos_commission += trade.commission;
+
         if (pos_vol.is_sign_positive() && delta_volume.is_sign_positive())
             || (pos_vol.is_sign_negative() && delta_volume.is_sign_negative())
             || pos_vol.is_zero()
@@ -365,42 +1033,305 @@ impl AccountPort for SqliteAccountStore {
             pos_vol += delta_volume;
             if pos_vol.is_zero() {
                 pos_price = Decimal::ZERO;
-            } else if (pos_vol.is_sign_positive() && delta_volume.is_sign_negative())
-                || (pos_vol.is_sign_negative() && delta_volume.is_sign_positive())
-            {
+            } else if pos_vol.is_sign_positive() == delta_volume.is_sign_positive() {
+                // 反手：剩余头寸与成交同向，按成交价开新仓
                 pos_price = trade.price;
             }
         }
 
-        sqlx::query("INSERT OR REPLACE INTO positions (symbol, quantity, avg_price, updated_at) VALUES (?, ?, ?, ?)")
+        sqlx::query("INSERT OR REPLACE INTO positions (symbol, quantity, avg_price, realized_pnl, commission, updated_at) VALUES (?, ?, ?, ?, ?, ?)")
             .bind(&trade.symbol)
             .bind(pos_vol.to_string())
             .bind(pos_price.to_string())
+            .bind(pos_realized.to_string())
+            .bind(pos_commission.to_string())
             .bind(Utc::now())
             .execute(&mut *tx)
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
 
+        add_pnl_totals(&mut tx, realized * fx_rate, trade.commission * fx_rate).await?;
+
         // 4. Ledger 明细落地
-        sqlx::query("INSERT INTO trade_ledger (action_type, asset_change, frozen_change, created_at) VALUES (?, ?, ?, ?)")
-            .bind("TradeFilled")
-            .bind(ledger_asset_change.to_string())
-            .bind(ledger_frozen_change.to_string())
+        let mut entry = ledger_entry(
+            account_id,
+            LedgerAction::TradeFilled,
+            currency,
+            ledger_asset_change,
+            ledger_frozen_change,
+            avail,
+            frozen,
+        );
+        entry.reference = Some(trade.order_id.0.clone());
+        insert_ledger(&mut tx, &mut entry).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn deposit(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        let entry = self
+            .single_cash_movement(account_id, LedgerAction::Deposit, currency, amount)
+            .await?;
+        info!(
+            "Deposited {} {} into account {}",
+            amount,
+            currency.unwrap_or(MAIN_SLOT),
+            account_id.0
+        );
+        Ok(entry)
+    }
+
+    async fn withdraw(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        let entry = self
+            .single_cash_movement(account_id, LedgerAction::Withdrawal, currency, -amount)
+            .await?;
+        info!(
+            "Withdrew {} {} from account {}",
+            amount,
+            currency.unwrap_or(MAIN_SLOT),
+            account_id.0
+        );
+        Ok(entry)
+    }
+
+    /// # Logic
+    /// SQLite 在 WAL 模式下无法跨库原子提交，以转出方库中的转账日志保证转账不丢失：
+    /// 1. 转出方在同一事务中扣款 (余额不足则回滚) 并写入以 `transfer_id` 为键的转账日志。
+    /// 2. 转入方按 `transfer_id` 幂等入账，成功后删除转账日志。
+    /// 3. 转入方入账失败时，在转出方的同一事务中冲回扣款并删除日志；
+    ///    进程在两步之间中断时，由启动时的 `recover_transfers` 按日志补完入账。
+    async fn transfer(
+        &self,
+        from: &AccountId,
+        to: &AccountId,
+        currency: Option<&str>,
+        amount: Decimal,
+        transfer_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        if from == to {
+            return Err(TradeError::InvalidCashMovement(
+                "cannot transfer to the same account".into(),
+            ));
+        }
+        let from_pool = self.get_or_init_pool(&from.0).await?;
+        let begin = |pool: SqlitePool| async move {
+            pool.begin()
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))
+        };
+
+        let mut from_tx = begin(from_pool.clone()).await?;
+        let outgoing = Self::move_cash(
+            &mut from_tx,
+            from,
+            LedgerAction::TransferOut,
+            currency,
+            -amount,
+            Some(transfer_id),
+        )
+        .await?;
+        sqlx::query(SQL_INSERT_OUTGOING_TRANSFER)
+            .bind(transfer_id)
+            .bind(&to.0)
+            .bind(currency)
+            .bind(amount.to_string())
             .bind(Utc::now())
-            .execute(&mut *tx)
+            .execute(&mut *from_tx)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        from_tx
+            .commit()
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
 
+        let credited = self
+            .credit_transfer(to, currency, amount, transfer_id)
+            .await
+            .and_then(|incoming| {
+                incoming.ok_or_else(|| {
+                    TradeError::InvalidCashMovement(format!(
+                        "transfer {} was already credited to account {}",
+                        transfer_id, to.0
+                    ))
+                })
+            });
+        let incoming = match credited {
+            Ok(incoming) => incoming,
+            Err(e) => {
+                error!(
+                    "Transfer {} failed to credit account {}, reverting debit on {}: {}",
+                    transfer_id, to.0, from.0, e
+                );
+                let mut revert_tx = begin(from_pool).await?;
+                Self::move_cash(
+                    &mut revert_tx,
+                    from,
+                    LedgerAction::TransferIn,
+                    currency,
+                    amount,
+                    Some(transfer_id),
+                )
+                .await?;
+                sqlx::query(SQL_DELETE_OUTGOING_TRANSFER)
+                    .bind(transfer_id)
+                    .execute(&mut *revert_tx)
+                    .await
+                    .map_err(|e| TradeError::InternalError(e.to_string()))?;
+                revert_tx
+                    .commit()
+                    .await
+                    .map_err(|e| TradeError::InternalError(e.to_string()))?;
+                return Err(e);
+            }
+        };
+        if let Err(e) = self.clear_transfer_journal(from, transfer_id).await {
+            // 入账已生效，残留的日志由启动时的恢复按幂等入账清理
+            warn!(
+                "Transfer {} completed but its journal on account {} was not cleared: {}",
+                transfer_id, from.0, e
+            );
+        }
+        info!(
+            "Transferred {} from account {} to {} ({})",
+            amount, from.0, to.0, transfer_id
+        );
+        Ok(CashTransfer {
+            transfer_id: transfer_id.to_string(),
+            outgoing,
+            incoming,
+        })
+    }
+
+    /// # Logic
+    /// 在单个账户库的事务中从 `from` 币种扣款，并按 `rate` 折算后计入 `to` 币种，两条流水共享 `conversion_id`。
+    async fn convert_currency(
+        &self,
+        account_id: &AccountId,
+        from: Option<&str>,
+        to: Option<&str>,
+        amount: Decimal,
+        rate: Decimal,
+        conversion_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        if from == to {
+            return Err(TradeError::InvalidCashMovement(
+                "cannot convert to the same currency".into(),
+            ));
+        }
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let outgoing = Self::move_cash(
+            &mut tx,
+            account_id,
+            LedgerAction::FxConversion,
+            from,
+            -amount,
+            Some(conversion_id),
+        )
+        .await?;
+        let incoming = Self::move_cash(
+            &mut tx,
+            account_id,
+            LedgerAction::FxConversion,
+            to,
+            amount * rate,
+            Some(conversion_id),
+        )
+        .await?;
         tx.commit()
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
-        Ok(())
+        info!(
+            "Converted {} {} to {} {} in account {} ({})",
+            amount,
+            from.unwrap_or(MAIN_SLOT),
+            incoming.asset_change,
+            to.unwrap_or(MAIN_SLOT),
+            account_id.0,
+            conversion_id
+        );
+        Ok(CashTransfer {
+            transfer_id: conversion_id.to_string(),
+            outgoing,
+            incoming,
+        })
+    }
+
+    async fn list_ledger(
+        &self,
+        account_id: &AccountId,
+        offset: usize,
+        limit: usize,
+    ) -> Result<HistoryPage<LedgerEntry>, TradeError> {
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM trade_ledger")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let rows = sqlx::query_as::<
+            _,
+            (
+                i64,
+                String,
+                String,
+                String,
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                chrono::DateTime<Utc>,
+            ),
+        >(SQL_SELECT_LEDGER_PAGE)
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .bind(i64::try_from(offset).unwrap_or(i64::MAX))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        let items = rows
+            .into_iter()
+            .map(|r| {
+                Ok(LedgerEntry {
+                    id: r.0,
+                    account_id: account_id.clone(),
+                    action: parse_action(&r.1)?,
+                    asset_change: parse_decimal(&r.2)?,
+                    frozen_change: parse_decimal(&r.3)?,
+                    available_balance: parse_decimal(&r.4)?,
+                    frozen_balance: parse_decimal(&r.5)?,
+                    reference: r.6,
+                    created_at: r.8.timestamp_millis(),
+                    currency: r.7,
+                })
+            })
+            .collect::<Result<Vec<_>, TradeError>>()?;
+        Ok(HistoryPage {
+            items,
+            total: usize::try_from(total).map_err(|e| TradeError::InternalError(e.to_string()))?,
+        })
     }
 
     async fn snapshot(&self, account_id: &AccountId) -> Result<AccountSnapshot, TradeError> {
         let pool = self.get_or_init_pool(&account_id.0).await?;
 
         let row: (String, String) = sqlx::query_as(SQL_SELECT_ASSET)
+            .bind(MAIN_SLOT)
             .fetch_one(&pool)
             .await
             .map_err(|e| TradeError::InternalError(e.to_string()))?;
@@ -409,22 +1340,43 @@ impl AccountPort for SqliteAccountStore {
         let frozen_balance = parse_decimal(&row.1)?;
         let total_equity = available_balance + frozen_balance;
 
-        let cur_positions = sqlx::query_as::<_, (String, String, String)>(
-            "SELECT symbol, quantity, avg_price FROM positions",
+        let totals: (String, String) = sqlx::query_as(SQL_SELECT_PNL_TOTALS)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        let cur_positions = sqlx::query_as::<_, (String, String, String, String, String)>(
+            "SELECT symbol, quantity, avg_price, realized_pnl, commission FROM positions",
         )
         .fetch_all(&pool)
         .await
         .map_err(|e| TradeError::InternalError(e.to_string()))?;
 
+        let currencies = sqlx::query_as::<_, (String, String, String)>(SQL_SELECT_FOREIGN_ASSETS)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .into_iter()
+            .map(|(currency, available, frozen)| {
+                Ok(CurrencyBalance {
+                    currency,
+                    available_balance: parse_decimal(&available)?,
+                    frozen_balance: parse_decimal(&frozen)?,
+                    ..CurrencyBalance::default()
+                })
+            })
+            .collect::<Result<Vec<_>, TradeError>>()?;
+
         let mut positions = Vec::new();
         for p in cur_positions {
             let vol = parse_decimal(&p.1)?;
             if !vol.is_zero() {
                 positions.push(Position {
-                    account_id: account_id.clone(),
-                    symbol: p.0,
                     volume: vol,
                     average_price: parse_decimal(&p.2)?,
+                    realized_pnl: parse_decimal(&p.3)?,
+                    commission: parse_decimal(&p.4)?,
+                    ..Position::empty(account_id.clone(), p.0)
                 });
             }
         }
@@ -435,9 +1387,57 @@ impl AccountPort for SqliteAccountStore {
             frozen_balance,
             total_equity,
             positions,
+            realized_pnl: parse_decimal(&totals.0)?,
+            total_commission: parse_decimal(&totals.1)?,
+            currencies,
+            ..AccountSnapshot::default()
         })
     }
 
+    async fn save_daily_pnl(&self, record: &DailyPnl) -> Result<(), TradeError> {
+        let pool = self.get_or_init_pool(&record.account_id.0).await?;
+        sqlx::query(SQL_UPSERT_DAILY_PNL)
+            .bind(record.date.to_string())
+            .bind(record.opening_equity.to_string())
+            .bind(record.total_equity.to_string())
+            .bind(record.realized_pnl.to_string())
+            .bind(record.unrealized_pnl.to_string())
+            .bind(record.total_commission.to_string())
+            .bind(record.day_pnl.to_string())
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn list_daily_pnl(&self, account_id: &AccountId) -> Result<Vec<DailyPnl>, TradeError> {
+        let pool = self.get_or_init_pool(&account_id.0).await?;
+        let rows = sqlx::query_as::<_, (String, String, String, String, String, String, String)>(
+            SQL_SELECT_DAILY_PNL,
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|r| {
+                Ok(DailyPnl {
+                    account_id: account_id.clone(),
+                    date: chrono::NaiveDate::from_str(&r.0).map_err(|e| {
+                        TradeError::InternalError(format!("Failed to parse date '{}': {}", r.0, e))
+                    })?,
+                    opening_equity: parse_decimal(&r.1)?,
+                    total_equity: parse_decimal(&r.2)?,
+                    realized_pnl: parse_decimal(&r.3)?,
+                    unrealized_pnl: parse_decimal(&r.4)?,
+                    total_commission: parse_decimal(&r.5)?,
+                    day_pnl: parse_decimal(&r.6)?,
+                })
+            })
+            .collect()
+    }
+
     async fn ensure_account(
         &self,
         account_id: &AccountId,
@@ -447,8 +1447,32 @@ impl AccountPort for SqliteAccountStore {
         // 如果 initial_balance > 0，执行一次充值。
         self.get_or_init_pool(&account_id.0).await?;
         if initial_balance > Decimal::ZERO {
-            self.deposit(account_id, initial_balance).await?;
+            self.deposit(account_id, None, initial_balance).await?;
         }
         Ok(())
     }
+
+    /// 扫描数据目录下的账户库 (`account_<id>.db`)。
+    async fn list_accounts(&self) -> Result<Vec<AccountId>, TradeError> {
+        let mut dir = tokio::fs::read_dir(&self.base_path)
+            .await
+            .map_err(|e| TradeError::InternalError(format!("Failed to read account dir: {}", e)))?;
+        let mut account_ids = Vec::new();
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .map_err(|e| TradeError::InternalError(format!("Failed to read account dir: {}", e)))?
+        {
+            let file_name = entry.file_name();
+            if let Some(account_id) = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix("account_"))
+                .and_then(|name| name.strip_suffix(".db"))
+            {
+                account_ids.push(AccountId(account_id.to_string()));
+            }
+        }
+        account_ids.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(account_ids)
+    }
 }
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use okane_core::trade::entity::{AlgoOrderRecord, AlgoOrderStatus, OrderId};
+use okane_core::trade::port::{AlgoOrderStore, TradeError};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// # Summary
+/// 基于内存的算法单仓储实现。
+///
+/// 作为 `AlgoOrderStore` 的适配器，主要用于测试与无需跨进程恢复的场景。
+pub struct MemoryAlgoOrderStore {
+    records: Arc<RwLock<HashMap<OrderId, AlgoOrderRecord>>>,
+}
+
+impl MemoryAlgoOrderStore {
+    pub fn new() -> Self {
+        Self {
+            records: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+impl Default for MemoryAlgoOrderStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AlgoOrderStore for MemoryAlgoOrderStore {
+    async fn save(&self, record: AlgoOrderRecord) -> Result<(), TradeError> {
+        self.records
+            .write()
+            .await
+            .insert(record.order.id.clone(), record);
+        Ok(())
+    }
+
+    async fn get(&self, order_id: &OrderId) -> Result<Option<AlgoOrderRecord>, TradeError> {
+        Ok(self.records.read().await.get(order_id).cloned())
+    }
+
+    async fn load_active(&self) -> Result<Vec<AlgoOrderRecord>, TradeError> {
+        let guard = self.records.read().await;
+        Ok(guard
+            .values()
+            .filter(|r| {
+                matches!(
+                    r.order.status,
+                    AlgoOrderStatus::Running | AlgoOrderStatus::Paused
+                )
+            })
+            .cloned()
+            .collect())
+    }
+}
@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use std::path::PathBuf;
+
+use okane_core::trade::entity::{
+    AccountId, AlgoOrder, AlgoOrderRecord, AlgoOrderStatus, AlgoType, OrderId,
+};
+use okane_core::trade::port::{AlgoOrderStore, TradeError};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// # Summary
+/// 算法单的 SQLite 分片实现，与活动订单共用一户一库 (account_<id>.db)，
+/// 使母单与其子单落在同一数据库文件中。
+pub struct SqliteAlgoOrderStore {
+    base_path: PathBuf,
+    pools: DashMap<String, SqlitePool>,
+}
+
+const SQL_INIT_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS algo_orders (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    algo TEXT NOT NULL,
+    status TEXT NOT NULL,
+    requested_volume TEXT NOT NULL,
+    filled_volume TEXT NOT NULL,
+    child_seq INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at DATETIME NOT NULL,
+    grid_sides TEXT
+);
+"#;
+
+/// 旧库补列：网格档位方向 (JSON)
+const SQL_MIGRATIONS: [&str; 1] = ["ALTER TABLE algo_orders ADD COLUMN grid_sides TEXT"];
+
+/// 查询显式列出各列：补列后同一连接上的 `SELECT *` 会按补列前的列数取值而越界
+const SQL_SELECT_ALGO_ORDER: &str = "SELECT id, account_id, symbol, algo, status, requested_volume, filled_volume, child_seq, created_at, grid_sides FROM algo_orders WHERE id = ?";
+const SQL_SELECT_ACTIVE_ALGO_ORDERS: &str = "SELECT id, account_id, symbol, algo, status, requested_volume, filled_volume, child_seq, created_at, grid_sides FROM algo_orders WHERE status IN ('Running', 'Paused') ORDER BY created_at";
+
+impl SqliteAlgoOrderStore {
+    pub fn new() -> Result<Self, TradeError> {
+        Self::new_with_path(None)
+    }
+
+    pub fn new_with_path(root_path: Option<PathBuf>) -> Result<Self, TradeError> {
+        let base_path = match root_path {
+            Some(p) => p,
+            None => crate::config::get_root_dir()
+                .map_err(|e| TradeError::InternalError(e.to_string()))?,
+        };
+
+        Ok(Self {
+            base_path,
+            pools: DashMap::new(),
+        })
+    }
+
+    /// 获取或初始化特定账户的 SQLite 连接池
+    async fn get_or_init_pool(&self, account_id: &str) -> Result<SqlitePool, TradeError> {
+        if let Some(pool) = self.pools.get(account_id) {
+            return Ok(pool.clone());
+        }
+
+        let db_path = self.base_path.join(format!("account_{}.db", account_id));
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+
+        let pool = SqlitePoolOptions::new()
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                TradeError::InternalError(format!("Failed to connect to SQLite: {}", e))
+            })?;
+
+        sqlx::query(SQL_INIT_TABLES)
+            .execute(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(format!("Failed to init tables: {}", e)))?;
+        crate::migration::add_columns(&pool, &SQL_MIGRATIONS)
+            .await
+            .map_err(|e| TradeError::InternalError(format!("Failed to migrate tables: {}", e)))?;
+
+        self.pools.insert(account_id.to_string(), pool.clone());
+        Ok(pool)
+    }
+
+    async fn ensure_discovered_pools(&self) -> Result<(), TradeError> {
+        let entries = std::fs::read_dir(&self.base_path).map_err(|e| {
+            TradeError::InternalError(format!("failed to scan algo order directory: {}", e))
+        })?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| {
+                TradeError::InternalError(format!("failed to read dir entry: {}", e))
+            })?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if !file_name.starts_with("account_") || !file_name.ends_with(".db") {
+                continue;
+            }
+
+            let account_id = &file_name["account_".len()..file_name.len() - ".db".len()];
+            if !self.pools.contains_key(account_id) {
+                self.get_or_init_pool(account_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn status_to_str(status: AlgoOrderStatus) -> &'static str {
+        match status {
+            AlgoOrderStatus::Running => "Running",
+            AlgoOrderStatus::Paused => "Paused",
+            AlgoOrderStatus::Completed => "Completed",
+            AlgoOrderStatus::Canceled => "Canceled",
+            AlgoOrderStatus::Expired => "Expired",
+            AlgoOrderStatus::Failed => "Failed",
+        }
+    }
+
+    /// Helper to convert a sqlite row to an AlgoOrderRecord
+    fn row_to_record(row: sqlx::sqlite::SqliteRow) -> Result<AlgoOrderRecord, TradeError> {
+        use sqlx::Row;
+
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "Running" => AlgoOrderStatus::Running,
+            "Paused" => AlgoOrderStatus::Paused,
+            "Completed" => AlgoOrderStatus::Completed,
+            "Canceled" => AlgoOrderStatus::Canceled,
+            "Expired" => AlgoOrderStatus::Expired,
+            "Failed" => AlgoOrderStatus::Failed,
+            _ => {
+                return Err(TradeError::InternalError(format!(
+                    "Invalid algo status: {}",
+                    status_str
+                )));
+            }
+        };
+
+        let algo_str: String = row.get("algo");
+        let algo: AlgoType = serde_json::from_str(&algo_str)
+            .map_err(|e| TradeError::InternalError(format!("Invalid algo params: {}", e)))?;
+
+        let requested_str: String = row.get("requested_volume");
+        let requested_volume = Decimal::from_str(&requested_str).map_err(|_| {
+            TradeError::InternalError("Requested volume decimal parse error".to_string())
+        })?;
+        let filled_str: String = row.get("filled_volume");
+        let filled_volume = Decimal::from_str(&filled_str).map_err(|_| {
+            TradeError::InternalError("Filled volume decimal parse error".to_string())
+        })?;
+
+        let child_seq: i64 = row.get("child_seq");
+        let child_seq = u64::try_from(child_seq)
+            .map_err(|_| TradeError::InternalError(format!("Invalid child_seq: {}", child_seq)))?;
+
+        let grid_sides: Option<String> = row.get("grid_sides");
+        let grid_sides = match grid_sides {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| TradeError::InternalError(format!("Invalid grid sides: {}", e)))?,
+            None => Vec::new(),
+        };
+
+        Ok(AlgoOrderRecord {
+            order: AlgoOrder {
+                id: OrderId(row.get("id")),
+                account_id: AccountId(row.get("account_id")),
+                symbol: row.get("symbol"),
+                algo,
+                status,
+                requested_volume,
+                filled_volume,
+                created_at: row.get("created_at"),
+            },
+            child_seq,
+            grid_sides,
+        })
+    }
+}
+
+#[async_trait]
+impl AlgoOrderStore for SqliteAlgoOrderStore {
+    async fn save(&self, record: AlgoOrderRecord) -> Result<(), TradeError> {
+        let order = &record.order;
+        let pool = self.get_or_init_pool(&order.account_id.0).await?;
+
+        let algo_json = serde_json::to_string(&order.algo)
+            .map_err(|e| TradeError::InternalError(format!("Failed to encode algo: {}", e)))?;
+        let child_seq = i64::try_from(record.child_seq).map_err(|_| {
+            TradeError::InternalError(format!("child_seq out of range: {}", record.child_seq))
+        })?;
+        let grid_sides = serde_json::to_string(&record.grid_sides).map_err(|e| {
+            TradeError::InternalError(format!("Failed to encode grid sides: {}", e))
+        })?;
+
+        sqlx::query(
+            "INSERT INTO algo_orders (id, account_id, symbol, algo, status, requested_volume, filled_volume, child_seq, created_at, updated_at, grid_sides)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                status=excluded.status,
+                filled_volume=excluded.filled_volume,
+                child_seq=excluded.child_seq,
+                updated_at=excluded.updated_at,
+                grid_sides=excluded.grid_sides
+            ")
+            .bind(&order.id.0)
+            .bind(&order.account_id.0)
+            .bind(&order.symbol)
+            .bind(algo_json)
+            .bind(Self::status_to_str(order.status))
+            .bind(order.requested_volume.to_string())
+            .bind(order.filled_volume.to_string())
+            .bind(child_seq)
+            .bind(order.created_at)
+            .bind(Utc::now())
+            .bind(grid_sides)
+            .execute(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get(&self, order_id: &OrderId) -> Result<Option<AlgoOrderRecord>, TradeError> {
+        self.ensure_discovered_pools().await?;
+        for entry in self.pools.iter() {
+            let row_opt = sqlx::query(SQL_SELECT_ALGO_ORDER)
+                .bind(&order_id.0)
+                .fetch_optional(entry.value())
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+            if let Some(row) = row_opt {
+                return Ok(Some(Self::row_to_record(row)?));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn load_active(&self) -> Result<Vec<AlgoOrderRecord>, TradeError> {
+        self.ensure_discovered_pools().await?;
+        let mut records = Vec::new();
+        for entry in self.pools.iter() {
+            let rows = sqlx::query(SQL_SELECT_ACTIVE_ALGO_ORDERS)
+                .fetch_all(entry.value())
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+            for row in rows {
+                records.push(Self::row_to_record(row)?);
+            }
+        }
+        Ok(records)
+    }
+}
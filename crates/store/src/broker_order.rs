@@ -0,0 +1,71 @@
+use async_trait::async_trait;
+use okane_core::trade::entity::{BrokerOrderRecord, OrderId, OrderStatus};
+use okane_core::trade::port::{BrokerOrderStore, TradeError};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// # Summary
+/// 基于内存的外发订单仓储实现。
+///
+/// 作为 `BrokerOrderStore` 的适配器，主要用于测试与无需跨进程对账的场景。
+pub struct MemoryBrokerOrderStore {
+    records: Arc<RwLock<HashMap<OrderId, BrokerOrderRecord>>>,
+}
+
+impl MemoryBrokerOrderStore {
+    pub fn new() -> Self {
+        Self {
+            records: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+impl Default for MemoryBrokerOrderStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl BrokerOrderStore for MemoryBrokerOrderStore {
+    async fn save(&self, record: BrokerOrderRecord) -> Result<(), TradeError> {
+        self.records
+            .write()
+            .await
+            .insert(record.order.id.clone(), record);
+        Ok(())
+    }
+
+    async fn get(&self, order_id: &OrderId) -> Result<Option<BrokerOrderRecord>, TradeError> {
+        Ok(self.records.read().await.get(order_id).cloned())
+    }
+
+    async fn find_by_external(
+        &self,
+        account_type: &str,
+        external_order_id: &str,
+    ) -> Result<Option<BrokerOrderRecord>, TradeError> {
+        Ok(self
+            .records
+            .read()
+            .await
+            .values()
+            .find(|r| r.account_type == account_type && r.external_order_id == external_order_id)
+            .cloned())
+    }
+
+    async fn load_open(&self) -> Result<Vec<BrokerOrderRecord>, TradeError> {
+        let guard = self.records.read().await;
+        Ok(guard
+            .values()
+            .filter(|r| {
+                matches!(
+                    r.order.status,
+                    OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartialFilled
+                )
+            })
+            .cloned()
+            .collect())
+    }
+}
@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use sqlx::{
+    Row, SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow},
+};
+use std::path::PathBuf;
+
+use okane_core::trade::entity::{BrokerOrderRecord, Order, OrderId, OrderStatus};
+use okane_core::trade::port::{BrokerOrderStore, TradeError};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// # Summary
+/// 外发订单的 SQLite 分片实现，与活动订单共用一户一库 (account_<id>.db)。
+///
+/// # Invariants
+/// - 每笔外发订单一行，`(account_type, external_order_id)` 唯一；订单终结后保留记录，
+///   使迟到的回报仍能找到对应的系统订单。
+pub struct SqliteBrokerOrderStore {
+    base_path: PathBuf,
+    pools: DashMap<String, SqlitePool>,
+}
+
+const SQL_INIT_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS broker_orders (
+    id TEXT PRIMARY KEY,
+    account_type TEXT NOT NULL,
+    external_order_id TEXT NOT NULL,
+    is_open INTEGER NOT NULL,
+    payload TEXT NOT NULL,
+    reserved_price TEXT,
+    settled_notional TEXT NOT NULL,
+    settled_commission TEXT NOT NULL,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_broker_orders_external
+    ON broker_orders (account_type, external_order_id);
+"#;
+
+fn parse_decimal(value: &str) -> Result<Decimal, TradeError> {
+    Decimal::from_str(value).map_err(|e| {
+        TradeError::InternalError(format!("Failed to parse Decimal '{}': {}", value, e))
+    })
+}
+
+impl SqliteBrokerOrderStore {
+    pub fn new() -> Result<Self, TradeError> {
+        Self::new_with_path(None)
+    }
+
+    pub fn new_with_path(root_path: Option<PathBuf>) -> Result<Self, TradeError> {
+        let base_path = match root_path {
+            Some(p) => p,
+            None => crate::config::get_root_dir()
+                .map_err(|e| TradeError::InternalError(e.to_string()))?,
+        };
+
+        Ok(Self {
+            base_path,
+            pools: DashMap::new(),
+        })
+    }
+
+    /// 获取或初始化特定账户的 SQLite 连接池
+    async fn get_or_init_pool(&self, account_id: &str) -> Result<SqlitePool, TradeError> {
+        if let Some(pool) = self.pools.get(account_id) {
+            return Ok(pool.clone());
+        }
+
+        let db_path = self.base_path.join(format!("account_{}.db", account_id));
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+
+        let pool = SqlitePoolOptions::new()
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                TradeError::InternalError(format!("Failed to connect to SQLite: {}", e))
+            })?;
+
+        sqlx::query(SQL_INIT_TABLES)
+            .execute(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(format!("Failed to init tables: {}", e)))?;
+
+        self.pools.insert(account_id.to_string(), pool.clone());
+        Ok(pool)
+    }
+
+    async fn ensure_discovered_pools(&self) -> Result<(), TradeError> {
+        let entries = std::fs::read_dir(&self.base_path).map_err(|e| {
+            TradeError::InternalError(format!("failed to scan broker order directory: {}", e))
+        })?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| {
+                TradeError::InternalError(format!("failed to read dir entry: {}", e))
+            })?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if !file_name.starts_with("account_") || !file_name.ends_with(".db") {
+                continue;
+            }
+
+            let account_id = &file_name["account_".len()..file_name.len() - ".db".len()];
+            if !self.pools.contains_key(account_id) {
+                self.get_or_init_pool(account_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn row_to_record(row: SqliteRow) -> Result<BrokerOrderRecord, TradeError> {
+        let payload: String = row.get("payload");
+        let order: Order = serde_json::from_str(&payload)
+            .map_err(|e| TradeError::InternalError(format!("Invalid order payload: {}", e)))?;
+        let reserved_price: Option<String> = row.get("reserved_price");
+        let settled_notional: String = row.get("settled_notional");
+        let settled_commission: String = row.get("settled_commission");
+
+        Ok(BrokerOrderRecord {
+            account_type: row.get("account_type"),
+            external_order_id: row.get("external_order_id"),
+            order,
+            reserved_price: reserved_price.as_deref().map(parse_decimal).transpose()?,
+            settled_notional: parse_decimal(&settled_notional)?,
+            settled_commission: parse_decimal(&settled_commission)?,
+        })
+    }
+
+    /// 在全部账户库中查询，返回第一条匹配的记录。
+    async fn find_one(
+        &self,
+        sql: &str,
+        binds: &[&str],
+    ) -> Result<Option<BrokerOrderRecord>, TradeError> {
+        self.ensure_discovered_pools().await?;
+        for entry in self.pools.iter() {
+            let mut query = sqlx::query(sql);
+            for value in binds {
+                query = query.bind(*value);
+            }
+            let row_opt = query
+                .fetch_optional(entry.value())
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+            if let Some(row) = row_opt {
+                return Ok(Some(Self::row_to_record(row)?));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl BrokerOrderStore for SqliteBrokerOrderStore {
+    async fn save(&self, record: BrokerOrderRecord) -> Result<(), TradeError> {
+        let order = &record.order;
+        let pool = self.get_or_init_pool(&order.account_id.0).await?;
+
+        let payload = serde_json::to_string(order)
+            .map_err(|e| TradeError::InternalError(format!("Failed to encode order: {}", e)))?;
+        let is_open = matches!(
+            order.status,
+            OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartialFilled
+        );
+
+        sqlx::query(
+            "INSERT INTO broker_orders (id, account_type, external_order_id, is_open, payload, reserved_price, settled_notional, settled_commission, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                external_order_id=excluded.external_order_id,
+                is_open=excluded.is_open,
+                payload=excluded.payload,
+                settled_notional=excluded.settled_notional,
+                settled_commission=excluded.settled_commission,
+                updated_at=excluded.updated_at
+            ")
+            .bind(&order.id.0)
+            .bind(&record.account_type)
+            .bind(&record.external_order_id)
+            .bind(is_open)
+            .bind(payload)
+            .bind(record.reserved_price.map(|p| p.to_string()))
+            .bind(record.settled_notional.to_string())
+            .bind(record.settled_commission.to_string())
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get(&self, order_id: &OrderId) -> Result<Option<BrokerOrderRecord>, TradeError> {
+        self.find_one("SELECT * FROM broker_orders WHERE id = ?", &[&order_id.0])
+            .await
+    }
+
+    async fn find_by_external(
+        &self,
+        account_type: &str,
+        external_order_id: &str,
+    ) -> Result<Option<BrokerOrderRecord>, TradeError> {
+        self.find_one(
+            "SELECT * FROM broker_orders WHERE account_type = ? AND external_order_id = ?",
+            &[account_type, external_order_id],
+        )
+        .await
+    }
+
+    async fn load_open(&self) -> Result<Vec<BrokerOrderRecord>, TradeError> {
+        self.ensure_discovered_pools().await?;
+        let mut records = Vec::new();
+        for entry in self.pools.iter() {
+            let rows = sqlx::query("SELECT * FROM broker_orders WHERE is_open = 1")
+                .fetch_all(entry.value())
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+            for row in rows {
+                records.push(Self::row_to_record(row)?);
+            }
+        }
+        records.sort_by_key(|r| r.order.created_at);
+        Ok(records)
+    }
+}
@@ -1,6 +1,13 @@
 pub mod account;
+pub mod algo_order;
+pub mod algo_order_sqlx;
+pub mod broker_order;
+pub mod broker_order_sqlx;
 pub mod config;
 pub mod market;
+mod migration;
+pub mod order_history;
+pub mod order_history_sqlx;
 pub mod pending_order;
 pub mod pending_order_sqlx;
 pub mod strategy;
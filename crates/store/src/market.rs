@@ -2,7 +2,8 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use okane_core::common::{Stock, TimeFrame};
-use okane_core::market::entity::Candle;
+use okane_core::market::coverage::{self, TimeRange};
+use okane_core::market::entity::{Candle, CorporateAction, CorporateActionKind};
 use okane_core::store::error::StoreError;
 use okane_core::store::port::MarketStore;
 use rust_decimal::Decimal;
@@ -11,6 +12,7 @@ use sqlx::{
     sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow},
 };
 use std::path::PathBuf;
+use std::str::FromStr;
 
 /// MarketStore 的 SQLite 实现，采用“一库一股”策略。
 ///
@@ -38,6 +40,22 @@ CREATE TABLE IF NOT EXISTS candles (
     is_final INTEGER NOT NULL,
     PRIMARY KEY (timeframe, time)
 );
+
+CREATE TABLE IF NOT EXISTS corporate_actions (
+    ex_date DATETIME NOT NULL,
+    action_type TEXT NOT NULL,
+    numerator INTEGER,
+    denominator INTEGER,
+    amount TEXT,
+    PRIMARY KEY (ex_date, action_type)
+);
+
+CREATE TABLE IF NOT EXISTS candle_coverage (
+    timeframe TEXT NOT NULL,
+    start DATETIME NOT NULL,
+    end DATETIME NOT NULL,
+    PRIMARY KEY (timeframe, start)
+);
 "#;
 
 const SQL_INSERT_CANDLE: &str = r#"
@@ -48,6 +66,21 @@ VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
 const SQL_SELECT_CANDLES: &str =
     "SELECT * FROM candles WHERE timeframe = ? AND time >= ? AND time <= ? ORDER BY time ASC";
 
+const SQL_SELECT_COVERAGE: &str =
+    "SELECT start, end FROM candle_coverage WHERE timeframe = ? ORDER BY start ASC";
+
+const SQL_DELETE_COVERAGE: &str = "DELETE FROM candle_coverage WHERE timeframe = ?";
+
+const SQL_INSERT_COVERAGE: &str =
+    "INSERT INTO candle_coverage (timeframe, start, end) VALUES (?, ?, ?)";
+
+const SQL_UPSERT_CORPORATE_ACTION: &str = r#"
+INSERT OR REPLACE INTO corporate_actions (ex_date, action_type, numerator, denominator, amount)
+VALUES (?, ?, ?, ?, ?)
+"#;
+
+const SQL_SELECT_CORPORATE_ACTIONS: &str = "SELECT ex_date, action_type, numerator, denominator, amount FROM corporate_actions WHERE ex_date >= ? AND ex_date <= ? ORDER BY ex_date ASC, action_type ASC";
+
 impl SqliteMarketStore {
     /// 创建新的 SqliteMarketStore 实例。
     ///
@@ -116,6 +149,52 @@ impl SqliteMarketStore {
         self.pools.insert(key, pool.clone());
         Ok(pool)
     }
+
+    /// 在同一事务中读出某周期的覆盖区间，经 `update` 变换后整体重写。
+    async fn rewrite_candle_coverage(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+        update: impl FnOnce(Vec<TimeRange>) -> Vec<TimeRange> + Send,
+    ) -> Result<(), StoreError> {
+        let pool = self.get_or_init_pool(stock).await?;
+        let timeframe_str = format!("{:?}", timeframe);
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+
+        let rows: Vec<(DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(SQL_SELECT_COVERAGE)
+            .bind(&timeframe_str)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+        let ranges = update(
+            rows.into_iter()
+                .map(|(start, end)| TimeRange::new(start, end))
+                .collect(),
+        );
+
+        sqlx::query(SQL_DELETE_COVERAGE)
+            .bind(&timeframe_str)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+        for range in ranges {
+            sqlx::query(SQL_INSERT_COVERAGE)
+                .bind(&timeframe_str)
+                .bind(range.start)
+                .bind(range.end)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StoreError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -198,7 +277,6 @@ impl MarketStore for SqliteMarketStore {
         let mut results = Vec::new();
         for row in rows {
             use sqlx::Row;
-            use std::str::FromStr;
             let open_str: String = row.get("open");
             let high_str: String = row.get("high");
             let low_str: String = row.get("low");
@@ -229,4 +307,208 @@ impl MarketStore for SqliteMarketStore {
         }
         Ok(results)
     }
+
+    /// # Summary
+    /// 加载已拉取的 K 线覆盖区间。
+    ///
+    /// # Logic
+    /// 1. 获取个股连接池。
+    /// 2. 按周期查询 `candle_coverage` 表。
+    ///
+    /// # Arguments
+    /// * `stock` - 目标证券。
+    /// * `timeframe` - 周期。
+    ///
+    /// # Returns
+    /// * `Result<Vec<TimeRange>, StoreError>`
+    async fn load_candle_coverage(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+    ) -> Result<Vec<TimeRange>, StoreError> {
+        let pool = self.get_or_init_pool(stock).await?;
+
+        let rows: Vec<(DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(SQL_SELECT_COVERAGE)
+            .bind(format!("{:?}", timeframe))
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(start, end)| TimeRange::new(start, end))
+            .collect())
+    }
+
+    /// # Summary
+    /// 记录已拉取的 K 线覆盖区间。
+    ///
+    /// # Logic
+    /// 1. 获取个股连接池并开启事务。
+    /// 2. 读出该周期的已有区间，与新区间合并。
+    /// 3. 删除旧区间并写入合并结果。
+    ///
+    /// # Arguments
+    /// * `stock` - 目标证券。
+    /// * `timeframe` - 周期。
+    /// * `range` - 新拉取的区间。
+    ///
+    /// # Returns
+    /// * `Result<(), StoreError>`
+    async fn save_candle_coverage(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+        range: TimeRange,
+    ) -> Result<(), StoreError> {
+        self.rewrite_candle_coverage(stock, timeframe, |mut ranges| {
+            ranges.push(range);
+            coverage::merge(&ranges)
+        })
+        .await
+    }
+
+    /// # Summary
+    /// 移除一段 K 线覆盖区间。
+    ///
+    /// # Logic
+    /// 1. 在同一事务中读出该周期的已有区间，扣除目标区间后整体重写。
+    ///
+    /// # Arguments
+    /// * `stock` - 目标证券。
+    /// * `timeframe` - 周期。
+    /// * `range` - 待移除的区间。
+    ///
+    /// # Returns
+    /// * `Result<(), StoreError>`
+    async fn clear_candle_coverage(
+        &self,
+        stock: &Stock,
+        timeframe: TimeFrame,
+        range: TimeRange,
+    ) -> Result<(), StoreError> {
+        self.rewrite_candle_coverage(stock, timeframe, |ranges| {
+            coverage::subtract(&ranges, range.start, range.end)
+        })
+        .await
+    }
+
+    /// # Summary
+    /// 批量保存公司行动。
+    ///
+    /// # Logic
+    /// 1. 获取个股连接池。
+    /// 2. 在单个事务中按 (除权除息日, 行动类型) 执行 `INSERT OR REPLACE`。
+    ///
+    /// # Arguments
+    /// * `stock` - 目标证券。
+    /// * `actions` - 公司行动列表。
+    ///
+    /// # Returns
+    /// * `Result<(), StoreError>`
+    async fn save_corporate_actions(
+        &self,
+        stock: &Stock,
+        actions: &[CorporateAction],
+    ) -> Result<(), StoreError> {
+        let pool = self.get_or_init_pool(stock).await?;
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+
+        for action in actions {
+            let (action_type, numerator, denominator, amount) = match action.kind {
+                CorporateActionKind::Split {
+                    numerator,
+                    denominator,
+                } => ("Split", Some(numerator), Some(denominator), None),
+                CorporateActionKind::Dividend { amount } => {
+                    ("Dividend", None, None, Some(amount.to_string()))
+                }
+            };
+            sqlx::query(SQL_UPSERT_CORPORATE_ACTION)
+                .bind(action.ex_date)
+                .bind(action_type)
+                .bind(numerator)
+                .bind(denominator)
+                .bind(amount)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StoreError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// # Summary
+    /// 加载公司行动。
+    ///
+    /// # Logic
+    /// 1. 获取个股连接池。
+    /// 2. 按除权除息日区间查询 `corporate_actions` 表。
+    ///
+    /// # Arguments
+    /// * `stock` - 目标证券。
+    /// * `start` - 开始时间。
+    /// * `end` - 结束时间。
+    ///
+    /// # Returns
+    /// * `Result<Vec<CorporateAction>, StoreError>`
+    async fn load_corporate_actions(
+        &self,
+        stock: &Stock,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<CorporateAction>, StoreError> {
+        let pool = self.get_or_init_pool(stock).await?;
+
+        let rows: Vec<(
+            DateTime<Utc>,
+            String,
+            Option<u32>,
+            Option<u32>,
+            Option<String>,
+        )> = sqlx::query_as(SQL_SELECT_CORPORATE_ACTIONS)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|(ex_date, action_type, numerator, denominator, amount)| {
+                let kind = match (action_type.as_str(), numerator, denominator, amount) {
+                    ("Split", Some(numerator), Some(denominator), _) => {
+                        CorporateActionKind::Split {
+                            numerator,
+                            denominator,
+                        }
+                    }
+                    ("Dividend", _, _, Some(amount)) => CorporateActionKind::Dividend {
+                        amount: Decimal::from_str(&amount).map_err(|e| {
+                            StoreError::Database(format!(
+                                "Failed to parse Decimal '{}': {}",
+                                amount, e
+                            ))
+                        })?,
+                    },
+                    _ => {
+                        return Err(StoreError::Database(format!(
+                            "Invalid corporate action row: {} at {}",
+                            action_type, ex_date
+                        )));
+                    }
+                };
+                Ok(CorporateAction {
+                    symbol: stock.symbol.clone(),
+                    ex_date,
+                    kind,
+                })
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,22 @@
+//! # 旧库补列
+//!
+//! SQLite 不支持 `ADD COLUMN IF NOT EXISTS`，补列语句在每次打开数据库时执行。
+
+use sqlx::SqlitePool;
+
+/// # Logic
+/// 依次执行 `ALTER TABLE ... ADD COLUMN` 语句；列已存在 (旧库已补过) 时跳过，
+/// 其余错误 (如库文件只读、表不存在) 原样返回。
+///
+/// # Returns
+/// * `Err(sqlx::Error)` - The first migration that failed for any reason other than a duplicate column.
+pub(crate) async fn add_columns(pool: &SqlitePool, migrations: &[&str]) -> Result<(), sqlx::Error> {
+    for migration in migrations {
+        match sqlx::query(migration).execute(pool).await {
+            Ok(_) => {}
+            Err(sqlx::Error::Database(e)) if e.message().contains("duplicate column name") => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
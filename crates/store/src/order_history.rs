@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use okane_core::trade::entity::{
+    HistoryPage, HistoryQuery, Order, OrderHistoryRecord, OrderId, OrderStatusEvent, Trade,
+};
+use okane_core::trade::port::{OrderHistoryPort, TradeError};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// # Summary
+/// 基于内存的订单与成交历史仓储实现，用于测试与纸面交易。
+pub struct MemoryOrderHistoryStore {
+    orders: Arc<RwLock<HashMap<OrderId, OrderHistoryRecord>>>,
+    trades: Arc<RwLock<Vec<Trade>>>,
+}
+
+impl MemoryOrderHistoryStore {
+    pub fn new() -> Self {
+        Self {
+            orders: Arc::new(RwLock::new(HashMap::new())),
+            trades: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    fn in_range(query: &HistoryQuery, timestamp: i64) -> bool {
+        query.start.is_none_or(|start| timestamp >= start)
+            && query.end.is_none_or(|end| timestamp < end)
+    }
+
+    fn paginate<T>(items: Vec<T>, query: &HistoryQuery) -> HistoryPage<T> {
+        let total = items.len();
+        HistoryPage {
+            items: items
+                .into_iter()
+                .skip(query.offset)
+                .take(query.limit)
+                .collect(),
+            total,
+        }
+    }
+}
+
+impl Default for MemoryOrderHistoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl OrderHistoryPort for MemoryOrderHistoryStore {
+    async fn record_order(&self, order: &Order, timestamp: i64) -> Result<(), TradeError> {
+        let mut guard = self.orders.write().await;
+        let record = guard
+            .entry(order.id.clone())
+            .or_insert_with(|| OrderHistoryRecord {
+                order: order.clone(),
+                events: Vec::new(),
+                updated_at: timestamp,
+            });
+        record.order = order.clone();
+        let changed = record.events.last().is_none_or(|last| {
+            last.status != order.status || last.filled_volume != order.filled_volume
+        });
+        if changed {
+            record.events.push(OrderStatusEvent {
+                status: order.status,
+                filled_volume: order.filled_volume,
+                timestamp,
+            });
+            record.updated_at = timestamp;
+        }
+        Ok(())
+    }
+
+    async fn record_trade(&self, trade: &Trade) -> Result<(), TradeError> {
+        self.trades.write().await.push(trade.clone());
+        Ok(())
+    }
+
+    async fn query_orders(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<OrderHistoryRecord>, TradeError> {
+        let guard = self.orders.read().await;
+        let mut records: Vec<OrderHistoryRecord> = guard
+            .values()
+            .filter(|record| {
+                let order = &record.order;
+                order.account_id == query.account_id
+                    && query.symbol.as_ref().is_none_or(|s| order.symbol == *s)
+                    && query.status.is_none_or(|s| order.status == s)
+                    && query
+                        .strategy_run_id
+                        .as_ref()
+                        .is_none_or(|run| order.strategy_run_id.as_ref() == Some(run))
+                    && Self::in_range(query, order.created_at)
+            })
+            .cloned()
+            .collect();
+        records.sort_by(|a, b| {
+            b.order
+                .created_at
+                .cmp(&a.order.created_at)
+                .then_with(|| b.order.id.0.cmp(&a.order.id.0))
+        });
+        Ok(Self::paginate(records, query))
+    }
+
+    async fn query_trades(&self, query: &HistoryQuery) -> Result<HistoryPage<Trade>, TradeError> {
+        let orders = self.orders.read().await;
+        let guard = self.trades.read().await;
+        let mut trades: Vec<Trade> = guard
+            .iter()
+            .filter(|trade| {
+                trade.account_id == query.account_id
+                    && query.symbol.as_ref().is_none_or(|s| trade.symbol == *s)
+                    && query.strategy_run_id.as_ref().is_none_or(|run| {
+                        orders.get(&trade.order_id).is_some_and(|record| {
+                            record.order.strategy_run_id.as_ref() == Some(run)
+                        })
+                    })
+                    && Self::in_range(query, trade.timestamp)
+            })
+            .cloned()
+            .collect();
+        // 同一时刻的成交保持写入顺序的逆序
+        trades.reverse();
+        trades.sort_by_key(|trade| std::cmp::Reverse(trade.timestamp));
+        Ok(Self::paginate(trades, query))
+    }
+}
@@ -0,0 +1,398 @@
+use async_trait::async_trait;
+use dashmap::DashMap;
+use sqlx::{
+    QueryBuilder, Row, Sqlite, SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use std::path::PathBuf;
+
+use okane_core::trade::entity::{
+    AccountId, HistoryPage, HistoryQuery, Order, OrderDirection, OrderHistoryRecord, OrderId,
+    OrderStatus, OrderStatusEvent, Trade,
+};
+use okane_core::trade::port::{OrderHistoryPort, TradeError};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// # Summary
+/// 订单与成交历史的 SQLite 实现，与活动订单共用一户一库 (account_<id>.db)。
+///
+/// # Invariants
+/// - `order_history` 每笔订单一行，保存最新的订单快照；`order_events` 只追加状态变迁。
+/// - `trade_history` 只追加，不随订单终结删除。
+pub struct SqliteOrderHistoryStore {
+    base_path: PathBuf,
+    pools: DashMap<String, SqlitePool>,
+}
+
+const SQL_INIT_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS order_history (
+    id TEXT PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    status TEXT NOT NULL,
+    strategy_run_id TEXT,
+    payload TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_history_created_at ON order_history (created_at);
+
+CREATE TABLE IF NOT EXISTS order_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    filled_volume TEXT NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_events_order_id ON order_events (order_id);
+
+CREATE TABLE IF NOT EXISTS trade_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_id TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    direction TEXT NOT NULL,
+    price TEXT NOT NULL,
+    volume TEXT NOT NULL,
+    commission TEXT NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_history_timestamp ON trade_history (timestamp);
+"#;
+
+fn status_name(status: OrderStatus) -> &'static str {
+    match status {
+        OrderStatus::Pending => "Pending",
+        OrderStatus::Submitted => "Submitted",
+        OrderStatus::PartialFilled => "PartialFilled",
+        OrderStatus::Filled => "Filled",
+        OrderStatus::Canceled => "Canceled",
+        OrderStatus::Rejected => "Rejected",
+        OrderStatus::Expired => "Expired",
+    }
+}
+
+fn parse_status(value: &str) -> Result<OrderStatus, TradeError> {
+    match value {
+        "Pending" => Ok(OrderStatus::Pending),
+        "Submitted" => Ok(OrderStatus::Submitted),
+        "PartialFilled" => Ok(OrderStatus::PartialFilled),
+        "Filled" => Ok(OrderStatus::Filled),
+        "Canceled" => Ok(OrderStatus::Canceled),
+        "Rejected" => Ok(OrderStatus::Rejected),
+        "Expired" => Ok(OrderStatus::Expired),
+        _ => Err(TradeError::InternalError(format!(
+            "Invalid status: {}",
+            value
+        ))),
+    }
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal, TradeError> {
+    Decimal::from_str(value).map_err(|e| {
+        TradeError::InternalError(format!("Failed to parse Decimal '{}': {}", value, e))
+    })
+}
+
+impl SqliteOrderHistoryStore {
+    pub fn new() -> Result<Self, TradeError> {
+        Self::new_with_path(None)
+    }
+
+    pub fn new_with_path(root_path: Option<PathBuf>) -> Result<Self, TradeError> {
+        let base_path = match root_path {
+            Some(p) => p,
+            None => crate::config::get_root_dir()
+                .map_err(|e| TradeError::InternalError(e.to_string()))?,
+        };
+
+        Ok(Self {
+            base_path,
+            pools: DashMap::new(),
+        })
+    }
+
+    async fn get_or_init_pool(&self, account_id: &str) -> Result<SqlitePool, TradeError> {
+        if let Some(pool) = self.pools.get(account_id) {
+            return Ok(pool.clone());
+        }
+
+        let db_path = self.base_path.join(format!("account_{}.db", account_id));
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+
+        let pool = SqlitePoolOptions::new()
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                TradeError::InternalError(format!("Failed to connect to SQLite: {}", e))
+            })?;
+
+        sqlx::query(SQL_INIT_TABLES)
+            .execute(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(format!("Failed to init tables: {}", e)))?;
+
+        self.pools.insert(account_id.to_string(), pool.clone());
+        Ok(pool)
+    }
+
+    /// 追加查询条件；`time_column` 为按时间区间过滤的列。
+    fn push_filters(
+        builder: &mut QueryBuilder<'_, Sqlite>,
+        query: &HistoryQuery,
+        time_column: &str,
+        with_status: bool,
+    ) {
+        builder.push(" WHERE 1 = 1");
+        if let Some(symbol) = &query.symbol {
+            builder.push(" AND symbol = ").push_bind(symbol.clone());
+        }
+        if let Some(start) = query.start {
+            builder
+                .push(format!(" AND {} >= ", time_column))
+                .push_bind(start);
+        }
+        if let Some(end) = query.end {
+            builder
+                .push(format!(" AND {} < ", time_column))
+                .push_bind(end);
+        }
+        if with_status && let Some(status) = query.status {
+            builder
+                .push(" AND status = ")
+                .push_bind(status_name(status));
+        }
+        if let Some(run_id) = &query.strategy_run_id {
+            if with_status {
+                builder
+                    .push(" AND strategy_run_id = ")
+                    .push_bind(run_id.clone());
+            } else {
+                builder
+                    .push(" AND order_id IN (SELECT id FROM order_history WHERE strategy_run_id = ")
+                    .push_bind(run_id.clone())
+                    .push(")");
+            }
+        }
+    }
+
+    async fn count(
+        pool: &SqlitePool,
+        table: &str,
+        query: &HistoryQuery,
+        time_column: &str,
+        with_status: bool,
+    ) -> Result<usize, TradeError> {
+        let mut builder = QueryBuilder::<Sqlite>::new(format!("SELECT COUNT(*) FROM {}", table));
+        Self::push_filters(&mut builder, query, time_column, with_status);
+        let total: i64 = builder
+            .build_query_scalar()
+            .fetch_one(pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        usize::try_from(total).map_err(|e| TradeError::InternalError(e.to_string()))
+    }
+
+    fn push_page(builder: &mut QueryBuilder<'_, Sqlite>, query: &HistoryQuery) {
+        builder
+            .push(" LIMIT ")
+            .push_bind(i64::try_from(query.limit).unwrap_or(i64::MAX))
+            .push(" OFFSET ")
+            .push_bind(i64::try_from(query.offset).unwrap_or(i64::MAX));
+    }
+
+    async fn load_events(
+        pool: &SqlitePool,
+        order_id: &str,
+    ) -> Result<Vec<OrderStatusEvent>, TradeError> {
+        let rows = sqlx::query(
+            "SELECT status, filled_volume, timestamp FROM order_events WHERE order_id = ? ORDER BY id",
+        )
+        .bind(order_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(OrderStatusEvent {
+                    status: parse_status(&row.get::<String, _>("status"))?,
+                    filled_volume: parse_decimal(&row.get::<String, _>("filled_volume"))?,
+                    timestamp: row.get("timestamp"),
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl OrderHistoryPort for SqliteOrderHistoryStore {
+    async fn record_order(&self, order: &Order, timestamp: i64) -> Result<(), TradeError> {
+        let pool = self.get_or_init_pool(&order.account_id.0).await?;
+        let payload = serde_json::to_string(order)
+            .map_err(|e| TradeError::InternalError(format!("Failed to encode order: {}", e)))?;
+        let status = status_name(order.status);
+        let filled_volume = order.filled_volume.to_string();
+
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        let last: Option<(String, String)> = sqlx::query_as(
+            "SELECT status, filled_volume FROM order_events WHERE order_id = ? ORDER BY id DESC LIMIT 1",
+        )
+        .bind(&order.id.0)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let changed = last.is_none_or(|(last_status, last_filled)| {
+            last_status != status || last_filled != filled_volume
+        });
+
+        sqlx::query(
+            "INSERT INTO order_history (id, symbol, status, strategy_run_id, payload, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                status=excluded.status,
+                payload=excluded.payload,
+                updated_at=CASE WHEN ? THEN excluded.updated_at ELSE order_history.updated_at END
+            ",
+        )
+        .bind(&order.id.0)
+        .bind(&order.symbol)
+        .bind(status)
+        .bind(order.strategy_run_id.as_deref())
+        .bind(payload)
+        .bind(order.created_at)
+        .bind(timestamp)
+        .bind(changed)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        if changed {
+            sqlx::query(
+                "INSERT INTO order_events (order_id, status, filled_volume, timestamp) VALUES (?, ?, ?, ?)",
+            )
+            .bind(&order.id.0)
+            .bind(status)
+            .bind(filled_volume)
+            .bind(timestamp)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn record_trade(&self, trade: &Trade) -> Result<(), TradeError> {
+        let pool = self.get_or_init_pool(&trade.account_id.0).await?;
+        let direction = match trade.direction {
+            OrderDirection::Buy => "Buy",
+            OrderDirection::Sell => "Sell",
+        };
+        sqlx::query(
+            "INSERT INTO trade_history (order_id, symbol, direction, price, volume, commission, timestamp) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&trade.order_id.0)
+        .bind(&trade.symbol)
+        .bind(direction)
+        .bind(trade.price.to_string())
+        .bind(trade.volume.to_string())
+        .bind(trade.commission.to_string())
+        .bind(trade.timestamp)
+        .execute(&pool)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn query_orders(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<OrderHistoryRecord>, TradeError> {
+        let pool = self.get_or_init_pool(&query.account_id.0).await?;
+        let total = Self::count(&pool, "order_history", query, "created_at", true).await?;
+
+        let mut builder =
+            QueryBuilder::<Sqlite>::new("SELECT payload, updated_at FROM order_history");
+        Self::push_filters(&mut builder, query, "created_at", true);
+        builder.push(" ORDER BY created_at DESC, id DESC");
+        Self::push_page(&mut builder, query);
+        let rows = builder
+            .build()
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        let mut items = Vec::with_capacity(rows.len());
+        for row in rows {
+            let payload: String = row.get("payload");
+            let order: Order = serde_json::from_str(&payload)
+                .map_err(|e| TradeError::InternalError(format!("Order parse error: {}", e)))?;
+            let events = Self::load_events(&pool, &order.id.0).await?;
+            items.push(OrderHistoryRecord {
+                order,
+                events,
+                updated_at: row.get("updated_at"),
+            });
+        }
+        Ok(HistoryPage { items, total })
+    }
+
+    async fn query_trades(&self, query: &HistoryQuery) -> Result<HistoryPage<Trade>, TradeError> {
+        let pool = self.get_or_init_pool(&query.account_id.0).await?;
+        let total = Self::count(&pool, "trade_history", query, "timestamp", false).await?;
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT order_id, symbol, direction, price, volume, commission, timestamp FROM trade_history",
+        );
+        Self::push_filters(&mut builder, query, "timestamp", false);
+        builder.push(" ORDER BY timestamp DESC, id DESC");
+        Self::push_page(&mut builder, query);
+        let rows = builder
+            .build()
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let direction = match row.get::<String, _>("direction").as_str() {
+                    "Buy" => OrderDirection::Buy,
+                    "Sell" => OrderDirection::Sell,
+                    other => {
+                        return Err(TradeError::InternalError(format!(
+                            "Invalid direction: {}",
+                            other
+                        )));
+                    }
+                };
+                Ok(Trade {
+                    order_id: OrderId(row.get("order_id")),
+                    account_id: AccountId(query.account_id.0.clone()),
+                    symbol: row.get("symbol"),
+                    direction,
+                    price: parse_decimal(&row.get::<String, _>("price"))?,
+                    volume: parse_decimal(&row.get::<String, _>("volume"))?,
+                    commission: parse_decimal(&row.get::<String, _>("commission"))?,
+                    timestamp: row.get("timestamp"),
+                    settlement: None,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|items| HistoryPage { items, total })
+    }
+}
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use okane_core::trade::entity::{AccountId, Order, OrderId, OrderStatus};
+use okane_core::trade::entity::{AccountId, Order, OrderGroup, OrderId, OrderStatus};
 use okane_core::trade::port::{PendingOrderPort, TradeError};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -11,12 +11,14 @@ use tokio::sync::RwLock;
 /// 作为 `PendingOrderPort` 的适配器，提供对暂存单的管理能力。
 pub struct MemoryPendingOrderStore {
     orders: Arc<RwLock<HashMap<OrderId, Order>>>,
+    groups: Arc<RwLock<HashMap<OrderId, OrderGroup>>>,
 }
 
 impl MemoryPendingOrderStore {
     pub fn new() -> Self {
         Self {
             orders: Arc::new(RwLock::new(HashMap::new())),
+            groups: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -60,6 +62,14 @@ impl PendingOrderPort for MemoryPendingOrderStore {
             .collect())
     }
 
+    async fn get_symbols(&self) -> Result<Vec<String>, TradeError> {
+        let guard = self.orders.read().await;
+        let mut symbols: Vec<String> = guard.values().map(|o| o.symbol.clone()).collect();
+        symbols.sort();
+        symbols.dedup();
+        Ok(symbols)
+    }
+
     async fn update_status(
         &self,
         order_id: &OrderId,
@@ -71,4 +81,17 @@ impl PendingOrderPort for MemoryPendingOrderStore {
         }
         Ok(())
     }
+
+    async fn save_group(&self, group: OrderGroup) -> Result<(), TradeError> {
+        self.groups.write().await.insert(group.id.clone(), group);
+        Ok(())
+    }
+
+    async fn get_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        Ok(self.groups.read().await.get(group_id).cloned())
+    }
+
+    async fn remove_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        Ok(self.groups.write().await.remove(group_id))
+    }
 }
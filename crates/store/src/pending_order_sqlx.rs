@@ -7,7 +7,9 @@ use sqlx::{
 };
 use std::path::PathBuf;
 
-use okane_core::trade::entity::{AccountId, Order, OrderDirection, OrderId, OrderStatus};
+use okane_core::trade::entity::{
+    AccountId, Order, OrderDirection, OrderGroup, OrderId, OrderStatus, TimeInForce,
+};
 use okane_core::trade::port::{PendingOrderPort, TradeError};
 use rust_decimal::Decimal;
 use std::str::FromStr;
@@ -30,11 +32,45 @@ CREATE TABLE IF NOT EXISTS pending_orders (
     volume TEXT NOT NULL,
     filled_volume TEXT NOT NULL,
     status TEXT NOT NULL,
+    order_trigger TEXT,
+    time_in_force TEXT,
+    expire_at INTEGER,
+    group_id TEXT,
+    margin_rate TEXT,
+    strategy_run_id TEXT,
+    currency TEXT,
+    split_adjusted_at INTEGER,
     created_at DATETIME NOT NULL,
     updated_at DATETIME NOT NULL
 );
+
+CREATE TABLE IF NOT EXISTS order_groups (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at DATETIME NOT NULL
+);
 "#;
 
+/// 旧库补列：触发条件 (JSON)、有效期、订单组、保证金率、策略运行、币种与拆股调整时间
+const SQL_MIGRATIONS: [&str; 8] = [
+    "ALTER TABLE pending_orders ADD COLUMN order_trigger TEXT",
+    "ALTER TABLE pending_orders ADD COLUMN time_in_force TEXT",
+    "ALTER TABLE pending_orders ADD COLUMN expire_at INTEGER",
+    "ALTER TABLE pending_orders ADD COLUMN group_id TEXT",
+    "ALTER TABLE pending_orders ADD COLUMN margin_rate TEXT",
+    "ALTER TABLE pending_orders ADD COLUMN strategy_run_id TEXT",
+    "ALTER TABLE pending_orders ADD COLUMN currency TEXT",
+    "ALTER TABLE pending_orders ADD COLUMN split_adjusted_at INTEGER",
+];
+
+/// 查询显式列出各列：补列后同一连接上的 `SELECT *` 会按补列前的列数取值而越界
+const SQL_SELECT_ORDER: &str = "SELECT id, account_id, symbol, direction, price, volume, filled_volume, status, order_trigger, time_in_force, expire_at, group_id, margin_rate, strategy_run_id, currency, split_adjusted_at, created_at FROM pending_orders WHERE id = ?";
+const SQL_SELECT_ORDERS_BY_ACCOUNT: &str = "SELECT id, account_id, symbol, direction, price, volume, filled_volume, status, order_trigger, time_in_force, expire_at, group_id, margin_rate, strategy_run_id, currency, split_adjusted_at, created_at FROM pending_orders WHERE account_id = ?";
+const SQL_SELECT_ORDERS_BY_SYMBOL: &str = "SELECT id, account_id, symbol, direction, price, volume, filled_volume, status, order_trigger, time_in_force, expire_at, group_id, margin_rate, strategy_run_id, currency, split_adjusted_at, created_at FROM pending_orders WHERE symbol = ?";
+
 impl SqlitePendingOrderStore {
     pub fn new() -> Result<Self, TradeError> {
         Self::new_with_path(None)
@@ -81,6 +117,10 @@ impl SqlitePendingOrderStore {
             .await
             .map_err(|e| TradeError::InternalError(format!("Failed to init tables: {}", e)))?;
 
+        crate::migration::add_columns(&pool, &SQL_MIGRATIONS)
+            .await
+            .map_err(|e| TradeError::InternalError(format!("Failed to migrate tables: {}", e)))?;
+
         self.pools.insert(account_id.to_string(), pool.clone());
         Ok(pool)
     }
@@ -109,6 +149,15 @@ impl SqlitePendingOrderStore {
         Ok(())
     }
 
+    /// Helper to convert a sqlite row to an OrderGroup
+    fn row_to_group(row: sqlx::sqlite::SqliteRow) -> Result<OrderGroup, TradeError> {
+        use sqlx::Row;
+
+        let payload: String = row.get("payload");
+        serde_json::from_str(&payload)
+            .map_err(|e| TradeError::InternalError(format!("Order group parse error: {}", e)))
+    }
+
     /// Helper to convert a sqlite row to an Order
     fn row_to_order(row: sqlx::sqlite::SqliteRow) -> Result<Order, TradeError> {
         use sqlx::Row;
@@ -133,6 +182,7 @@ impl SqlitePendingOrderStore {
             "Filled" => OrderStatus::Filled,
             "Canceled" => OrderStatus::Canceled,
             "Rejected" => OrderStatus::Rejected,
+            "Expired" => OrderStatus::Expired,
             _ => {
                 return Err(TradeError::InternalError(format!(
                     "Invalid status: {}",
@@ -159,6 +209,35 @@ impl SqlitePendingOrderStore {
             TradeError::InternalError("Filled Volume decimal parse error".to_string())
         })?;
 
+        let trigger_str: Option<String> = row.get("order_trigger");
+        let trigger =
+            match trigger_str {
+                Some(t) => Some(serde_json::from_str(&t).map_err(|e| {
+                    TradeError::InternalError(format!("Trigger parse error: {}", e))
+                })?),
+                None => None,
+            };
+
+        let tif_str: Option<String> = row.get("time_in_force");
+        let time_in_force = match tif_str {
+            Some(t) => serde_json::from_str(&t).map_err(|e| {
+                TradeError::InternalError(format!("Time in force parse error: {}", e))
+            })?,
+            None => TimeInForce::default(),
+        };
+        let expire_at: Option<i64> = row.get("expire_at");
+        let group_id: Option<String> = row.get("group_id");
+        let strategy_run_id: Option<String> = row.get("strategy_run_id");
+        let currency: Option<String> = row.get("currency");
+        let split_adjusted_at: Option<i64> = row.get("split_adjusted_at");
+        let margin_rate_str: Option<String> = row.get("margin_rate");
+        let margin_rate = match margin_rate_str {
+            Some(r) => Some(Decimal::from_str(&r).map_err(|_| {
+                TradeError::InternalError("Margin rate decimal parse error".to_string())
+            })?),
+            None => None,
+        };
+
         // 如果数据库中的时间戳解析失败，必须显式抛出错误以防止策略回测逻辑被静默误导。
         let created_at: chrono::DateTime<Utc> = row
             .try_get("created_at")
@@ -174,6 +253,14 @@ impl SqlitePendingOrderStore {
             filled_volume,
             status,
             created_at: created_at.timestamp_millis(),
+            trigger,
+            time_in_force,
+            expire_at,
+            group_id: group_id.map(OrderId),
+            margin_rate,
+            strategy_run_id,
+            currency,
+            split_adjusted_at,
         })
     }
 }
@@ -184,6 +271,14 @@ impl PendingOrderPort for SqlitePendingOrderStore {
         let pool = self.get_or_init_pool(&order.account_id.0).await?;
 
         let price_str = order.price.map(|p| p.to_string());
+        let trigger_str = order
+            .trigger
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let tif_str = serde_json::to_string(&order.time_in_force)
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
         let dir_str = match order.direction {
             OrderDirection::Buy => "Buy",
             OrderDirection::Sell => "Sell",
@@ -195,16 +290,21 @@ impl PendingOrderPort for SqlitePendingOrderStore {
             OrderStatus::Filled => "Filled",
             OrderStatus::Canceled => "Canceled",
             OrderStatus::Rejected => "Rejected",
+            OrderStatus::Expired => "Expired",
         };
 
         let now = Utc::now();
 
         sqlx::query(
-            "INSERT INTO pending_orders (id, account_id, symbol, direction, price, volume, filled_volume, status, created_at, updated_at) 
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "INSERT INTO pending_orders (id, account_id, symbol, direction, price, volume, filled_volume, status, order_trigger, time_in_force, expire_at, group_id, margin_rate, strategy_run_id, currency, split_adjusted_at, created_at, updated_at) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
              ON CONFLICT(id) DO UPDATE SET 
+                price=excluded.price,
+                volume=excluded.volume,
                 filled_volume=excluded.filled_volume,
                 status=excluded.status,
+                order_trigger=excluded.order_trigger,
+                split_adjusted_at=excluded.split_adjusted_at,
                 updated_at=excluded.updated_at
             ")
             .bind(&order.id.0)
@@ -215,6 +315,14 @@ impl PendingOrderPort for SqlitePendingOrderStore {
             .bind(order.volume.to_string())
             .bind(order.filled_volume.to_string())
             .bind(status_str)
+            .bind(trigger_str)
+            .bind(tif_str)
+            .bind(order.expire_at)
+            .bind(order.group_id.as_ref().map(|id| id.0.clone()))
+            .bind(order.margin_rate.map(|r| r.to_string()))
+            .bind(order.strategy_run_id.as_deref())
+            .bind(order.currency.as_deref())
+            .bind(order.split_adjusted_at)
             .bind(now)  // Since creation time is immutable in DB context, we just bind it to upsert
             .bind(now)
             .execute(&pool)
@@ -233,7 +341,7 @@ impl PendingOrderPort for SqlitePendingOrderStore {
             let pool = entry.value();
 
             // 尝试查询以获取将要删除的实体
-            let row_opt = sqlx::query("SELECT * FROM pending_orders WHERE id = ?")
+            let row_opt = sqlx::query(SQL_SELECT_ORDER)
                 .bind(&order_id.0)
                 .fetch_optional(pool)
                 .await
@@ -258,7 +366,7 @@ impl PendingOrderPort for SqlitePendingOrderStore {
         self.ensure_discovered_pools().await?;
         for entry in self.pools.iter() {
             let pool = entry.value();
-            let row_opt = sqlx::query("SELECT * FROM pending_orders WHERE id = ?")
+            let row_opt = sqlx::query(SQL_SELECT_ORDER)
                 .bind(&order_id.0)
                 .fetch_optional(pool)
                 .await
@@ -273,7 +381,7 @@ impl PendingOrderPort for SqlitePendingOrderStore {
 
     async fn get_by_account(&self, account_id: &AccountId) -> Result<Vec<Order>, TradeError> {
         let pool = self.get_or_init_pool(&account_id.0).await?;
-        let rows = sqlx::query("SELECT * FROM pending_orders WHERE account_id = ?")
+        let rows = sqlx::query(SQL_SELECT_ORDERS_BY_ACCOUNT)
             .bind(&account_id.0)
             .fetch_all(&pool)
             .await
@@ -291,7 +399,7 @@ impl PendingOrderPort for SqlitePendingOrderStore {
         let mut orders = Vec::new();
         for entry in self.pools.iter() {
             let pool = entry.value();
-            let rows = sqlx::query("SELECT * FROM pending_orders WHERE symbol = ?")
+            let rows = sqlx::query(SQL_SELECT_ORDERS_BY_SYMBOL)
                 .bind(symbol)
                 .fetch_all(pool)
                 .await
@@ -304,6 +412,27 @@ impl PendingOrderPort for SqlitePendingOrderStore {
         Ok(orders)
     }
 
+    async fn get_symbols(&self) -> Result<Vec<String>, TradeError> {
+        use sqlx::Row;
+
+        self.ensure_discovered_pools().await?;
+        let mut symbols = Vec::new();
+        for entry in self.pools.iter() {
+            let pool = entry.value();
+            let rows = sqlx::query("SELECT DISTINCT symbol FROM pending_orders")
+                .fetch_all(pool)
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+            for row in rows {
+                symbols.push(row.get::<String, _>("symbol"));
+            }
+        }
+        symbols.sort();
+        symbols.dedup();
+        Ok(symbols)
+    }
+
     async fn update_status(
         &self,
         order_id: &OrderId,
@@ -317,6 +446,7 @@ impl PendingOrderPort for SqlitePendingOrderStore {
             OrderStatus::Filled => "Filled",
             OrderStatus::Canceled => "Canceled",
             OrderStatus::Rejected => "Rejected",
+            OrderStatus::Expired => "Expired",
         };
 
         for entry in self.pools.iter() {
@@ -336,4 +466,70 @@ impl PendingOrderPort for SqlitePendingOrderStore {
         }
         Ok(())
     }
+
+    async fn save_group(&self, group: OrderGroup) -> Result<(), TradeError> {
+        let pool = self.get_or_init_pool(&group.account_id.0).await?;
+        let payload = serde_json::to_string(&group).map_err(|e| {
+            TradeError::InternalError(format!("Failed to encode order group: {}", e))
+        })?;
+
+        sqlx::query(
+            "INSERT INTO order_groups (id, account_id, symbol, payload, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                payload=excluded.payload,
+                updated_at=excluded.updated_at
+            ",
+        )
+        .bind(&group.id.0)
+        .bind(&group.account_id.0)
+        .bind(&group.symbol)
+        .bind(payload)
+        .bind(group.created_at)
+        .bind(Utc::now())
+        .execute(&pool)
+        .await
+        .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        self.ensure_discovered_pools().await?;
+        for entry in self.pools.iter() {
+            let row_opt = sqlx::query("SELECT * FROM order_groups WHERE id = ?")
+                .bind(&group_id.0)
+                .fetch_optional(entry.value())
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+            if let Some(row) = row_opt {
+                return Ok(Some(Self::row_to_group(row)?));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn remove_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        self.ensure_discovered_pools().await?;
+        for entry in self.pools.iter() {
+            let pool = entry.value();
+            let row_opt = sqlx::query("SELECT * FROM order_groups WHERE id = ?")
+                .bind(&group_id.0)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+
+            if let Some(row) = row_opt {
+                let group = Self::row_to_group(row)?;
+                sqlx::query("DELETE FROM order_groups WHERE id = ?")
+                    .bind(&group_id.0)
+                    .execute(pool)
+                    .await
+                    .map_err(|e| TradeError::InternalError(e.to_string()))?;
+                return Ok(Some(group));
+            }
+        }
+        Ok(None)
+    }
 }
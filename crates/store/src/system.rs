@@ -1,7 +1,9 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use okane_core::store::error::StoreError;
-use okane_core::store::port::{AccountProfile, Position, StockMetadata, SystemStore, User};
+use okane_core::store::port::{
+    AccountAuditRecord, AccountProfile, Position, StockMetadata, SystemStore, User,
+};
 use rust_decimal::Decimal;
 use sqlx::{
     SqlitePool,
@@ -55,9 +57,21 @@ CREATE TABLE IF NOT EXISTS accounts (
     account_type TEXT NOT NULL DEFAULT 'local',
     config TEXT NOT NULL DEFAULT '{}',
     created_at DATETIME NOT NULL,
+    halted BOOLEAN NOT NULL DEFAULT 0,
     FOREIGN KEY(owner_id) REFERENCES users(id)
 );
 
+CREATE TABLE IF NOT EXISTS account_audit_log (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    operator_id TEXT NOT NULL,
+    reason TEXT,
+    detail TEXT NOT NULL DEFAULT '{}',
+    created_at DATETIME NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_account_audit_log_account ON account_audit_log(account_id, created_at);
+
 CREATE TABLE IF NOT EXISTS watchlists (
     user_id TEXT NOT NULL,
     symbol TEXT NOT NULL,
@@ -94,6 +108,14 @@ CREATE TABLE IF NOT EXISTS user_notify_config (
 );
 "#;
 
+/// 旧库补列：账户名称、类型、配置与停机标记
+const SQL_MIGRATIONS: [&str; 4] = [
+    "ALTER TABLE accounts ADD COLUMN account_name TEXT NOT NULL DEFAULT ''",
+    "ALTER TABLE accounts ADD COLUMN account_type TEXT NOT NULL DEFAULT 'local'",
+    "ALTER TABLE accounts ADD COLUMN config TEXT NOT NULL DEFAULT '{}'",
+    "ALTER TABLE accounts ADD COLUMN halted BOOLEAN NOT NULL DEFAULT 0",
+];
+
 const SQL_SELECT_USER: &str = "SELECT id, name, password_hash, role, force_password_change, created_at FROM users WHERE id = ?";
 const SQL_INSERT_USER: &str = r#"
 INSERT OR REPLACE INTO users (id, name, password_hash, role, force_password_change, created_at)
@@ -135,6 +157,11 @@ const SQL_INSERT_SETTING: &str =
     "INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, ?, ?)";
 const SQL_COUNT_USERS: &str = "SELECT COUNT(*) FROM users";
 const SQL_SELECT_USER_NOTIFY: &str = "SELECT config_json FROM user_notify_config WHERE user_id = ?";
+const SQL_INSERT_ACCOUNT_AUDIT: &str = r#"
+INSERT INTO account_audit_log (id, account_id, action, operator_id, reason, detail, created_at)
+VALUES (?, ?, ?, ?, ?, ?, ?)
+"#;
+const SQL_SELECT_ACCOUNT_AUDIT: &str = "SELECT id, account_id, action, operator_id, reason, detail, created_at FROM account_audit_log WHERE account_id = ? ORDER BY created_at DESC, rowid DESC";
 const SQL_UPSERT_USER_NOTIFY: &str =
     "INSERT OR REPLACE INTO user_notify_config (user_id, config_json, updated_at) VALUES (?, ?, ?)";
 
@@ -184,18 +211,9 @@ impl SqliteSystemStore {
             .await
             .map_err(|e| StoreError::Database(e.to_string()))?;
 
-        sqlx::query("ALTER TABLE accounts ADD COLUMN account_name TEXT NOT NULL DEFAULT ''")
-            .execute(&pool)
+        crate::migration::add_columns(&pool, &SQL_MIGRATIONS)
             .await
-            .ok();
-        sqlx::query("ALTER TABLE accounts ADD COLUMN account_type TEXT NOT NULL DEFAULT 'local'")
-            .execute(&pool)
-            .await
-            .ok();
-        sqlx::query("ALTER TABLE accounts ADD COLUMN config TEXT NOT NULL DEFAULT '{}'")
-            .execute(&pool)
-            .await
-            .ok();
+            .map_err(|e| StoreError::Database(e.to_string()))?;
 
         let count: (i64,) = sqlx::query_as(SQL_COUNT_USERS)
             .fetch_one(&pool)
@@ -495,8 +513,8 @@ impl SystemStore for SqliteSystemStore {
         &self,
         account_id: &str,
     ) -> Result<Option<AccountProfile>, StoreError> {
-        let row = sqlx::query_as::<_, (String, String, String, String, String, DateTime<Utc>)>(
-            "SELECT id, account_name, owner_id, account_type, config, created_at FROM accounts WHERE id = ?",
+        let row = sqlx::query_as::<_, (String, String, String, String, String, DateTime<Utc>, bool)>(
+            "SELECT id, account_name, owner_id, account_type, config, created_at, halted FROM accounts WHERE id = ?",
         )
         .bind(account_id)
         .fetch_optional(&self.pool)
@@ -509,14 +527,124 @@ impl SystemStore for SqliteSystemStore {
                 account_name: row.1,
                 owner_id: row.2,
                 account_type: row.3,
-                config: serde_json::from_str(&row.4)
-                    .map_err(|e| StoreError::Database(format!("failed to parse account config: {}", e)))?,
+                config: serde_json::from_str(&row.4).map_err(|e| {
+                    StoreError::Database(format!("failed to parse account config: {}", e))
+                })?,
                 created_at: row.5,
+                halted: row.6,
             })
         })
         .transpose()
     }
 
+    async fn update_account_config(
+        &self,
+        account_id: &str,
+        config: serde_json::Value,
+    ) -> Result<(), StoreError> {
+        let result = sqlx::query("UPDATE accounts SET config = ? WHERE id = ?")
+            .bind(config.to_string())
+            .bind(account_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(StoreError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn set_account_halted(&self, account_id: &str, halted: bool) -> Result<(), StoreError> {
+        let result = sqlx::query("UPDATE accounts SET halted = ? WHERE id = ?")
+            .bind(halted)
+            .bind(account_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(StoreError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn save_account_audit(&self, record: &AccountAuditRecord) -> Result<(), StoreError> {
+        sqlx::query(SQL_INSERT_ACCOUNT_AUDIT)
+            .bind(&record.id)
+            .bind(&record.account_id)
+            .bind(record.action.to_string())
+            .bind(&record.operator_id)
+            .bind(&record.reason)
+            .bind(record.detail.to_string())
+            .bind(record.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn list_account_audit(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<AccountAuditRecord>, StoreError> {
+        let rows = sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                String,
+                String,
+                Option<String>,
+                String,
+                DateTime<Utc>,
+            ),
+        >(SQL_SELECT_ACCOUNT_AUDIT)
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Database(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(AccountAuditRecord {
+                    id: row.0,
+                    account_id: row.1,
+                    action: row.2.parse().map_err(StoreError::Database)?,
+                    operator_id: row.3,
+                    reason: row.4,
+                    detail: serde_json::from_str(&row.5).map_err(|e| {
+                        StoreError::Database(format!("failed to parse audit detail: {}", e))
+                    })?,
+                    created_at: row.6,
+                })
+            })
+            .collect()
+    }
+
+    async fn list_account_profiles(&self) -> Result<Vec<AccountProfile>, StoreError> {
+        let rows = sqlx::query_as::<_, (String, String, String, String, String, DateTime<Utc>, bool)>(
+            "SELECT id, account_name, owner_id, account_type, config, created_at, halted FROM accounts ORDER BY id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Database(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(AccountProfile {
+                    id: row.0,
+                    account_name: row.1,
+                    owner_id: row.2,
+                    account_type: row.3,
+                    config: serde_json::from_str(&row.4).map_err(|e| {
+                        StoreError::Database(format!("failed to parse account config: {}", e))
+                    })?,
+                    created_at: row.5,
+                    halted: row.6,
+                })
+            })
+            .collect()
+    }
+
     async fn get_user_accounts(&self, user_id: &str) -> Result<Vec<String>, StoreError> {
         let rows = sqlx::query_as::<_, (String,)>("SELECT id FROM accounts WHERE owner_id = ?")
             .bind(user_id)
@@ -26,7 +26,7 @@ async fn test_sqlite_account_high_concurrency() -> anyhow::Result<()> {
 
     // Deposit 1000 initial cash
     store
-        .deposit(&acct, dec!(1000.0))
+        .deposit(&acct, None, dec!(1000.0))
         .await
         .map_err(|e| anyhow::anyhow!(e))?;
 
@@ -41,7 +41,7 @@ async fn test_sqlite_account_high_concurrency() -> anyhow::Result<()> {
         handles.push(tokio::spawn(async move {
             let req_funds = dec!(15.0); // Predict 15$ needed
             // 1. Freeze
-            let res = store_clone.freeze_funds(&a_id, req_funds).await;
+            let res = store_clone.freeze_funds(&a_id, None, req_funds).await;
             if res.is_ok() {
                 // 2. Execute trade for 14$ (saving 1$)
                 let trade = Trade {
@@ -53,6 +53,7 @@ async fn test_sqlite_account_high_concurrency() -> anyhow::Result<()> {
                     volume: dec!(1.0),
                     commission: dec!(0.0),
                     timestamp: i64::from(i),
+                    settlement: None,
                 };
                 store_clone
                     .process_trade(&a_id, &trade, req_funds)
@@ -91,3 +92,551 @@ async fn test_sqlite_account_high_concurrency() -> anyhow::Result<()> {
     assert_eq!(snap.positions[0].volume, dec!(50.0));
     Ok(())
 }
+
+#[tokio::test]
+async fn test_sqlite_account_tracks_realized_pnl_and_daily_snapshots() -> anyhow::Result<()> {
+    use okane_core::trade::entity::DailyPnl;
+
+    let tmp_dir =
+        tempfile::tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    okane_store::config::set_root_dir(tmp_dir.path().to_path_buf());
+    let store =
+        SqliteAccountStore::new().map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    let acct = AccountId("PnlTx".to_string());
+    store
+        .deposit(&acct, None, dec!(10000))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let fill = |id: &str, direction, price, volume| Trade {
+        order_id: okane_core::trade::entity::OrderId(id.to_string()),
+        account_id: acct.clone(),
+        symbol: "AAPL".to_string(),
+        direction,
+        price,
+        volume,
+        commission: dec!(1),
+        timestamp: 0,
+        settlement: None,
+    };
+    store
+        .freeze_funds(&acct, None, dec!(1001))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    // 买入 10 @ 100，卖出 4 @ 110 (+40)，再卖出 10 @ 105 平 6 股 (+30) 并反手做空 4 股
+    for (trade, reserved) in [
+        (
+            fill("O1", OrderDirection::Buy, dec!(100), dec!(10)),
+            dec!(1001),
+        ),
+        (
+            fill("O2", OrderDirection::Sell, dec!(110), dec!(4)),
+            dec!(0),
+        ),
+        (
+            fill("O3", OrderDirection::Sell, dec!(105), dec!(10)),
+            dec!(0),
+        ),
+    ] {
+        store
+            .process_trade(&acct, &trade, reserved)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    let snap = store
+        .snapshot(&acct)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(snap.realized_pnl, dec!(70));
+    assert_eq!(snap.total_commission, dec!(3));
+    assert_eq!(snap.positions.len(), 1);
+    assert_eq!(snap.positions[0].volume, dec!(-4));
+    assert_eq!(snap.positions[0].average_price, dec!(105));
+    assert_eq!(snap.positions[0].realized_pnl, dec!(70));
+    assert_eq!(snap.positions[0].commission, dec!(3));
+
+    // 同一日期的快照覆盖写入
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 4)
+        .ok_or_else(|| anyhow::anyhow!("invalid date"))?;
+    let mut record = DailyPnl {
+        account_id: acct.clone(),
+        date,
+        opening_equity: dec!(10000),
+        total_equity: dec!(10050),
+        realized_pnl: dec!(70),
+        unrealized_pnl: dec!(-20),
+        total_commission: dec!(3),
+        day_pnl: dec!(50),
+    };
+    store
+        .save_daily_pnl(&record)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    record.total_equity = dec!(10067);
+    record.day_pnl = dec!(67);
+    store
+        .save_daily_pnl(&record)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let history = store
+        .list_daily_pnl(&acct)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(history, vec![record]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sqlite_account_cash_movements_and_ledger() -> anyhow::Result<()> {
+    use okane_core::trade::entity::LedgerAction;
+    use okane_core::trade::port::TradeError;
+
+    let tmp_dir =
+        tempfile::tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    okane_store::config::set_root_dir(tmp_dir.path().to_path_buf());
+    let store =
+        SqliteAccountStore::new().map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    let main = AccountId("CashMain".to_string());
+    let side = AccountId("CashSide".to_string());
+
+    store
+        .ensure_account(&main, dec!(1000))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    store
+        .ensure_account(&side, dec!(0))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let entry = store
+        .deposit(&main, None, dec!(500))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(entry.available_balance, dec!(1500));
+    store
+        .freeze_funds(&main, None, dec!(200))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let entry = store
+        .withdraw(&main, None, dec!(300))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(entry.action, LedgerAction::Withdrawal);
+    assert_eq!(entry.available_balance, dec!(1000));
+    assert_eq!(entry.frozen_balance, dec!(200));
+    assert!(matches!(
+        store.withdraw(&main, None, dec!(1001)).await,
+        Err(TradeError::InsufficientFunds { .. })
+    ));
+
+    let transfer = store
+        .transfer(&main, &side, None, dec!(400), "xfer-1")
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(transfer.outgoing.available_balance, dec!(600));
+    assert_eq!(transfer.incoming.available_balance, dec!(400));
+    assert_eq!(transfer.incoming.reference.as_deref(), Some("xfer-1"));
+
+    // 余额不足的转账两边都不生效
+    assert!(matches!(
+        store
+            .transfer(&side, &main, None, dec!(401), "xfer-2")
+            .await,
+        Err(TradeError::InsufficientFunds { .. })
+    ));
+    let side_snap = store
+        .snapshot(&side)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(side_snap.available_balance, dec!(400));
+
+    let page = store
+        .list_ledger(&main, 0, 10)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let actions: Vec<LedgerAction> = page.items.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            LedgerAction::TransferOut,
+            LedgerAction::Withdrawal,
+            LedgerAction::FreezeFunds,
+            LedgerAction::Deposit,
+            LedgerAction::Deposit,
+        ]
+    );
+    assert_eq!(page.total, 5);
+    let page = store
+        .list_ledger(&main, 1, 2)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[0].action, LedgerAction::Withdrawal);
+    assert_eq!(page.items[1].available_balance, dec!(1300));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sqlite_account_backfills_legacy_ledger_balances() -> anyhow::Result<()> {
+    let tmp_dir =
+        tempfile::tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let accounts_dir = tmp_dir.path().join("accounts");
+    std::fs::create_dir_all(&accounts_dir)?;
+
+    // 旧版流水表没有余额列
+    let options = sqlx::sqlite::SqliteConnectOptions::new()
+        .filename(accounts_dir.join("account_Legacy.db"))
+        .create_if_missing(true);
+    let pool = sqlx::SqlitePool::connect_with(options).await?;
+    sqlx::query(
+        "CREATE TABLE trade_ledger (id INTEGER PRIMARY KEY AUTOINCREMENT, action_type TEXT NOT NULL, asset_change TEXT NOT NULL, frozen_change TEXT NOT NULL, created_at DATETIME NOT NULL)",
+    )
+    .execute(&pool)
+    .await?;
+    for (action, asset, frozen) in [
+        ("Deposit", "1000", "0"),
+        ("FreezeFunds", "-300", "300"),
+        ("TradeFilled", "50", "-300"),
+    ] {
+        sqlx::query("INSERT INTO trade_ledger (action_type, asset_change, frozen_change, created_at) VALUES (?, ?, ?, ?)")
+            .bind(action)
+            .bind(asset)
+            .bind(frozen)
+            .bind(chrono::Utc::now())
+            .execute(&pool)
+            .await?;
+    }
+    pool.close().await;
+
+    let store = SqliteAccountStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+        .map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    let page = store
+        .list_ledger(&AccountId("Legacy".to_string()), 0, 10)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let balances: Vec<_> = page
+        .items
+        .iter()
+        .map(|e| (e.available_balance, e.frozen_balance))
+        .collect();
+    assert_eq!(
+        balances,
+        vec![
+            (dec!(750), dec!(0)),
+            (dec!(700), dec!(300)),
+            (dec!(1000), dec!(0)),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sqlite_account_applies_corporate_actions_once() -> anyhow::Result<()> {
+    use chrono::{TimeZone, Utc};
+    use okane_core::market::entity::{CorporateAction, CorporateActionKind};
+    use okane_core::trade::entity::LedgerAction;
+
+    let tmp_dir =
+        tempfile::tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let store = SqliteAccountStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+        .map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    let acct = AccountId("CorpAct".to_string());
+    store
+        .ensure_account(&acct, dec!(10000))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    store
+        .freeze_funds(&acct, None, dec!(1000))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let buy = Trade {
+        order_id: okane_core::trade::entity::OrderId("CA1".to_string()),
+        account_id: acct.clone(),
+        symbol: "AAPL".to_string(),
+        direction: OrderDirection::Buy,
+        price: dec!(100),
+        volume: dec!(10),
+        commission: dec!(0),
+        timestamp: 0,
+        settlement: None,
+    };
+    store
+        .process_trade(&acct, &buy, dec!(1000))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let ex_date = |day| {
+        Utc.with_ymd_and_hms(2024, 3, day, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("invalid date"))
+    };
+    let split = CorporateAction {
+        symbol: "AAPL".to_string(),
+        ex_date: ex_date(1)?,
+        kind: CorporateActionKind::Split {
+            numerator: 4,
+            denominator: 1,
+        },
+    };
+    let dividend = CorporateAction {
+        symbol: "AAPL".to_string(),
+        ex_date: ex_date(8)?,
+        kind: CorporateActionKind::Dividend { amount: dec!(0.5) },
+    };
+
+    let entry = store
+        .apply_corporate_action(&acct, &split, None)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .ok_or_else(|| anyhow::anyhow!("split should apply"))?;
+    assert_eq!(entry.action, LedgerAction::Split);
+    let entry = store
+        .apply_corporate_action(&acct, &dividend, None)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .ok_or_else(|| anyhow::anyhow!("dividend should apply"))?;
+    assert_eq!(entry.action, LedgerAction::Dividend);
+    // 拆股后 40 股 × 0.5
+    assert_eq!(entry.available_balance, dec!(9020));
+
+    // 重复作用不生效
+    for action in [&split, &dividend] {
+        assert!(
+            store
+                .apply_corporate_action(&acct, action, None)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?
+                .is_none()
+        );
+    }
+    let snapshot = store
+        .snapshot(&acct)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let position = snapshot
+        .positions
+        .iter()
+        .find(|p| p.symbol == "AAPL")
+        .ok_or_else(|| anyhow::anyhow!("position should exist"))?;
+    assert_eq!(position.volume, dec!(40));
+    assert_eq!(position.average_price, dec!(25));
+    assert_eq!(snapshot.available_balance, dec!(9020));
+    assert_eq!(snapshot.realized_pnl, dec!(20));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sqlite_account_keeps_foreign_currency_cash_apart() -> anyhow::Result<()> {
+    use okane_core::trade::entity::{FxSettlement, LedgerAction, OrderId};
+    use okane_core::trade::port::TradeError;
+
+    let tmp_dir =
+        tempfile::tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let store = SqliteAccountStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+        .map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    let acct = AccountId("FxAcct".to_string());
+    store
+        .ensure_account(&acct, dec!(1000))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    // 1 USD = 8 HKD：基础货币换出 500，外币槽位入账 4000
+    let conversion = store
+        .convert_currency(&acct, None, Some("HKD"), dec!(500), dec!(8), "fx-1")
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(conversion.outgoing.available_balance, dec!(500));
+    assert_eq!(conversion.incoming.available_balance, dec!(4000));
+    assert_eq!(conversion.incoming.currency.as_deref(), Some("HKD"));
+    assert_eq!(conversion.incoming.action, LedgerAction::FxConversion);
+    assert!(matches!(
+        store.withdraw(&acct, Some("HKD"), dec!(4001)).await,
+        Err(TradeError::InsufficientFunds { .. })
+    ));
+
+    // 以港币结算的买入只动用港币现金，费用按汇率计入基础货币合计
+    let settlement = FxSettlement {
+        currency: "HKD".to_string(),
+        rate: dec!(0.125),
+    };
+    store
+        .freeze_funds(&acct, Some("HKD"), dec!(3008))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let buy = Trade {
+        order_id: OrderId("hk-buy".to_string()),
+        account_id: acct.clone(),
+        symbol: "0700.HK".to_string(),
+        direction: OrderDirection::Buy,
+        price: dec!(300),
+        volume: dec!(10),
+        commission: dec!(8),
+        timestamp: 0,
+        settlement: Some(settlement),
+    };
+    store
+        .process_trade(&acct, &buy, dec!(3008))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let snapshot = store
+        .snapshot(&acct)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(snapshot.available_balance, dec!(500));
+    assert_eq!(snapshot.total_commission, dec!(1));
+    assert_eq!(snapshot.currencies.len(), 1);
+    assert_eq!(snapshot.currencies[0].currency, "HKD");
+    assert_eq!(snapshot.currencies[0].available_balance, dec!(992));
+    assert_eq!(snapshot.currencies[0].frozen_balance, dec!(0));
+
+    let page = store
+        .list_ledger(&acct, 0, 1)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(page.items[0].action, LedgerAction::TradeFilled);
+    assert_eq!(page.items[0].currency.as_deref(), Some("HKD"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sqlite_account_persists_borrow_fee_accrual() -> anyhow::Result<()> {
+    use okane_core::trade::entity::LedgerAction;
+
+    let tmp_dir =
+        tempfile::tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let store = SqliteAccountStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+        .map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    let acct = AccountId("MarginAcct".to_string());
+    store
+        .ensure_account(&acct, dec!(1000))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(
+        store
+            .borrow_fee_accrued_on(&acct)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+        None
+    );
+
+    // 零费用只推进计提日期，不写流水
+    let first = chrono::NaiveDate::from_ymd_opt(2024, 3, 4)
+        .ok_or_else(|| anyhow::anyhow!("invalid date"))?;
+    let second = chrono::NaiveDate::from_ymd_opt(2024, 3, 6)
+        .ok_or_else(|| anyhow::anyhow!("invalid date"))?;
+    store
+        .accrue_borrow_fee(&acct, dec!(0), first)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    store
+        .accrue_borrow_fee(&acct, dec!(3), second)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    // 重新打开后计提日期与扣费仍在
+    drop(store);
+    let store = SqliteAccountStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+        .map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    assert_eq!(
+        store
+            .borrow_fee_accrued_on(&acct)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+        Some(second)
+    );
+    let snap = store
+        .snapshot(&acct)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(snap.available_balance, dec!(997));
+    let page = store
+        .list_ledger(&acct, 0, 10)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let actions: Vec<LedgerAction> = page.items.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![LedgerAction::BorrowFee, LedgerAction::Deposit]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sqlite_account_recovers_interrupted_transfers() -> anyhow::Result<()> {
+    use okane_core::trade::port::TradeError;
+
+    let tmp_dir =
+        tempfile::tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let store = SqliteAccountStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+        .map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    let main = AccountId("XferMain".to_string());
+    let side = AccountId("XferSide".to_string());
+    store
+        .ensure_account(&main, dec!(1000))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    store
+        .ensure_account(&side, dec!(0))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    store
+        .transfer(&main, &side, None, dec!(400), "xfer-1")
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    // 重复的转账标识不会重复入账，转出方扣款随即冲回
+    assert!(matches!(
+        store
+            .transfer(&main, &side, None, dec!(100), "xfer-1")
+            .await,
+        Err(TradeError::InvalidCashMovement(_))
+    ));
+
+    // 模拟进程中断：xfer-1 入账后日志未删除，xfer-2 扣款提交后尚未入账
+    let pool = store
+        .get_or_init_pool(&main.0)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    for (transfer_id, amount) in [("xfer-1", "400"), ("xfer-2", "100")] {
+        sqlx::query(
+            "INSERT INTO outgoing_transfers (transfer_id, to_account, currency, amount, created_at) VALUES (?, ?, NULL, ?, ?)",
+        )
+        .bind(transfer_id)
+        .bind(&side.0)
+        .bind(amount)
+        .bind(chrono::Utc::now())
+        .execute(&pool)
+        .await?;
+    }
+    drop(pool);
+    drop(store);
+
+    let store = SqliteAccountStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+        .map_err(|e| anyhow::anyhow!("Failed to create store: {}", e))?;
+    assert_eq!(
+        store
+            .recover_transfers()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+        2
+    );
+    assert_eq!(
+        store
+            .recover_transfers()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+        0
+    );
+    let main_snap = store
+        .snapshot(&main)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(main_snap.available_balance, dec!(600));
+    let side_snap = store
+        .snapshot(&side)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(side_snap.available_balance, dec!(500));
+    Ok(())
+}
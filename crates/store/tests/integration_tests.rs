@@ -2,8 +2,13 @@ use chrono::{TimeZone, Utc};
 use okane_core::common::{Stock, TimeFrame};
 use okane_core::market::entity::Candle;
 use okane_core::store::port::{MarketStore, Position, StockMetadata, SystemStore, User};
-use okane_core::trade::entity::{AccountId, Order, OrderDirection, OrderId, OrderStatus};
-use okane_core::trade::port::PendingOrderPort;
+use okane_core::trade::entity::{
+    AccountId, AlgoOrder, AlgoOrderRecord, AlgoOrderStatus, AlgoType, BrokerOrderRecord, Order,
+    OrderDirection, OrderGroup, OrderId, OrderStatus, OrderTrigger, TimeInForce, TrailOffset,
+};
+use okane_core::trade::port::{AlgoOrderStore, BrokerOrderStore, PendingOrderPort};
+use okane_store::algo_order_sqlx::SqliteAlgoOrderStore;
+use okane_store::broker_order_sqlx::SqliteBrokerOrderStore;
 use okane_store::config::set_root_dir;
 use okane_store::market::SqliteMarketStore;
 use okane_store::pending_order_sqlx::SqlitePendingOrderStore;
@@ -137,6 +142,68 @@ async fn test_store_full_integration() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_pending_order_store_migrates_legacy_database() -> anyhow::Result<()> {
+    let tmp_dir = tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let root_path = tmp_dir.path().to_path_buf();
+
+    // 早期版本的活动订单表只有基础列
+    let legacy = sqlx::SqlitePool::connect_with(
+        sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(root_path.join("account_acct_legacy.db"))
+            .create_if_missing(true),
+    )
+    .await?;
+    sqlx::query(
+        "CREATE TABLE pending_orders (
+            id TEXT PRIMARY KEY,
+            account_id TEXT NOT NULL,
+            symbol TEXT NOT NULL,
+            direction TEXT NOT NULL,
+            price TEXT,
+            volume TEXT NOT NULL,
+            filled_volume TEXT NOT NULL,
+            status TEXT NOT NULL,
+            created_at DATETIME NOT NULL,
+            updated_at DATETIME NOT NULL
+        )",
+    )
+    .execute(&legacy)
+    .await?;
+    sqlx::query(
+        "INSERT INTO pending_orders VALUES ('legacy-1', 'acct_legacy', 'AAPL', 'Buy', '150', '10', '0', 'Pending', ?, ?)",
+    )
+    .bind(Utc::now())
+    .bind(Utc::now())
+    .execute(&legacy)
+    .await?;
+    legacy.close().await;
+
+    // 补列后旧订单可读，新列可写；再次打开时已存在的列不会报错
+    let store = SqlitePendingOrderStore::new_with_path(Some(root_path.clone()))?;
+    let recovered = store
+        .get(&OrderId("legacy-1".to_string()))
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("legacy order should be readable after migration"))?;
+    assert_eq!(recovered.volume, dec!(10));
+    assert!(recovered.split_adjusted_at.is_none());
+    store
+        .save(Order {
+            split_adjusted_at: Some(1_700_000_000_000),
+            ..recovered
+        })
+        .await?;
+    drop(store);
+
+    let reopened = SqlitePendingOrderStore::new_with_path(Some(root_path))?;
+    let migrated = reopened
+        .get(&OrderId("legacy-1".to_string()))
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("legacy order should survive a reopen"))?;
+    assert_eq!(migrated.split_adjusted_at, Some(1_700_000_000_000));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_pending_order_store_recovers_orders_after_restart() -> anyhow::Result<()> {
     let tmp_dir = tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
@@ -155,8 +222,55 @@ async fn test_pending_order_store_recovers_orders_after_restart() -> anyhow::Res
         filled_volume: dec!(0.0),
         status: OrderStatus::Pending,
         created_at: Utc::now().timestamp_millis(),
+        trigger: None,
+        time_in_force: TimeInForce::Gtd {
+            expire_at: 1_900_000_000_000,
+        },
+        expire_at: Some(1_900_000_000_000),
+        group_id: None,
+        margin_rate: None,
+        strategy_run_id: Some("run-1".to_string()),
+        currency: Some("HKD".to_string()),
+        split_adjusted_at: Some(1_700_000_000_000),
     };
     store.save(order.clone()).await?;
+
+    // 跟踪止损单的触发状态随订单一起持久化，并在更新时覆盖
+    let mut trailing = OrderTrigger::trailing(TrailOffset::Percent(dec!(5)));
+    trailing.arm(OrderDirection::Sell, dec!(200));
+    let mut stop_order = Order::new(
+        OrderId("restart-order-2".to_string()),
+        AccountId("acct_restart".to_string()),
+        "AAPL".to_string(),
+        OrderDirection::Sell,
+        None,
+        dec!(5),
+        Utc::now().timestamp_millis(),
+    )
+    .with_trigger(trailing);
+    store.save(stop_order.clone()).await?;
+    if let Some(trigger) = stop_order.trigger.as_mut() {
+        trigger.extreme = Some(dec!(210));
+    }
+    store.save(stop_order.clone()).await?;
+
+    // 括号单的子单模板与订单组一起持久化
+    let group = OrderGroup::bracket(
+        OrderId("restart-group-1".to_string()),
+        &order,
+        Order::new(
+            OrderId("restart-tp".to_string()),
+            AccountId("acct_restart".to_string()),
+            "AAPL".to_string(),
+            OrderDirection::Sell,
+            Some(dec!(170)),
+            dec!(10),
+            0,
+        ),
+        stop_order.clone(),
+        0,
+    );
+    store.save_group(group).await?;
     drop(store);
 
     let restarted = SqlitePendingOrderStore::new_with_path(Some(root_path))?;
@@ -165,10 +279,33 @@ async fn test_pending_order_store_recovers_orders_after_restart() -> anyhow::Res
         .await?
         .ok_or_else(|| anyhow::anyhow!("order should be recoverable after restart"))?;
     assert_eq!(recovered.account_id.0, "acct_restart");
+    assert!(recovered.trigger.is_none());
+    assert_eq!(recovered.time_in_force, order.time_in_force);
+    assert_eq!(recovered.expire_at, Some(1_900_000_000_000));
+    assert_eq!(recovered.strategy_run_id.as_deref(), Some("run-1"));
+
+    let recovered_stop = restarted
+        .get(&stop_order.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("stop order should be recoverable after restart"))?;
+    assert_eq!(recovered_stop.trigger, stop_order.trigger);
+    restarted.remove(&stop_order.id).await?;
+
+    let group = restarted
+        .remove_group(&OrderId("restart-group-1".to_string()))
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("order group should be recoverable after restart"))?;
+    assert_eq!(group.parent_id, Some(order_id.clone()));
+    assert_eq!(group.legs.len(), 2);
+    assert!(!group.activated);
+    assert!(restarted.get_group(&group.id).await?.is_none());
 
     let by_symbol = restarted.get_by_symbol("AAPL").await?;
     assert_eq!(by_symbol.len(), 1);
 
+    let symbols = restarted.get_symbols().await?;
+    assert_eq!(symbols, vec!["AAPL".to_string()]);
+
     let removed = restarted
         .remove(&order_id)
         .await?
@@ -177,3 +314,434 @@ async fn test_pending_order_store_recovers_orders_after_restart() -> anyhow::Res
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_algo_order_store_recovers_active_orders_after_restart() -> anyhow::Result<()> {
+    let tmp_dir = tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let root_path = tmp_dir.path().to_path_buf();
+
+    let store = SqliteAlgoOrderStore::new_with_path(Some(root_path.clone()))?;
+    let running = AlgoOrder::new(
+        OrderId("algo-vwap-1".to_string()),
+        AccountId("acct_algo".to_string()),
+        "AAPL".to_string(),
+        AlgoType::Vwap {
+            total_volume: dec!(100),
+            start_time: 1_700_000_000_000,
+            end_time: 1_700_003_600_000,
+            max_participation: dec!(0.15),
+            direction: OrderDirection::Buy,
+        },
+        dec!(100),
+        Utc::now().timestamp_millis(),
+    );
+    store
+        .save(AlgoOrderRecord {
+            order: running.clone(),
+            child_seq: 3,
+            grid_sides: Vec::new(),
+        })
+        .await?;
+
+    let mut canceled = AlgoOrder::new(
+        OrderId("algo-grid-1".to_string()),
+        AccountId("acct_algo".to_string()),
+        "MSFT".to_string(),
+        AlgoType::Grid {
+            upper_price: dec!(410.5),
+            lower_price: dec!(400.25),
+            grids: 4,
+        },
+        dec!(40),
+        Utc::now().timestamp_millis(),
+    );
+    canceled.status = AlgoOrderStatus::Canceled;
+    store
+        .save(AlgoOrderRecord {
+            order: canceled.clone(),
+            child_seq: 8,
+            grid_sides: vec![
+                OrderDirection::Sell,
+                OrderDirection::Buy,
+                OrderDirection::Buy,
+                OrderDirection::Sell,
+            ],
+        })
+        .await?;
+    drop(store);
+
+    let restarted = SqliteAlgoOrderStore::new_with_path(Some(root_path))?;
+    let active = restarted.load_active().await?;
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].order.id, running.id);
+    assert_eq!(active[0].child_seq, 3);
+    assert_eq!(
+        serde_json::to_value(&active[0].order.algo)?,
+        serde_json::to_value(&running.algo)?
+    );
+
+    let terminal = restarted
+        .get(&canceled.id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("terminal algo order should still be stored"))?;
+    assert_eq!(terminal.order.status, AlgoOrderStatus::Canceled);
+    assert_eq!(
+        terminal.grid_sides,
+        vec![
+            OrderDirection::Sell,
+            OrderDirection::Buy,
+            OrderDirection::Buy,
+            OrderDirection::Sell,
+        ]
+    );
+    match terminal.order.algo {
+        AlgoType::Grid {
+            upper_price,
+            lower_price,
+            grids,
+        } => {
+            assert_eq!(upper_price, dec!(410.5));
+            assert_eq!(lower_price, dec!(400.25));
+            assert_eq!(grids, 4);
+        }
+        other => return Err(anyhow::anyhow!("unexpected algo type: {:?}", other)),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_broker_order_store_maps_external_ids_after_restart() -> anyhow::Result<()> {
+    let tmp_dir = tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let root_path = tmp_dir.path().to_path_buf();
+
+    let store = SqliteBrokerOrderStore::new_with_path(Some(root_path.clone()))?;
+    let record = |id: &str, external_id: &str, status| {
+        let mut order = Order::new(
+            OrderId(id.to_string()),
+            AccountId("acct_broker".to_string()),
+            "AAPL".to_string(),
+            OrderDirection::Buy,
+            Some(dec!(100)),
+            dec!(10),
+            Utc::now().timestamp_millis(),
+        );
+        order.status = status;
+        BrokerOrderRecord {
+            account_type: "simulated".to_string(),
+            external_order_id: external_id.to_string(),
+            order,
+            reserved_price: Some(dec!(100)),
+            settled_notional: dec!(0),
+            settled_commission: dec!(0),
+        }
+    };
+    store
+        .save(record("bo-1", "SIM-1", OrderStatus::Submitted))
+        .await?;
+    store
+        .save(record("bo-2", "SIM-2", OrderStatus::Canceled))
+        .await?;
+
+    // 部分成交后覆盖保存
+    let mut partial = record("bo-1", "SIM-1", OrderStatus::PartialFilled);
+    partial.order.filled_volume = dec!(4);
+    partial.settled_notional = dec!(399.5);
+    partial.settled_commission = dec!(0.4);
+    store.save(partial).await?;
+    drop(store);
+
+    let restarted = SqliteBrokerOrderStore::new_with_path(Some(root_path))?;
+    let open = restarted.load_open().await?;
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].order.id.0, "bo-1");
+    assert_eq!(open[0].order.filled_volume, dec!(4));
+    assert_eq!(open[0].settled_notional, dec!(399.5));
+    assert_eq!(open[0].reserved_price, Some(dec!(100)));
+
+    let found = restarted
+        .find_by_external("simulated", "SIM-2")
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("final broker order should still be mapped"))?;
+    assert_eq!(found.order.id.0, "bo-2");
+    assert_eq!(found.order.status, OrderStatus::Canceled);
+    assert!(
+        restarted
+            .find_by_external("other", "SIM-2")
+            .await?
+            .is_none()
+    );
+    assert!(restarted.get(&OrderId("bo-1".to_string())).await?.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_order_history_store_keeps_lifecycle_and_filters_fills() -> anyhow::Result<()> {
+    use okane_core::trade::entity::{HistoryQuery, Trade};
+    use okane_core::trade::port::OrderHistoryPort;
+    use okane_store::order_history_sqlx::SqliteOrderHistoryStore;
+
+    let tmp_dir = tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let store = SqliteOrderHistoryStore::new_with_path(Some(tmp_dir.path().to_path_buf()))?;
+    let account = AccountId("acct_history".to_string());
+
+    let mut limit_order = Order::new(
+        OrderId("hist-1".to_string()),
+        account.clone(),
+        "AAPL".to_string(),
+        OrderDirection::Buy,
+        Some(dec!(100)),
+        dec!(10),
+        1_000,
+    );
+    limit_order.strategy_run_id = Some("run-a".to_string());
+    store.record_order(&limit_order, 1_000).await?;
+    // 状态未变化时不追加变迁
+    store.record_order(&limit_order, 1_500).await?;
+    limit_order.filled_volume = dec!(4);
+    limit_order.status = OrderStatus::PartialFilled;
+    store.record_order(&limit_order, 2_000).await?;
+    limit_order.filled_volume = dec!(10);
+    limit_order.status = OrderStatus::Filled;
+    store.record_order(&limit_order, 3_000).await?;
+
+    let mut manual = Order::new(
+        OrderId("hist-2".to_string()),
+        account.clone(),
+        "MSFT".to_string(),
+        OrderDirection::Sell,
+        Some(dec!(300)),
+        dec!(5),
+        5_000,
+    );
+    store.record_order(&manual, 5_000).await?;
+    manual.status = OrderStatus::Canceled;
+    store.record_order(&manual, 6_000).await?;
+
+    let fill = |volume, timestamp| Trade {
+        order_id: limit_order.id.clone(),
+        account_id: account.clone(),
+        symbol: "AAPL".to_string(),
+        direction: OrderDirection::Buy,
+        price: dec!(100),
+        volume,
+        commission: dec!(0.1),
+        timestamp,
+        settlement: None,
+    };
+    store.record_trade(&fill(dec!(4), 2_000)).await?;
+    store.record_trade(&fill(dec!(6), 3_000)).await?;
+
+    let all = HistoryQuery {
+        account_id: account.clone(),
+        limit: 50,
+        ..HistoryQuery::default()
+    };
+    let page = store.query_orders(&all).await?;
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items[0].order.id.0, "hist-2");
+    let filled = &page.items[1];
+    assert_eq!(filled.order.status, OrderStatus::Filled);
+    assert_eq!(filled.order.strategy_run_id.as_deref(), Some("run-a"));
+    let statuses: Vec<_> = filled.events.iter().map(|e| e.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            OrderStatus::Pending,
+            OrderStatus::PartialFilled,
+            OrderStatus::Filled
+        ]
+    );
+    assert_eq!(filled.updated_at, 3_000);
+
+    let canceled = store
+        .query_orders(&HistoryQuery {
+            status: Some(OrderStatus::Canceled),
+            ..all.clone()
+        })
+        .await?;
+    assert_eq!(canceled.total, 1);
+    assert_eq!(canceled.items[0].order.id.0, "hist-2");
+
+    let paged = store
+        .query_orders(&HistoryQuery {
+            offset: 1,
+            limit: 1,
+            ..all.clone()
+        })
+        .await?;
+    assert_eq!(paged.total, 2);
+    assert_eq!(paged.items.len(), 1);
+    assert_eq!(paged.items[0].order.id.0, "hist-1");
+
+    let trades = store
+        .query_trades(&HistoryQuery {
+            strategy_run_id: Some("run-a".to_string()),
+            start: Some(2_500),
+            ..all.clone()
+        })
+        .await?;
+    assert_eq!(trades.total, 1);
+    assert_eq!(trades.items[0].volume, dec!(6));
+    assert_eq!(trades.items[0].account_id, account);
+
+    let other_run = store
+        .query_trades(&HistoryQuery {
+            strategy_run_id: Some("run-b".to_string()),
+            ..all
+        })
+        .await?;
+    assert_eq!(other_run.total, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_market_store_upserts_corporate_actions() -> anyhow::Result<()> {
+    use okane_core::market::entity::{CorporateAction, CorporateActionKind};
+
+    let tmp_dir = tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let market_store = SqliteMarketStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+        .map_err(|e| anyhow::anyhow!("Failed to create market store: {}", e))?;
+    let stock = Stock {
+        symbol: "MSFT".into(),
+        exchange: None,
+    };
+    let day = |d| {
+        Utc.with_ymd_and_hms(2024, 5, d, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow::anyhow!("Invalid date"))
+    };
+    let action = |d, kind| -> anyhow::Result<CorporateAction> {
+        Ok(CorporateAction {
+            symbol: "MSFT".into(),
+            ex_date: day(d)?,
+            kind,
+        })
+    };
+    market_store
+        .save_corporate_actions(
+            &stock,
+            &[
+                action(
+                    10,
+                    CorporateActionKind::Split {
+                        numerator: 3,
+                        denominator: 2,
+                    },
+                )?,
+                action(2, CorporateActionKind::Dividend { amount: dec!(0.7) })?,
+            ],
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    // 同日同类型的记录被覆盖
+    market_store
+        .save_corporate_actions(
+            &stock,
+            &[action(
+                2,
+                CorporateActionKind::Dividend { amount: dec!(0.75) },
+            )?],
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let loaded = market_store
+        .load_corporate_actions(&stock, day(1)?, day(31)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(
+        loaded,
+        vec![
+            action(2, CorporateActionKind::Dividend { amount: dec!(0.75) })?,
+            action(
+                10,
+                CorporateActionKind::Split {
+                    numerator: 3,
+                    denominator: 2,
+                },
+            )?,
+        ]
+    );
+    let loaded = market_store
+        .load_corporate_actions(&stock, day(3)?, day(31)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(loaded.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_market_store_merges_candle_coverage_per_timeframe() -> anyhow::Result<()> {
+    use okane_core::market::coverage::TimeRange;
+
+    let tmp_dir = tempdir().map_err(|e| anyhow::anyhow!("Failed to create temp dir: {}", e))?;
+    let root_path = tmp_dir.path().to_path_buf();
+    let market_store = SqliteMarketStore::new_with_path(Some(root_path.clone()))
+        .map_err(|e| anyhow::anyhow!("Failed to create market store: {}", e))?;
+    let stock = Stock {
+        symbol: "MSFT".into(),
+        exchange: None,
+    };
+    let range = |a: u32, b: u32| -> anyhow::Result<TimeRange> {
+        let day = |d| {
+            Utc.with_ymd_and_hms(2024, 5, d, 0, 0, 0)
+                .single()
+                .ok_or_else(|| anyhow::anyhow!("Invalid date"))
+        };
+        Ok(TimeRange::new(day(a)?, day(b)?))
+    };
+
+    for r in [range(10, 12)?, range(1, 3)?, range(3, 5)?, range(20, 25)?] {
+        market_store
+            .save_candle_coverage(&stock, TimeFrame::Day1, r)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+    market_store
+        .save_candle_coverage(&stock, TimeFrame::Minute1, range(4, 5)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    // 跨越多个已有区间的新区间将其合并为一段
+    market_store
+        .save_candle_coverage(&stock, TimeFrame::Day1, range(11, 21)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    // 重新打开存储后覆盖区间仍在
+    let reopened = SqliteMarketStore::new_with_path(Some(root_path))
+        .map_err(|e| anyhow::anyhow!("Failed to reopen market store: {}", e))?;
+    let daily = reopened
+        .load_candle_coverage(&stock, TimeFrame::Day1)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(daily, vec![range(1, 5)?, range(10, 25)?]);
+    let minutes = reopened
+        .load_candle_coverage(&stock, TimeFrame::Minute1)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(minutes, vec![range(4, 5)?]);
+    assert!(
+        reopened
+            .load_candle_coverage(&stock, TimeFrame::Hour1)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?
+            .is_empty()
+    );
+
+    // 移除区间只影响该周期，被切开的区间保留两侧
+    reopened
+        .clear_candle_coverage(&stock, TimeFrame::Day1, range(4, 15)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let daily = reopened
+        .load_candle_coverage(&stock, TimeFrame::Day1)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(daily, vec![range(1, 4)?, range(15, 25)?]);
+    let minutes = reopened
+        .load_candle_coverage(&stock, TimeFrame::Minute1)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    assert_eq!(minutes, vec![range(4, 5)?]);
+    Ok(())
+}
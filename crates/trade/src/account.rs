@@ -1,8 +1,12 @@
 use async_trait::async_trait;
-use okane_core::trade::entity::{AccountId, AccountSnapshot, OrderDirection, Position, Trade};
+use okane_core::market::entity::{CorporateAction, CorporateActionKind};
+use okane_core::trade::entity::{
+    AccountId, AccountSnapshot, CashTransfer, CurrencyBalance, DailyPnl, FxSettlement, HistoryPage,
+    LedgerAction, LedgerEntry, OrderDirection, Position, Trade,
+};
 use okane_core::trade::port::{AccountPort, TradeError};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -11,87 +15,230 @@ use tokio::sync::RwLock;
 /// 通过 RwLock 保护内部状态以防御高并发条件下的竞态数据错乱。
 pub struct AccountState {
     pub account_id: AccountId,
-    /// 可用现金 (可用于新开单的额度)
+    /// 可用现金 (可用于新开单的额度)，基础货币
     pub available_balance: Decimal,
-    /// 冻结资金 (已被在途开单挂起，尚未成交扣款的部分)
+    /// 冻结资金 (已被在途开单挂起，尚未成交扣款的部分)，基础货币
     pub frozen_balance: Decimal,
+    /// 非基础货币的现金余额，按币种代码索引
+    pub foreign_cash: BTreeMap<String, CurrencyBalance>,
     /// 单个标的的持仓记录映射
     pub positions: HashMap<String, Position>,
+    /// 累计已实现盈亏 (含已平仓标的)，基础货币
+    pub realized_pnl: Decimal,
+    /// 累计交易费用，基础货币
+    pub total_commission: Decimal,
+    /// 每日盈亏快照
+    pub daily_pnl: BTreeMap<chrono::NaiveDate, DailyPnl>,
+    /// 融券费已计提至的日期
+    pub borrow_fee_accrued_on: Option<chrono::NaiveDate>,
+    /// 各标的公司行动已处理至的时刻
+    pub corporate_action_progress: HashMap<String, chrono::DateTime<chrono::Utc>>,
+    /// 资金流水 (按发生顺序)
+    pub ledger: Vec<LedgerEntry>,
 }
 
 impl AccountState {
     pub fn new(account_id: AccountId, initial_balance: Decimal) -> Self {
-        Self {
+        let mut state = Self {
             account_id,
             available_balance: initial_balance,
             frozen_balance: Decimal::ZERO,
+            foreign_cash: BTreeMap::new(),
             positions: HashMap::new(),
+            realized_pnl: Decimal::ZERO,
+            total_commission: Decimal::ZERO,
+            daily_pnl: BTreeMap::new(),
+            borrow_fee_accrued_on: None,
+            corporate_action_progress: HashMap::new(),
+            ledger: Vec::new(),
+        };
+        if initial_balance > Decimal::ZERO {
+            state.record_ledger(
+                LedgerAction::Deposit,
+                None,
+                initial_balance,
+                Decimal::ZERO,
+                None,
+            );
+        }
+        state
+    }
+
+    /// 币种对应的 (可用, 冻结) 现金，`None` 为基础货币；外币首次使用时以零余额开立。
+    fn cash_mut(&mut self, currency: Option<&str>) -> (&mut Decimal, &mut Decimal) {
+        match currency {
+            None => (&mut self.available_balance, &mut self.frozen_balance),
+            Some(code) => {
+                let balance = self
+                    .foreign_cash
+                    .entry(code.to_string())
+                    .or_insert_with(|| CurrencyBalance {
+                        currency: code.to_string(),
+                        ..CurrencyBalance::default()
+                    });
+                (&mut balance.available_balance, &mut balance.frozen_balance)
+            }
+        }
+    }
+
+    /// # Logic
+    /// 以该币种的当前余额作为变动后余额追加一条资金流水，须在资金变动之后调用。
+    pub fn record_ledger(
+        &mut self,
+        action: LedgerAction,
+        currency: Option<&str>,
+        asset_change: Decimal,
+        frozen_change: Decimal,
+        reference: Option<String>,
+    ) -> LedgerEntry {
+        let (available, frozen) = self.cash_mut(currency);
+        let (available_balance, frozen_balance) = (*available, *frozen);
+        let entry = LedgerEntry {
+            id: i64::try_from(self.ledger.len()).unwrap_or(i64::MAX) + 1,
+            account_id: self.account_id.clone(),
+            action,
+            asset_change,
+            frozen_change,
+            available_balance,
+            frozen_balance,
+            reference,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            currency: currency.map(str::to_string),
+        };
+        self.ledger.push(entry.clone());
+        entry
+    }
+
+    /// # Logic
+    /// 出金或转出：该币种可用资金不足时拒绝。
+    pub fn withdraw_funds(
+        &mut self,
+        currency: Option<&str>,
+        amount: Decimal,
+    ) -> Result<(), TradeError> {
+        let (available, _) = self.cash_mut(currency);
+        if *available < amount {
+            return Err(TradeError::InsufficientFunds {
+                required: amount,
+                actual: *available,
+            });
         }
+        *available -= amount;
+        Ok(())
     }
 
     /// # Logic
     /// 开仓挂单时，冻结相应的准备金。
-    pub fn freeze_funds(&mut self, amount: Decimal) -> Result<(), TradeError> {
-        if self.available_balance < amount {
+    pub fn freeze_funds(
+        &mut self,
+        currency: Option<&str>,
+        amount: Decimal,
+    ) -> Result<(), TradeError> {
+        let (available, frozen) = self.cash_mut(currency);
+        if *available < amount {
             return Err(TradeError::InsufficientFunds {
                 required: amount,
-                actual: self.available_balance,
+                actual: *available,
             });
         }
-        self.available_balance -= amount;
-        self.frozen_balance += amount;
+        *available -= amount;
+        *frozen += amount;
+        Ok(())
+    }
+
+    /// # Logic
+    /// 保证金账户开仓挂单时按剩余保证金额度冻结保证金，可用现金允许因此为负 (即融资)。
+    pub fn freeze_margin(
+        &mut self,
+        currency: Option<&str>,
+        amount: Decimal,
+        excess_margin: Decimal,
+    ) -> Result<(), TradeError> {
+        if excess_margin < amount {
+            return Err(TradeError::InsufficientFunds {
+                required: amount,
+                actual: excess_margin,
+            });
+        }
+        let (available, frozen) = self.cash_mut(currency);
+        *available -= amount;
+        *frozen += amount;
         Ok(())
     }
 
     /// # Logic
     /// 撤单时解冻准备金，归还到可用余额。
-    pub fn unfreeze_funds(&mut self, amount: Decimal) -> Result<(), TradeError> {
-        if amount > self.frozen_balance {
+    pub fn unfreeze_funds(
+        &mut self,
+        currency: Option<&str>,
+        amount: Decimal,
+    ) -> Result<(), TradeError> {
+        let account_id = self.account_id.0.clone();
+        let (available, frozen) = self.cash_mut(currency);
+        if amount > *frozen {
             return Err(TradeError::InternalError(format!(
                 "Account Reconciliation Failure: Tried to unfreeze {} but only {} frozen for account {}",
-                amount, self.frozen_balance, self.account_id.0
+                amount, frozen, account_id
             )));
         }
-        self.frozen_balance -= amount;
-        self.available_balance += amount;
+        *frozen -= amount;
+        *available += amount;
         Ok(())
     }
 
     /// # Logic
     /// 实际发生成交时扣款（如买入扣款），必须完全从冻结资金中扣除。
-    pub fn deduct_funds(&mut self, target_amount: Decimal) -> Result<(), TradeError> {
-        if target_amount > self.frozen_balance {
+    pub fn deduct_funds(
+        &mut self,
+        currency: Option<&str>,
+        target_amount: Decimal,
+    ) -> Result<(), TradeError> {
+        let account_id = self.account_id.0.clone();
+        let (_, frozen) = self.cash_mut(currency);
+        if target_amount > *frozen {
             return Err(TradeError::InternalError(format!(
                 "account reconciliation failure: tried to deduct {} but only {} frozen for account {}",
-                target_amount, self.frozen_balance, self.account_id.0
+                target_amount, frozen, account_id
             )));
         }
-        self.frozen_balance -= target_amount;
+        *frozen -= target_amount;
         Ok(())
     }
 
     /// # Logic
     /// 到账/增加现金（如卖出所得、分红）。
-    pub fn add_funds(&mut self, amount: Decimal) {
-        self.available_balance += amount;
+    pub fn add_funds(&mut self, currency: Option<&str>, amount: Decimal) {
+        let (available, _) = self.cash_mut(currency);
+        *available += amount;
     }
 
     /// # Logic
     /// 调整目标证券的持仓数量。对于平仓操作可能直接抹平持仓。
-    pub fn update_position(&mut self, symbol: &str, delta_volume: Decimal, trade_price: Decimal) {
+    ///
+    /// # Returns
+    /// 本次调整的已实现盈亏 (减仓部分按持仓均价结算，以成交币种计)，由调用方折算计入账户累计。
+    pub fn update_position(
+        &mut self,
+        symbol: &str,
+        delta_volume: Decimal,
+        trade_price: Decimal,
+    ) -> Decimal {
         if delta_volume.is_zero() {
-            return;
+            return Decimal::ZERO;
         }
 
         let position = self
             .positions
             .entry(symbol.to_string())
-            .or_insert_with(|| Position {
-                account_id: self.account_id.clone(),
-                symbol: symbol.to_string(),
-                volume: Decimal::ZERO,
-                average_price: Decimal::ZERO,
-            });
+            .or_insert_with(|| Position::empty(self.account_id.clone(), symbol.to_string()));
+
+        let realized = Position::closing_pnl(
+            position.volume,
+            position.average_price,
+            delta_volume,
+            trade_price,
+        );
+        position.realized_pnl += realized;
 
         // 多头买入或空头卖出（开仓动作，通常会增加头寸绝对值，更新平均价）
         if (position.volume.is_sign_positive() && delta_volume.is_sign_positive())
@@ -110,13 +257,90 @@ impl AccountState {
             // 如果头寸被平光，甚至是反向开新仓，重置价格（简化处理，真实往往拆为平仓和开仓两笔流水）
             if position.volume.is_zero() {
                 position.average_price = Decimal::ZERO;
-            } else if (position.volume.is_sign_positive() && delta_volume.is_sign_negative())
-                || (position.volume.is_sign_negative() && delta_volume.is_sign_positive())
-            {
+            } else if position.volume.is_sign_positive() == delta_volume.is_sign_positive() {
                 // 如果刚好反手了
                 position.average_price = trade_price;
             }
         }
+        realized
+    }
+
+    /// # Logic
+    /// 累计一笔成交的交易费用到标的持仓 (成交币种) 与账户 (按 `fx_rate` 折算为基础货币)。
+    pub fn record_commission(&mut self, symbol: &str, commission: Decimal, fx_rate: Decimal) {
+        if let Some(position) = self.positions.get_mut(symbol) {
+            position.commission += commission;
+        }
+        self.total_commission += commission * fx_rate;
+    }
+
+    /// 把该标的的公司行动处理进度推进至 `until`，不后退。
+    pub fn advance_corporate_action_progress(
+        &mut self,
+        symbol: &str,
+        until: chrono::DateTime<chrono::Utc>,
+    ) {
+        let progress = self
+            .corporate_action_progress
+            .entry(symbol.to_string())
+            .or_insert(until);
+        *progress = (*progress).max(until);
+    }
+
+    /// # Logic
+    /// 1. 流水中已有同一公司行动的记录、除权除息日不晚于该标的处理进度或未持有该标的时不做处理。
+    /// 2. 拆股按比例调整持仓数量与均价；现金分红按持仓数量计入结算币种现金与已实现盈亏。
+    pub fn apply_corporate_action(
+        &mut self,
+        action: &CorporateAction,
+        settlement: Option<&FxSettlement>,
+    ) -> Option<LedgerEntry> {
+        let key = action.key();
+        if self.ledger.iter().any(|entry| {
+            matches!(entry.action, LedgerAction::Split | LedgerAction::Dividend)
+                && entry.reference.as_deref() == Some(key.as_str())
+        }) {
+            return None;
+        }
+        if self
+            .corporate_action_progress
+            .get(&action.symbol)
+            .is_some_and(|until| action.ex_date <= *until)
+        {
+            return None;
+        }
+        let position = self
+            .positions
+            .get_mut(&action.symbol)
+            .filter(|position| !position.volume.is_zero())?;
+        match action.kind {
+            CorporateActionKind::Split { .. } => {
+                let ratio = action.split_ratio().filter(|ratio| !ratio.is_zero())?;
+                position.volume *= ratio;
+                position.average_price /= ratio;
+                Some(self.record_ledger(
+                    LedgerAction::Split,
+                    None,
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    Some(key),
+                ))
+            }
+            CorporateActionKind::Dividend { amount } => {
+                let currency = settlement.map(|s| s.currency.as_str());
+                let cash = position.volume * amount;
+                position.realized_pnl += cash;
+                self.realized_pnl += cash * settlement.map_or(Decimal::ONE, |s| s.rate);
+                self.add_funds(currency, cash);
+                Some(self.record_ledger(
+                    LedgerAction::Dividend,
+                    currency,
+                    cash,
+                    Decimal::ZERO,
+                    Some(key),
+                ))
+            }
+        }
     }
 
     /// # Logic
@@ -130,6 +354,10 @@ impl AccountState {
             frozen_balance: self.frozen_balance,
             total_equity,
             positions: self.positions.values().cloned().collect(),
+            realized_pnl: self.realized_pnl,
+            total_commission: self.total_commission,
+            currencies: self.foreign_cash.values().cloned().collect(),
+            ..AccountSnapshot::default()
         }
     }
 }
@@ -179,21 +407,66 @@ impl AccountPort for AccountManager {
     async fn freeze_funds(
         &self,
         account_id: &AccountId,
+        currency: Option<&str>,
         amount: rust_decimal::Decimal,
     ) -> Result<(), TradeError> {
         let account_lock = self.get_account(account_id)?;
         let mut acct = account_lock.write().await;
-        acct.freeze_funds(amount)
+        acct.freeze_funds(currency, amount)?;
+        acct.record_ledger(LedgerAction::FreezeFunds, currency, -amount, amount, None);
+        Ok(())
     }
 
     async fn unfreeze_funds(
         &self,
         account_id: &AccountId,
+        currency: Option<&str>,
         amount: rust_decimal::Decimal,
     ) -> Result<(), TradeError> {
         let account_lock = self.get_account(account_id)?;
         let mut acct = account_lock.write().await;
-        acct.unfreeze_funds(amount)
+        acct.unfreeze_funds(currency, amount)?;
+        acct.record_ledger(LedgerAction::UnfreezeFunds, currency, amount, -amount, None);
+        Ok(())
+    }
+
+    async fn freeze_margin(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+        excess_margin: rust_decimal::Decimal,
+    ) -> Result<(), TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let mut acct = account_lock.write().await;
+        acct.freeze_margin(currency, amount, excess_margin)?;
+        acct.record_ledger(LedgerAction::FreezeMargin, currency, -amount, amount, None);
+        Ok(())
+    }
+
+    async fn borrow_fee_accrued_on(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<chrono::NaiveDate>, TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let acct = account_lock.read().await;
+        Ok(acct.borrow_fee_accrued_on)
+    }
+
+    async fn accrue_borrow_fee(
+        &self,
+        account_id: &AccountId,
+        amount: rust_decimal::Decimal,
+        accrued_on: chrono::NaiveDate,
+    ) -> Result<(), TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let mut acct = account_lock.write().await;
+        if amount > Decimal::ZERO {
+            acct.add_funds(None, -amount);
+            acct.record_ledger(LedgerAction::BorrowFee, None, -amount, Decimal::ZERO, None);
+        }
+        acct.borrow_fee_accrued_on = Some(accrued_on);
+        Ok(())
     }
 
     async fn process_trade(
@@ -204,35 +477,240 @@ impl AccountPort for AccountManager {
     ) -> Result<(), TradeError> {
         let account_lock = self.get_account(account_id)?;
         let mut acct = account_lock.write().await;
+        let currency = trade.settlement.as_ref().map(|s| s.currency.as_str());
+        let fx_rate = trade.settlement.as_ref().map_or(Decimal::ONE, |s| s.rate);
 
-        if trade.direction == OrderDirection::Buy {
-            let actual_cost = trade.price * trade.volume + trade.commission;
-            acct.deduct_funds(actual_cost)?;
-            let over_frozen = est_req_funds - actual_cost;
-            if over_frozen > rust_decimal::Decimal::ZERO {
-                acct.unfreeze_funds(over_frozen)?;
-            }
-        } else {
-            let actual_gain = trade.price * trade.volume - trade.commission;
-            acct.add_funds(actual_gain);
+        if est_req_funds > rust_decimal::Decimal::ZERO {
+            acct.unfreeze_funds(currency, est_req_funds)?;
         }
+        let notional = trade.price * trade.volume;
+        let cash_flow = if trade.direction == OrderDirection::Buy {
+            -(notional + trade.commission)
+        } else {
+            notional - trade.commission
+        };
+        acct.add_funds(currency, cash_flow);
 
         let position_delta = if trade.direction == OrderDirection::Buy {
             trade.volume
         } else {
             -trade.volume
         };
-        acct.update_position(&trade.symbol, position_delta, trade.price);
+        let opening = acct
+            .positions
+            .get(&trade.symbol)
+            .is_none_or(|position| position.volume.is_zero());
+        if opening && let Some(opened_at) = chrono::DateTime::from_timestamp_millis(trade.timestamp)
+        {
+            acct.advance_corporate_action_progress(&trade.symbol, opened_at);
+        }
+        let realized = acct.update_position(&trade.symbol, position_delta, trade.price);
+        acct.realized_pnl += realized * fx_rate;
+        acct.record_commission(&trade.symbol, trade.commission, fx_rate);
+        let released = est_req_funds.max(Decimal::ZERO);
+        acct.record_ledger(
+            LedgerAction::TradeFilled,
+            currency,
+            released + cash_flow,
+            -released,
+            Some(trade.order_id.0.clone()),
+        );
 
         Ok(())
     }
 
+    async fn apply_corporate_action(
+        &self,
+        account_id: &AccountId,
+        action: &CorporateAction,
+        settlement: Option<&FxSettlement>,
+    ) -> Result<Option<LedgerEntry>, TradeError> {
+        action
+            .validate()
+            .map_err(TradeError::InvalidCorporateAction)?;
+        let account_lock = self.get_account(account_id)?;
+        let mut acct = account_lock.write().await;
+        Ok(acct.apply_corporate_action(action, settlement))
+    }
+
+    async fn corporate_actions_processed_until(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+    ) -> Result<Option<chrono::DateTime<chrono::Utc>>, TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let acct = account_lock.read().await;
+        Ok(acct.corporate_action_progress.get(symbol).copied())
+    }
+
+    async fn mark_corporate_actions_processed(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+        until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let mut acct = account_lock.write().await;
+        acct.advance_corporate_action_progress(symbol, until);
+        Ok(())
+    }
+
+    async fn deposit(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let mut acct = account_lock.write().await;
+        acct.add_funds(currency, amount);
+        Ok(acct.record_ledger(LedgerAction::Deposit, currency, amount, Decimal::ZERO, None))
+    }
+
+    async fn withdraw(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let mut acct = account_lock.write().await;
+        acct.withdraw_funds(currency, amount)?;
+        Ok(acct.record_ledger(
+            LedgerAction::Withdrawal,
+            currency,
+            -amount,
+            Decimal::ZERO,
+            None,
+        ))
+    }
+
+    /// # Logic
+    /// 按账户 ID 顺序同时持有两户写锁，校验并完成双边记账。
+    async fn transfer(
+        &self,
+        from: &AccountId,
+        to: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+        transfer_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        if from == to {
+            return Err(TradeError::InvalidCashMovement(
+                "cannot transfer to the same account".into(),
+            ));
+        }
+        let from_lock = self.get_account(from)?;
+        let to_lock = self.get_account(to)?;
+        let (mut from_acct, mut to_acct) = if from.0 < to.0 {
+            let from_acct = from_lock.write().await;
+            (from_acct, to_lock.write().await)
+        } else {
+            let to_acct = to_lock.write().await;
+            (from_lock.write().await, to_acct)
+        };
+
+        from_acct.withdraw_funds(currency, amount)?;
+        to_acct.add_funds(currency, amount);
+        let reference = Some(transfer_id.to_string());
+        Ok(CashTransfer {
+            transfer_id: transfer_id.to_string(),
+            outgoing: from_acct.record_ledger(
+                LedgerAction::TransferOut,
+                currency,
+                -amount,
+                Decimal::ZERO,
+                reference.clone(),
+            ),
+            incoming: to_acct.record_ledger(
+                LedgerAction::TransferIn,
+                currency,
+                amount,
+                Decimal::ZERO,
+                reference,
+            ),
+        })
+    }
+
+    async fn convert_currency(
+        &self,
+        account_id: &AccountId,
+        from: Option<&str>,
+        to: Option<&str>,
+        amount: rust_decimal::Decimal,
+        rate: rust_decimal::Decimal,
+        conversion_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        if from == to {
+            return Err(TradeError::InvalidCashMovement(
+                "cannot convert to the same currency".into(),
+            ));
+        }
+        let account_lock = self.get_account(account_id)?;
+        let mut acct = account_lock.write().await;
+        acct.withdraw_funds(from, amount)?;
+        let converted = amount * rate;
+        acct.add_funds(to, converted);
+        let reference = Some(conversion_id.to_string());
+        Ok(CashTransfer {
+            transfer_id: conversion_id.to_string(),
+            outgoing: acct.record_ledger(
+                LedgerAction::FxConversion,
+                from,
+                -amount,
+                Decimal::ZERO,
+                reference.clone(),
+            ),
+            incoming: acct.record_ledger(
+                LedgerAction::FxConversion,
+                to,
+                converted,
+                Decimal::ZERO,
+                reference,
+            ),
+        })
+    }
+
+    async fn list_ledger(
+        &self,
+        account_id: &AccountId,
+        offset: usize,
+        limit: usize,
+    ) -> Result<HistoryPage<LedgerEntry>, TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let acct = account_lock.read().await;
+        Ok(HistoryPage {
+            items: acct
+                .ledger
+                .iter()
+                .rev()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+            total: acct.ledger.len(),
+        })
+    }
+
     async fn snapshot(&self, account_id: &AccountId) -> Result<AccountSnapshot, TradeError> {
         let acct_lock = self.get_account(account_id)?;
         let state = acct_lock.read().await;
         Ok(state.to_snapshot())
     }
 
+    async fn save_daily_pnl(&self, record: &DailyPnl) -> Result<(), TradeError> {
+        let account_lock = self.get_account(&record.account_id)?;
+        let mut acct = account_lock.write().await;
+        acct.daily_pnl.insert(record.date, record.clone());
+        Ok(())
+    }
+
+    async fn list_daily_pnl(&self, account_id: &AccountId) -> Result<Vec<DailyPnl>, TradeError> {
+        let account_lock = self.get_account(account_id)?;
+        let acct = account_lock.read().await;
+        Ok(acct.daily_pnl.values().cloned().collect())
+    }
+
     async fn ensure_account(
         &self,
         account_id: &AccountId,
@@ -241,4 +719,11 @@ impl AccountPort for AccountManager {
         self.ensure_account_exists(account_id.clone(), initial_balance);
         Ok(())
     }
+
+    async fn list_accounts(&self) -> Result<Vec<AccountId>, TradeError> {
+        let mut account_ids: Vec<AccountId> =
+            self.accounts.iter().map(|kv| kv.key().clone()).collect();
+        account_ids.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(account_ids)
+    }
 }
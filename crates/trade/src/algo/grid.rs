@@ -0,0 +1,304 @@
+use okane_core::trade::entity::{OrderDirection, OrderId};
+use okane_core::trade::port::TradeError;
+use rust_decimal::Decimal;
+
+/// # Summary
+/// 网格中的单个档位，对应相邻两条网格线构成的价格区间。
+///
+/// # Invariants
+/// - `buy_price < sell_price`。
+/// - `child` 为空时 `child_remaining` 为零。
+#[derive(Debug, Clone)]
+pub(crate) struct GridLevel {
+    /// 档位下沿，买入挂单价
+    pub(crate) buy_price: Decimal,
+    /// 档位上沿，卖出挂单价
+    pub(crate) sell_price: Decimal,
+    /// 每次挂单数量
+    pub(crate) volume: Decimal,
+    /// 下一次需要挂出的方向
+    pub(crate) side: OrderDirection,
+    /// 当前在途的子单
+    pub(crate) child: Option<OrderId>,
+    /// 在途子单尚未成交的数量
+    pub(crate) child_remaining: Decimal,
+}
+
+/// 一次待执行的挂单动作。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GridPlacement {
+    pub(crate) level: usize,
+    pub(crate) direction: OrderDirection,
+    pub(crate) price: Decimal,
+    pub(crate) volume: Decimal,
+}
+
+/// # Summary
+/// 网格算法的执行状态。
+///
+/// # Logic
+/// 在 `[lower_price, upper_price]` 之间等距划出 `grids` 个档位，目标数量均分到每个档位。
+/// 每个档位从空仓开始：价格位于档位下沿之上时挂买单；买单全部成交后在上沿挂卖单；
+/// 卖单全部成交后重新在下沿挂买单，如此往复。
+#[derive(Debug, Clone)]
+pub(crate) struct GridState {
+    pub(crate) levels: Vec<GridLevel>,
+}
+
+impl GridState {
+    /// # Logic
+    /// Lay out `grids` equally spaced levels between the bounds and split `total_volume` evenly.
+    ///
+    /// # Arguments
+    /// * `upper_price` - Upper bound of the grid.
+    /// * `lower_price` - Lower bound of the grid.
+    /// * `grids` - Number of levels, must be positive.
+    /// * `total_volume` - Parent order volume shared by all levels.
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - A grid with every level waiting to buy.
+    /// * `Err(TradeError::AlgoOrderError)` - If the parameters cannot form a grid.
+    pub(crate) fn new(
+        upper_price: Decimal,
+        lower_price: Decimal,
+        grids: u32,
+        total_volume: Decimal,
+    ) -> Result<Self, TradeError> {
+        if grids == 0 {
+            return Err(TradeError::AlgoOrderError(
+                "grid count must be greater than zero".into(),
+            ));
+        }
+        if lower_price <= Decimal::ZERO || upper_price <= lower_price {
+            return Err(TradeError::AlgoOrderError(
+                "grid bounds must satisfy 0 < lower_price < upper_price".into(),
+            ));
+        }
+        if total_volume <= Decimal::ZERO {
+            return Err(TradeError::AlgoOrderError(
+                "grid volume must be greater than zero".into(),
+            ));
+        }
+
+        let count = Decimal::from(grids);
+        let step = (upper_price - lower_price) / count;
+        let volume = total_volume / count;
+
+        let levels = (0..grids)
+            .map(|i| {
+                let buy_price = lower_price + step * Decimal::from(i);
+                GridLevel {
+                    buy_price,
+                    sell_price: buy_price + step,
+                    volume,
+                    side: OrderDirection::Buy,
+                    child: None,
+                    child_remaining: Decimal::ZERO,
+                }
+            })
+            .collect();
+
+        Ok(Self { levels })
+    }
+
+    /// # Logic
+    /// List the orders that should be placed at `last_price`.
+    /// A sell is always re-armed right after its buy completes; a buy is only placed
+    /// while the market trades above the level floor, otherwise it would cross immediately.
+    pub(crate) fn placements(&self, last_price: Decimal) -> Vec<GridPlacement> {
+        self.levels
+            .iter()
+            .enumerate()
+            .filter(|(_, level)| level.child.is_none())
+            .filter_map(|(i, level)| match level.side {
+                OrderDirection::Buy if last_price > level.buy_price => Some(GridPlacement {
+                    level: i,
+                    direction: OrderDirection::Buy,
+                    price: level.buy_price,
+                    volume: level.volume,
+                }),
+                OrderDirection::Buy => None,
+                OrderDirection::Sell => Some(GridPlacement {
+                    level: i,
+                    direction: OrderDirection::Sell,
+                    price: level.sell_price,
+                    volume: level.volume,
+                }),
+            })
+            .collect()
+    }
+
+    /// 记录某档位已挂出的子单。
+    pub(crate) fn on_placed(&mut self, level: usize, child: OrderId) {
+        if let Some(level) = self.levels.get_mut(level) {
+            level.child_remaining = level.volume;
+            level.child = Some(child);
+        }
+    }
+
+    /// 各档位下一次挂单的方向，按档位自下而上排列。
+    pub(crate) fn sides(&self) -> Vec<OrderDirection> {
+        self.levels.iter().map(|l| l.side).collect()
+    }
+
+    /// # Logic
+    /// Restore the per-level sides persisted before a restart.
+    /// A record whose level count does not match the grid (e.g. written before sides were
+    /// persisted) is ignored and every level resumes on the buy side.
+    pub(crate) fn restore_sides(&mut self, sides: &[OrderDirection]) {
+        if sides.len() != self.levels.len() {
+            return;
+        }
+        for (level, side) in self.levels.iter_mut().zip(sides) {
+            level.side = *side;
+        }
+    }
+
+    /// # Logic
+    /// Re-attach a resting child found after a restart to the level it was placed on,
+    /// matched by direction and price. Levels without a resting child keep their restored side.
+    ///
+    /// # Returns
+    /// * `true` - The child matched a free level.
+    /// * `false` - No free level quotes this direction and price.
+    pub(crate) fn restore_child(
+        &mut self,
+        direction: OrderDirection,
+        price: Decimal,
+        child: OrderId,
+        remaining: Decimal,
+    ) -> bool {
+        let Some(level) = self.levels.iter_mut().find(|l| {
+            l.child.is_none()
+                && match direction {
+                    OrderDirection::Buy => l.buy_price == price,
+                    OrderDirection::Sell => l.sell_price == price,
+                }
+        }) else {
+            return false;
+        };
+        level.side = direction;
+        level.child = Some(child);
+        level.child_remaining = remaining;
+        true
+    }
+
+    /// # Logic
+    /// Apply a child fill. When the child is fully filled the level flips to the opposite side.
+    ///
+    /// # Returns
+    /// * `true` - The child belongs to this grid and is now fully filled.
+    /// * `false` - The child is unknown or still has remaining volume.
+    pub(crate) fn on_fill(&mut self, child: &OrderId, volume: Decimal) -> bool {
+        let Some(level) = self
+            .levels
+            .iter_mut()
+            .find(|l| l.child.as_ref() == Some(child))
+        else {
+            return false;
+        };
+
+        level.child_remaining -= volume;
+        if level.child_remaining > Decimal::ZERO {
+            return false;
+        }
+
+        level.child = None;
+        level.child_remaining = Decimal::ZERO;
+        level.side = match level.side {
+            OrderDirection::Buy => OrderDirection::Sell,
+            OrderDirection::Sell => OrderDirection::Buy,
+        };
+        true
+    }
+
+    /// 当前所有在途子单。
+    pub(crate) fn children(&self) -> Vec<OrderId> {
+        self.levels.iter().filter_map(|l| l.child.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_grid_layout_splits_range_and_volume() -> Result<(), TradeError> {
+        let grid = GridState::new(dec!(110), dec!(100), 4, dec!(40))?;
+        assert_eq!(grid.levels.len(), 4);
+        assert_eq!(grid.levels[0].buy_price, dec!(100));
+        assert_eq!(grid.levels[0].sell_price, dec!(102.5));
+        assert_eq!(grid.levels[3].sell_price, dec!(110));
+        assert!(grid.levels.iter().all(|l| l.volume == dec!(10)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_rejects_inverted_bounds() {
+        let res = GridState::new(dec!(100), dec!(110), 4, dec!(40));
+        assert!(matches!(res, Err(TradeError::AlgoOrderError(_))));
+    }
+
+    #[test]
+    fn test_grid_only_buys_below_market() -> Result<(), TradeError> {
+        let grid = GridState::new(dec!(110), dec!(100), 4, dec!(40))?;
+        let placements = grid.placements(dec!(104));
+        let levels: Vec<usize> = placements.iter().map(|p| p.level).collect();
+        assert_eq!(levels, vec![0, 1]);
+        assert!(
+            placements
+                .iter()
+                .all(|p| p.direction == OrderDirection::Buy)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_rearms_opposite_side_after_full_fill() -> Result<(), TradeError> {
+        let mut grid = GridState::new(dec!(110), dec!(100), 4, dec!(40))?;
+        let child = OrderId("c1".into());
+        grid.on_placed(0, child.clone());
+
+        assert!(!grid.on_fill(&child, dec!(4)));
+        assert!(grid.on_fill(&child, dec!(6)));
+
+        let placements = grid.placements(dec!(90));
+        assert_eq!(
+            placements,
+            vec![GridPlacement {
+                level: 0,
+                direction: OrderDirection::Sell,
+                price: dec!(102.5),
+                volume: dec!(10),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid_restores_persisted_sides() -> Result<(), TradeError> {
+        let mut grid = GridState::new(dec!(110), dec!(100), 2, dec!(20))?;
+        let child = OrderId("c1".into());
+        grid.on_placed(0, child.clone());
+        assert!(grid.on_fill(&child, dec!(10)));
+
+        let mut restarted = GridState::new(dec!(110), dec!(100), 2, dec!(20))?;
+        restarted.restore_sides(&grid.sides());
+        assert_eq!(
+            restarted.placements(dec!(90)),
+            vec![GridPlacement {
+                level: 0,
+                direction: OrderDirection::Sell,
+                price: dec!(105),
+                volume: dec!(10),
+            }]
+        );
+
+        // 档位数不匹配的旧记录被忽略，全部档位从买入方向开始
+        let mut legacy = GridState::new(dec!(110), dec!(100), 2, dec!(20))?;
+        legacy.restore_sides(&[OrderDirection::Sell]);
+        assert_eq!(legacy.sides(), vec![OrderDirection::Buy; 2]);
+        Ok(())
+    }
+}
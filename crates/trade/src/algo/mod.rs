@@ -0,0 +1,1101 @@
+use async_trait::async_trait;
+use dashmap::DashMap;
+use okane_core::common::TimeFrame;
+use okane_core::common::time::TimeProvider;
+use okane_core::market::entity::Candle;
+use okane_core::market::port::Market;
+use okane_core::trade::entity::{
+    AccountId, AlgoOrder, AlgoOrderRecord, AlgoOrderStatus, AlgoType, Order, OrderDirection,
+    OrderId, Trade,
+};
+use okane_core::trade::port::{
+    AlgoOrderPort, AlgoOrderStore, BrokerOrderStore, PendingOrderPort, TradeError, TradePort,
+};
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::{Mutex, RwLock};
+
+use crate::live::LiveMatchingService;
+
+mod grid;
+mod snipe;
+mod twap;
+mod vwap;
+
+use grid::GridState;
+use snipe::SnipeState;
+use twap::{TwapAction, TwapState};
+use vwap::{VwapAction, VwapState};
+
+/// VWAP 成交量分布回看的历史天数
+const VWAP_PROFILE_LOOKBACK_DAYS: i64 = 5;
+
+/// 各算法专属的执行状态。
+enum ExecutionPlan {
+    /// 触发状态与保护性子单
+    Snipe(SnipeState),
+    /// 网格档位与在途子单
+    Grid(GridState),
+    /// 时间切片计划与在途子单
+    Twap(TwapState),
+    /// 成交量分布、参与率额度与在途子单
+    Vwap(VwapState),
+}
+
+/// # Summary
+/// 单个算法单的运行时上下文，仅在服务内部维护，不对外暴露。
+struct AlgoRuntime {
+    /// 已派生的子单序号，用于生成子单 ID
+    child_seq: u64,
+    plan: ExecutionPlan,
+}
+
+impl AlgoRuntime {
+    /// # Logic
+    /// Validate the algo parameters and build the initial execution state.
+    /// Time-driven plans are anchored at `now_ms`, the submission time on the service clock.
+    ///
+    /// # Arguments
+    /// * `order` - Algo order to build the runtime for.
+    /// * `now_ms` - Submission time on the service clock.
+    /// * `history` - Historical 1-minute candles, only used to shape the VWAP volume profile.
+    ///
+    /// # Returns
+    /// * `Err(TradeError::AlgoOrderError)` - If the algo type is unsupported or misconfigured.
+    fn for_algo(order: &AlgoOrder, now_ms: i64, history: &[Candle]) -> Result<Self, TradeError> {
+        let plan = match &order.algo {
+            AlgoType::Snipe {
+                target_price,
+                max_slippage,
+                direction,
+            } => ExecutionPlan::Snipe(SnipeState::new(
+                *target_price,
+                *max_slippage,
+                *direction,
+                order.requested_volume,
+            )?),
+            AlgoType::Grid {
+                upper_price,
+                lower_price,
+                grids,
+            } => ExecutionPlan::Grid(GridState::new(
+                *upper_price,
+                *lower_price,
+                *grids,
+                order.requested_volume,
+            )?),
+            AlgoType::Twap {
+                duration_secs,
+                total_volume,
+                direction,
+            } => {
+                if *total_volume != order.requested_volume {
+                    return Err(TradeError::AlgoOrderError(
+                        "twap total_volume must equal requested volume".into(),
+                    ));
+                }
+                ExecutionPlan::Twap(TwapState::new(
+                    *duration_secs,
+                    *total_volume,
+                    *direction,
+                    now_ms,
+                )?)
+            }
+            AlgoType::Vwap {
+                total_volume,
+                start_time,
+                end_time,
+                max_participation,
+                direction,
+            } => {
+                if *total_volume != order.requested_volume {
+                    return Err(TradeError::AlgoOrderError(
+                        "vwap total_volume must equal requested volume".into(),
+                    ));
+                }
+                ExecutionPlan::Vwap(VwapState::new(
+                    *total_volume,
+                    *direction,
+                    *start_time,
+                    *end_time,
+                    *max_participation,
+                    history,
+                )?)
+            }
+        };
+        Ok(Self { child_seq: 0, plan })
+    }
+
+    fn next_child_id(&mut self, algo_id: &OrderId) -> OrderId {
+        self.child_seq += 1;
+        OrderId(format!("{}-{}", algo_id.0, self.child_seq))
+    }
+
+    fn children(&self) -> Vec<OrderId> {
+        match &self.plan {
+            ExecutionPlan::Snipe(snipe) => snipe.children(),
+            ExecutionPlan::Grid(grid) => grid.children(),
+            ExecutionPlan::Twap(twap) => twap.children(),
+            ExecutionPlan::Vwap(vwap) => vwap.children(),
+        }
+    }
+
+    /// 网格各档位下一次挂单的方向，随算法单一并持久化；其他算法为空。
+    fn grid_sides(&self) -> Vec<OrderDirection> {
+        match &self.plan {
+            ExecutionPlan::Grid(grid) => grid.sides(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// # Logic
+    /// 重启恢复：先恢复持久化的网格档位方向，再将仍在活动订单簿中的子单重新挂回执行状态。
+    ///
+    /// # Returns
+    /// * `Vec<OrderId>` - Children that could not be attached to this plan.
+    fn restore(
+        &mut self,
+        filled: Decimal,
+        grid_sides: &[OrderDirection],
+        children: &[Order],
+    ) -> Vec<OrderId> {
+        let remaining = |c: &Order| c.volume - c.filled_volume;
+        match &mut self.plan {
+            ExecutionPlan::Snipe(snipe) => children
+                .iter()
+                .filter(|c| {
+                    c.direction != snipe.direction()
+                        || !snipe.restore_child(c.id.clone(), remaining(c))
+                })
+                .map(|c| c.id.clone())
+                .collect(),
+            ExecutionPlan::Grid(grid) => {
+                grid.restore_sides(grid_sides);
+                children
+                    .iter()
+                    .filter(|c| {
+                        !c.price.is_some_and(|price| {
+                            grid.restore_child(c.direction, price, c.id.clone(), remaining(c))
+                        })
+                    })
+                    .map(|c| c.id.clone())
+                    .collect()
+            }
+            ExecutionPlan::Twap(twap) => {
+                twap.restore(
+                    filled,
+                    children
+                        .iter()
+                        .map(|c| (c.id.clone(), remaining(c)))
+                        .collect(),
+                );
+                Vec::new()
+            }
+            ExecutionPlan::Vwap(vwap) => {
+                vwap.restore(
+                    filled,
+                    children
+                        .iter()
+                        .map(|c| (c.id.clone(), remaining(c)))
+                        .collect(),
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// 返回子单是否已全部成交 (不再在途)。
+    fn on_fill(&mut self, child: &OrderId, volume: Decimal) -> bool {
+        match &mut self.plan {
+            ExecutionPlan::Snipe(snipe) => snipe.on_fill(child, volume),
+            ExecutionPlan::Grid(grid) => grid.on_fill(child, volume),
+            ExecutionPlan::Twap(twap) => twap.on_fill(child, volume),
+            ExecutionPlan::Vwap(vwap) => vwap.on_fill(child, volume),
+        }
+    }
+
+    /// 算法是否已达成目标。网格单持续运行直至撤单。
+    fn is_complete(&self, filled: Decimal) -> bool {
+        match &self.plan {
+            ExecutionPlan::Snipe(snipe) => snipe.is_complete(filled),
+            ExecutionPlan::Grid(_) => false,
+            ExecutionPlan::Twap(twap) => twap.is_complete(filled),
+            ExecutionPlan::Vwap(vwap) => vwap.is_complete(filled),
+        }
+    }
+}
+
+/// # Summary
+/// 算法单管理与执行服务。
+///
+/// # Invariants
+/// - `tick` 与撤单由 `exec_lock` 串行化，撤单完成后不会再派生新的子单。
+/// - 子单成交回报先进入 `pending_fills`，在下一次 `tick` 开始时统一结算，
+///   避免在派生子单的调用栈中重入修改算法单状态。
+pub struct AlgoOrderService {
+    /// 存放所有活跃算法单
+    algo_orders: DashMap<OrderId, AlgoOrder>,
+    /// 算法单运行时状态
+    runtimes: DashMap<OrderId, AlgoRuntime>,
+    /// 在途子单 ID -> 母单 ID
+    child_owners: DashMap<OrderId, OrderId>,
+    /// 尚未结算到算法单上的子单成交
+    pending_fills: Mutex<Vec<Trade>>,
+    /// 串行化驱动与撤单
+    exec_lock: tokio::sync::Mutex<()>,
+    /// 基础交易服务，用于下发子单
+    trade_port: Arc<dyn TradePort>,
+    /// 时间提供者
+    time_provider: Arc<dyn TimeProvider>,
+    /// 可选的行情入口，VWAP 用于拉取历史成交量分布
+    market: Option<Arc<dyn Market>>,
+    /// 可选的算法单仓储，用于进程重启后恢复运行中的算法单
+    store: Option<Arc<dyn AlgoOrderStore>>,
+    /// 可选的外发订单仓储，恢复时从中找回路由到券商网关的在途子单
+    broker_orders: Option<Arc<dyn BrokerOrderStore>>,
+    /// 实时撮合循环，纸面交易下负责为算法单所在标的订阅行情
+    live_matcher: RwLock<Option<Arc<LiveMatchingService>>>,
+}
+
+impl AlgoOrderService {
+    /// # Logic
+    /// Create an algo order service backed by the provided trade port and time provider.
+    ///
+    /// # Arguments
+    /// * `trade_port` - Trade submission port for spawned child orders.
+    /// * `time_provider` - Clock source used for child order timestamps.
+    ///
+    /// # Returns
+    /// * `Self` - A new in-memory algo order service instance.
+    pub fn new(trade_port: Arc<dyn TradePort>, time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self {
+            algo_orders: DashMap::new(),
+            runtimes: DashMap::new(),
+            child_owners: DashMap::new(),
+            pending_fills: Mutex::new(Vec::new()),
+            exec_lock: tokio::sync::Mutex::new(()),
+            trade_port,
+            time_provider,
+            market: None,
+            store: None,
+            broker_orders: None,
+            live_matcher: RwLock::new(None),
+        }
+    }
+
+    /// 关联行情入口，使 VWAP 能按历史日内成交量分布拆单；未关联时退化为均匀分布。
+    pub fn with_market(mut self, market: Arc<dyn Market>) -> Self {
+        self.market = Some(market);
+        self
+    }
+
+    /// 关联算法单仓储，使算法单状态在每次变化后落盘，并可通过 `recover` 在重启后恢复。
+    pub fn with_store(mut self, store: Arc<dyn AlgoOrderStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// 关联外发订单仓储，使 `recover` 能找回已路由到券商网关、不在本地活动订单簿中的子单。
+    pub fn with_broker_orders(mut self, broker_orders: Arc<dyn BrokerOrderStore>) -> Self {
+        self.broker_orders = Some(broker_orders);
+        self
+    }
+
+    /// # Logic
+    /// 1. 从仓储加载全部非终态算法单。
+    /// 2. 按 `{algo_id}-` 前缀在本地活动订单与未终结的外发订单中找出其在途子单，重建执行状态并登记子单归属；
+    ///    时间驱动的算法以 `created_at` 作为计划起点。
+    /// 3. 参数已失效的算法单标记为 `Failed` 并撤销其子单。
+    ///
+    /// 应在实时撮合循环 `start` 之前调用，使恢复的标的被一并订阅。
+    ///
+    /// # Arguments
+    /// * `pending_port` - Pending order repository holding the surviving child orders.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of algo orders resumed.
+    /// * `Err(TradeError)` - The algo store, the pending order repository or the broker
+    ///   order store failed.
+    pub async fn recover(&self, pending_port: &dyn PendingOrderPort) -> Result<usize, TradeError> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let _guard = self.exec_lock.lock().await;
+
+        let broker_children: Vec<Order> = match &self.broker_orders {
+            Some(broker_orders) => broker_orders
+                .load_open()
+                .await?
+                .into_iter()
+                .map(|record| record.order)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut resumed = 0;
+        for record in store.load_active().await? {
+            let order = record.order;
+            let prefix = format!("{}-", order.id.0);
+            let mut children: Vec<Order> = pending_port
+                .get_by_account(&order.account_id)
+                .await?
+                .into_iter()
+                .filter(|c| c.id.0.starts_with(&prefix))
+                .collect();
+            for child in &broker_children {
+                if child.account_id == order.account_id
+                    && child.id.0.starts_with(&prefix)
+                    && !children.iter().any(|c| c.id == child.id)
+                {
+                    children.push(child.clone());
+                }
+            }
+
+            let history = self.load_volume_history(&order).await;
+            let id = order.id.clone();
+            match AlgoRuntime::for_algo(&order, order.created_at, &history) {
+                Ok(mut runtime) => {
+                    runtime.child_seq = record.child_seq;
+                    let orphans =
+                        runtime.restore(order.filled_volume, &record.grid_sides, &children);
+                    for child in children.iter().filter(|c| !orphans.contains(&c.id)) {
+                        self.child_owners.insert(child.id.clone(), id.clone());
+                    }
+                    for orphan in orphans {
+                        tracing::warn!(
+                            "Child order {} of algo order {} could not be re-attached",
+                            orphan.0,
+                            id.0
+                        );
+                    }
+                    self.runtimes.insert(id.clone(), runtime);
+                    self.algo_orders.insert(id, order);
+                    resumed += 1;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to resume algo order {}: {}", id.0, e);
+                    let child_ids = children.into_iter().map(|c| c.id).collect();
+                    self.algo_orders.insert(
+                        id.clone(),
+                        AlgoOrder {
+                            status: AlgoOrderStatus::Failed,
+                            ..order
+                        },
+                    );
+                    self.cancel_child_orders(child_ids).await?;
+                    self.persist(&id).await?;
+                }
+            }
+        }
+
+        tracing::info!("Recovered {} running algo orders", resumed);
+        Ok(resumed)
+    }
+
+    /// # Logic
+    /// Attach the live matching loop so that newly submitted algo orders get market data.
+    ///
+    /// # Arguments
+    /// * `live_matcher` - Live matching service shared with the trade service.
+    ///
+    /// # Returns
+    /// None.
+    pub fn set_live_matcher(
+        &self,
+        live_matcher: Arc<LiveMatchingService>,
+    ) -> Result<(), TradeError> {
+        let mut guard = self
+            .live_matcher
+            .write()
+            .map_err(|e| TradeError::InternalError(format!("live matcher lock poisoned: {}", e)))?;
+        *guard = Some(live_matcher);
+        Ok(())
+    }
+
+    /// 指定标的上是否存在运行中的算法单。
+    pub fn has_running_orders(&self, symbol: &str) -> bool {
+        self.algo_orders
+            .iter()
+            .any(|o| o.symbol == symbol && o.status == AlgoOrderStatus::Running)
+    }
+
+    /// 列出存在运行中算法单的全部标的 (去重)。
+    pub fn running_symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self
+            .algo_orders
+            .iter()
+            .filter(|o| o.status == AlgoOrderStatus::Running)
+            .map(|o| o.symbol.clone())
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        symbols
+    }
+
+    /// # Logic
+    /// Record a fill reported by the trade service. Fills of orders that were not
+    /// spawned by an algo order are ignored. The fill is settled on the next `tick`.
+    ///
+    /// # Arguments
+    /// * `trade` - Execution produced by the matcher.
+    ///
+    /// # Returns
+    /// None.
+    pub fn on_trade(&self, trade: &Trade) -> Result<(), TradeError> {
+        if !self.child_owners.contains_key(&trade.order_id) {
+            return Ok(());
+        }
+        self.pending_fills
+            .lock()
+            .map_err(|e| TradeError::InternalError(format!("fill queue lock poisoned: {}", e)))?
+            .push(trade.clone());
+        Ok(())
+    }
+
+    /// 驱动算法单运行的方法。每当新 K 线到达时调用。
+    ///
+    /// # Logic
+    /// 1. 结算上一轮以来的子单成交。
+    /// 2. 逐个驱动该标的上运行中的算法单；单个算法单出错时标记为 `Failed` 并撤销其子单，
+    ///    不影响其他算法单。
+    /// 3. 结算本轮子单的同步成交，已达成目标的算法单标记为 `Completed`。
+    pub async fn tick(&self, symbol: &str, candle: &Candle) -> Result<(), TradeError> {
+        let _guard = self.exec_lock.lock().await;
+        let mut dirty = self.apply_fills()?;
+
+        let ids: Vec<OrderId> = self
+            .algo_orders
+            .iter()
+            .filter(|o| o.symbol == symbol && o.status == AlgoOrderStatus::Running)
+            .map(|o| o.id.clone())
+            .collect();
+        let before: Vec<_> = ids.iter().map(|id| self.fingerprint(id)).collect();
+
+        for id in &ids {
+            if let Err(e) = self.drive(id, candle).await {
+                tracing::error!("Algo order {} failed: {}", id.0, e);
+                if let Some(mut order) = self.algo_orders.get_mut(id) {
+                    order.status = AlgoOrderStatus::Failed;
+                }
+                self.cancel_children(id).await?;
+            }
+        }
+
+        dirty.extend(self.apply_fills()?);
+        for id in &ids {
+            let completed = match (self.algo_orders.get(id), self.runtimes.get(id)) {
+                (Some(order), Some(runtime)) => {
+                    order.status == AlgoOrderStatus::Running
+                        && runtime.is_complete(order.filled_volume)
+                }
+                _ => false,
+            };
+            if completed {
+                if let Some(mut order) = self.algo_orders.get_mut(id) {
+                    order.status = AlgoOrderStatus::Completed;
+                }
+                self.cancel_children(id).await?;
+            }
+        }
+
+        // 仅持久化本轮状态发生变化的算法单；落盘失败不影响撮合
+        for (id, fingerprint) in ids.iter().zip(before) {
+            if self.fingerprint(id) != fingerprint {
+                dirty.insert(id.clone());
+            }
+        }
+        for id in dirty {
+            if let Err(e) = self.persist(&id).await {
+                tracing::error!("Failed to persist algo order {}: {}", id.0, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// 算法单中需要持久化的可变部分，用于判断本轮是否发生变化。
+    fn fingerprint(&self, id: &OrderId) -> Option<(AlgoOrderStatus, Decimal, u64)> {
+        let order = self.algo_orders.get(id)?;
+        let child_seq = self.runtimes.get(id).map(|r| r.child_seq).unwrap_or(0);
+        Some((order.status, order.filled_volume, child_seq))
+    }
+
+    /// 将算法单当前快照写入仓储；未关联仓储时为空操作。
+    async fn persist(&self, id: &OrderId) -> Result<(), TradeError> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        let Some(order) = self.algo_orders.get(id).map(|o| o.value().clone()) else {
+            return Ok(());
+        };
+        // 进入终态后运行时已回收，子单序号与档位方向不再需要
+        let (child_seq, grid_sides) = self
+            .runtimes
+            .get(id)
+            .map(|r| (r.child_seq, r.grid_sides()))
+            .unwrap_or((0, Vec::new()));
+        store
+            .save(AlgoOrderRecord {
+                order,
+                child_seq,
+                grid_sides,
+            })
+            .await
+    }
+
+    /// 将排队的子单成交累加到母单，并推进对应算法的执行状态。
+    /// 网格单的已成交量为买入减去卖出后的净仓位。
+    ///
+    /// # Returns
+    /// * `HashSet<OrderId>` - Algo orders whose filled volume changed.
+    fn apply_fills(&self) -> Result<HashSet<OrderId>, TradeError> {
+        let fills =
+            std::mem::take(&mut *self.pending_fills.lock().map_err(|e| {
+                TradeError::InternalError(format!("fill queue lock poisoned: {}", e))
+            })?);
+
+        let mut touched = HashSet::new();
+        for trade in fills {
+            let Some(algo_id) = self
+                .child_owners
+                .get(&trade.order_id)
+                .map(|e| e.value().clone())
+            else {
+                continue;
+            };
+
+            if let Some(mut order) = self.algo_orders.get_mut(&algo_id) {
+                // 网格单买卖往复，成交量按方向轧差为网格当前持有的净仓位
+                let volume = match order.algo {
+                    AlgoType::Grid { .. } if trade.direction == OrderDirection::Sell => {
+                        -trade.volume
+                    }
+                    _ => trade.volume,
+                };
+                order.filled_volume += volume;
+            }
+            let completed = match self.runtimes.get_mut(&algo_id) {
+                Some(mut runtime) => runtime.on_fill(&trade.order_id, trade.volume),
+                None => true,
+            };
+            if completed {
+                self.child_owners.remove(&trade.order_id);
+            }
+            touched.insert(algo_id);
+        }
+        Ok(touched)
+    }
+
+    async fn drive(&self, id: &OrderId, candle: &Candle) -> Result<(), TradeError> {
+        let Some(order) = self.algo_orders.get(id).map(|o| o.value().clone()) else {
+            return Ok(());
+        };
+
+        match &order.algo {
+            AlgoType::Snipe { .. } => self.drive_snipe(&order, candle).await,
+            AlgoType::Grid { .. } => self.drive_grid(&order, candle).await,
+            AlgoType::Twap { .. } => self.drive_twap(&order).await,
+            AlgoType::Vwap { .. } => self.drive_vwap(&order, candle).await,
+        }
+    }
+
+    /// 狙击单逻辑：价格首次到达目标价时挂出保护性限价子单，成交价不劣于滑点上限
+    async fn drive_snipe(&self, order: &AlgoOrder, candle: &Candle) -> Result<(), TradeError> {
+        let (trigger, direction) = match self.runtimes.get(&order.id).as_deref() {
+            Some(AlgoRuntime {
+                plan: ExecutionPlan::Snipe(snipe),
+                ..
+            }) => (
+                snipe.check(candle.close, order.filled_volume),
+                snipe.direction(),
+            ),
+            _ => {
+                return Err(TradeError::AlgoOrderError(format!(
+                    "snipe state missing for algo order {}",
+                    order.id.0
+                )));
+            }
+        };
+        let Some(trigger) = trigger else {
+            return Ok(());
+        };
+
+        let child = Order::new(
+            self.next_child_id(&order.id)?,
+            order.account_id.clone(),
+            order.symbol.clone(),
+            direction,
+            Some(trigger.limit_price),
+            trigger.volume,
+            self.now_ms()?,
+        );
+        let child_id = self.submit_child(&order.id, child).await?;
+        if let Some(mut runtime) = self.runtimes.get_mut(&order.id)
+            && let ExecutionPlan::Snipe(snipe) = &mut runtime.plan
+        {
+            snipe.on_placed(child_id, trigger.volume);
+        }
+        Ok(())
+    }
+
+    /// 网格逻辑：为空闲档位挂出买单或卖单，成交后的反向挂单在此补齐
+    async fn drive_grid(&self, order: &AlgoOrder, candle: &Candle) -> Result<(), TradeError> {
+        let placements = match self.runtimes.get(&order.id).as_deref() {
+            Some(AlgoRuntime {
+                plan: ExecutionPlan::Grid(grid),
+                ..
+            }) => grid.placements(candle.close),
+            _ => {
+                return Err(TradeError::AlgoOrderError(format!(
+                    "grid state missing for algo order {}",
+                    order.id.0
+                )));
+            }
+        };
+
+        for placement in placements {
+            let child = Order::new(
+                self.next_child_id(&order.id)?,
+                order.account_id.clone(),
+                order.symbol.clone(),
+                placement.direction,
+                Some(placement.price),
+                placement.volume,
+                self.now_ms()?,
+            );
+            let child_id = self.submit_child(&order.id, child).await?;
+            if let Some(mut runtime) = self.runtimes.get_mut(&order.id)
+                && let ExecutionPlan::Grid(grid) = &mut runtime.plan
+            {
+                grid.on_placed(placement.level, child_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// TWAP 逻辑：按服务时钟下发到期切片；超过结束时间后撤销在途子单，并以市价扫尾剩余数量
+    async fn drive_twap(&self, order: &AlgoOrder) -> Result<(), TradeError> {
+        let now_ms = self.now_ms()?;
+        let (action, direction) = match self.runtimes.get(&order.id).as_deref() {
+            Some(AlgoRuntime {
+                plan: ExecutionPlan::Twap(twap),
+                ..
+            }) => (
+                twap.next_action(now_ms, order.filled_volume),
+                twap.direction(),
+            ),
+            _ => {
+                return Err(TradeError::AlgoOrderError(format!(
+                    "twap state missing for algo order {}",
+                    order.id.0
+                )));
+            }
+        };
+
+        let volume = match action {
+            TwapAction::Wait => return Ok(()),
+            TwapAction::Slice(volume) => volume,
+            TwapAction::Sweep(volume) => {
+                let stale = match self.runtimes.get_mut(&order.id) {
+                    Some(mut runtime) => match &mut runtime.plan {
+                        ExecutionPlan::Twap(twap) => twap.take_children(),
+                        _ => Vec::new(),
+                    },
+                    None => Vec::new(),
+                };
+                self.cancel_child_orders(stale).await?;
+                volume
+            }
+        };
+
+        let child = Order::new(
+            self.next_child_id(&order.id)?,
+            order.account_id.clone(),
+            order.symbol.clone(),
+            direction,
+            None,
+            volume,
+            now_ms,
+        );
+        let child_id = self.submit_child(&order.id, child).await?;
+        if let Some(mut runtime) = self.runtimes.get_mut(&order.id)
+            && let ExecutionPlan::Twap(twap) = &mut runtime.plan
+        {
+            twap.on_placed(child_id, volume);
+        }
+        Ok(())
+    }
+
+    /// VWAP 逻辑：按成交量分布推进计划，子单受本分钟参与率额度约束；窗口结束即收尾
+    async fn drive_vwap(&self, order: &AlgoOrder, candle: &Candle) -> Result<(), TradeError> {
+        let now_ms = self.now_ms()?;
+        let (action, direction) = match self.runtimes.get_mut(&order.id).as_deref_mut() {
+            Some(AlgoRuntime {
+                plan: ExecutionPlan::Vwap(vwap),
+                ..
+            }) => (vwap.next_action(now_ms, candle), vwap.direction()),
+            _ => {
+                return Err(TradeError::AlgoOrderError(format!(
+                    "vwap state missing for algo order {}",
+                    order.id.0
+                )));
+            }
+        };
+
+        let volume = match action {
+            VwapAction::Wait => return Ok(()),
+            VwapAction::Child(volume) => volume,
+            VwapAction::Expired => {
+                // 窗口结束不扫尾，避免突破参与率上限；未全部成交时以 Expired 收尾
+                tracing::info!(
+                    "Vwap algo order {} window ended with {}/{} filled",
+                    order.id.0,
+                    order.filled_volume,
+                    order.requested_volume
+                );
+                if let Some(mut stored) = self.algo_orders.get_mut(&order.id) {
+                    stored.status = if stored.filled_volume >= stored.requested_volume {
+                        AlgoOrderStatus::Completed
+                    } else {
+                        AlgoOrderStatus::Expired
+                    };
+                }
+                return self.cancel_children(&order.id).await;
+            }
+        };
+
+        let child = Order::new(
+            self.next_child_id(&order.id)?,
+            order.account_id.clone(),
+            order.symbol.clone(),
+            direction,
+            None,
+            volume,
+            now_ms,
+        );
+        let child_id = self.submit_child(&order.id, child).await?;
+        if let Some(mut runtime) = self.runtimes.get_mut(&order.id)
+            && let ExecutionPlan::Vwap(vwap) = &mut runtime.plan
+        {
+            vwap.on_placed(child_id, volume);
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 拉取 VWAP 窗口之前若干天的 1 分钟 K 线，用于构建日内成交量分布。
+    /// 其他算法类型、未关联行情或拉取失败时返回空列表，由调用方退化为均匀分布。
+    async fn load_volume_history(&self, order: &AlgoOrder) -> Vec<Candle> {
+        let (AlgoType::Vwap { start_time, .. }, Some(market)) = (&order.algo, &self.market) else {
+            return Vec::new();
+        };
+        let Some(end) = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(*start_time) else {
+            return Vec::new();
+        };
+        let start = end - chrono::Duration::days(VWAP_PROFILE_LOOKBACK_DAYS);
+
+        let history = match market.get_stock(&order.symbol).await {
+            Ok(stock) => stock.fetch_history(TimeFrame::Minute1, start, end).await,
+            Err(e) => Err(e),
+        };
+        match history {
+            Ok(candles) => candles,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load volume profile for {}, falling back to uniform: {}",
+                    order.symbol,
+                    e
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    fn next_child_id(&self, algo_id: &OrderId) -> Result<OrderId, TradeError> {
+        let mut runtime = self.runtimes.get_mut(algo_id).ok_or_else(|| {
+            TradeError::AlgoOrderError(format!("runtime missing for algo order {}", algo_id.0))
+        })?;
+        Ok(runtime.next_child_id(algo_id))
+    }
+
+    /// 下发子单并登记归属。先按预分配 ID 登记，保证同步成交的回报不会丢失。
+    async fn submit_child(&self, algo_id: &OrderId, child: Order) -> Result<OrderId, TradeError> {
+        let planned_id = child.id.clone();
+        self.child_owners
+            .insert(planned_id.clone(), algo_id.clone());
+
+        let child_id = match self.trade_port.submit_order(child).await {
+            Ok(id) => id,
+            Err(e) => {
+                self.child_owners.remove(&planned_id);
+                return Err(e);
+            }
+        };
+        if child_id != planned_id {
+            self.child_owners.remove(&planned_id);
+            self.child_owners.insert(child_id.clone(), algo_id.clone());
+        }
+        Ok(child_id)
+    }
+
+    /// 撤销算法单的全部在途子单。已成交或已撤销的子单视为无需处理。
+    async fn cancel_children(&self, algo_id: &OrderId) -> Result<(), TradeError> {
+        let children = self
+            .runtimes
+            .remove(algo_id)
+            .map(|(_, runtime)| runtime.children())
+            .unwrap_or_default();
+        self.cancel_child_orders(children).await
+    }
+
+    async fn cancel_child_orders(&self, children: Vec<OrderId>) -> Result<(), TradeError> {
+        for child in children {
+            self.child_owners.remove(&child);
+            match self.trade_port.cancel_order(child.clone()).await {
+                Ok(())
+                | Err(TradeError::OrderNotFound(_))
+                | Err(TradeError::InvalidOrderStatus) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn now_ms(&self) -> Result<i64, TradeError> {
+        Ok(self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .timestamp_millis())
+    }
+}
+
+#[async_trait]
+impl AlgoOrderPort for AlgoOrderService {
+    async fn submit_algo_order(&self, order: AlgoOrder) -> Result<OrderId, TradeError> {
+        let history = self.load_volume_history(&order).await;
+        let runtime = AlgoRuntime::for_algo(&order, self.now_ms()?, &history)?;
+        if let Some(store) = &self.store {
+            store
+                .save(AlgoOrderRecord {
+                    order: order.clone(),
+                    child_seq: 0,
+                    grid_sides: runtime.grid_sides(),
+                })
+                .await?;
+        }
+        let id = order.id.clone();
+        let symbol = order.symbol.clone();
+        self.runtimes.insert(id.clone(), runtime);
+        self.algo_orders.insert(id.clone(), order);
+
+        let live_matcher = self
+            .live_matcher
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("live matcher lock poisoned: {}", e)))?
+            .clone();
+        if let Some(live) = live_matcher {
+            live.watch(&symbol);
+        }
+        Ok(id)
+    }
+
+    async fn cancel_algo_order(&self, order_id: &OrderId) -> Result<(), TradeError> {
+        self.update_algo_status(order_id, AlgoOrderStatus::Canceled)
+            .await
+    }
+
+    async fn get_algo_order(&self, order_id: &OrderId) -> Result<Option<AlgoOrder>, TradeError> {
+        Ok(self.algo_orders.get(order_id).map(|o| o.value().clone()))
+    }
+
+    async fn get_algo_orders(&self, account_id: &AccountId) -> Result<Vec<AlgoOrder>, TradeError> {
+        Ok(self
+            .algo_orders
+            .iter()
+            .filter(|o| o.value().account_id == *account_id)
+            .map(|o| o.value().clone())
+            .collect())
+    }
+
+    /// # Logic
+    /// 进入终态 (Completed / Canceled / Expired / Failed) 时，先结算已到达的成交，再撤销全部在途子单。
+    async fn update_algo_status(
+        &self,
+        order_id: &OrderId,
+        status: AlgoOrderStatus,
+    ) -> Result<(), TradeError> {
+        let _guard = self.exec_lock.lock().await;
+        self.apply_fills()?;
+
+        match self.algo_orders.get_mut(order_id) {
+            Some(mut order) => order.status = status,
+            None => return Err(TradeError::AlgoOrderNotFound(order_id.0.clone())),
+        }
+
+        if matches!(
+            status,
+            AlgoOrderStatus::Completed
+                | AlgoOrderStatus::Canceled
+                | AlgoOrderStatus::Expired
+                | AlgoOrderStatus::Failed
+        ) {
+            self.cancel_children(order_id).await?;
+        }
+        self.persist(order_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::test_utils::SpyTradePort;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_submit_and_get_algo_order() -> anyhow::Result<()> {
+        let trade_port = Arc::new(SpyTradePort::new());
+        let time_provider = Arc::new(FakeClockProvider::new(chrono::Utc::now()));
+        let service = AlgoOrderService::new(trade_port, time_provider);
+
+        let account_id = AccountId("test_acct".into());
+        let order_id = OrderId("algo_01".into());
+        let order = AlgoOrder::new(
+            order_id.clone(),
+            account_id.clone(),
+            "AAPL".into(),
+            AlgoType::Snipe {
+                target_price: dec!(150.0),
+                max_slippage: dec!(0.1),
+                direction: OrderDirection::Buy,
+            },
+            dec!(10),
+            1000,
+        );
+
+        service.submit_algo_order(order).await?;
+
+        let retrieved = service
+            .get_algo_order(&order_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("AlgoOrder not found"))?;
+        assert_eq!(retrieved.symbol, "AAPL");
+
+        let all = service.get_algo_orders(&account_id).await?;
+        assert_eq!(all.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancel_algo_order() -> anyhow::Result<()> {
+        let trade_port = Arc::new(SpyTradePort::new());
+        let time_provider = Arc::new(FakeClockProvider::new(chrono::Utc::now()));
+        let service = AlgoOrderService::new(trade_port, time_provider);
+
+        let order_id = OrderId("algo_01".into());
+        let order = AlgoOrder::new(
+            order_id.clone(),
+            AccountId("test".into()),
+            "AAPL".into(),
+            AlgoType::Snipe {
+                target_price: dec!(150.0),
+                max_slippage: dec!(0.1),
+                direction: OrderDirection::Buy,
+            },
+            dec!(10),
+            1000,
+        );
+
+        service.submit_algo_order(order).await?;
+        service.cancel_algo_order(&order_id).await?;
+
+        let retrieved = service
+            .get_algo_order(&order_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("AlgoOrder not found"))?;
+        assert_eq!(retrieved.status, AlgoOrderStatus::Canceled);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_snipe_algo_trigger() -> anyhow::Result<()> {
+        let spy_trade = Arc::new(SpyTradePort::new());
+        let time_provider = Arc::new(FakeClockProvider::new(chrono::Utc::now()));
+
+        let service = AlgoOrderService::new(spy_trade.clone(), time_provider);
+
+        let order_id = OrderId("snipe_01".into());
+        let order = AlgoOrder {
+            id: order_id.clone(),
+            account_id: AccountId("test".into()),
+            symbol: "AAPL".into(),
+            algo: AlgoType::Snipe {
+                target_price: dec!(100.0),
+                max_slippage: dec!(0.1),
+                direction: OrderDirection::Buy,
+            },
+            status: AlgoOrderStatus::Running,
+            requested_volume: dec!(10),
+            filled_volume: Decimal::ZERO,
+            created_at: 1000,
+        };
+
+        service.submit_algo_order(order).await?;
+
+        // 1. Price above target - no trigger
+        let candle_high = Candle {
+            time: chrono::Utc::now(),
+            open: dec!(105),
+            high: dec!(106),
+            low: dec!(104),
+            close: dec!(105),
+            adj_close: None,
+            volume: dec!(100),
+            is_final: true,
+        };
+        service.tick("AAPL", &candle_high).await?;
+        assert_eq!(spy_trade.get_submitted_orders()?.len(), 0);
+        let retrieved = service
+            .get_algo_order(&order_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("AlgoOrder not found"))?;
+        assert_eq!(retrieved.status, AlgoOrderStatus::Running);
+
+        // 2. Price hits target - trigger!
+        let candle_hit = Candle {
+            time: chrono::Utc::now(),
+            open: dec!(101),
+            high: dec!(102),
+            low: dec!(99),
+            close: dec!(100),
+            adj_close: None,
+            volume: dec!(100),
+            is_final: true,
+        };
+        service.tick("AAPL", &candle_hit).await?;
+
+        let submitted = spy_trade.get_submitted_orders()?;
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(submitted[0].symbol, "AAPL");
+        assert_eq!(submitted[0].direction, OrderDirection::Buy);
+        assert_eq!(submitted[0].volume, dec!(10));
+        // 保护性限价 = 目标价 + 最大滑点
+        assert_eq!(submitted[0].price, Some(dec!(100.1)));
+        assert_eq!(submitted[0].id, OrderId("snipe_01-1".into()));
+
+        // 3. 子单尚未成交：母单保持运行，且不会重复触发
+        service.tick("AAPL", &candle_hit).await?;
+        assert_eq!(spy_trade.get_submitted_orders()?.len(), 1);
+        let retrieved = service
+            .get_algo_order(&order_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("AlgoOrder not found"))?;
+        assert_eq!(retrieved.status, AlgoOrderStatus::Running);
+        assert_eq!(retrieved.filled_volume, Decimal::ZERO);
+
+        Ok(())
+    }
+}
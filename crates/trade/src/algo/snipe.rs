@@ -0,0 +1,208 @@
+use okane_core::trade::entity::{OrderDirection, OrderId};
+use okane_core::trade::port::TradeError;
+use rust_decimal::Decimal;
+
+/// 一次待执行的狙击下单动作。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SnipeTrigger {
+    /// 保护性限价：买入为目标价加滑点，卖出为目标价减滑点
+    pub(crate) limit_price: Decimal,
+    pub(crate) volume: Decimal,
+}
+
+/// # Summary
+/// 狙击单的执行状态。
+///
+/// # Logic
+/// 价格首次到达目标价 (买入时不高于、卖出时不低于) 即触发，以 `target_price ± max_slippage`
+/// 挂出一笔保护性限价子单，成交价因此不会劣于滑点上限。触发只发生一次，
+/// 母单在子单全部成交前保持运行。
+///
+/// # Invariants
+/// - 最多只有一笔在途子单；`child` 为空时 `child_remaining` 为零。
+#[derive(Debug, Clone)]
+pub(crate) struct SnipeState {
+    target_price: Decimal,
+    max_slippage: Decimal,
+    direction: OrderDirection,
+    total_volume: Decimal,
+    /// 是否已触发
+    triggered: bool,
+    /// 在途子单
+    child: Option<OrderId>,
+    /// 在途子单尚未成交的数量
+    child_remaining: Decimal,
+}
+
+impl SnipeState {
+    /// # Logic
+    /// Validate the snipe parameters.
+    ///
+    /// # Arguments
+    /// * `target_price` - Trigger price, must be positive.
+    /// * `max_slippage` - Absolute price tolerance beyond the target, must not be negative.
+    /// * `direction` - Buy snipes trigger at or below the target, sell snipes at or above it.
+    /// * `total_volume` - Parent order volume, must be positive.
+    ///
+    /// # Returns
+    /// * `Err(TradeError::AlgoOrderError)` - If any parameter is invalid.
+    pub(crate) fn new(
+        target_price: Decimal,
+        max_slippage: Decimal,
+        direction: OrderDirection,
+        total_volume: Decimal,
+    ) -> Result<Self, TradeError> {
+        if target_price <= Decimal::ZERO {
+            return Err(TradeError::AlgoOrderError(
+                "snipe target_price must be greater than zero".into(),
+            ));
+        }
+        if max_slippage < Decimal::ZERO {
+            return Err(TradeError::AlgoOrderError(
+                "snipe max_slippage must not be negative".into(),
+            ));
+        }
+        if direction == OrderDirection::Sell && max_slippage >= target_price {
+            return Err(TradeError::AlgoOrderError(
+                "snipe max_slippage must be below target_price for sells".into(),
+            ));
+        }
+        if total_volume <= Decimal::ZERO {
+            return Err(TradeError::AlgoOrderError(
+                "snipe volume must be greater than zero".into(),
+            ));
+        }
+        Ok(Self {
+            target_price,
+            max_slippage,
+            direction,
+            total_volume,
+            triggered: false,
+            child: None,
+            child_remaining: Decimal::ZERO,
+        })
+    }
+
+    /// 子单方向。
+    pub(crate) fn direction(&self) -> OrderDirection {
+        self.direction
+    }
+
+    /// # Logic
+    /// Return the protective child to place if `last_price` reaches the target for the first time.
+    pub(crate) fn check(&self, last_price: Decimal, filled: Decimal) -> Option<SnipeTrigger> {
+        if self.triggered {
+            return None;
+        }
+        let (reached, limit_price) = match self.direction {
+            OrderDirection::Buy => (
+                last_price <= self.target_price,
+                self.target_price + self.max_slippage,
+            ),
+            OrderDirection::Sell => (
+                last_price >= self.target_price,
+                self.target_price - self.max_slippage,
+            ),
+        };
+        let volume = self.total_volume - filled;
+        (reached && volume > Decimal::ZERO).then_some(SnipeTrigger {
+            limit_price,
+            volume,
+        })
+    }
+
+    /// 记录已挂出的保护性子单。
+    pub(crate) fn on_placed(&mut self, child: OrderId, volume: Decimal) {
+        self.triggered = true;
+        self.child = Some(child);
+        self.child_remaining = volume;
+    }
+
+    /// 子单成交回报，返回子单是否已全部成交。
+    pub(crate) fn on_fill(&mut self, child: &OrderId, volume: Decimal) -> bool {
+        if self.child.as_ref() != Some(child) {
+            return true;
+        }
+        self.child_remaining -= volume;
+        if self.child_remaining > Decimal::ZERO {
+            return false;
+        }
+        self.child = None;
+        self.child_remaining = Decimal::ZERO;
+        true
+    }
+
+    /// 重启恢复：接管仍在挂单中的子单，返回是否接管成功。
+    pub(crate) fn restore_child(&mut self, child: OrderId, remaining: Decimal) -> bool {
+        if self.child.is_some() {
+            return false;
+        }
+        self.on_placed(child, remaining);
+        true
+    }
+
+    /// 当前所有在途子单。
+    pub(crate) fn children(&self) -> Vec<OrderId> {
+        self.child.iter().cloned().collect()
+    }
+
+    /// 成交数量达到目标即视为完成。
+    pub(crate) fn is_complete(&self, filled: Decimal) -> bool {
+        filled >= self.total_volume
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_snipe_buy_places_limit_above_target() -> Result<(), TradeError> {
+        let snipe = SnipeState::new(dec!(100), dec!(0.5), OrderDirection::Buy, dec!(10))?;
+        assert_eq!(snipe.check(dec!(100.2), dec!(0)), None);
+        assert_eq!(
+            snipe.check(dec!(99.8), dec!(0)),
+            Some(SnipeTrigger {
+                limit_price: dec!(100.5),
+                volume: dec!(10),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_snipe_sell_places_limit_below_target() -> Result<(), TradeError> {
+        let snipe = SnipeState::new(dec!(100), dec!(0.5), OrderDirection::Sell, dec!(10))?;
+        assert_eq!(snipe.check(dec!(99.9), dec!(0)), None);
+        assert_eq!(
+            snipe.check(dec!(101), dec!(0)),
+            Some(SnipeTrigger {
+                limit_price: dec!(99.5),
+                volume: dec!(10),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_snipe_triggers_once_and_tracks_partial_fills() -> Result<(), TradeError> {
+        let mut snipe = SnipeState::new(dec!(100), dec!(0), OrderDirection::Buy, dec!(10))?;
+        let child = OrderId("s-1".into());
+        snipe.on_placed(child.clone(), dec!(10));
+        assert_eq!(snipe.check(dec!(90), dec!(0)), None);
+
+        assert!(!snipe.on_fill(&child, dec!(4)));
+        assert_eq!(snipe.children(), vec![child.clone()]);
+        assert!(snipe.on_fill(&child, dec!(6)));
+        assert!(snipe.children().is_empty());
+        assert!(snipe.is_complete(dec!(10)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_snipe_rejects_negative_slippage() {
+        let res = SnipeState::new(dec!(100), dec!(-1), OrderDirection::Buy, dec!(10));
+        assert!(matches!(res, Err(TradeError::AlgoOrderError(_))));
+    }
+}
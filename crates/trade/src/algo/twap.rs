@@ -0,0 +1,234 @@
+use okane_core::trade::entity::{OrderDirection, OrderId};
+use okane_core::trade::port::TradeError;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// TWAP 子单的切片间隔 (毫秒)，与 1 分钟 K 线对齐。
+const SLICE_INTERVAL_MS: i64 = 60_000;
+
+/// # Summary
+/// 时间加权平均价格 (TWAP) 算法的执行状态。
+///
+/// # Logic
+/// 将 `total_volume` 按分钟均分为若干切片，第 k 个切片在 `start + k * 1min` 到期。
+/// 每次驱动时计算截至当前应累计下发的数量，与已下发数量的差额作为一笔子单下发，
+/// 因此行情中断期间错过的切片会在恢复后合并为一笔追单。
+/// 到达结束时间后撤销所有在途子单，并以剩余未成交数量做最后一次扫尾。
+///
+/// # Invariants
+/// - `slices >= 1`，`start_ms < end_ms`。
+/// - `submitted` 只增不减，且不超过 `total_volume` (扫尾单除外，其按未成交量计)。
+#[derive(Debug, Clone)]
+pub(crate) struct TwapState {
+    start_ms: i64,
+    end_ms: i64,
+    slices: i64,
+    total_volume: Decimal,
+    direction: OrderDirection,
+    /// 已下发子单的累计数量
+    submitted: Decimal,
+    /// 在途子单及其未成交数量
+    in_flight: HashMap<OrderId, Decimal>,
+}
+
+/// 一次 TWAP 驱动的决策结果。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TwapAction {
+    /// 当前无需下单
+    Wait,
+    /// 按计划下发一笔切片 (可能合并了错过的切片)
+    Slice(Decimal),
+    /// 已到结束时间：撤销在途子单后，以剩余数量扫尾
+    Sweep(Decimal),
+}
+
+impl TwapState {
+    /// # Logic
+    /// Build a TWAP schedule anchored at `start_ms`.
+    ///
+    /// # Arguments
+    /// * `duration_secs` - Execution window length, must be positive.
+    /// * `total_volume` - Volume to execute, must be positive.
+    /// * `direction` - Side of every child order, including the final sweep.
+    /// * `start_ms` - Schedule anchor taken from the service clock.
+    ///
+    /// # Returns
+    /// * `Err(TradeError::AlgoOrderError)` - If the window or volume is invalid.
+    pub(crate) fn new(
+        duration_secs: u64,
+        total_volume: Decimal,
+        direction: OrderDirection,
+        start_ms: i64,
+    ) -> Result<Self, TradeError> {
+        if duration_secs == 0 {
+            return Err(TradeError::AlgoOrderError(
+                "twap duration must be greater than zero".into(),
+            ));
+        }
+        if total_volume <= Decimal::ZERO {
+            return Err(TradeError::AlgoOrderError(
+                "twap volume must be greater than zero".into(),
+            ));
+        }
+
+        let duration_ms = i64::try_from(duration_secs)
+            .ok()
+            .and_then(|secs| secs.checked_mul(1000))
+            .ok_or_else(|| TradeError::AlgoOrderError("twap duration is too large".into()))?;
+        let end_ms = start_ms
+            .checked_add(duration_ms)
+            .ok_or_else(|| TradeError::AlgoOrderError("twap duration is too large".into()))?;
+        let slices = (duration_ms + SLICE_INTERVAL_MS - 1) / SLICE_INTERVAL_MS;
+
+        Ok(Self {
+            start_ms,
+            end_ms,
+            slices,
+            total_volume,
+            direction,
+            submitted: Decimal::ZERO,
+            in_flight: HashMap::new(),
+        })
+    }
+
+    pub(crate) fn direction(&self) -> OrderDirection {
+        self.direction
+    }
+
+    /// 截至 `now_ms` 按计划应累计下发的数量。
+    fn scheduled_volume(&self, now_ms: i64) -> Decimal {
+        if now_ms < self.start_ms {
+            return Decimal::ZERO;
+        }
+        let due = ((now_ms - self.start_ms) / SLICE_INTERVAL_MS + 1).min(self.slices);
+        if due >= self.slices {
+            return self.total_volume;
+        }
+        self.total_volume * Decimal::from(due) / Decimal::from(self.slices)
+    }
+
+    /// # Logic
+    /// Decide what to do at `now_ms` given the volume already filled by children.
+    pub(crate) fn next_action(&self, now_ms: i64, filled: Decimal) -> TwapAction {
+        if now_ms >= self.end_ms {
+            let remaining = self.total_volume - filled;
+            return if remaining > Decimal::ZERO {
+                TwapAction::Sweep(remaining)
+            } else {
+                TwapAction::Wait
+            };
+        }
+
+        let pending = self.scheduled_volume(now_ms) - self.submitted;
+        if pending > Decimal::ZERO {
+            TwapAction::Slice(pending)
+        } else {
+            TwapAction::Wait
+        }
+    }
+
+    /// 记录已下发的子单。
+    pub(crate) fn on_placed(&mut self, child: OrderId, volume: Decimal) {
+        self.submitted += volume;
+        self.in_flight.insert(child, volume);
+    }
+
+    /// 子单成交回报，返回子单是否已全部成交。
+    pub(crate) fn on_fill(&mut self, child: &OrderId, volume: Decimal) -> bool {
+        let Some(remaining) = self.in_flight.get_mut(child) else {
+            return true;
+        };
+        *remaining -= volume;
+        if *remaining > Decimal::ZERO {
+            return false;
+        }
+        self.in_flight.remove(child);
+        true
+    }
+
+    /// 取出全部在途子单，用于扫尾前撤单。
+    pub(crate) fn take_children(&mut self) -> Vec<OrderId> {
+        self.in_flight.drain().map(|(id, _)| id).collect()
+    }
+
+    /// 重启恢复：以已成交数量与在途子单的剩余数量重建下发进度。
+    pub(crate) fn restore(&mut self, filled: Decimal, children: Vec<(OrderId, Decimal)>) {
+        self.submitted = filled
+            + children
+                .iter()
+                .map(|(_, remaining)| *remaining)
+                .sum::<Decimal>();
+        self.in_flight = children.into_iter().collect();
+    }
+
+    /// 当前所有在途子单。
+    pub(crate) fn children(&self) -> Vec<OrderId> {
+        self.in_flight.keys().cloned().collect()
+    }
+
+    /// 成交数量达到目标即视为完成。
+    pub(crate) fn is_complete(&self, filled: Decimal) -> bool {
+        filled >= self.total_volume
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_twap_slices_evenly_per_minute() -> Result<(), TradeError> {
+        let twap = TwapState::new(300, dec!(100), OrderDirection::Buy, 0)?;
+        assert_eq!(twap.next_action(0, dec!(0)), TwapAction::Slice(dec!(20)));
+        assert_eq!(
+            twap.next_action(SLICE_INTERVAL_MS * 2, dec!(0)),
+            TwapAction::Slice(dec!(60))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_twap_waits_until_next_slice_is_due() -> Result<(), TradeError> {
+        let mut twap = TwapState::new(300, dec!(100), OrderDirection::Buy, 0)?;
+        twap.on_placed(OrderId("c1".into()), dec!(20));
+        assert_eq!(twap.next_action(30_000, dec!(20)), TwapAction::Wait);
+        assert_eq!(
+            twap.next_action(SLICE_INTERVAL_MS, dec!(20)),
+            TwapAction::Slice(dec!(20))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_twap_catches_up_missed_slices_in_one_child() -> Result<(), TradeError> {
+        let mut twap = TwapState::new(300, dec!(100), OrderDirection::Buy, 0)?;
+        twap.on_placed(OrderId("c1".into()), dec!(20));
+        assert_eq!(
+            twap.next_action(SLICE_INTERVAL_MS * 3, dec!(20)),
+            TwapAction::Slice(dec!(60))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_twap_sweeps_unfilled_volume_after_end() -> Result<(), TradeError> {
+        let mut twap = TwapState::new(120, dec!(10), OrderDirection::Buy, 0)?;
+        twap.on_placed(OrderId("c1".into()), dec!(10));
+        assert!(!twap.on_fill(&OrderId("c1".into()), dec!(7)));
+        assert_eq!(
+            twap.next_action(120_000, dec!(7)),
+            TwapAction::Sweep(dec!(3))
+        );
+        assert_eq!(twap.take_children(), vec![OrderId("c1".into())]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_twap_rejects_zero_duration() {
+        assert!(matches!(
+            TwapState::new(0, dec!(10), OrderDirection::Buy, 0),
+            Err(TradeError::AlgoOrderError(_))
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+use chrono::Timelike;
+use okane_core::market::entity::Candle;
+use okane_core::trade::entity::{OrderDirection, OrderId};
+use okane_core::trade::port::TradeError;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// 成交量分布的桶宽 (毫秒)，与 1 分钟 K 线对齐。
+const BUCKET_MS: i64 = 60_000;
+
+/// 一天的分钟数，日内分布按分钟折叠。
+const MINUTES_PER_DAY: i64 = 1440;
+
+/// # Summary
+/// 成交量加权平均价格 (VWAP) 算法的执行状态。
+///
+/// # Logic
+/// 1. 以历史 1 分钟 K 线按“日内分钟”聚合成交量，得到窗口内每分钟的累计目标占比；
+///    历史数据缺失时退化为均匀分布。
+/// 2. 每次驱动时按累计占比计算截至当前应下发的数量，与已下发数量之差为期望子单量。
+/// 3. 子单量同时受参与率约束：同一分钟内累计下发量不超过
+///    `max_participation * 该分钟已观测成交量`。
+/// 4. 窗口结束后不做扫尾，以免突破参与率上限，剩余数量视为未完成。
+///
+/// # Invariants
+/// - `cumulative` 单调不减，最后一个元素为 1。
+/// - `submitted <= total_volume`。
+#[derive(Debug, Clone)]
+pub(crate) struct VwapState {
+    start_ms: i64,
+    end_ms: i64,
+    total_volume: Decimal,
+    direction: OrderDirection,
+    max_participation: Decimal,
+    /// 窗口内第 i 分钟结束时应累计完成的占比
+    cumulative: Vec<Decimal>,
+    /// 已下发子单的累计数量
+    submitted: Decimal,
+    /// 在途子单及其未成交数量
+    in_flight: HashMap<OrderId, Decimal>,
+    /// 当前参与率统计所在的分钟桶
+    bucket: i64,
+    /// 当前分钟桶内已下发的数量
+    bucket_sent: Decimal,
+}
+
+/// 一次 VWAP 驱动的决策结果。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum VwapAction {
+    /// 窗口未开始、已跟上计划或参与率额度已用尽
+    Wait,
+    /// 下发一笔子单
+    Child(Decimal),
+    /// 窗口已结束
+    Expired,
+}
+
+impl VwapState {
+    /// # Logic
+    /// Validate the window and build the intraday volume profile from `history`.
+    ///
+    /// # Arguments
+    /// * `total_volume` - Volume to execute, must be positive.
+    /// * `direction` - Side of every child order.
+    /// * `start_ms` / `end_ms` - Execution window, at most one day long.
+    /// * `max_participation` - Participation cap in `(0, 1]`.
+    /// * `history` - Historical 1-minute candles used to shape the profile.
+    ///
+    /// # Returns
+    /// * `Err(TradeError::AlgoOrderError)` - If any parameter is invalid.
+    pub(crate) fn new(
+        total_volume: Decimal,
+        direction: OrderDirection,
+        start_ms: i64,
+        end_ms: i64,
+        max_participation: Decimal,
+        history: &[Candle],
+    ) -> Result<Self, TradeError> {
+        if total_volume <= Decimal::ZERO {
+            return Err(TradeError::AlgoOrderError(
+                "vwap volume must be greater than zero".into(),
+            ));
+        }
+        if end_ms <= start_ms {
+            return Err(TradeError::AlgoOrderError(
+                "vwap window must satisfy start_time < end_time".into(),
+            ));
+        }
+        if max_participation <= Decimal::ZERO || max_participation > Decimal::ONE {
+            return Err(TradeError::AlgoOrderError(
+                "vwap max_participation must be within (0, 1]".into(),
+            ));
+        }
+        let buckets = (end_ms - start_ms + BUCKET_MS - 1) / BUCKET_MS;
+        if buckets > MINUTES_PER_DAY {
+            return Err(TradeError::AlgoOrderError(
+                "vwap window must not exceed one day".into(),
+            ));
+        }
+
+        Ok(Self {
+            start_ms,
+            end_ms,
+            total_volume,
+            direction,
+            max_participation,
+            cumulative: build_profile(history, start_ms, buckets),
+            submitted: Decimal::ZERO,
+            in_flight: HashMap::new(),
+            bucket: -1,
+            bucket_sent: Decimal::ZERO,
+        })
+    }
+
+    pub(crate) fn direction(&self) -> OrderDirection {
+        self.direction
+    }
+
+    /// # Logic
+    /// Size the next child at `now_ms` from the profile schedule, capped by the
+    /// participation budget left in the current minute of `candle`.
+    pub(crate) fn next_action(&mut self, now_ms: i64, candle: &Candle) -> VwapAction {
+        if now_ms >= self.end_ms {
+            return VwapAction::Expired;
+        }
+        if now_ms < self.start_ms {
+            return VwapAction::Wait;
+        }
+
+        let bucket = candle.time.timestamp_millis().div_euclid(BUCKET_MS);
+        if bucket != self.bucket {
+            self.bucket = bucket;
+            self.bucket_sent = Decimal::ZERO;
+        }
+
+        let offset = usize::try_from((now_ms - self.start_ms) / BUCKET_MS).unwrap_or(usize::MAX);
+        let fraction = self
+            .cumulative
+            .get(offset)
+            .or(self.cumulative.last())
+            .copied()
+            .unwrap_or(Decimal::ONE);
+        let scheduled = self.total_volume * fraction;
+        let budget = self.max_participation * candle.volume - self.bucket_sent;
+
+        let volume = (scheduled - self.submitted).min(budget);
+        if volume > Decimal::ZERO {
+            VwapAction::Child(volume)
+        } else {
+            VwapAction::Wait
+        }
+    }
+
+    /// 记录已下发的子单，并计入当前分钟的参与量。
+    pub(crate) fn on_placed(&mut self, child: OrderId, volume: Decimal) {
+        self.submitted += volume;
+        self.bucket_sent += volume;
+        self.in_flight.insert(child, volume);
+    }
+
+    /// 子单成交回报，返回子单是否已全部成交。
+    pub(crate) fn on_fill(&mut self, child: &OrderId, volume: Decimal) -> bool {
+        let Some(remaining) = self.in_flight.get_mut(child) else {
+            return true;
+        };
+        *remaining -= volume;
+        if *remaining > Decimal::ZERO {
+            return false;
+        }
+        self.in_flight.remove(child);
+        true
+    }
+
+    /// 重启恢复：以已成交数量与在途子单的剩余数量重建下发进度。
+    pub(crate) fn restore(&mut self, filled: Decimal, children: Vec<(OrderId, Decimal)>) {
+        self.submitted = filled
+            + children
+                .iter()
+                .map(|(_, remaining)| *remaining)
+                .sum::<Decimal>();
+        self.in_flight = children.into_iter().collect();
+    }
+
+    /// 当前所有在途子单。
+    pub(crate) fn children(&self) -> Vec<OrderId> {
+        self.in_flight.keys().cloned().collect()
+    }
+
+    /// 成交数量达到目标即视为完成。
+    pub(crate) fn is_complete(&self, filled: Decimal) -> bool {
+        filled >= self.total_volume
+    }
+}
+
+/// # Logic
+/// 将历史成交量按日内分钟折叠到窗口的各个分钟桶，再归一化为累计占比。
+/// 无可用成交量时返回均匀分布。
+fn build_profile(history: &[Candle], start_ms: i64, buckets: i64) -> Vec<Decimal> {
+    let len = usize::try_from(buckets).unwrap_or(1).max(1);
+    let start_minute = minute_of_day_ms(start_ms);
+
+    let mut volumes = vec![Decimal::ZERO; len];
+    for candle in history {
+        let minute = i64::from(candle.time.hour()) * 60 + i64::from(candle.time.minute());
+        let offset = (minute - start_minute).rem_euclid(MINUTES_PER_DAY);
+        if let Some(slot) = usize::try_from(offset)
+            .ok()
+            .and_then(|i| volumes.get_mut(i))
+        {
+            *slot += candle.volume;
+        }
+    }
+
+    let total: Decimal = volumes.iter().sum();
+    if total <= Decimal::ZERO {
+        volumes = vec![Decimal::ONE; len];
+    }
+    let total: Decimal = volumes.iter().sum();
+
+    let mut acc = Decimal::ZERO;
+    let mut cumulative: Vec<Decimal> = volumes
+        .iter()
+        .map(|v| {
+            acc += *v;
+            acc / total
+        })
+        .collect();
+    // 消除除法舍入误差，保证窗口末尾目标恰为全部数量
+    if let Some(last) = cumulative.last_mut() {
+        *last = Decimal::ONE;
+    }
+    cumulative
+}
+
+fn minute_of_day_ms(ms: i64) -> i64 {
+    ms.div_euclid(BUCKET_MS).rem_euclid(MINUTES_PER_DAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use rust_decimal_macros::dec;
+
+    fn candle_at(ms: i64, volume: Decimal) -> Candle {
+        Candle {
+            time: DateTime::<Utc>::from_timestamp_millis(ms).unwrap_or_default(),
+            open: dec!(100),
+            high: dec!(100),
+            low: dec!(100),
+            close: dec!(100),
+            adj_close: None,
+            volume,
+            is_final: true,
+        }
+    }
+
+    #[test]
+    fn test_vwap_profile_follows_historical_volume() {
+        // 前一日同一时段：第 1 分钟 300，第 2 分钟 100
+        let day = MINUTES_PER_DAY * BUCKET_MS;
+        let history = vec![
+            candle_at(-day, dec!(300)),
+            candle_at(-day + BUCKET_MS, dec!(100)),
+        ];
+        let profile = build_profile(&history, 0, 2);
+        assert_eq!(profile, vec![dec!(0.75), dec!(1)]);
+    }
+
+    #[test]
+    fn test_vwap_profile_is_uniform_without_history() {
+        let profile = build_profile(&[], 0, 4);
+        assert_eq!(profile, vec![dec!(0.25), dec!(0.5), dec!(0.75), dec!(1)]);
+    }
+
+    #[test]
+    fn test_vwap_caps_child_by_participation() -> Result<(), TradeError> {
+        let mut vwap = VwapState::new(
+            dec!(100),
+            OrderDirection::Buy,
+            0,
+            2 * BUCKET_MS,
+            dec!(0.1),
+            &[],
+        )?;
+        // 计划 50，但本分钟成交量 200 仅允许 20
+        assert_eq!(
+            vwap.next_action(0, &candle_at(0, dec!(200))),
+            VwapAction::Child(dec!(20))
+        );
+        vwap.on_placed(OrderId("c1".into()), dec!(20));
+        // 同一分钟滚动成交量增至 300，额度只剩 10
+        assert_eq!(
+            vwap.next_action(30_000, &candle_at(0, dec!(300))),
+            VwapAction::Child(dec!(10))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_vwap_expires_at_window_end() -> Result<(), TradeError> {
+        let mut vwap =
+            VwapState::new(dec!(100), OrderDirection::Buy, 0, BUCKET_MS, dec!(0.5), &[])?;
+        assert_eq!(
+            vwap.next_action(BUCKET_MS, &candle_at(BUCKET_MS, dec!(1000))),
+            VwapAction::Expired
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_vwap_rejects_invalid_participation() {
+        let res = VwapState::new(dec!(100), OrderDirection::Buy, 0, BUCKET_MS, dec!(1.5), &[]);
+        assert!(matches!(res, Err(TradeError::AlgoOrderError(_))));
+    }
+}
@@ -0,0 +1,490 @@
+use okane_core::config::FillModelConfig;
+use okane_core::market::entity::Candle;
+pub use okane_core::market::entity::OhlcPath;
+use okane_core::trade::entity::{Order, OrderDirection, TriggerKind};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 参与率额度的分桶宽度 (毫秒)。
+const BUCKET_MS: i64 = 60_000;
+
+/// 一笔 K 线内成交的价格与数量。
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarFill {
+    pub price: Decimal,
+    pub volume: Decimal,
+}
+
+/// # Summary
+/// 可插拔的 K 线成交模型，决定一根 K 线内哪些挂单成交、以什么价格、成交多少。
+pub trait FillModel: Send + Sync {
+    /// # Logic
+    /// Evaluate the resting `orders` of `symbol` against one bar.
+    ///
+    /// # Arguments
+    /// * `symbol` - Symbol the bar belongs to.
+    /// * `orders` - Active orders of the symbol; already-filled volume is respected and
+    ///   trigger state (fired flag, trailing extreme) is updated in place.
+    /// * `candle` - The bar being matched. Updates of an unfinished bar share its time.
+    ///
+    /// # Returns
+    /// `(index into orders, fill)` pairs in the order the fills happen inside the bar.
+    fn fill_bar(
+        &self,
+        symbol: &str,
+        orders: &mut [Order],
+        candle: &Candle,
+    ) -> Vec<(usize, BarFill)>;
+}
+
+/// # Summary
+/// 默认的 K 线成交模型。
+///
+/// # Logic
+/// 1. 跳空：开盘价已越过限价 (买单开盘不高于限价、卖单开盘不低于限价) 时按开盘价成交；
+///    市价单同样按开盘价成交。
+/// 2. 否则沿 `path` 移动，价格首次触及限价时按限价成交；触达先后决定成交顺序。
+/// 3. 带触发条件的订单先沿路径判定触发 (跳空越过触发价按开盘价触发)，
+///    触发后自触发点起按市价或限价继续判定成交；跟踪止损沿路径更新最有利价格。
+/// 4. 设置 `max_participation` 时，同一标的同一根 K 线内的累计成交量不超过
+///    `max_participation * candle.volume`，超出部分保留为部分成交，留待后续 K 线。
+///
+/// # Invariants
+/// - `max_participation` 若存在，取值在 `(0, 1]`。
+/// - `consumed` 只记录每个标的最近一个分钟桶的已用额度，分钟变化即重置；
+///   实时撮合按最新价构造的单点 K 线因此与所属 1 分钟 K 线共享额度。
+#[derive(Debug, Default)]
+pub struct BarFillModel {
+    path: OhlcPath,
+    max_participation: Option<Decimal>,
+    /// 标的 -> (分钟桶, 该分钟桶内已成交数量)
+    consumed: Mutex<HashMap<String, (i64, Decimal)>>,
+}
+
+impl BarFillModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按配置构造：设置价格路径，配置了参与率上限时施加额度。
+    pub fn from_config(config: &FillModelConfig) -> Self {
+        let model = Self::new().with_path(config.ohlc_path);
+        match config.max_participation {
+            Some(max_participation) => model.with_max_participation(max_participation),
+            None => model,
+        }
+    }
+
+    /// 设置 K 线内部价格路径假设。
+    pub fn with_path(mut self, path: OhlcPath) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// # Logic
+    /// Cap the volume filled per symbol and bar to a share of `Candle::volume`.
+    ///
+    /// # Arguments
+    /// * `max_participation` - Participation rate, values outside `(0, 1]` are clamped into it.
+    ///
+    /// # Returns
+    /// The model with the cap applied.
+    pub fn with_max_participation(mut self, max_participation: Decimal) -> Self {
+        self.max_participation = Some(max_participation.clamp(Decimal::ZERO, Decimal::ONE));
+        self
+    }
+
+    /// # Logic
+    /// Walk the bar path until a pending trigger of `order` fires, updating the
+    /// trailing extreme on the way and marking the trigger as fired.
+    ///
+    /// # Returns
+    /// `(segment, price)` where the trigger fired, or the open if there is no pending trigger;
+    /// `None` if the trigger is still pending after the whole bar.
+    fn trigger(order: &mut Order, points: &[Decimal; 4]) -> Option<(usize, Decimal)> {
+        let direction = order.direction;
+        let Some(trigger) = order.trigger.as_mut().filter(|t| !t.triggered) else {
+            return Some((0, points[0]));
+        };
+        // 买单向上穿越、卖单向下穿越触发价即触发
+        let fires = |price: Decimal, level: Decimal| match direction {
+            OrderDirection::Buy => price >= level,
+            OrderDirection::Sell => price <= level,
+        };
+        let favourable = |a: Decimal, b: Decimal| match direction {
+            OrderDirection::Buy => a.min(b),
+            OrderDirection::Sell => a.max(b),
+        };
+
+        let mut extreme = trigger.extreme.unwrap_or(points[0]);
+        let mut fired = None;
+        for (i, price) in points.iter().copied().enumerate() {
+            let level = trigger.level_from(direction, extreme);
+            if fires(price, level) {
+                // 开盘即越过触发价视为跳空，按开盘价触发
+                fired = Some((i, if i == 0 { price } else { level }));
+                break;
+            }
+            extreme = favourable(extreme, price);
+        }
+        if matches!(trigger.kind, TriggerKind::TrailingStop { .. }) {
+            trigger.extreme = Some(extreme);
+        }
+        trigger.triggered = fired.is_some();
+        fired
+    }
+
+    /// # Logic
+    /// Find where along the bar path `order` becomes marketable, firing its trigger first.
+    ///
+    /// # Returns
+    /// `(segment, distance, price)`: segment 0 means the open already crossed the limit,
+    /// otherwise the path segment ending at `points[segment]`; `distance` orders touches
+    /// within the same segment.
+    fn touch(order: &mut Order, points: &[Decimal; 4]) -> Option<(usize, Decimal, Decimal)> {
+        let (start, start_price) = Self::trigger(order, points)?;
+        let distance = |seg: usize, price: Decimal| match seg.checked_sub(1) {
+            Some(prev) => (price - points[prev]).abs(),
+            None => Decimal::ZERO,
+        };
+        let Some(limit) = order.price else {
+            return Some((start, distance(start, start_price), start_price));
+        };
+        let crossed = |price: Decimal| match order.direction {
+            OrderDirection::Buy => price <= limit,
+            OrderDirection::Sell => price >= limit,
+        };
+        if crossed(start_price) {
+            return Some((start, distance(start, start_price), start_price));
+        }
+        (start.max(1)..points.len())
+            .find(|&i| crossed(points[i]))
+            .map(|i| (i, distance(i, limit), limit))
+    }
+
+    /// 取出当前 K 线剩余的参与额度；未设置上限时返回 `None`。
+    fn budget(&self, symbol: &str, candle: &Candle) -> Option<(i64, Decimal)> {
+        let rate = self.max_participation?;
+        let bucket = candle.time.timestamp_millis().div_euclid(BUCKET_MS);
+        let used = self
+            .consumed
+            .lock()
+            .ok()
+            .and_then(|consumed| consumed.get(symbol).copied())
+            .filter(|(at, _)| *at == bucket)
+            .map_or(Decimal::ZERO, |(_, used)| used);
+        Some((bucket, rate * candle.volume - used))
+    }
+}
+
+impl FillModel for BarFillModel {
+    fn fill_bar(
+        &self,
+        symbol: &str,
+        orders: &mut [Order],
+        candle: &Candle,
+    ) -> Vec<(usize, BarFill)> {
+        let points = self.path.points(candle);
+        let mut touched: Vec<_> = orders
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, order)| order.volume > order.filled_volume)
+            .filter_map(|(idx, order)| {
+                Self::touch(order, &points).map(|(seg, dist, price)| (seg, dist, idx, price))
+            })
+            .collect();
+        touched.sort_by_key(|touch| (touch.0, touch.1));
+
+        let mut budget = self.budget(symbol, candle);
+        let mut fills = Vec::with_capacity(touched.len());
+        for (_, _, idx, price) in touched {
+            let Some(order) = orders.get(idx) else {
+                continue;
+            };
+            let mut volume = order.volume - order.filled_volume;
+            if let Some((_, left)) = budget.as_mut() {
+                volume = volume.min(*left);
+                *left -= volume;
+            }
+            if volume <= Decimal::ZERO {
+                break;
+            }
+            fills.push((idx, BarFill { price, volume }));
+        }
+
+        if let (Some(rate), Some((bucket, left))) = (self.max_participation, budget)
+            && let Ok(mut consumed) = self.consumed.lock()
+        {
+            consumed.insert(symbol.to_string(), (bucket, rate * candle.volume - left));
+        }
+        fills
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use okane_core::trade::entity::{AccountId, OrderId, OrderTrigger, TrailOffset};
+    use rust_decimal_macros::dec;
+
+    fn bar(open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> Candle {
+        Candle {
+            time: Utc::now(),
+            open,
+            high,
+            low,
+            close,
+            adj_close: None,
+            volume: dec!(1000),
+            is_final: true,
+        }
+    }
+
+    fn limit(id: &str, direction: OrderDirection, price: Decimal, volume: Decimal) -> Order {
+        Order::new(
+            OrderId(id.into()),
+            AccountId("acc".into()),
+            "AAPL".into(),
+            direction,
+            Some(price),
+            volume,
+            0,
+        )
+    }
+
+    fn stop_market(id: &str, direction: OrderDirection, trigger: OrderTrigger) -> Order {
+        Order::new(
+            OrderId(id.into()),
+            AccountId("acc".into()),
+            "AAPL".into(),
+            direction,
+            None,
+            dec!(1),
+            0,
+        )
+        .with_trigger(trigger)
+    }
+
+    #[test]
+    fn test_gap_through_limit_fills_at_open() {
+        let model = BarFillModel::new();
+        let mut orders = [
+            limit("buy", OrderDirection::Buy, dec!(100), dec!(10)),
+            limit("sell", OrderDirection::Sell, dec!(90), dec!(10)),
+        ];
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders[..1],
+            &bar(dec!(95), dec!(97), dec!(94), dec!(96)),
+        );
+        assert_eq!(
+            fills,
+            vec![(
+                0,
+                BarFill {
+                    price: dec!(95),
+                    volume: dec!(10)
+                }
+            )]
+        );
+
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders[1..],
+            &bar(dec!(95), dec!(97), dec!(94), dec!(96)),
+        );
+        assert_eq!(
+            fills,
+            vec![(
+                0,
+                BarFill {
+                    price: dec!(95),
+                    volume: dec!(10)
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_limit_touched_intrabar_fills_at_limit() {
+        let model = BarFillModel::new();
+        let mut orders = vec![limit("buy", OrderDirection::Buy, dec!(98), dec!(10))];
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders,
+            &bar(dec!(100), dec!(101), dec!(97), dec!(99)),
+        );
+        assert_eq!(
+            fills,
+            vec![(
+                0,
+                BarFill {
+                    price: dec!(98),
+                    volume: dec!(10)
+                }
+            )]
+        );
+
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders,
+            &bar(dec!(100), dec!(101), dec!(99), dec!(99)),
+        );
+        assert!(fills.is_empty());
+    }
+
+    #[test]
+    fn test_path_decides_which_side_fills_first() {
+        let mut orders = vec![
+            limit("buy", OrderDirection::Buy, dec!(95), dec!(1)),
+            limit("sell", OrderDirection::Sell, dec!(105), dec!(1)),
+        ];
+        let candle = bar(dec!(100), dec!(106), dec!(94), dec!(100));
+
+        let high_first = BarFillModel::new().with_path(OhlcPath::OpenHighLowClose);
+        let ids: Vec<usize> = high_first
+            .fill_bar("AAPL", &mut orders, &candle)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(ids, vec![1, 0]);
+
+        let low_first = BarFillModel::new().with_path(OhlcPath::OpenLowHighClose);
+        let ids: Vec<usize> = low_first
+            .fill_bar("AAPL", &mut orders, &candle)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_participation_cap_is_shared_within_a_bar() {
+        let model = BarFillModel::new().with_max_participation(dec!(0.01));
+        let mut orders = vec![
+            limit("a", OrderDirection::Buy, dec!(100), dec!(8)),
+            limit("b", OrderDirection::Buy, dec!(99), dec!(8)),
+        ];
+        let candle = bar(dec!(100), dec!(100), dec!(98), dec!(99));
+
+        // 1000 * 1% = 10：a 先触达成交 8，b 只剩 2
+        let fills = model.fill_bar("AAPL", &mut orders, &candle);
+        assert_eq!(
+            fills,
+            vec![
+                (
+                    0,
+                    BarFill {
+                        price: dec!(100),
+                        volume: dec!(8)
+                    }
+                ),
+                (
+                    1,
+                    BarFill {
+                        price: dec!(99),
+                        volume: dec!(2)
+                    }
+                ),
+            ]
+        );
+        // 同一根 K 线的后续更新不再有额度
+        assert!(model.fill_bar("AAPL", &mut orders, &candle).is_empty());
+    }
+
+    #[test]
+    fn test_stop_limit_triggers_then_waits_for_limit() {
+        let model = BarFillModel::new().with_path(OhlcPath::OpenLowHighClose);
+        // 买入止损 105，限价 104：上穿 105 触发后需回落至 104 才成交
+        let mut orders = [limit("stop", OrderDirection::Buy, dec!(104), dec!(1))
+            .with_trigger(OrderTrigger::stop(dec!(105)))];
+
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders,
+            &bar(dec!(100), dec!(106), dec!(99), dec!(105)),
+        );
+        assert!(fills.is_empty());
+        assert!(!orders[0].is_awaiting_trigger());
+
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders,
+            &bar(dec!(105), dec!(105), dec!(103), dec!(104)),
+        );
+        assert_eq!(
+            fills,
+            vec![(
+                0,
+                BarFill {
+                    price: dec!(104),
+                    volume: dec!(1)
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_trailing_stop_follows_high_and_fires_on_pullback() {
+        let model = BarFillModel::new();
+        let mut trigger = OrderTrigger::trailing(TrailOffset::Percent(dec!(10)));
+        trigger.arm(OrderDirection::Sell, dec!(100));
+        let mut orders = [stop_market("trail", OrderDirection::Sell, trigger)];
+
+        // 最高涨到 120，触发价随之上移到 108，最低 109 未触发
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders,
+            &bar(dec!(100), dec!(120), dec!(109), dec!(110)),
+        );
+        assert!(fills.is_empty());
+        assert_eq!(
+            orders[0].trigger.as_ref().and_then(|t| t.extreme),
+            Some(dec!(120))
+        );
+
+        // 下一根跌破 108，按触发价成交
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders,
+            &bar(dec!(110), dec!(111), dec!(100), dec!(101)),
+        );
+        assert_eq!(
+            fills,
+            vec![(
+                0,
+                BarFill {
+                    price: dec!(108),
+                    volume: dec!(1)
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_stop_gapped_through_fills_at_open() {
+        let model = BarFillModel::new();
+        let mut orders = [stop_market(
+            "stop",
+            OrderDirection::Sell,
+            OrderTrigger::stop(dec!(95)),
+        )];
+        let fills = model.fill_bar(
+            "AAPL",
+            &mut orders,
+            &bar(dec!(90), dec!(92), dec!(89), dec!(91)),
+        );
+        assert_eq!(
+            fills,
+            vec![(
+                0,
+                BarFill {
+                    price: dec!(90),
+                    volume: dec!(1)
+                }
+            )]
+        );
+    }
+}
@@ -0,0 +1,753 @@
+use futures::StreamExt;
+use okane_core::common::time::TimeProvider;
+use okane_core::market::port::Market;
+use okane_core::store::port::SystemStore;
+use okane_core::trade::cost::CostModel;
+use okane_core::trade::entity::{
+    AccountId, BrokerOrderRecord, ExecutionReport, Order, OrderDirection, OrderId, OrderStatus,
+    Trade,
+};
+use okane_core::trade::fx;
+use okane_core::trade::margin::MarginModel;
+use okane_core::trade::port::{
+    AccountPort, BrokerOrderStore, BrokerPort, OrderHistoryPort, TradeError,
+};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 回报流断开后重新订阅前的默认等待时间
+const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// 暂存的未找到订单映射的回报上限，超出时丢弃最早的
+const MAX_UNMATCHED_REPORTS: usize = 1024;
+
+fn is_active_order_status(status: OrderStatus) -> bool {
+    matches!(
+        status,
+        OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartialFilled
+    )
+}
+
+/// # Summary
+/// 逻辑交易账号后端类型 (`AccountProfile.account_type`) 到券商网关实现的注册表。
+///
+/// # Invariants
+/// - `local` 由本地撮合处理，`RoutedTradePort` 不会将其路由到此处注册的网关。
+#[derive(Clone, Default)]
+pub struct BrokerRegistry {
+    brokers: HashMap<String, Arc<dyn BrokerPort>>,
+}
+
+impl BrokerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记一个后端类型对应的网关，同名登记会覆盖之前的网关。
+    pub fn with_broker(mut self, account_type: &str, broker: Arc<dyn BrokerPort>) -> Self {
+        self.brokers.insert(account_type.to_string(), broker);
+        self
+    }
+
+    pub fn get(&self, account_type: &str) -> Option<Arc<dyn BrokerPort>> {
+        self.brokers.get(account_type).cloned()
+    }
+
+    /// 已登记的全部后端类型。
+    pub fn account_types(&self) -> Vec<String> {
+        let mut types: Vec<String> = self.brokers.keys().cloned().collect();
+        types.sort();
+        types
+    }
+}
+
+/// # Summary
+/// 券商网关服务：把委托外发至注册表中的网关，并消费执行回报更新订单状态、经 `AccountPort` 结算成交。
+///
+/// # Invariants
+/// - 外发订单以账户基础货币结算，只支持现金账户规则：买单按限价 (市价单按最新价加账号成本模型的滑点)
+///   加账号成本模型的预估费用全额冻结资金，随成交与终结逐步解冻；成交成本超出冻结时先补冻结再结算。
+/// - 保证金账户与多币种账户中以非基础货币挂牌的标的不外发，提交时即被拒绝。
+/// - 回报按累计成交与已结算累计值的差额结算，重复、乱序或对账补发的回报不会重复入账。
+/// - 订单映射在外发前保存，外发期间不持有结算锁；券商返回订单号前到达的回报暂存，
+///   映射更新后补结算。
+/// - 外发中的订单不参与对账，对账只会拒绝外发失败或在本进程启动前中断的 `Pending` 订单。
+pub struct BrokerGateway {
+    registry: BrokerRegistry,
+    account_port: Arc<dyn AccountPort>,
+    store: Arc<dyn BrokerOrderStore>,
+    market: Arc<dyn Market>,
+    time_provider: Arc<dyn TimeProvider>,
+    /// 可选的订单与成交历史仓储
+    order_history: Option<Arc<dyn OrderHistoryPort>>,
+    /// 逻辑交易账号档案来源，按账号配置解析成本模型、保证金模型与基础货币；
+    /// 未配置时外发订单均按无费用、无滑点的单一货币现金账户处理
+    system_store: Option<Arc<dyn SystemStore>>,
+    /// 回报流断开后重新订阅前的等待时间
+    reconnect_delay: Duration,
+    /// 串行化回报结算；内含尚未找到订单映射的回报 `(account_type, report)`
+    settle_lock: tokio::sync::Mutex<VecDeque<(String, ExecutionReport)>>,
+    /// 已保存映射、外发尚未返回的订单
+    in_flight: Mutex<HashSet<OrderId>>,
+    /// 各后端类型订阅回报并完成对账的次数
+    connections: tokio::sync::watch::Sender<HashMap<String, u64>>,
+}
+
+/// 外发期间持有的在途标记，外发结果落盘后 (或提前返回时) 随析构移除
+struct InFlightGuard<'a> {
+    in_flight: &'a Mutex<HashSet<OrderId>>,
+    order_id: OrderId,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        match self.in_flight.lock() {
+            Ok(mut in_flight) => {
+                in_flight.remove(&self.order_id);
+            }
+            Err(e) => tracing::error!("Broker gateway in-flight lock poisoned: {}", e),
+        }
+    }
+}
+
+impl BrokerGateway {
+    pub fn new(
+        registry: BrokerRegistry,
+        account_port: Arc<dyn AccountPort>,
+        store: Arc<dyn BrokerOrderStore>,
+        market: Arc<dyn Market>,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            registry,
+            account_port,
+            store,
+            market,
+            time_provider,
+            order_history: None,
+            system_store: None,
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+            settle_lock: tokio::sync::Mutex::new(VecDeque::new()),
+            in_flight: Mutex::new(HashSet::new()),
+            connections: tokio::sync::watch::Sender::new(HashMap::new()),
+        }
+    }
+
+    /// 关联订单与成交历史仓储，使外发订单同样出现在交易流水中。
+    pub fn with_order_history(mut self, order_history: Arc<dyn OrderHistoryPort>) -> Self {
+        self.order_history = Some(order_history);
+        self
+    }
+
+    /// 设置回报流断开后重新订阅前的等待时间。
+    pub fn with_reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// 关联逻辑交易账号档案，按账号配置的成本模型冻结资金并拒绝不支持的账户类型。
+    pub fn with_account_configs(mut self, system_store: Arc<dyn SystemStore>) -> Self {
+        self.system_store = Some(system_store);
+        self
+    }
+
+    /// 该后端类型是否已登记网关。
+    pub fn supports(&self, account_type: &str) -> bool {
+        self.registry.get(account_type).is_some()
+    }
+
+    /// # Logic
+    /// 等待 `start` 为该后端类型订阅回报流并完成首次对账。
+    pub async fn wait_connected(&self, account_type: &str) -> Result<(), TradeError> {
+        self.connections
+            .subscribe()
+            .wait_for(|connections| connections.contains_key(account_type))
+            .await
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn broker(&self, account_type: &str) -> Result<Arc<dyn BrokerPort>, TradeError> {
+        self.registry.get(account_type).ok_or_else(|| {
+            TradeError::BrokerIntegrationError(format!(
+                "no broker gateway registered for account type {}",
+                account_type
+            ))
+        })
+    }
+
+    fn lock_in_flight(&self) -> Result<std::sync::MutexGuard<'_, HashSet<OrderId>>, TradeError> {
+        self.in_flight
+            .lock()
+            .map_err(|e| TradeError::InternalError(format!("in-flight lock poisoned: {}", e)))
+    }
+
+    /// 标记订单外发中；重复外发同一订单时返回 `InvalidOrder`。
+    fn mark_in_flight(&self, order_id: &OrderId) -> Result<InFlightGuard<'_>, TradeError> {
+        if !self.lock_in_flight()?.insert(order_id.clone()) {
+            return Err(TradeError::InvalidOrder(format!(
+                "order {} is already being sent",
+                order_id.0
+            )));
+        }
+        Ok(InFlightGuard {
+            in_flight: &self.in_flight,
+            order_id: order_id.clone(),
+        })
+    }
+
+    fn is_in_flight(&self, order_id: &OrderId) -> Result<bool, TradeError> {
+        Ok(self.lock_in_flight()?.contains(order_id))
+    }
+
+    fn now_ms(&self) -> Result<i64, TradeError> {
+        Ok(self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .timestamp_millis())
+    }
+
+    async fn latest_price(&self, symbol: &str) -> Result<Decimal, TradeError> {
+        let stock = self.market.get_stock(symbol).await.map_err(|e| {
+            TradeError::BrokerIntegrationError(format!("Failed to get market data: {}", e))
+        })?;
+        stock
+            .current_price()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .ok_or_else(|| {
+                TradeError::InternalError(format!("No latest price available for stock {}", symbol))
+            })
+    }
+
+    /// 将订单的当前状态写入订单历史；失败只记录日志不影响调用方。
+    async fn record_order_history(&self, order: &Order, timestamp: i64) {
+        if let Some(history) = &self.order_history
+            && let Err(e) = history.record_order(order, timestamp).await
+        {
+            tracing::error!("Failed to record history of order {}: {}", order.id.0, e);
+        }
+    }
+
+    /// # Logic
+    /// 按账号配置解析外发委托的成本模型：外发订单只按现金账户规则以基础货币结算，
+    /// 保证金账户与以非基础货币挂牌的标的被拒绝；未关联账号档案或账号无档案时不计费用与滑点。
+    ///
+    /// # Returns
+    /// * `Err(TradeError::InvalidOrder)` - If the account is a margin account.
+    /// * `Err(TradeError::InvalidCurrency)` - If the symbol is not listed in the account base currency.
+    async fn cost_model_for(&self, order: &Order) -> Result<CostModel, TradeError> {
+        let Some(system_store) = &self.system_store else {
+            return Ok(CostModel::default());
+        };
+        let Some(profile) = system_store
+            .get_account_profile(&order.account_id.0)
+            .await
+            .map_err(|e| {
+                TradeError::InternalError(format!("account profile lookup failed: {}", e))
+            })?
+        else {
+            return Ok(CostModel::default());
+        };
+        if MarginModel::from_account_config(&profile.config)?.is_margin() {
+            return Err(TradeError::InvalidOrder(
+                "broker gateways do not support margin accounts".to_string(),
+            ));
+        }
+        let listing = fx::listing_currency(&order.symbol);
+        if let Some(base) = fx::base_currency_from_account_config(&profile.config)?
+            && base != listing
+        {
+            return Err(TradeError::InvalidCurrency(format!(
+                "broker gateways settle in the base currency {}, but {} is listed in {}",
+                base, order.symbol, listing
+            )));
+        }
+        CostModel::from_account_config(&profile.config)
+    }
+
+    /// 回滚提交时冻结的资金；失败只记录日志，调用方返回原始错误。
+    async fn release_reservation(&self, order: &Order, reserved_price: Option<Decimal>) {
+        if let Some(price) = reserved_price
+            && let Err(e) = self
+                .account_port
+                .unfreeze_funds(&order.account_id, None, price * order.volume)
+                .await
+        {
+            tracing::error!(
+                "Failed to release funds reserved for order {}: {}",
+                order.id.0,
+                e
+            );
+        }
+    }
+
+    /// # Logic
+    /// 1. 校验委托：数量为正，不支持触发单与订单组；按账号配置解析成本模型 (拒绝保证金与非基础货币结算)。
+    /// 2. 买单按限价 (市价单按最新价加滑点) 加预估费用冻结资金。
+    /// 3. 标记订单外发中，以 `Pending` 状态保存订单映射，券商订单号暂记为订单 ID；
+    ///    保存失败时退回冻结资金。
+    /// 4. 外发至网关 (不持有结算锁)；失败时以 `Rejected` 终结订单并退回冻结资金。
+    /// 5. 记录券商订单号，补结算外发期间暂存的该订单回报；外发结果落盘后才移除在途标记。
+    ///    订单已被券商受理，此后的本地失败只记录日志，映射留待对账同步。
+    ///
+    /// # Returns
+    /// * `Err(TradeError::BrokerIntegrationError)` - If no gateway is registered or the gateway rejects the order.
+    /// * `Err(TradeError::InsufficientFunds)` - If cash is insufficient for a buy order.
+    pub async fn submit_order(
+        &self,
+        account_type: &str,
+        mut order: Order,
+    ) -> Result<OrderId, TradeError> {
+        if order.volume <= Decimal::ZERO {
+            return Err(TradeError::InvalidOrder(
+                "order volume must be positive".to_string(),
+            ));
+        }
+        if order.trigger.is_some() || order.group_id.is_some() {
+            return Err(TradeError::InvalidOrder(
+                "broker gateways do not support trigger or group orders".to_string(),
+            ));
+        }
+        let broker = self.broker(account_type)?;
+        let cost_model = self.cost_model_for(&order).await?;
+        let now_ms = self.now_ms()?;
+        order.status = OrderStatus::Pending;
+        order.filled_volume = Decimal::ZERO;
+        order.currency = None;
+        order.margin_rate = None;
+
+        let reserved_price = match order.direction {
+            OrderDirection::Buy => {
+                let price = match order.price {
+                    Some(price) => price,
+                    None => cost_model.apply_slippage(
+                        OrderDirection::Buy,
+                        self.latest_price(&order.symbol).await?,
+                    ),
+                };
+                let fees = cost_model.fees(OrderDirection::Buy, price, order.volume);
+                Some(price + fees / order.volume)
+            }
+            OrderDirection::Sell => None,
+        };
+        if let Some(price) = reserved_price {
+            self.account_port
+                .freeze_funds(&order.account_id, None, price * order.volume)
+                .await?;
+        }
+
+        // 在途标记先于映射保存，对账看到的 Pending 记录要么在途、要么确已中断
+        let _in_flight = match self.mark_in_flight(&order.id) {
+            Ok(guard) => guard,
+            Err(e) => {
+                self.release_reservation(&order, reserved_price).await;
+                return Err(e);
+            }
+        };
+        // 先保存映射再外发：外发后进程中断或保存失败都不会留下无映射的券商订单
+        let record = BrokerOrderRecord {
+            account_type: account_type.to_string(),
+            external_order_id: order.id.0.clone(),
+            order: order.clone(),
+            reserved_price,
+            settled_notional: Decimal::ZERO,
+            settled_commission: Decimal::ZERO,
+        };
+        if let Err(e) = self.store.save(record).await {
+            self.release_reservation(&order, reserved_price).await;
+            return Err(e);
+        }
+
+        let sent = broker.send_order(&order).await;
+        let mut unmatched = self.settle_lock.lock().await;
+        let external_order_id = match sent {
+            Ok(id) => id,
+            Err(e) => {
+                let rejected = match self.store.get(&order.id).await {
+                    Ok(Some(record)) => self.reject_unsent(record, now_ms).await,
+                    Ok(None) => Err(TradeError::OrderNotFound(order.id.0.clone())),
+                    Err(load_error) => Err(load_error),
+                };
+                if let Err(reject_error) = rejected {
+                    tracing::error!(
+                        "Failed to reject unsent order {}, leaving it to reconciliation: {}",
+                        order.id.0,
+                        reject_error
+                    );
+                }
+                return Err(e);
+            }
+        };
+        tracing::info!(
+            "Order {} sent to {} gateway as {}",
+            order.id.0,
+            account_type,
+            external_order_id
+        );
+
+        if let Err(e) = self
+            .record_sent(&order.id, &external_order_id, now_ms)
+            .await
+        {
+            tracing::error!(
+                "Failed to record order {} accepted by {} gateway as {}, leaving it to reconciliation: {}",
+                order.id.0,
+                account_type,
+                external_order_id,
+                e
+            );
+            return Ok(order.id);
+        }
+
+        let (early, rest): (VecDeque<_>, VecDeque<_>) =
+            unmatched.drain(..).partition(|(kind, report)| {
+                kind == account_type && report.external_order_id == external_order_id
+            });
+        *unmatched = rest;
+        for (_, report) in early {
+            let settled = match self.store.get(&order.id).await {
+                Ok(Some(latest)) => self.settle_report(latest, &report).await,
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = settled {
+                tracing::error!(
+                    "Failed to settle early report of order {}, leaving it to reconciliation: {}",
+                    order.id.0,
+                    e
+                );
+            }
+        }
+        Ok(order.id)
+    }
+
+    /// 记录券商受理的订单号，`Pending` 订单转为 `Submitted`。
+    async fn record_sent(
+        &self,
+        order_id: &OrderId,
+        external_order_id: &str,
+        timestamp: i64,
+    ) -> Result<(), TradeError> {
+        let mut record = self
+            .store
+            .get(order_id)
+            .await?
+            .ok_or_else(|| TradeError::OrderNotFound(order_id.0.clone()))?;
+        record.external_order_id = external_order_id.to_string();
+        if record.order.status == OrderStatus::Pending {
+            record.order.status = OrderStatus::Submitted;
+            self.record_order_history(&record.order, timestamp).await;
+        }
+        self.store.save(record).await
+    }
+
+    /// # Logic
+    /// 以 `Rejected` 终结未被券商受理的订单，退回未成交部分的冻结资金并记入订单历史。
+    async fn reject_unsent(
+        &self,
+        mut record: BrokerOrderRecord,
+        timestamp: i64,
+    ) -> Result<(), TradeError> {
+        if !is_active_order_status(record.order.status) {
+            return Ok(());
+        }
+        let order = &mut record.order;
+        order.status = OrderStatus::Rejected;
+        let remaining = order.volume - order.filled_volume;
+        if let Some(price) = record.reserved_price
+            && remaining > Decimal::ZERO
+        {
+            self.account_port
+                .unfreeze_funds(&order.account_id, None, price * remaining)
+                .await?;
+        }
+        self.record_order_history(order, timestamp).await;
+        self.store.save(record).await
+    }
+
+    /// # Logic
+    /// 向网关请求撤单；撤单结果以 `Canceled` 回报为准。
+    ///
+    /// # Returns
+    /// * `Err(TradeError::OrderNotFound)` - If the order was never sent to a gateway.
+    /// * `Err(TradeError::InvalidOrderStatus)` - If the order is already final.
+    pub async fn cancel_order(&self, order_id: &OrderId) -> Result<(), TradeError> {
+        let record = self
+            .store
+            .get(order_id)
+            .await?
+            .ok_or_else(|| TradeError::OrderNotFound(order_id.0.clone()))?;
+        if !is_active_order_status(record.order.status) {
+            return Err(TradeError::InvalidOrderStatus);
+        }
+        self.broker(&record.account_type)?
+            .cancel_order(&record.external_order_id)
+            .await
+    }
+
+    /// 读取外发订单的最新状态；未外发过的订单返回 `None`。
+    pub async fn get_order(&self, order_id: &OrderId) -> Result<Option<Order>, TradeError> {
+        Ok(self.store.get(order_id).await?.map(|record| record.order))
+    }
+
+    /// 账户下未终结的外发订单。
+    pub async fn get_orders(&self, account_id: &AccountId) -> Result<Vec<Order>, TradeError> {
+        Ok(self
+            .store
+            .load_open()
+            .await?
+            .into_iter()
+            .filter(|record| &record.order.account_id == account_id)
+            .map(|record| record.order)
+            .collect())
+    }
+
+    /// # Logic
+    /// 处理一条执行回报：按 `(account_type, external_order_id)` 找到系统订单并结算。
+    /// 找不到映射的回报 (外发尚未返回券商订单号，或其他系统下的单) 记录告警后暂存。
+    pub async fn apply_report(
+        &self,
+        account_type: &str,
+        report: &ExecutionReport,
+    ) -> Result<(), TradeError> {
+        let mut unmatched = self.settle_lock.lock().await;
+        let Some(record) = self
+            .store
+            .find_by_external(account_type, &report.external_order_id)
+            .await?
+        else {
+            // 可能是外发尚未返回券商订单号的订单，暂存至映射更新
+            tracing::warn!(
+                "Execution report for unknown {} order {} held",
+                account_type,
+                report.external_order_id
+            );
+            if unmatched.len() >= MAX_UNMATCHED_REPORTS {
+                unmatched.pop_front();
+            }
+            unmatched.push_back((account_type.to_string(), report.clone()));
+            return Ok(());
+        };
+        self.settle_report(record, report).await
+    }
+
+    /// # Logic
+    /// 1. 已终结的订单忽略后续回报。
+    /// 2. 累计成交超出已结算部分时，按累计成交金额与手续费的差额构造一笔成交，
+    ///    经 `AccountPort::process_trade` 结算并解冻对应的冻结资金；成交成本超出冻结时先补冻结。
+    /// 3. 更新订单状态；订单终结时退回未成交部分的冻结资金。
+    /// 4. 保存映射记录，状态或成交数量变化时写入订单历史。
+    async fn settle_report(
+        &self,
+        mut record: BrokerOrderRecord,
+        report: &ExecutionReport,
+    ) -> Result<(), TradeError> {
+        if !is_active_order_status(record.order.status) {
+            return Ok(());
+        }
+        let order = &mut record.order;
+        let before = (order.status, order.filled_volume);
+
+        let fill_volume = report.filled_volume - order.filled_volume;
+        if fill_volume > Decimal::ZERO {
+            let notional = report.average_price * report.filled_volume;
+            let trade = Trade {
+                order_id: order.id.clone(),
+                account_id: order.account_id.clone(),
+                symbol: order.symbol.clone(),
+                direction: order.direction,
+                price: (notional - record.settled_notional) / fill_volume,
+                volume: fill_volume,
+                commission: (report.commission - record.settled_commission).max(Decimal::ZERO),
+                timestamp: report.timestamp,
+                settlement: None,
+            };
+            let mut est_req_funds = record
+                .reserved_price
+                .map_or(Decimal::ZERO, |price| price * fill_volume);
+            // 成交价高于冻结单价 (市价单跳空超出预留滑点) 时先补足冻结，避免可用资金为负
+            let actual_cost = trade.price * trade.volume + trade.commission;
+            if record.reserved_price.is_some() && actual_cost > est_req_funds {
+                match self
+                    .account_port
+                    .freeze_funds(&order.account_id, None, actual_cost - est_req_funds)
+                    .await
+                {
+                    Ok(()) => est_req_funds = actual_cost,
+                    Err(e) => tracing::warn!(
+                        "Fill of broker order {} exceeds its reserved funds: {}",
+                        order.id.0,
+                        e
+                    ),
+                }
+            }
+            self.account_port
+                .process_trade(&order.account_id, &trade, est_req_funds)
+                .await?;
+            if let Some(history) = &self.order_history
+                && let Err(e) = history.record_trade(&trade).await
+            {
+                tracing::error!("Failed to record fill of order {}: {}", order.id.0, e);
+            }
+            order.filled_volume = report.filled_volume;
+            record.settled_notional = notional;
+            record.settled_commission = record.settled_commission.max(report.commission);
+        }
+
+        order.status = match report.status {
+            OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartialFilled => {
+                if order.filled_volume > Decimal::ZERO {
+                    OrderStatus::PartialFilled
+                } else {
+                    OrderStatus::Submitted
+                }
+            }
+            status => status,
+        };
+        if !is_active_order_status(order.status) {
+            let remaining = order.volume - order.filled_volume;
+            if let Some(price) = record.reserved_price
+                && remaining > Decimal::ZERO
+            {
+                self.account_port
+                    .unfreeze_funds(&order.account_id, None, price * remaining)
+                    .await?;
+            }
+            tracing::info!(
+                "Broker order {} finished as {:?} with {} of {} filled{}",
+                order.id.0,
+                order.status,
+                order.filled_volume,
+                order.volume,
+                report
+                    .reason
+                    .as_deref()
+                    .map(|r| format!(": {}", r))
+                    .unwrap_or_default()
+            );
+        }
+
+        if before != (order.status, order.filled_volume) {
+            self.record_order_history(order, report.timestamp).await;
+        }
+        self.store.save(record).await
+    }
+
+    /// # Logic
+    /// 对账：逐一查询该网关下未终结订单的最新状态，补结算断线期间错过的回报。
+    /// 外发中的订单跳过；外发已失败或在本进程启动前中断 (仍为 `Pending` 且不在途)、
+    /// 券商又查无此单的订单按未受理处理：以 `Rejected` 终结并退回冻结资金。
+    /// 单笔查询失败只记录告警，不影响其余订单。
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of open orders whose status was fetched and applied.
+    pub async fn reconcile(&self, account_type: &str) -> Result<usize, TradeError> {
+        let broker = self.broker(account_type)?;
+        let open = self.store.load_open().await?;
+        let mut reconciled = 0;
+        for record in open.iter().filter(|r| r.account_type == account_type) {
+            // 外发尚未返回，券商可能还未登记该订单，结果以 submit_order 为准
+            if self.is_in_flight(&record.order.id)? {
+                continue;
+            }
+            let report = match broker.query_order_status(&record.external_order_id).await {
+                Ok(report) => report,
+                Err(TradeError::OrderNotFound(_))
+                    if record.order.status == OrderStatus::Pending =>
+                {
+                    let _guard = self.settle_lock.lock().await;
+                    // 查询期间可能开始了同一订单的重新外发
+                    if let Some(latest) = self.store.get(&record.order.id).await?
+                        && latest.order.status == OrderStatus::Pending
+                        && !self.is_in_flight(&latest.order.id)?
+                    {
+                        tracing::warn!(
+                            "Order {} was never accepted by the {} gateway, rejecting it",
+                            latest.order.id.0,
+                            account_type
+                        );
+                        self.reject_unsent(latest, self.now_ms()?).await?;
+                        reconciled += 1;
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to query {} order {}: {}",
+                        account_type,
+                        record.external_order_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let _guard = self.settle_lock.lock().await;
+            // 查询期间回报流可能已推进该订单，以最新记录为准
+            if let Some(latest) = self.store.get(&record.order.id).await? {
+                self.settle_report(latest, &report).await?;
+                reconciled += 1;
+            }
+        }
+        Ok(reconciled)
+    }
+
+    /// # Logic
+    /// 为每个已登记的网关启动回报消费任务：订阅回报流后先对账，再逐条处理回报；
+    /// 流结束或订阅失败时等待 `reconnect_delay` 后重新订阅并再次对账。
+    pub fn start(self: &Arc<Self>) {
+        for account_type in self.registry.account_types() {
+            let gateway = self.clone();
+            tokio::spawn(async move {
+                loop {
+                    match gateway.run(&account_type).await {
+                        Ok(()) => tracing::warn!(
+                            "Execution report stream of {} closed, reconnecting",
+                            account_type
+                        ),
+                        Err(e) => tracing::error!(
+                            "Execution report stream of {} failed: {}",
+                            account_type,
+                            e
+                        ),
+                    }
+                    tokio::time::sleep(gateway.reconnect_delay).await;
+                }
+            });
+        }
+    }
+
+    /// # Logic
+    /// 1. 订阅回报流 (先于对账，避免对账与订阅之间的回报丢失)。
+    /// 2. 对账全部未终结订单，记录一次连接完成。
+    /// 3. 逐条处理回报，单条失败只记录日志；流结束时返回。
+    async fn run(&self, account_type: &str) -> Result<(), TradeError> {
+        let mut stream = self
+            .broker(account_type)?
+            .subscribe_execution_reports()
+            .await?;
+        let reconciled = self.reconcile(account_type).await?;
+        tracing::info!(
+            "Connected to {} gateway, reconciled {} open orders",
+            account_type,
+            reconciled
+        );
+        self.connections.send_modify(|connections| {
+            *connections.entry(account_type.to_string()).or_default() += 1;
+        });
+
+        while let Some(report) = stream.next().await {
+            if let Err(e) = self.apply_report(account_type, &report).await {
+                tracing::error!(
+                    "Failed to apply execution report for {} order {}: {}",
+                    account_type,
+                    report.external_order_id,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,12 @@
 pub mod account;
 pub mod algo;
+pub mod fill_model;
+pub mod gateway;
+pub mod live;
 pub mod matcher;
+pub mod risk;
 pub mod router;
 pub mod service;
+pub mod session;
+pub mod simulated_broker;
 pub mod trade_log;
@@ -0,0 +1,244 @@
+use dashmap::DashMap;
+use futures::StreamExt;
+use okane_core::common::TimeFrame;
+use okane_core::common::time::TimeProvider;
+use okane_core::market::entity::Candle;
+use okane_core::market::port::Market;
+use okane_core::trade::port::{BacktestTradePort, PendingOrderPort, TradeError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::algo::AlgoOrderService;
+
+/// 行情流出错或关闭后重新订阅前的默认等待时间
+const DEFAULT_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+/// 连续重新订阅失败时退避等待的上限
+const MAX_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(60);
+
+/// # Summary
+/// 纸面交易 (LivePaper) 环境下的实时撮合循环。
+///
+/// 为每个存在活动挂单或运行中算法单的标的订阅 `Stock::subscribe`，
+/// 并将每一次行情推送转化为一次 `BacktestTradePort::tick`，
+/// 使实盘模拟与回测共用同一套穿越判定与结算逻辑。
+///
+/// # Invariants
+/// - 同一标的最多只有一个订阅任务，由 `watched` 登记。
+/// - 标的上既无活动挂单也无运行中算法单时，订阅任务自行退出并注销登记。
+/// - 行情流出错或关闭时订阅任务不退出，按指数退避重新订阅。
+pub struct LiveMatchingService {
+    /// 行情入口，用于订阅实时 K 线
+    market: Arc<dyn Market>,
+    /// 撮合与结算入口 (通常为本地 `TradeService`)
+    trade_port: Arc<dyn BacktestTradePort>,
+    /// 活动订单仓储，用于判断标的是否仍需订阅
+    pending_port: Arc<dyn PendingOrderPort>,
+    /// 可选的算法单服务，运行中的算法单同样需要行情驱动
+    algo_service: Option<Arc<AlgoOrderService>>,
+    /// 实时时钟源，成交时间戳取自该时钟而非 K 线起始时间
+    time_provider: Arc<dyn TimeProvider>,
+    /// 当前已在订阅中的标的集合
+    watched: DashMap<String, ()>,
+    /// 行情流中断后首次重新订阅前的等待时间，连续失败时逐次翻倍
+    resubscribe_delay: Duration,
+}
+
+impl LiveMatchingService {
+    /// # Logic
+    /// Create a live matching service without any active subscription.
+    ///
+    /// # Arguments
+    /// * `market` - Market entry used to subscribe to real-time candles.
+    /// * `trade_port` - Tick-driven matching port that settles crossed orders.
+    /// * `pending_port` - Pending order repository used to decide which symbols to watch.
+    /// * `time_provider` - Clock source for trade timestamps.
+    ///
+    /// # Returns
+    /// * `Self` - A new idle live matching service.
+    pub fn new(
+        market: Arc<dyn Market>,
+        trade_port: Arc<dyn BacktestTradePort>,
+        pending_port: Arc<dyn PendingOrderPort>,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            market,
+            trade_port,
+            pending_port,
+            algo_service: None,
+            time_provider,
+            watched: DashMap::new(),
+            resubscribe_delay: DEFAULT_RESUBSCRIBE_DELAY,
+        }
+    }
+
+    /// 设置行情流中断后首次重新订阅前的等待时间。
+    pub fn with_resubscribe_delay(mut self, delay: Duration) -> Self {
+        self.resubscribe_delay = delay;
+        self
+    }
+
+    /// 关联算法单服务，使运行中的算法单所在标的同样被订阅。
+    pub fn with_algo_service(mut self, algo_service: Arc<AlgoOrderService>) -> Self {
+        self.algo_service = Some(algo_service);
+        self
+    }
+
+    /// # Logic
+    /// Subscribe every symbol that already holds pending orders or running algo orders.
+    /// Called once at startup so that orders persisted before a restart keep matching.
+    ///
+    /// # Returns
+    /// * `Ok(())` - All recovered symbols are being watched.
+    /// * `Err(TradeError)` - The pending order repository could not be scanned.
+    pub async fn start(self: &Arc<Self>) -> Result<(), TradeError> {
+        let mut symbols = self.pending_port.get_symbols().await?;
+        if let Some(algo) = &self.algo_service {
+            symbols.extend(algo.running_symbols());
+        }
+        symbols.sort();
+        symbols.dedup();
+
+        for symbol in &symbols {
+            self.watch(symbol);
+        }
+        tracing::info!("Live matching started for {} symbols", symbols.len());
+        Ok(())
+    }
+
+    /// # Logic
+    /// Ensure a subscription task exists for `symbol`. No-op if the symbol is already watched.
+    ///
+    /// # Arguments
+    /// * `symbol` - Symbol that just received a resting order or a running algo order.
+    pub fn watch(self: &Arc<Self>, symbol: &str) {
+        if self.watched.insert(symbol.to_string(), ()).is_some() {
+            return;
+        }
+
+        let service = self.clone();
+        let symbol = symbol.to_string();
+        tokio::spawn(async move {
+            service.run(&symbol).await;
+            service.watched.remove(&symbol);
+
+            // 注销与新订单入队之间存在竞态：注销后再次确认，避免订单被遗漏
+            match service.has_active_orders(&symbol).await {
+                Ok(true) => service.watch(&symbol),
+                Ok(false) => tracing::debug!("Live matching for {} idle, unsubscribed", symbol),
+                Err(e) => tracing::error!("Failed to re-check active orders for {}: {}", symbol, e),
+            }
+        });
+    }
+
+    /// 当前是否正在订阅该标的。
+    pub fn is_watching(&self, symbol: &str) -> bool {
+        self.watched.contains_key(symbol)
+    }
+
+    async fn has_active_orders(&self, symbol: &str) -> Result<bool, TradeError> {
+        if let Some(algo) = &self.algo_service
+            && algo.has_running_orders(symbol)
+        {
+            return Ok(true);
+        }
+        Ok(!self.pending_port.get_by_symbol(symbol).await?.is_empty())
+    }
+
+    /// # Logic
+    /// 1. 订阅并消费行情流，直至标的不再有活动订单。
+    /// 2. 订阅失败、行情流出错或关闭时记录日志，等待退避间隔后重新订阅；
+    ///    本次订阅收到过行情则退避间隔复位，否则逐次翻倍，上限 `MAX_RESUBSCRIBE_DELAY`。
+    /// 3. 等待期间订单可能已全部成交或撤销，重新订阅前再次检查，无活动订单则退出。
+    async fn run(&self, symbol: &str) {
+        let mut delay = self.resubscribe_delay;
+        loop {
+            let mut received = false;
+            match self.consume(symbol, &mut received).await {
+                Ok(()) => return,
+                Err(e) => {
+                    if received {
+                        delay = self.resubscribe_delay;
+                    }
+                    tracing::warn!(
+                        "Live matching stream for {} interrupted, resubscribing in {:?}: {}",
+                        symbol,
+                        delay,
+                        e
+                    );
+                }
+            }
+
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RESUBSCRIBE_DELAY);
+            match self.has_active_orders(symbol).await {
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(e) => tracing::error!("Failed to check active orders for {}: {}", symbol, e),
+            }
+        }
+    }
+
+    /// # Logic
+    /// 1. 订阅标的 1 分钟 K 线流。
+    /// 2. 实时推送的是同一根 K 线的滚动快照，其 high/low 覆盖了订单提交之前的价格，
+    ///    因此按最新价构造单点 K 线送入 `tick`，避免新挂单被历史极值误撮合。
+    /// 3. 收盘定稿的 K 线 (`is_final`) 跳过：其价格已随最后一个快照撮合过，且送达时已进入下一分钟，
+    ///    再送入会把整分钟成交量重复计入新分钟的 VWAP 参与额度。
+    /// 4. 每次撮合后检查标的是否仍有活动订单，无则退出。
+    ///
+    /// # Arguments
+    /// * `symbol` - Symbol to subscribe.
+    /// * `received` - Set once the subscription delivers its first candle.
+    ///
+    /// # Returns
+    /// * `Ok(())` - The symbol has no more active orders.
+    /// * `Err(TradeError)` - Subscription failed, the candle stream failed or it ended.
+    async fn consume(&self, symbol: &str, received: &mut bool) -> Result<(), TradeError> {
+        let stock = self.market.get_stock(symbol).await.map_err(|e| {
+            TradeError::BrokerIntegrationError(format!("Failed to get market data: {}", e))
+        })?;
+        let mut stream = stock.subscribe(TimeFrame::Minute1).map_err(|e| {
+            TradeError::BrokerIntegrationError(format!("Failed to subscribe {}: {}", symbol, e))
+        })?;
+
+        while let Some(item) = stream.next().await {
+            let candle = item.map_err(|e| {
+                TradeError::BrokerIntegrationError(format!("candle stream error: {}", e))
+            })?;
+            *received = true;
+            if candle.is_final {
+                continue;
+            }
+            let now = self
+                .time_provider
+                .now()
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+            let quote = Candle {
+                time: now,
+                open: candle.close,
+                high: candle.close,
+                low: candle.close,
+                close: candle.close,
+                adj_close: None,
+                volume: candle.volume,
+                is_final: false,
+            };
+
+            // 单个订单结算失败不应中断整个标的的撮合
+            if let Err(e) = self.trade_port.tick(symbol, &quote).await {
+                tracing::error!("Live tick for {} failed: {}", symbol, e);
+            }
+
+            if !self.has_active_orders(symbol).await? {
+                return Ok(());
+            }
+        }
+
+        Err(TradeError::BrokerIntegrationError(format!(
+            "candle stream for {} closed",
+            symbol
+        )))
+    }
+}
@@ -1,23 +1,86 @@
+use crate::fill_model::{BarFillModel, FillModel};
+use okane_core::market::entity::Candle;
+use okane_core::trade::cost::CostModel;
 use okane_core::trade::entity::{Order, OrderDirection, OrderStatus, Trade};
 use okane_core::trade::port::MatcherPort;
 use rust_decimal::Decimal;
+use std::sync::Arc;
+
+/// 按逻辑交易账号成本模型构建撮合器的工厂函数类型。
+pub type MatcherFactory = Arc<dyn Fn(&CostModel) -> Arc<dyn MatcherPort> + Send + Sync>;
 
 /// # Summary
 /// 针对测试和纸面模拟盘环境的内存级撮合引擎。
-/// 接收逻辑委托单并执行基于当前（虚拟）价格的简化成交判定。
+/// 接收逻辑委托单并执行基于当前（虚拟）价格的简化成交判定；
+/// 挂单按 K 线撮合时的价格与数量由可插拔的 `FillModel` 决定，
+/// 滑点与费用由逻辑交易账号的 `CostModel` 决定。
 pub struct LocalMatchEngine {
-    commission_rate: Decimal,
+    cost_model: CostModel,
+    fill_model: Arc<dyn FillModel>,
 }
 
 impl LocalMatchEngine {
+    /// 仅按成交额比例收取佣金的撮合引擎。
     pub fn new(commission_rate: Decimal) -> Self {
-        Self { commission_rate }
+        Self::with_cost_model(CostModel::percent_commission(commission_rate))
+    }
+
+    /// 按逻辑交易账号的成本模型构建撮合引擎。
+    pub fn with_cost_model(cost_model: CostModel) -> Self {
+        Self {
+            cost_model,
+            fill_model: Arc::new(BarFillModel::new()),
+        }
+    }
+
+    /// 替换 K 线撮合使用的成交模型，默认为不限参与率的 `BarFillModel`。
+    pub fn with_fill_model(mut self, fill_model: Arc<dyn FillModel>) -> Self {
+        self.fill_model = fill_model;
+        self
+    }
+
+    fn is_active(order: &Order) -> bool {
+        matches!(
+            order.status,
+            OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartialFilled
+        )
+    }
+
+    /// # Logic
+    /// Build matchers for logical accounts that share `fill_model`, so participation
+    /// caps stay global per symbol while each account keeps its own cost model.
+    pub fn factory(fill_model: Arc<dyn FillModel>) -> MatcherFactory {
+        Arc::new(move |cost_model: &CostModel| -> Arc<dyn MatcherPort> {
+            Arc::new(
+                LocalMatchEngine::with_cost_model(cost_model.clone())
+                    .with_fill_model(fill_model.clone()),
+            )
+        })
+    }
+
+    /// 对参考成交价施加滑点；限价单的成交价不劣于限价。
+    fn slipped_price(&self, order: &Order, price: Decimal) -> Decimal {
+        let slipped = self.cost_model.apply_slippage(order.direction, price);
+        match (order.direction, order.price) {
+            (OrderDirection::Buy, Some(limit)) => slipped.min(limit),
+            (OrderDirection::Sell, Some(limit)) => slipped.max(limit),
+            (_, None) => slipped,
+        }
     }
 }
 
 impl MatcherPort for LocalMatchEngine {
-    fn estimate_commission(&self, price: Decimal, volume: Decimal) -> Decimal {
-        price * volume * self.commission_rate
+    fn estimate_commission(
+        &self,
+        direction: OrderDirection,
+        price: Decimal,
+        volume: Decimal,
+    ) -> Decimal {
+        self.cost_model.fees(direction, price, volume)
+    }
+
+    fn estimate_fill_price(&self, direction: OrderDirection, reference_price: Decimal) -> Decimal {
+        self.cost_model.apply_slippage(direction, reference_price)
     }
 
     fn execute_order(
@@ -26,7 +89,7 @@ impl MatcherPort for LocalMatchEngine {
         current_market_price: Decimal,
         now_ms: i64,
     ) -> Option<Trade> {
-        if order.status != OrderStatus::Pending && order.status != OrderStatus::Submitted {
+        if !Self::is_active(order) || order.is_awaiting_trigger() {
             return None;
         }
 
@@ -45,10 +108,10 @@ impl MatcherPort for LocalMatchEngine {
             }
         }
 
-        let execute_price = current_market_price;
+        let execute_price = self.slipped_price(order, current_market_price);
         let executed_volume = order.volume - order.filled_volume;
 
-        let commission = self.estimate_commission(execute_price, executed_volume);
+        let commission = self.estimate_commission(order.direction, execute_price, executed_volume);
 
         order.filled_volume += executed_volume;
         order.status = OrderStatus::Filled;
@@ -62,10 +125,56 @@ impl MatcherPort for LocalMatchEngine {
             volume: executed_volume,
             commission,
             timestamp: now_ms,
+            settlement: None,
         };
 
         Some(trade)
     }
+
+    fn execute_bar(&self, symbol: &str, orders: &mut [Order], candle: &Candle) -> Vec<Trade> {
+        let active: Vec<usize> = (0..orders.len())
+            .filter(|&i| orders.get(i).is_some_and(Self::is_active))
+            .collect();
+        let mut snapshot: Vec<Order> = active
+            .iter()
+            .filter_map(|&i| orders.get(i).cloned())
+            .collect();
+        let now_ms = candle.time.timestamp_millis();
+        let fills = self.fill_model.fill_bar(symbol, &mut snapshot, candle);
+
+        // 回写成交模型更新的触发状态 (已触发标记、跟踪止损最有利价格)
+        for (updated, &i) in snapshot.into_iter().zip(active.iter()) {
+            if let Some(order) = orders.get_mut(i) {
+                order.trigger = updated.trigger;
+            }
+        }
+
+        let mut trades = Vec::new();
+        for (idx, fill) in fills {
+            let Some(order) = active.get(idx).and_then(|&i| orders.get_mut(i)) else {
+                continue;
+            };
+            let price = self.slipped_price(order, fill.price);
+            order.filled_volume += fill.volume;
+            order.status = if order.filled_volume >= order.volume {
+                OrderStatus::Filled
+            } else {
+                OrderStatus::PartialFilled
+            };
+            trades.push(Trade {
+                order_id: order.id.clone(),
+                account_id: order.account_id.clone(),
+                symbol: order.symbol.clone(),
+                direction: order.direction,
+                price,
+                volume: fill.volume,
+                commission: self.estimate_commission(order.direction, price, fill.volume),
+                timestamp: now_ms,
+                settlement: None,
+            });
+        }
+        trades
+    }
 }
 
 #[cfg(test)]
@@ -140,4 +249,46 @@ mod tests {
         let trade = matcher.execute_order(&mut order, dec!(150.0), 1001);
         assert!(trade.is_none());
     }
+
+    #[test]
+    fn test_cost_model_slippage_never_crosses_limit() -> Result<(), Box<dyn std::error::Error>> {
+        use okane_core::trade::cost::{CommissionModel, SlippageModel};
+
+        let matcher = LocalMatchEngine::with_cost_model(CostModel {
+            slippage: SlippageModel::FixedBps { bps: dec!(50) },
+            commission: CommissionModel::None,
+            stamp_duty_rate: dec!(0.001),
+            exchange_fee_rate: Decimal::ZERO,
+        });
+        let mut market = Order::new(
+            OrderId("m1".to_string()),
+            AccountId("acc1".to_string()),
+            "AAPL".to_string(),
+            OrderDirection::Sell,
+            None,
+            dec!(10),
+            1000,
+        );
+        let trade = matcher
+            .execute_order(&mut market, dec!(100), 1001)
+            .ok_or("Failed to execute order")?;
+        // 卖出下调 0.5%，并收取 0.1% 印花税
+        assert_eq!(trade.price, dec!(99.5));
+        assert_eq!(trade.commission, dec!(0.995));
+
+        let mut limit = Order::new(
+            OrderId("l1".to_string()),
+            AccountId("acc1".to_string()),
+            "AAPL".to_string(),
+            OrderDirection::Buy,
+            Some(dec!(100.2)),
+            dec!(10),
+            1000,
+        );
+        let trade = matcher
+            .execute_order(&mut limit, dec!(100), 1001)
+            .ok_or("Failed to execute order")?;
+        assert_eq!(trade.price, dec!(100.2));
+        Ok(())
+    }
 }
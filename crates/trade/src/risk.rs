@@ -0,0 +1,495 @@
+use async_trait::async_trait;
+use dashmap::DashMap;
+use okane_core::common::time::TimeProvider;
+use okane_core::market::port::Market;
+use okane_core::store::port::SystemStore;
+use okane_core::trade::entity::{
+    AccountId, AccountSnapshot, CashTransfer, DailyPnl, HistoryPage, HistoryQuery, LedgerEntry,
+    Order, OrderDirection, OrderGroup, OrderGroupKind, OrderHistoryRecord, OrderId, Trade,
+};
+use okane_core::trade::port::{TradeError, TradePort};
+use okane_core::trade::risk::{RiskRules, RiskViolation};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// 委托频率限制的滑动窗口长度 (毫秒)。
+const ORDER_RATE_WINDOW_MS: i64 = 60_000;
+
+/// 当日亏损基线在系统配置中的键前缀，后接账号 ID。
+const DAILY_LOSS_BASELINE_KEY: &str = "risk.daily_loss_baseline.";
+
+/// # Summary
+/// 单个账号的风控运行状态：近一分钟受理的委托时间戳与当日亏损基线。
+#[derive(Default)]
+struct RiskWindow {
+    /// 近一个窗口内已放行委托的时间戳 (毫秒，升序)
+    accepted_at: VecDeque<i64>,
+    /// 已确定的当日亏损基线 `(日期, 累计已实现盈亏 - 费用)`
+    daily_baseline: Option<(chrono::NaiveDate, Decimal)>,
+}
+
+/// # Summary
+/// 事前风控装饰器，包裹任意 `TradePort`，按逻辑交易账号配置中的 `risk_rules`
+/// 在委托送达下游之前拦截违规订单。
+///
+/// # Invariants
+/// - 撤单、查询与紧急停止的平仓单直接透传，不做任何拦截。
+/// - 当日已实现亏损以账户累计的 (已实现盈亏 - 费用) 相对当日基线的减少量计，出入金与划转不影响。
+///   基线在每日首次检查时确定并写入系统配置，进程重启后不变。
+/// - 同一账号的风控检查与下游受理在账号锁内串行执行，并发委托不会同时通过头寸与敞口限制。
+/// - 账号档案不存在时不施加规则，由下游报告账号错误。
+pub struct RiskControlledTradePort {
+    inner: Arc<dyn TradePort>,
+    system_store: Arc<dyn SystemStore>,
+    market: Arc<dyn Market>,
+    time_provider: Arc<dyn TimeProvider>,
+    windows: Mutex<HashMap<AccountId, RiskWindow>>,
+    /// 各账号的检查-受理锁
+    account_locks: DashMap<AccountId, Arc<tokio::sync::Mutex<()>>>,
+}
+
+impl RiskControlledTradePort {
+    pub fn new(
+        inner: Arc<dyn TradePort>,
+        system_store: Arc<dyn SystemStore>,
+        market: Arc<dyn Market>,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            inner,
+            system_store,
+            market,
+            time_provider,
+            windows: Mutex::new(HashMap::new()),
+            account_locks: DashMap::new(),
+        }
+    }
+
+    /// 账号的检查-受理锁，持有期间同一账号的其他委托等待。
+    async fn lock_account(&self, account_id: &AccountId) -> tokio::sync::OwnedMutexGuard<()> {
+        let lock = self
+            .account_locks
+            .entry(account_id.clone())
+            .or_default()
+            .clone();
+        lock.lock_owned().await
+    }
+
+    async fn rules_for(&self, account_id: &AccountId) -> Result<RiskRules, TradeError> {
+        let profile = self
+            .system_store
+            .get_account_profile(&account_id.0)
+            .await
+            .map_err(|e| {
+                TradeError::InternalError(format!("account profile lookup failed: {}", e))
+            })?;
+        match profile {
+            Some(profile) => RiskRules::from_account_config(&profile.config),
+            None => Ok(RiskRules::default()),
+        }
+    }
+
+    async fn latest_price(&self, symbol: &str) -> Result<Decimal, TradeError> {
+        let stock = self.market.get_stock(symbol).await.map_err(|e| {
+            TradeError::BrokerIntegrationError(format!("Failed to get market data: {}", e))
+        })?;
+        stock
+            .current_price()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .ok_or_else(|| {
+                TradeError::InternalError(format!("No latest price available for stock {}", symbol))
+            })
+    }
+
+    /// 委托的参考单价：限价单取限价，止损单取触发价，其余取最新价。
+    async fn reference_price(&self, order: &Order) -> Result<Decimal, TradeError> {
+        if let Some(price) = order.reserve_price() {
+            return Ok(price);
+        }
+        self.latest_price(&order.symbol).await
+    }
+
+    fn now(&self) -> Result<chrono::DateTime<chrono::Utc>, TradeError> {
+        self.time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))
+    }
+
+    fn lock_windows(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<AccountId, RiskWindow>>, TradeError> {
+        self.windows
+            .lock()
+            .map_err(|e| TradeError::InternalError(format!("risk window lock poisoned: {}", e)))
+    }
+
+    fn signed_remaining(order: &Order) -> Decimal {
+        let remaining = order.volume - order.filled_volume;
+        match order.direction {
+            OrderDirection::Buy => remaining,
+            OrderDirection::Sell => -remaining,
+        }
+    }
+
+    /// 读取系统配置中持久化的当日亏损基线；无记录或记录无法解析时返回 `None`。
+    async fn load_baseline(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<(chrono::NaiveDate, Decimal)>, TradeError> {
+        let key = format!("{}{}", DAILY_LOSS_BASELINE_KEY, account_id.0);
+        let Some(value) = self.system_store.get_setting(&key).await.map_err(|e| {
+            TradeError::InternalError(format!("risk baseline lookup failed: {}", e))
+        })?
+        else {
+            return Ok(None);
+        };
+        let parsed = serde_json::from_str::<serde_json::Value>(&value)
+            .ok()
+            .and_then(|value| {
+                let date = value.get("date")?.as_str()?.parse().ok()?;
+                let baseline = value.get("baseline")?.as_str()?.parse().ok()?;
+                Some((date, baseline))
+            });
+        if parsed.is_none() {
+            tracing::warn!(
+                "Ignored unreadable daily loss baseline of account {}: {}",
+                account_id.0,
+                value
+            );
+        }
+        Ok(parsed)
+    }
+
+    /// # Logic
+    /// 当日亏损基线，每个账号每日只确定一次：
+    /// 1. 内存或系统配置中已有当日基线时直接沿用。
+    /// 2. 否则以上一记录日每日盈亏快照中的累计 (已实现盈亏 - 费用) 为基线；
+    ///    无更早记录时 (如首次受风控规则约束的账户) 取当前快照，即当日开盘状态。
+    /// 3. 新确定的基线写入系统配置，进程重启后不变。
+    async fn daily_baseline(
+        &self,
+        snapshot: &AccountSnapshot,
+        today: chrono::NaiveDate,
+    ) -> Result<Decimal, TradeError> {
+        let account_id = &snapshot.account_id;
+        if let Some((date, baseline)) = self
+            .lock_windows()?
+            .get(account_id)
+            .and_then(|window| window.daily_baseline)
+            && date == today
+        {
+            return Ok(baseline);
+        }
+        let baseline = match self.load_baseline(account_id).await? {
+            Some((date, baseline)) if date == today => baseline,
+            _ => {
+                let baseline = self
+                    .inner
+                    .get_daily_pnl(account_id)
+                    .await?
+                    .iter()
+                    .rev()
+                    .find(|record| record.date < today)
+                    .map_or(
+                        snapshot.realized_pnl - snapshot.total_commission,
+                        |record| record.realized_pnl - record.total_commission,
+                    );
+                let value = serde_json::json!({
+                    "date": today.to_string(),
+                    "baseline": baseline.to_string(),
+                });
+                self.system_store
+                    .set_setting(
+                        &format!("{}{}", DAILY_LOSS_BASELINE_KEY, account_id.0),
+                        &value.to_string(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        TradeError::InternalError(format!("risk baseline save failed: {}", e))
+                    })?;
+                baseline
+            }
+        };
+        self.lock_windows()?
+            .entry(account_id.clone())
+            .or_default()
+            .daily_baseline = Some((today, baseline));
+        Ok(baseline)
+    }
+
+    /// 当日已实现亏损：当前累计 (已实现盈亏 - 费用) 相对当日基线的减少量。
+    async fn daily_loss(&self, snapshot: &AccountSnapshot) -> Result<Decimal, TradeError> {
+        let today = self.now()?.date_naive();
+        let baseline = self.daily_baseline(snapshot, today).await?;
+        let realized = snapshot.realized_pnl - snapshot.total_commission;
+        Ok((baseline - realized).max(Decimal::ZERO))
+    }
+
+    /// # Logic
+    /// 1. 标的黑白名单与单笔名义金额。
+    /// 2. `position_checks` 为假时到此为止 (括号单的离场子单在入场前无从评估持仓)。
+    /// 3. 以 `持仓 + 在途委托` 为基础，计算本单前后该标的的预估头寸与总敞口：
+    ///    当日亏损达限后仅允许缩小头寸的委托；头寸与总敞口仅在本单使其扩大时受限。
+    /// 4. 禁止卖空时，卖出数量不得超过持仓减去在途卖单。
+    async fn check_order(
+        &self,
+        rules: &RiskRules,
+        order: &Order,
+        position_checks: bool,
+    ) -> Result<(), TradeError> {
+        rules.check_symbol(&order.symbol)?;
+        let price = self.reference_price(order).await?;
+        let notional = price * order.volume;
+        if let Some(limit) = rules.max_order_notional
+            && notional > limit
+        {
+            return Err(RiskViolation::OrderNotional { notional, limit }.into());
+        }
+
+        let needs_book = rules.max_position_per_symbol.is_some()
+            || rules.max_gross_exposure.is_some()
+            || rules.daily_loss_limit.is_some()
+            || rules.no_short;
+        if !position_checks || !needs_book {
+            return Ok(());
+        }
+
+        let snapshot = self.inner.get_account(order.account_id.clone()).await?;
+        let pending = self.inner.get_orders(&order.account_id).await?;
+
+        let mut net: HashMap<&str, Decimal> = HashMap::new();
+        for position in &snapshot.positions {
+            *net.entry(position.symbol.as_str()).or_default() += position.volume;
+        }
+        let mut pending_sell = Decimal::ZERO;
+        for pending_order in &pending {
+            *net.entry(pending_order.symbol.as_str()).or_default() +=
+                Self::signed_remaining(pending_order);
+            if pending_order.symbol == order.symbol
+                && pending_order.direction == OrderDirection::Sell
+            {
+                pending_sell += pending_order.volume - pending_order.filled_volume;
+            }
+        }
+        let before = net.get(order.symbol.as_str()).copied().unwrap_or_default();
+        let after = before + Self::signed_remaining(order);
+        let increases = after.abs() > before.abs();
+
+        if let Some(limit) = rules.daily_loss_limit {
+            let loss = self.daily_loss(&snapshot).await?;
+            if loss >= limit && after.abs() >= before.abs() {
+                return Err(RiskViolation::DailyLoss { loss, limit }.into());
+            }
+        }
+
+        if rules.no_short && order.direction == OrderDirection::Sell {
+            let held = snapshot
+                .positions
+                .iter()
+                .filter(|p| p.symbol == order.symbol)
+                .map(|p| p.volume)
+                .sum::<Decimal>();
+            let sellable = (held - pending_sell).max(Decimal::ZERO);
+            if order.volume > sellable {
+                return Err(RiskViolation::ShortSell {
+                    symbol: order.symbol.clone(),
+                    volume: order.volume,
+                    sellable,
+                }
+                .into());
+            }
+        }
+
+        if let Some(limit) = rules.max_position_per_symbol
+            && increases
+            && after.abs() > limit
+        {
+            return Err(RiskViolation::PositionLimit {
+                symbol: order.symbol.clone(),
+                projected: after.abs(),
+                limit,
+            }
+            .into());
+        }
+
+        if let Some(limit) = rules.max_gross_exposure
+            && increases
+        {
+            let mut others = Decimal::ZERO;
+            for (symbol, volume) in net.iter().filter(|(s, _)| **s != order.symbol) {
+                if !volume.is_zero() {
+                    others += volume.abs() * self.latest_price(symbol).await?;
+                }
+            }
+            let projected = others + after.abs() * price;
+            if projected > limit {
+                return Err(RiskViolation::GrossExposure { projected, limit }.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// 滑动窗口内受理数已达上限则拒绝，否则登记本次受理。
+    fn admit(&self, rules: &RiskRules, account_id: &AccountId) -> Result<(), TradeError> {
+        let now_ms = self.now()?.timestamp_millis();
+        let mut windows = self.lock_windows()?;
+        let window = windows
+            .entry(account_id.clone())
+            .or_insert_with(RiskWindow::default);
+        while window
+            .accepted_at
+            .front()
+            .is_some_and(|at| now_ms - *at >= ORDER_RATE_WINDOW_MS)
+        {
+            window.accepted_at.pop_front();
+        }
+        if let Some(limit) = rules.max_orders_per_minute
+            && window.accepted_at.len() >= usize::try_from(limit).unwrap_or(usize::MAX)
+        {
+            return Err(RiskViolation::OrderRate { limit }.into());
+        }
+        window.accepted_at.push_back(now_ms);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TradePort for RiskControlledTradePort {
+    async fn submit_order(&self, order: Order) -> Result<OrderId, TradeError> {
+        let rules = self.rules_for(&order.account_id).await?;
+        let _guard = self.lock_account(&order.account_id).await;
+        self.check_order(&rules, &order, true).await?;
+        self.admit(&rules, &order.account_id)?;
+        self.inner.submit_order(order).await
+    }
+
+    /// 平仓单只减少敞口，不做事前风控检查，也不占用下单频率额度。
+    async fn submit_flatten_order(&self, order: Order) -> Result<OrderId, TradeError> {
+        self.inner.submit_flatten_order(order).await
+    }
+
+    async fn cancel_order(&self, order_id: OrderId) -> Result<(), TradeError> {
+        self.inner.cancel_order(order_id).await
+    }
+
+    async fn get_account(&self, account_id: AccountId) -> Result<AccountSnapshot, TradeError> {
+        self.inner.get_account(account_id).await
+    }
+
+    async fn get_orders(&self, account_id: &AccountId) -> Result<Vec<Order>, TradeError> {
+        self.inner.get_orders(account_id).await
+    }
+
+    async fn get_order(&self, order_id: &OrderId) -> Result<Option<Order>, TradeError> {
+        self.inner.get_order(order_id).await
+    }
+
+    async fn ensure_account(
+        &self,
+        account_id: AccountId,
+        initial_balance: Decimal,
+    ) -> Result<(), TradeError> {
+        self.inner.ensure_account(account_id, initial_balance).await
+    }
+
+    /// # Logic
+    /// OCO 子单互斥，逐一按完整规则检查；括号单检查入场单，离场子单只检查标的与名义金额。
+    /// 整个订单组计为一次委托受理。
+    async fn submit_order_group(
+        &self,
+        group: OrderGroup,
+        entry: Option<Order>,
+    ) -> Result<OrderId, TradeError> {
+        let rules = self.rules_for(&group.account_id).await?;
+        let _guard = self.lock_account(&group.account_id).await;
+        if let Some(entry) = &entry {
+            self.check_order(&rules, entry, true).await?;
+        }
+        let leg_position_checks = group.kind == OrderGroupKind::Oco;
+        for leg in &group.legs {
+            self.check_order(&rules, leg, leg_position_checks).await?;
+        }
+        self.admit(&rules, &group.account_id)?;
+        self.inner.submit_order_group(group, entry).await
+    }
+
+    async fn cancel_order_group(&self, group_id: &OrderId) -> Result<(), TradeError> {
+        self.inner.cancel_order_group(group_id).await
+    }
+
+    async fn get_order_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        self.inner.get_order_group(group_id).await
+    }
+
+    async fn get_daily_pnl(&self, account_id: &AccountId) -> Result<Vec<DailyPnl>, TradeError> {
+        self.inner.get_daily_pnl(account_id).await
+    }
+
+    async fn get_order_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<OrderHistoryRecord>, TradeError> {
+        self.inner.get_order_history(query).await
+    }
+
+    async fn get_trade_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<Trade>, TradeError> {
+        self.inner.get_trade_history(query).await
+    }
+
+    async fn deposit(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        self.inner.deposit(account_id, currency, amount).await
+    }
+
+    async fn withdraw(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        self.inner.withdraw(account_id, currency, amount).await
+    }
+
+    async fn transfer(
+        &self,
+        from: &AccountId,
+        to: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+        transfer_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        self.inner
+            .transfer(from, to, currency, amount, transfer_id)
+            .await
+    }
+
+    async fn convert_currency(
+        &self,
+        account_id: &AccountId,
+        from: &str,
+        to: &str,
+        amount: rust_decimal::Decimal,
+        conversion_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        self.inner
+            .convert_currency(account_id, from, to, amount, conversion_id)
+            .await
+    }
+
+    async fn get_ledger(
+        &self,
+        account_id: &AccountId,
+        offset: usize,
+        limit: usize,
+    ) -> Result<HistoryPage<LedgerEntry>, TradeError> {
+        self.inner.get_ledger(account_id, offset, limit).await
+    }
+}
@@ -1,16 +1,24 @@
 use async_trait::async_trait;
 use okane_core::store::port::SystemStore;
-use okane_core::trade::entity::{AccountId, AccountSnapshot, Order, OrderId};
+use okane_core::trade::entity::{
+    AccountId, AccountSnapshot, CashTransfer, DailyPnl, HistoryPage, HistoryQuery, LedgerEntry,
+    Order, OrderGroup, OrderHistoryRecord, OrderId, Trade,
+};
 use okane_core::trade::port::{TradeError, TradePort};
 use std::sync::Arc;
 
+use crate::gateway::BrokerGateway;
+
 /// # Summary
 /// 基于逻辑交易账号后端的统一交易路由器。
 ///
-/// 当前版本完整支持本地后端，并为平台后端保留显式的执行入口。
+/// `local` 账号由本地撮合处理，其余后端类型交由券商网关 (`BrokerGateway`) 中登记的网关；
+/// 未登记网关的后端类型拒绝新委托。
+/// 已紧急停止 (halted) 的账号在重新启用前不接受新委托 (紧急停止自身的平仓单除外)，撤单与查询不受影响。
 pub struct RoutedTradePort {
     local_trade_port: Arc<dyn TradePort>,
     system_store: Arc<dyn SystemStore>,
+    gateway: Option<Arc<BrokerGateway>>,
 }
 
 impl RoutedTradePort {
@@ -18,35 +26,78 @@ impl RoutedTradePort {
         Self {
             local_trade_port,
             system_store,
+            gateway: None,
         }
     }
 
-    async fn type_for_account(&self, account_id: &str) -> Result<String, TradeError> {
+    /// 关联券商网关，使非本地后端的账号可以下单。
+    pub fn with_gateway(mut self, gateway: Arc<BrokerGateway>) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    /// # Logic
+    /// 读取账号档案并返回其后端类型；已紧急停止的账号拒绝任何新委托。
+    async fn type_for_new_order(&self, account_id: &str) -> Result<String, TradeError> {
+        self.account_type(account_id, false).await
+    }
+
+    /// # Logic
+    /// 读取账号档案并返回其后端类型；`allow_halted` 为假时已紧急停止的账号被拒绝。
+    async fn account_type(
+        &self,
+        account_id: &str,
+        allow_halted: bool,
+    ) -> Result<String, TradeError> {
         let profile = self
             .system_store
             .get_account_profile(account_id)
             .await
-            .map_err(|e| TradeError::InternalError(format!("account profile lookup failed: {}", e)))?
+            .map_err(|e| {
+                TradeError::InternalError(format!("account profile lookup failed: {}", e))
+            })?
             .ok_or_else(|| TradeError::AccountNotFound(account_id.to_string()))?;
+        if profile.halted && !allow_halted {
+            return Err(TradeError::AccountHalted(account_id.to_string()));
+        }
         Ok(profile.account_type)
     }
-}
 
-#[async_trait]
-impl TradePort for RoutedTradePort {
-    async fn submit_order(&self, order: Order) -> Result<OrderId, TradeError> {
-        match self.type_for_account(&order.account_id.0).await?.as_str() {
+    /// 按账号后端类型把新委托交给本地撮合或对应的券商网关。
+    async fn route_order(&self, account_type: &str, order: Order) -> Result<OrderId, TradeError> {
+        match account_type {
             "local" => self.local_trade_port.submit_order(order).await,
-            account_type => Err(TradeError::BrokerIntegrationError(
-                format!(
+            account_type => match &self.gateway {
+                Some(gateway) if gateway.supports(account_type) => {
+                    gateway.submit_order(account_type, order).await
+                }
+                _ => Err(TradeError::BrokerIntegrationError(format!(
                     "account type {} is registered, but no platform gateway is configured",
                     account_type
-                ),
-            )),
+                ))),
+            },
         }
     }
+}
+
+#[async_trait]
+impl TradePort for RoutedTradePort {
+    async fn submit_order(&self, order: Order) -> Result<OrderId, TradeError> {
+        let account_type = self.type_for_new_order(&order.account_id.0).await?;
+        self.route_order(&account_type, order).await
+    }
+
+    async fn submit_flatten_order(&self, order: Order) -> Result<OrderId, TradeError> {
+        let account_type = self.account_type(&order.account_id.0, true).await?;
+        self.route_order(&account_type, order).await
+    }
 
     async fn cancel_order(&self, order_id: OrderId) -> Result<(), TradeError> {
+        if let Some(gateway) = &self.gateway
+            && gateway.get_order(&order_id).await?.is_some()
+        {
+            return gateway.cancel_order(&order_id).await;
+        }
         self.local_trade_port.cancel_order(order_id).await
     }
 
@@ -55,10 +106,19 @@ impl TradePort for RoutedTradePort {
     }
 
     async fn get_orders(&self, account_id: &AccountId) -> Result<Vec<Order>, TradeError> {
-        self.local_trade_port.get_orders(account_id).await
+        let mut orders = self.local_trade_port.get_orders(account_id).await?;
+        if let Some(gateway) = &self.gateway {
+            orders.extend(gateway.get_orders(account_id).await?);
+        }
+        Ok(orders)
     }
 
     async fn get_order(&self, order_id: &OrderId) -> Result<Option<Order>, TradeError> {
+        if let Some(gateway) = &self.gateway
+            && let Some(order) = gateway.get_order(order_id).await?
+        {
+            return Ok(Some(order));
+        }
         self.local_trade_port.get_order(order_id).await
     }
 
@@ -71,4 +131,103 @@ impl TradePort for RoutedTradePort {
             .ensure_account(account_id, initial_balance)
             .await
     }
+
+    async fn submit_order_group(
+        &self,
+        group: OrderGroup,
+        entry: Option<Order>,
+    ) -> Result<OrderId, TradeError> {
+        match self.type_for_new_order(&group.account_id.0).await?.as_str() {
+            "local" => self.local_trade_port.submit_order_group(group, entry).await,
+            account_type => Err(TradeError::BrokerIntegrationError(format!(
+                "order groups are not supported for account type {}",
+                account_type
+            ))),
+        }
+    }
+
+    async fn cancel_order_group(&self, group_id: &OrderId) -> Result<(), TradeError> {
+        self.local_trade_port.cancel_order_group(group_id).await
+    }
+
+    async fn get_order_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        self.local_trade_port.get_order_group(group_id).await
+    }
+
+    async fn get_daily_pnl(&self, account_id: &AccountId) -> Result<Vec<DailyPnl>, TradeError> {
+        self.local_trade_port.get_daily_pnl(account_id).await
+    }
+
+    async fn get_order_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<OrderHistoryRecord>, TradeError> {
+        self.local_trade_port.get_order_history(query).await
+    }
+
+    async fn get_trade_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<Trade>, TradeError> {
+        self.local_trade_port.get_trade_history(query).await
+    }
+
+    async fn deposit(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        self.local_trade_port
+            .deposit(account_id, currency, amount)
+            .await
+    }
+
+    async fn withdraw(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        self.local_trade_port
+            .withdraw(account_id, currency, amount)
+            .await
+    }
+
+    async fn transfer(
+        &self,
+        from: &AccountId,
+        to: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+        transfer_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        self.local_trade_port
+            .transfer(from, to, currency, amount, transfer_id)
+            .await
+    }
+
+    async fn convert_currency(
+        &self,
+        account_id: &AccountId,
+        from: &str,
+        to: &str,
+        amount: rust_decimal::Decimal,
+        conversion_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        self.local_trade_port
+            .convert_currency(account_id, from, to, amount, conversion_id)
+            .await
+    }
+
+    async fn get_ledger(
+        &self,
+        account_id: &AccountId,
+        offset: usize,
+        limit: usize,
+    ) -> Result<HistoryPage<LedgerEntry>, TradeError> {
+        self.local_trade_port
+            .get_ledger(account_id, offset, limit)
+            .await
+    }
 }
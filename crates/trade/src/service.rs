@@ -1,16 +1,28 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use okane_core::common::time::TimeProvider;
-use okane_core::market::entity::Candle;
+use okane_core::market::entity::{Candle, CorporateAction};
 use okane_core::market::port::Market;
+use okane_core::store::port::SystemStore;
+use okane_core::trade::cost::CostModel;
 use okane_core::trade::entity::{
-    AccountId, AccountSnapshot, Order, OrderDirection, OrderId, OrderStatus,
+    AccountId, AccountSnapshot, CashTransfer, CurrencyBalance, DailyPnl, FxSettlement, HistoryPage,
+    HistoryQuery, LedgerEntry, Order, OrderDirection, OrderGroup, OrderGroupKind,
+    OrderHistoryRecord, OrderId, OrderStatus, TimeInForce, Trade,
 };
+use okane_core::trade::fx;
+use okane_core::trade::margin::MarginModel;
 use okane_core::trade::port::{
-    AccountPort, BacktestTradePort, MatcherPort, PendingOrderPort, TradeError, TradePort,
+    AccountPort, BacktestTradePort, MatcherPort, OrderHistoryPort, PendingOrderPort, TradeError,
+    TradePort,
 };
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::RwLock;
 
+use crate::live::LiveMatchingService;
+use crate::matcher::MatcherFactory;
+use crate::session::TradingCalendar;
 use crate::trade_log::TradeLog;
 
 /// # Summary
@@ -18,7 +30,13 @@ use crate::trade_log::TradeLog;
 /// 实现了 `TradePort`，对接着最原始的逻辑 AccountManager 和 LocalMatchEngine。
 pub struct TradeService {
     account_port: Arc<dyn AccountPort>,
+    /// 默认撮合器，未配置账号成本模型或账号档案不存在时使用
     matcher: Arc<dyn MatcherPort>,
+    /// 逻辑交易账号档案来源与撮合器工厂；配置后按账号的 `CostModel` 构建撮合器，
+    /// 并按 `MarginModel` 启用保证金交易
+    account_matchers: Option<(Arc<dyn SystemStore>, MatcherFactory)>,
+    /// 已按账号构建的撮合器缓存，账号配置中的成本模型变化后重建
+    matcher_cache: RwLock<HashMap<AccountId, CachedMatcher>>,
     /// 用于获取当前标的市场行情的抽象指针 (用于模拟成交)
     market: Arc<dyn Market>,
     /// 活动订单持久化端口
@@ -27,8 +45,39 @@ pub struct TradeService {
     algo_service: RwLock<Option<Arc<crate::algo::AlgoOrderService>>>,
     /// 逻辑时钟源，回测时由 FakeClockProvider 提供，实盘为 RealTimeProvider
     time_provider: Arc<dyn TimeProvider>,
+    /// 各市场的交易时段，决定 DAY 订单的过期时刻
+    calendar: TradingCalendar,
     /// 可选的交易事件收集器 — 记录所有成交，用于回测结果提取
     trade_log: Option<Arc<TradeLog>>,
+    /// 可选的订单与成交历史仓储，保留终结订单的完整生命周期
+    order_history: Option<Arc<dyn OrderHistoryPort>>,
+    /// 实时撮合循环，仅纸面交易环境注入；回测由行情回放直接驱动 `tick`
+    live_matcher: RwLock<Option<Arc<LiveMatchingService>>>,
+    /// 已登记的保证金账户，由 `tick` 与 `maintain_accounts` 检查维持保证金；
+    /// 融券费计提日期由账户端口持久化，重启后由 `restore_margin_accounts` 按账号档案恢复登记
+    margin_accounts: RwLock<HashSet<AccountId>>,
+    /// 各账户最近写入的每日盈亏快照，用于沿用当日开盘权益并跳过未变化的写入
+    last_daily_pnl: RwLock<HashMap<AccountId, DailyPnl>>,
+    /// 已登记账户的公司行动处理进度，`tick` 与定时维护时把除权除息日已到的公司行动作用于其持仓与挂单；
+    /// 进度由账户端口持久化，重启后由 `restore_corporate_action_accounts` 恢复持仓账户的登记
+    corporate_action_marks: RwLock<HashMap<AccountId, CorporateActionMark>>,
+}
+
+/// # Summary
+/// 按账号成本模型构建的撮合器缓存项。
+struct CachedMatcher {
+    /// 构建撮合器时的成本模型，用于发现账号配置的更新
+    cost_model: CostModel,
+    matcher: Arc<dyn MatcherPort>,
+}
+
+/// # Summary
+/// 单个账户的公司行动处理进度。
+struct CorporateActionMark {
+    /// 登记时刻；账户端口中既无处理进度也无开仓时刻的标的 (旧版本建立的持仓) 从此刻起处理
+    registered_at: DateTime<Utc>,
+    /// 各标的公司行动已处理至的时刻，账户端口中持久化进度的缓存
+    processed: HashMap<String, DateTime<Utc>>,
 }
 
 impl TradeService {
@@ -40,39 +89,1282 @@ impl TradeService {
     }
 
     fn estimate_buy_funds(
-        &self,
+        matcher: &dyn MatcherPort,
         price: rust_decimal::Decimal,
         volume: rust_decimal::Decimal,
     ) -> rust_decimal::Decimal {
-        let commission = self.matcher.estimate_commission(price, volume);
+        let commission = matcher.estimate_commission(OrderDirection::Buy, price, volume);
         price * volume + commission
     }
 
+    /// # Logic
+    /// 委托按成交数量占用的冻结资金：保证金委托按提交时的保证金率冻结名义金额 (多空皆然)，
+    /// 其余委托仅买单按全额 (含费用) 冻结。
+    fn estimate_reserved_funds(
+        matcher: &dyn MatcherPort,
+        order: &Order,
+        price: rust_decimal::Decimal,
+        volume: rust_decimal::Decimal,
+    ) -> rust_decimal::Decimal {
+        match order.margin_rate {
+            Some(rate) => price * volume * rate,
+            None if order.direction == OrderDirection::Buy => {
+                Self::estimate_buy_funds(matcher, price, volume)
+            }
+            None => rust_decimal::Decimal::ZERO,
+        }
+    }
+
+    /// 读取逻辑交易账号配置；未接入账号档案或档案不存在时返回 `None`。
+    async fn account_config(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<serde_json::Value>, TradeError> {
+        let Some((system_store, _)) = &self.account_matchers else {
+            return Ok(None);
+        };
+        let profile = system_store
+            .get_account_profile(&account_id.0)
+            .await
+            .map_err(|e| {
+                TradeError::InternalError(format!("account profile lookup failed: {}", e))
+            })?;
+        Ok(profile.map(|profile| profile.config))
+    }
+
+    /// 账号的保证金模型，缺省为现金账户。
+    async fn margin_model(&self, account_id: &AccountId) -> Result<MarginModel, TradeError> {
+        match self.account_config(account_id).await? {
+            Some(config) => MarginModel::from_account_config(&config),
+            None => Ok(MarginModel::default()),
+        }
+    }
+
+    /// 账号的基础货币，未配置时为单一货币账户 (`None`)。
+    async fn base_currency(&self, account_id: &AccountId) -> Result<Option<String>, TradeError> {
+        match self.account_config(account_id).await? {
+            Some(config) => fx::base_currency_from_account_config(&config),
+            None => Ok(None),
+        }
+    }
+
+    /// # Logic
+    /// 1 单位 `from` 折合的 `to` 数量：取合成行情 `{from}{to}=X` 的最新价，
+    /// 缺失时取反向行情 `{to}{from}=X` 的倒数。
+    ///
+    /// # Returns
+    /// * `Err(TradeError::BrokerIntegrationError)` - If neither quote has a positive price.
+    async fn fx_rate(&self, from: &str, to: &str) -> Result<rust_decimal::Decimal, TradeError> {
+        if from == to {
+            return Ok(rust_decimal::Decimal::ONE);
+        }
+        if let Ok(rate) = self.latest_price(&fx::fx_symbol(from, to)).await
+            && rate > rust_decimal::Decimal::ZERO
+        {
+            return Ok(rate);
+        }
+        match self.latest_price(&fx::fx_symbol(to, from)).await {
+            Ok(rate) if rate > rust_decimal::Decimal::ZERO => Ok(rust_decimal::Decimal::ONE / rate),
+            _ => Err(TradeError::BrokerIntegrationError(format!(
+                "no fx rate available for {}/{}",
+                from, to
+            ))),
+        }
+    }
+
+    /// # Logic
+    /// 标的在账户中的结算币种：单一货币账户或挂牌货币即基础货币时为 `None` (基础货币现金)，
+    /// 否则为挂牌货币。
+    async fn settlement_currency(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+    ) -> Result<Option<String>, TradeError> {
+        let listing = fx::listing_currency(symbol);
+        Ok(self
+            .base_currency(account_id)
+            .await?
+            .filter(|base| base != listing)
+            .map(|_| listing.to_string()))
+    }
+
+    /// 以 `currency` 结算时折算为基础货币的汇率；`None` (基础货币) 无需折算。
+    async fn fx_settlement(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+    ) -> Result<Option<FxSettlement>, TradeError> {
+        let Some(currency) = currency else {
+            return Ok(None);
+        };
+        let base = self
+            .base_currency(account_id)
+            .await?
+            .ok_or_else(|| TradeError::InvalidCurrency("account has no base currency".into()))?;
+        Ok(Some(FxSettlement {
+            currency: currency.to_string(),
+            rate: self.fx_rate(currency, &base).await?,
+        }))
+    }
+
+    /// # Logic
+    /// 解析出入金与兑换请求中的币种：缺省或等于基础货币时为 `None` (基础货币现金)；
+    /// 单一货币账户不接受其他币种。
+    async fn cash_currency(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+    ) -> Result<Option<String>, TradeError> {
+        let Some(currency) = currency else {
+            return Ok(None);
+        };
+        let currency = fx::normalize_currency(currency)?;
+        match self.base_currency(account_id).await? {
+            Some(base) if base == currency => Ok(None),
+            Some(_) => Ok(Some(currency)),
+            None => Err(TradeError::InvalidCurrency(format!(
+                "account {} is single-currency and cannot hold {}",
+                account_id.0, currency
+            ))),
+        }
+    }
+
+    /// 以基础货币计的现金变动额，用于平移当日开盘权益。
+    async fn base_amount(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<rust_decimal::Decimal, TradeError> {
+        Ok(self
+            .fx_settlement(account_id, currency)
+            .await?
+            .map_or(amount, |settlement| amount * settlement.rate))
+    }
+
+    /// # Logic
+    /// 1. 未配置账号成本模型时返回默认撮合器。
+    /// 2. 读取账号档案；档案尚不存在时返回默认撮合器且不缓存。
+    /// 3. 缓存的撮合器构建自相同的 `config.cost_model` 时直接返回；否则 (首次使用或账号配置
+    ///    已更新) 按当前成本模型重建并替换缓存项。
+    ///
+    /// # Arguments
+    /// * `account_id` - Logical account the order belongs to.
+    ///
+    /// # Returns
+    /// * `Err(TradeError::InvalidCostModel)` - If the account config carries a malformed cost model.
+    async fn matcher_for(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Arc<dyn MatcherPort>, TradeError> {
+        let Some((_, factory)) = &self.account_matchers else {
+            return Ok(self.matcher.clone());
+        };
+        let Some(config) = self.account_config(account_id).await? else {
+            return Ok(self.matcher.clone());
+        };
+        let cost_model = CostModel::from_account_config(&config)?;
+        if let Some(cached) = self
+            .matcher_cache
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("matcher cache lock poisoned: {}", e)))?
+            .get(account_id)
+            && cached.cost_model == cost_model
+        {
+            return Ok(cached.matcher.clone());
+        }
+
+        let matcher = factory(&cost_model);
+        self.matcher_cache
+            .write()
+            .map_err(|e| TradeError::InternalError(format!("matcher cache lock poisoned: {}", e)))?
+            .insert(
+                account_id.clone(),
+                CachedMatcher {
+                    cost_model,
+                    matcher: matcher.clone(),
+                },
+            );
+        Ok(matcher)
+    }
+
+    async fn latest_price(&self, symbol: &str) -> Result<rust_decimal::Decimal, TradeError> {
+        let stock = self.market.get_stock(symbol).await.map_err(|e| {
+            TradeError::BrokerIntegrationError(format!("Failed to get market data: {}", e))
+        })?;
+        stock
+            .current_price()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .ok_or_else(|| {
+                TradeError::InternalError(format!("No latest price available for stock {}", symbol))
+            })
+    }
+
+    /// # Logic
+    /// 1. 按最新价计算持仓市值与未实现盈亏，总权益 = 现金 + 冻结 + 持仓市值。
+    /// 2. 多币种账户按汇率行情把各币种现金、持仓市值与未实现盈亏折算为基础货币，
+    ///    并填充分币种明细；持仓本身的最新价与未实现盈亏保持挂牌货币。
+    /// 3. 保证金账户同时核算保证金状态，权益跌破维持保证金要求时记录追保告警。
+    ///
+    /// # Returns
+    /// 估值后的快照与各持仓以基础货币计的最新价 (与 `positions` 一一对应)。
     async fn mark_to_market_snapshot(
         &self,
         mut snapshot: AccountSnapshot,
+        margin: &MarginModel,
+    ) -> Result<(AccountSnapshot, Vec<rust_decimal::Decimal>), TradeError> {
+        let base = self.base_currency(&snapshot.account_id).await?;
+        let mut rates: HashMap<String, rust_decimal::Decimal> = HashMap::new();
+        if let Some(base) = &base {
+            let currencies: BTreeSet<String> = snapshot
+                .positions
+                .iter()
+                .map(|position| fx::listing_currency(&position.symbol).to_string())
+                .chain(snapshot.currencies.iter().map(|b| b.currency.clone()))
+                .collect();
+            for currency in currencies {
+                let rate = self.fx_rate(&currency, base).await?;
+                rates.insert(currency, rate);
+            }
+        }
+        let rate_of = |currency: &str| {
+            rates
+                .get(currency)
+                .copied()
+                .unwrap_or(rust_decimal::Decimal::ONE)
+        };
+
+        let mut prices = Vec::with_capacity(snapshot.positions.len());
+        let mut unrealized_pnl = rust_decimal::Decimal::ZERO;
+        for position in &mut snapshot.positions {
+            let price = self.latest_price(&position.symbol).await?;
+            position.mark(price);
+            let rate = rate_of(fx::listing_currency(&position.symbol));
+            unrealized_pnl += position.unrealized_pnl * rate;
+            prices.push(price * rate);
+        }
+        snapshot.unrealized_pnl = unrealized_pnl;
+        let positions_market_value: rust_decimal::Decimal = snapshot
+            .positions
+            .iter()
+            .zip(&prices)
+            .map(|(position, price)| position.volume * *price)
+            .sum();
+
+        let mut cash = snapshot.available_balance + snapshot.frozen_balance;
+        let mut frozen = snapshot.frozen_balance;
+        if let Some(base) = &base {
+            let mut breakdown: BTreeMap<String, CurrencyBalance> = BTreeMap::new();
+            breakdown.insert(
+                base.clone(),
+                CurrencyBalance {
+                    currency: base.clone(),
+                    available_balance: snapshot.available_balance,
+                    frozen_balance: snapshot.frozen_balance,
+                    ..CurrencyBalance::default()
+                },
+            );
+            for balance in std::mem::take(&mut snapshot.currencies) {
+                let rate = rate_of(&balance.currency);
+                cash += (balance.available_balance + balance.frozen_balance) * rate;
+                frozen += balance.frozen_balance * rate;
+                breakdown.insert(balance.currency.clone(), balance);
+            }
+            for position in &snapshot.positions {
+                let currency = fx::listing_currency(&position.symbol);
+                let value =
+                    position.volume * position.last_price.unwrap_or(rust_decimal::Decimal::ZERO);
+                breakdown
+                    .entry(currency.to_string())
+                    .or_insert_with(|| CurrencyBalance {
+                        currency: currency.to_string(),
+                        ..CurrencyBalance::default()
+                    })
+                    .market_value += value;
+            }
+            for balance in breakdown.values_mut() {
+                let rate = rate_of(&balance.currency);
+                balance.fx_rate = Some(rate);
+                balance.base_equity =
+                    (balance.available_balance + balance.frozen_balance + balance.market_value)
+                        * rate;
+            }
+            snapshot.currencies = breakdown.into_values().collect();
+            snapshot.base_currency = Some(base.clone());
+        }
+        snapshot.total_equity = cash + positions_market_value;
+
+        if margin.is_margin() {
+            let status = margin.status(
+                snapshot.total_equity,
+                frozen,
+                snapshot
+                    .positions
+                    .iter()
+                    .zip(&prices)
+                    .map(|(position, price)| (position.volume, *price)),
+            );
+            if status.margin_call {
+                tracing::warn!(
+                    "Margin call on account {}: equity {} below maintenance requirement {}",
+                    snapshot.account_id.0,
+                    snapshot.total_equity,
+                    status.maintenance_requirement
+                );
+            }
+            snapshot.margin = Some(status);
+        }
+        Ok((snapshot, prices))
+    }
+
+    /// # Logic
+    /// 按估值后的快照生成当日盈亏：当日开盘权益沿用当日已有记录；否则取前一记录日的收盘权益，
+    /// 无任何记录时取本次权益。
+    async fn daily_pnl_of(&self, snapshot: &AccountSnapshot) -> Result<DailyPnl, TradeError> {
+        let date = self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .date_naive();
+        let last = self
+            .last_daily_pnl
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("daily pnl lock poisoned: {}", e)))?
+            .get(&snapshot.account_id)
+            .cloned();
+        let opening_equity = match &last {
+            Some(record) if record.date == date => record.opening_equity,
+            _ => {
+                let history = self
+                    .account_port
+                    .list_daily_pnl(&snapshot.account_id)
+                    .await?;
+                match history.iter().rev().find(|record| record.date <= date) {
+                    Some(record) if record.date == date => record.opening_equity,
+                    Some(record) => record.total_equity,
+                    None => snapshot.total_equity,
+                }
+            }
+        };
+        Ok(DailyPnl {
+            account_id: snapshot.account_id.clone(),
+            date,
+            opening_equity,
+            total_equity: snapshot.total_equity,
+            realized_pnl: snapshot.realized_pnl,
+            unrealized_pnl: snapshot.unrealized_pnl,
+            total_commission: snapshot.total_commission,
+            day_pnl: snapshot.total_equity - opening_equity,
+        })
+    }
+
+    /// 填充快照的当日盈亏并覆盖当日快照；与上次写入相同则跳过。
+    async fn record_daily_pnl(&self, snapshot: &mut AccountSnapshot) -> Result<(), TradeError> {
+        let record = self.daily_pnl_of(snapshot).await?;
+        snapshot.day_pnl = record.day_pnl;
+        let unchanged = self
+            .last_daily_pnl
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("daily pnl lock poisoned: {}", e)))?
+            .get(&snapshot.account_id)
+            == Some(&record);
+        if unchanged {
+            return Ok(());
+        }
+        self.account_port.save_daily_pnl(&record).await?;
+        self.last_daily_pnl
+            .write()
+            .map_err(|e| TradeError::InternalError(format!("daily pnl lock poisoned: {}", e)))?
+            .insert(record.account_id.clone(), record);
+        Ok(())
+    }
+
+    /// 按最新估值落地账户的当日盈亏快照，返回估值后的快照。
+    async fn settle_daily_pnl(
+        &self,
+        account_id: &AccountId,
     ) -> Result<AccountSnapshot, TradeError> {
-        let mut positions_market_value = rust_decimal::Decimal::ZERO;
-        for position in &snapshot.positions {
-            let stock = self.market.get_stock(&position.symbol).await.map_err(|e| {
-                TradeError::BrokerIntegrationError(format!("Failed to get market data: {}", e))
-            })?;
-            let latest_price = stock
-                .current_price()
-                .map_err(|e| TradeError::InternalError(e.to_string()))?
-                .ok_or_else(|| {
-                    TradeError::InternalError(format!(
-                        "No latest price available for stock {}",
-                        position.symbol
-                    ))
-                })?;
-            positions_market_value += position.volume * latest_price;
-        }
-        snapshot.total_equity =
-            snapshot.available_balance + snapshot.frozen_balance + positions_market_value;
+        let margin = self.margin_model(account_id).await?;
+        let snapshot = self.account_port.snapshot(account_id).await?;
+        let (mut snapshot, _) = self.mark_to_market_snapshot(snapshot, &margin).await?;
+        self.record_daily_pnl(&mut snapshot).await?;
         Ok(snapshot)
     }
 
+    /// # Logic
+    /// 出入金不计入当日盈亏：出入金前已经以变动前的估值落地当日快照，
+    /// 变动后把当日快照的开盘权益与总权益一并平移 `net_flow`。
+    async fn shift_opening_equity(
+        &self,
+        account_id: &AccountId,
+        net_flow: rust_decimal::Decimal,
+    ) -> Result<(), TradeError> {
+        let date = self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .date_naive();
+        let Some(mut record) = self
+            .last_daily_pnl
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("daily pnl lock poisoned: {}", e)))?
+            .get(account_id)
+            .filter(|record| record.date == date)
+            .cloned()
+        else {
+            return Ok(());
+        };
+        record.opening_equity += net_flow;
+        record.total_equity += net_flow;
+        self.account_port.save_daily_pnl(&record).await?;
+        self.last_daily_pnl
+            .write()
+            .map_err(|e| TradeError::InternalError(format!("daily pnl lock poisoned: {}", e)))?
+            .insert(account_id.clone(), record);
+        Ok(())
+    }
+
+    fn validate_cash_amount(amount: rust_decimal::Decimal) -> Result<(), TradeError> {
+        if amount <= rust_decimal::Decimal::ZERO {
+            return Err(TradeError::InvalidCashMovement(
+                "amount must be positive".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 出金或转出前按最新估值落地当日快照；保证金账户的出金额另不得超过剩余保证金额度。
+    async fn ensure_withdrawable(
+        &self,
+        account_id: &AccountId,
+        amount: rust_decimal::Decimal,
+    ) -> Result<(), TradeError> {
+        let snapshot = self.settle_daily_pnl(account_id).await?;
+        if let Some(status) = &snapshot.margin
+            && amount > status.excess_margin
+        {
+            return Err(TradeError::InsufficientFunds {
+                required: amount,
+                actual: status.excess_margin.max(rust_decimal::Decimal::ZERO),
+            });
+        }
+        Ok(())
+    }
+
+    /// 登记保证金账户；从未计提过融券费的账户自登记当日起计提。
+    async fn track_margin_account(&self, account_id: &AccountId) -> Result<(), TradeError> {
+        let inserted = self
+            .margin_accounts
+            .write()
+            .map_err(|e| TradeError::InternalError(format!("margin account lock poisoned: {}", e)))?
+            .insert(account_id.clone());
+        if inserted
+            && self
+                .account_port
+                .borrow_fee_accrued_on(account_id)
+                .await?
+                .is_none()
+        {
+            let today = self
+                .time_provider
+                .now()
+                .map_err(|e| TradeError::InternalError(e.to_string()))?
+                .date_naive();
+            self.account_port
+                .accrue_borrow_fee(account_id, rust_decimal::Decimal::ZERO, today)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 启动时按账号档案恢复本地保证金账户的登记，使维持保证金检查与融券费计提 (含停机期间)
+    /// 在重启后继续进行；单个账户恢复失败仅记录日志。
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - 恢复登记的保证金账户数量。
+    /// * `Err(TradeError)` - 账号档案无法读取。
+    pub async fn restore_margin_accounts(&self) -> Result<usize, TradeError> {
+        let Some((system_store, _)) = &self.account_matchers else {
+            return Ok(0);
+        };
+        let profiles = system_store.list_account_profiles().await.map_err(|e| {
+            TradeError::InternalError(format!("account profile listing failed: {}", e))
+        })?;
+        let mut restored = 0;
+        for profile in profiles {
+            if profile.account_type != "local" {
+                continue;
+            }
+            let account_id = AccountId(profile.id);
+            let result = match MarginModel::from_account_config(&profile.config) {
+                Ok(margin) if margin.is_margin() => self
+                    .track_margin_account(&account_id)
+                    .await
+                    .map(|()| restored += 1),
+                Ok(_) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                tracing::error!("Failed to restore margin account {}: {}", account_id.0, e);
+            }
+        }
+        Ok(restored)
+    }
+
+    /// # Logic
+    /// 启动时登记账户端口中全部持有持仓的账户，使其在无委托、无查询时同样由定时维护
+    /// 按持久化的处理进度 (或开仓时刻) 补处理停机期间除权除息的公司行动；单个账户失败仅记录日志。
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - 登记的持仓账户数量。
+    /// * `Err(TradeError)` - 账户列表无法读取。
+    pub async fn restore_corporate_action_accounts(&self) -> Result<usize, TradeError> {
+        let mut restored = 0;
+        for account_id in self.account_port.list_accounts().await? {
+            let result = match self.account_port.snapshot(&account_id).await {
+                Ok(snapshot) if !snapshot.positions.is_empty() => self
+                    .track_corporate_actions(&account_id)
+                    .map(|()| restored += 1),
+                Ok(_) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                tracing::error!(
+                    "Failed to restore corporate action tracking of account {}: {}",
+                    account_id.0,
+                    e
+                );
+            }
+        }
+        Ok(restored)
+    }
+
+    /// # Logic
+    /// 定时维护入口，由应用按固定间隔调用，单个账户失败仅记录日志：
+    /// 1. 已登记账户持仓与挂单涉及的标的，应用除权除息日已到的公司行动 (持仓无挂单时不会有 `tick` 驱动)。
+    /// 2. 已登记的保证金账户计提融券费并检查维持保证金 (必要时强制平仓)。
+    /// 3. 账户中心的全部账户按最新估值落地当日盈亏快照，包括本进程尚未交易过的账户。
+    pub async fn maintain_accounts(&self) -> Result<(), TradeError> {
+        for symbol in self.tracked_symbols().await? {
+            if let Err(e) = self.apply_corporate_actions(&symbol).await {
+                tracing::error!("Failed to apply corporate actions of {}: {}", symbol, e);
+            }
+        }
+
+        let margin_accounts: Vec<AccountId> = self
+            .margin_accounts
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("margin account lock poisoned: {}", e)))?
+            .iter()
+            .cloned()
+            .collect();
+        self.maintain_margin_accounts(margin_accounts).await;
+
+        for account_id in self.account_port.list_accounts().await? {
+            if let Err(e) = self.settle_daily_pnl(&account_id).await {
+                tracing::error!(
+                    "Failed to record daily pnl of account {}: {}",
+                    account_id.0,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// 已登记账户持仓或挂单涉及的标的，不依赖是否有行情驱动 `tick`。
+    async fn tracked_symbols(&self) -> Result<BTreeSet<String>, TradeError> {
+        let accounts: Vec<AccountId> = self
+            .corporate_action_marks
+            .read()
+            .map_err(|e| {
+                TradeError::InternalError(format!("corporate action lock poisoned: {}", e))
+            })?
+            .keys()
+            .cloned()
+            .collect();
+        let mut symbols = BTreeSet::new();
+        for account_id in accounts {
+            let snapshot = self.account_port.snapshot(&account_id).await?;
+            symbols.extend(snapshot.positions.into_iter().map(|p| p.symbol));
+            symbols.extend(
+                self.pending_port
+                    .get_by_account(&account_id)
+                    .await?
+                    .into_iter()
+                    .map(|order| order.symbol),
+            );
+        }
+        Ok(symbols)
+    }
+
+    /// 逐一检查保证金账户的维持保证金；单个账户失败不影响其余账户。
+    async fn maintain_margin_accounts(&self, account_ids: Vec<AccountId>) {
+        for account_id in account_ids {
+            let result = match self.margin_model(&account_id).await {
+                Ok(margin) if margin.is_margin() => {
+                    self.maintain_margin(&account_id, &margin).await
+                }
+                Ok(_) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                tracing::error!(
+                    "Failed to maintain margin of account {}: {}",
+                    account_id.0,
+                    e
+                );
+            }
+        }
+    }
+
+    /// 登记账户，此后除权除息的公司行动在 `tick` 与定时维护时作用于其持仓与挂单。
+    fn track_corporate_actions(&self, account_id: &AccountId) -> Result<(), TradeError> {
+        let now = self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        self.corporate_action_marks
+            .write()
+            .map_err(|e| {
+                TradeError::InternalError(format!("corporate action lock poisoned: {}", e))
+            })?
+            .entry(account_id.clone())
+            .or_insert_with(|| CorporateActionMark {
+                registered_at: now,
+                processed: HashMap::new(),
+            });
+        Ok(())
+    }
+
+    /// # Logic
+    /// 账户在该标的上公司行动已处理至的时刻：优先取缓存，其次取账户端口持久化的进度
+    /// (含开仓时刻)，均无时取登记时刻。
+    async fn corporate_action_since(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+    ) -> Result<Option<DateTime<Utc>>, TradeError> {
+        let cached = self
+            .corporate_action_marks
+            .read()
+            .map_err(|e| {
+                TradeError::InternalError(format!("corporate action lock poisoned: {}", e))
+            })?
+            .get(account_id)
+            .map(|mark| (mark.processed.get(symbol).copied(), mark.registered_at));
+        let Some((processed, registered_at)) = cached else {
+            return Ok(None);
+        };
+        if processed.is_some() {
+            return Ok(processed);
+        }
+        let persisted = self
+            .account_port
+            .corporate_actions_processed_until(account_id, symbol)
+            .await?;
+        Ok(Some(persisted.unwrap_or_else(|| {
+            tracing::debug!(
+                "No corporate action progress for {} on account {}, processing from {}",
+                symbol,
+                account_id.0,
+                registered_at
+            );
+            registered_at
+        })))
+    }
+
+    /// # Logic
+    /// 1. 取各登记账户在该标的上已处理至的时刻 (重启后由账户端口持久化的进度续接，停机期间的
+    ///    公司行动同样补处理)，向行情查询其后至当前时刻除权除息的公司行动。
+    /// 2. 按除权除息日顺序交由账户端口作用于持仓 (端口保证幂等)，成功后推进并持久化该账户的
+    ///    处理进度；单个账户失败不推进进度，下次 `tick` 重试。
+    async fn apply_corporate_actions(&self, symbol: &str) -> Result<(), TradeError> {
+        let now = self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        let accounts: Vec<AccountId> = self
+            .corporate_action_marks
+            .read()
+            .map_err(|e| {
+                TradeError::InternalError(format!("corporate action lock poisoned: {}", e))
+            })?
+            .keys()
+            .cloned()
+            .collect();
+        let mut pending = Vec::new();
+        for account_id in accounts {
+            match self.corporate_action_since(&account_id, symbol).await {
+                Ok(Some(since)) if since < now => pending.push((account_id, since)),
+                Ok(_) => {}
+                Err(e) => tracing::error!(
+                    "Failed to load corporate action progress of {} on account {}: {}",
+                    symbol,
+                    account_id.0,
+                    e
+                ),
+            }
+        }
+        let Some(earliest) = pending.iter().map(|(_, since)| *since).min() else {
+            return Ok(());
+        };
+
+        let stock = self.market.get_stock(symbol).await.map_err(|e| {
+            TradeError::BrokerIntegrationError(format!("Failed to get market data: {}", e))
+        })?;
+        let mut actions = stock
+            .corporate_actions(earliest, now)
+            .await
+            .map_err(|e| TradeError::BrokerIntegrationError(e.to_string()))?;
+        actions.sort_by_key(|action| action.ex_date);
+
+        for (account_id, since) in pending {
+            let due = actions
+                .iter()
+                .filter(|action| action.ex_date > since && action.ex_date <= now);
+            let result = match self
+                .apply_account_corporate_actions(&account_id, symbol, due)
+                .await
+            {
+                Ok(()) => {
+                    self.account_port
+                        .mark_corporate_actions_processed(&account_id, symbol, now)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => {
+                    if let Some(mark) = self
+                        .corporate_action_marks
+                        .write()
+                        .map_err(|e| {
+                            TradeError::InternalError(format!(
+                                "corporate action lock poisoned: {}",
+                                e
+                            ))
+                        })?
+                        .get_mut(&account_id)
+                    {
+                        mark.processed.insert(symbol.to_string(), now);
+                    }
+                }
+                Err(e) => tracing::error!(
+                    "Failed to apply corporate actions of {} on account {}: {}",
+                    symbol,
+                    account_id.0,
+                    e
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// 按除权除息日顺序把公司行动作用于单个账户，分红以该标的在账户中的结算币种入账。
+    async fn apply_account_corporate_actions(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+        actions: impl Iterator<Item = &CorporateAction>,
+    ) -> Result<(), TradeError> {
+        let currency = self.settlement_currency(account_id, symbol).await?;
+        let settlement = self.fx_settlement(account_id, currency.as_deref()).await?;
+        for action in actions {
+            if let Some(entry) = self
+                .account_port
+                .apply_corporate_action(account_id, action, settlement.as_ref())
+                .await?
+            {
+                tracing::info!(
+                    "Applied {:?} of {} on account {}",
+                    entry.action,
+                    symbol,
+                    account_id.0
+                );
+            }
+            if let Some(ratio) = action.split_ratio() {
+                self.adjust_pending_for_split(account_id, symbol, ratio, action.ex_date)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 按拆股比例调整账户在该标的上除权日前提交的挂单，调整标记随订单持久化，重复执行不会重复调整。
+    async fn adjust_pending_for_split(
+        &self,
+        account_id: &AccountId,
+        symbol: &str,
+        ratio: rust_decimal::Decimal,
+        ex_date: DateTime<Utc>,
+    ) -> Result<(), TradeError> {
+        let ex_date_ms = ex_date.timestamp_millis();
+        for mut order in self.pending_port.get_by_account(account_id).await? {
+            if order.symbol == symbol && order.apply_split(ratio, ex_date_ms) {
+                tracing::info!(
+                    "Adjusted pending order {} of {} for split ratio {}",
+                    order.id.0,
+                    symbol,
+                    ratio
+                );
+                self.pending_port.save(order).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 1. 按当前空头市值计提自持久化的计提日期以来逐日的融券费，扣费与计提日期一并落地。
+    /// 2. 按最新行情估值；权益跌破维持保证金要求时强制平仓。
+    async fn maintain_margin(
+        &self,
+        account_id: &AccountId,
+        margin: &MarginModel,
+    ) -> Result<(), TradeError> {
+        self.track_margin_account(account_id).await?;
+        let today = self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .date_naive();
+        let accrued_on = self.account_port.borrow_fee_accrued_on(account_id).await?;
+
+        let snapshot = self.account_port.snapshot(account_id).await?;
+        let (mut snapshot, mut prices) = self.mark_to_market_snapshot(snapshot, margin).await?;
+        let days = accrued_on.map_or(0, |day| (today - day).num_days());
+        let short_market_value = snapshot
+            .margin
+            .as_ref()
+            .map_or(rust_decimal::Decimal::ZERO, |status| {
+                status.short_market_value
+            });
+        let fee = margin.borrow_fee(short_market_value, days);
+        if accrued_on.is_none() || days > 0 {
+            self.account_port
+                .accrue_borrow_fee(account_id, fee, today)
+                .await?;
+        }
+        if fee > rust_decimal::Decimal::ZERO {
+            tracing::info!(
+                "Charged borrow fee {} for {} day(s) on account {}",
+                fee,
+                days,
+                account_id.0
+            );
+            let refreshed = self.account_port.snapshot(account_id).await?;
+            (snapshot, prices) = self.mark_to_market_snapshot(refreshed, margin).await?;
+        }
+
+        if snapshot
+            .margin
+            .as_ref()
+            .is_some_and(|status| status.margin_call)
+        {
+            self.liquidate(snapshot, &prices, margin).await?;
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 强制平仓：
+    /// 1. 撤销在途开仓委托，退回占用的保证金。
+    /// 2. 按持仓市值从大到小以市价单逐一平仓，直至剩余敞口的维持保证金要求不超过权益。
+    async fn liquidate(
+        &self,
+        snapshot: AccountSnapshot,
+        prices: &[rust_decimal::Decimal],
+        margin: &MarginModel,
+    ) -> Result<(), TradeError> {
+        let account_id = snapshot.account_id.clone();
+        for order in self.pending_port.get_by_account(&account_id).await? {
+            if order
+                .margin_rate
+                .is_some_and(|rate| rate > rust_decimal::Decimal::ZERO)
+                && let Some(order) = self.cancel_pending(&order.id).await?
+            {
+                self.advance_group_logged(&order).await;
+            }
+        }
+
+        let mut positions: Vec<_> = snapshot
+            .positions
+            .iter()
+            .zip(prices)
+            .filter(|(position, _)| !position.volume.is_zero())
+            .collect();
+        positions
+            .sort_by_key(|(position, price)| std::cmp::Reverse(position.volume.abs() * **price));
+        let mut gross_exposure: rust_decimal::Decimal = positions
+            .iter()
+            .map(|(position, price)| position.volume.abs() * **price)
+            .sum();
+        let now_ms = self.now_ms()?;
+        for (position, price) in positions {
+            if snapshot.total_equity >= gross_exposure * margin.maintenance_margin_rate {
+                break;
+            }
+            let direction = if position.volume.is_sign_positive() {
+                OrderDirection::Sell
+            } else {
+                OrderDirection::Buy
+            };
+            let order = Order::new(
+                OrderId(format!(
+                    "liq-{}-{}-{}",
+                    account_id.0, position.symbol, now_ms
+                )),
+                account_id.clone(),
+                position.symbol.clone(),
+                direction,
+                None,
+                position.volume.abs(),
+                now_ms,
+            );
+            match self.submit_order(order).await {
+                Ok(order_id) => tracing::warn!(
+                    "Forced liquidation of {} {} on account {} (order {})",
+                    position.volume,
+                    position.symbol,
+                    account_id.0,
+                    order_id.0
+                ),
+                Err(e) => tracing::error!(
+                    "Forced liquidation of {} on account {} failed: {}",
+                    position.symbol,
+                    account_id.0,
+                    e
+                ),
+            }
+            gross_exposure -= position.volume.abs() * *price;
+        }
+        Ok(())
+    }
+
+    fn current_algo_service(
+        &self,
+    ) -> Result<Option<Arc<crate::algo::AlgoOrderService>>, TradeError> {
+        Ok(self
+            .algo_service
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("algo service lock poisoned: {}", e)))?
+            .clone())
+    }
+
+    fn now_ms(&self) -> Result<i64, TradeError> {
+        Ok(self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .timestamp_millis())
+    }
+
+    /// # Logic
+    /// 提交时按账户类型占用资金：
+    /// 保证金账户的平仓委托 (数量不超过持仓扣除同向挂单后的剩余) 不占用购买力，
+    /// 开仓 (多空皆然) 按初始保证金率占用购买力；
+    /// 现金账户仅多头买单冻结全额现金。
+    ///
+    /// # Returns
+    /// * `Err(TradeError::InsufficientFunds)` - If cash or buying power is insufficient.
+    async fn reserve_funds(
+        &self,
+        order: &mut Order,
+        matcher: &dyn MatcherPort,
+        est_price: rust_decimal::Decimal,
+    ) -> Result<(), TradeError> {
+        let margin = self.margin_model(&order.account_id).await?;
+        if margin.is_margin() {
+            self.track_margin_account(&order.account_id).await?;
+            let snapshot = self.account_port.snapshot(&order.account_id).await?;
+            let held = snapshot
+                .positions
+                .iter()
+                .filter(|p| p.symbol == order.symbol)
+                .map(|p| p.volume)
+                .sum::<rust_decimal::Decimal>();
+            // 同向挂单已占用的可平仓数量，与风控 `no_short` 的可卖数量一致
+            let pending_same_side = self
+                .pending_port
+                .get_by_account(&order.account_id)
+                .await?
+                .iter()
+                .filter(|p| {
+                    p.id != order.id && p.symbol == order.symbol && p.direction == order.direction
+                })
+                .map(|p| p.volume - p.filled_volume)
+                .sum::<rust_decimal::Decimal>();
+            let closing = match order.direction {
+                OrderDirection::Buy => held.is_sign_negative(),
+                OrderDirection::Sell => held.is_sign_positive(),
+            } && order.volume <= held.abs() - pending_same_side;
+            if closing {
+                order.margin_rate = Some(rust_decimal::Decimal::ZERO);
+            } else {
+                order.margin_rate = Some(margin.initial_margin_rate);
+                let (snapshot, _) = self.mark_to_market_snapshot(snapshot, &margin).await?;
+                let excess_margin = snapshot
+                    .margin
+                    .map_or(rust_decimal::Decimal::ZERO, |status| status.excess_margin);
+                // 剩余保证金额度以基础货币计，折算为委托的结算币种
+                let excess_margin = match self
+                    .fx_settlement(&order.account_id, order.currency.as_deref())
+                    .await?
+                {
+                    Some(settlement) => excess_margin / settlement.rate,
+                    None => excess_margin,
+                };
+                let required =
+                    Self::estimate_reserved_funds(matcher, order, est_price, order.volume);
+                self.account_port
+                    .freeze_margin(
+                        &order.account_id,
+                        order.currency.as_deref(),
+                        required,
+                        excess_margin,
+                    )
+                    .await?;
+            }
+        } else if order.direction == OrderDirection::Buy {
+            let est_req_funds = Self::estimate_buy_funds(matcher, est_price, order.volume);
+            self.account_port
+                .freeze_funds(&order.account_id, order.currency.as_deref(), est_req_funds)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// 将订单的当前状态写入订单历史；订单本身已处理，失败只记录日志不影响调用方。
+    async fn record_order_history(&self, order: &Order) {
+        let Some(history) = &self.order_history else {
+            return;
+        };
+        let result = match self.now_ms() {
+            Ok(now_ms) => history.record_order(order, now_ms).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to record history of order {}: {}", order.id.0, e);
+        }
+    }
+
+    /// 订单终止 (撤单、过期) 时，按冻结时的预估单价退回未成交部分的冻结资金。
+    async fn release_frozen_funds(
+        &self,
+        matcher: &dyn MatcherPort,
+        order: &Order,
+        price: rust_decimal::Decimal,
+    ) -> Result<(), TradeError> {
+        let remaining_volume = order.volume - order.filled_volume;
+        let amount = Self::estimate_reserved_funds(matcher, order, price, remaining_volume);
+        if amount <= rust_decimal::Decimal::ZERO {
+            return Ok(());
+        }
+        self.account_port
+            .unfreeze_funds(&order.account_id, order.currency.as_deref(), amount)
+            .await
+    }
+
+    /// 移除已到期的挂单并退回冻结资金。
+    async fn expire_order(&self, order: Order) -> Result<(), TradeError> {
+        let Some(mut order) = self.pending_port.remove(&order.id).await? else {
+            return Ok(());
+        };
+        order.status = OrderStatus::Expired;
+        if let Some(price) = order.reserve_price() {
+            let matcher = self.matcher_for(&order.account_id).await?;
+            self.release_frozen_funds(matcher.as_ref(), &order, price)
+                .await?;
+        }
+        self.record_order_history(&order).await;
+        tracing::info!(
+            "Order {} expired with {} of {} filled",
+            order.id.0,
+            order.filled_volume,
+            order.volume
+        );
+        self.advance_group_logged(&order).await;
+        Ok(())
+    }
+
+    /// 从活动订单中移除并退回冻结资金；订单不存在或已终结时返回 `None`。
+    async fn cancel_pending(&self, order_id: &OrderId) -> Result<Option<Order>, TradeError> {
+        let Some(mut order) = self.pending_port.remove(order_id).await? else {
+            return Ok(None);
+        };
+        order.status = OrderStatus::Canceled;
+        if let Some(price) = order.reserve_price() {
+            let matcher = self.matcher_for(&order.account_id).await?;
+            self.release_frozen_funds(matcher.as_ref(), &order, price)
+                .await?;
+        }
+        self.record_order_history(&order).await;
+        Ok(Some(order))
+    }
+
+    /// # Logic
+    /// 校验订单组：同账户同标的、ID 不重复、子单非 IOC / FOK 且触发参数合法；
+    /// 括号单的入场单须与 `parent_id` 一致，子单方向与入场单相反。
+    fn validate_group(group: &OrderGroup, entry: Option<&Order>) -> Result<(), TradeError> {
+        let invalid = |msg: &str| Err(TradeError::InvalidOrder(msg.to_string()));
+        if group.legs.len() < 2 {
+            return invalid("order group needs at least two legs");
+        }
+        let entry = match (group.kind, entry) {
+            (OrderGroupKind::Oco, None) if group.parent_id.is_none() => None,
+            (OrderGroupKind::Bracket, Some(entry))
+                if group.parent_id.as_ref() == Some(&entry.id) =>
+            {
+                Some(entry)
+            }
+            _ => return invalid("bracket orders need an entry order matching parent_id"),
+        };
+        let mut ids: Vec<&OrderId> = group.legs.iter().map(|leg| &leg.id).collect();
+        ids.extend(entry.map(|entry| &entry.id));
+        ids.sort_by_key(|id| &id.0);
+        ids.dedup();
+        if ids.len() != group.legs.len() + usize::from(entry.is_some()) {
+            return invalid("order ids in a group must be distinct");
+        }
+        for order in group.legs.iter().chain(entry) {
+            if order.account_id != group.account_id || order.symbol != group.symbol {
+                return invalid("orders in a group must share account and symbol");
+            }
+        }
+        for leg in &group.legs {
+            if leg.time_in_force.is_immediate() {
+                return invalid("group legs cannot be ioc or fok");
+            }
+            if leg.trigger.as_ref().is_some_and(|t| !t.is_valid()) {
+                return invalid("stop price and trail offset must be positive");
+            }
+            if entry.is_some_and(|entry| entry.direction == leg.direction) {
+                return invalid("bracket legs must close the entry direction");
+            }
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 提交订单组的全部子单。若先提交的子单立即成交导致订单组被撤销，则停止提交；
+    /// 任一子单提交失败时撤销已提交的子单并移除订单组。
+    async fn activate_group(&self, mut group: OrderGroup) -> Result<(), TradeError> {
+        group.activated = true;
+        self.pending_port.save_group(group.clone()).await?;
+
+        for leg in &group.legs {
+            if self.pending_port.get_group(&group.id).await?.is_none() {
+                break;
+            }
+            let mut leg = leg.clone();
+            leg.group_id = Some(group.id.clone());
+            if let Err(e) = self.submit_order(leg).await {
+                self.pending_port.remove_group(&group.id).await?;
+                for leg in &group.legs {
+                    self.cancel_pending(&leg.id).await?;
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// # Logic
+    /// 订单成交或终结后推进所属订单组：
+    /// 1. 括号单入场单终结：有成交则按成交量激活止盈止损子单，否则移除订单组。
+    /// 2. 子单出现成交或终结：移除订单组并撤销其余子单 (退回冻结资金)。
+    async fn advance_group(&self, order: &Order) -> Result<(), TradeError> {
+        let Some(group_id) = &order.group_id else {
+            return Ok(());
+        };
+        let Some(mut group) = self.pending_port.get_group(group_id).await? else {
+            return Ok(());
+        };
+
+        if group.parent_id.as_ref() == Some(&order.id) {
+            if Self::is_active_order_status(order.status) || group.activated {
+                return Ok(());
+            }
+            if order.filled_volume <= rust_decimal::Decimal::ZERO {
+                self.pending_port.remove_group(group_id).await?;
+                return Ok(());
+            }
+            for leg in &mut group.legs {
+                leg.volume = order.filled_volume;
+            }
+            return self.activate_group(group).await;
+        }
+
+        if !group.is_leg(&order.id)
+            || (order.filled_volume <= rust_decimal::Decimal::ZERO
+                && Self::is_active_order_status(order.status))
+        {
+            return Ok(());
+        }
+        self.pending_port.remove_group(group_id).await?;
+        for leg in group.legs.iter().filter(|leg| leg.id != order.id) {
+            self.cancel_pending(&leg.id).await?;
+        }
+        Ok(())
+    }
+
+    /// 推进订单组；订单本身已结算，失败只记录日志不影响调用方。
+    async fn advance_group_logged(&self, order: &Order) {
+        if let Err(e) = self.advance_group(order).await {
+            tracing::error!(
+                "Failed to advance order group of order {}: {}",
+                order.id.0,
+                e
+            );
+        }
+    }
+
+    /// 记录并结算一笔成交：按委托的结算币种附上汇率，写入交易日志、更新账户，并回报给算法单服务。
+    async fn settle_trade(
+        &self,
+        order: &Order,
+        trade: &Trade,
+        est_req_funds: rust_decimal::Decimal,
+    ) -> Result<(), TradeError> {
+        let trade = Trade {
+            settlement: self
+                .fx_settlement(&order.account_id, order.currency.as_deref())
+                .await?,
+            ..trade.clone()
+        };
+        if let Some(log) = &self.trade_log {
+            log.record(&trade)
+                .map_err(|e| TradeError::InternalError(e.to_string()))?;
+        }
+        self.account_port
+            .process_trade(&order.account_id, &trade, est_req_funds)
+            .await?;
+        if let Some(history) = &self.order_history
+            && let Err(e) = history.record_trade(&trade).await
+        {
+            tracing::error!("Failed to record fill of order {}: {}", order.id.0, e);
+        }
+        if let Some(algo) = self.current_algo_service()? {
+            algo.on_trade(&trade)?;
+        }
+        Ok(())
+    }
+
+    /// 通知实时撮合循环该标的上有新的活动订单。
+    fn watch_symbol(&self, symbol: &str) -> Result<(), TradeError> {
+        let live_matcher = self
+            .live_matcher
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("live matcher lock poisoned: {}", e)))?
+            .clone();
+        if let Some(live) = live_matcher {
+            live.watch(symbol);
+        }
+        Ok(())
+    }
+
     pub fn new(
         account_port: Arc<dyn AccountPort>,
         matcher: Arc<dyn MatcherPort>,
@@ -83,11 +1375,19 @@ impl TradeService {
         Self {
             account_port,
             matcher,
+            account_matchers: None,
+            matcher_cache: RwLock::new(HashMap::new()),
             market,
             pending_port,
             algo_service: RwLock::new(None),
             time_provider,
+            calendar: TradingCalendar::default(),
             trade_log: None,
+            order_history: None,
+            live_matcher: RwLock::new(None),
+            margin_accounts: RwLock::new(HashSet::new()),
+            last_daily_pnl: RwLock::new(HashMap::new()),
+            corporate_action_marks: RwLock::new(HashMap::new()),
         }
     }
 
@@ -119,22 +1419,75 @@ impl TradeService {
         Ok(())
     }
 
+    /// # Logic
+    /// Attach the live matching loop that drives `tick` from real-time market data.
+    ///
+    /// # Arguments
+    /// * `live_matcher` - Live matching service subscribing symbols with resting orders.
+    ///
+    /// # Returns
+    /// None.
+    pub fn set_live_matcher(
+        &self,
+        live_matcher: Arc<LiveMatchingService>,
+    ) -> Result<(), TradeError> {
+        let mut guard = self
+            .live_matcher
+            .write()
+            .map_err(|e| TradeError::InternalError(format!("live matcher lock poisoned: {}", e)))?;
+        *guard = Some(live_matcher);
+        Ok(())
+    }
+
+    /// # Logic
+    /// Build each logical account's matcher from the cost model in its profile config
+    /// instead of sharing the default matcher, and apply the margin model of margin accounts.
+    ///
+    /// # Arguments
+    /// * `system_store` - Source of `AccountProfile`.
+    /// * `factory` - Builds a matcher from a `CostModel`.
+    ///
+    /// # Returns
+    /// The service with per-account matchers enabled.
+    pub fn with_account_cost_models(
+        mut self,
+        system_store: Arc<dyn SystemStore>,
+        factory: MatcherFactory,
+    ) -> Self {
+        self.account_matchers = Some((system_store, factory));
+        self
+    }
+
+    /// 设置各市场的交易时段，默认为美股与港股常规时段。
+    pub fn with_trading_calendar(mut self, calendar: TradingCalendar) -> Self {
+        self.calendar = calendar;
+        self
+    }
+
     /// 设置交易事件收集器。回测场景下使用。
     pub fn with_trade_log(mut self, trade_log: Arc<TradeLog>) -> Self {
         self.trade_log = Some(trade_log);
         self
     }
+
+    /// 设置订单与成交历史仓储。
+    pub fn with_order_history(mut self, order_history: Arc<dyn OrderHistoryPort>) -> Self {
+        self.order_history = Some(order_history);
+        self
+    }
 }
 
 #[async_trait]
 impl TradePort for TradeService {
     /// # Logic
-    /// 1. 如果是买单，计算所需的预估冻结金额 (如果市价单且没有预估金额，则按最新价 * 倍数 兜底)。
-    /// 2. 从账户端口请求冻结。如果可用金额不足抛错。
+    /// 1. 计算所需的预估冻结金额 (如果市价单且没有预估金额，则按最新价 * 倍数 兜底)：
+    ///    现金账户仅买单冻结全额；保证金账户的开仓委托 (多空皆然) 按初始保证金率冻结。
+    /// 2. 从账户端口请求冻结。如果可用金额或购买力不足抛错。
     /// 3. 提交订单到本地撮合端口（由于是模拟回测环境，直接触发立即执行）。
     /// 4. 撮合器吐出 Trade，账户端口按 Trade 真实价格和数量扣减冻结资金及更新持仓。
     async fn submit_order(&self, mut order: Order) -> Result<OrderId, TradeError> {
         let order_id = order.id.clone();
+        self.track_corporate_actions(&order.account_id)?;
 
         let stock = self.market.get_stock(&order.symbol).await.map_err(|e| {
             TradeError::BrokerIntegrationError(format!("Failed to get market data: {}", e))
@@ -156,45 +1509,121 @@ impl TradePort for TradeService {
                 TradeError::InternalError("No latest price available for stock".into())
             })?;
 
-        // 预估单价 (限价单取限价，市价单取市场最新的成交价进行预估撮合)。
-        // OK: Intentional business fallback for Market Orders
-        let est_price = order.price.unwrap_or(latest_price);
-        let est_req_funds = self.estimate_buy_funds(est_price, order.volume);
+        let matcher = self.matcher_for(&order.account_id).await?;
+        let now_ms = self.now_ms()?;
+        order.currency = self
+            .settlement_currency(&order.account_id, &order.symbol)
+            .await?;
 
-        // 如果是多头买单，先冻结需要的总现金款
-        if order.direction == OrderDirection::Buy {
-            self.account_port
-                .freeze_funds(&order.account_id, est_req_funds)
-                .await?;
+        // 止损 / 跟踪止损单以最新价锚定触发条件，等待行情触发
+        let direction = order.direction;
+        if let Some(trigger) = order.trigger.as_mut() {
+            if !trigger.is_valid() {
+                return Err(TradeError::InvalidOrder(
+                    "stop price and trail offset must be positive".into(),
+                ));
+            }
+            if order.time_in_force.is_immediate() {
+                return Err(TradeError::InvalidOrder(
+                    "ioc and fok orders cannot carry a stop trigger".into(),
+                ));
+            }
+            trigger.arm(direction, latest_price);
         }
 
-        // 如果是市价单 (price == None)，立刻尝试撮合。
-        // 如果是限价单，先放入 Pending 队列等待下一个 Tick。
-        if order.price.is_none() {
-            let now_ms = self
-                .time_provider
-                .now()
-                .map_err(|e| TradeError::InternalError(e.to_string()))?
-                .timestamp_millis();
+        // 按有效期解析过期时间
+        order.expire_at = match order.time_in_force {
+            TimeInForce::Day => {
+                let now = self
+                    .time_provider
+                    .now()
+                    .map_err(|e| TradeError::InternalError(e.to_string()))?;
+                let close = self
+                    .calendar
+                    .session_for(&order.symbol)
+                    .next_close(now)
+                    .ok_or_else(|| {
+                        TradeError::InternalError("session close out of range".into())
+                    })?;
+                Some(close.timestamp_millis())
+            }
+            TimeInForce::Gtd { expire_at } if expire_at <= now_ms => {
+                return Err(TradeError::InvalidOrder(
+                    "expire_at must be in the future".into(),
+                ));
+            }
+            TimeInForce::Gtd { expire_at } => Some(expire_at),
+            TimeInForce::Gtc | TimeInForce::Ioc | TimeInForce::Fok => None,
+        };
+
+        // 预估单价 (限价单取限价，止损市价单取触发价，市价单取市场最新的成交价加滑点进行预估撮合)。
+        let est_price = match order.reserve_price() {
+            Some(reserve_price) => reserve_price,
+            None => matcher.estimate_fill_price(order.direction, latest_price),
+        };
+
+        if let Err(e) = self
+            .reserve_funds(&mut order, matcher.as_ref(), est_price)
+            .await
+        {
+            order.status = OrderStatus::Rejected;
+            self.record_order_history(&order).await;
+            return Err(e);
+        }
+
+        // 如果是市价单 (price == None) 或 IOC / FOK 单，立刻尝试撮合。
+        // 如果是限价单或带触发条件的订单，先放入 Pending 队列等待下一个 Tick。
+        if order.time_in_force.is_immediate() || (order.price.is_none() && order.trigger.is_none())
+        {
             order.status = OrderStatus::Submitted;
+            self.record_order_history(&order).await;
 
-            if let Some(trade) = self.matcher.execute_order(&mut order, latest_price, now_ms) {
-                if let Some(log) = &self.trade_log {
-                    log.record(&trade)
-                        .map_err(|e| TradeError::InternalError(e.to_string()))?;
-                }
-                self.account_port
-                    .process_trade(&order.account_id, &trade, est_req_funds)
+            // FOK 只接受全部成交，否则整单不成交
+            let remaining_volume = order.volume - order.filled_volume;
+            let mut attempt = order.clone();
+            let trade = matcher
+                .execute_order(&mut attempt, latest_price, now_ms)
+                .filter(|trade| {
+                    order.time_in_force != TimeInForce::Fok || trade.volume >= remaining_volume
+                });
+            if let Some(trade) = trade {
+                order = attempt;
+                let est_trade_funds = Self::estimate_reserved_funds(
+                    matcher.as_ref(),
+                    &order,
+                    est_price,
+                    trade.volume,
+                );
+                self.settle_trade(&order, &trade, est_trade_funds).await?;
+            }
+
+            if order.time_in_force.is_immediate() && Self::is_active_order_status(order.status) {
+                // IOC / FOK 未成交的部分立即过期
+                self.release_frozen_funds(matcher.as_ref(), &order, est_price)
                     .await?;
+                order.status = OrderStatus::Expired;
+                tracing::info!(
+                    "Order {} expired with {} of {} filled",
+                    order.id.0,
+                    order.filled_volume,
+                    order.volume
+                );
             }
+            self.record_order_history(&order).await;
+            self.advance_group_logged(&order).await;
 
             if Self::is_active_order_status(order.status) {
+                let symbol = order.symbol.clone();
                 self.pending_port.save(order).await?;
+                self.watch_symbol(&symbol)?;
             }
         } else {
-            // 限价单，等待未来穿越
+            // 限价单或止损单，等待未来穿越
             order.status = OrderStatus::Pending;
+            self.record_order_history(&order).await;
+            let symbol = order.symbol.clone();
             self.pending_port.save(order).await?;
+            self.watch_symbol(&symbol)?;
         }
 
         Ok(order_id)
@@ -210,27 +1639,25 @@ impl TradePort for TradeService {
             return Err(TradeError::InvalidOrderStatus);
         }
 
-        let mut order =
-            self.pending_port.remove(&order_id).await?.ok_or_else(|| {
-                TradeError::OrderNotFound("order not found or already filled".into())
-            })?;
-        order.status = OrderStatus::Canceled;
-        // 还需要退回冻结资金
-        if order.direction == OrderDirection::Buy
-            && let Some(price) = order.price
-        {
-            let remaining_volume = order.volume - order.filled_volume;
-            let amount = self.estimate_buy_funds(price, remaining_volume);
-            self.account_port
-                .unfreeze_funds(&order.account_id, amount)
-                .await?;
-        }
+        // 移除并退回冻结资金
+        let order = self
+            .cancel_pending(&order_id)
+            .await?
+            .ok_or_else(|| TradeError::OrderNotFound("order not found or already filled".into()))?;
+        self.advance_group_logged(&order).await;
         Ok(())
     }
 
+    /// # Logic
+    /// 按最新行情估值并填充当日盈亏，不产生副作用：融券费计提、强制平仓与当日快照的落地
+    /// 由 `tick` 与 `maintain_accounts` 完成。
     async fn get_account(&self, account_id: AccountId) -> Result<AccountSnapshot, TradeError> {
+        self.track_corporate_actions(&account_id)?;
+        let margin = self.margin_model(&account_id).await?;
         let snapshot = self.account_port.snapshot(&account_id).await?;
-        self.mark_to_market_snapshot(snapshot).await
+        let (mut snapshot, _) = self.mark_to_market_snapshot(snapshot, &margin).await?;
+        snapshot.day_pnl = self.daily_pnl_of(&snapshot).await?.day_pnl;
+        Ok(snapshot)
     }
 
     async fn get_orders(&self, account_id: &AccountId) -> Result<Vec<Order>, TradeError> {
@@ -248,62 +1675,388 @@ impl TradePort for TradeService {
     ) -> Result<(), TradeError> {
         self.account_port
             .ensure_account(&account_id, initial_balance)
+            .await?;
+        self.track_corporate_actions(&account_id)
+    }
+
+    /// # Logic
+    /// 1. 校验订单组。
+    /// 2. OCO：立即提交全部子单。
+    /// 3. 括号单：先持久化订单组 (子单为模板)，再提交入场单；入场单立即成交时随即激活子单。
+    async fn submit_order_group(
+        &self,
+        group: OrderGroup,
+        entry: Option<Order>,
+    ) -> Result<OrderId, TradeError> {
+        Self::validate_group(&group, entry.as_ref())?;
+        let group_id = group.id.clone();
+
+        match entry {
+            None => self.activate_group(group).await?,
+            Some(mut entry) => {
+                self.pending_port.save_group(group).await?;
+                entry.group_id = Some(group_id.clone());
+                if let Err(e) = self.submit_order(entry).await {
+                    self.pending_port.remove_group(&group_id).await?;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(group_id)
+    }
+
+    async fn cancel_order_group(&self, group_id: &OrderId) -> Result<(), TradeError> {
+        let group = self
+            .pending_port
+            .remove_group(group_id)
+            .await?
+            .ok_or_else(|| TradeError::OrderNotFound("order group not found or done".into()))?;
+        if let Some(parent_id) = &group.parent_id {
+            self.cancel_pending(parent_id).await?;
+        }
+        if group.activated {
+            for leg in &group.legs {
+                self.cancel_pending(&leg.id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_order_group(&self, group_id: &OrderId) -> Result<Option<OrderGroup>, TradeError> {
+        self.pending_port.get_group(group_id).await
+    }
+
+    async fn get_daily_pnl(&self, account_id: &AccountId) -> Result<Vec<DailyPnl>, TradeError> {
+        self.account_port.list_daily_pnl(account_id).await
+    }
+
+    async fn deposit(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        Self::validate_cash_amount(amount)?;
+        let currency = self.cash_currency(account_id, currency).await?;
+        self.settle_daily_pnl(account_id).await?;
+        let entry = self
+            .account_port
+            .deposit(account_id, currency.as_deref(), amount)
+            .await?;
+        let net_flow = self
+            .base_amount(account_id, currency.as_deref(), amount)
+            .await?;
+        self.shift_opening_equity(account_id, net_flow).await?;
+        Ok(entry)
+    }
+
+    async fn withdraw(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<LedgerEntry, TradeError> {
+        Self::validate_cash_amount(amount)?;
+        let currency = self.cash_currency(account_id, currency).await?;
+        let net_flow = self
+            .base_amount(account_id, currency.as_deref(), amount)
+            .await?;
+        self.ensure_withdrawable(account_id, net_flow).await?;
+        let entry = self
+            .account_port
+            .withdraw(account_id, currency.as_deref(), amount)
+            .await?;
+        self.shift_opening_equity(account_id, -net_flow).await?;
+        Ok(entry)
+    }
+
+    /// # Logic
+    /// 币种在两个账户中须落在同一现金槽位 (同为基础货币或同为外币)，
+    /// 两户各按自身基础货币平移当日开盘权益。
+    async fn transfer(
+        &self,
+        from: &AccountId,
+        to: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+        transfer_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        Self::validate_cash_amount(amount)?;
+        if from == to {
+            return Err(TradeError::InvalidCashMovement(
+                "cannot transfer to the same account".into(),
+            ));
+        }
+        let from_currency = self.cash_currency(from, currency).await?;
+        if self.cash_currency(to, currency).await? != from_currency {
+            return Err(TradeError::InvalidCurrency(
+                "currency must be the base currency of both accounts or of neither".into(),
+            ));
+        }
+        let outgoing = self
+            .base_amount(from, from_currency.as_deref(), amount)
+            .await?;
+        let incoming = self
+            .base_amount(to, from_currency.as_deref(), amount)
+            .await?;
+        self.ensure_withdrawable(from, outgoing).await?;
+        self.settle_daily_pnl(to).await?;
+        let transfer = self
+            .account_port
+            .transfer(from, to, from_currency.as_deref(), amount, transfer_id)
+            .await?;
+        self.shift_opening_equity(from, -outgoing).await?;
+        self.shift_opening_equity(to, incoming).await?;
+        Ok(transfer)
+    }
+
+    /// # Logic
+    /// 按最新汇率在同一账户内兑换现金：兑换不改变以基础货币计的权益，因而不平移当日开盘权益；
+    /// 换出金额按基础货币计不得超过保证金账户的剩余保证金额度。
+    async fn convert_currency(
+        &self,
+        account_id: &AccountId,
+        from: &str,
+        to: &str,
+        amount: rust_decimal::Decimal,
+        conversion_id: &str,
+    ) -> Result<CashTransfer, TradeError> {
+        Self::validate_cash_amount(amount)?;
+        let from_code = fx::normalize_currency(from)?;
+        let to_code = fx::normalize_currency(to)?;
+        let from_slot = self.cash_currency(account_id, Some(&from_code)).await?;
+        let to_slot = self.cash_currency(account_id, Some(&to_code)).await?;
+        if from_slot == to_slot {
+            return Err(TradeError::InvalidCashMovement(
+                "cannot convert to the same currency".into(),
+            ));
+        }
+        let rate = self.fx_rate(&from_code, &to_code).await?;
+        let base_amount = self
+            .base_amount(account_id, from_slot.as_deref(), amount)
+            .await?;
+        self.ensure_withdrawable(account_id, base_amount).await?;
+        self.account_port
+            .convert_currency(
+                account_id,
+                from_slot.as_deref(),
+                to_slot.as_deref(),
+                amount,
+                rate,
+                conversion_id,
+            )
+            .await
+    }
+
+    async fn get_ledger(
+        &self,
+        account_id: &AccountId,
+        offset: usize,
+        limit: usize,
+    ) -> Result<HistoryPage<LedgerEntry>, TradeError> {
+        self.account_port
+            .list_ledger(account_id, offset, limit)
             .await
     }
+
+    async fn get_order_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<OrderHistoryRecord>, TradeError> {
+        match &self.order_history {
+            Some(history) => history.query_orders(query).await,
+            None => Ok(HistoryPage::default()),
+        }
+    }
+
+    async fn get_trade_history(
+        &self,
+        query: &HistoryQuery,
+    ) -> Result<HistoryPage<Trade>, TradeError> {
+        match &self.order_history {
+            Some(history) => history.query_trades(query).await,
+            None => Ok(HistoryPage::default()),
+        }
+    }
 }
 
 #[async_trait]
 impl BacktestTradePort for TradeService {
     async fn tick(&self, symbol: &str, candle: &Candle) -> Result<(), TradeError> {
+        // 除权除息先于撮合：持仓按除权除息前的持有情况享有公司行动
+        if let Err(e) = self.apply_corporate_actions(symbol).await {
+            tracing::error!("Failed to apply corporate actions of {}: {}", symbol, e);
+        }
+
         // 首先驱动算法单
-        let algo_service = self
-            .algo_service
-            .read()
-            .map_err(|e| TradeError::InternalError(format!("algo service lock poisoned: {}", e)))?
-            .clone();
-        if let Some(algo) = algo_service {
+        if let Some(algo) = self.current_algo_service()? {
             algo.tick(symbol, candle).await?;
         }
 
-        let high = candle.high;
-        let low = candle.low;
+        // 到期的挂单先行过期，不再参与撮合；过期可能连带撤销同组订单，因此重新读取
+        let now_ms = self.now_ms()?;
+        let (expired, mut pending): (Vec<Order>, Vec<Order>) = self
+            .pending_port
+            .get_by_symbol(symbol)
+            .await?
+            .into_iter()
+            .partition(|order| order.is_expired_at(now_ms));
+        if !expired.is_empty() {
+            for order in expired {
+                self.expire_order(order).await?;
+            }
+            pending = self.pending_port.get_by_symbol(symbol).await?;
+        }
 
-        let pending = self.pending_port.get_by_symbol(symbol).await?;
+        // 仅限价单与带触发条件的订单挂单等待 K 线撮合；触发与成交的价格、数量由撮合器的
+        // 成交模型决定 (跳空按开盘价、盘中触达按触发价或限价、按参与率上限部分成交)
+        let pending = pending
+            .into_iter()
+            .filter(|order| order.price.is_some() || order.trigger.is_some());
 
-        for mut order in pending {
-            if let Some(limit_price) = order.price {
-                // 击穿判定
-                let is_hit = (order.direction == OrderDirection::Buy && low <= limit_price)
-                    || (order.direction == OrderDirection::Sell && high >= limit_price);
+        // 各账号按自身成本模型撮合
+        let mut order_accounts: HashSet<AccountId> = HashSet::new();
+        let mut by_account: Vec<(AccountId, Vec<Order>)> = Vec::new();
+        for order in pending {
+            match by_account
+                .iter_mut()
+                .find(|(id, _)| *id == order.account_id)
+            {
+                Some((_, orders)) => orders.push(order),
+                None => by_account.push((order.account_id.clone(), vec![order])),
+            }
+        }
 
-                if is_hit {
-                    // 成交取限价或者由于跳空引起的开盘价劣势
-                    let exec_price = limit_price;
-                    let now_ms = candle.time.timestamp_millis();
+        for (account_id, mut orders) in by_account {
+            order_accounts.insert(account_id.clone());
+            let matcher = match self.matcher_for(&account_id).await {
+                Ok(matcher) => matcher,
+                Err(e) => {
+                    tracing::error!("Skipping orders of account {}: {}", account_id.0, e);
+                    continue;
+                }
+            };
+            let before = orders.clone();
+            let mut trades = matcher.execute_bar(symbol, &mut orders, candle);
 
-                    if let Some(trade) = self.matcher.execute_order(&mut order, exec_price, now_ms)
-                    {
-                        if let Some(log) = &self.trade_log {
-                            log.record(&trade)
-                                .map_err(|e| TradeError::InternalError(e.to_string()))?;
-                        }
-                        let est_req_funds = self.estimate_buy_funds(limit_price, trade.volume);
-                        self.account_port
-                            .process_trade(&order.account_id, &trade, est_req_funds)
-                            .await?;
+            // OCO：同一根 K 线内同组只保留最先成交的子单，其余子单恢复到撮合前的状态
+            let mut first_in_group: HashMap<OrderId, OrderId> = HashMap::new();
+            let mut rolled_back: Vec<OrderId> = Vec::new();
+            for trade in &trades {
+                let Some(group_id) = orders
+                    .iter()
+                    .find(|order| order.id == trade.order_id)
+                    .and_then(|order| order.group_id.clone())
+                else {
+                    continue;
+                };
+                let first = first_in_group
+                    .entry(group_id)
+                    .or_insert_with(|| trade.order_id.clone());
+                if *first != trade.order_id {
+                    rolled_back.push(trade.order_id.clone());
+                }
+            }
+            if !rolled_back.is_empty() {
+                trades.retain(|trade| !rolled_back.contains(&trade.order_id));
+                for (order, original) in orders.iter_mut().zip(&before) {
+                    if rolled_back.contains(&order.id) {
+                        *order = original.clone();
                     }
+                }
+            }
 
-                    // 核心修复：只有达到终态才移除，否则更新（部分成交）
-                    if order.status == OrderStatus::Filled || order.status == OrderStatus::Canceled
-                    {
-                        self.pending_port.remove(&order.id).await?;
-                    } else {
-                        // 如果有部分成交或状态变更，更新持久化存储
+            for trade in &trades {
+                let Some(order) = orders.iter().find(|order| order.id == trade.order_id) else {
+                    continue;
+                };
+                // 冻结资金按限价或触发价预估，成交价更优时的差额由账户端口解冻
+                let est_req_funds = Self::estimate_reserved_funds(
+                    matcher.as_ref(),
+                    order,
+                    order.reserve_price().unwrap_or(trade.price),
+                    trade.volume,
+                );
+                // 现金账户的止损市价单跳空成交可能高于预估，先补足冻结
+                let actual_cost = trade.price * trade.volume + trade.commission;
+                let est_req_funds = if order.margin_rate.is_none()
+                    && trade.direction == OrderDirection::Buy
+                    && actual_cost > est_req_funds
+                {
+                    self.account_port
+                        .freeze_funds(
+                            &order.account_id,
+                            order.currency.as_deref(),
+                            actual_cost - est_req_funds,
+                        )
+                        .await?;
+                    actual_cost
+                } else {
+                    est_req_funds
+                };
+                self.settle_trade(order, trade, est_req_funds).await?;
+            }
+
+            let mut traded_orders = Vec::new();
+            for (order, original) in orders.into_iter().zip(before) {
+                let traded = trades.iter().any(|trade| trade.order_id == order.id);
+                if !traded {
+                    // 未成交但触发状态 (已触发 / 跟踪极值) 变化的订单需要持久化
+                    if order.trigger != original.trigger {
                         self.pending_port.save(order).await?;
                     }
+                    continue;
+                }
+                self.record_order_history(&order).await;
+                if order.group_id.is_some() {
+                    traded_orders.push(order.clone());
+                }
+                // 只有达到终态才移除，部分成交则更新持久化存储
+                if order.status == OrderStatus::Filled || order.status == OrderStatus::Canceled {
+                    self.pending_port.remove(&order.id).await?;
+                } else {
+                    self.pending_port.save(order).await?;
+                }
+            }
+
+            // 持久化完成后再推进订单组，避免被撤销的同组订单被重新写回
+            for order in &traded_orders {
+                self.advance_group_logged(order).await;
+            }
+        }
+
+        // 新行情下仅检查在该标的上有委托或持仓的保证金账户；单个账户失败不影响撮合
+        let margin_accounts: Vec<AccountId> = self
+            .margin_accounts
+            .read()
+            .map_err(|e| TradeError::InternalError(format!("margin account lock poisoned: {}", e)))?
+            .iter()
+            .cloned()
+            .collect();
+        let mut affected = Vec::new();
+        for account_id in margin_accounts {
+            if order_accounts.contains(&account_id) {
+                affected.push(account_id);
+                continue;
+            }
+            match self.account_port.snapshot(&account_id).await {
+                Ok(snapshot)
+                    if snapshot
+                        .positions
+                        .iter()
+                        .any(|p| p.symbol == symbol && !p.volume.is_zero()) =>
+                {
+                    affected.push(account_id)
                 }
+                Ok(_) => {}
+                Err(e) => tracing::error!(
+                    "Failed to load positions of margin account {}: {}",
+                    account_id.0,
+                    e
+                ),
             }
         }
+        self.maintain_margin_accounts(affected).await;
 
         Ok(())
     }
@@ -0,0 +1,219 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use okane_core::config::TradingSessionConfig;
+use okane_core::trade::port::TradeError;
+
+/// 常规交易时段收盘时刻 (交易所本地时间 16:00)，美股与港股相同。
+const EQUITY_CLOSE: NaiveTime = match NaiveTime::from_hms_opt(16, 0, 0) {
+    Some(time) => time,
+    None => NaiveTime::MIN,
+};
+
+/// # Summary
+/// 单个市场的交易时段定义，用于确定 DAY 订单的过期时刻。
+///
+/// # Invariants
+/// - 收盘时刻以交易所本地时间表示，按 IANA 时区换算，随夏令时切换。
+/// - 周六、周日与 `holidays` 中的日期不是交易日。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradingSession {
+    timezone: Tz,
+    close: NaiveTime,
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl Default for TradingSession {
+    /// 默认为美股常规时段。
+    fn default() -> Self {
+        Self::us_equity()
+    }
+}
+
+impl TradingSession {
+    /// # Arguments
+    /// * `timezone` - Exchange timezone.
+    /// * `close` - Session close in exchange local time.
+    pub fn new(timezone: Tz, close: NaiveTime) -> Self {
+        Self {
+            timezone,
+            close,
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// 美股常规时段：美东时间 16:00 收盘。
+    pub fn us_equity() -> Self {
+        Self::new(chrono_tz::America::New_York, EQUITY_CLOSE)
+    }
+
+    /// 港股常规时段：香港时间 16:00 收盘。
+    pub fn hk_equity() -> Self {
+        Self::new(chrono_tz::Asia::Hong_Kong, EQUITY_CLOSE)
+    }
+
+    /// 追加休市日 (交易所本地日期)。
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    /// # Logic
+    /// 1. 取 `now` 所在交易所本地日期的收盘时刻；已过收盘则顺延至次日。
+    /// 2. 落在周末或休市日则继续顺延至下一个交易日。
+    ///
+    /// # Returns
+    /// The first session close strictly after `now`, or `None` if it is out of range.
+    pub fn next_close(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local_now = now.with_timezone(&self.timezone);
+        let mut date = local_now.date_naive();
+        if local_now.time() >= self.close {
+            date = date.checked_add_days(Days::new(1))?;
+        }
+        while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) || self.holidays.contains(&date)
+        {
+            date = date.checked_add_days(Days::new(1))?;
+        }
+        date.and_time(self.close)
+            .and_local_timezone(self.timezone)
+            .earliest()
+            .map(|close| close.with_timezone(&Utc))
+    }
+}
+
+/// # Summary
+/// 按标的所属市场选择交易时段。
+///
+/// # Logic
+/// 标的代码按交易所后缀 (Yahoo 代码风格，如 `.HK`) 匹配市场，无匹配后缀的标的使用默认时段。
+///
+/// # Invariants
+/// - 缺省包含美股 (默认) 与港股 (`.HK`) 常规时段。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradingCalendar {
+    default: TradingSession,
+    markets: Vec<(String, TradingSession)>,
+}
+
+impl Default for TradingCalendar {
+    fn default() -> Self {
+        Self {
+            default: TradingSession::us_equity(),
+            markets: vec![(".HK".to_string(), TradingSession::hk_equity())],
+        }
+    }
+}
+
+impl TradingCalendar {
+    /// 设置某个交易所后缀的交易时段，空后缀替换默认时段。
+    pub fn with_session(mut self, suffix: &str, session: TradingSession) -> Self {
+        let suffix = suffix.trim().to_ascii_uppercase();
+        if suffix.is_empty() {
+            self.default = session;
+        } else if let Some((_, existing)) = self.markets.iter_mut().find(|(s, _)| *s == suffix) {
+            *existing = session;
+        } else {
+            self.markets.push((suffix, session));
+        }
+        self
+    }
+
+    /// # Logic
+    /// 在内置时段的基础上按配置逐项覆盖。
+    ///
+    /// # Returns
+    /// * `Err(TradeError::InvalidOrder)` - 时区不是合法的 IANA 名称。
+    pub fn from_config(configs: &[TradingSessionConfig]) -> Result<Self, TradeError> {
+        configs
+            .iter()
+            .try_fold(Self::default(), |calendar, config| {
+                let timezone = Tz::from_str(&config.timezone).map_err(|e| {
+                    TradeError::InvalidOrder(format!("invalid timezone {}: {}", config.timezone, e))
+                })?;
+                let session = TradingSession::new(timezone, config.close)
+                    .with_holidays(config.holidays.iter().copied());
+                Ok(calendar.with_session(&config.suffix, session))
+            })
+    }
+
+    /// 标的所属市场的交易时段。
+    pub fn session_for(&self, symbol: &str) -> &TradingSession {
+        let symbol = symbol.to_ascii_uppercase();
+        self.markets
+            .iter()
+            .find(|(suffix, _)| symbol.ends_with(suffix.as_str()))
+            .map_or(&self.default, |(_, session)| session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> Result<DateTime<Utc>, String> {
+        Utc.with_ymd_and_hms(y, m, d, h, 0, 0)
+            .single()
+            .ok_or_else(|| "invalid time".to_string())
+    }
+
+    #[test]
+    fn test_next_close_rolls_over_after_close_and_weekends() -> Result<(), String> {
+        let session = TradingSession::default();
+
+        // 周三 15:00 UTC (夏令时，美东 11:00) -> 当日 20:00 UTC 收盘
+        assert_eq!(
+            session.next_close(at(2026, 3, 18, 15)?),
+            Some(at(2026, 3, 18, 20)?)
+        );
+        // 周三收盘后 -> 周四收盘
+        assert_eq!(
+            session.next_close(at(2026, 3, 18, 20)?),
+            Some(at(2026, 3, 19, 20)?)
+        );
+        // 周五收盘后 -> 下周一收盘
+        assert_eq!(
+            session.next_close(at(2026, 3, 20, 22)?),
+            Some(at(2026, 3, 23, 20)?)
+        );
+        // 冬令时：周三 15:00 UTC (美东 10:00) -> 当日 21:00 UTC 收盘
+        assert_eq!(
+            session.next_close(at(2026, 1, 14, 15)?),
+            Some(at(2026, 1, 14, 21)?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_close_skips_holidays_and_uses_market_of_symbol() -> Result<(), String> {
+        let configs = vec![TradingSessionConfig {
+            suffix: String::new(),
+            timezone: "America/New_York".to_string(),
+            close: EQUITY_CLOSE,
+            holidays: vec![NaiveDate::from_ymd_opt(2026, 7, 3).ok_or("invalid date")?],
+        }];
+        let calendar = TradingCalendar::from_config(&configs).map_err(|e| e.to_string())?;
+
+        // 周四收盘后，周五 (独立日调休) 休市 -> 下周一收盘
+        assert_eq!(
+            calendar.session_for("AAPL").next_close(at(2026, 7, 2, 21)?),
+            Some(at(2026, 7, 6, 20)?)
+        );
+        // 港股按香港时间 16:00 (08:00 UTC) 收盘，不受美股休市日影响
+        assert_eq!(
+            calendar
+                .session_for("0700.hk")
+                .next_close(at(2026, 7, 3, 1)?),
+            Some(at(2026, 7, 3, 8)?)
+        );
+
+        let invalid = vec![TradingSessionConfig {
+            timezone: "Mars/Olympus".to_string(),
+            ..configs[0].clone()
+        }];
+        assert!(TradingCalendar::from_config(&invalid).is_err());
+        Ok(())
+    }
+}
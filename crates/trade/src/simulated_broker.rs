@@ -0,0 +1,295 @@
+use async_trait::async_trait;
+use okane_core::common::time::TimeProvider;
+use okane_core::market::port::Market;
+use okane_core::trade::entity::{ExecutionReport, Order, OrderDirection, OrderStatus, TimeInForce};
+use okane_core::trade::port::{BrokerPort, ExecutionReportStream, TradeError};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// # Summary
+/// 离线模拟的券商网关，以行情最新价成交外发订单，用于在无真实券商连接时测试网关链路。
+///
+/// # Invariants
+/// - 券商订单号形如 `SIM-{seq}`，按提交顺序递增。
+/// - 市价单立即按最新价成交；限价单在最新价穿越限价时按最新价成交，
+///   未成交的限价单在 `match_orders` 时按当时的最新价重新判断。
+/// - `disconnect` 之后产生的回报不会推送给旧的订阅者，只能经 `query_order_status` 获取。
+pub struct SimulatedBroker {
+    market: Arc<dyn Market>,
+    time_provider: Arc<dyn TimeProvider>,
+    /// 按成交额计收的手续费率
+    commission_rate: Decimal,
+    /// 单次撮合的最大成交数量，为空时一次全部成交
+    max_fill_volume: Option<Decimal>,
+    state: Mutex<SimulatedState>,
+}
+
+#[derive(Default)]
+struct SimulatedState {
+    next_seq: u64,
+    orders: BTreeMap<String, SimulatedOrder>,
+    subscribers: Vec<mpsc::UnboundedSender<ExecutionReport>>,
+}
+
+struct SimulatedOrder {
+    order: Order,
+    report: ExecutionReport,
+}
+
+impl SimulatedState {
+    /// 推送回报，并移除已断开的订阅者。
+    fn emit(&mut self, report: &ExecutionReport) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(report.clone()).is_ok());
+    }
+}
+
+impl SimulatedBroker {
+    pub fn new(market: Arc<dyn Market>, time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self {
+            market,
+            time_provider,
+            commission_rate: Decimal::ZERO,
+            max_fill_volume: None,
+            state: Mutex::new(SimulatedState::default()),
+        }
+    }
+
+    /// 设置按成交额计收的手续费率。
+    pub fn with_commission_rate(mut self, rate: Decimal) -> Self {
+        self.commission_rate = rate;
+        self
+    }
+
+    /// 限制单次撮合的最大成交数量，用于模拟部分成交。
+    pub fn with_max_fill_volume(mut self, volume: Decimal) -> Self {
+        self.max_fill_volume = Some(volume);
+        self
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<'_, SimulatedState>, TradeError> {
+        self.state.lock().map_err(|e| {
+            TradeError::InternalError(format!("simulated broker lock poisoned: {}", e))
+        })
+    }
+
+    fn now_ms(&self) -> Result<i64, TradeError> {
+        Ok(self
+            .time_provider
+            .now()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .timestamp_millis())
+    }
+
+    async fn latest_price(&self, symbol: &str) -> Result<Decimal, TradeError> {
+        let stock = self.market.get_stock(symbol).await.map_err(|e| {
+            TradeError::BrokerIntegrationError(format!("Failed to get market data: {}", e))
+        })?;
+        stock
+            .current_price()
+            .map_err(|e| TradeError::InternalError(e.to_string()))?
+            .ok_or_else(|| {
+                TradeError::BrokerIntegrationError(format!(
+                    "No latest price available for stock {}",
+                    symbol
+                ))
+            })
+    }
+
+    /// # Logic
+    /// 按最新价尝试成交剩余数量 (受 `max_fill_volume` 限制)，更新累计成交均价与手续费。
+    /// FOK 订单不能一次全部成交时不成交。
+    ///
+    /// # Returns
+    /// 有新成交时返回 `true`。
+    fn try_fill(&self, sim: &mut SimulatedOrder, price: Decimal, now_ms: i64) -> bool {
+        let crossed = match (sim.order.direction, sim.order.price) {
+            (_, None) => true,
+            (OrderDirection::Buy, Some(limit)) => price <= limit,
+            (OrderDirection::Sell, Some(limit)) => price >= limit,
+        };
+        let report = &mut sim.report;
+        let remaining = sim.order.volume - report.filled_volume;
+        let volume = self
+            .max_fill_volume
+            .map_or(remaining, |max| remaining.min(max));
+        if !crossed
+            || volume <= Decimal::ZERO
+            || (sim.order.time_in_force == TimeInForce::Fok && volume < remaining)
+        {
+            return false;
+        }
+
+        let filled = report.filled_volume + volume;
+        report.average_price =
+            (report.average_price * report.filled_volume + price * volume) / filled;
+        report.filled_volume = filled;
+        report.commission += price * volume * self.commission_rate;
+        report.status = if filled >= sim.order.volume {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartialFilled
+        };
+        report.timestamp = now_ms;
+        true
+    }
+
+    /// # Logic
+    /// 按各标的当前最新价重新撮合全部未终结订单，推送有新成交的回报。
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of orders that received a fill.
+    pub async fn match_orders(&self) -> Result<usize, TradeError> {
+        let symbols: Vec<String> = {
+            let state = self.lock_state()?;
+            let mut symbols: Vec<String> = state
+                .orders
+                .values()
+                .filter(|sim| is_open(sim.report.status))
+                .map(|sim| sim.order.symbol.clone())
+                .collect();
+            symbols.sort();
+            symbols.dedup();
+            symbols
+        };
+        let mut prices = HashMap::new();
+        for symbol in symbols {
+            let price = self.latest_price(&symbol).await?;
+            prices.insert(symbol, price);
+        }
+
+        let now_ms = self.now_ms()?;
+        let mut state = self.lock_state()?;
+        let mut reports = Vec::new();
+        for sim in state.orders.values_mut() {
+            if !is_open(sim.report.status) {
+                continue;
+            }
+            if let Some(price) = prices.get(&sim.order.symbol)
+                && self.try_fill(sim, *price, now_ms)
+            {
+                reports.push(sim.report.clone());
+            }
+        }
+        for report in &reports {
+            state.emit(report);
+        }
+        Ok(reports.len())
+    }
+
+    /// 按固定间隔调用 `match_orders`，使挂单随行情变化成交。
+    pub fn start(self: &Arc<Self>, interval: Duration) {
+        let broker = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = broker.match_orders().await {
+                    tracing::warn!("Simulated broker matching failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 断开全部回报订阅，模拟连接中断。
+    pub fn disconnect(&self) -> Result<(), TradeError> {
+        self.lock_state()?.subscribers.clear();
+        Ok(())
+    }
+}
+
+fn is_open(status: OrderStatus) -> bool {
+    matches!(
+        status,
+        OrderStatus::Pending | OrderStatus::Submitted | OrderStatus::PartialFilled
+    )
+}
+
+#[async_trait]
+impl BrokerPort for SimulatedBroker {
+    /// # Logic
+    /// 受理订单并推送 `Submitted` 回报，随后按最新价尝试立即成交；
+    /// IOC / FOK 订单未能全部成交的部分立即过期。
+    async fn send_order(&self, order: &Order) -> Result<String, TradeError> {
+        if order.trigger.is_some() {
+            return Err(TradeError::BrokerIntegrationError(
+                "simulated broker does not support trigger orders".to_string(),
+            ));
+        }
+        let price = self.latest_price(&order.symbol).await?;
+        let now_ms = self.now_ms()?;
+
+        let mut state = self.lock_state()?;
+        state.next_seq += 1;
+        let external_order_id = format!("SIM-{}", state.next_seq);
+        let mut sim = SimulatedOrder {
+            order: order.clone(),
+            report: ExecutionReport {
+                external_order_id: external_order_id.clone(),
+                status: OrderStatus::Submitted,
+                filled_volume: Decimal::ZERO,
+                average_price: Decimal::ZERO,
+                commission: Decimal::ZERO,
+                timestamp: now_ms,
+                reason: None,
+            },
+        };
+        state.emit(&sim.report);
+        if self.try_fill(&mut sim, price, now_ms) {
+            state.emit(&sim.report);
+        }
+        if order.time_in_force.is_immediate() && is_open(sim.report.status) {
+            sim.report.status = OrderStatus::Expired;
+            sim.report.reason = Some("immediate order not fully filled".to_string());
+            state.emit(&sim.report);
+        }
+        state.orders.insert(external_order_id.clone(), sim);
+        Ok(external_order_id)
+    }
+
+    async fn cancel_order(&self, external_order_id: &str) -> Result<(), TradeError> {
+        let now_ms = self.now_ms()?;
+        let mut state = self.lock_state()?;
+        let sim = state
+            .orders
+            .get_mut(external_order_id)
+            .ok_or_else(|| TradeError::OrderNotFound(external_order_id.to_string()))?;
+        if !is_open(sim.report.status) {
+            return Err(TradeError::InvalidOrderStatus);
+        }
+        sim.report.status = OrderStatus::Canceled;
+        sim.report.timestamp = now_ms;
+        let report = sim.report.clone();
+        state.emit(&report);
+        Ok(())
+    }
+
+    /// 按券商订单号或订单 ID 查询。
+    async fn query_order_status(
+        &self,
+        external_order_id: &str,
+    ) -> Result<ExecutionReport, TradeError> {
+        let state = self.lock_state()?;
+        state
+            .orders
+            .get(external_order_id)
+            .or_else(|| {
+                state
+                    .orders
+                    .values()
+                    .find(|sim| sim.order.id.0 == external_order_id)
+            })
+            .map(|sim| sim.report.clone())
+            .ok_or_else(|| TradeError::OrderNotFound(external_order_id.to_string()))
+    }
+
+    async fn subscribe_execution_reports(&self) -> Result<ExecutionReportStream, TradeError> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.lock_state()?.subscribers.push(tx);
+        Ok(Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|report| (report, rx))
+        })))
+    }
+}
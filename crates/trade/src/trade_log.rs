@@ -82,6 +82,7 @@ mod tests {
             volume: dec!(100),
             commission: dec!(0.1),
             timestamp: 123456789,
+            settlement: None,
         };
 
         log.record(&trade)?;
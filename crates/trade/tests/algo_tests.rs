@@ -0,0 +1,994 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use okane_core::common::time::FakeClockProvider;
+use okane_core::common::{Stock as StockIdentity, TimeFrame};
+use okane_core::market::entity::Candle;
+use okane_core::market::error::MarketError;
+use okane_core::market::port::{CandleStream, Market, Stock, StockStatus};
+use okane_core::trade::entity::{
+    AccountId, AlgoOrder, AlgoOrderStatus, AlgoType, BrokerOrderRecord, Order, OrderDirection,
+    OrderId,
+};
+use okane_core::trade::port::{
+    AlgoOrderPort, AlgoOrderStore, BacktestTradePort, BrokerOrderStore, PendingOrderPort, TradePort,
+};
+use okane_store::algo_order::MemoryAlgoOrderStore;
+use okane_store::broker_order::MemoryBrokerOrderStore;
+use okane_store::pending_order::MemoryPendingOrderStore;
+use okane_trade::account::AccountManager;
+use okane_trade::algo::AlgoOrderService;
+use okane_trade::matcher::LocalMatchEngine;
+use okane_trade::service::TradeService;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+struct FixedPriceStock {
+    identity: StockIdentity,
+    history: Vec<Candle>,
+}
+
+#[async_trait::async_trait]
+impl Stock for FixedPriceStock {
+    fn identity(&self) -> &StockIdentity {
+        &self.identity
+    }
+    fn current_price(&self) -> Result<Option<Decimal>, MarketError> {
+        Ok(Some(dec!(150.0)))
+    }
+    fn latest_candle(&self, _timeframe: TimeFrame) -> Result<Option<Candle>, MarketError> {
+        Ok(None)
+    }
+    fn last_closed_candle(&self, _timeframe: TimeFrame) -> Result<Option<Candle>, MarketError> {
+        Ok(None)
+    }
+    fn subscribe(&self, _timeframe: TimeFrame) -> Result<CandleStream, MarketError> {
+        Err(MarketError::Unknown(
+            "subscribe is not supported in this test".to_string(),
+        ))
+    }
+    async fn fetch_history(
+        &self,
+        _timeframe: TimeFrame,
+        _start: DateTime<Utc>,
+        _end: DateTime<Utc>,
+    ) -> Result<Vec<Candle>, MarketError> {
+        Ok(self.history.clone())
+    }
+    fn status(&self) -> StockStatus {
+        StockStatus::Online
+    }
+}
+
+struct FixedPriceMarket {
+    history: Vec<Candle>,
+}
+
+#[async_trait::async_trait]
+impl Market for FixedPriceMarket {
+    async fn get_stock(&self, symbol: &str) -> Result<Arc<dyn Stock>, MarketError> {
+        Ok(Arc::new(FixedPriceStock {
+            identity: StockIdentity {
+                symbol: symbol.to_string(),
+                exchange: None,
+            },
+            history: self.history.clone(),
+        }))
+    }
+
+    async fn search_symbols(
+        &self,
+        _query: &str,
+    ) -> Result<Vec<okane_core::store::port::StockMetadata>, MarketError> {
+        Ok(vec![])
+    }
+}
+
+struct Harness {
+    clock: Arc<FakeClockProvider>,
+    trade_service: Arc<TradeService>,
+    algo_service: Arc<AlgoOrderService>,
+}
+
+fn build_harness(acct_id: &AccountId) -> anyhow::Result<Harness> {
+    build_harness_with_history(acct_id, vec![])
+}
+
+fn build_harness_with_history(
+    acct_id: &AccountId,
+    history: Vec<Candle>,
+) -> anyhow::Result<Harness> {
+    let market = Arc::new(FixedPriceMarket { history });
+    let account_manager = Arc::new(AccountManager::new());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(100000.0));
+    let clock = Arc::new(FakeClockProvider::new(start_time()?));
+    let trade_service = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(LocalMatchEngine::new(Decimal::ZERO)),
+        market.clone(),
+        Arc::new(MemoryPendingOrderStore::new()),
+        clock.clone(),
+    ));
+    let algo_service =
+        Arc::new(AlgoOrderService::new(trade_service.clone(), clock.clone()).with_market(market));
+    trade_service.set_algo_service(algo_service.clone())?;
+    Ok(Harness {
+        clock,
+        trade_service,
+        algo_service,
+    })
+}
+
+/// 以共享的账户、活动订单与算法单仓储构建服务，模拟进程重启前后的两个实例
+fn build_persistent_harness(
+    account_manager: Arc<AccountManager>,
+    pending_port: Arc<MemoryPendingOrderStore>,
+    algo_store: Arc<MemoryAlgoOrderStore>,
+) -> anyhow::Result<Harness> {
+    let clock = Arc::new(FakeClockProvider::new(start_time()?));
+    let trade_service = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(LocalMatchEngine::new(Decimal::ZERO)),
+        Arc::new(FixedPriceMarket { history: vec![] }),
+        pending_port,
+        clock.clone(),
+    ));
+    let algo_service = Arc::new(
+        AlgoOrderService::new(trade_service.clone(), clock.clone()).with_store(algo_store),
+    );
+    trade_service.set_algo_service(algo_service.clone())?;
+    Ok(Harness {
+        clock,
+        trade_service,
+        algo_service,
+    })
+}
+
+fn start_time() -> anyhow::Result<DateTime<Utc>> {
+    Utc.with_ymd_and_hms(2026, 3, 2, 14, 30, 0)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("invalid start time"))
+}
+
+fn bar(time: DateTime<Utc>, low: Decimal, high: Decimal, close: Decimal) -> Candle {
+    Candle {
+        time,
+        open: close,
+        high,
+        low,
+        close,
+        adj_close: None,
+        volume: dec!(10000),
+        is_final: true,
+    }
+}
+
+#[tokio::test]
+async fn test_grid_places_buys_below_market_and_rearms_sell_after_fill() -> anyhow::Result<()> {
+    let acct_id = AccountId("GridWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+
+    let algo_id = OrderId("grid_01".into());
+    h.algo_service
+        .submit_algo_order(AlgoOrder::new(
+            algo_id.clone(),
+            acct_id.clone(),
+            "AAPL".into(),
+            AlgoType::Grid {
+                upper_price: dec!(150),
+                lower_price: dec!(140),
+                grids: 2,
+            },
+            dec!(20),
+            t0.timestamp_millis(),
+        ))
+        .await?;
+
+    // 1. 价格位于两个档位之上：两个档位都挂出买单
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(147), dec!(149), dec!(148)))
+        .await?;
+    let mut orders = h.trade_service.get_orders(&acct_id).await?;
+    orders.sort_by_key(|o| o.price);
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders[0].price, Some(dec!(140)));
+    assert_eq!(orders[1].price, Some(dec!(145)));
+    assert!(orders.iter().all(|o| o.direction == OrderDirection::Buy));
+    assert!(orders.iter().all(|o| o.volume == dec!(10)));
+
+    // 2. 价格下探击穿 145 档买单
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(144), dec!(146), dec!(144)))
+        .await?;
+
+    // 3. 下一根 K 线结算成交，并在档位上沿挂出卖单
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(145.5), dec!(146), dec!(146)))
+        .await?;
+    let mut orders = h.trade_service.get_orders(&acct_id).await?;
+    orders.sort_by_key(|o| o.price);
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders[0].direction, OrderDirection::Buy);
+    assert_eq!(orders[0].price, Some(dec!(140)));
+    assert_eq!(orders[1].direction, OrderDirection::Sell);
+    assert_eq!(orders[1].price, Some(dec!(150)));
+
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("grid order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(10));
+    assert_eq!(algo.status, AlgoOrderStatus::Running);
+
+    // 4. 价格上冲击穿 150 档卖单，下一根 K 线结算：净仓位归零，145 档重新挂买单
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(149), dec!(151), dec!(150)))
+        .await?;
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(148), dec!(149), dec!(148)))
+        .await?;
+    let mut orders = h.trade_service.get_orders(&acct_id).await?;
+    orders.sort_by_key(|o| o.price);
+    assert_eq!(orders.len(), 2);
+    assert!(orders.iter().all(|o| o.direction == OrderDirection::Buy));
+    assert_eq!(orders[1].price, Some(dec!(145)));
+
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("grid order not found"))?;
+    assert_eq!(algo.filled_volume, Decimal::ZERO);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_grid_cancel_cancels_children_and_releases_funds() -> anyhow::Result<()> {
+    let acct_id = AccountId("GridCancelWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+
+    let algo_id = OrderId("grid_02".into());
+    h.algo_service
+        .submit_algo_order(AlgoOrder::new(
+            algo_id.clone(),
+            acct_id.clone(),
+            "AAPL".into(),
+            AlgoType::Grid {
+                upper_price: dec!(150),
+                lower_price: dec!(140),
+                grids: 2,
+            },
+            dec!(20),
+            t0.timestamp_millis(),
+        ))
+        .await?;
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(147), dec!(149), dec!(148)))
+        .await?;
+    let snapshot = h.trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(2850));
+
+    h.algo_service.cancel_algo_order(&algo_id).await?;
+
+    assert!(h.trade_service.get_orders(&acct_id).await?.is_empty());
+    let snapshot = h.trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(0));
+    assert_eq!(snapshot.available_balance, dec!(100000));
+
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("grid order not found"))?;
+    assert_eq!(algo.status, AlgoOrderStatus::Canceled);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_grid_rejects_invalid_bounds_on_submit() -> anyhow::Result<()> {
+    let acct_id = AccountId("GridInvalidWallet".to_string());
+    let h = build_harness(&acct_id)?;
+
+    let res = h
+        .algo_service
+        .submit_algo_order(AlgoOrder::new(
+            OrderId("grid_bad".into()),
+            acct_id,
+            "AAPL".into(),
+            AlgoType::Grid {
+                upper_price: dec!(140),
+                lower_price: dec!(150),
+                grids: 2,
+            },
+            dec!(20),
+            0,
+        ))
+        .await;
+    assert!(matches!(
+        res,
+        Err(okane_core::trade::port::TradeError::AlgoOrderError(_))
+    ));
+    Ok(())
+}
+
+fn twap_order(id: &str, acct_id: &AccountId, duration_secs: u64, volume: Decimal) -> AlgoOrder {
+    AlgoOrder::new(
+        OrderId(id.into()),
+        acct_id.clone(),
+        "AAPL".into(),
+        AlgoType::Twap {
+            duration_secs,
+            total_volume: volume,
+            direction: OrderDirection::Buy,
+        },
+        volume,
+        0,
+    )
+}
+
+#[tokio::test]
+async fn test_twap_slices_volume_over_window_and_completes() -> anyhow::Result<()> {
+    let acct_id = AccountId("TwapWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+    let algo_id = OrderId("twap_01".into());
+    h.algo_service
+        .submit_algo_order(twap_order("twap_01", &acct_id, 180, dec!(30)))
+        .await?;
+
+    // 每分钟一个切片，共 3 个切片，每片 10 股
+    for minute in 0..3 {
+        let now = t0 + Duration::minutes(minute);
+        h.clock.set_time(now)?;
+        h.trade_service
+            .tick("AAPL", &bar(now, dec!(149), dec!(151), dec!(150)))
+            .await?;
+
+        let algo = h
+            .algo_service
+            .get_algo_order(&algo_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("twap order not found"))?;
+        assert_eq!(algo.filled_volume, dec!(10) * Decimal::from(minute + 1));
+    }
+
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("twap order not found"))?;
+    assert_eq!(algo.status, AlgoOrderStatus::Completed);
+
+    let snapshot = h.trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.positions.len(), 1);
+    assert_eq!(snapshot.positions[0].volume, dec!(30));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sell_twap_unwinds_position_over_window() -> anyhow::Result<()> {
+    let acct_id = AccountId("TwapSellWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+    h.trade_service
+        .submit_order(Order::new(
+            OrderId("seed_position".into()),
+            acct_id.clone(),
+            "AAPL".into(),
+            OrderDirection::Buy,
+            None,
+            dec!(30),
+            0,
+        ))
+        .await?;
+
+    let algo_id = OrderId("twap_sell".into());
+    h.algo_service
+        .submit_algo_order(AlgoOrder {
+            algo: AlgoType::Twap {
+                duration_secs: 180,
+                total_volume: dec!(30),
+                direction: OrderDirection::Sell,
+            },
+            ..twap_order("twap_sell", &acct_id, 180, dec!(30))
+        })
+        .await?;
+
+    // 每分钟卖出一个切片，持仓逐片减少直至清空
+    for minute in 0..3 {
+        let now = t0 + Duration::minutes(minute);
+        h.clock.set_time(now)?;
+        h.trade_service
+            .tick("AAPL", &bar(now, dec!(149), dec!(151), dec!(150)))
+            .await?;
+    }
+
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("twap order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(30));
+    assert_eq!(algo.status, AlgoOrderStatus::Completed);
+    let snapshot = h.trade_service.get_account(acct_id).await?;
+    assert!(snapshot.positions.iter().all(|p| p.volume == dec!(0)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_twap_waits_between_slices_and_catches_up_missed_ones() -> anyhow::Result<()> {
+    let acct_id = AccountId("TwapCatchUpWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+    let algo_id = OrderId("twap_02".into());
+    h.algo_service
+        .submit_algo_order(twap_order("twap_02", &acct_id, 300, dec!(50)))
+        .await?;
+
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(149), dec!(151), dec!(150)))
+        .await?;
+    // 同一分钟内的第二根 K 线不应重复下单
+    let now = t0 + Duration::seconds(30);
+    h.clock.set_time(now)?;
+    h.trade_service
+        .tick("AAPL", &bar(now, dec!(149), dec!(151), dec!(150)))
+        .await?;
+    let snapshot = h.trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.positions[0].volume, dec!(10));
+
+    // 行情中断至第 4 个切片：错过的切片合并为一笔追单
+    let now = t0 + Duration::minutes(3);
+    h.clock.set_time(now)?;
+    h.trade_service
+        .tick("AAPL", &bar(now, dec!(149), dec!(151), dec!(150)))
+        .await?;
+    let snapshot = h.trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.positions[0].volume, dec!(40));
+
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("twap order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(40));
+    assert_eq!(algo.status, AlgoOrderStatus::Running);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_twap_sweeps_remaining_volume_after_window_ends() -> anyhow::Result<()> {
+    let acct_id = AccountId("TwapSweepWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+    let algo_id = OrderId("twap_03".into());
+    h.algo_service
+        .submit_algo_order(twap_order("twap_03", &acct_id, 600, dec!(100)))
+        .await?;
+
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(149), dec!(151), dec!(150)))
+        .await?;
+
+    // 窗口结束后首次行情：剩余 90 股一次性扫尾
+    let now = t0 + Duration::minutes(15);
+    h.clock.set_time(now)?;
+    h.trade_service
+        .tick("AAPL", &bar(now, dec!(149), dec!(151), dec!(150)))
+        .await?;
+
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("twap order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(100));
+    assert_eq!(algo.status, AlgoOrderStatus::Completed);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_twap_rejects_mismatched_total_volume() -> anyhow::Result<()> {
+    let acct_id = AccountId("TwapInvalidWallet".to_string());
+    let h = build_harness(&acct_id)?;
+
+    let mut order = twap_order("twap_bad", &acct_id, 600, dec!(100));
+    order.requested_volume = dec!(50);
+    let res = h.algo_service.submit_algo_order(order).await;
+    assert!(matches!(
+        res,
+        Err(okane_core::trade::port::TradeError::AlgoOrderError(_))
+    ));
+    Ok(())
+}
+
+fn vwap_order(
+    id: &str,
+    acct_id: &AccountId,
+    window: Duration,
+    volume: Decimal,
+    max_participation: Decimal,
+) -> anyhow::Result<AlgoOrder> {
+    let t0 = start_time()?;
+    Ok(AlgoOrder::new(
+        OrderId(id.into()),
+        acct_id.clone(),
+        "AAPL".into(),
+        AlgoType::Vwap {
+            total_volume: volume,
+            start_time: t0.timestamp_millis(),
+            end_time: (t0 + window).timestamp_millis(),
+            max_participation,
+            direction: OrderDirection::Buy,
+        },
+        volume,
+        0,
+    ))
+}
+
+#[tokio::test]
+async fn test_sell_vwap_unwinds_position_within_window() -> anyhow::Result<()> {
+    let acct_id = AccountId("VwapSellWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+    h.trade_service
+        .submit_order(Order::new(
+            OrderId("seed_position".into()),
+            acct_id.clone(),
+            "AAPL".into(),
+            OrderDirection::Buy,
+            None,
+            dec!(20),
+            0,
+        ))
+        .await?;
+
+    let algo_id = OrderId("vwap_sell".into());
+    let mut order = vwap_order(
+        "vwap_sell",
+        &acct_id,
+        Duration::minutes(1),
+        dec!(20),
+        dec!(0.1),
+    )?;
+    if let AlgoType::Vwap { direction, .. } = &mut order.algo {
+        *direction = OrderDirection::Sell;
+    }
+    h.algo_service.submit_algo_order(order).await?;
+
+    // 单分钟窗口：本分钟成交量 10000 的 10% 足以一次卖出全部 20 股
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(149), dec!(151), dec!(150)))
+        .await?;
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("vwap order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(20));
+    assert_eq!(algo.status, AlgoOrderStatus::Completed);
+    let snapshot = h.trade_service.get_account(acct_id).await?;
+    assert!(snapshot.positions.iter().all(|p| p.volume == dec!(0)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vwap_follows_historical_volume_profile() -> anyhow::Result<()> {
+    let acct_id = AccountId("VwapWallet".to_string());
+    let t0 = start_time()?;
+    // 前一交易日同一时段：第 1 分钟成交 3000，第 2 分钟成交 1000
+    let yesterday = t0 - Duration::days(1);
+    let history = vec![
+        Candle {
+            volume: dec!(3000),
+            ..bar(yesterday, dec!(149), dec!(151), dec!(150))
+        },
+        Candle {
+            volume: dec!(1000),
+            ..bar(
+                yesterday + Duration::minutes(1),
+                dec!(149),
+                dec!(151),
+                dec!(150),
+            )
+        },
+    ];
+    let h = build_harness_with_history(&acct_id, history)?;
+    let algo_id = OrderId("vwap_01".into());
+    h.algo_service
+        .submit_algo_order(vwap_order(
+            "vwap_01",
+            &acct_id,
+            Duration::minutes(2),
+            dec!(100),
+            dec!(0.5),
+        )?)
+        .await?;
+
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(149), dec!(151), dec!(150)))
+        .await?;
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("vwap order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(75));
+
+    let now = t0 + Duration::minutes(1);
+    h.clock.set_time(now)?;
+    h.trade_service
+        .tick("AAPL", &bar(now, dec!(149), dec!(151), dec!(150)))
+        .await?;
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("vwap order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(100));
+    assert_eq!(algo.status, AlgoOrderStatus::Completed);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vwap_never_exceeds_participation_cap() -> anyhow::Result<()> {
+    let acct_id = AccountId("VwapCapWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+    let algo_id = OrderId("vwap_02".into());
+    h.algo_service
+        .submit_algo_order(vwap_order(
+            "vwap_02",
+            &acct_id,
+            Duration::minutes(1),
+            dec!(100),
+            dec!(0.1),
+        )?)
+        .await?;
+
+    // 本分钟成交量 200，参与率 10%：最多 20 股
+    h.trade_service
+        .tick(
+            "AAPL",
+            &Candle {
+                volume: dec!(200),
+                ..bar(t0, dec!(149), dec!(151), dec!(150))
+            },
+        )
+        .await?;
+    // 同一分钟滚动成交量增至 300：额度仅剩 10 股
+    let now = t0 + Duration::seconds(30);
+    h.clock.set_time(now)?;
+    h.trade_service
+        .tick(
+            "AAPL",
+            &Candle {
+                volume: dec!(300),
+                ..bar(t0, dec!(149), dec!(151), dec!(150))
+            },
+        )
+        .await?;
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("vwap order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(30));
+
+    // 窗口结束：不扫尾，未全部成交的母单以 Expired 收尾
+    let now = t0 + Duration::minutes(1);
+    h.clock.set_time(now)?;
+    h.trade_service
+        .tick("AAPL", &bar(now, dec!(149), dec!(151), dec!(150)))
+        .await?;
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("vwap order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(30));
+    assert_eq!(algo.status, AlgoOrderStatus::Expired);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_grid_resumes_after_restart_and_tracks_surviving_children() -> anyhow::Result<()> {
+    let acct_id = AccountId("GridRestartWallet".to_string());
+    let account_manager = Arc::new(AccountManager::new());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(100000.0));
+    let pending_port = Arc::new(MemoryPendingOrderStore::new());
+    let algo_store = Arc::new(MemoryAlgoOrderStore::new());
+    let t0 = start_time()?;
+
+    let before = build_persistent_harness(
+        account_manager.clone(),
+        pending_port.clone(),
+        algo_store.clone(),
+    )?;
+    let algo_id = OrderId("grid_restart".into());
+    before
+        .algo_service
+        .submit_algo_order(AlgoOrder::new(
+            algo_id.clone(),
+            acct_id.clone(),
+            "AAPL".into(),
+            AlgoType::Grid {
+                upper_price: dec!(150),
+                lower_price: dec!(140),
+                grids: 2,
+            },
+            dec!(20),
+            t0.timestamp_millis(),
+        ))
+        .await?;
+    before
+        .trade_service
+        .tick("AAPL", &bar(t0, dec!(147), dec!(149), dec!(148)))
+        .await?;
+    let record = algo_store
+        .get(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("grid order should be persisted"))?;
+    assert_eq!(record.child_seq, 2);
+    drop(before);
+
+    // 重启：新实例从仓储恢复算法单，并接管仍在挂单中的两个子单
+    let after =
+        build_persistent_harness(account_manager, pending_port.clone(), algo_store.clone())?;
+    assert_eq!(after.algo_service.recover(pending_port.as_ref()).await?, 1);
+    assert_eq!(
+        after.algo_service.running_symbols(),
+        vec!["AAPL".to_string()]
+    );
+
+    // 击穿 145 档买单：成交归属到恢复的母单，并在上沿挂出卖单
+    after
+        .trade_service
+        .tick("AAPL", &bar(t0, dec!(144), dec!(146), dec!(144)))
+        .await?;
+    after
+        .trade_service
+        .tick("AAPL", &bar(t0, dec!(145.5), dec!(146), dec!(146)))
+        .await?;
+    let algo = after
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("grid order not recovered"))?;
+    assert_eq!(algo.filled_volume, dec!(10));
+
+    let orders = after.trade_service.get_orders(&acct_id).await?;
+    assert_eq!(orders.len(), 2);
+    let sell = orders
+        .iter()
+        .find(|o| o.direction == OrderDirection::Sell)
+        .ok_or_else(|| anyhow::anyhow!("sell should be re-armed"))?;
+    assert_eq!(sell.id, OrderId("grid_restart-3".into()));
+
+    // 撤销恢复后的母单会一并撤销接管的子单
+    after.algo_service.cancel_algo_order(&algo_id).await?;
+    assert!(after.trade_service.get_orders(&acct_id).await?.is_empty());
+    let record = algo_store
+        .get(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("grid order should be persisted"))?;
+    assert_eq!(record.order.status, AlgoOrderStatus::Canceled);
+    assert!(algo_store.load_active().await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recover_reattaches_children_routed_to_broker_gateway() -> anyhow::Result<()> {
+    let acct_id = AccountId("GridRoutedWallet".to_string());
+    let account_manager = Arc::new(AccountManager::new());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(100000.0));
+    let pending_port = Arc::new(MemoryPendingOrderStore::new());
+    let algo_store = Arc::new(MemoryAlgoOrderStore::new());
+    let t0 = start_time()?;
+
+    let before = build_persistent_harness(
+        account_manager.clone(),
+        pending_port.clone(),
+        algo_store.clone(),
+    )?;
+    let algo_id = OrderId("grid_routed".into());
+    before
+        .algo_service
+        .submit_algo_order(AlgoOrder::new(
+            algo_id.clone(),
+            acct_id.clone(),
+            "AAPL".into(),
+            AlgoType::Grid {
+                upper_price: dec!(150),
+                lower_price: dec!(140),
+                grids: 2,
+            },
+            dec!(20),
+            t0.timestamp_millis(),
+        ))
+        .await?;
+    before
+        .trade_service
+        .tick("AAPL", &bar(t0, dec!(147), dec!(149), dec!(148)))
+        .await?;
+
+    // 子单实际由券商网关托管：只存在于外发订单仓储，本地活动订单簿为空
+    let broker_orders = Arc::new(MemoryBrokerOrderStore::new());
+    for child in pending_port.get_by_account(&acct_id).await? {
+        broker_orders
+            .save(BrokerOrderRecord {
+                account_type: "fix".into(),
+                external_order_id: child.id.0.clone(),
+                order: child,
+                reserved_price: None,
+                settled_notional: Decimal::ZERO,
+                settled_commission: Decimal::ZERO,
+            })
+            .await?;
+    }
+    assert_eq!(broker_orders.load_open().await?.len(), 2);
+    drop(before);
+
+    // 重启：关联外发订单仓储后，两个档位均接管券商侧子单，不再重复挂单
+    let clock = Arc::new(FakeClockProvider::new(t0));
+    let routed_pending = Arc::new(MemoryPendingOrderStore::new());
+    let trade_service = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(LocalMatchEngine::new(Decimal::ZERO)),
+        Arc::new(FixedPriceMarket { history: vec![] }),
+        routed_pending.clone(),
+        clock.clone(),
+    ));
+    let algo_service = Arc::new(
+        AlgoOrderService::new(trade_service.clone(), clock)
+            .with_store(algo_store)
+            .with_broker_orders(broker_orders),
+    );
+    trade_service.set_algo_service(algo_service.clone())?;
+    assert_eq!(algo_service.recover(routed_pending.as_ref()).await?, 1);
+    trade_service
+        .tick("AAPL", &bar(t0, dec!(147), dec!(149), dec!(148)))
+        .await?;
+    assert!(trade_service.get_orders(&acct_id).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_grid_restart_keeps_sell_side_of_level_without_resting_child() -> anyhow::Result<()> {
+    let acct_id = AccountId("GridSideWallet".to_string());
+    let account_manager = Arc::new(AccountManager::new());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(100000.0));
+    let pending_port = Arc::new(MemoryPendingOrderStore::new());
+    let algo_store = Arc::new(MemoryAlgoOrderStore::new());
+    let t0 = start_time()?;
+
+    let before = build_persistent_harness(
+        account_manager.clone(),
+        pending_port.clone(),
+        algo_store.clone(),
+    )?;
+    let algo_id = OrderId("grid_sides".into());
+    before
+        .algo_service
+        .submit_algo_order(AlgoOrder::new(
+            algo_id.clone(),
+            acct_id.clone(),
+            "AAPL".into(),
+            AlgoType::Grid {
+                upper_price: dec!(150),
+                lower_price: dec!(140),
+                grids: 2,
+            },
+            dec!(20),
+            t0.timestamp_millis(),
+        ))
+        .await?;
+    // 145 档买单成交后在上沿挂出卖单
+    for candle in [
+        bar(t0, dec!(147), dec!(149), dec!(148)),
+        bar(t0, dec!(144), dec!(146), dec!(144)),
+        bar(t0, dec!(145.5), dec!(146), dec!(146)),
+    ] {
+        before.trade_service.tick("AAPL", &candle).await?;
+    }
+    let record = algo_store
+        .get(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("grid order should be persisted"))?;
+    assert_eq!(
+        record.grid_sides,
+        vec![OrderDirection::Buy, OrderDirection::Sell]
+    );
+
+    // 停机期间卖单被撤销：该档位不再有在途子单
+    before
+        .trade_service
+        .cancel_order(OrderId("grid_sides-3".into()))
+        .await?;
+    drop(before);
+
+    let after =
+        build_persistent_harness(account_manager, pending_port.clone(), algo_store.clone())?;
+    assert_eq!(after.algo_service.recover(pending_port.as_ref()).await?, 1);
+    after
+        .trade_service
+        .tick("AAPL", &bar(t0, dec!(147), dec!(149), dec!(148)))
+        .await?;
+
+    // 档位按持久化的方向恢复：重新挂出卖单，而不是在 145 重复买入
+    let mut orders = after.trade_service.get_orders(&acct_id).await?;
+    orders.sort_by_key(|o| o.price);
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders[0].direction, OrderDirection::Buy);
+    assert_eq!(orders[0].price, Some(dec!(140)));
+    assert_eq!(orders[1].direction, OrderDirection::Sell);
+    assert_eq!(orders[1].price, Some(dec!(150)));
+    assert_eq!(orders[1].id, OrderId("grid_sides-4".into()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sell_snipe_fills_at_protective_limit_then_completes() -> anyhow::Result<()> {
+    let acct_id = AccountId("SnipeSellWallet".to_string());
+    let h = build_harness(&acct_id)?;
+    let t0 = start_time()?;
+
+    h.trade_service
+        .submit_order(Order::new(
+            OrderId("seed_position".into()),
+            acct_id.clone(),
+            "AAPL".into(),
+            OrderDirection::Buy,
+            None,
+            dec!(10),
+            0,
+        ))
+        .await?;
+
+    let algo_id = OrderId("snipe_sell".into());
+    h.algo_service
+        .submit_algo_order(AlgoOrder::new(
+            algo_id.clone(),
+            acct_id.clone(),
+            "AAPL".into(),
+            AlgoType::Snipe {
+                target_price: dec!(155),
+                max_slippage: dec!(1),
+                direction: OrderDirection::Sell,
+            },
+            dec!(10),
+            t0.timestamp_millis(),
+        ))
+        .await?;
+
+    // 价格未达目标：不触发
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(150), dec!(154.5), dec!(154)))
+        .await?;
+    assert!(h.trade_service.get_orders(&acct_id).await?.is_empty());
+
+    // 到达目标：以 155 - 1 挂出保护性卖出限价，开盘价 155.5 已优于限价，同一根 K 线上按开盘价成交
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(154), dec!(156), dec!(155.5)))
+        .await?;
+    assert!(h.trade_service.get_orders(&acct_id).await?.is_empty());
+
+    // 下一根 K 线结算子单成交，母单完成
+    h.trade_service
+        .tick("AAPL", &bar(t0, dec!(150), dec!(152), dec!(151)))
+        .await?;
+    let algo = h
+        .algo_service
+        .get_algo_order(&algo_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("snipe order not found"))?;
+    assert_eq!(algo.filled_volume, dec!(10));
+    assert_eq!(algo.status, AlgoOrderStatus::Completed);
+
+    let snapshot = h.trade_service.get_account(acct_id).await?;
+    assert!(snapshot.positions.iter().all(|p| p.volume == dec!(0)));
+    assert_eq!(snapshot.available_balance, dec!(100055));
+    Ok(())
+}
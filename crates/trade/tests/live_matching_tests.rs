@@ -0,0 +1,325 @@
+use okane_core::common::{Stock as StockIdentity, TimeFrame};
+use okane_core::market::entity::Candle;
+use okane_core::market::error::MarketError;
+use okane_core::market::port::{CandleStream, Market, Stock, StockStatus};
+use okane_core::test_utils::wait_for_condition;
+use okane_core::trade::entity::{AccountId, Order, OrderDirection, OrderId};
+use okane_core::trade::port::{PendingOrderPort, TradePort};
+use okane_store::pending_order::MemoryPendingOrderStore;
+use okane_trade::account::AccountManager;
+use okane_trade::live::LiveMatchingService;
+use okane_trade::matcher::LocalMatchEngine;
+use okane_trade::service::TradeService;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// 通过 broadcast 通道推送实时行情的测试标的，推送 `None` 模拟行情流出错
+struct LiveStock {
+    identity: StockIdentity,
+    tx: broadcast::Sender<Option<Candle>>,
+}
+
+#[async_trait::async_trait]
+impl Stock for LiveStock {
+    fn identity(&self) -> &StockIdentity {
+        &self.identity
+    }
+    fn current_price(&self) -> Result<Option<Decimal>, MarketError> {
+        Ok(Some(dec!(150.0)))
+    }
+    fn latest_candle(&self, _timeframe: TimeFrame) -> Result<Option<Candle>, MarketError> {
+        Ok(None)
+    }
+    fn last_closed_candle(&self, _timeframe: TimeFrame) -> Result<Option<Candle>, MarketError> {
+        Ok(None)
+    }
+    fn subscribe(&self, _timeframe: TimeFrame) -> Result<CandleStream, MarketError> {
+        let rx = self.tx.subscribe();
+        Ok(Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            let item = match rx.recv().await.ok()? {
+                Some(candle) => Ok(candle),
+                None => Err(MarketError::Network("feed dropped".to_string())),
+            };
+            Some((item, rx))
+        })))
+    }
+    async fn fetch_history(
+        &self,
+        _timeframe: TimeFrame,
+        _start: chrono::DateTime<chrono::Utc>,
+        _end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Candle>, MarketError> {
+        Ok(vec![])
+    }
+    fn status(&self) -> StockStatus {
+        StockStatus::Online
+    }
+}
+
+struct LiveMarket {
+    stock: Arc<LiveStock>,
+}
+
+#[async_trait::async_trait]
+impl Market for LiveMarket {
+    async fn get_stock(&self, _symbol: &str) -> Result<Arc<dyn Stock>, MarketError> {
+        Ok(self.stock.clone())
+    }
+
+    async fn search_symbols(
+        &self,
+        _query: &str,
+    ) -> Result<Vec<okane_core::store::port::StockMetadata>, MarketError> {
+        Ok(vec![])
+    }
+}
+
+struct Harness {
+    tx: broadcast::Sender<Option<Candle>>,
+    pending_port: Arc<MemoryPendingOrderStore>,
+    trade_service: Arc<TradeService>,
+    live: Arc<LiveMatchingService>,
+}
+
+fn build_harness(acct_id: &AccountId) -> Harness {
+    let (tx, _) = broadcast::channel(16);
+    let market = Arc::new(LiveMarket {
+        stock: Arc::new(LiveStock {
+            identity: StockIdentity {
+                symbol: "AAPL".to_string(),
+                exchange: None,
+            },
+            tx: tx.clone(),
+        }),
+    });
+    let account_manager = Arc::new(AccountManager::new());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000.0));
+    let pending_port = Arc::new(MemoryPendingOrderStore::new());
+    let time_provider = Arc::new(okane_core::common::time::RealTimeProvider);
+    let trade_service = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(LocalMatchEngine::new(Decimal::ZERO)),
+        market.clone(),
+        pending_port.clone(),
+        time_provider.clone(),
+    ));
+    let live = Arc::new(
+        LiveMatchingService::new(
+            market,
+            trade_service.clone(),
+            pending_port.clone(),
+            time_provider,
+        )
+        .with_resubscribe_delay(Duration::from_millis(10)),
+    );
+    Harness {
+        tx,
+        pending_port,
+        trade_service,
+        live,
+    }
+}
+
+fn quote(close: Decimal) -> Candle {
+    Candle {
+        time: chrono::Utc::now(),
+        open: dec!(150.0),
+        high: dec!(150.0),
+        low: close.min(dec!(150.0)),
+        close,
+        adj_close: None,
+        volume: dec!(1000.0),
+        is_final: false,
+    }
+}
+
+#[tokio::test]
+async fn test_live_loop_fills_resting_limit_order_when_price_crosses() -> anyhow::Result<()> {
+    let acct_id = AccountId("LiveWallet".to_string());
+    let h = build_harness(&acct_id);
+    h.trade_service.set_live_matcher(h.live.clone())?;
+
+    let order = Order::new(
+        OrderId("live_limit_buy".into()),
+        acct_id.clone(),
+        "AAPL".into(),
+        OrderDirection::Buy,
+        Some(dec!(140.0)),
+        dec!(10.0),
+        0,
+    );
+    h.trade_service.submit_order(order).await?;
+    assert!(h.live.is_watching("AAPL"));
+
+    let tx = h.tx.clone();
+    let subscribed = wait_for_condition(Duration::from_secs(2), Duration::from_millis(10), || {
+        let tx = tx.clone();
+        async move { tx.receiver_count() > 0 }
+    })
+    .await;
+    assert!(subscribed, "live loop should subscribe the symbol");
+
+    h.tx.send(Some(quote(dec!(145.0))))?;
+    h.tx.send(Some(quote(dec!(139.5))))?;
+
+    let pending_port = h.pending_port.clone();
+    let filled = wait_for_condition(Duration::from_secs(2), Duration::from_millis(10), || {
+        let pending_port = pending_port.clone();
+        async move {
+            pending_port
+                .get_by_symbol("AAPL")
+                .await
+                .map(|orders| orders.is_empty())
+                .unwrap_or(false)
+        }
+    })
+    .await;
+    assert!(
+        filled,
+        "crossed limit order should be settled by the live loop"
+    );
+
+    // 报价由 145 直接跳到 139.5，越过限价的报价即为成交价
+    let snapshot = h.trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.available_balance, dec!(8605.0));
+    assert_eq!(snapshot.frozen_balance, dec!(0.0));
+    assert_eq!(snapshot.positions.len(), 1);
+    assert_eq!(snapshot.positions[0].average_price, dec!(139.5));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_live_loop_skips_finalized_bars() -> anyhow::Result<()> {
+    let acct_id = AccountId("FinalBarWallet".to_string());
+    let h = build_harness(&acct_id);
+    h.trade_service.set_live_matcher(h.live.clone())?;
+
+    let order = Order::new(
+        OrderId("final_bar_buy".into()),
+        acct_id.clone(),
+        "AAPL".into(),
+        OrderDirection::Buy,
+        Some(dec!(140.0)),
+        dec!(10.0),
+        0,
+    );
+    h.trade_service.submit_order(order).await?;
+
+    let tx = h.tx.clone();
+    let subscribed = wait_for_condition(Duration::from_secs(2), Duration::from_millis(10), || {
+        let tx = tx.clone();
+        async move { tx.receiver_count() > 0 }
+    })
+    .await;
+    assert!(subscribed, "live loop should subscribe the symbol");
+
+    // 定稿 K 线不参与撮合，订单由随后的快照以 139.5 成交
+    h.tx.send(Some(Candle {
+        is_final: true,
+        ..quote(dec!(139.0))
+    }))?;
+    h.tx.send(Some(quote(dec!(139.5))))?;
+
+    let pending_port = h.pending_port.clone();
+    let filled = wait_for_condition(Duration::from_secs(2), Duration::from_millis(10), || {
+        let pending_port = pending_port.clone();
+        async move {
+            pending_port
+                .get_by_symbol("AAPL")
+                .await
+                .map(|orders| orders.is_empty())
+                .unwrap_or(false)
+        }
+    })
+    .await;
+    assert!(filled, "snapshot after the final bar should fill the order");
+
+    let snapshot = h.trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.positions.len(), 1);
+    assert_eq!(snapshot.positions[0].average_price, dec!(139.5));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_live_loop_recovers_symbols_with_persisted_orders() -> anyhow::Result<()> {
+    let acct_id = AccountId("RecoveredWallet".to_string());
+    let h = build_harness(&acct_id);
+
+    h.pending_port
+        .save(Order::new(
+            OrderId("persisted_limit".into()),
+            acct_id,
+            "AAPL".into(),
+            OrderDirection::Sell,
+            Some(dec!(160.0)),
+            dec!(1.0),
+            0,
+        ))
+        .await?;
+
+    assert!(!h.live.is_watching("AAPL"));
+    h.live.start().await?;
+    assert!(h.live.is_watching("AAPL"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_live_loop_resubscribes_after_stream_error() -> anyhow::Result<()> {
+    let acct_id = AccountId("ResubscribeWallet".to_string());
+    let h = build_harness(&acct_id);
+    h.trade_service.set_live_matcher(h.live.clone())?;
+
+    h.trade_service
+        .submit_order(Order::new(
+            OrderId("resubscribe_buy".into()),
+            acct_id.clone(),
+            "AAPL".into(),
+            OrderDirection::Buy,
+            Some(dec!(140.0)),
+            dec!(10.0),
+            0,
+        ))
+        .await?;
+
+    let tx = h.tx.clone();
+    let subscribed = wait_for_condition(Duration::from_secs(2), Duration::from_millis(10), || {
+        let tx = tx.clone();
+        async move { tx.receiver_count() > 0 }
+    })
+    .await;
+    assert!(subscribed, "live loop should subscribe the symbol");
+
+    // 行情流出错后订阅任务不退出，退避后重新订阅
+    h.tx.send(None)?;
+    assert!(h.live.is_watching("AAPL"));
+
+    // 重新订阅前推送的行情可能丢失，持续推送直到挂单成交
+    let tx = h.tx.clone();
+    let pending_port = h.pending_port.clone();
+    let filled = wait_for_condition(Duration::from_secs(5), Duration::from_millis(20), || {
+        let tx = tx.clone();
+        let pending_port = pending_port.clone();
+        async move {
+            let settled = pending_port
+                .get_by_symbol("AAPL")
+                .await
+                .map(|orders| orders.is_empty())
+                .unwrap_or(false);
+            if !settled {
+                // 重新订阅前没有接收方，发送失败时留待下一轮重试
+                tx.send(Some(quote(dec!(139.5)))).ok();
+            }
+            settled
+        }
+    })
+    .await;
+    assert!(filled, "resubscribed live loop should settle the order");
+
+    let snapshot = h.trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.positions.len(), 1);
+    assert_eq!(snapshot.positions[0].average_price, dec!(139.5));
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use okane_core::common::{Stock as StockIdentity, TimeFrame};
-use okane_core::market::entity::Candle;
+use okane_core::market::entity::{Candle, CorporateAction};
 use okane_core::market::error::MarketError;
 use okane_core::market::port::{CandleStream, Market, Stock, StockStatus};
 use okane_core::trade::entity::{AccountId, AccountSnapshot, Order, OrderDirection, OrderId};
@@ -14,6 +14,7 @@ use tokio::time::{Duration, sleep};
 struct DummyStock {
     identity: StockIdentity,
     price: rust_decimal::Decimal,
+    actions: Vec<CorporateAction>,
 }
 
 #[async_trait::async_trait]
@@ -49,6 +50,19 @@ impl Stock for DummyStock {
     fn status(&self) -> StockStatus {
         StockStatus::Online
     }
+
+    async fn corporate_actions(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<CorporateAction>, MarketError> {
+        Ok(self
+            .actions
+            .iter()
+            .filter(|action| action.ex_date >= start && action.ex_date <= end)
+            .cloned()
+            .collect())
+    }
 }
 
 struct MockMarket;
@@ -62,6 +76,7 @@ impl Market for MockMarket {
                 exchange: None,
             },
             price: dec!(150.0),
+            actions: vec![],
         }))
     }
 
@@ -338,3 +353,2442 @@ async fn test_filled_market_order_is_not_returned_as_active_and_cannot_be_cancel
 
     Ok(())
 }
+
+fn bar(
+    minute: i64,
+    open: rust_decimal::Decimal,
+    high: rust_decimal::Decimal,
+    low: rust_decimal::Decimal,
+    volume: rust_decimal::Decimal,
+) -> anyhow::Result<Candle> {
+    Ok(Candle {
+        time: chrono::DateTime::from_timestamp_millis(minute * 60_000)
+            .ok_or_else(|| anyhow::anyhow!("invalid bar time"))?,
+        open,
+        high,
+        low,
+        close: open,
+        adj_close: None,
+        volume,
+        is_final: true,
+    })
+}
+
+#[tokio::test]
+async fn test_limit_order_fills_at_gap_open_and_partially_across_bars() -> anyhow::Result<()> {
+    use okane_core::trade::entity::OrderStatus;
+    use okane_core::trade::port::BacktestTradePort;
+    use okane_trade::fill_model::BarFillModel;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("GapWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000.0));
+
+    let pending_port = Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new());
+    let matcher = Arc::new(
+        okane_trade::matcher::LocalMatchEngine::new(rust_decimal::Decimal::ZERO).with_fill_model(
+            Arc::new(BarFillModel::new().with_max_participation(dec!(0.1))),
+        ),
+    );
+    let trade_service = TradeService::new(
+        account_manager,
+        matcher,
+        Arc::new(MockMarket),
+        pending_port,
+        Arc::new(okane_core::common::time::RealTimeProvider),
+    );
+
+    let order_id = OrderId("gap_limit_buy".into());
+    trade_service
+        .submit_order(Order::new(
+            order_id.clone(),
+            acct_id.clone(),
+            "AAPL".into(),
+            OrderDirection::Buy,
+            Some(dec!(100.0)),
+            dec!(15.0),
+            0,
+        ))
+        .await?;
+
+    // 第一根 K 线跳空低开于 95，按开盘价成交，但参与率只允许 100 * 10% = 10
+    trade_service
+        .tick("AAPL", &bar(0, dec!(95), dec!(96), dec!(94), dec!(100))?)
+        .await?;
+    let order = trade_service
+        .get_order(&order_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("partially filled order should stay active"))?;
+    assert_eq!(order.status, OrderStatus::PartialFilled);
+    assert_eq!(order.filled_volume, dec!(10.0));
+
+    // 第二根 K 线盘中回落触及限价，剩余 5 按限价成交
+    trade_service
+        .tick("AAPL", &bar(1, dec!(102), dec!(103), dec!(99), dec!(100))?)
+        .await?;
+    assert!(trade_service.get_order(&order_id).await?.is_none());
+
+    let snapshot = trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(0.0));
+    assert_eq!(snapshot.available_balance, dec!(8550.0));
+    assert_eq!(snapshot.positions[0].volume, dec!(15.0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stop_orders_wait_for_trigger_and_top_up_gap_fills() -> anyhow::Result<()> {
+    use okane_core::trade::entity::{OrderTrigger, TrailOffset};
+    use okane_core::trade::port::BacktestTradePort;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("StopWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000.0));
+
+    let pending_port = Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new());
+    let matcher = Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+        rust_decimal::Decimal::ZERO,
+    ));
+    let trade_service = TradeService::new(
+        account_manager,
+        matcher,
+        Arc::new(MockMarket),
+        pending_port,
+        Arc::new(okane_core::common::time::RealTimeProvider),
+    );
+
+    // 最新价 150：买入止损 160 按触发价冻结 1600，跟踪止损卖单以 150 为初始最高价
+    let stop_id = OrderId("stop_buy".into());
+    trade_service
+        .submit_order(
+            Order::new(
+                stop_id.clone(),
+                acct_id.clone(),
+                "AAPL".into(),
+                OrderDirection::Buy,
+                None,
+                dec!(10.0),
+                0,
+            )
+            .with_trigger(OrderTrigger::stop(dec!(160))),
+        )
+        .await?;
+    let trailing_id = OrderId("trailing_sell".into());
+    trade_service
+        .submit_order(
+            Order::new(
+                trailing_id.clone(),
+                acct_id.clone(),
+                "AAPL".into(),
+                OrderDirection::Sell,
+                None,
+                dec!(5.0),
+                0,
+            )
+            .with_trigger(OrderTrigger::trailing(TrailOffset::Amount(dec!(5)))),
+        )
+        .await?;
+    assert_eq!(
+        trade_service
+            .get_account(acct_id.clone())
+            .await?
+            .frozen_balance,
+        dec!(1600.0)
+    );
+
+    // 未触及任何触发价，但跟踪止损的最高价上移至 158 并被持久化
+    trade_service
+        .tick("AAPL", &bar(0, dec!(155), dec!(158), dec!(154), dec!(100))?)
+        .await?;
+    let trailing = trade_service
+        .get_order(&trailing_id)
+        .await?
+        .and_then(|order| order.trigger)
+        .ok_or_else(|| anyhow::anyhow!("trailing stop should stay pending"))?;
+    assert_eq!(trailing.extreme, Some(dec!(158)));
+    assert!(!trailing.triggered);
+    trade_service.cancel_order(trailing_id).await?;
+
+    // 跳空高开 165 穿越触发价，按开盘价成交，超出冻结的 50 先补冻结再结算
+    trade_service
+        .tick("AAPL", &bar(1, dec!(165), dec!(166), dec!(164), dec!(100))?)
+        .await?;
+    assert!(trade_service.get_order(&stop_id).await?.is_none());
+
+    let snapshot = trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(0.0));
+    assert_eq!(snapshot.available_balance, dec!(8350.0));
+    assert_eq!(snapshot.positions[0].volume, dec!(10.0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_time_in_force_expires_orders_and_releases_funds() -> anyhow::Result<()> {
+    use chrono::TimeZone;
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::trade::entity::{OrderStatus, TimeInForce};
+    use okane_core::trade::port::BacktestTradePort;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("TifWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000.0));
+
+    // 周三 15:00 UTC (夏令时，美东 11:00)，DAY 单当日 20:00 UTC 收盘过期
+    let open = chrono::Utc
+        .with_ymd_and_hms(2026, 3, 18, 15, 0, 0)
+        .single()
+        .ok_or_else(|| anyhow::anyhow!("invalid time"))?;
+    let clock = Arc::new(FakeClockProvider::new(open));
+    let trade_service = TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        Arc::new(MockMarket),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        clock.clone(),
+    );
+    let limit_buy = |id: &str, price, tif| {
+        Order::new(
+            OrderId(id.into()),
+            acct_id.clone(),
+            "AAPL".into(),
+            OrderDirection::Buy,
+            Some(price),
+            dec!(10.0),
+            0,
+        )
+        .with_time_in_force(tif)
+    };
+
+    // 最新价 150：限价 140 的 IOC / FOK 无法立即成交，整单过期且不占用资金
+    for tif in [TimeInForce::Ioc, TimeInForce::Fok] {
+        let order_id = trade_service
+            .submit_order(limit_buy("ioc_miss", dec!(140), tif))
+            .await?;
+        assert!(trade_service.get_order(&order_id).await?.is_none());
+    }
+    // 限价 155 的 IOC 以最新价立即全部成交
+    trade_service
+        .submit_order(limit_buy("ioc_hit", dec!(155), TimeInForce::Ioc))
+        .await?;
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(0.0));
+    assert_eq!(snapshot.available_balance, dec!(8500.0));
+
+    // 过去的 GTD 时间被拒绝
+    let err = trade_service
+        .submit_order(limit_buy(
+            "gtd_past",
+            dec!(140),
+            TimeInForce::Gtd {
+                expire_at: open.timestamp_millis(),
+            },
+        ))
+        .await
+        .err()
+        .ok_or_else(|| anyhow::anyhow!("past gtd should be rejected"))?;
+    assert!(matches!(
+        err,
+        okane_core::trade::port::TradeError::InvalidOrder(_)
+    ));
+
+    let day_id = trade_service
+        .submit_order(limit_buy("day", dec!(140), TimeInForce::Day))
+        .await?;
+    let gtd_expire_at = open.timestamp_millis() + 3_600_000;
+    let gtd_id = trade_service
+        .submit_order(limit_buy(
+            "gtd",
+            dec!(140),
+            TimeInForce::Gtd {
+                expire_at: gtd_expire_at,
+            },
+        ))
+        .await?;
+    let day_order = trade_service
+        .get_order(&day_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("day order should rest"))?;
+    assert_eq!(day_order.status, OrderStatus::Pending);
+    assert_eq!(
+        day_order.expire_at,
+        Some(open.timestamp_millis() + 5 * 3_600_000)
+    );
+    assert_eq!(
+        trade_service
+            .get_account(acct_id.clone())
+            .await?
+            .frozen_balance,
+        dec!(2800.0)
+    );
+
+    // 一小时后 GTD 到期，收盘后 DAY 到期；到期的订单在撮合前移除并解冻
+    clock.set_time(open + chrono::Duration::hours(1))?;
+    trade_service
+        .tick("AAPL", &bar(0, dec!(150), dec!(151), dec!(149), dec!(100))?)
+        .await?;
+    assert!(trade_service.get_order(&gtd_id).await?.is_none());
+    assert!(trade_service.get_order(&day_id).await?.is_some());
+
+    clock.set_time(open + chrono::Duration::hours(5))?;
+    trade_service
+        .tick("AAPL", &bar(1, dec!(130), dec!(131), dec!(129), dec!(100))?)
+        .await?;
+    assert!(trade_service.get_order(&day_id).await?.is_none());
+
+    let snapshot = trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(0.0));
+    assert_eq!(snapshot.available_balance, dec!(8500.0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bracket_activates_legs_after_entry_and_oco_cancels_sibling() -> anyhow::Result<()> {
+    use okane_core::trade::entity::{OrderGroup, OrderTrigger};
+    use okane_core::trade::port::BacktestTradePort;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("BracketWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000.0));
+
+    let trade_service = TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        Arc::new(MockMarket),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        Arc::new(okane_core::common::time::RealTimeProvider),
+    );
+    let new_order = |id: &str, direction, price| {
+        Order::new(
+            OrderId(id.into()),
+            acct_id.clone(),
+            "AAPL".into(),
+            direction,
+            price,
+            dec!(10.0),
+            0,
+        )
+    };
+
+    let entry = new_order("entry", OrderDirection::Buy, Some(dec!(100)));
+    let group_id = trade_service
+        .submit_order_group(
+            OrderGroup::bracket(
+                OrderId("bracket".into()),
+                &entry,
+                new_order("take_profit", OrderDirection::Sell, Some(dec!(120))),
+                new_order("stop_loss", OrderDirection::Sell, None)
+                    .with_trigger(OrderTrigger::stop(dec!(90))),
+                0,
+            ),
+            Some(entry),
+        )
+        .await?;
+
+    // 入场单成交前子单不挂出
+    assert_eq!(trade_service.get_orders(&acct_id).await?.len(), 1);
+
+    trade_service
+        .tick("AAPL", &bar(0, dec!(101), dec!(102), dec!(99), dec!(100))?)
+        .await?;
+    let group = trade_service
+        .get_order_group(&group_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("bracket should stay active with its legs"))?;
+    assert!(group.activated);
+    let mut active: Vec<String> = trade_service
+        .get_orders(&acct_id)
+        .await?
+        .into_iter()
+        .map(|order| order.id.0)
+        .collect();
+    active.sort();
+    assert_eq!(active, ["stop_loss", "take_profit"]);
+
+    // 同一根 K 线先到最高价触发止盈，随后的低点不再触发止损
+    trade_service
+        .tick("AAPL", &bar(1, dec!(110), dec!(121), dec!(89), dec!(100))?)
+        .await?;
+    assert!(trade_service.get_orders(&acct_id).await?.is_empty());
+    assert!(trade_service.get_order_group(&group_id).await?.is_none());
+
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(10200.0));
+    assert_eq!(snapshot.frozen_balance, dec!(0.0));
+
+    // OCO 买单两腿各自冻结资金；撤销其中一腿时另一腿一并撤销并解冻
+    let oco_id = trade_service
+        .submit_order_group(
+            OrderGroup::oco(
+                OrderId("oco".into()),
+                new_order("oco_limit", OrderDirection::Buy, Some(dec!(140))),
+                new_order("oco_stop", OrderDirection::Buy, None)
+                    .with_trigger(OrderTrigger::stop(dec!(160))),
+                0,
+            ),
+            None,
+        )
+        .await?;
+    assert_eq!(
+        trade_service
+            .get_account(acct_id.clone())
+            .await?
+            .frozen_balance,
+        dec!(3000.0)
+    );
+    trade_service
+        .cancel_order(OrderId("oco_limit".into()))
+        .await?;
+    assert!(trade_service.get_orders(&acct_id).await?.is_empty());
+    assert!(trade_service.get_order_group(&oco_id).await?.is_none());
+    assert_eq!(
+        trade_service.get_account(acct_id).await?.frozen_balance,
+        dec!(0.0)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_risk_controls_reject_orders_per_account_rules() -> anyhow::Result<()> {
+    use okane_core::store::port::SystemStore;
+    use okane_core::trade::port::TradeError;
+    use okane_core::trade::risk::RiskViolation;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    system_store
+        .save_user(&okane_core::store::port::User {
+            id: "u1".to_string(),
+            name: "Risk Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: okane_core::store::port::UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account(
+            "u1",
+            "RiskWallet",
+            "risk wallet",
+            "local",
+            serde_json::json!({
+                "risk_rules": {
+                    "max_order_notional": "3000",
+                    "max_position_per_symbol": "30",
+                    "max_orders_per_minute": 3,
+                    "no_short": true,
+                    "denied_symbols": ["TSLA"]
+                }
+            }),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("RiskWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(100000.0));
+    let market = Arc::new(MockMarket);
+    let trade_service = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        market.clone(),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        Arc::new(okane_core::common::time::RealTimeProvider),
+    ));
+    let risk_port = okane_trade::risk::RiskControlledTradePort::new(
+        trade_service,
+        system_store,
+        market,
+        Arc::new(okane_core::common::time::RealTimeProvider),
+    );
+
+    let mut seq = 0;
+    let mut submit = async |symbol: &str, direction, volume| {
+        seq += 1;
+        risk_port
+            .submit_order(Order::new(
+                OrderId(format!("R_{}", seq)),
+                acct_id.clone(),
+                symbol.to_string(),
+                direction,
+                None,
+                volume,
+                0,
+            ))
+            .await
+    };
+
+    // 无持仓卖出被拦截
+    assert!(matches!(
+        submit("AAPL", OrderDirection::Sell, dec!(10)).await,
+        Err(TradeError::RiskRejected(RiskViolation::ShortSell { .. }))
+    ));
+    assert!(matches!(
+        submit("TSLA", OrderDirection::Buy, dec!(1)).await,
+        Err(TradeError::RiskRejected(
+            RiskViolation::SymbolNotAllowed { .. }
+        ))
+    ));
+    // 25 * 150 = 3750 超过单笔名义金额上限
+    assert!(matches!(
+        submit("AAPL", OrderDirection::Buy, dec!(25)).await,
+        Err(TradeError::RiskRejected(
+            RiskViolation::OrderNotional { .. }
+        ))
+    ));
+
+    submit("AAPL", OrderDirection::Buy, dec!(15)).await?;
+    // 15 + 16 = 31 超过单标的持仓上限
+    assert!(matches!(
+        submit("AAPL", OrderDirection::Buy, dec!(16)).await,
+        Err(TradeError::RiskRejected(
+            RiskViolation::PositionLimit { .. }
+        ))
+    ));
+    submit("AAPL", OrderDirection::Sell, dec!(10)).await?;
+    assert!(matches!(
+        submit("AAPL", OrderDirection::Sell, dec!(10)).await,
+        Err(TradeError::RiskRejected(RiskViolation::ShortSell { .. }))
+    ));
+
+    // 被拦截的委托不计入频率，已受理 2 笔，第 4 笔触发频率上限
+    submit("AAPL", OrderDirection::Buy, dec!(1)).await?;
+    assert!(matches!(
+        submit("AAPL", OrderDirection::Buy, dec!(1)).await,
+        Err(TradeError::RiskRejected(RiskViolation::OrderRate {
+            limit: 3
+        }))
+    ));
+
+    let snapshot = risk_port.get_account(acct_id.clone()).await?;
+    assert_eq!(
+        snapshot.positions.first().map(|p| p.volume),
+        Some(dec!(6.0))
+    );
+    Ok(())
+}
+
+/// 受理委托前先等待片刻的交易端口，使并发委托的风控检查与受理交错执行。
+struct DelayedTradePort {
+    inner: Arc<TradeService>,
+}
+
+#[async_trait::async_trait]
+impl TradePort for DelayedTradePort {
+    async fn submit_order(
+        &self,
+        order: Order,
+    ) -> Result<OrderId, okane_core::trade::port::TradeError> {
+        sleep(Duration::from_millis(50)).await;
+        self.inner.submit_order(order).await
+    }
+
+    async fn cancel_order(
+        &self,
+        order_id: OrderId,
+    ) -> Result<(), okane_core::trade::port::TradeError> {
+        self.inner.cancel_order(order_id).await
+    }
+
+    async fn get_account(
+        &self,
+        account_id: AccountId,
+    ) -> Result<AccountSnapshot, okane_core::trade::port::TradeError> {
+        self.inner.get_account(account_id).await
+    }
+
+    async fn get_orders(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Vec<Order>, okane_core::trade::port::TradeError> {
+        self.inner.get_orders(account_id).await
+    }
+
+    async fn get_order(
+        &self,
+        order_id: &OrderId,
+    ) -> Result<Option<Order>, okane_core::trade::port::TradeError> {
+        self.inner.get_order(order_id).await
+    }
+
+    async fn ensure_account(
+        &self,
+        account_id: AccountId,
+        initial_balance: rust_decimal::Decimal,
+    ) -> Result<(), okane_core::trade::port::TradeError> {
+        self.inner.ensure_account(account_id, initial_balance).await
+    }
+
+    async fn get_daily_pnl(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Vec<okane_core::trade::entity::DailyPnl>, okane_core::trade::port::TradeError> {
+        self.inner.get_daily_pnl(account_id).await
+    }
+
+    async fn withdraw(
+        &self,
+        account_id: &AccountId,
+        currency: Option<&str>,
+        amount: rust_decimal::Decimal,
+    ) -> Result<okane_core::trade::entity::LedgerEntry, okane_core::trade::port::TradeError> {
+        self.inner.withdraw(account_id, currency, amount).await
+    }
+}
+
+#[tokio::test]
+async fn test_risk_daily_loss_uses_realized_pnl_and_serializes_checks() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::store::port::SystemStore;
+    use okane_core::trade::port::TradeError;
+    use okane_core::trade::risk::RiskViolation;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    system_store
+        .save_user(&okane_core::store::port::User {
+            id: "u1".to_string(),
+            name: "Risk Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: okane_core::store::port::UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account(
+            "u1",
+            "LossWallet",
+            "loss wallet",
+            "local",
+            serde_json::json!({
+                "risk_rules": {
+                    "daily_loss_limit": "100",
+                    "max_position_per_symbol": "80"
+                }
+            }),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("LossWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(100000));
+    let market = Arc::new(ConfigurableMarket::new(dec!(150)));
+    let start = chrono::DateTime::parse_from_rfc3339("2024-03-04T15:00:00Z")?.to_utc();
+    let clock = Arc::new(FakeClockProvider::new(start));
+    let trade_service = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        market.clone(),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        clock.clone(),
+    ));
+    let risk_port = okane_trade::risk::RiskControlledTradePort::new(
+        Arc::new(DelayedTradePort {
+            inner: trade_service.clone(),
+        }),
+        system_store,
+        market.clone(),
+        clock.clone(),
+    );
+    let order = |id: &str, direction, volume| {
+        Order::new(
+            OrderId(id.to_string()),
+            acct_id.clone(),
+            "AAPL".to_string(),
+            direction,
+            None,
+            volume,
+            0,
+        )
+    };
+
+    // 并发的两笔开仓各自未超限、合计超过单标的持仓上限，检查与受理串行后只有一笔通过
+    let (first, second) = tokio::join!(
+        risk_port.submit_order(order("B1", OrderDirection::Buy, dec!(50))),
+        risk_port.submit_order(order("B2", OrderDirection::Buy, dec!(50))),
+    );
+    assert_eq!(usize::from(first.is_ok()) + usize::from(second.is_ok()), 1);
+    trade_service.maintain_accounts().await?;
+
+    // 出金不计入当日亏损
+    risk_port.withdraw(&acct_id, None, dec!(50000)).await?;
+    risk_port
+        .submit_order(order("B3", OrderDirection::Buy, dec!(1)))
+        .await?;
+
+    // 140 卖出 20 股实现亏损 200，达到当日亏损上限后仅允许减仓
+    market.set_price(dec!(140))?;
+    risk_port
+        .submit_order(order("S1", OrderDirection::Sell, dec!(20)))
+        .await?;
+    assert!(matches!(
+        risk_port
+            .submit_order(order("B4", OrderDirection::Buy, dec!(1)))
+            .await,
+        Err(TradeError::RiskRejected(RiskViolation::DailyLoss { .. }))
+    ));
+    risk_port
+        .submit_order(order("S2", OrderDirection::Sell, dec!(1)))
+        .await?;
+
+    // 次日以前一日收盘快照为基线重新计算
+    trade_service.maintain_accounts().await?;
+    clock.set_time(start + chrono::Duration::days(1))?;
+    risk_port
+        .submit_order(order("B5", OrderDirection::Buy, dec!(1)))
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_risk_daily_loss_seeds_baseline_for_accounts_with_prior_losses() -> anyhow::Result<()>
+{
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::store::port::SystemStore;
+    use okane_core::trade::port::TradeError;
+    use okane_core::trade::risk::RiskViolation;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    system_store
+        .save_user(&okane_core::store::port::User {
+            id: "u1".to_string(),
+            name: "Risk Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: okane_core::store::port::UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account(
+            "u1",
+            "SeasonedWallet",
+            "seasoned wallet",
+            "local",
+            serde_json::json!({ "risk_rules": { "daily_loss_limit": "100" } }),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("SeasonedWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(100000));
+    let market = Arc::new(ConfigurableMarket::new(dec!(150)));
+    let start = chrono::DateTime::parse_from_rfc3339("2024-03-04T15:00:00Z")?.to_utc();
+    let clock = Arc::new(FakeClockProvider::new(start));
+    let trade_service = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        market.clone(),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        clock.clone(),
+    ));
+    let order = |id: &str, direction, volume| {
+        Order::new(
+            OrderId(id.to_string()),
+            acct_id.clone(),
+            "AAPL".to_string(),
+            direction,
+            None,
+            volume,
+            0,
+        )
+    };
+
+    // 启用风控前已累计亏损 500，且从未落地过每日盈亏快照
+    trade_service
+        .submit_order(order("P1", OrderDirection::Buy, dec!(60)))
+        .await?;
+    market.set_price(dec!(140))?;
+    trade_service
+        .submit_order(order("P2", OrderDirection::Sell, dec!(50)))
+        .await?;
+    assert_eq!(
+        trade_service
+            .get_account(acct_id.clone())
+            .await?
+            .realized_pnl,
+        dec!(-500)
+    );
+    assert!(trade_service.get_daily_pnl(&acct_id).await?.is_empty());
+
+    // 首次检查以当前快照为当日基线，历史亏损不计入当日
+    let risk_port = okane_trade::risk::RiskControlledTradePort::new(
+        trade_service.clone(),
+        system_store.clone(),
+        market.clone(),
+        clock.clone(),
+    );
+    risk_port
+        .submit_order(order("B1", OrderDirection::Buy, dec!(10)))
+        .await?;
+
+    // 持仓均价 145，120 卖出 10 股当日亏损 250 后达限；基线已持久化，重建风控端口后仍按同一基线拦截
+    market.set_price(dec!(120))?;
+    risk_port
+        .submit_order(order("S1", OrderDirection::Sell, dec!(10)))
+        .await?;
+    let risk_port = okane_trade::risk::RiskControlledTradePort::new(
+        trade_service.clone(),
+        system_store,
+        market,
+        clock,
+    );
+    assert!(matches!(
+        risk_port
+            .submit_order(order("B2", OrderDirection::Buy, dec!(1)))
+            .await,
+        Err(TradeError::RiskRejected(RiskViolation::DailyLoss { loss, .. })) if loss == dec!(250)
+    ));
+    Ok(())
+}
+
+/// 可配置的行情源：单独报价的标的优先，其余标的按统一的可调价格报价 (未设置时视为不存在)，
+/// 并可附带公司行动，用于模拟盯市、汇率与除权除息。
+struct ConfigurableMarket {
+    price: std::sync::Mutex<Option<rust_decimal::Decimal>>,
+    quotes: std::sync::Mutex<std::collections::HashMap<String, rust_decimal::Decimal>>,
+    actions: Vec<CorporateAction>,
+}
+
+impl ConfigurableMarket {
+    fn new(price: rust_decimal::Decimal) -> Self {
+        Self {
+            price: std::sync::Mutex::new(Some(price)),
+            ..Self::unquoted()
+        }
+    }
+
+    /// 只有通过 `set_quote` 报价的标的存在行情。
+    fn unquoted() -> Self {
+        Self {
+            price: std::sync::Mutex::new(None),
+            quotes: std::sync::Mutex::new(std::collections::HashMap::new()),
+            actions: Vec::new(),
+        }
+    }
+
+    fn with_actions(mut self, actions: Vec<CorporateAction>) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    fn set_price(&self, price: rust_decimal::Decimal) -> anyhow::Result<()> {
+        *self
+            .price
+            .lock()
+            .map_err(|e| anyhow::anyhow!("price lock poisoned: {}", e))? = Some(price);
+        Ok(())
+    }
+
+    fn set_quote(&self, symbol: &str, price: rust_decimal::Decimal) -> anyhow::Result<()> {
+        self.quotes
+            .lock()
+            .map_err(|e| anyhow::anyhow!("quote lock poisoned: {}", e))?
+            .insert(symbol.to_string(), price);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Market for ConfigurableMarket {
+    async fn get_stock(&self, symbol: &str) -> Result<std::sync::Arc<dyn Stock>, MarketError> {
+        let quote = self
+            .quotes
+            .lock()
+            .map_err(|e| MarketError::Unknown(e.to_string()))?
+            .get(symbol)
+            .copied();
+        let price = match quote {
+            Some(price) => price,
+            None => self
+                .price
+                .lock()
+                .map_err(|e| MarketError::Unknown(e.to_string()))?
+                .ok_or(MarketError::NotFound)?,
+        };
+        Ok(Arc::new(DummyStock {
+            identity: StockIdentity {
+                symbol: symbol.to_string(),
+                exchange: None,
+            },
+            price,
+            actions: self.actions.clone(),
+        }))
+    }
+
+    async fn search_symbols(
+        &self,
+        _query: &str,
+    ) -> Result<Vec<okane_core::store::port::StockMetadata>, MarketError> {
+        Ok(vec![])
+    }
+}
+
+/// 保证金账户测试环境：账户档案、行情、挂单簿与时钟在重建的交易服务之间共享，用于模拟重启。
+struct MarginHarness {
+    _tmp_dir: tempfile::TempDir,
+    acct_id: AccountId,
+    account_manager: Arc<AccountManager>,
+    market: Arc<ConfigurableMarket>,
+    pending_store: Arc<okane_store::pending_order::MemoryPendingOrderStore>,
+    clock: Arc<okane_core::common::time::FakeClockProvider>,
+    system_store: Arc<okane_store::system::SqliteSystemStore>,
+    start: chrono::DateTime<chrono::Utc>,
+}
+
+impl MarginHarness {
+    /// 权益 10000 的保证金账户，标的统一报价 150，无佣金。
+    async fn new(account_id: &str) -> anyhow::Result<Self> {
+        let tmp_dir = tempfile::tempdir()?;
+        let system_store = margin_system_store(tmp_dir.path(), account_id).await?;
+        let acct_id = AccountId(account_id.to_string());
+        let account_manager = Arc::new(AccountManager::new());
+        account_manager.ensure_account_exists(acct_id.clone(), dec!(10000));
+        let start = chrono::DateTime::parse_from_rfc3339("2024-03-04T15:00:00Z")?.to_utc();
+        Ok(Self {
+            _tmp_dir: tmp_dir,
+            acct_id,
+            account_manager,
+            market: Arc::new(ConfigurableMarket::new(dec!(150))),
+            pending_store: Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+            clock: Arc::new(okane_core::common::time::FakeClockProvider::new(start)),
+            system_store,
+            start,
+        })
+    }
+
+    /// 以共享状态新建交易服务，多次调用即模拟进程重启。
+    fn service(&self) -> TradeService {
+        TradeService::new(
+            self.account_manager.clone(),
+            Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+                rust_decimal::Decimal::ZERO,
+            )),
+            self.market.clone(),
+            self.pending_store.clone(),
+            self.clock.clone(),
+        )
+        .with_account_cost_models(
+            self.system_store.clone(),
+            okane_trade::matcher::LocalMatchEngine::factory(Arc::new(
+                okane_trade::fill_model::BarFillModel::new(),
+            )),
+        )
+    }
+
+    fn order(
+        &self,
+        id: &str,
+        symbol: &str,
+        direction: OrderDirection,
+        price: Option<rust_decimal::Decimal>,
+        volume: rust_decimal::Decimal,
+    ) -> Order {
+        Order::new(
+            OrderId(id.to_string()),
+            self.acct_id.clone(),
+            symbol.to_string(),
+            direction,
+            price,
+            volume,
+            0,
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_margin_short_reserves_initial_margin_and_caps_buying_power() -> anyhow::Result<()> {
+    use okane_core::trade::port::TradeError;
+
+    let h = MarginHarness::new("MarginWallet").await?;
+    let trade_service = h.service();
+
+    // 卖空 100 股 (名义 15000) 占用 7500 初始保证金，权益 10000 足以覆盖
+    trade_service
+        .submit_order(h.order("S1", "AAPL", OrderDirection::Sell, None, dec!(100)))
+        .await?;
+    let snapshot = trade_service.get_account(h.acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(25000));
+    assert_eq!(snapshot.total_equity, dec!(10000));
+    let margin = snapshot
+        .margin
+        .ok_or_else(|| anyhow::anyhow!("margin status missing"))?;
+    assert_eq!(margin.excess_margin, dec!(2500));
+    assert_eq!(margin.buying_power, dec!(5000));
+
+    // 再融资买入 6000 名义金额需 3000 保证金，超出剩余额度
+    assert!(matches!(
+        trade_service
+            .submit_order(h.order("B1", "MSFT", OrderDirection::Buy, None, dec!(40)))
+            .await,
+        Err(TradeError::InsufficientFunds { .. })
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_margin_borrow_fee_accrues_for_downtime_after_restart() -> anyhow::Result<()> {
+    let h = MarginHarness::new("BorrowFeeWallet").await?;
+    let trade_service = h.service();
+    trade_service
+        .submit_order(h.order("S1", "AAPL", OrderDirection::Sell, None, dec!(100)))
+        .await?;
+
+    // 两天后查询快照不计提融券费
+    h.clock.set_time(h.start + chrono::Duration::days(2))?;
+    let snapshot = trade_service.get_account(h.acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(25000));
+
+    // 重启后按账号档案恢复登记，定时维护按空头市值补提停机期间的融券费：15000 × 3.6% × 2 / 360 = 3
+    let trade_service = h.service();
+    assert_eq!(trade_service.restore_margin_accounts().await?, 1);
+    trade_service.maintain_accounts().await?;
+    let snapshot = trade_service.get_account(h.acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(24997));
+
+    // 同日重复维护不会重复计提
+    trade_service.maintain_accounts().await?;
+    let snapshot = trade_service.get_account(h.acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(24997));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_margin_call_liquidates_short_on_next_tick() -> anyhow::Result<()> {
+    use okane_core::trade::port::BacktestTradePort;
+
+    let h = MarginHarness::new("LiquidationWallet").await?;
+    let trade_service = h.service();
+    trade_service
+        .submit_order(h.order("S1", "AAPL", OrderDirection::Sell, None, dec!(100)))
+        .await?;
+
+    // 价格涨至 201：权益 4900 低于维持保证金 5025，查询只报告追保
+    h.market.set_price(dec!(201))?;
+    let snapshot = trade_service.get_account(h.acct_id.clone()).await?;
+    assert!(snapshot.margin.is_some_and(|m| m.margin_call));
+    assert!(snapshot.positions.iter().all(|p| !p.volume.is_zero()));
+
+    // 持仓标的的新行情触发强制平仓
+    trade_service
+        .tick(
+            "AAPL",
+            &bar(0, dec!(201), dec!(201), dec!(201), dec!(1000))?,
+        )
+        .await?;
+    let snapshot = trade_service.get_account(h.acct_id.clone()).await?;
+    assert!(snapshot.positions.iter().all(|p| p.volume.is_zero()));
+    assert_eq!(snapshot.available_balance, dec!(4900));
+    assert_eq!(snapshot.frozen_balance, dec!(0));
+    assert!(!snapshot.margin.is_some_and(|m| m.margin_call));
+    Ok(())
+}
+
+/// 建立绑定了保证金账户档案 (初始 50%、维持 25%、融券年息 3.6%) 的系统存储。
+async fn margin_system_store(
+    dir: &std::path::Path,
+    account_id: &str,
+) -> anyhow::Result<Arc<okane_store::system::SqliteSystemStore>> {
+    use okane_core::store::port::SystemStore;
+
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(dir.to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    system_store
+        .save_user(&okane_core::store::port::User {
+            id: "u1".to_string(),
+            name: "Margin Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: okane_core::store::port::UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account(
+            "u1",
+            account_id,
+            "margin wallet",
+            "local",
+            serde_json::json!({
+                "margin": {
+                    "kind": "margin",
+                    "initial_margin_rate": "0.5",
+                    "maintenance_margin_rate": "0.25",
+                    "borrow_rate": "0.036"
+                }
+            }),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(system_store)
+}
+
+#[tokio::test]
+async fn test_margin_sell_beyond_pending_closing_volume_reserves_margin() -> anyhow::Result<()> {
+    use okane_core::trade::port::TradeError;
+
+    let h = MarginHarness::new("PendingCloseWallet").await?;
+    let trade_service = h.service();
+
+    // 多头 100 股占用 7500 初始保证金，剩余额度 2500
+    trade_service
+        .submit_order(h.order("B1", "AAPL", OrderDirection::Buy, None, dec!(100)))
+        .await?;
+    // 挂单卖出 100 股平掉全部持仓，不占用保证金
+    trade_service
+        .submit_order(h.order(
+            "S1",
+            "AAPL",
+            OrderDirection::Sell,
+            Some(dec!(300)),
+            dec!(100),
+        ))
+        .await?;
+    // 持仓已被挂单占满，再卖 100 股是开空仓，需 15000 保证金
+    assert!(matches!(
+        trade_service
+            .submit_order(h.order(
+                "S2",
+                "AAPL",
+                OrderDirection::Sell,
+                Some(dec!(300)),
+                dec!(100),
+            ))
+            .await,
+        Err(TradeError::InsufficientFunds { .. })
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_account_cost_model_update_rebuilds_cached_matcher() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::store::port::SystemStore;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    system_store
+        .save_user(&okane_core::store::port::User {
+            id: "u1".to_string(),
+            name: "Cost Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: okane_core::store::port::UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account(
+            "u1",
+            "CostWallet",
+            "cost wallet",
+            "local",
+            serde_json::json!({
+                "cost_model": { "commission": { "type": "per_share", "rate": "1" } }
+            }),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("CostWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000));
+    let trade_service = TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        Arc::new(ConfigurableMarket::new(dec!(150))),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        Arc::new(FakeClockProvider::new(chrono::Utc::now())),
+    )
+    .with_account_cost_models(
+        system_store.clone(),
+        okane_trade::matcher::LocalMatchEngine::factory(Arc::new(
+            okane_trade::fill_model::BarFillModel::new(),
+        )),
+    );
+    let order = |id: &str| {
+        Order::new(
+            OrderId(id.to_string()),
+            acct_id.clone(),
+            "AAPL".to_string(),
+            OrderDirection::Buy,
+            None,
+            dec!(10),
+            0,
+        )
+    };
+
+    // 按股收取佣金：10 股 × 1 = 10
+    trade_service.submit_order(order("B1")).await?;
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(8490));
+
+    // 改为免佣后，缓存的撮合器按新的成本模型重建
+    system_store
+        .update_account_config("CostWallet", serde_json::json!({}))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    trade_service.submit_order(order("B2")).await?;
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(6990));
+
+    assert!(matches!(
+        system_store
+            .update_account_config("MissingWallet", serde_json::json!({}))
+            .await,
+        Err(okane_core::store::error::StoreError::NotFound)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_account_tracks_realized_unrealized_and_daily_pnl() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("PnlWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000));
+    let market = Arc::new(ConfigurableMarket::new(dec!(150)));
+    let start = chrono::DateTime::parse_from_rfc3339("2024-03-04T15:00:00Z")?.to_utc();
+    let clock = Arc::new(FakeClockProvider::new(start));
+    let trade_service = TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(dec!(0.001))),
+        market.clone(),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        clock.clone(),
+    );
+    let order = |id: &str, direction, volume| {
+        Order::new(
+            OrderId(id.to_string()),
+            acct_id.clone(),
+            "AAPL".to_string(),
+            direction,
+            None,
+            volume,
+            0,
+        )
+    };
+
+    // 开盘前的定时维护确立当日期初权益
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.day_pnl, dec!(0));
+    trade_service.maintain_accounts().await?;
+
+    // 150 买入 50 股 (佣金 7.5)，160 卖出 20 股 (佣金 3.2)，已实现 200
+    trade_service
+        .submit_order(order("B1", OrderDirection::Buy, dec!(50)))
+        .await?;
+    market.set_price(dec!(160))?;
+    trade_service
+        .submit_order(order("S1", OrderDirection::Sell, dec!(20)))
+        .await?;
+
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.day_pnl, dec!(489.3));
+    assert!(
+        trade_service.get_daily_pnl(&acct_id).await?[0]
+            .day_pnl
+            .is_zero()
+    );
+    trade_service.maintain_accounts().await?;
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.realized_pnl, dec!(200));
+    assert_eq!(snapshot.total_commission, dec!(10.7));
+    assert_eq!(snapshot.unrealized_pnl, dec!(300));
+    assert_eq!(snapshot.total_equity, dec!(10489.3));
+    assert_eq!(snapshot.day_pnl, dec!(489.3));
+    let position = snapshot
+        .positions
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("position missing"))?;
+    assert_eq!(position.volume, dec!(30));
+    assert_eq!(position.last_price, Some(dec!(160)));
+    assert_eq!(position.realized_pnl, dec!(200));
+    assert_eq!(position.commission, dec!(10.7));
+
+    // 次日以前一日收盘权益为期初
+    clock.set_time(start + chrono::Duration::days(1))?;
+    market.set_price(dec!(155))?;
+    trade_service.maintain_accounts().await?;
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.unrealized_pnl, dec!(150));
+    assert_eq!(snapshot.day_pnl, dec!(-150));
+
+    let history = trade_service.get_daily_pnl(&acct_id).await?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].total_equity, dec!(10489.3));
+    assert_eq!(history[0].day_pnl, dec!(489.3));
+    assert_eq!(history[1].opening_equity, dec!(10489.3));
+    assert_eq!(history[1].total_equity, dec!(10339.3));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_maintenance_records_daily_pnl_of_idle_accounts() -> anyhow::Result<()> {
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("IdleWallet".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(5000));
+    let trade_service = TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(dec!(0.001))),
+        Arc::new(ConfigurableMarket::new(dec!(150))),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        Arc::new(okane_core::common::time::RealTimeProvider),
+    );
+
+    // 本进程从未访问过的账户同样落地当日盈亏快照
+    trade_service.maintain_accounts().await?;
+    let history = trade_service.get_daily_pnl(&acct_id).await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].total_equity, dec!(5000));
+    assert!(history[0].day_pnl.is_zero());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cash_movements_keep_day_pnl_and_write_ledger() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::trade::entity::LedgerAction;
+    use okane_core::trade::port::TradeError;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let main = AccountId("CashMain".to_string());
+    let side = AccountId("CashSide".to_string());
+    account_manager.ensure_account_exists(main.clone(), dec!(10000));
+    account_manager.ensure_account_exists(side.clone(), dec!(0));
+    let market = Arc::new(ConfigurableMarket::new(dec!(150)));
+    let start = chrono::DateTime::parse_from_rfc3339("2024-03-04T15:00:00Z")?.to_utc();
+    let trade_service = TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(dec!(0.001))),
+        market,
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        Arc::new(FakeClockProvider::new(start)),
+    );
+
+    // 定时维护确立当日期初权益后，150 买入 50 股，佣金 7.5 计入当日亏损
+    trade_service.get_account(main.clone()).await?;
+    trade_service.maintain_accounts().await?;
+    trade_service
+        .submit_order(Order::new(
+            OrderId("B1".to_string()),
+            main.clone(),
+            "AAPL".to_string(),
+            OrderDirection::Buy,
+            None,
+            dec!(50),
+            0,
+        ))
+        .await?;
+
+    let entry = trade_service.deposit(&main, None, dec!(1000)).await?;
+    assert_eq!(entry.action, LedgerAction::Deposit);
+    assert_eq!(entry.available_balance, dec!(3492.5));
+    let transfer = trade_service
+        .transfer(&main, &side, None, dec!(500), "xfer-1")
+        .await?;
+    assert_eq!(transfer.outgoing.available_balance, dec!(2992.5));
+    assert_eq!(transfer.incoming.available_balance, dec!(500));
+
+    // 出入金与转账不计入当日盈亏
+    let snapshot = trade_service.get_account(main.clone()).await?;
+    assert_eq!(snapshot.total_equity, dec!(10492.5));
+    assert_eq!(snapshot.day_pnl, dec!(-7.5));
+    assert_eq!(
+        trade_service.get_account(side.clone()).await?.day_pnl,
+        dec!(0)
+    );
+
+    assert!(matches!(
+        trade_service.withdraw(&main, None, dec!(3000)).await,
+        Err(TradeError::InsufficientFunds { .. })
+    ));
+    assert!(matches!(
+        trade_service.withdraw(&main, None, dec!(0)).await,
+        Err(TradeError::InvalidCashMovement(_))
+    ));
+    assert!(matches!(
+        trade_service
+            .transfer(&main, &main, None, dec!(1), "xfer-2")
+            .await,
+        Err(TradeError::InvalidCashMovement(_))
+    ));
+
+    let ledger = trade_service.get_ledger(&main, 0, 10).await?;
+    let actions: Vec<LedgerAction> = ledger.items.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            LedgerAction::TransferOut,
+            LedgerAction::Deposit,
+            LedgerAction::TradeFilled,
+            LedgerAction::FreezeFunds,
+            LedgerAction::Deposit,
+        ]
+    );
+    assert_eq!(ledger.items[2].reference.as_deref(), Some("B1"));
+    assert_eq!(ledger.items[2].frozen_balance, dec!(0));
+    Ok(())
+}
+
+/// 公司行动测试的起始时间，AAPL 于次日 (2024-03-05) 除权。
+fn corporate_action_start() -> anyhow::Result<chrono::DateTime<chrono::Utc>> {
+    Ok(chrono::DateTime::parse_from_rfc3339("2024-03-04T15:00:00Z")?.to_utc())
+}
+
+fn aapl_action(
+    ex_date: &str,
+    kind: okane_core::market::entity::CorporateActionKind,
+) -> anyhow::Result<CorporateAction> {
+    Ok(CorporateAction {
+        symbol: "AAPL".to_string(),
+        ex_date: chrono::DateTime::parse_from_rfc3339(ex_date)?.to_utc(),
+        kind,
+    })
+}
+
+fn aapl_split() -> anyhow::Result<CorporateAction> {
+    aapl_action(
+        "2024-03-05T00:00:00Z",
+        okane_core::market::entity::CorporateActionKind::Split {
+            numerator: 2,
+            denominator: 1,
+        },
+    )
+}
+
+/// AAPL 报价 150、无佣金的交易服务，账户中心与时钟由调用方提供以便模拟重启。
+fn corporate_action_service(
+    account_port: Arc<dyn okane_core::trade::port::AccountPort>,
+    actions: Vec<CorporateAction>,
+    clock: Arc<okane_core::common::time::FakeClockProvider>,
+) -> TradeService {
+    TradeService::new(
+        account_port,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        Arc::new(ConfigurableMarket::new(dec!(150)).with_actions(actions)),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        clock,
+    )
+}
+
+fn aapl_buy(id: &str, account_id: &AccountId, price: Option<rust_decimal::Decimal>) -> Order {
+    Order::new(
+        OrderId(id.into()),
+        account_id.clone(),
+        "AAPL".into(),
+        OrderDirection::Buy,
+        price,
+        dec!(10),
+        0,
+    )
+}
+
+fn first_position(
+    snapshot: &AccountSnapshot,
+) -> anyhow::Result<(rust_decimal::Decimal, rust_decimal::Decimal)> {
+    snapshot
+        .positions
+        .first()
+        .map(|p| (p.volume, p.average_price))
+        .ok_or_else(|| anyhow::anyhow!("position missing"))
+}
+
+#[tokio::test]
+async fn test_tick_applies_split_to_positions_on_ex_date() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::market::entity::CorporateActionKind;
+    use okane_core::trade::port::BacktestTradePort;
+
+    let start = corporate_action_start()?;
+    let clock = Arc::new(FakeClockProvider::new(start));
+    let trade_service = corporate_action_service(
+        Arc::new(AccountManager::new()),
+        vec![
+            // 登记之前的公司行动不追溯
+            aapl_action(
+                "2024-03-01T00:00:00Z",
+                CorporateActionKind::Dividend { amount: dec!(5) },
+            )?,
+            aapl_split()?,
+        ],
+        clock.clone(),
+    );
+    let acct_id = AccountId("CorpActionWallet".to_string());
+    trade_service
+        .ensure_account(acct_id.clone(), dec!(10000))
+        .await?;
+    trade_service
+        .submit_order(aapl_buy("CA_BUY", &acct_id, None))
+        .await?;
+
+    // 拆股日：持仓翻倍，均价减半
+    clock.set_time(start + chrono::Duration::days(1))?;
+    trade_service
+        .tick("AAPL", &bar(0, dec!(75), dec!(75), dec!(75), dec!(100))?)
+        .await?;
+    let snapshot = trade_service.get_account(acct_id).await?;
+    assert_eq!(first_position(&snapshot)?, (dec!(20), dec!(75)));
+    assert_eq!(snapshot.available_balance, dec!(8500));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tick_pays_dividend_once_and_writes_ledger() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::market::entity::CorporateActionKind;
+    use okane_core::trade::entity::LedgerAction;
+    use okane_core::trade::port::BacktestTradePort;
+
+    let start = corporate_action_start()?;
+    let clock = Arc::new(FakeClockProvider::new(start));
+    let trade_service = corporate_action_service(
+        Arc::new(AccountManager::new()),
+        vec![
+            aapl_split()?,
+            aapl_action(
+                "2024-03-06T00:00:00Z",
+                CorporateActionKind::Dividend { amount: dec!(1) },
+            )?,
+        ],
+        clock.clone(),
+    );
+    let acct_id = AccountId("DividendWallet".to_string());
+    trade_service
+        .ensure_account(acct_id.clone(), dec!(10000))
+        .await?;
+    trade_service
+        .submit_order(aapl_buy("DIV_BUY", &acct_id, None))
+        .await?;
+
+    // 除息日：拆股后的 20 股 × 1 计入现金与已实现盈亏，重复 tick 不重复派息
+    clock.set_time(start + chrono::Duration::days(2))?;
+    let candle = bar(0, dec!(75), dec!(75), dec!(75), dec!(100))?;
+    trade_service.tick("AAPL", &candle).await?;
+    trade_service.tick("AAPL", &candle).await?;
+    let snapshot = trade_service.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(8520));
+    assert_eq!(snapshot.realized_pnl, dec!(20));
+
+    let ledger = trade_service.get_ledger(&acct_id, 0, 10).await?;
+    let actions: Vec<LedgerAction> = ledger.items.iter().map(|e| e.action).collect();
+    assert_eq!(actions[..2], [LedgerAction::Dividend, LedgerAction::Split]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_maintain_accounts_applies_split_to_positions_once() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+
+    let start = corporate_action_start()?;
+    let clock = Arc::new(FakeClockProvider::new(start));
+    let trade_service = corporate_action_service(
+        Arc::new(AccountManager::new()),
+        vec![aapl_split()?],
+        clock.clone(),
+    );
+    let acct_id = AccountId("SplitWallet".to_string());
+    trade_service
+        .ensure_account(acct_id.clone(), dec!(10000))
+        .await?;
+    trade_service
+        .submit_order(aapl_buy("SPLIT_BUY", &acct_id, None))
+        .await?;
+
+    // 没有行情驱动 tick，定时维护同样应用拆股；重复维护不重复调整
+    clock.set_time(start + chrono::Duration::days(1))?;
+    trade_service.maintain_accounts().await?;
+    trade_service.maintain_accounts().await?;
+    let snapshot = trade_service.get_account(acct_id).await?;
+    assert_eq!(first_position(&snapshot)?, (dec!(20), dec!(75)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_maintain_accounts_applies_split_to_resting_orders() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+
+    let start = corporate_action_start()?;
+    let clock = Arc::new(FakeClockProvider::new(start));
+    let trade_service = corporate_action_service(
+        Arc::new(AccountManager::new()),
+        vec![aapl_split()?],
+        clock.clone(),
+    );
+    let acct_id = AccountId("SplitOrderWallet".to_string());
+    trade_service
+        .ensure_account(acct_id.clone(), dec!(10000))
+        .await?;
+    trade_service
+        .submit_order(aapl_buy("SPLIT_LIMIT", &acct_id, Some(dec!(140))))
+        .await?;
+
+    // 挂单数量翻倍、限价减半，名义金额不变
+    clock.set_time(start + chrono::Duration::days(1))?;
+    trade_service.maintain_accounts().await?;
+    let order = trade_service
+        .get_order(&OrderId("SPLIT_LIMIT".into()))
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("pending order missing"))?;
+    assert_eq!(order.volume, dec!(20));
+    assert_eq!(order.price, Some(dec!(70)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restored_accounts_apply_corporate_actions_missed_during_downtime()
+-> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+
+    let start = corporate_action_start()?;
+    let tmp_dir = tempfile::tempdir()?;
+    let clock = Arc::new(FakeClockProvider::new(start));
+    let new_service = || -> anyhow::Result<TradeService> {
+        let account_store = okane_store::account::SqliteAccountStore::new_with_path(Some(
+            tmp_dir.path().to_path_buf(),
+        ))?;
+        Ok(corporate_action_service(
+            Arc::new(account_store),
+            vec![aapl_split()?],
+            clock.clone(),
+        ))
+    };
+    let held = AccountId("DowntimeHeld".to_string());
+    let late = AccountId("DowntimeLate".to_string());
+
+    let before = new_service()?;
+    before.ensure_account(held.clone(), dec!(10000)).await?;
+    before
+        .submit_order(aapl_buy("HELD_BUY", &held, None))
+        .await?;
+    drop(before);
+
+    // 停机期间拆股除权；除权后才开仓的账户不受影响
+    clock.set_time(start + chrono::Duration::days(1))?;
+    let after = new_service()?;
+    after.ensure_account(late.clone(), dec!(10000)).await?;
+    after
+        .submit_order(aapl_buy("LATE_BUY", &late, None))
+        .await?;
+
+    // 重启后未经任何委托或查询，恢复登记即可由定时维护补处理
+    let restarted = new_service()?;
+    assert_eq!(restarted.restore_corporate_action_accounts().await?, 2);
+    restarted.maintain_accounts().await?;
+    restarted.maintain_accounts().await?;
+    assert_eq!(
+        first_position(&restarted.get_account(held).await?)?,
+        (dec!(20), dec!(75))
+    );
+    assert_eq!(
+        first_position(&restarted.get_account(late).await?)?,
+        (dec!(10), dec!(150))
+    );
+    Ok(())
+}
+
+/// 基础货币为 USD 的多币种账户 (权益 10000 USD)，0700.HK 报价 300 HKD，HKDUSD=X 报价 0.125。
+async fn fx_trade_service(
+    dir: &std::path::Path,
+    account_id: &AccountId,
+) -> anyhow::Result<(TradeService, Arc<ConfigurableMarket>)> {
+    use okane_core::store::port::SystemStore;
+
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(dir.to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    system_store
+        .save_user(&okane_core::store::port::User {
+            id: "u1".to_string(),
+            name: "Fx Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: okane_core::store::port::UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account(
+            "u1",
+            &account_id.0,
+            "global wallet",
+            "local",
+            serde_json::json!({ "base_currency": "usd" }),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let account_manager = Arc::new(AccountManager::new());
+    account_manager.ensure_account_exists(account_id.clone(), dec!(10000));
+    let market = Arc::new(ConfigurableMarket::unquoted());
+    market.set_quote("0700.HK", dec!(300))?;
+    market.set_quote("HKDUSD=X", dec!(0.125))?;
+    let start = chrono::DateTime::parse_from_rfc3339("2024-03-04T03:00:00Z")?.to_utc();
+    let trade_service = TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        market.clone(),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        Arc::new(okane_core::common::time::FakeClockProvider::new(start)),
+    )
+    .with_account_cost_models(
+        system_store,
+        okane_trade::matcher::LocalMatchEngine::factory(Arc::new(
+            okane_trade::fill_model::BarFillModel::new(),
+        )),
+    );
+    Ok((trade_service, market))
+}
+
+fn hk_order(id: &str, account_id: &AccountId, direction: OrderDirection) -> Order {
+    Order::new(
+        OrderId(id.to_string()),
+        account_id.clone(),
+        "0700.HK".to_string(),
+        direction,
+        None,
+        dec!(10),
+        0,
+    )
+}
+
+/// 兑换 1000 USD 为 8000 HKD 后以 300 HKD 买入 10 股 0700.HK，再将报价调至 320 HKD。
+async fn open_hk_position(
+    trade_service: &TradeService,
+    market: &ConfigurableMarket,
+    account_id: &AccountId,
+) -> anyhow::Result<()> {
+    trade_service
+        .convert_currency(account_id, "USD", "HKD", dec!(1000), "fx-1")
+        .await?;
+    trade_service
+        .submit_order(hk_order("B1", account_id, OrderDirection::Buy))
+        .await?;
+    market.set_quote("0700.HK", dec!(320))?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multi_currency_buy_does_not_spend_base_currency_cash() -> anyhow::Result<()> {
+    use okane_core::trade::port::TradeError;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let acct_id = AccountId("GlobalWallet".to_string());
+    let (trade_service, _) = fx_trade_service(tmp_dir.path(), &acct_id).await?;
+
+    // 港币现金不足时不会动用美元现金
+    assert!(matches!(
+        trade_service
+            .submit_order(hk_order("B0", &acct_id, OrderDirection::Buy))
+            .await,
+        Err(TradeError::InsufficientFunds { .. })
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_currency_conversion_falls_back_to_inverse_quote() -> anyhow::Result<()> {
+    use okane_core::trade::entity::LedgerAction;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let acct_id = AccountId("ConvertWallet".to_string());
+    let (trade_service, _) = fx_trade_service(tmp_dir.path(), &acct_id).await?;
+
+    // USDHKD=X 无报价时取 HKDUSD=X 的倒数：1000 USD 兑换 8000 HKD
+    let conversion = trade_service
+        .convert_currency(&acct_id, "USD", "hkd", dec!(1000), "fx-1")
+        .await?;
+    assert_eq!(conversion.incoming.asset_change, dec!(8000));
+    assert_eq!(conversion.incoming.currency.as_deref(), Some("HKD"));
+    assert_eq!(conversion.outgoing.action, LedgerAction::FxConversion);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multi_currency_snapshot_reports_equity_in_base_currency() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let acct_id = AccountId("EquityWallet".to_string());
+    let (trade_service, market) = fx_trade_service(tmp_dir.path(), &acct_id).await?;
+    open_hk_position(&trade_service, &market, &acct_id).await?;
+
+    // 权益 = 9000 USD + (5000 HKD 现金 + 3200 HKD 市值) × 0.125
+    let snapshot = trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.base_currency.as_deref(), Some("USD"));
+    assert_eq!(snapshot.available_balance, dec!(9000));
+    assert_eq!(snapshot.total_equity, dec!(10025));
+    assert_eq!(snapshot.unrealized_pnl, dec!(25));
+    assert_eq!(snapshot.positions[0].unrealized_pnl, dec!(200));
+    let currencies: Vec<(&str, rust_decimal::Decimal, rust_decimal::Decimal)> = snapshot
+        .currencies
+        .iter()
+        .map(|b| (b.currency.as_str(), b.market_value, b.base_equity))
+        .collect();
+    assert_eq!(
+        currencies,
+        vec![
+            ("HKD", dec!(3200), dec!(1025)),
+            ("USD", dec!(0), dec!(9000))
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multi_currency_realized_pnl_is_converted_to_base_currency() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let acct_id = AccountId("RealizedWallet".to_string());
+    let (trade_service, market) = fx_trade_service(tmp_dir.path(), &acct_id).await?;
+    open_hk_position(&trade_service, &market, &acct_id).await?;
+
+    // 平仓盈利 200 HKD 折合 25 USD 计入账户已实现盈亏，卖出所得留在港币现金中
+    trade_service
+        .submit_order(hk_order("S1", &acct_id, OrderDirection::Sell))
+        .await?;
+    let snapshot = trade_service.get_account(acct_id).await?;
+    assert_eq!(snapshot.realized_pnl, dec!(25));
+    assert_eq!(snapshot.total_equity, dec!(10025));
+    assert_eq!(snapshot.currencies[0].available_balance, dec!(8200));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multi_currency_cash_movements_validate_currency() -> anyhow::Result<()> {
+    use okane_core::trade::port::TradeError;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let acct_id = AccountId("CashFxWallet".to_string());
+    let (trade_service, _) = fx_trade_service(tmp_dir.path(), &acct_id).await?;
+
+    // 基础货币的出金不经汇率；未知币种被拒绝
+    let entry = trade_service
+        .withdraw(&acct_id, Some("USD"), dec!(25))
+        .await?;
+    assert_eq!(entry.currency, None);
+    assert!(matches!(
+        trade_service.deposit(&acct_id, Some("HK"), dec!(1)).await,
+        Err(TradeError::InvalidCurrency(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_broker_gateway_settles_reports_and_reconciles_after_reconnect() -> anyhow::Result<()>
+{
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::store::port::SystemStore;
+    use okane_core::trade::entity::OrderStatus;
+    use okane_core::trade::port::BrokerPort;
+    use okane_trade::gateway::{BrokerGateway, BrokerRegistry};
+    use okane_trade::router::RoutedTradePort;
+    use okane_trade::simulated_broker::SimulatedBroker;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let system_store = Arc::new(
+        okane_store::system::SqliteSystemStore::new_with_path(Some(tmp_dir.path().to_path_buf()))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?,
+    );
+    system_store
+        .save_user(&okane_core::store::port::User {
+            id: "u1".to_string(),
+            name: "Gateway Tester".to_string(),
+            password_hash: "dummy_hash".to_string(),
+            role: okane_core::store::port::UserRole::Standard,
+            force_password_change: false,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    system_store
+        .bind_account(
+            "u1",
+            "SimAcct",
+            "sim",
+            "simulated",
+            serde_json::json!({
+                "cost_model": { "commission": { "type": "percent", "rate": "0.001" } }
+            }),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("SimAcct".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000));
+    let market = Arc::new(ConfigurableMarket::unquoted());
+    market.set_quote("AAPL", dec!(100))?;
+    let clock = Arc::new(FakeClockProvider::new(chrono::Utc::now()));
+
+    // 每次撮合最多成交 5 股，手续费率 0.1%
+    let broker = Arc::new(
+        SimulatedBroker::new(market.clone(), clock.clone())
+            .with_commission_rate(dec!(0.001))
+            .with_max_fill_volume(dec!(5)),
+    );
+    let gateway = Arc::new(
+        BrokerGateway::new(
+            BrokerRegistry::new().with_broker("simulated", broker.clone()),
+            account_manager.clone(),
+            Arc::new(okane_store::broker_order::MemoryBrokerOrderStore::new()),
+            market.clone(),
+            clock.clone(),
+        )
+        .with_reconnect_delay(Duration::from_millis(10))
+        .with_account_configs(system_store.clone()),
+    );
+    gateway.start();
+    let local = Arc::new(TradeService::new(
+        account_manager,
+        Arc::new(okane_trade::matcher::LocalMatchEngine::new(
+            rust_decimal::Decimal::ZERO,
+        )),
+        market.clone(),
+        Arc::new(okane_store::pending_order::MemoryPendingOrderStore::new()),
+        clock,
+    ));
+    let router = RoutedTradePort::new(local, system_store).with_gateway(gateway.clone());
+
+    async fn wait_for(
+        router: &RoutedTradePort,
+        order_id: &OrderId,
+        status: OrderStatus,
+    ) -> anyhow::Result<Order> {
+        for _ in 0..200 {
+            if let Some(order) = router.get_order(order_id).await?
+                && order.status == status
+            {
+                return Ok(order);
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        Err(anyhow::anyhow!(
+            "order {} never reached {:?}",
+            order_id.0,
+            status
+        ))
+    }
+
+    // 限价买入 10 股：首次撮合成交 5 股，其余 5 股按限价加 0.1% 预估手续费冻结
+    let buy = OrderId("GB1".to_string());
+    router
+        .submit_order(Order::new(
+            buy.clone(),
+            acct_id.clone(),
+            "AAPL".to_string(),
+            OrderDirection::Buy,
+            Some(dec!(100)),
+            dec!(10),
+            0,
+        ))
+        .await?;
+    let order = wait_for(&router, &buy, OrderStatus::PartialFilled).await?;
+    assert_eq!(order.filled_volume, dec!(5));
+    let snapshot = router.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(8999));
+    assert_eq!(snapshot.frozen_balance, dec!(500.5));
+    assert_eq!(router.get_orders(&acct_id).await?.len(), 1);
+
+    // 断线期间剩余 5 股以 90 成交，回报丢失，重连后经对账补结算
+    broker.disconnect()?;
+    market.set_quote("AAPL", dec!(90))?;
+    assert_eq!(broker.match_orders().await?, 1);
+    let order = wait_for(&router, &buy, OrderStatus::Filled).await?;
+    assert_eq!(order.filled_volume, dec!(10));
+    let snapshot = router.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(9049.05));
+    assert_eq!(snapshot.frozen_balance, dec!(0));
+    assert_eq!(snapshot.positions[0].volume, dec!(10));
+    assert_eq!(snapshot.positions[0].average_price, dec!(95));
+
+    // 重复的回报不会重复入账
+    let report = broker.query_order_status("SIM-1").await?;
+    gateway.apply_report("simulated", &report).await?;
+    assert_eq!(gateway.reconcile("simulated").await?, 0);
+    let snapshot = router.get_account(acct_id.clone()).await?;
+    assert_eq!(snapshot.available_balance, dec!(9049.05));
+
+    // 未穿越的限价卖单经路由撤单
+    let sell = OrderId("GS1".to_string());
+    router
+        .submit_order(Order::new(
+            sell.clone(),
+            acct_id.clone(),
+            "AAPL".to_string(),
+            OrderDirection::Sell,
+            Some(dec!(200)),
+            dec!(10),
+            0,
+        ))
+        .await?;
+    router.cancel_order(sell.clone()).await?;
+    wait_for(&router, &sell, OrderStatus::Canceled).await?;
+    assert!(router.get_orders(&acct_id).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_broker_gateway_reserves_fees_and_recovers_unsent_orders() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::store::port::SystemStore;
+    use okane_core::trade::entity::{BrokerOrderRecord, OrderStatus};
+    use okane_core::trade::port::{AccountPort, BrokerOrderStore, BrokerPort, TradeError};
+    use okane_trade::gateway::{BrokerGateway, BrokerRegistry};
+    use okane_trade::simulated_broker::SimulatedBroker;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let system_store = margin_system_store(tmp_dir.path(), "SimMargin").await?;
+    for (account_id, config) in [
+        (
+            "SimAcct",
+            serde_json::json!({
+                "cost_model": {
+                    "slippage": { "type": "fixed_bps", "bps": "100" },
+                    "commission": { "type": "percent", "rate": "0.001" }
+                }
+            }),
+        ),
+        ("SimMulti", serde_json::json!({ "base_currency": "USD" })),
+    ] {
+        system_store
+            .bind_account("u1", account_id, "sim", "simulated", config)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("SimAcct".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000));
+    let market = Arc::new(ConfigurableMarket::unquoted());
+    market.set_quote("AAPL", dec!(100))?;
+    let clock = Arc::new(FakeClockProvider::new(chrono::Utc::now()));
+    let broker = Arc::new(
+        SimulatedBroker::new(market.clone(), clock.clone())
+            .with_commission_rate(dec!(0.001))
+            .with_max_fill_volume(dec!(5)),
+    );
+    let store = Arc::new(okane_store::broker_order::MemoryBrokerOrderStore::new());
+    let gateway = BrokerGateway::new(
+        BrokerRegistry::new().with_broker("simulated", broker.clone()),
+        account_manager.clone(),
+        store.clone(),
+        market.clone(),
+        clock,
+    )
+    .with_account_configs(system_store);
+
+    // 保证金账户与非基础货币挂牌的标的不外发
+    let rejected = |id: &str, account: &str, symbol: &str| {
+        Order::new(
+            OrderId(id.to_string()),
+            AccountId(account.to_string()),
+            symbol.to_string(),
+            OrderDirection::Buy,
+            Some(dec!(100)),
+            dec!(1),
+            0,
+        )
+    };
+    assert!(matches!(
+        gateway
+            .submit_order("simulated", rejected("GR1", "SimMargin", "AAPL"))
+            .await,
+        Err(TradeError::InvalidOrder(_))
+    ));
+    assert!(matches!(
+        gateway
+            .submit_order("simulated", rejected("GR2", "SimMulti", "0700.HK"))
+            .await,
+        Err(TradeError::InvalidCurrency(_))
+    ));
+    assert!(
+        gateway
+            .get_order(&OrderId("GR1".to_string()))
+            .await?
+            .is_none()
+    );
+
+    // 市价买入 10 股：按账号成本模型以最新价加 1% 滑点与 0.1% 手续费冻结，外发时同步成交 5 股
+    let buy = OrderId("GM1".to_string());
+    gateway
+        .submit_order(
+            "simulated",
+            Order::new(
+                buy.clone(),
+                acct_id.clone(),
+                "AAPL".to_string(),
+                OrderDirection::Buy,
+                None,
+                dec!(10),
+                0,
+            ),
+        )
+        .await?;
+    let report = broker.query_order_status("GM1").await?;
+    gateway.apply_report("simulated", &report).await?;
+    let order = gateway
+        .get_order(&buy)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("order not saved"))?;
+    assert_eq!(order.status, OrderStatus::PartialFilled);
+    assert_eq!(order.filled_volume, dec!(5));
+    let snapshot = account_manager.snapshot(&acct_id).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(505.505));
+
+    // 剩余 5 股跳空到 120 成交，超出冻结的部分先补冻结，可用资金不为负
+    market.set_quote("AAPL", dec!(120))?;
+    broker.match_orders().await?;
+    let report = broker.query_order_status("GM1").await?;
+    gateway.apply_report("simulated", &report).await?;
+    let snapshot = account_manager.snapshot(&acct_id).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(0));
+    assert_eq!(snapshot.available_balance, dec!(8898.9));
+
+    // 映射已保存但外发前中断的订单：券商查无此单，对账时拒绝并退回冻结资金
+    let mut unsent = Order::new(
+        OrderId("GM2".to_string()),
+        acct_id.clone(),
+        "AAPL".to_string(),
+        OrderDirection::Buy,
+        Some(dec!(100)),
+        dec!(10),
+        0,
+    );
+    unsent.status = OrderStatus::Pending;
+    account_manager
+        .freeze_funds(&acct_id, None, dec!(1001))
+        .await?;
+    store
+        .save(BrokerOrderRecord {
+            account_type: "simulated".to_string(),
+            external_order_id: "GM2".to_string(),
+            order: unsent,
+            reserved_price: Some(dec!(100.1)),
+            settled_notional: dec!(0),
+            settled_commission: dec!(0),
+        })
+        .await?;
+    assert_eq!(gateway.reconcile("simulated").await?, 1);
+    let order = gateway
+        .get_order(&OrderId("GM2".to_string()))
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("order missing"))?;
+    assert_eq!(order.status, OrderStatus::Rejected);
+    let snapshot = account_manager.snapshot(&acct_id).await?;
+    assert_eq!(snapshot.frozen_balance, dec!(0));
+    assert_eq!(snapshot.available_balance, dec!(8898.9));
+    Ok(())
+}
+
+/// 外发阻塞到放行为止的券商桩；放行前查询订单返回 `OrderNotFound`。
+struct BlockingBroker {
+    send_started: tokio::sync::Notify,
+    release_send: tokio::sync::Notify,
+    accepted: std::sync::Mutex<std::collections::HashSet<String>>,
+}
+
+#[async_trait::async_trait]
+impl okane_core::trade::port::BrokerPort for BlockingBroker {
+    async fn send_order(
+        &self,
+        order: &Order,
+    ) -> Result<String, okane_core::trade::port::TradeError> {
+        self.send_started.notify_one();
+        self.release_send.notified().await;
+        self.accepted
+            .lock()
+            .map_err(|e| okane_core::trade::port::TradeError::InternalError(e.to_string()))?
+            .insert(order.id.0.clone());
+        Ok(order.id.0.clone())
+    }
+
+    async fn cancel_order(
+        &self,
+        external_order_id: &str,
+    ) -> Result<(), okane_core::trade::port::TradeError> {
+        Err(okane_core::trade::port::TradeError::OrderNotFound(
+            external_order_id.to_string(),
+        ))
+    }
+
+    async fn query_order_status(
+        &self,
+        external_order_id: &str,
+    ) -> Result<okane_core::trade::entity::ExecutionReport, okane_core::trade::port::TradeError>
+    {
+        let accepted = self
+            .accepted
+            .lock()
+            .map_err(|e| okane_core::trade::port::TradeError::InternalError(e.to_string()))?
+            .contains(external_order_id);
+        if !accepted {
+            return Err(okane_core::trade::port::TradeError::OrderNotFound(
+                external_order_id.to_string(),
+            ));
+        }
+        Ok(okane_core::trade::entity::ExecutionReport {
+            external_order_id: external_order_id.to_string(),
+            status: okane_core::trade::entity::OrderStatus::Submitted,
+            filled_volume: dec!(0),
+            average_price: dec!(0),
+            commission: dec!(0),
+            timestamp: 0,
+            reason: None,
+        })
+    }
+
+    async fn subscribe_execution_reports(
+        &self,
+    ) -> Result<okane_core::trade::port::ExecutionReportStream, okane_core::trade::port::TradeError>
+    {
+        Ok(Box::pin(futures::stream::pending()))
+    }
+}
+
+#[tokio::test]
+async fn test_broker_gateway_reconcile_skips_orders_being_sent() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::trade::entity::OrderStatus;
+    use okane_core::trade::port::AccountPort;
+    use okane_trade::gateway::{BrokerGateway, BrokerRegistry};
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("BlockAcct".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000));
+    let market = Arc::new(ConfigurableMarket::unquoted());
+    let broker = Arc::new(BlockingBroker {
+        send_started: tokio::sync::Notify::new(),
+        release_send: tokio::sync::Notify::new(),
+        accepted: std::sync::Mutex::new(std::collections::HashSet::new()),
+    });
+    let gateway = Arc::new(BrokerGateway::new(
+        BrokerRegistry::new().with_broker("blocking", broker.clone()),
+        account_manager.clone(),
+        Arc::new(okane_store::broker_order::MemoryBrokerOrderStore::new()),
+        market,
+        Arc::new(FakeClockProvider::new(chrono::Utc::now())),
+    ));
+
+    // 外发阻塞期间券商查无此单，对账不能把在途订单当作未受理拒绝
+    let buy = OrderId("BB1".to_string());
+    let submit = tokio::spawn({
+        let gateway = gateway.clone();
+        let order = Order::new(
+            buy.clone(),
+            acct_id.clone(),
+            "AAPL".to_string(),
+            OrderDirection::Buy,
+            Some(dec!(100)),
+            dec!(10),
+            0,
+        );
+        async move { gateway.submit_order("blocking", order).await }
+    });
+    tokio::time::timeout(Duration::from_secs(5), broker.send_started.notified()).await?;
+    assert_eq!(gateway.reconcile("blocking").await?, 0);
+    let order = gateway
+        .get_order(&buy)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("order not saved"))?;
+    assert_eq!(order.status, OrderStatus::Pending);
+    assert_eq!(
+        account_manager.snapshot(&acct_id).await?.frozen_balance,
+        dec!(1000)
+    );
+
+    // 放行后订单正常受理，之后的对账按券商状态同步
+    broker.release_send.notify_one();
+    tokio::time::timeout(Duration::from_secs(5), submit).await???;
+    let order = gateway
+        .get_order(&buy)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("order missing"))?;
+    assert_eq!(order.status, OrderStatus::Submitted);
+    assert_eq!(gateway.reconcile("blocking").await?, 1);
+    assert_eq!(
+        account_manager.snapshot(&acct_id).await?.frozen_balance,
+        dec!(1000)
+    );
+    Ok(())
+}
+
+/// 允许的保存次数用尽后保存失败的外发订单仓储。
+struct FailingSaveBrokerOrderStore {
+    inner: okane_store::broker_order::MemoryBrokerOrderStore,
+    saves_left: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl okane_core::trade::port::BrokerOrderStore for FailingSaveBrokerOrderStore {
+    async fn save(
+        &self,
+        record: okane_core::trade::entity::BrokerOrderRecord,
+    ) -> Result<(), okane_core::trade::port::TradeError> {
+        use std::sync::atomic::Ordering;
+        if self
+            .saves_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_err()
+        {
+            return Err(okane_core::trade::port::TradeError::InternalError(
+                "broker order store unavailable".to_string(),
+            ));
+        }
+        self.inner.save(record).await
+    }
+
+    async fn get(
+        &self,
+        order_id: &OrderId,
+    ) -> Result<
+        Option<okane_core::trade::entity::BrokerOrderRecord>,
+        okane_core::trade::port::TradeError,
+    > {
+        self.inner.get(order_id).await
+    }
+
+    async fn find_by_external(
+        &self,
+        account_type: &str,
+        external_order_id: &str,
+    ) -> Result<
+        Option<okane_core::trade::entity::BrokerOrderRecord>,
+        okane_core::trade::port::TradeError,
+    > {
+        self.inner
+            .find_by_external(account_type, external_order_id)
+            .await
+    }
+
+    async fn load_open(
+        &self,
+    ) -> Result<
+        Vec<okane_core::trade::entity::BrokerOrderRecord>,
+        okane_core::trade::port::TradeError,
+    > {
+        self.inner.load_open().await
+    }
+}
+
+#[tokio::test]
+async fn test_broker_gateway_keeps_accepted_order_when_recording_fails() -> anyhow::Result<()> {
+    use okane_core::common::time::FakeClockProvider;
+    use okane_core::trade::entity::OrderStatus;
+    use okane_core::trade::port::{AccountPort, BrokerPort};
+    use okane_trade::gateway::{BrokerGateway, BrokerRegistry};
+    use okane_trade::simulated_broker::SimulatedBroker;
+    use std::sync::atomic::Ordering;
+
+    let account_manager = Arc::new(AccountManager::new());
+    let acct_id = AccountId("SimAcct".to_string());
+    account_manager.ensure_account_exists(acct_id.clone(), dec!(10000));
+    let market = Arc::new(ConfigurableMarket::unquoted());
+    market.set_quote("AAPL", dec!(100))?;
+    let clock = Arc::new(FakeClockProvider::new(chrono::Utc::now()));
+    let broker = Arc::new(SimulatedBroker::new(market.clone(), clock.clone()));
+    // 只允许外发前保存映射，外发后记录券商订单号失败
+    let store = Arc::new(FailingSaveBrokerOrderStore {
+        inner: okane_store::broker_order::MemoryBrokerOrderStore::new(),
+        saves_left: std::sync::atomic::AtomicUsize::new(1),
+    });
+    let gateway = BrokerGateway::new(
+        BrokerRegistry::new().with_broker("simulated", broker.clone()),
+        account_manager.clone(),
+        store.clone(),
+        market,
+        clock,
+    );
+    let order = |id: &str| {
+        Order::new(
+            OrderId(id.to_string()),
+            acct_id.clone(),
+            "AAPL".to_string(),
+            OrderDirection::Buy,
+            Some(dec!(50)),
+            dec!(10),
+            0,
+        )
+    };
+
+    // 券商已受理的订单报告成功，映射与冻结资金保留给对账
+    let id = gateway.submit_order("simulated", order("GF1")).await?;
+    assert_eq!(
+        broker.query_order_status(&id.0).await?.status,
+        OrderStatus::Submitted
+    );
+    let saved = gateway
+        .get_order(&id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("order mapping missing"))?;
+    assert_eq!(saved.status, OrderStatus::Pending);
+    assert_eq!(
+        account_manager.snapshot(&acct_id).await?.frozen_balance,
+        dec!(500)
+    );
+
+    // 外发前保存映射失败时返回原始错误并退回冻结资金
+    assert!(matches!(
+        gateway.submit_order("simulated", order("GF2")).await,
+        Err(okane_core::trade::port::TradeError::InternalError(msg)) if msg.contains("unavailable")
+    ));
+    assert_eq!(
+        account_manager.snapshot(&acct_id).await?.frozen_balance,
+        dec!(500)
+    );
+
+    // 仓储恢复后对账按券商状态同步
+    store.saves_left.store(usize::MAX, Ordering::SeqCst);
+    assert_eq!(gateway.reconcile("simulated").await?, 1);
+    let synced = gateway
+        .get_order(&id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("order mapping missing"))?;
+    assert_eq!(synced.status, OrderStatus::Submitted);
+    Ok(())
+}
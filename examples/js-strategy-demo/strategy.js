@@ -10,6 +10,14 @@
  * - host.fetchHistory(symbol: string, tf: string, limit: number) -> string (只读的 JSON 历史 K 线数组)
  * - host.buy(symbol: string, price: number|null, volume: number) -> string (下买单, 返回 order_id JSON)
  * - host.sell(symbol: string, price: number|null, volume: number) -> string (下卖单, 返回 order_id JSON)
+ * - host.stopOrder(symbol: string, direction: "buy"|"sell", volume: string, params: object) -> string
+ *   (下止损单; params 取 stop_price / trail_amount / trail_percent 之一，附 limit_price 时为止损限价单)
+ * - host.submitOco(symbol: string, direction: "buy"|"sell", volume: string, params: object) -> string
+ *   (下 OCO 单; params 含 limit_price 与 stop_price，任一成交即撤销另一腿，返回订单组 ID)
+ * - host.submitBracket(symbol: string, direction: "buy"|"sell", volume: string, params: object) -> string
+ *   (下括号单; params 含可选 price 以及 take_profit、stop_loss，入场成交后激活止盈止损)
+ * - host.getOrderGroup(groupId: string) -> string (查询生效中的订单组 JSON 或 null)
+ * - host.cancelOrderGroup(groupId: string) -> string (撤销订单组, "ok" 或 error JSON)
  * - host.getAccount() -> string (查询账户快照 JSON)
  * - host.getOrder(orderId: string) -> string (查询订单详情 JSON)
  * - host.cancelOrder(orderId: string) -> string (撤单, "ok" 或 error JSON)